@@ -1,7 +1,9 @@
 #![windows_subsystem = "windows"]
 
+use ori_term::app::{App, LaunchMode};
+
 fn main() {
-    if let Err(e) = ori_term::app::App::run() {
+    if let Err(e) = App::run(LaunchMode::Normal, None) {
         let _ = std::fs::write("oriterm_error.log", format!("{e:?}"));
     }
 }
@@ -0,0 +1,279 @@
+//! Time-driven animation and the wake-up decision in `about_to_wait`.
+//!
+//! Animations are [`Tween`]s: a value easing from a start offset to zero
+//! over a fixed duration, evaluated from the clock rather than stepped per
+//! frame, so dropped frames never slow them down. [`Animations`] owns the
+//! running ones; while any run, each tick requests an animation frame, so
+//! `about_to_wait` wakes at the next frame. Otherwise the loop sleeps until
+//! the earliest timer (cursor or text blink toggle, settled resize, chord
+//! timeout).
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use winit::window::WindowId;
+
+use super::App;
+use super::redraw::RedrawReason;
+
+/// How long a displaced tab takes to slide into its new slot.
+const DODGE_DURATION: Duration = Duration::from_millis(220);
+
+/// How long a dropped tab takes to settle into its slot.
+const SETTLE_DURATION: Duration = Duration::from_millis(150);
+
+/// Offsets smaller than this (in pixels) aren't worth animating.
+const MIN_OFFSET: f32 = 0.5;
+
+/// Bell badge pulses per second.
+const PULSE_HZ: f32 = 0.5;
+
+/// Progress curves, mapping linear time `0..=1` to animation progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Easing {
+    /// Fast start, gentle stop.
+    OutCubic,
+    /// Overshoots the target slightly before settling, for an elastic feel.
+    OutBack,
+}
+
+impl Easing {
+    pub(super) fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::OutCubic => 1.0 - (1.0 - t).powi(3),
+            Self::OutBack => {
+                const C1: f32 = 1.70158;
+                const C3: f32 = C1 + 1.0;
+                1.0 + C3 * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2)
+            }
+        }
+    }
+}
+
+/// A value easing from `from` to zero.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Tween {
+    from: f32,
+    start: Instant,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl Tween {
+    pub(super) fn new(from: f32, duration: Duration, easing: Easing, now: Instant) -> Self {
+        Self {
+            from,
+            start: now,
+            duration,
+            easing,
+        }
+    }
+
+    pub(super) fn value(&self, now: Instant) -> f32 {
+        self.from * (1.0 - self.easing.apply(self.progress(now)))
+    }
+
+    pub(super) fn is_done(&self, now: Instant) -> bool {
+        self.progress(now) >= 1.0
+    }
+
+    fn progress(&self, now: Instant) -> f32 {
+        let elapsed = now.saturating_duration_since(self.start).as_secs_f32();
+        elapsed / self.duration.as_secs_f32().max(f32::EPSILON)
+    }
+}
+
+/// Every running animation.
+#[derive(Debug)]
+pub(crate) struct Animations {
+    /// Per window, the tween moving each tab slot's tab into place.
+    tabs: HashMap<WindowId, Vec<Option<Tween>>>,
+    /// Tab offsets as of the last tick, as the renderer reads them.
+    tab_offsets: HashMap<WindowId, Vec<f32>>,
+    /// Phase origin of the bell badge pulse.
+    pulse_start: Instant,
+}
+
+impl Animations {
+    pub(super) fn new(now: Instant) -> Self {
+        Self {
+            tabs: HashMap::new(),
+            tab_offsets: HashMap::new(),
+            pulse_start: now,
+        }
+    }
+
+    /// A dragged tab moved from slot `from` to `to`: slide the tabs it
+    /// displaced from their old slots into their new ones. `tab_w` is the
+    /// slot width and `count` the number of tabs.
+    pub(super) fn dodge_tabs(
+        &mut self,
+        window: WindowId,
+        (from, to): (usize, usize),
+        tab_w: f32,
+        count: usize,
+        now: Instant,
+    ) {
+        let mut offsets = self.reordered_offsets(window, (from, to), tab_w, count, now);
+        // The dragged tab follows the pointer.
+        offsets[to] = 0.0;
+        self.start_tabs(window, &offsets, DODGE_DURATION, Easing::OutBack, now);
+    }
+
+    /// A tab moved from slot `from` to `to` without the pointer: the tabs
+    /// it displaced dodge as for a drag, and it slides over from its old
+    /// slot too.
+    pub(super) fn slide_tab(
+        &mut self,
+        window: WindowId,
+        (from, to): (usize, usize),
+        tab_w: f32,
+        count: usize,
+        now: Instant,
+    ) {
+        let offsets = self.reordered_offsets(window, (from, to), tab_w, count, now);
+        self.start_tabs(window, &offsets, DODGE_DURATION, Easing::OutBack, now);
+    }
+
+    /// Offsets that keep every tab where it was drawn after the tab in
+    /// slot `from` moves to `to`. Running offsets follow their tabs.
+    fn reordered_offsets(
+        &self,
+        window: WindowId,
+        (from, to): (usize, usize),
+        tab_w: f32,
+        count: usize,
+        now: Instant,
+    ) -> Vec<f32> {
+        let mut offsets = self.current_offsets(window, count, now);
+        let moved = offsets.remove(from);
+        offsets.insert(to, moved + (from as f32 - to as f32) * tab_w);
+        let (displaced, shift) = if from < to {
+            (from..to, tab_w)
+        } else {
+            (to + 1..from + 1, -tab_w)
+        };
+        for offset in &mut offsets[displaced] {
+            *offset += shift;
+        }
+        offsets
+    }
+
+    /// A dragged tab was dropped `offset` pixels from its slot `idx`: ease it
+    /// into place.
+    pub(super) fn settle_tab(
+        &mut self,
+        window: WindowId,
+        (idx, offset): (usize, f32),
+        count: usize,
+        now: Instant,
+    ) {
+        let mut offsets = self.current_offsets(window, count, now);
+        if let Some(slot) = offsets.get_mut(idx) {
+            *slot = offset;
+        }
+        self.start_tabs(window, &offsets, SETTLE_DURATION, Easing::OutCubic, now);
+    }
+
+    /// Stop the window's tab animations, snapping tabs to their slots.
+    pub(super) fn clear_tabs(&mut self, window: WindowId) {
+        self.tabs.remove(&window);
+        self.tab_offsets.remove(&window);
+    }
+
+    /// Horizontal offset of each tab slot from its resting place.
+    pub(super) fn tab_offsets(&self, window: WindowId) -> &[f32] {
+        self.tab_offsets.get(&window).map_or(&[], Vec::as_slice)
+    }
+
+    /// Bell badge pulse, between 0 and 1.
+    pub(super) fn pulse_phase(&self, now: Instant) -> f32 {
+        let secs = now
+            .saturating_duration_since(self.pulse_start)
+            .as_secs_f32();
+        (secs * std::f32::consts::TAU * PULSE_HZ).sin() * 0.5 + 0.5
+    }
+
+    /// Advance to `now`, dropping finished tweens. Returns whether any tab
+    /// moved, including the final step into its slot.
+    pub(super) fn tick(&mut self, now: Instant) -> bool {
+        let moving = !self.tabs.is_empty();
+        self.tabs.retain(|_, slots| {
+            for slot in slots.iter_mut() {
+                if slot.is_some_and(|t| t.is_done(now)) {
+                    *slot = None;
+                }
+            }
+            slots.iter().any(Option::is_some)
+        });
+        self.tab_offsets = self
+            .tabs
+            .iter()
+            .map(|(&wid, slots)| {
+                let offsets = slots.iter().map(|t| t.map_or(0.0, |t| t.value(now)));
+                (wid, offsets.collect())
+            })
+            .collect();
+        moving
+    }
+
+    fn current_offsets(&self, window: WindowId, count: usize, now: Instant) -> Vec<f32> {
+        let mut offsets: Vec<f32> = self.tabs.get(&window).map_or_else(Vec::new, |slots| {
+            slots
+                .iter()
+                .map(|t| t.map_or(0.0, |t| t.value(now)))
+                .collect()
+        });
+        offsets.resize(count, 0.0);
+        offsets
+    }
+
+    fn start_tabs(
+        &mut self,
+        window: WindowId,
+        offsets: &[f32],
+        duration: Duration,
+        easing: Easing,
+        now: Instant,
+    ) {
+        let slots = offsets
+            .iter()
+            .map(|&o| (o.abs() >= MIN_OFFSET).then(|| Tween::new(o, duration, easing, now)))
+            .collect();
+        self.tabs.insert(window, slots);
+        self.tick(now);
+    }
+}
+
+impl App {
+    /// Advance every animation to `now` and request frames for the windows
+    /// still animating.
+    pub(super) fn tick_animations(&mut self, now: Instant) {
+        self.tick_kinetic_scroll();
+        let tabs_moving = self.animations.tick(now);
+        if tabs_moving {
+            self.tab_bar_dirty = true;
+        }
+        let bell_pulsing = self.unseen.any_bell();
+        if tabs_moving || bell_pulsing {
+            self.redraw
+                .request_all(self.windows.keys().copied(), RedrawReason::ANIMATION);
+        }
+    }
+
+    /// The earliest instant a timer needs the event loop: a cursor or text
+    /// blink toggle, or one of `deadlines`.
+    pub(super) fn next_timer(&self, deadlines: &[Option<Instant>]) -> Option<Instant> {
+        let cursor_toggle = self.config.terminal.cursor_blink.then(|| {
+            let elapsed_ms = self.cursor_blink_reset.elapsed().as_millis() as u64;
+            Instant::now()
+                + Duration::from_millis(self.cursor_blink().until_next_toggle(elapsed_ms))
+        });
+        [cursor_toggle, self.next_text_blink_toggle()]
+            .into_iter()
+            .chain(deadlines.iter().copied())
+            .flatten()
+            .min()
+    }
+}
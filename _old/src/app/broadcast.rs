@@ -0,0 +1,104 @@
+//! Broadcast input: keys typed in a window go to several of its tabs.
+//!
+//! `Action::ToggleBroadcast` turns broadcasting on for one window. While
+//! it's on, key presses and committed IME or compose text reach the active
+//! tab and every other tab in the window not left out from the tab menu.
+//! Each tab encodes keys with its own modes, so a tab in application
+//! cursor mode still gets the sequences it expects. The tab bar outlines
+//! the receiving tabs.
+
+use winit::event::{ElementState, KeyEvent};
+use winit::window::WindowId;
+
+use crate::key_encoding::{self, KeyEventType};
+use crate::tab::TabId;
+
+use super::input_keyboard::build_modifiers;
+use super::{App, RedrawReason};
+
+impl App {
+    /// Turn broadcast input on or off for `window_id`.
+    pub(super) fn toggle_broadcast(&mut self, window_id: WindowId) {
+        if let Some(tw) = self.windows.get_mut(&window_id) {
+            tw.broadcast = !tw.broadcast;
+            self.tab_bar_dirty = true;
+            self.redraw.request(window_id, RedrawReason::TAB_BAR);
+        }
+    }
+
+    /// Leave tab `idx` of the focused window out of its broadcast input,
+    /// or put it back.
+    pub(super) fn toggle_broadcast_tab(&mut self, idx: usize) {
+        let Some(wid) = self.focused_window else {
+            return;
+        };
+        let tab_id = self.windows.get(&wid).and_then(|tw| tw.tabs.get(idx).copied());
+        if let Some(tab) = tab_id.and_then(|tid| self.tabs.get_mut(&tid)) {
+            tab.broadcast_excluded = !tab.broadcast_excluded;
+            self.tab_bar_dirty = true;
+            self.redraw.request(wid, RedrawReason::TAB_BAR);
+        }
+    }
+
+    /// Tabs in `window_id` that get its broadcast input; empty when the
+    /// window isn't broadcasting.
+    pub(super) fn broadcast_receivers(&self, window_id: WindowId) -> Vec<TabId> {
+        let Some(tw) = self.windows.get(&window_id).filter(|tw| tw.broadcast) else {
+            return Vec::new();
+        };
+        tw.tabs
+            .iter()
+            .copied()
+            .filter(|tid| self.tabs.get(tid).is_some_and(|tab| !tab.broadcast_excluded))
+            .collect()
+    }
+
+    /// Tabs that typing in `window_id` reaches: the active tab, then the
+    /// other broadcast receivers.
+    pub(super) fn input_targets(&self, window_id: WindowId) -> Vec<TabId> {
+        let active = self.active_tab_id(window_id);
+        let receivers = self.broadcast_receivers(window_id);
+        active
+            .into_iter()
+            .chain(receivers.into_iter().filter(|tid| Some(*tid) != active))
+            .collect()
+    }
+
+    /// Encode `event` for each tab typing in `window_id` reaches and write
+    /// it. The bytes go out first: the mode comes from an atomic, so the
+    /// write never waits for the reader thread to release the terminal
+    /// lock. A press then scrolls each tab to live and clears its selection.
+    pub(super) fn send_key_to_tabs(&mut self, window_id: WindowId, event: &KeyEvent) {
+        let is_pressed = event.state == ElementState::Pressed;
+        let mods = build_modifiers(self.modifiers);
+        let evt = if event.repeat {
+            KeyEventType::Repeat
+        } else if is_pressed {
+            KeyEventType::Press
+        } else {
+            KeyEventType::Release
+        };
+        for tid in self.input_targets(window_id) {
+            let Some(tab) = self.tabs.get_mut(&tid) else {
+                continue;
+            };
+            let bytes = key_encoding::encode_key(
+                &event.logical_key,
+                mods,
+                tab.mode(),
+                event.text.as_ref().map(winit::keyboard::SmolStr::as_str),
+                event.location,
+                evt,
+            );
+            if !bytes.is_empty() {
+                tab.send_input(&bytes);
+            }
+            if is_pressed {
+                if tab.grid().display_offset != 0 {
+                    tab.scroll_to_bottom();
+                }
+                tab.clear_selection();
+            }
+        }
+    }
+}
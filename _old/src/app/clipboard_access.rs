@@ -0,0 +1,79 @@
+//! OSC 52 clipboard writes: size limit, per-tab policy, and confirmation.
+//!
+//! The PTY thread only queues a write on the terminal state. Here the main
+//! thread drops oversized payloads, then applies the tab's decision or
+//! `terminal.osc52`. With `ask`, the first write from a tab opens a prompt
+//! menu, and the answer sticks to the tab so later writes go through (or
+//! are dropped) silently.
+
+use crate::clipboard::{self, ClipboardAccess};
+use crate::context_menu;
+use crate::log;
+use crate::tab::TabId;
+use crate::tab_bar::TAB_BAR_HEIGHT;
+
+use super::{App, RedrawReason};
+
+impl App {
+    /// Apply the clipboard policy to an OSC 52 write of `text` from `tab_id`.
+    pub(super) fn handle_clipboard_request(&mut self, tab_id: TabId, text: String) {
+        let max = self.config.terminal.osc52_max_bytes;
+        if text.len() > max {
+            log(&format!(
+                "osc52: dropped {} byte write from tab {} (limit {max})",
+                text.len(),
+                tab_id.0
+            ));
+            return;
+        }
+        let access = self
+            .tabs
+            .get(&tab_id)
+            .and_then(|tab| tab.clipboard_access)
+            .unwrap_or(self.config.terminal.osc52);
+        match access {
+            ClipboardAccess::Allow => {
+                clipboard::set_text(&text);
+            }
+            ClipboardAccess::Deny => {
+                log(&format!("osc52: denied write from tab {}", tab_id.0));
+            }
+            ClipboardAccess::Ask => self.prompt_clipboard_write(tab_id, text),
+        }
+    }
+
+    /// Ask whether `tab_id` may write the clipboard, holding `text` until
+    /// the prompt is answered. A newer write replaces the held one.
+    fn prompt_clipboard_write(&mut self, tab_id: TabId, text: String) {
+        let Some(wid) = self.window_containing_tab(tab_id) else {
+            return;
+        };
+        let Some(tw) = self.windows.get(&wid) else {
+            return;
+        };
+        let width = tw.window.inner_size().width as f32;
+        let scale = self.scale_factor as f32;
+        let mut menu = context_menu::build_clipboard_prompt((0.0, 0.0), tab_id, text.len(), scale);
+        menu.layout(&self.ui_collection);
+        // Centered just below the tab bar, clear of the pointer.
+        let top = self.chrome_px(TAB_BAR_HEIGHT) as f32 + menu.menu_padding_y();
+        menu.position = (((width - menu.width) / 2.0).max(0.0), top);
+        self.context_menu = Some(menu);
+        self.pending_clipboard = Some((tab_id, text));
+        self.tab_bar_dirty = true;
+        self.redraw.request(wid, RedrawReason::INPUT);
+    }
+
+    /// Record the prompt answer for `tab_id` and apply it to the held write.
+    pub(super) fn resolve_clipboard_prompt(&mut self, tab_id: TabId, access: ClipboardAccess) {
+        if let Some(tab) = self.tabs.get_mut(&tab_id) {
+            tab.clipboard_access = Some(access);
+        }
+        let pending = self
+            .pending_clipboard
+            .take_if(|(pending_tab, _)| *pending_tab == tab_id);
+        if let (ClipboardAccess::Allow, Some((_, text))) = (access, pending) {
+            clipboard::set_text(&text);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Confirmation before closing tabs that are running a command.
+//!
+//! Closing from the keyboard, the tab bar, a menu, or the window's close
+//! button goes through here. When a tab has a foreground process other
+//! than its shell (filtered by `behavior.confirm_close_processes`), a
+//! prompt lists what's running and the close only happens once confirmed.
+//! Closing a pinned tab always asks. Tabs whose shell exits close without
+//! asking.
+
+use winit::event_loop::ActiveEventLoop;
+use winit::window::WindowId;
+
+use crate::context_menu::{self, CloseTarget};
+use crate::tab::TabId;
+use crate::tab_bar::TAB_BAR_HEIGHT;
+
+use super::{App, RedrawReason};
+
+impl App {
+    /// Close `tab_id`, asking first if it's pinned or running a command.
+    pub(super) fn request_close_tab(&mut self, tab_id: TabId, event_loop: &ActiveEventLoop) {
+        let mut reasons = self.running_processes(&[tab_id]);
+        if self.tabs.get(&tab_id).is_some_and(|tab| tab.pinned) {
+            reasons.push("This tab is pinned".to_owned());
+        }
+        match self.window_containing_tab(tab_id) {
+            Some(wid) if !reasons.is_empty() => {
+                self.prompt_close(wid, CloseTarget::Tab(tab_id), &reasons);
+            }
+            _ => self.close_tab(tab_id, event_loop),
+        }
+    }
+
+    /// Close `window_id`, asking first if any of its tabs is running a
+    /// command.
+    pub(super) fn request_close_window(
+        &mut self,
+        window_id: WindowId,
+        event_loop: &ActiveEventLoop,
+    ) {
+        let tab_ids = self
+            .windows
+            .get(&window_id)
+            .map(|tw| tw.tabs.clone())
+            .unwrap_or_default();
+        let processes = self.running_processes(&tab_ids);
+        if processes.is_empty() {
+            self.close_window(window_id, event_loop);
+        } else {
+            self.prompt_close(window_id, CloseTarget::Window(window_id), &processes);
+        }
+    }
+
+    /// A line for each command in `tab_ids` that closing should ask
+    /// about, without duplicates.
+    fn running_processes(&mut self, tab_ids: &[TabId]) -> Vec<String> {
+        let mut processes = Vec::new();
+        for tid in tab_ids {
+            let Some(tab) = self.tabs.get_mut(tid) else {
+                continue;
+            };
+            // The last wakeup may predate a command started since.
+            tab.refresh_foreground();
+            let name = tab.foreground_process();
+            if tab.is_busy() && self.config.behavior.confirms_close(name) {
+                let line = format!("{} is still running", name.unwrap_or("A command"));
+                if !processes.contains(&line) {
+                    processes.push(line);
+                }
+            }
+        }
+        processes
+    }
+
+    /// Open the close confirmation for `target` in `window_id`, centered
+    /// below the tab bar like the clipboard prompt.
+    fn prompt_close(&mut self, window_id: WindowId, target: CloseTarget, reasons: &[String]) {
+        let Some(tw) = self.windows.get(&window_id) else {
+            return;
+        };
+        let width = tw.window.inner_size().width as f32;
+        let scale = self.scale_factor as f32;
+        let mut menu = context_menu::build_close_prompt((0.0, 0.0), target, reasons, scale);
+        menu.layout(&self.ui_collection);
+        let top = self.chrome_px(TAB_BAR_HEIGHT) as f32 + menu.menu_padding_y();
+        menu.position = (((width - menu.width) / 2.0).max(0.0), top);
+        self.context_menu = Some(menu);
+        self.tab_bar_dirty = true;
+        self.redraw.request(window_id, RedrawReason::INPUT);
+    }
+}
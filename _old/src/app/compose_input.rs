@@ -0,0 +1,61 @@
+//! Dead-key and compose-key sequences in terminal windows.
+
+use winit::event::KeyEvent;
+use winit::keyboard::{Key, NamedKey};
+use winit::window::WindowId;
+
+use crate::compose::ComposeOutcome;
+
+use super::App;
+
+impl App {
+    /// Run a key press through the composer. Returns true when the key was
+    /// part of a sequence and must not reach the PTY.
+    pub(super) fn compose_key_press(&mut self, window_id: WindowId, event: &KeyEvent) -> bool {
+        let compose_key = self.config.behavior.compose_key;
+        let text = event
+            .text
+            .as_deref()
+            .filter(|t| !t.chars().any(char::is_control));
+        let outcome = if compose_key.matches(&event.logical_key, event.location) {
+            self.composer.compose_key()
+        } else if let Key::Dead(accent) = event.logical_key {
+            self.composer.dead_key(accent)
+        } else if !self.composer.is_composing() {
+            return false;
+        } else if let Some(text) = text {
+            self.composer.text(text)
+        } else if is_modifier(&event.logical_key) {
+            // Shift for an uppercase letter; the sequence goes on.
+            return true;
+        } else {
+            // Any other key abandons the sequence; Escape does nothing else.
+            self.composer.cancel();
+            return event.logical_key == Key::Named(NamedKey::Escape);
+        };
+        match outcome {
+            ComposeOutcome::Pass => false,
+            ComposeOutcome::Consumed => true,
+            ComposeOutcome::Commit(text) => {
+                self.send_committed_text(window_id, &text);
+                true
+            }
+        }
+    }
+}
+
+/// Whether `key` is a modifier on its own.
+pub(super) fn is_modifier(key: &Key) -> bool {
+    matches!(
+        key,
+        Key::Named(
+            NamedKey::Shift
+                | NamedKey::Control
+                | NamedKey::Alt
+                | NamedKey::AltGraph
+                | NamedKey::Super
+                | NamedKey::Meta
+                | NamedKey::Hyper
+        )
+    )
+}
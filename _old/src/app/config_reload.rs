@@ -1,5 +1,7 @@
 //! Configuration hot-reload — applies config changes to all windows and tabs.
 
+use std::path::PathBuf;
+
 use winit::window::WindowId;
 
 use crate::config::{self, Config};
@@ -7,13 +9,35 @@ use crate::font::FontCollection;
 use crate::keybindings;
 use crate::log;
 use crate::palette;
-use super::{App, UI_FONT_SCALE};
+use crate::shell_integration;
+use crate::url_detect::RuleSet;
+use super::{App, RedrawReason, UI_FONT_SCALE};
+
+/// Write shell-integration scripts to disk when `enabled`.
+///
+/// Returns the scripts directory new tabs should inject, or `None` when
+/// integration is disabled or the scripts could not be written.
+pub(super) fn prepare_shell_integration(enabled: bool) -> Option<PathBuf> {
+    if !enabled {
+        return None;
+    }
+    match shell_integration::ensure_scripts_on_disk(&config::config_dir()) {
+        Ok(dir) => {
+            log(&format!("shell_integration: scripts ready in {}", dir.display()));
+            Some(dir)
+        }
+        Err(e) => {
+            log(&format!("shell_integration: failed to write scripts: {e}"));
+            None
+        }
+    }
+}
 
 impl App {
     /// Applies a reloaded configuration to all windows and tabs.
     ///
-    /// Reloads the config file, applies color scheme, font, cursor style, and keybinding changes,
-    /// and marks all windows dirty for redraw.
+    /// Reloads the config file, applies color scheme, font, cursor style, keybinding,
+    /// and shell-integration changes, and marks all windows dirty for redraw.
     pub(super) fn apply_config_reload(&mut self) {
         let new_config = match Config::try_load() {
             Ok(c) => c,
@@ -94,10 +118,39 @@ impl App {
             }
         }
 
+        // Alt screen clip edge (applies from the next resize), answerback,
+        // and C1 controls
+        for tab in self.tabs.values_mut() {
+            tab.set_alt_screen_clip(new_config.terminal.alt_screen_clip);
+            tab.set_answerback(&new_config.terminal.answerback);
+            tab.set_c1_controls(new_config.terminal.c1_controls);
+        }
+
+        // Multi-click timing and cycling
+        self.clicks.set_policy(
+            new_config.behavior.double_click_interval(),
+            new_config.behavior.click_cycle,
+        );
+
+        // Clipboard history size (0 forgets every entry)
+        self.clipboard_history.set_capacity(new_config.behavior.clipboard_history);
+
         // Keybindings
-        self.bindings = keybindings::merge_bindings(&new_config.keybind);
+        self.bindings = keybindings::merge_bindings(&new_config.keybind, new_config.keybind_profile);
+
+        // Detection rules
+        self.url_cache.set_rules(RuleSet::new(&new_config.rules));
+
+        // Shell integration: (re)write scripts so new tabs pick up the toggle
+        // and any scripts removed from disk. Running shells keep their setup.
+        if new_config.behavior.shell_integration || self.shell_integration_dir.is_some() {
+            self.shell_integration_dir =
+                prepare_shell_integration(new_config.behavior.shell_integration);
+        }
 
         self.config = new_config;
+        // The rules may have changed, or the scheme they fall back to.
+        self.reapply_host_rules();
 
         // Mark everything dirty — config may affect both grid and tab bar.
         self.tab_bar_dirty = true;
@@ -106,9 +159,7 @@ impl App {
         }
 
         // Redraw all windows
-        for tw in self.windows.values() {
-            tw.window.request_redraw();
-        }
+        self.redraw.request_all(self.windows.keys().copied(), RedrawReason::CONFIG);
         log("config reload: applied successfully");
     }
 }
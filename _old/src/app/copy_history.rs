@@ -0,0 +1,61 @@
+//! Copying to the clipboard, and the clipboard history picker
+//! (`Action::PasteFromHistory`) for pasting an earlier copy.
+
+use winit::window::WindowId;
+
+use crate::clipboard;
+use crate::context_menu;
+use crate::tab::TabId;
+use crate::tab_bar::TAB_BAR_HEIGHT;
+
+use super::{App, RedrawReason};
+
+impl App {
+    /// Put `text` on the clipboard and remember it in the history.
+    pub(super) fn copy_to_clipboard(&mut self, text: &str) {
+        clipboard::set_text(text);
+        self.clipboard_history.push(text);
+    }
+
+    /// Copy the current selection text to clipboard. Returns true if text was copied.
+    pub(super) fn copy_selection_to_clipboard(&mut self, tab_id: TabId) -> bool {
+        let Some(tab) = self.tabs.get(&tab_id) else {
+            return false;
+        };
+        let Some(text) = tab.selection.as_ref().map(|sel| self.selection_text(&tab.grid(), sel))
+        else {
+            return false;
+        };
+        if text.is_empty() {
+            return false;
+        }
+        self.copy_to_clipboard(&text);
+        true
+    }
+
+    /// Open the clipboard history picker in `window_id`, centered below the
+    /// tab bar. Does nothing while the history is empty.
+    pub(super) fn open_clipboard_history(&mut self, window_id: WindowId) {
+        if self.clipboard_history.is_empty() {
+            return;
+        }
+        let Some(tw) = self.windows.get(&window_id) else {
+            return;
+        };
+        let width = tw.window.inner_size().width as f32;
+        let scale = self.scale_factor as f32;
+        let mut menu = context_menu::build_history_menu((0.0, 0.0), &self.clipboard_history, scale);
+        menu.layout(&self.ui_collection);
+        let top = self.chrome_px(TAB_BAR_HEIGHT) as f32 + menu.menu_padding_y();
+        menu.position = (((width - menu.width) / 2.0).max(0.0), top);
+        self.context_menu = Some(menu);
+        self.redraw.request(window_id, RedrawReason::INPUT);
+    }
+
+    /// Paste history entry `index` (0 = newest) into the active tab.
+    pub(super) fn paste_history_entry(&mut self, window_id: WindowId, index: usize) {
+        if let Some(text) = self.clipboard_history.get(index).map(str::to_owned) {
+            self.paste_text(window_id, &text);
+        }
+    }
+}
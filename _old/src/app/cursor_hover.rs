@@ -10,7 +10,7 @@ use crate::tab::Tab;
 use crate::tab_bar::{CONTROLS_ZONE_WIDTH, TAB_BAR_HEIGHT, TabBarHit, TabBarLayout};
 use crate::term_mode::TermMode;
 
-use super::{App, RESIZE_BORDER};
+use super::{App, RESIZE_BORDER, RedrawReason};
 
 impl App {
     /// Detects if cursor is in the resize border zone.
@@ -62,6 +62,9 @@ impl App {
         event_loop: &ActiveEventLoop,
     ) {
         self.cursor_pos.insert(window_id, position);
+        if self.update_scrollbar_drag(window_id, position) {
+            return;
+        }
 
         // Context menu hover tracking
         if let Some(ref mut menu) = self.context_menu {
@@ -69,9 +72,7 @@ impl App {
             menu.hovered = menu.hit_test(position.x as f32, position.y as f32);
             if menu.hovered != old {
                 self.tab_bar_dirty = true;
-                if let Some(tw) = self.windows.get(&window_id) {
-                    tw.window.request_redraw();
-                }
+                self.redraw.request(window_id, RedrawReason::HOVER);
             }
         }
 
@@ -87,7 +88,7 @@ impl App {
             && self.modifiers.control_key()
             && !self.is_settings_window(window_id)
         {
-            if let Some((col, line)) = self.pixel_to_cell(position) {
+            if let Some((col, line)) = self.pixel_to_cell(window_id, position) {
                 let result = self.detect_hover_url(window_id, col, line);
                 (result.cursor_icon, result.hover, result.url_range)
             } else {
@@ -113,10 +114,12 @@ impl App {
         if let Some(tw) = self.windows.get(&window_id) {
             tw.window.set_cursor(cursor_icon);
             if hover_changed {
-                tw.window.request_redraw();
+                self.redraw.request(window_id, RedrawReason::HOVER);
             }
         }
 
+        self.update_gutter_hover(window_id, position);
+
         // Mouse motion reporting (before selection drag).
         // When the PTY handles motion, skip selection drag to avoid conflicts.
         let mouse_motion_reported = self.report_mouse_motion(window_id, position);
@@ -131,6 +134,26 @@ impl App {
         self.update_drag_state(window_id, position, event_loop);
     }
 
+    /// Track the exit status mark under the mouse. Redraws while one is
+    /// hovered, since its tooltip follows the pointer.
+    fn update_gutter_hover(&mut self, window_id: WindowId, position: PhysicalPosition<f64>) {
+        let tab = self.active_tab_id(window_id).and_then(|tid| self.tabs.get(&tid));
+        let line = self.gutter_line(window_id, position).filter(|&line| {
+            tab.is_some_and(|tab| {
+                let grid = tab.grid();
+                line < grid.lines
+                    && grid.visible_row(line).meta.as_ref().is_some_and(|m| m.command.is_some())
+            })
+        });
+        let Some(tw) = self.windows.get_mut(&window_id) else {
+            return;
+        };
+        let was = std::mem::replace(&mut tw.gutter_hover, line);
+        if line.is_some() || was.is_some() {
+            self.redraw.request(window_id, RedrawReason::HOVER);
+        }
+    }
+
     /// Send mouse motion reports to the PTY when mouse tracking is active.
     ///
     /// Returns true if motion was reported (caller should skip selection drag).
@@ -154,7 +177,7 @@ impl App {
         if !(report_all || report_motion && self.left_mouse_down) {
             return false;
         }
-        let Some((col, line)) = self.pixel_to_cell(position) else {
+        let Some((col, line)) = self.pixel_to_cell(window_id, position) else {
             return false;
         };
         let cell = (col, line);
@@ -171,13 +194,14 @@ impl App {
     fn update_tab_bar_hover(&mut self, window_id: WindowId, position: PhysicalPosition<f64>) {
         let y = position.y as usize;
         let x = position.x as usize;
-        let tab_bar_h = self.scale_px(TAB_BAR_HEIGHT);
+        let tab_bar_h = self.chrome_px(TAB_BAR_HEIGHT);
 
         if y < tab_bar_h {
             if let Some(tw) = self.windows.get(&window_id) {
                 let twl = self.tab_width_lock_for(window_id);
                 let layout = TabBarLayout::compute(
                     tw.tabs.len(),
+                    self.pinned_count(window_id),
                     tw.window.inner_size().width as usize,
                     self.scale_factor,
                     twl,
@@ -186,7 +210,7 @@ impl App {
                 let prev = self.hover_hit.insert(window_id, hit);
                 if prev != Some(hit) {
                     self.tab_bar_dirty = true;
-                    tw.window.request_redraw();
+                    self.redraw.request(window_id, RedrawReason::HOVER);
                 }
             }
         } else {
@@ -198,9 +222,7 @@ impl App {
             let prev = self.hover_hit.insert(window_id, TabBarHit::None);
             if prev != Some(TabBarHit::None) {
                 self.tab_bar_dirty = true;
-                if let Some(tw) = self.windows.get(&window_id) {
-                    tw.window.request_redraw();
-                }
+                self.redraw.request(window_id, RedrawReason::HOVER);
             }
         }
     }
@@ -312,7 +334,7 @@ impl App {
                         self.drag = None;
                         self.drag_visual_x = None;
                         self.tear_off_magnetism = 0.0;
-                        self.tab_anim_offsets.remove(&source_wid);
+                        self.animations.clear_tabs(source_wid);
                         #[cfg(target_os = "windows")]
                         {
                             self.begin_os_tab_drag(new_wid, tab_id, mouse_off, grab_offset, 5);
@@ -1,6 +1,6 @@
 //! winit event loop — `ApplicationHandler` impl + window effects.
 
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
 use winit::application::ApplicationHandler;
 use winit::event::{ElementState, WindowEvent};
@@ -9,13 +9,12 @@ use winit::keyboard::{Key, NamedKey};
 use winit::window::WindowId;
 
 use crate::config;
-use crate::key_encoding::{self, KeyEventType};
-use crate::keybindings;
 use crate::log;
 use crate::tab::{Tab, TermEvent};
 use crate::term_mode::TermMode;
 
-use super::{App, build_modifiers};
+use super::redraw::Visibility;
+use super::{App, RedrawReason};
 
 impl ApplicationHandler<TermEvent> for App {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
@@ -37,7 +36,7 @@ impl ApplicationHandler<TermEvent> for App {
                     self.handle_scale_factor_changed(wid, sf);
                 }
             }
-            self.new_tab_in_window(wid);
+            self.open_first_tabs(wid);
         }
     }
 
@@ -55,17 +54,27 @@ impl ApplicationHandler<TermEvent> for App {
                 // another Wakeup after it parses more data.
                 tab.clear_wakeup();
                 tab.set_grid_dirty(true);
+                let echoed = tab.take_echo();
 
                 // Briefly lock terminal to read side-effect state.
                 let mut term = tab.terminal.lock();
                 let title_changed = term.title_dirty;
                 term.title_dirty = false;
                 let bell_active = term.bell_start.is_some();
+                let bell_rang = term.take_new_bell();
+                let mode = term.mode;
                 let notifications = term.drain_notifications();
+                let clipboard_request = term.clipboard_request.take();
                 drop(term);
 
+                if let Some(text) = clipboard_request {
+                    self.handle_clipboard_request(tab_id, text);
+                }
+
                 if title_changed {
                     self.tab_bar_dirty = true;
+                    // OSC 7 changes the host and the title together.
+                    self.update_host_rule(tab_id);
                 }
 
                 // Process bell state: if this tab rang the bell and is NOT
@@ -74,28 +83,59 @@ impl ApplicationHandler<TermEvent> for App {
                     .window_containing_tab(tab_id)
                     .and_then(|wid| self.windows.get(&wid))
                     .is_some_and(|tw| tw.active_tab_id() == Some(tab_id));
+                let is_focused = self.is_tab_focused(tab_id);
+                let mut shown = Vec::new();
+                let mut follow_match = false;
                 if let Some(tab) = self.tabs.get_mut(&tab_id) {
                     if bell_active && !is_active {
-                        tab.has_bell_badge = true;
+                        self.unseen.ring(&mut tab.unseen);
                         self.tab_bar_dirty = true;
                     }
-                    if is_active && tab.has_bell_badge {
-                        tab.has_bell_badge = false;
+                    if is_active {
+                        self.unseen.clear_bell(&mut tab.unseen);
                     }
-                }
-                for notif in notifications {
-                    if notif.title.is_empty() {
-                        log(&format!("notification: {}", notif.body));
-                    } else {
-                        log(&format!("notification: {} — {}", notif.title, notif.body));
+                    tab.has_activity |= !is_active;
+                    tab.foreground_may_have_changed(Instant::now());
+                    if is_active {
+                        tab.mark_output_seen();
                     }
+                    tab.forget_cleared_rows();
+                    follow_match = tab.refresh_search();
+                    // Background output changes the activity dot and unread
+                    // badge.
+                    if !is_active {
+                        self.tab_bar_dirty = true;
+                    }
+                    for event in notifications {
+                        let Some(notif) = tab.apply_notification_event(event) else {
+                            continue;
+                        };
+                        if !is_focused {
+                            self.unseen.notify(&mut tab.unseen);
+                            self.tab_bar_dirty = true;
+                        }
+                        shown.push(notif);
+                    }
+                }
+                if follow_match {
+                    self.scroll_to_search_match(tab_id);
+                }
+                if bell_rang {
+                    self.bell_attention(tab_id, mode);
                 }
+                for notif in &shown {
+                    self.show_desktop_notification(tab_id, notif, is_focused);
+                }
+                self.update_app_badge();
 
                 self.url_cache.invalidate();
                 if let Some(wid) = self.window_containing_tab(tab_id) {
                     if let Some(tw) = self.windows.get(&wid) {
                         if tw.active_tab_id() == Some(tab_id) || bell_active {
-                            self.pending_redraw.insert(wid);
+                            let mut reason = RedrawReason::PTY_OUTPUT;
+                            let immediate = self.config.terminal.immediate_echo;
+                            reason.set(RedrawReason::ECHO, echoed && immediate);
+                            self.redraw.request(wid, reason);
                         }
                     }
                 }
@@ -107,6 +147,33 @@ impl ApplicationHandler<TermEvent> for App {
             TermEvent::ConfigReload => {
                 self.apply_config_reload();
             }
+            TermEvent::Progress(tab_id, progress) => {
+                if let Some(tab) = self.tabs.get_mut(&tab_id) {
+                    if tab.progress != progress {
+                        tab.progress = progress;
+                        self.tab_bar_dirty = true;
+                    }
+                }
+            }
+            TermEvent::NotificationResponse(tab_id, id, response) => {
+                self.handle_notification_response(tab_id, id, response);
+            }
+            TermEvent::Ipc(call) => self.answer_ipc(&call),
+            TermEvent::Paste(tab_id) => {
+                if let Some(tab) = self.tabs.get_mut(&tab_id) {
+                    tab.reap_paste();
+                    self.tab_bar_dirty = true;
+                }
+            }
+            TermEvent::Search(tab_id, batch) => {
+                let moved = self.tabs.get_mut(&tab_id).is_some_and(|tab| tab.apply_search_batch(batch));
+                if moved {
+                    self.scroll_to_search_match(tab_id);
+                }
+                if let Some(wid) = self.window_containing_tab(tab_id) {
+                    self.redraw.request(wid, RedrawReason::INPUT);
+                }
+            }
         }
     }
 
@@ -115,92 +182,82 @@ impl ApplicationHandler<TermEvent> for App {
         #[cfg(target_os = "windows")]
         self.check_torn_off_merge();
 
-        // Tick tab drag/reorder animations (time-based decay, independent of mouse events)
-        let anim_active = self.decay_tab_animations();
-        let has_bell_badge = self.tabs.values().any(|t| t.has_bell_badge);
+        // Advance tab, bell, and kinetic scroll animations to this instant.
+        self.tick_animations(Instant::now());
+        #[cfg(target_os = "windows")]
+        if crate::platform_windows::take_appearance_changed() {
+            self.refresh_chrome_theme(None);
+        }
 
-        // Cursor blink: detect transition since last render.
+        // Cursor and text blink: detect transitions since last render.
         let cursor_blink_dirty = if self.config.terminal.cursor_blink {
             self.cursor_blink_visible() != self.prev_cursor_visible
         } else {
             false
         };
+        let text_blink_dirty = self.next_text_blink_toggle().is_some()
+            && self.text_blink_phase() != self.prev_blink_phase;
+
+        // A command starting or ending changes the busy icon and title.
+        let foreground_deadline = self.poll_foregrounds();
+
+        // Title changes, tab switches, and tab moves all dirty the tab bar.
+        if self.tab_bar_dirty {
+            self.sync_tab_focus();
+            self.update_app_badge();
+            self.update_window_titles();
+            #[cfg(target_os = "windows")]
+            self.update_taskbar_progress();
+        }
 
-        // Aggregate dirty state: pending PTY output, tab bar changes,
-        // bell animation, drag animation, cursor blink transition,
-        // or any active tab with dirty grid content.
-        let grid_dirty = self.windows.values().any(|tw| {
-            tw.active_tab_id()
+        // Turn app-wide dirty state into per-window requests.
+        let mut global = RedrawReason::empty();
+        global.set(RedrawReason::TAB_BAR, self.tab_bar_dirty);
+        global.set(RedrawReason::CURSOR_BLINK, cursor_blink_dirty);
+        global.set(RedrawReason::TEXT_BLINK, text_blink_dirty);
+        if !global.is_empty() {
+            self.redraw.request_all(self.windows.keys().copied(), global);
+        }
+        for (&wid, tw) in &self.windows {
+            let grid_dirty = tw
+                .active_tab_id()
                 .and_then(|tid| self.tabs.get(&tid))
-                .is_some_and(Tab::grid_dirty)
-        });
-        let needs_render = !self.pending_redraw.is_empty()
-            || self.tab_bar_dirty
-            || grid_dirty
-            || has_bell_badge
-            || anim_active
-            || cursor_blink_dirty;
+                .is_some_and(Tab::grid_dirty);
+            if grid_dirty {
+                self.redraw.request(wid, RedrawReason::PTY_OUTPUT);
+            }
+        }
+        self.redraw.retain(|wid| self.windows.contains_key(wid));
 
         // Direct rendering bypasses WM_PAINT starvation on Windows.
         // WM_PAINT has the lowest message priority — continuous WM_MOUSEMOVE
         // (e.g. htop with MOUSE_ALL) starves it indefinitely. Rendering from
         // about_to_wait is immune to this starvation.
-        let frame_budget = Duration::from_millis(8);
-        if needs_render && self.last_render_time.elapsed() >= frame_budget {
-            self.pending_redraw.clear();
-            let wids: Vec<WindowId> = self.windows.keys().copied().collect();
-            for wid in wids {
-                self.render_window(wid);
-            }
-            self.last_render_time = Instant::now();
+        let now = Instant::now();
+        for wid in self.redraw.due(now) {
+            self.redraw.begin_frame(wid, now);
+            self.render_window(wid);
         }
 
-        // Schedule next wake-up based on what needs attention.
-        if needs_render {
-            // Active dirty state — wake at frame budget to render.
-            let remaining = frame_budget.saturating_sub(self.last_render_time.elapsed());
-            event_loop.set_control_flow(ControlFlow::WaitUntil(Instant::now() + remaining));
-        } else if self.config.terminal.cursor_blink {
-            // Idle with cursor blink — schedule at next blink transition.
-            let interval_ms = self.config.terminal.cursor_blink_interval_ms.max(1);
-            let elapsed_ms = self.cursor_blink_reset.elapsed().as_millis() as u64;
-            let next_toggle_ms = ((elapsed_ms / interval_ms) + 1) * interval_ms;
-            let sleep_ms = next_toggle_ms.saturating_sub(elapsed_ms);
-            event_loop.set_control_flow(ControlFlow::WaitUntil(
-                Instant::now() + Duration::from_millis(sleep_ms),
-            ));
-        } else {
-            // Fully idle — sleep until next event.
-            event_loop.set_control_flow(ControlFlow::Wait);
-        }
+        // Deliver settled PTY resizes; keep waking until the rest settle.
+        let resize_deadline = self.flush_pty_resizes();
+        let chord_deadline = self.expire_key_chord();
+
+        // Sleep until the next frame is due (running animations keep one
+        // pending) or a timer fires.
+        let frame = self.redraw.next_deadline();
+        let wake = self.next_timer(&[
+            frame,
+            resize_deadline,
+            chord_deadline,
+            foreground_deadline,
+        ]);
+        event_loop.set_control_flow(wake.map_or(ControlFlow::Wait, ControlFlow::WaitUntil));
 
         // Periodic stats logging.
         self.about_to_wait_count += 1;
         if self.stats_log_time.elapsed().as_secs() >= 5 {
-            let secs = self.stats_log_time.elapsed().as_secs_f64();
-            let avg_ms = if self.render_count > 0 {
-                self.render_total_ms / f64::from(self.render_count)
-            } else {
-                0.0
-            };
-            log(&format!(
-                "stats: renders={}/s (avg {avg_ms:.1}ms, total {:.0}ms), \
-                 pty_wakeups={}/s, \
-                 win_events={}/s, cursor_moved={}/s, about_to_wait={}/s",
-                (f64::from(self.render_count) / secs) as u32,
-                self.render_total_ms,
-                (f64::from(self.pty_event_count) / secs) as u32,
-                (f64::from(self.window_event_count) / secs) as u32,
-                (f64::from(self.cursor_moved_count) / secs) as u32,
-                (f64::from(self.about_to_wait_count) / secs) as u32,
-            ));
-            self.render_count = 0;
-            self.render_total_ms = 0.0;
-            self.pty_event_count = 0;
-            self.window_event_count = 0;
-            self.cursor_moved_count = 0;
-            self.about_to_wait_count = 0;
-            self.stats_log_time = Instant::now();
+            self.log_stats();
         }
     }
 
@@ -213,18 +270,32 @@ impl ApplicationHandler<TermEvent> for App {
         self.window_event_count += 1;
         match event {
             WindowEvent::CloseRequested => {
-                self.close_window(window_id, event_loop);
+                self.request_close_window(window_id, event_loop);
             }
 
             WindowEvent::RedrawRequested => {
+                self.redraw.begin_frame(window_id, Instant::now());
                 self.render_window(window_id);
             }
 
+            WindowEvent::Occluded(occluded) => {
+                let visibility = if occluded {
+                    Visibility::Hidden
+                } else {
+                    Visibility::Visible
+                };
+                self.redraw.set_occlusion(window_id, visibility);
+            }
+
+            WindowEvent::Moved(_) => {
+                // The window may have landed on a display with another refresh rate.
+                self.update_frame_interval(window_id);
+            }
+
             WindowEvent::Resized(size) => {
+                self.redraw.set_size(window_id, size.width, size.height);
                 self.handle_resize(window_id, size.width, size.height);
-                if let Some(tw) = self.windows.get(&window_id) {
-                    tw.window.request_redraw();
-                }
+                self.redraw.request(window_id, RedrawReason::RESIZE);
             }
 
             WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
@@ -239,8 +310,8 @@ impl ApplicationHandler<TermEvent> for App {
                 self.handle_mouse_input(window_id, state, button, event_loop);
             }
 
-            WindowEvent::MouseWheel { delta, .. } => {
-                self.handle_mouse_wheel(window_id, delta);
+            WindowEvent::MouseWheel { delta, phase, .. } => {
+                self.handle_mouse_wheel(window_id, delta, phase);
             }
 
             WindowEvent::CursorMoved { position, .. } => {
@@ -248,6 +319,30 @@ impl ApplicationHandler<TermEvent> for App {
                 self.handle_cursor_moved(window_id, position, event_loop);
             }
 
+            WindowEvent::Ime(ime) if self.is_settings_window(window_id) => {
+                self.handle_settings_ime(window_id, ime);
+            }
+
+            WindowEvent::Ime(ime) if self.is_renaming_tab(window_id) => {
+                self.handle_tab_rename_ime(window_id, ime);
+            }
+
+            WindowEvent::Ime(ime) if self.tab_switcher_in(window_id).is_some() => {
+                self.handle_tab_switcher_ime(window_id, ime);
+            }
+
+            WindowEvent::Ime(ime) if self.search_active == Some(window_id) => {
+                self.handle_search_ime(window_id, ime);
+            }
+
+            WindowEvent::Ime(ime) if self.inspector_focus == Some(window_id) => {
+                self.handle_inspector_ime(window_id, ime);
+            }
+
+            WindowEvent::Ime(ime) => {
+                self.handle_ime(window_id, ime);
+            }
+
             WindowEvent::KeyboardInput { event, .. } => {
                 // Allow key release through only when Kitty REPORT_EVENT_TYPES is active.
                 if event.state != ElementState::Pressed {
@@ -260,12 +355,15 @@ impl ApplicationHandler<TermEvent> for App {
                     }
                 }
 
+                if event.repeat && self.is_repeat_suppressed(&event.logical_key) {
+                    return;
+                }
                 let is_pressed = event.state == ElementState::Pressed;
 
-                // Settings window: Escape closes it, all other keys ignored
-                if is_pressed && self.is_settings_window(window_id) {
-                    if matches!(event.logical_key, Key::Named(NamedKey::Escape)) {
-                        self.close_settings_window();
+                // Settings window: keys go to its filter box
+                if self.is_settings_window(window_id) {
+                    if is_pressed {
+                        self.handle_settings_key(window_id, &event);
                     }
                     return;
                 }
@@ -278,110 +376,105 @@ impl ApplicationHandler<TermEvent> for App {
                     return;
                 }
 
-                // Search mode: intercept all keys when search is active
+                // Escape cancels a paste in flight
+                if is_pressed
+                    && matches!(event.logical_key, Key::Named(NamedKey::Escape))
+                    && self.cancel_paste(window_id)
+                {
+                    return;
+                }
+
+                // Tab rename field: intercept all keys while it has focus
+                if self.is_renaming_tab(window_id) {
+                    if is_pressed {
+                        self.handle_tab_rename_key(window_id, &event);
+                    }
+                    return;
+                }
+
+                // Tab switcher: intercept all keys while it is open
+                if self.tab_switcher_in(window_id).is_some() {
+                    if is_pressed {
+                        self.handle_tab_switcher_key(window_id, &event);
+                    }
+                    return;
+                }
+
+                // Search mode: intercept all keys when search is active,
+                // apart from bindings limited to search
                 if self.search_active == Some(window_id) {
+                    if self.binding_key_event(window_id, &event, event_loop) {
+                        return;
+                    }
                     if is_pressed {
                         self.handle_search_key(window_id, &event);
                     }
                     return;
                 }
 
+                // Inspector filter box: intercept all keys while it has focus
+                if self.inspector_focus == Some(window_id) {
+                    if is_pressed {
+                        self.handle_inspector_key(window_id, &event);
+                    }
+                    return;
+                }
+
+                // Keys belong to the input method while it is composing
+                if self.ime_preedit.contains_key(&window_id) {
+                    return;
+                }
+                if is_pressed && self.compose_key_press(window_id, &event) {
+                    return;
+                }
+
                 // Handle Escape during drag
                 if is_pressed && matches!(event.logical_key, Key::Named(NamedKey::Escape)) {
                     if let Some(drag) = self.drag.take() {
                         // Clear drag visuals and animation state
-                        self.tab_anim_offsets.remove(&drag.source_window);
+                        self.animations.clear_tabs(drag.source_window);
                         self.drag_visual_x = None;
                         self.tab_bar_dirty = true;
                         // Redraw all windows
-                        for tw in self.windows.values() {
-                            tw.window.request_redraw();
-                        }
+                        self.redraw.request_all(self.windows.keys().copied(), RedrawReason::INPUT);
                         return;
                     }
                 }
 
-                // Keybinding lookup
-                let mods = build_modifiers(self.modifiers);
-                if let Some(binding_key) = keybindings::key_to_binding_key(&event.logical_key) {
-                    if let Some(action) =
-                        keybindings::find_binding(&self.bindings, &binding_key, mods)
-                    {
-                        let action = action.clone();
-                        if is_pressed {
-                            if self.execute_action(&action, window_id, event_loop) {
-                                return;
-                            }
-                            // SmartCopy with no selection — fall through to PTY
-                        } else {
-                            return; // matched binding on key release — consume
-                        }
-                    }
+                // Keybindings, including chord sequences
+                if self.binding_key_event(window_id, &event, event_loop) {
+                    return;
                 }
 
-                // Any keyboard input to PTY — scroll to live and clear selection
+                // Any keyboard input to PTY (broadcast to other tabs too)
                 if is_pressed {
                     self.cursor_blink_reset = Instant::now();
                 }
-                if let Some(tid) = self.active_tab_id(window_id) {
-                    // Scroll to live on press (not release).
-                    if is_pressed {
-                        if let Some(tab) = self.tabs.get_mut(&tid) {
-                            if tab.grid().display_offset != 0 {
-                                tab.scroll_to_bottom();
-                            }
-                            tab.clear_selection();
-                        }
-                    }
+                self.send_key_to_tabs(window_id, &event);
+            }
 
-                    if let Some(tab) = self.tabs.get_mut(&tid) {
-                        let mods = build_modifiers(self.modifiers);
-                        let evt = if event.repeat {
-                            KeyEventType::Repeat
-                        } else if event.state == ElementState::Pressed {
-                            KeyEventType::Press
-                        } else {
-                            KeyEventType::Release
-                        };
-                        let bytes = key_encoding::encode_key(
-                            &event.logical_key,
-                            mods,
-                            tab.mode(),
-                            event.text.as_ref().map(winit::keyboard::SmolStr::as_str),
-                            event.location,
-                            evt,
-                        );
-                        if !bytes.is_empty() {
-                            tab.send_pty(&bytes);
-                        }
-                    }
-                }
+            WindowEvent::ThemeChanged(theme) => {
+                self.refresh_chrome_theme(Some(theme));
             }
 
             WindowEvent::Focused(focused) => {
+                if focused {
+                    self.focused_window = Some(window_id);
+                    self.update_app_badge();
+                } else {
+                    self.focused_window.take_if(|wid| *wid == window_id);
+                }
+                self.sync_tab_focus();
+                if !focused {
+                    self.composer.cancel();
+                }
                 // Dismiss context menu on focus loss
                 if !focused && self.context_menu.is_some() {
                     self.dismiss_context_menu(window_id);
                 }
-                // Skip settings window — no PTY to send to
-                if self.is_settings_window(window_id) {
-                    return;
-                }
                 if focused {
                     self.cursor_blink_reset = Instant::now();
                 }
-                if let Some(tid) = self.active_tab_id(window_id) {
-                    if let Some(tab) = self.tabs.get_mut(&tid) {
-                        if tab.mode().contains(TermMode::FOCUS_IN_OUT) {
-                            let seq = if focused {
-                                b"\x1b[I" as &[u8]
-                            } else {
-                                b"\x1b[O"
-                            };
-                            tab.send_pty(seq);
-                        }
-                    }
-                }
             }
 
             _ => {}
@@ -0,0 +1,94 @@
+//! Applying `[[host_rule]]` tables to tabs as their host and title change.
+
+use crate::config::{HostRule, find_host_rule};
+use crate::palette;
+use crate::tab::TabId;
+
+use super::App;
+use super::redraw::RedrawReason;
+
+impl App {
+    /// Re-match `tab_id` after its OSC 7 host or title changed, restyling
+    /// it if a different rule now applies.
+    pub(super) fn update_host_rule(&mut self, tab_id: TabId) {
+        let rule = self.matching_host_rule(tab_id);
+        if self
+            .tabs
+            .get(&tab_id)
+            .is_some_and(|tab| tab.host_rule != rule)
+        {
+            self.apply_host_rule(tab_id, rule);
+        }
+    }
+
+    /// Re-match every tab after the rules or the color scheme changed.
+    pub(super) fn reapply_host_rules(&mut self) {
+        let tab_ids: Vec<TabId> = self.tabs.keys().copied().collect();
+        for tab_id in tab_ids {
+            let rule = self.matching_host_rule(tab_id);
+            // Tabs untouched by rules already have the global scheme.
+            if rule.is_some()
+                || self
+                    .tabs
+                    .get(&tab_id)
+                    .is_some_and(|t| t.host_rule.is_some())
+            {
+                self.apply_host_rule(tab_id, rule);
+            }
+        }
+    }
+
+    fn matching_host_rule(&self, tab_id: TabId) -> Option<usize> {
+        let tab = self.tabs.get(&tab_id)?;
+        let term = tab.terminal.lock();
+        find_host_rule(
+            &self.config.host_rule,
+            term.host.as_deref(),
+            &term.effective_title(),
+        )
+    }
+
+    /// Give `tab_id` the scheme of `rule`, or the global scheme without one.
+    fn apply_host_rule(&mut self, tab_id: TabId, rule: Option<usize>) {
+        let scheme = rule
+            .and_then(|i| self.config.host_rule.get(i))
+            .and_then(|r| r.scheme.as_deref())
+            .and_then(palette::find_scheme)
+            .or_else(|| palette::find_scheme(self.active_scheme));
+        let Some(tab) = self.tabs.get_mut(&tab_id) else {
+            return;
+        };
+        tab.host_rule = rule;
+        tab.apply_color_config(
+            scheme,
+            &self.config.colors,
+            self.config.behavior.bold_is_bright,
+        );
+        tab.set_grid_dirty(true);
+        if let Some(wid) = self.window_containing_tab(tab_id) {
+            self.redraw.request(wid, RedrawReason::CONFIG);
+        }
+    }
+
+    /// Border color of the host rule styling `tab_id`, if any.
+    pub(super) fn host_rule_border(&self, tab_id: TabId) -> Option<vte::ansi::Rgb> {
+        self.host_rule_color(tab_id, |rule| rule.border.as_deref())
+    }
+
+    /// Tab bar accent of `tab_id`: the color its program set with OSC 6,
+    /// else the `tab_color` of its host rule.
+    pub(super) fn tab_accent(&self, tab_id: TabId) -> Option<vte::ansi::Rgb> {
+        let tab = self.tabs.get(&tab_id)?;
+        let osc_color = tab.terminal.lock().tab_color;
+        osc_color.or_else(|| self.host_rule_color(tab_id, |rule| rule.tab_color.as_deref()))
+    }
+
+    fn host_rule_color(
+        &self,
+        tab_id: TabId,
+        field: impl Fn(&HostRule) -> Option<&str>,
+    ) -> Option<vte::ansi::Rgb> {
+        let rule = self.tabs.get(&tab_id)?.host_rule?;
+        field(self.config.host_rule.get(rule)?).and_then(palette::parse_hex_color)
+    }
+}
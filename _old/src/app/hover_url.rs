@@ -1,16 +1,24 @@
-//! Hyperlink and implicit URL hover detection and opening.
+//! Hyperlink, rule match, and implicit URL hover detection and actions.
 
+use std::path::Path;
+use std::process::Command;
+
+use winit::dpi::PhysicalPosition;
 use winit::window::{CursorIcon, WindowId};
 
+use crate::clipboard;
+use crate::hyperlink::LinkId;
 use crate::log;
-use crate::url_detect::UrlSegment;
+use crate::tab::{Tab, TabId};
+use crate::url_detect::{FileTarget, MatchAction, UrlSegment, file_url, is_executable};
 
 use super::App;
 
 /// Result of hover URL detection at the current cursor position.
 pub(super) struct HoverResult {
     pub cursor_icon: CursorIcon,
-    pub hover: Option<(WindowId, String)>,
+    /// The hovered OSC 8 link; implicit URLs are reported in `url_range`.
+    pub hover: Option<(WindowId, LinkId)>,
     pub url_range: Option<Vec<UrlSegment>>,
 }
 
@@ -53,18 +61,18 @@ impl App {
         if let Some(h) = row[col].hyperlink() {
             return HoverResult {
                 cursor_icon: CursorIcon::Pointer,
-                hover: Some((window_id, h.uri.clone())),
+                hover: Some((window_id, h.id)),
                 url_range: None,
             };
         }
 
         // Fall through to implicit URL detection.
-        let url_hit = self.url_cache.url_at(&grid, abs_row, col);
+        let url_hit = self.url_cache.match_at(&grid, abs_row, col);
         drop(grid);
         if let Some(hit) = url_hit {
             return HoverResult {
                 cursor_icon: CursorIcon::Pointer,
-                hover: Some((window_id, hit.url)),
+                hover: None,
                 url_range: Some(hit.segments),
             };
         }
@@ -72,6 +80,84 @@ impl App {
         no_hit
     }
 
+    /// The OSC 8 hyperlink or detected match at (`abs_row`, `col`) of the
+    /// tab's grid and what Ctrl+click does with it.
+    pub(super) fn link_at(&mut self, tab_id: TabId, abs_row: usize, col: usize) -> Option<MatchAction> {
+        let tab = self.tabs.get(&tab_id)?;
+        let grid = tab.grid();
+        let row = grid.absolute_row(abs_row)?;
+        if col >= row.len() {
+            return None;
+        }
+        // OSC 8 hyperlink takes priority.
+        if let Some(h) = row[col].hyperlink() {
+            return Some(MatchAction::Open(h.uri.clone()));
+        }
+        // Fall through to rule and implicit URL detection.
+        self.url_cache.match_at(&grid, abs_row, col).map(|hit| hit.action)
+    }
+
+    /// The URL under the pixel `pos` of the window's active tab, for the
+    /// right-click link menu.
+    pub(super) fn url_at_pixel(
+        &mut self,
+        window_id: WindowId,
+        pos: PhysicalPosition<f64>,
+    ) -> Option<String> {
+        let (col, line) = self.pixel_to_cell(window_id, pos)?;
+        let tab_id = self.active_tab_id(window_id)?;
+        let abs_row = self.tabs.get(&tab_id)?.grid().viewport_to_absolute(line);
+        match self.link_at(tab_id, abs_row, col)? {
+            MatchAction::Open(url) => Some(url),
+            _ => None,
+        }
+    }
+
+    /// Carry out what Ctrl+click on a link, rule match, or path asks for.
+    pub(super) fn run_match_action(&self, tab_id: TabId, action: MatchAction) {
+        match action {
+            MatchAction::Open(url) => Self::open_url(&url),
+            MatchAction::Copy(text) => {
+                clipboard::set_text(&text);
+            }
+            MatchAction::Run { program, args } => {
+                log(&format!("rules: running {program} {args:?}"));
+                if let Err(e) = Command::new(&program).args(&args).spawn() {
+                    log(&format!("rules: failed to run {program}: {e}"));
+                }
+            }
+            MatchAction::OpenFile(target) => self.open_file(tab_id, &target),
+        }
+    }
+
+    /// Open a detected file in `behavior.editor`, or with the system handler.
+    /// Relative paths resolve against the tab's working directory. Without
+    /// an editor, programs and scripts are refused: the system handler
+    /// would run them.
+    fn open_file(&self, tab_id: TabId, target: &FileTarget) {
+        let cwd = self.tabs.get(&tab_id).and_then(Tab::cwd);
+        let Some(path) = target.resolve(cwd.as_deref().map(Path::new)) else {
+            log(&format!("open file: {} not found", target.path));
+            return;
+        };
+        let Some(template) = self.config.behavior.editor.as_deref() else {
+            if is_executable(&path) {
+                log(&format!("open file: refusing to run {}", path.display()));
+            } else {
+                Self::open_url(&file_url(&path));
+            }
+            return;
+        };
+        let argv = target.editor_argv(template, &path);
+        let Some((program, args)) = argv.split_first() else {
+            return;
+        };
+        log(&format!("open file: running {program} {args:?}"));
+        if let Err(e) = Command::new(program).args(args).spawn() {
+            log(&format!("open file: failed to run {program}: {e}"));
+        }
+    }
+
     /// Open a URL in the default browser. Only allows safe schemes.
     ///
     /// On Windows, uses `ShellExecuteW` directly (like Windows Terminal and
@@ -115,11 +201,11 @@ impl App {
         }
         #[cfg(target_os = "linux")]
         {
-            let _ = std::process::Command::new("xdg-open").arg(uri).spawn();
+            let _ = Command::new("xdg-open").arg(uri).spawn();
         }
         #[cfg(target_os = "macos")]
         {
-            let _ = std::process::Command::new("open").arg(uri).spawn();
+            let _ = Command::new("open").arg(uri).spawn();
         }
     }
 }
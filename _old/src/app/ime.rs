@@ -0,0 +1,84 @@
+//! IME composition in terminal windows.
+//!
+//! While an input method composes, its preedit string is drawn over the
+//! cells at the terminal cursor, underlined, and key presses go to the IME
+//! instead of the PTY. Committed text is sent to the PTY as if typed.
+
+use std::time::Instant;
+
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::event::Ime;
+use winit::window::WindowId;
+
+use crate::grid::GRID_PADDING_LEFT;
+use crate::ui::text_input::Preedit;
+
+use super::{App, RedrawReason};
+
+impl App {
+    /// Handle an IME event for a terminal window.
+    pub(super) fn handle_ime(&mut self, window_id: WindowId, ime: Ime) {
+        match ime {
+            Ime::Preedit(text, cursor) if !text.is_empty() => {
+                self.ime_preedit
+                    .insert(window_id, Preedit::new(&text, cursor));
+            }
+            Ime::Commit(text) => {
+                self.ime_preedit.remove(&window_id);
+                self.send_committed_text(window_id, &text);
+            }
+            Ime::Preedit(..) | Ime::Enabled | Ime::Disabled => {
+                self.ime_preedit.remove(&window_id);
+            }
+        }
+        self.place_ime_cursor_area(window_id);
+        self.mark_active_grid_dirty(window_id);
+        self.redraw.request(window_id, RedrawReason::INPUT);
+    }
+
+    /// Type IME or compose `text` into the window's active tab (and any
+    /// broadcast receivers), the same way a key press is delivered:
+    /// written first, then back to the live screen with the selection
+    /// cleared.
+    pub(super) fn send_committed_text(&mut self, window_id: WindowId, text: &str) {
+        self.cursor_blink_reset = Instant::now();
+        for tid in self.input_targets(window_id) {
+            let Some(tab) = self.tabs.get_mut(&tid) else {
+                continue;
+            };
+            tab.send_input(text.as_bytes());
+            if tab.grid().display_offset != 0 {
+                tab.scroll_to_bottom();
+            }
+            tab.clear_selection();
+        }
+    }
+
+    /// Tell the IME where the terminal cursor is, so its candidate window
+    /// opens next to the composition.
+    fn place_ime_cursor_area(&self, window_id: WindowId) {
+        let Some(tw) = self.windows.get(&window_id) else {
+            return;
+        };
+        let Some(tab) = self
+            .active_tab_id(window_id)
+            .and_then(|tid| self.tabs.get(&tid))
+        else {
+            return;
+        };
+        let (col, row) = {
+            let grid = tab.grid();
+            (grid.cursor.col, grid.cursor.row)
+        };
+        let (cw, ch) = (
+            self.font_collection.cell_width,
+            self.font_collection.cell_height,
+        );
+        let x = self.chrome_px(GRID_PADDING_LEFT) + col * cw;
+        let y = self.grid_top() + row * ch;
+        tw.window.set_ime_cursor_area(
+            PhysicalPosition::new(x as u32, y as u32),
+            PhysicalSize::new(cw as u32, ch as u32),
+        );
+    }
+}
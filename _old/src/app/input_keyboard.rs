@@ -1,13 +1,26 @@
 //! Keyboard input handling — action dispatch from keybindings.
 
 use winit::event_loop::ActiveEventLoop;
+use winit::keyboard::{Key, ModifiersState};
 use winit::window::WindowId;
 
-use crate::keybindings::Action;
+use crate::keybindings::{self, Action};
+use crate::grid::Grid;
+use crate::key_encoding::Modifiers;
+use crate::search::SearchState;
+use crate::selection::{self, Concealed, Selection, TrailingWhitespace};
 
-use super::App;
+use super::{App, RedrawReason};
 
 impl App {
+    /// Whether OS auto-repeat of `key` is ignored (`behavior.no_repeat`).
+    /// Only the repeats are dropped: the first press and, with kitty event
+    /// types (`REPORT_EVENT_TYPES`), the release still reach the program,
+    /// so it never sees a key stuck down.
+    pub(super) fn is_repeat_suppressed(&self, key: &Key) -> bool {
+        keybindings::key_in_list(key, &self.config.behavior.no_repeat)
+    }
+
     /// Mutate the active tab for `window_id` and request a redraw.
     /// Returns `true` if the tab was found and the closure ran.
     fn with_active_tab_redraw(
@@ -21,12 +34,49 @@ impl App {
         if let Some(tab) = self.tabs.get_mut(&tid) {
             f(tab);
         }
-        if let Some(tw) = self.windows.get(&window_id) {
-            tw.window.request_redraw();
-        }
+        self.redraw.request(window_id, RedrawReason::INPUT);
         true
     }
 
+    /// The text of `sel` in `grid` as it's copied, per
+    /// `terminal.copy_concealed` and `behavior.strip_trailing_whitespace`.
+    pub(super) fn selection_text(&self, grid: &Grid, sel: &Selection) -> String {
+        let concealed = if self.config.terminal.copy_concealed {
+            Concealed::Include
+        } else {
+            Concealed::Blank
+        };
+        let trailing = if self.config.behavior.strip_trailing_whitespace {
+            TrailingWhitespace::Strip
+        } else {
+            TrailingWhitespace::Keep
+        };
+        selection::extract_text(grid, sel, concealed, trailing)
+    }
+
+    /// Put the output of the active tab's most recent command on the
+    /// clipboard, leaving the selection alone.
+    fn copy_last_output(&mut self, window_id: WindowId) {
+        let Some(tab) = self.active_tab_id(window_id).and_then(|tid| self.tabs.get(&tid)) else {
+            return;
+        };
+        let grid = tab.grid();
+        let text = Selection::last_output(&grid).map(|sel| self.selection_text(&grid, &sel));
+        drop(grid);
+        if let Some(text) = text.filter(|t| !t.is_empty()) {
+            self.copy_to_clipboard(&text);
+        }
+    }
+
+    /// Write `bytes` to the active tab's PTY.
+    fn send_to_active_tab(&mut self, window_id: WindowId, bytes: &[u8]) {
+        if let Some(tid) = self.active_tab_id(window_id) {
+            if let Some(tab) = self.tabs.get_mut(&tid) {
+                tab.send_input(bytes);
+            }
+        }
+    }
+
     /// Switch to an adjacent tab (wrapping). `delta` is +1 for next, -1 for prev.
     fn cycle_tab(&mut self, window_id: WindowId, delta: isize) {
         if let Some(tw) = self.windows.get_mut(&window_id) {
@@ -34,7 +84,7 @@ impl App {
             if n > 1 {
                 tw.active_tab = (tw.active_tab as isize + delta).rem_euclid(n as isize) as usize;
                 self.tab_bar_dirty = true;
-                tw.window.request_redraw();
+                self.redraw.request(window_id, RedrawReason::INPUT);
             }
         }
     }
@@ -60,7 +110,7 @@ impl App {
             }
             Action::CloseTab => {
                 if let Some(tid) = self.active_tab_id(window_id) {
-                    self.close_tab(tid, event_loop);
+                    self.request_close_tab(tid, event_loop);
                 }
             }
             Action::NextTab => self.cycle_tab(window_id, 1),
@@ -103,9 +153,7 @@ impl App {
                         if let Some(tab) = self.tabs.get_mut(&tid) {
                             tab.clear_selection();
                         }
-                        if let Some(tw) = self.windows.get(&window_id) {
-                            tw.window.request_redraw();
-                        }
+                        self.redraw.request(window_id, RedrawReason::INPUT);
                     } else {
                         // No selection — fall through to PTY.
                         return false;
@@ -128,23 +176,56 @@ impl App {
                     tab.navigate_to_next_prompt();
                 });
             }
-            Action::SendText(text) => {
-                if let Some(tid) = self.active_tab_id(window_id) {
-                    if let Some(tab) = self.tabs.get_mut(&tid) {
-                        tab.send_pty(text.as_bytes());
-                    }
-                }
-            }
+            Action::SendText(text) => self.send_to_active_tab(window_id, text.as_bytes()),
+            Action::SendHex(bytes) => self.send_to_active_tab(window_id, bytes),
             Action::DuplicateTab => {
                 if let Some(tw) = self.windows.get(&window_id) {
                     self.duplicate_tab_at(tw.active_tab);
                 }
             }
+            Action::RenameTab => {
+                if let Some(tw) = self.windows.get(&window_id) {
+                    self.start_tab_rename(window_id, tw.active_tab);
+                }
+            }
             Action::MoveTabToNewWindow => {
                 if let Some(tw) = self.windows.get(&window_id) {
                     self.move_tab_to_new_window(tw.active_tab, event_loop);
                 }
             }
+            Action::TogglePinTab => {
+                if let Some(tw) = self.windows.get(&window_id) {
+                    self.toggle_pin_tab(window_id, tw.active_tab);
+                }
+            }
+            Action::OpenTabSwitcher => self.open_tab_switcher(window_id),
+            Action::MoveTabLeft => self.move_active_tab(window_id, -1),
+            Action::MoveTabRight => self.move_active_tab(window_id, 1),
+            Action::MoveTabToWindow(n) => self.move_active_tab_to_window(window_id, *n),
+            Action::ToggleFocusMode => self.toggle_focus_mode(),
+            Action::ToggleInspector => self.toggle_inspector(window_id),
+            Action::ToggleBroadcast => self.toggle_broadcast(window_id),
+            Action::ClearScreenAndScrollback => {
+                self.with_active_tab_redraw(
+                    window_id,
+                    crate::tab::Tab::clear_screen_and_scrollback,
+                );
+            }
+            Action::ClearScrollback => {
+                self.with_active_tab_redraw(window_id, crate::tab::Tab::clear_scrollback);
+            }
+            Action::SelectAll => {
+                self.with_active_tab_redraw(window_id, crate::tab::Tab::select_all);
+            }
+            Action::CopyLastOutput => self.copy_last_output(window_id),
+            Action::PasteFromHistory => self.open_clipboard_history(window_id),
+            Action::NextMatch => return self.step_search_match(window_id, SearchState::next_match),
+            Action::PreviousMatch => {
+                return self.step_search_match(window_id, SearchState::prev_match);
+            }
+            Action::SelectLastOutput => {
+                self.with_active_tab_redraw(window_id, crate::tab::Tab::select_last_output);
+            }
             Action::None => {
                 // Explicitly unbound — should not appear after merge, but
                 // consume the key if it does.
@@ -153,3 +234,21 @@ impl App {
         true
     }
 }
+
+/// Translate winit's modifier state into the key encoder's flags.
+pub(super) fn build_modifiers(m: ModifiersState) -> Modifiers {
+    let mut mods = Modifiers::empty();
+    if m.shift_key() {
+        mods |= Modifiers::SHIFT;
+    }
+    if m.alt_key() {
+        mods |= Modifiers::ALT;
+    }
+    if m.control_key() {
+        mods |= Modifiers::CONTROL;
+    }
+    if m.super_key() {
+        mods |= Modifiers::SUPER;
+    }
+    mods
+}
@@ -3,19 +3,19 @@
 use std::time::Instant;
 
 use winit::dpi::PhysicalPosition;
-use winit::event::{ElementState, MouseButton, MouseScrollDelta};
+use winit::event::{ElementState, MouseButton};
 use winit::event_loop::ActiveEventLoop;
 use winit::window::WindowId;
 
-use crate::context_menu;
+use crate::context_menu::{self, TabMenuChecks};
 use crate::drag::DragState;
-use crate::selection::Selection;
+use crate::selection::{Selection, SelectionMode};
 use crate::tab_bar::{
     NEW_TAB_BUTTON_WIDTH, TAB_BAR_HEIGHT, TAB_LEFT_MARGIN, TabBarHit, TabBarLayout,
 };
 use crate::term_mode::TermMode;
 
-use super::{App, DOUBLE_CLICK_MS, SCROLL_LINES};
+use super::{App, RedrawReason};
 
 impl App {
     #[allow(clippy::too_many_lines, reason = "event dispatch table with inline handlers")]
@@ -43,7 +43,9 @@ impl App {
                 match entry {
                     context_menu::MenuEntry::Item { action, .. }
                     | context_menu::MenuEntry::Check { action, .. } => Some(action.clone()),
-                    context_menu::MenuEntry::Separator => None,
+                    context_menu::MenuEntry::Label { .. } | context_menu::MenuEntry::Separator => {
+                        None
+                    }
                 }
             });
             let clicked_inside = self
@@ -65,6 +67,27 @@ impl App {
             // Clicked outside the menu — fall through to process the click normally
         }
 
+        if button == MouseButton::Left && state == ElementState::Pressed {
+            self.commit_tab_rename();
+            self.close_tab_switcher();
+            self.search_bar_click(window_id, pos.y);
+        }
+
+        // The scrollbar takes its presses ahead of mouse reporting and
+        // selection; resize borders at the window edge still win.
+        if button == MouseButton::Left {
+            match state {
+                ElementState::Pressed
+                    if self.resize_direction_at(window_id, pos).is_none()
+                        && self.start_scrollbar_drag(window_id, pos) =>
+                {
+                    return;
+                }
+                ElementState::Released if self.scrollbar_drag.take().is_some() => return,
+                _ => {}
+            }
+        }
+
         // Mouse reporting: if any mouse mode is active and Shift is NOT held,
         // report to PTY and skip normal handling (Shift overrides mouse reporting
         // so the user can still select text).
@@ -75,7 +98,7 @@ impl App {
                     .get(&tid)
                     .is_some_and(|t| t.mode().intersects(TermMode::ANY_MOUSE));
                 if mouse_active {
-                    if let Some((col, line)) = self.pixel_to_cell(pos) {
+                    if let Some((col, line)) = self.pixel_to_cell(window_id, pos) {
                         let btn_code = match button {
                             MouseButton::Left => 0u8,
                             MouseButton::Middle => 1,
@@ -106,13 +129,14 @@ impl App {
         // Right-click handling
         if button == MouseButton::Right {
             if state == ElementState::Released {
-                let tab_bar_h = self.scale_px(TAB_BAR_HEIGHT);
+                let tab_bar_h = self.chrome_px(TAB_BAR_HEIGHT);
                 if y < tab_bar_h {
                     // Right-click in tab bar → context menu overlay
                     if let Some(tw) = self.windows.get(&window_id) {
                         let twl = self.tab_width_lock_for(window_id);
                         let layout = TabBarLayout::compute(
                             tw.tabs.len(),
+                            self.pinned_count(window_id),
                             tw.window.inner_size().width as usize,
                             self.scale_factor,
                             twl,
@@ -122,37 +146,54 @@ impl App {
                         let menu_pos = (pos.x as f32, pos.y as f32);
                         let mut menu = match hit {
                             TabBarHit::Tab(idx) | TabBarHit::CloseTab(idx) => {
-                                context_menu::build_tab_menu(menu_pos, idx, s)
+                                let tab = tw.tabs.get(idx).and_then(|tid| self.tabs.get(tid));
+                                let checks = TabMenuChecks {
+                                    muted: tab.is_some_and(|tab| tab.notifications_muted),
+                                    pinned: tab.is_some_and(|tab| tab.pinned),
+                                    broadcast: tw.broadcast.then(|| {
+                                        tab.is_some_and(|tab| !tab.broadcast_excluded)
+                                    }),
+                                };
+                                context_menu::build_tab_menu(menu_pos, idx, checks, s)
                             }
                             _ => context_menu::build_tab_bar_menu(menu_pos, s),
                         };
                         menu.layout(&self.ui_collection);
                         self.context_menu = Some(menu);
                         self.tab_bar_dirty = true;
-                        tw.window.request_redraw();
+                        self.redraw.request(window_id, RedrawReason::INPUT);
                     }
+                } else if let Some(url) = self.url_at_pixel(window_id, pos) {
+                    // Right-click on a link → Open / Copy URL menu
+                    let s = self.scale_factor as f32;
+                    let mut menu =
+                        context_menu::build_link_menu((pos.x as f32, pos.y as f32), &url, s);
+                    menu.layout(&self.ui_collection);
+                    self.context_menu = Some(menu);
+                    self.tab_bar_dirty = true;
+                    self.redraw.request(window_id, RedrawReason::INPUT);
+                } else if self.active_tab_id(window_id).is_some() {
+                    // Right-click in grid area → Copy / Paste / Select All menu
+                    let s = self.scale_factor as f32;
+                    let mut menu = context_menu::build_grid_menu((pos.x as f32, pos.y as f32), s);
+                    menu.layout(&self.ui_collection);
+                    self.context_menu = Some(menu);
+                    self.tab_bar_dirty = true;
+                    self.redraw.request(window_id, RedrawReason::INPUT);
                 } else {
-                    // Right-click in grid area → copy if selection, paste if not
-                    if let Some(tid) = self.active_tab_id(window_id) {
-                        let has_selection =
-                            self.tabs.get(&tid).is_some_and(|t| t.selection.is_some());
-                        if has_selection {
-                            self.copy_selection_to_clipboard(tid);
-                            if let Some(tab) = self.tabs.get_mut(&tid) {
-                                tab.clear_selection();
-                            }
-                            if let Some(tw) = self.windows.get(&window_id) {
-                                tw.window.request_redraw();
-                            }
-                        } else {
-                            self.paste_from_clipboard(window_id);
-                        }
-                    }
+                    // No terminal here (settings window) — nothing to offer
                 }
             }
             return;
         }
 
+        if button == MouseButton::Middle {
+            if state == ElementState::Pressed {
+                self.handle_middle_click(window_id, pos, event_loop);
+            }
+            return;
+        }
+
         if button != MouseButton::Left {
             return;
         }
@@ -173,7 +214,7 @@ impl App {
                     return;
                 }
 
-                let tab_bar_h = self.scale_px(TAB_BAR_HEIGHT);
+                let tab_bar_h = self.chrome_px(TAB_BAR_HEIGHT);
                 if y < tab_bar_h {
                     let tw = match self.windows.get(&window_id) {
                         Some(tw) => tw,
@@ -182,6 +223,7 @@ impl App {
                     let twl = self.tab_width_lock_for(window_id);
                     let layout = TabBarLayout::compute(
                         tw.tabs.len(),
+                        self.pinned_count(window_id),
                         tw.window.inner_size().width as usize,
                         self.scale_factor,
                         twl,
@@ -196,8 +238,7 @@ impl App {
                             // Show dropdown menu overlay below the button.
                             // Reuse `layout` computed above (same window, same state).
                             let s = self.scale_factor as f32;
-                            let tabs_end = self.scale_px(TAB_LEFT_MARGIN)
-                                + layout.tab_count * layout.tab_width;
+                            let tabs_end = self.scale_px(TAB_LEFT_MARGIN) + layout.tabs_width();
                             let menu_x =
                                 (tabs_end + self.scale_px(NEW_TAB_BUTTON_WIDTH)) as f32;
                             let menu_y = self.scale_px(TAB_BAR_HEIGHT) as f32;
@@ -207,9 +248,7 @@ impl App {
                             menu.layout(&self.ui_collection);
                             self.context_menu = Some(menu);
                             self.tab_bar_dirty = true;
-                            if let Some(tw) = self.windows.get(&window_id) {
-                                tw.window.request_redraw();
-                            }
+                            self.redraw.request(window_id, RedrawReason::INPUT);
                         }
                         TabBarHit::CloseTab(idx) => {
                             let tw = match self.windows.get(&window_id) {
@@ -220,7 +259,7 @@ impl App {
                             // buttons stay under cursor during rapid closes.
                             self.tab_width_lock = Some((window_id, layout.tab_width));
                             if let Some(&tab_id) = tw.tabs.get(idx) {
-                                self.close_tab(tab_id, event_loop);
+                                self.request_close_tab(tab_id, event_loop);
                             }
                         }
                         TabBarHit::Minimize => {
@@ -232,24 +271,17 @@ impl App {
                             self.toggle_maximize(window_id);
                         }
                         TabBarHit::CloseWindow => {
-                            self.close_window(window_id, event_loop);
+                            self.request_close_window(window_id, event_loop);
                         }
                         TabBarHit::DragArea => {
-                            // Check for double-click to toggle maximize
-                            let now = Instant::now();
-                            let is_double = self.last_click_time.is_some_and(|t| {
-                                now.duration_since(t).as_millis() < DOUBLE_CLICK_MS
-                            }) && self.last_click_window == Some(window_id);
-
-                            if is_double {
-                                // Double-click: toggle maximize
-                                self.last_click_time = None;
-                                self.last_click_window = None;
-                                self.toggle_maximize(window_id);
+                            let clicks = self.clicks.click(Instant::now(), window_id, None);
+                            if clicks >= 2 {
+                                // Double-click: `behavior.double_click_tab_bar`
+                                self.clicks.reset();
+                                let action = self.config.behavior.double_click_tab_bar;
+                                self.run_mouse_action(action, window_id, None, event_loop);
                             } else {
                                 // Single click: start window drag
-                                self.last_click_time = Some(now);
-                                self.last_click_window = Some(window_id);
                                 self.start_window_drag(window_id);
                             }
                         }
@@ -261,12 +293,12 @@ impl App {
                             if let Some(&tab_id) = tw.tabs.get(idx) {
                                 // Reuse `layout` computed above.
                                 let left_margin = self.scale_px(TAB_LEFT_MARGIN) as f64;
-                                let tab_left = left_margin + idx as f64 * layout.tab_width as f64;
+                                let tab_left = left_margin + layout.tab_offset(idx) as f64;
                                 let offset_in_tab = pos.x - tab_left;
                                 if let Some(tw) = self.windows.get_mut(&window_id) {
                                     tw.active_tab = idx;
                                     self.tab_bar_dirty = true;
-                                    tw.window.request_redraw();
+                                    self.redraw.request(window_id, RedrawReason::INPUT);
                                 }
                                 let mut drag = DragState::new(tab_id, window_id, pos);
                                 drag.mouse_offset_in_tab = offset_in_tab;
@@ -294,6 +326,12 @@ impl App {
                 if self.left_mouse_down {
                     self.left_mouse_down = false;
                     if let Some(tid) = self.active_tab_id(window_id) {
+                        // Block selection guides only show while dragging.
+                        if let Some(tab) = self.tabs.get(&tid).filter(|t| {
+                            t.selection.as_ref().is_some_and(|s| s.mode == SelectionMode::Block)
+                        }) {
+                            tab.set_grid_dirty(true);
+                        }
                         let is_empty = self
                             .tabs
                             .get(&tid)
@@ -304,108 +342,23 @@ impl App {
                                 tab.clear_selection();
                             }
                         } else if self.config.behavior.copy_on_select {
+                            self.set_primary_from_selection(tid);
                             self.copy_selection_to_clipboard(tid);
                         } else {
                             // copy_on_select disabled — keep selection visible
+                            self.set_primary_from_selection(tid);
                         }
                     }
                 }
 
                 if let Some(drag) = self.drag.take() {
-                    // Clear drag visuals and rebuild tab bar (show tab at slot again).
-                    self.drag_visual_x = None;
-                    self.tab_anim_offsets.remove(&drag.source_window);
+                    // Slide the tab into its slot and rebuild the tab bar.
+                    self.settle_dropped_tab(drag.source_window, drag.tab_id);
                     self.tab_bar_dirty = true;
                     self.tear_off_magnetism = 0.0;
-                    if let Some(tw) = self.windows.get(&drag.source_window) {
-                        tw.window.request_redraw();
-                    }
+                    self.redraw.request(drag.source_window, RedrawReason::INPUT);
                 }
             }
         }
     }
-
-    pub(super) fn handle_mouse_wheel(&mut self, window_id: WindowId, delta: MouseScrollDelta) {
-        let lines = match delta {
-            MouseScrollDelta::LineDelta(_, y) => {
-                if y > 0.0 {
-                    SCROLL_LINES as i32
-                } else {
-                    -(SCROLL_LINES as i32)
-                }
-            }
-            MouseScrollDelta::PixelDelta(pos) => {
-                let cell_h = self.font_collection.cell_height as f64;
-                if cell_h > 0.0 {
-                    (pos.y / cell_h).round() as i32
-                } else {
-                    0
-                }
-            }
-        };
-
-        if lines == 0 {
-            return;
-        }
-
-        let Some(tid) = self.active_tab_id(window_id) else {
-            return;
-        };
-
-        // Mouse reporting: scroll events sent to PTY when mouse mode active
-        if !self.modifiers.shift_key() {
-            let mouse_active = self
-                .tabs
-                .get(&tid)
-                .is_some_and(|t| t.mode().intersects(TermMode::ANY_MOUSE));
-            if mouse_active {
-                let pos = self
-                    .cursor_pos
-                    .get(&window_id)
-                    .copied()
-                    .unwrap_or(PhysicalPosition::new(0.0, 0.0));
-                if let Some((col, line)) = self.pixel_to_cell(pos) {
-                    let btn = if lines > 0 { 64u8 } else { 65 };
-                    let count = lines.unsigned_abs() as usize;
-                    for _ in 0..count {
-                        self.send_mouse_report(tid, btn, col, line, true);
-                    }
-                }
-                return;
-            }
-        }
-
-        // Alternate scroll: convert scroll to arrow keys in alt screen
-        if !self.modifiers.shift_key() {
-            let alt_scroll_mode = self.tabs.get(&tid).and_then(|t| {
-                let mode = t.mode();
-                let is_alt_scroll = mode.contains(TermMode::ALT_SCREEN)
-                    && mode.contains(TermMode::ALTERNATE_SCROLL);
-                is_alt_scroll.then(|| mode.contains(TermMode::APP_CURSOR))
-            });
-            if let Some(app_cursor) = alt_scroll_mode {
-                let (up, down) = if app_cursor {
-                    (b"\x1bOA" as &[u8], b"\x1bOB" as &[u8])
-                } else {
-                    (b"\x1b[A" as &[u8], b"\x1b[B" as &[u8])
-                };
-                let seq = if lines > 0 { up } else { down };
-                let count = lines.unsigned_abs() as usize;
-                if let Some(tab) = self.tabs.get_mut(&tid) {
-                    for _ in 0..count {
-                        tab.send_pty(seq);
-                    }
-                }
-                return;
-            }
-        }
-
-        // Normal scrollback
-        if let Some(tab) = self.tabs.get_mut(&tid) {
-            tab.scroll_lines(lines);
-        }
-        if let Some(tw) = self.windows.get(&window_id) {
-            tw.window.request_redraw();
-        }
-    }
 }
@@ -0,0 +1,98 @@
+//! Escape sequence inspector — toggle, filter box keys, and IME.
+//!
+//! Opening the inspector starts recording the active tab's sequences and
+//! gives the filter box keyboard focus. Enter hands the keyboard back to
+//! the terminal while the panel keeps updating; Escape closes the panel.
+
+use winit::event::Ime;
+use winit::keyboard::{Key, NamedKey};
+use winit::window::WindowId;
+
+use super::{App, RedrawReason};
+use crate::tab::Tab;
+use crate::ui::text_input::KeyOutcome;
+
+impl App {
+    /// Open the inspector on the active tab, or close it if it is open.
+    pub(super) fn toggle_inspector(&mut self, window_id: WindowId) {
+        let Some(tab) = self
+            .active_tab_id(window_id)
+            .and_then(|id| self.tabs.get(&id))
+        else {
+            return;
+        };
+        if tab.is_inspecting() {
+            self.close_inspector(window_id);
+            return;
+        }
+        tab.start_inspecting();
+        tab.set_grid_dirty(true);
+        self.inspector_focus = Some(window_id);
+        self.ime_preedit.remove(&window_id);
+        self.redraw.request(window_id, RedrawReason::INPUT);
+    }
+
+    fn close_inspector(&mut self, window_id: WindowId) {
+        if let Some(tab) = self
+            .active_tab_id(window_id)
+            .and_then(|id| self.tabs.get(&id))
+        {
+            tab.stop_inspecting();
+        }
+        if self.inspector_focus == Some(window_id) {
+            self.inspector_focus = None;
+        }
+        self.redraw.request(window_id, RedrawReason::INPUT);
+    }
+
+    pub(super) fn handle_inspector_key(
+        &mut self,
+        window_id: WindowId,
+        event: &winit::event::KeyEvent,
+    ) {
+        // The focus stays with the window; a tab switch leaves it on a tab
+        // that isn't being inspected.
+        let inspecting = self
+            .active_tab_id(window_id)
+            .and_then(|id| self.tabs.get(&id))
+            .is_some_and(Tab::is_inspecting);
+        if !inspecting {
+            self.inspector_focus = None;
+            return;
+        }
+        match event.logical_key {
+            Key::Named(NamedKey::Escape) => self.close_inspector(window_id),
+            Key::Named(NamedKey::Enter) => {
+                self.inspector_focus = None;
+                self.inspector_filter_changed(window_id, KeyOutcome::Moved);
+            }
+            _ => {
+                let outcome = self
+                    .inspector_filter
+                    .handle_key(&event.logical_key, self.modifiers);
+                self.inspector_filter_changed(window_id, outcome);
+            }
+        }
+    }
+
+    /// IME input while the inspector's filter box has focus.
+    pub(super) fn handle_inspector_ime(&mut self, window_id: WindowId, ime: Ime) {
+        let outcome = self.inspector_filter.handle_ime(ime);
+        self.inspector_filter_changed(window_id, outcome);
+    }
+
+    /// Redraw the panel after the filter box changed. The panel is part of
+    /// the main frame, so the active tab is marked dirty.
+    fn inspector_filter_changed(&mut self, window_id: WindowId, outcome: KeyOutcome) {
+        if outcome == KeyOutcome::Ignored {
+            return;
+        }
+        if let Some(tab) = self
+            .active_tab_id(window_id)
+            .and_then(|id| self.tabs.get(&id))
+        {
+            tab.set_grid_dirty(true);
+        }
+        self.redraw.request(window_id, RedrawReason::INPUT);
+    }
+}
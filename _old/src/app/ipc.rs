@@ -0,0 +1,23 @@
+//! Answering `oriterm cli` requests from the active tab.
+
+use crate::ipc::IpcCall;
+
+use super::App;
+
+impl App {
+    /// Answer `call` from the focused window's active tab, or any window's
+    /// when none has focus.
+    pub(super) fn answer_ipc(&self, call: &IpcCall) {
+        let tab = self
+            .focused_window
+            .or_else(|| self.windows.keys().next().copied())
+            .and_then(|wid| self.active_tab_id(wid))
+            .and_then(|tid| self.tabs.get(&tid));
+        let answer = match tab {
+            Some(tab) => call.request.answer(&tab.grid(), tab.pty_stats()),
+            None => Err("no active tab".to_owned()),
+        };
+        // The client may have given up waiting.
+        let _ = call.reply.send(answer);
+    }
+}
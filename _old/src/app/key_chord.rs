@@ -0,0 +1,132 @@
+//! Keybinding lookup, including multi-key chord sequences.
+//!
+//! A key that starts a bound sequence (the Ctrl+A of Ctrl+A then C) is
+//! held as a [`PendingChord`] until the next key completes the binding,
+//! a key outside the sequence abandons it, or the chord times out. While a
+//! chord is pending every key is swallowed and the window shows the keys
+//! pressed so far.
+//!
+//! Bindings with a `when` condition only match in the [`KeyContext`] of the
+//! window the key was pressed in.
+
+use std::time::Instant;
+
+use winit::event::{ElementState, KeyEvent};
+use winit::event_loop::ActiveEventLoop;
+use winit::window::WindowId;
+
+use crate::keybindings::{self, KeyChord, KeyContext, KeyMatch};
+use crate::term_mode::TermMode;
+
+use super::compose_input::is_modifier;
+use super::input_keyboard::build_modifiers;
+use super::redraw::RedrawReason;
+use super::App;
+
+/// A chord sequence waiting for its next key.
+pub(crate) struct PendingChord {
+    pub(super) window: WindowId,
+    pub(super) chords: Vec<KeyChord>,
+    pub(super) deadline: Instant,
+}
+
+impl PendingChord {
+    /// The indicator text, e.g. `Ctrl+A …`.
+    pub(super) fn label(&self) -> String {
+        let mut label = String::new();
+        for chord in &self.chords {
+            label.push_str(&chord.to_string());
+            label.push(' ');
+        }
+        label.push('…');
+        label
+    }
+}
+
+impl App {
+    /// Run a key event through the keybindings. Returns true when it was
+    /// consumed and must not reach the PTY.
+    pub(super) fn binding_key_event(
+        &mut self,
+        window_id: WindowId,
+        event: &KeyEvent,
+        event_loop: &ActiveEventLoop,
+    ) -> bool {
+        let in_chord = self.pending_chord.is_some();
+        let ctx = self.key_context(window_id);
+        let Some(key) = keybindings::key_to_binding_key(&event.logical_key) else {
+            return in_chord;
+        };
+        let chord = KeyChord {
+            key,
+            mods: build_modifiers(self.modifiers),
+        };
+        if event.state != ElementState::Pressed {
+            // Releases of bound keys, and of every key mid-chord.
+            return in_chord
+                || keybindings::match_key(&self.bindings, &[], &chord, ctx) != KeyMatch::None;
+        }
+        if in_chord && is_modifier(&event.logical_key) {
+            // Ctrl or Shift held for the next chord.
+            return true;
+        }
+
+        let mut pending = Vec::new();
+        if let Some(p) = self.pending_chord.take() {
+            // Clear the indicator; a chord from another window is dropped.
+            self.redraw.request(p.window, RedrawReason::INPUT);
+            if p.window == window_id {
+                pending = p.chords;
+            }
+        }
+        match keybindings::match_key(&self.bindings, &pending, &chord, ctx) {
+            KeyMatch::Pending => {
+                pending.push(chord);
+                self.pending_chord = Some(PendingChord {
+                    window: window_id,
+                    chords: pending,
+                    deadline: Instant::now() + self.config.behavior.chord_timeout(),
+                });
+                self.redraw.request(window_id, RedrawReason::INPUT);
+                true
+            }
+            KeyMatch::Action(action) => {
+                let action = action.clone();
+                // SmartCopy with no selection falls through to the PTY,
+                // unless it ended a chord.
+                self.execute_action(&action, window_id, event_loop) || !pending.is_empty()
+            }
+            // A key outside the sequence abandons it and goes nowhere.
+            KeyMatch::None => !pending.is_empty(),
+        }
+    }
+
+    /// The state of `window_id` that binding conditions test.
+    fn key_context(&self, window_id: WindowId) -> KeyContext {
+        let mut ctx = KeyContext::empty();
+        if let Some(tab) = self
+            .active_tab_id(window_id)
+            .and_then(|id| self.tabs.get(&id))
+        {
+            ctx.set(
+                KeyContext::ALT_SCREEN,
+                tab.mode().contains(TermMode::ALT_SCREEN),
+            );
+            ctx.set(KeyContext::SELECTION, tab.selection.is_some());
+        }
+        ctx.set(KeyContext::SEARCH, self.search_active == Some(window_id));
+        ctx
+    }
+
+    /// Drop a chord whose timeout has passed. Returns when the pending
+    /// chord, if any, times out.
+    pub(super) fn expire_key_chord(&mut self) -> Option<Instant> {
+        let pending = self.pending_chord.as_ref()?;
+        if Instant::now() < pending.deadline {
+            return Some(pending.deadline);
+        }
+        self.redraw.request(pending.window, RedrawReason::INPUT);
+        self.pending_chord = None;
+        None
+    }
+}
@@ -1,22 +1,51 @@
 //! Application state, startup, and module dispatch.
 
+mod animation;
+mod broadcast;
+mod clipboard_access;
+mod close_confirm;
+mod compose_input;
 mod config_reload;
+mod copy_history;
 mod cursor_hover;
 mod event_loop;
+mod host_rules;
 mod hover_url;
+mod ime;
 mod input_keyboard;
 mod input_mouse;
+mod inspector_ui;
+mod ipc;
+mod key_chord;
 mod mouse_coord;
+mod mouse_gesture;
 mod mouse_report;
 mod mouse_selection;
+mod mouse_wheel;
+mod notifications;
+mod paste;
+mod redraw;
 mod render_coord;
+mod scrollbar_drag;
 mod search_ui;
+mod session;
 mod settings_ui;
+mod startup;
+mod stats_log;
+mod system_theme;
+mod tab_bar_cache;
 mod tab_drag;
 mod tab_management;
+mod tab_pin;
+mod tab_rename;
+mod tab_reorder;
+mod tab_switcher;
+#[cfg(test)]
+mod tests;
+mod window_class;
 mod window_management;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::time::Instant;
 
@@ -25,39 +54,61 @@ use winit::event_loop::{EventLoop, EventLoopProxy};
 use winit::keyboard::ModifiersState;
 use winit::window::{Window, WindowId};
 
-use crate::clipboard;
+use crate::clipboard_history::ClipboardHistory;
+use crate::compose::Composer;
 use crate::config::{self, Config};
 use crate::config::monitor::ConfigMonitor;
+use crate::desktop_notify::Notifier;
 use crate::context_menu::MenuOverlay;
 use crate::drag::DragState;
 use crate::font::FontCollection;
+use crate::hyperlink::LinkId;
+use crate::ipc::Endpoint;
 use crate::gpu::{GpuRenderer, GpuState};
-use crate::key_encoding::Modifiers;
 use crate::keybindings::{self, KeyBinding};
 use crate::log;
 use crate::palette;
-use crate::selection;
+use crate::render::BlinkPhase;
+use crate::search::SearchHistory;
+use crate::selection::ClickTracker;
 use crate::tab::{Tab, TabId, TermEvent};
-use crate::tab_bar::TabBarHit;
-use crate::term_mode::TermMode;
-use crate::url_detect::{UrlDetectCache, UrlSegment};
+use crate::tab_bar::{ChromeTheme, TabBarHit};
+use crate::ui::settings_layout::{SettingsLayout, SettingsPage};
+use crate::ui::tab_switcher::TabSwitcher;
+use crate::ui::text_input::{Preedit, TextInput};
+use crate::url_detect::{RuleSet, UrlDetectCache, UrlSegment};
 use crate::window::TermWindow;
 
+use config_reload::prepare_shell_integration;
+use mouse_wheel::KineticScroll;
+use notifications::UnseenTotal;
+use redraw::{RedrawReason, RedrawScheduler};
+use tab_bar_cache::TabBarCache;
+
 /// Resize border thickness in pixels.
 pub(super) const RESIZE_BORDER: f64 = 8.0;
 
-/// Double-click detection threshold in milliseconds.
-pub(super) const DOUBLE_CLICK_MS: u128 = 400;
-
 /// Scroll lines per mouse wheel tick.
 pub(super) const SCROLL_LINES: usize = 3;
 
 /// UI font scale relative to grid font (tab bar, search bar, menus).
 pub(super) const UI_FONT_SCALE: f32 = 0.75;
 
+/// How the app starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchMode {
+    /// Load and watch the user's config file.
+    Normal,
+    /// `--safe`: built-in defaults, no GPU transparency effects, no shell
+    /// integration, and no config watching.
+    Safe,
+}
+
 #[allow(clippy::struct_excessive_bools, reason = "App state needs multiple flag fields")]
 pub struct App {
     pub(super) config: Config,
+    /// Window class for new windows (see [`crate::config::WindowConfig::app_id`]).
+    pub(super) window_class: String,
     pub(super) windows: HashMap<WindowId, TermWindow>,
     pub(super) tabs: HashMap<TabId, Tab>,
     pub(super) ui_collection: FontCollection,
@@ -67,55 +118,97 @@ pub struct App {
     pub(super) drag: Option<DragState>,
     pub(super) next_tab_id: u64,
     pub(super) proxy: EventLoopProxy<TermEvent>,
+    /// Desktop toast worker.
+    pub(super) notifier: Notifier,
     pub(super) cursor_pos: HashMap<WindowId, PhysicalPosition<f64>>,
     pub(super) hover_hit: HashMap<WindowId, TabBarHit>,
     pub(super) modifiers: ModifiersState,
     pub(super) first_window_created: bool,
-    pub(super) last_click_time: Option<Instant>,
-    pub(super) last_click_window: Option<WindowId>,
+    /// The oriterm window with keyboard focus, if any.
+    pub(super) focused_window: Option<WindowId>,
+    /// Unseen notifications and bells across all tabs.
+    pub(super) unseen: UnseenTotal,
+    /// Unseen notification and bell count last published to the OS.
+    pub(super) published_badge: u32,
+    /// Multi-click detection for the grid and the tab bar drag area.
+    pub(super) clicks: ClickTracker,
     // Selection state
     pub(super) left_mouse_down: bool,
-    pub(super) last_grid_click_pos: Option<(usize, usize)>,
-    pub(super) click_count: u8,
     // Mouse reporting dedup
     pub(super) last_mouse_cell: Option<(usize, usize)>,
     // Search
     pub(super) search_active: Option<WindowId>,
+    pub(super) search_history: SearchHistory,
+    pub(super) tab_rename: Option<tab_rename::TabRename>,
+    /// The open tab switcher and the window it's drawn over.
+    pub(super) tab_switcher: Option<(WindowId, TabSwitcher)>,
+    // Escape sequence inspector: the window whose filter box has focus
+    pub(super) inspector_focus: Option<WindowId>,
+    pub(super) inspector_filter: TextInput,
     // Hyperlink hover
-    pub(super) hover_hyperlink: Option<(WindowId, String)>,
+    pub(super) hover_hyperlink: Option<(WindowId, LinkId)>,
     // Implicit URL detection
     pub(super) url_cache: UrlDetectCache,
     pub(super) hover_url_range: Option<Vec<UrlSegment>>,
     // Context menus & settings
     pub(super) context_menu: Option<MenuOverlay>,
+    /// OSC 52 write waiting on the clipboard prompt.
+    pub(super) pending_clipboard: Option<(TabId, String)>,
+    /// Recent copies offered by `PasteFromHistory`.
+    pub(super) clipboard_history: ClipboardHistory,
     pub(super) settings_window: Option<WindowId>,
     pub(super) active_scheme: &'static str,
-    pub(super) _config_monitor: Option<ConfigMonitor>,
+    /// Settings window filter box.
+    pub(super) settings_filter: TextInput,
+    /// Settings page and the options matching the filter.
+    pub(super) settings_layout: SettingsLayout,
+    /// The `colors.minimum_contrast` the settings toggle last replaced.
+    pub(super) contrast_before_toggle: Option<f32>,
+    pub(super) config_monitor: Option<ConfigMonitor>,
+    /// The published IPC endpoint, removed on exit.
+    pub(super) ipc_endpoint: Option<Endpoint>,
+    /// Whether this is a `--safe` launch (config is never written).
+    pub(super) launch_mode: LaunchMode,
     pub(super) bindings: Vec<KeyBinding>,
+    /// Keybinding chord sequence waiting for its next key.
+    pub(super) pending_chord: Option<key_chord::PendingChord>,
+    /// Scrollbar thumb being dragged: window, and where in the thumb it
+    /// was grabbed (pixels below its top).
+    pub(super) scrollbar_drag: Option<(WindowId, f32)>,
     pub(super) scale_factor: f64,
-    /// Per-tab X offsets for dodge animation, keyed by window.
-    pub(super) tab_anim_offsets: HashMap<WindowId, Vec<f32>>,
-    /// Last animation tick time (for time-based decay).
-    pub(super) last_anim_time: Instant,
+    /// Running tab dodge/settle animations and the bell pulse.
+    pub(super) animations: animation::Animations,
+    /// Per-window touchpad scroll remainder and momentum.
+    pub(super) kinetic: HashMap<WindowId, KineticScroll>,
+    /// Per-window IME composition at the terminal cursor.
+    pub(super) ime_preedit: HashMap<WindowId, Preedit>,
+    /// OS light/dark preference and accent color for the chrome.
+    pub(super) chrome_theme: ChromeTheme,
+    /// Dead-key and compose-key sequence in progress.
+    pub(super) composer: Composer,
     /// Pixel X position of the dragged tab within its window, for rendering.
     pub(super) drag_visual_x: Option<(WindowId, f32)>,
     /// When the cursor blink timer was last reset (keystroke, PTY output, focus).
     pub(super) cursor_blink_reset: Instant,
     /// True when the tab bar needs to be rebuilt (hover, tab add/remove, etc.).
     pub(super) tab_bar_dirty: bool,
+    /// Tab bar and grid padding hidden (`Action::ToggleFocusMode`).
+    pub(super) focus_mode: bool,
     /// Chrome-style tab width lock: after closing a tab via close button,
     /// tabs keep their current width until the mouse leaves the tab bar.
     pub(super) tab_width_lock: Option<(WindowId, usize)>,
     /// Previous cursor visibility state for dirty tracking.
     pub(super) prev_cursor_visible: bool,
-    /// Deferred redraw requests: coalesces multiple PTY events into one
-    /// `request_redraw()` per window, drained in `about_to_wait`.
-    pub(super) pending_redraw: HashSet<WindowId>,
+    /// Start of the SGR 5/6 text blink clock.
+    pub(super) text_blink_start: Instant,
+    /// Text blink phase at the last render, to detect toggles.
+    pub(super) prev_blink_phase: BlinkPhase,
+    /// Per-window redraw requests, merged and paced in `about_to_wait`.
+    pub(super) redraw: RedrawScheduler,
     /// Path to written shell integration scripts (None when disabled).
     pub(super) shell_integration_dir: Option<PathBuf>,
     /// Cached tab bar data — rebuilt only when `tab_bar_dirty`.
-    pub(super) cached_tab_info: Vec<(TabId, String)>,
-    pub(super) cached_bell_badges: Vec<bool>,
+    pub(super) tab_bar_cache: TabBarCache,
     #[cfg(target_os = "windows")]
     /// Torn-off tab pending OS drag completion for post-drag merge check.
     /// Fields: `(window_id, tab_id, mouse_offset_in_tab)`.
@@ -129,8 +222,6 @@ pub struct App {
     /// Y tolerance is added to the tear-off check so the cursor needs to
     /// travel further before tearing off again. Cleared when the drag ends.
     pub(super) tear_off_magnetism: f64,
-    /// Last time any window was rendered (for frame-rate limiting).
-    pub(super) last_render_time: Instant,
     // Performance instrumentation (periodic stats logging).
     pub(super) render_count: u32,
     pub(super) render_total_ms: f64,
@@ -142,7 +233,8 @@ pub struct App {
 }
 
 impl App {
-    pub fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// Start oriterm. `class` (`--class`) overrides `window.app_id`.
+    pub fn run(mode: LaunchMode, class: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
         std::panic::set_hook(Box::new(|info| {
             let _ = std::fs::write("oriterm_panic.log", format!("{info}"));
         }));
@@ -151,64 +243,16 @@ impl App {
         let startup = Instant::now();
         log("starting");
 
-        let t0 = Instant::now();
-        let config = Config::load();
-        log(&format!(
-            "config: font_size={}, scheme={}, shell={:?}, scrollback={}, cols={}, rows={}, \
-             opacity={}, tab_bar_opacity={}, blur={}, cursor={}, cursor_blink={}, \
-             cursor_blink_interval_ms={}, copy_on_select={}, bold_is_bright={} \
-             ({:.1}ms)",
-            config.font.size,
-            config.colors.scheme,
-            config.terminal.shell,
-            config.terminal.scrollback,
-            config.window.columns,
-            config.window.rows,
-            config.window.effective_opacity(),
-            config.window.effective_tab_bar_opacity(),
-            config.window.blur,
-            config.terminal.cursor_style,
-            config.terminal.cursor_blink,
-            config.terminal.cursor_blink_interval_ms,
-            config.behavior.copy_on_select,
-            config.behavior.bold_is_bright,
-            t0.elapsed().as_secs_f64() * 1000.0,
-        ));
+        let config = startup::load_config(mode);
+        let (font_collection, ui_collection) = startup::load_fonts(&config);
 
-        let t0 = Instant::now();
-        let font_collection = FontCollection::load(
-            config.font.size,
-            config.font.family.as_deref(),
-            &FontCollection::parse_features(&config.font.features),
-            &config.font.fallback,
-            config.font.effective_weight(),
-        );
-        let ui_size = font_collection.size * UI_FONT_SCALE;
-        let ui_family = config.font.tab_bar_font_family.as_deref()
-            .or(config.font.family.as_deref());
-        let ui_collection = FontCollection::load(
-            ui_size,
-            ui_family,
-            &[],
-            &[],
-            config.font.effective_tab_bar_weight(),
-        );
-        log(&format!(
-            "font loaded: cell={}x{}, baseline={}, size={}, weight={}, wght_axis={} \
-             (ui: size={}, weight={}, wght_axis={}) ({:.1}ms)",
-            font_collection.cell_width,
-            font_collection.cell_height,
-            font_collection.baseline,
-            font_collection.size,
-            config.font.effective_weight(),
-            font_collection.has_wght_axis(),
-            ui_collection.size,
-            config.font.effective_tab_bar_weight(),
-            ui_collection.has_wght_axis(),
-            t0.elapsed().as_secs_f64() * 1000.0,
-        ));
+        let bindings = keybindings::merge_bindings(&config.keybind, config.keybind_profile);
 
-        let bindings = keybindings::merge_bindings(&config.keybind);
+        // The window class can't change once windows exist, so config
+        // reloads keep the one chosen at startup.
+        let window_class = class.unwrap_or_else(|| config.window.effective_app_id().to_owned());
+        #[cfg(target_os = "windows")]
+        crate::platform_windows::set_app_user_model_id(&window_class);
 
         let active_scheme =
             palette::find_scheme(&config.colors.scheme).map_or("Catppuccin Mocha", |s| s.name);
@@ -223,33 +267,35 @@ impl App {
             t0.elapsed().as_secs_f64() * 1000.0
         ));
 
-        let config_monitor = ConfigMonitor::new(proxy.clone());
-
-        let shell_integration_dir = if config.behavior.shell_integration {
-            match crate::shell_integration::ensure_scripts_on_disk(&config::config_dir()) {
-                Ok(dir) => {
-                    log(&format!(
-                        "shell_integration: scripts written to {}",
-                        dir.display()
-                    ));
-                    Some(dir)
-                }
-                Err(e) => {
-                    log(&format!("shell_integration: failed to write scripts: {e}"));
-                    None
-                }
-            }
-        } else {
-            None
+        // Safe mode never reads the config file, so don't reload it either.
+        let config_monitor = match mode {
+            LaunchMode::Normal => ConfigMonitor::new(proxy.clone()),
+            LaunchMode::Safe => None,
         };
 
+        let ipc_endpoint = crate::ipc::serve(proxy.clone());
+        let shell_integration_dir = prepare_shell_integration(config.behavior.shell_integration);
+
         log(&format!(
             "pre-event-loop total: {:.1}ms",
             startup.elapsed().as_secs_f64() * 1000.0
         ));
 
+        let clicks = ClickTracker::new(
+            config.behavior.double_click_interval(),
+            config.behavior.click_cycle,
+        );
+        let url_cache = UrlDetectCache::new(RuleSet::new(&config.rules));
+        let clipboard_history = ClipboardHistory::new(config.behavior.clipboard_history);
+        let search_history = if config.behavior.save_search_history {
+            SearchHistory::load(&config::search_history_path())
+        } else {
+            SearchHistory::default()
+        };
+        let notifier = Notifier::new(&window_class, proxy.clone());
         let mut app = Self {
             config,
+            window_class,
             windows: HashMap::new(),
             tabs: HashMap::new(),
             ui_collection,
@@ -258,44 +304,63 @@ impl App {
             renderer: None,
             drag: None,
             next_tab_id: 1,
+            notifier,
             proxy,
             cursor_pos: HashMap::new(),
             hover_hit: HashMap::new(),
             modifiers: ModifiersState::empty(),
             first_window_created: false,
-            last_click_time: None,
-            last_click_window: None,
+            focused_window: None,
+            unseen: UnseenTotal::default(),
+            published_badge: 0,
+            clicks,
             left_mouse_down: false,
-            last_grid_click_pos: None,
-            click_count: 0,
             last_mouse_cell: None,
             search_active: None,
+            search_history,
+            tab_rename: None,
+            tab_switcher: None,
+            inspector_focus: None,
+            inspector_filter: TextInput::default(),
             hover_hyperlink: None,
-            url_cache: UrlDetectCache::default(),
+            url_cache,
             hover_url_range: None,
             context_menu: None,
+            pending_clipboard: None,
+            clipboard_history,
             settings_window: None,
             active_scheme,
-            _config_monitor: config_monitor,
+            settings_layout: SettingsLayout::compute(SettingsPage::default(), ""),
+            settings_filter: TextInput::default(),
+            contrast_before_toggle: None,
+            config_monitor,
+            ipc_endpoint,
+            launch_mode: mode,
             bindings,
+            pending_chord: None,
+            scrollbar_drag: None,
             scale_factor: 1.0,
-            tab_anim_offsets: HashMap::new(),
-            last_anim_time: Instant::now(),
+            animations: animation::Animations::new(Instant::now()),
+            kinetic: HashMap::new(),
+            ime_preedit: HashMap::new(),
+            composer: Composer::default(),
+            chrome_theme: ChromeTheme::default(),
             drag_visual_x: None,
             cursor_blink_reset: Instant::now(),
             tab_bar_dirty: true,
+            focus_mode: false,
             tab_width_lock: None,
             prev_cursor_visible: true,
-            pending_redraw: HashSet::new(),
+            text_blink_start: Instant::now(),
+            prev_blink_phase: BlinkPhase::VISIBLE,
+            redraw: RedrawScheduler::default(),
             shell_integration_dir,
-            cached_tab_info: Vec::new(),
-            cached_bell_badges: Vec::new(),
+            tab_bar_cache: TabBarCache::default(),
             #[cfg(target_os = "windows")]
             torn_off_pending: None,
             #[cfg(target_os = "windows")]
             merge_drag_suppress_release: false,
             tear_off_magnetism: 0.0,
-            last_render_time: Instant::now(),
             render_count: 0,
             render_total_ms: 0.0,
             pty_event_count: 0,
@@ -306,6 +371,7 @@ impl App {
         };
 
         event_loop.run_app(&mut app)?;
+        log("event loop stopped, exiting");
         Ok(())
     }
 
@@ -334,27 +400,11 @@ impl App {
             .and_then(TermWindow::active_tab_id)
     }
 
-    /// Copy the current selection text to clipboard. Returns true if text was copied.
-    pub(super) fn copy_selection_to_clipboard(&self, tab_id: TabId) -> bool {
-        if let Some(tab) = self.tabs.get(&tab_id) {
-            if let Some(ref sel) = tab.selection {
-                let text = selection::extract_text(&tab.grid(), sel);
-                if !text.is_empty() {
-                    clipboard::set_text(&text);
-                    return true;
-                }
-            }
-        }
-        false
-    }
-
     /// Dismiss the context menu overlay and mark the tab bar dirty.
     pub(super) fn dismiss_context_menu(&mut self, window_id: WindowId) {
         self.context_menu = None;
         self.tab_bar_dirty = true;
-        if let Some(tw) = self.windows.get(&window_id) {
-            tw.window.request_redraw();
-        }
+        self.redraw.request(window_id, RedrawReason::INPUT);
     }
 
     /// Toggle the maximized state of a window.
@@ -363,7 +413,7 @@ impl App {
             let new_max = !tw.is_maximized;
             tw.is_maximized = new_max;
             tw.window.set_maximized(new_max);
-            tw.window.request_redraw();
+            self.redraw.request(window_id, RedrawReason::RESIZE);
         }
     }
 
@@ -373,23 +423,6 @@ impl App {
             renderer.rebuild_atlas(gpu);
         }
     }
-
-    pub(super) fn paste_from_clipboard(&mut self, window_id: WindowId) {
-        let Some(tab_id) = self.active_tab_id(window_id) else {
-            return;
-        };
-        if let Some(text) = clipboard::get_text() {
-            if let Some(tab) = self.tabs.get_mut(&tab_id) {
-                if tab.mode().contains(TermMode::BRACKETED_PASTE) {
-                    tab.send_pty(b"\x1b[200~");
-                    tab.send_pty(text.as_bytes());
-                    tab.send_pty(b"\x1b[201~");
-                } else {
-                    tab.send_pty(text.as_bytes());
-                }
-            }
-        }
-    }
 }
 
 /// Apply compositor blur/vibrancy when opacity < 1.0.
@@ -440,20 +473,3 @@ pub(super) fn apply_window_effects(window: &Window, wc: &config::WindowConfig) {
         }
     }
 }
-
-pub(super) fn build_modifiers(m: ModifiersState) -> Modifiers {
-    let mut mods = Modifiers::empty();
-    if m.shift_key() {
-        mods |= Modifiers::SHIFT;
-    }
-    if m.alt_key() {
-        mods |= Modifiers::ALT;
-    }
-    if m.control_key() {
-        mods |= Modifiers::CONTROL;
-    }
-    if m.super_key() {
-        mods |= Modifiers::SUPER;
-    }
-    mods
-}
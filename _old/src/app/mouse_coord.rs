@@ -1,6 +1,7 @@
 //! Pixel-to-cell coordinate conversion.
 
 use winit::dpi::PhysicalPosition;
+use winit::window::WindowId;
 
 use crate::grid::{GRID_PADDING_LEFT, GRID_PADDING_TOP};
 use crate::selection::Side;
@@ -9,29 +10,50 @@ use crate::tab_bar::TAB_BAR_HEIGHT;
 use super::App;
 
 impl App {
+    /// Scaled size of window chrome `v` (tab bar or grid padding). Zero in
+    /// focus mode, which hides both.
+    pub(super) fn chrome_px(&self, v: usize) -> usize {
+        if self.focus_mode { 0 } else { self.scale_px(v) }
+    }
+
     /// Top pixel of the grid area (below tab bar + padding).
     pub(super) fn grid_top(&self) -> usize {
-        self.scale_px(TAB_BAR_HEIGHT) + self.scale_px(GRID_PADDING_TOP)
+        self.chrome_px(TAB_BAR_HEIGHT) + self.chrome_px(GRID_PADDING_TOP)
     }
 
-    /// Convert pixel coordinates to grid cell (col, `viewport_line`).
+    /// Convert pixel coordinates to grid cell (col, `viewport_line`) in
+    /// `window_id`, following the rows as drawn mid touchpad scroll.
     /// Returns None if outside the grid area.
-    pub(super) fn pixel_to_cell(&self, pos: PhysicalPosition<f64>) -> Option<(usize, usize)> {
-        let x = pos.x as usize;
-        let y = pos.y as usize;
-        let grid_top = self.grid_top();
-        let padding_left = self.scale_px(GRID_PADDING_LEFT);
-        if y < grid_top || x < padding_left {
-            return None;
-        }
-        let cw = self.font_collection.cell_width;
-        let ch = self.font_collection.cell_height;
-        if cw == 0 || ch == 0 {
+    pub(super) fn pixel_to_cell(
+        &self,
+        window_id: WindowId,
+        pos: PhysicalPosition<f64>,
+    ) -> Option<(usize, usize)> {
+        let origin = (self.chrome_px(GRID_PADDING_LEFT), self.grid_top());
+        let offset = self.active_scroll_offset(window_id);
+        cell_at(pos, origin, self.cell_px(), offset)
+    }
+
+    /// Viewport line beside the mouse while it's in the grid's left padding
+    /// gutter. `None` elsewhere, and in focus mode, which has no gutter.
+    pub(super) fn gutter_line(
+        &self,
+        window_id: WindowId,
+        pos: PhysicalPosition<f64>,
+    ) -> Option<usize> {
+        if pos.x >= self.chrome_px(GRID_PADDING_LEFT) as f64 {
             return None;
         }
-        let col = (x - padding_left) / cw;
-        let line = (y - grid_top) / ch;
-        Some((col, line))
+        let offset = self.active_scroll_offset(window_id);
+        cell_at(pos, (0, self.grid_top()), self.cell_px(), offset).map(|(_, line)| line)
+    }
+
+    /// Cell width and height in physical pixels.
+    fn cell_px(&self) -> (usize, usize) {
+        (
+            self.font_collection.cell_width,
+            self.font_collection.cell_height,
+        )
     }
 
     /// Determine which side of the cell the cursor is on.
@@ -41,7 +63,7 @@ impl App {
         if cw == 0 {
             return Side::Left;
         }
-        let padding_left = self.scale_px(GRID_PADDING_LEFT);
+        let padding_left = self.chrome_px(GRID_PADDING_LEFT);
         let cell_x = (x.saturating_sub(padding_left)) % cw;
         if cell_x < cw / 2 {
             Side::Left
@@ -50,3 +72,24 @@ impl App {
         }
     }
 }
+
+/// The (col, line) at `pos` in a grid whose top-left pixel is `origin`,
+/// with rows drawn `offset` pixels down (up when negative). `None` left of
+/// or above the grid, and in the gap a downward offset opens above the
+/// first row.
+pub(super) fn cell_at(
+    pos: PhysicalPosition<f64>,
+    origin: (usize, usize),
+    (cw, ch): (usize, usize),
+    offset: f64,
+) -> Option<(usize, usize)> {
+    let x = pos.x - origin.0 as f64;
+    let y = pos.y - origin.1 as f64;
+    if x < 0.0 || y < 0.0 || y < offset || cw == 0 || ch == 0 {
+        return None;
+    }
+    Some((
+        (x / cw as f64) as usize,
+        ((y - offset) / ch as f64) as usize,
+    ))
+}
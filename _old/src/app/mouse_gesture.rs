@@ -0,0 +1,68 @@
+//! Configurable mouse gestures: middle-click on a tab or in the terminal,
+//! and double-click on the empty tab bar (`behavior.middle_click_tab`,
+//! `middle_click_grid`, `double_click_tab_bar`).
+
+use winit::dpi::PhysicalPosition;
+use winit::event_loop::ActiveEventLoop;
+use winit::window::WindowId;
+
+use crate::config::MouseAction;
+use crate::tab_bar::{TAB_BAR_HEIGHT, TabBarHit};
+
+use super::App;
+
+impl App {
+    /// Run the middle-click gesture for wherever the mouse is: a tab, or
+    /// the terminal below the tab bar.
+    pub(super) fn handle_middle_click(
+        &mut self,
+        window_id: WindowId,
+        pos: PhysicalPosition<f64>,
+        event_loop: &ActiveEventLoop,
+    ) {
+        if self.is_settings_window(window_id) {
+            return;
+        }
+        let behavior = &self.config.behavior;
+        let (action, tab) = if (pos.y as usize) >= self.chrome_px(TAB_BAR_HEIGHT) {
+            (behavior.middle_click_grid, None)
+        } else {
+            match self.hover_hit.get(&window_id) {
+                Some(TabBarHit::Tab(idx) | TabBarHit::CloseTab(idx)) => {
+                    (behavior.middle_click_tab, Some(*idx))
+                }
+                _ => return,
+            }
+        };
+        self.run_mouse_action(action, window_id, tab, event_loop);
+    }
+
+    /// Perform a gesture's action in `window_id`. `tab` is the index of the
+    /// clicked tab, if the gesture was on one; tab actions fall back to the
+    /// active tab.
+    pub(super) fn run_mouse_action(
+        &mut self,
+        action: MouseAction,
+        window_id: WindowId,
+        tab: Option<usize>,
+        event_loop: &ActiveEventLoop,
+    ) {
+        match action {
+            MouseAction::None => {}
+            MouseAction::CloseTab => {
+                let tab_id = self.windows.get(&window_id).and_then(|tw| {
+                    tab.map_or_else(|| tw.active_tab_id(), |idx| tw.tabs.get(idx).copied())
+                });
+                if let Some(tab_id) = tab_id {
+                    self.request_close_tab(tab_id, event_loop);
+                }
+            }
+            MouseAction::NewTab => {
+                self.new_tab_in_window(window_id);
+            }
+            MouseAction::ToggleMaximize => self.toggle_maximize(window_id),
+            MouseAction::Paste => self.paste_from_clipboard(window_id),
+            MouseAction::PastePrimary => self.paste_primary(window_id),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-//! Grid selection — click count detection, single/double/triple click, shift-extend.
+//! Grid selection — single/double/triple/quadruple click, shift-extend.
 
 use std::time::Instant;
 
@@ -8,68 +8,26 @@ use winit::window::WindowId;
 use crate::grid::StableRowIndex;
 use crate::selection::{self, Selection, SelectionMode, SelectionPoint, Side};
 
-use super::{App, DOUBLE_CLICK_MS};
+use super::{App, RedrawReason};
 
 impl App {
-    /// Detect click count (1=char, 2=word, 3=line), cycling on rapid clicks.
-    pub(super) fn detect_click_count(
-        &mut self,
-        window_id: WindowId,
-        col: usize,
-        line: usize,
-    ) -> u8 {
-        let now = Instant::now();
-        let same_pos = self.last_grid_click_pos == Some((col, line));
-        let same_window = self.last_click_window == Some(window_id);
-        let within_time = self
-            .last_click_time
-            .is_some_and(|t| now.duration_since(t).as_millis() < DOUBLE_CLICK_MS);
-
-        let count = if same_pos && same_window && within_time {
-            match self.click_count {
-                1 => 2,
-                2 => 3,
-                _ => 1,
-            }
-        } else {
-            1
-        };
-
-        self.last_click_time = Some(now);
-        self.last_click_window = Some(window_id);
-        self.last_grid_click_pos = Some((col, line));
-        self.click_count = count;
-        count
-    }
-
-    /// Handle Ctrl+click to open a hyperlink URL (OSC 8 or implicit).
+    /// Handle Ctrl+click on a hyperlink (OSC 8), a detection rule match, or
+    /// an implicit URL.
     ///
-    /// Returns true if a URL was opened and the click should be consumed.
+    /// Returns true if an action ran and the click should be consumed.
     fn handle_ctrl_click_url(&mut self, tab_id: crate::tab::TabId, abs_row: usize, col: usize) -> bool {
-        // Single lock: check OSC 8 hyperlink and implicit URL in one scope.
-        let url: Option<String> = self.tabs.get(&tab_id).and_then(|tab| {
-            let grid = tab.grid();
-            let row = grid.absolute_row(abs_row)?;
-            if col >= row.len() {
-                return None;
+        match self.link_at(tab_id, abs_row, col) {
+            Some(action) => {
+                self.run_match_action(tab_id, action);
+                true
             }
-            // OSC 8 hyperlink takes priority.
-            if let Some(h) = row[col].hyperlink() {
-                return Some(h.uri.clone());
-            }
-            // Fall through to implicit URL detection.
-            self.url_cache.url_at(&grid, abs_row, col).map(|hit| hit.url)
-        });
-        if let Some(ref url) = url {
-            Self::open_url(url);
-            return true;
+            None => false,
         }
-        false
     }
 
     /// Handle a left-click press in the grid area — selection start.
     pub(super) fn handle_grid_press(&mut self, window_id: WindowId, pos: PhysicalPosition<f64>) {
-        let (col, line) = match self.pixel_to_cell(pos) {
+        let (col, line) = match self.pixel_to_cell(window_id, pos) {
             Some(c) => c,
             None => return,
         };
@@ -86,7 +44,7 @@ impl App {
                 let c = col.min(g.cols.saturating_sub(1));
                 let l = line.min(g.lines.saturating_sub(1));
                 let abs = g.viewport_to_absolute(l);
-                let stable = StableRowIndex::from_absolute(&g, abs);
+                let stable = StableRowIndex::from_absolute(&*g, abs);
                 (g.cols, c, l, abs, stable)
             }
             None => return,
@@ -97,7 +55,7 @@ impl App {
             return;
         }
 
-        let click_count = self.detect_click_count(window_id, col, line);
+        let click_count = self.clicks.click(Instant::now(), window_id, Some((col, line)));
         let shift = self.modifiers.shift_key();
         let alt = self.modifiers.alt_key();
 
@@ -111,9 +69,7 @@ impl App {
                         side,
                     });
                     self.left_mouse_down = true;
-                    if let Some(tw) = self.windows.get(&window_id) {
-                        tw.window.request_redraw();
-                    }
+                    self.redraw.request(window_id, RedrawReason::INPUT);
                     return;
                 }
             }
@@ -123,30 +79,44 @@ impl App {
         // need one more grid lock for word/line boundary computation.
         let new_selection = match click_count {
             2 => {
-                // Double-click: word selection
+                // Double-click: word selection, or the whole URL under it
                 self.tabs.get(&tab_id).map(|tab| {
                     let grid = tab.grid();
-                    let (ws, we) = selection::word_boundaries(&grid, abs_row, col);
+                    let (start, end) = selection::word_span(&grid, &mut self.url_cache, abs_row, col);
                     Selection::new_word(
-                        SelectionPoint { row: stable_row, col: ws, side: Side::Left },
-                        SelectionPoint { row: stable_row, col: we, side: Side::Right },
+                        SelectionPoint {
+                            row: StableRowIndex::from_absolute(&*grid, start.0),
+                            col: start.1,
+                            side: Side::Left,
+                        },
+                        SelectionPoint {
+                            row: StableRowIndex::from_absolute(&*grid, end.0),
+                            col: end.1,
+                            side: Side::Right,
+                        },
                     )
                 })
             }
-            3 => {
-                // Triple-click: line selection
+            3 | 4 => {
+                // Triple-click: line selection; quadruple: command block or paragraph
                 self.tabs.get(&tab_id).map(|tab| {
                     let grid = tab.grid();
-                    let ls = selection::logical_line_start(&grid, abs_row);
-                    let le = selection::logical_line_end(&grid, abs_row);
+                    let (ls, le) = if click_count == 3 {
+                        (
+                            selection::logical_line_start(&grid, abs_row),
+                            selection::logical_line_end(&grid, abs_row),
+                        )
+                    } else {
+                        selection::block_bounds(&grid, abs_row)
+                    };
                     Selection::new_line(
                         SelectionPoint {
-                            row: StableRowIndex::from_absolute(&grid, ls),
+                            row: StableRowIndex::from_absolute(&*grid, ls),
                             col: 0,
                             side: Side::Left,
                         },
                         SelectionPoint {
-                            row: StableRowIndex::from_absolute(&grid, le),
+                            row: StableRowIndex::from_absolute(&*grid, le),
                             col: grid_cols.saturating_sub(1),
                             side: Side::Right,
                         },
@@ -170,9 +140,7 @@ impl App {
         }
         self.left_mouse_down = true;
 
-        if let Some(tw) = self.windows.get(&window_id) {
-            tw.window.request_redraw();
-        }
+        self.redraw.request(window_id, RedrawReason::INPUT);
     }
 
     /// Update selection endpoint during mouse drag.
@@ -189,7 +157,7 @@ impl App {
             return;
         };
 
-        if let Some((col, line)) = self.pixel_to_cell(position) {
+        if let Some((col, line)) = self.pixel_to_cell(window_id, position) {
             let side = self.pixel_to_side(position);
             let Some(tab) = self.tabs.get_mut(&tid) else {
                 return;
@@ -207,13 +175,22 @@ impl App {
                 let col = col.min(grid.cols.saturating_sub(1));
                 let line = line.min(grid.lines.saturating_sub(1));
                 let abs_row = grid.viewport_to_absolute(line);
-                let stable_row = StableRowIndex::from_absolute(&grid, abs_row);
+                let stable_row = StableRowIndex::from_absolute(&*grid, abs_row);
 
                 match sel_mode {
                     Some(SelectionMode::Word) => {
-                        let (ws, we) = selection::word_boundaries(&grid, abs_row, col);
-                        let start_pt = SelectionPoint { row: stable_row, col: ws, side: Side::Left };
-                        let end_pt = SelectionPoint { row: stable_row, col: we, side: Side::Right };
+                        let (start, end) =
+                            selection::word_span(&grid, &mut self.url_cache, abs_row, col);
+                        let start_pt = SelectionPoint {
+                            row: StableRowIndex::from_absolute(&*grid, start.0),
+                            col: start.1,
+                            side: Side::Left,
+                        };
+                        let end_pt = SelectionPoint {
+                            row: StableRowIndex::from_absolute(&*grid, end.0),
+                            col: end.1,
+                            side: Side::Right,
+                        };
                         if sel_anchor.is_some_and(|a| start_pt < a) {
                             Some(start_pt)
                         } else {
@@ -226,13 +203,13 @@ impl App {
                         let grid_cols = grid.cols;
                         if sel_anchor.is_some_and(|a| stable_row < a.row) {
                             Some(SelectionPoint {
-                                row: StableRowIndex::from_absolute(&grid, ls),
+                                row: StableRowIndex::from_absolute(&*grid, ls),
                                 col: 0,
                                 side: Side::Left,
                             })
                         } else {
                             Some(SelectionPoint {
-                                row: StableRowIndex::from_absolute(&grid, le),
+                                row: StableRowIndex::from_absolute(&*grid, le),
                                 col: grid_cols.saturating_sub(1),
                                 side: Side::Right,
                             })
@@ -268,8 +245,6 @@ impl App {
             }
         }
 
-        if let Some(tw) = self.windows.get(&window_id) {
-            tw.window.request_redraw();
-        }
+        self.redraw.request(window_id, RedrawReason::INPUT);
     }
 }
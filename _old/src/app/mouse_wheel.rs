@@ -0,0 +1,400 @@
+//! Mouse wheel and touchpad scrolling, vertical and horizontal.
+//!
+//! Each axis goes, in order of preference, to the application as wheel
+//! button reports (64/65 up/down, 66/67 left/right) when mouse reporting is
+//! on, to arrow keys in alternate-scroll mode, and otherwise to the
+//! scrollback. The grid never extends past the window sideways, so plain
+//! horizontal scrolling has nothing to move and is dropped.
+//!
+//! Touchpads report pixels. Those accumulate per window in a
+//! [`KineticScroll`], which turns them into whole-line steps and keeps the
+//! leftover fraction as a sub-cell offset the grid renderer draws. Lifting
+//! the fingers mid-swipe keeps the view coasting with decaying speed,
+//! except on macOS, where the system sends its own momentum events.
+
+use std::time::{Duration, Instant};
+
+use winit::dpi::PhysicalPosition;
+use winit::event::{MouseScrollDelta, TouchPhase};
+use winit::window::WindowId;
+
+use crate::grid::Grid;
+use crate::tab::Tab;
+use crate::term_mode::TermMode;
+
+use super::{App, RedrawReason, SCROLL_LINES};
+
+/// Coasting speed falls to 1/e after this many seconds.
+const MOMENTUM_TIME_CONSTANT: f64 = 0.325;
+
+/// Coasting stops below this speed (pixels per second).
+const MOMENTUM_MIN_SPEED: f64 = 60.0;
+
+/// Touchpad deltas further apart than this restart the speed estimate.
+const VELOCITY_WINDOW: Duration = Duration::from_millis(100);
+
+/// Which way a wheel moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum WheelAxis {
+    Vertical,
+    Horizontal,
+}
+
+impl WheelAxis {
+    /// Mouse report button for `steps` along this axis. Positive steps
+    /// reveal content up or left.
+    pub(super) fn button(self, steps: i32) -> u8 {
+        match (self, steps > 0) {
+            (Self::Vertical, true) => 64,
+            (Self::Vertical, false) => 65,
+            (Self::Horizontal, true) => 66,
+            (Self::Horizontal, false) => 67,
+        }
+    }
+
+    /// Arrow key sent per step in alternate-scroll mode (SS3 form under
+    /// DECCKM).
+    pub(super) fn arrow_key(self, steps: i32, app_cursor: bool) -> [u8; 3] {
+        let key = match (self, steps > 0) {
+            (Self::Vertical, true) => b'A',
+            (Self::Vertical, false) => b'B',
+            (Self::Horizontal, true) => b'D',
+            (Self::Horizontal, false) => b'C',
+        };
+        [0x1b, if app_cursor { b'O' } else { b'[' }, key]
+    }
+}
+
+/// Whole wheel steps for one axis of a line-wheel notch: [`SCROLL_LINES`]
+/// in the direction winit reports (positive reveals content up or left).
+pub(super) fn notch_steps(v: f32) -> i32 {
+    if v > 0.0 {
+        SCROLL_LINES as i32
+    } else if v < 0.0 {
+        -(SCROLL_LINES as i32)
+    } else {
+        0
+    }
+}
+
+/// Fractional touchpad scrolling with momentum for one window.
+///
+/// Pixel deltas add up until they cover a whole cell; [`Self::offset_y`]
+/// is what is left over. Amounts are (x, y) in physical pixels with
+/// winit's signs.
+#[derive(Debug, Default)]
+pub(crate) struct KineticScroll {
+    /// Pixels scrolled that do not yet make a whole cell.
+    remainder: (f64, f64),
+    /// Recent speed in pixels per second.
+    velocity: (f64, f64),
+    /// Time of the last delta or momentum tick.
+    last: Option<Instant>,
+    coasting: bool,
+}
+
+impl KineticScroll {
+    /// Add a touchpad delta. Returns the whole (columns, lines) it
+    /// completes.
+    pub(super) fn push(&mut self, dx: f64, dy: f64, cell: (f64, f64), now: Instant) -> (i32, i32) {
+        self.coasting = false;
+        match self.last.map(|t| now.saturating_duration_since(t)) {
+            Some(dt) if dt > VELOCITY_WINDOW => self.velocity = (0.0, 0.0),
+            Some(dt) if !dt.is_zero() => {
+                let s = dt.as_secs_f64();
+                self.velocity.0 = 0.5 * self.velocity.0 + 0.5 * dx / s;
+                self.velocity.1 = 0.5 * self.velocity.1 + 0.5 * dy / s;
+            }
+            _ => {}
+        }
+        self.last = Some(now);
+        self.advance(dx, dy, cell)
+    }
+
+    /// The fingers lifted. Starts coasting if they were still moving fast
+    /// enough; otherwise snaps to the nearest line and returns the steps
+    /// that takes.
+    pub(super) fn release(&mut self, cell: (f64, f64), now: Instant) -> (i32, i32) {
+        let paused = self
+            .last
+            .is_none_or(|t| now.saturating_duration_since(t) > VELOCITY_WINDOW);
+        if paused {
+            self.velocity = (0.0, 0.0);
+        }
+        self.last = Some(now);
+        self.coasting = self.speed() >= MOMENTUM_MIN_SPEED;
+        if self.coasting {
+            (0, 0)
+        } else {
+            self.settle(cell)
+        }
+    }
+
+    /// Advance a coast to `now`. Returns the whole steps covered, including
+    /// the final snap to a line when the coast runs out.
+    pub(super) fn tick(&mut self, cell: (f64, f64), now: Instant) -> (i32, i32) {
+        if !self.coasting {
+            return (0, 0);
+        }
+        let dt = self
+            .last
+            .map_or(0.0, |t| now.saturating_duration_since(t).as_secs_f64());
+        self.last = Some(now);
+        let decay = (-dt / MOMENTUM_TIME_CONSTANT).exp();
+        let travel = MOMENTUM_TIME_CONSTANT * (1.0 - decay);
+        let (cols, lines) = self.advance(self.velocity.0 * travel, self.velocity.1 * travel, cell);
+        self.velocity = (self.velocity.0 * decay, self.velocity.1 * decay);
+        if self.speed() >= MOMENTUM_MIN_SPEED {
+            return (cols, lines);
+        }
+        self.coasting = false;
+        let (snap_cols, snap_lines) = self.settle(cell);
+        (cols + snap_cols, lines + snap_lines)
+    }
+
+    /// Stop coasting, keeping the current position (a finger touched down).
+    pub(super) fn stop(&mut self) {
+        self.coasting = false;
+        self.velocity = (0.0, 0.0);
+    }
+
+    /// Whether momentum is still scrolling.
+    pub(super) fn is_coasting(&self) -> bool {
+        self.coasting
+    }
+
+    /// Vertical sub-cell offset in pixels. Positive moves the content down
+    /// toward the line above.
+    pub(super) fn offset_y(&self) -> f64 {
+        self.remainder.1
+    }
+
+    /// Round the remainder to the nearest whole cell and clear it.
+    pub(super) fn settle(&mut self, cell: (f64, f64)) -> (i32, i32) {
+        let snap = |rem: f64, size: f64| {
+            if size > 0.0 {
+                (rem / size).round() as i32
+            } else {
+                0
+            }
+        };
+        let steps = (
+            snap(self.remainder.0, cell.0),
+            snap(self.remainder.1, cell.1),
+        );
+        self.remainder = (0.0, 0.0);
+        steps
+    }
+
+    fn speed(&self) -> f64 {
+        self.velocity.0.hypot(self.velocity.1)
+    }
+
+    fn advance(&mut self, dx: f64, dy: f64, cell: (f64, f64)) -> (i32, i32) {
+        let whole = |rem: &mut f64, d: f64, size: f64| {
+            if size <= 0.0 {
+                return 0;
+            }
+            *rem += d;
+            let cells = (*rem / size).trunc();
+            *rem -= cells * size;
+            cells as i32
+        };
+        (
+            whole(&mut self.remainder.0, dx, cell.0),
+            whole(&mut self.remainder.1, dy, cell.1),
+        )
+    }
+}
+
+impl App {
+    pub(super) fn handle_mouse_wheel(
+        &mut self,
+        window_id: WindowId,
+        delta: MouseScrollDelta,
+        phase: TouchPhase,
+    ) {
+        let (cols, lines) = match delta {
+            MouseScrollDelta::LineDelta(x, y) => {
+                self.kinetic.remove(&window_id);
+                (notch_steps(x), notch_steps(y))
+            }
+            MouseScrollDelta::PixelDelta(pos) => self.touchpad_scroll(window_id, pos, phase),
+        };
+        self.deliver_wheel(window_id, cols, lines);
+        self.redraw.request(window_id, RedrawReason::INPUT);
+    }
+
+    /// Feed a touchpad delta to the window's [`KineticScroll`].
+    fn touchpad_scroll(
+        &mut self,
+        window_id: WindowId,
+        pos: PhysicalPosition<f64>,
+        phase: TouchPhase,
+    ) -> (i32, i32) {
+        let cell = self.cell_size();
+        let now = Instant::now();
+        let kinetic = self.kinetic.entry(window_id).or_default();
+        let steps = match phase {
+            TouchPhase::Started => {
+                kinetic.stop();
+                kinetic.push(pos.x, pos.y, cell, now)
+            }
+            TouchPhase::Moved => kinetic.push(pos.x, pos.y, cell, now),
+            TouchPhase::Ended => {
+                let (cols, lines) = kinetic.push(pos.x, pos.y, cell, now);
+                // macOS follows up with momentum deltas of its own.
+                if cfg!(target_os = "macos") {
+                    (cols, lines)
+                } else {
+                    let (snap_cols, snap_lines) = kinetic.release(cell, now);
+                    (cols + snap_cols, lines + snap_lines)
+                }
+            }
+            TouchPhase::Cancelled => {
+                kinetic.stop();
+                kinetic.settle(cell)
+            }
+        };
+        self.mark_active_grid_dirty(window_id);
+        steps
+    }
+
+    /// Advance every coasting touchpad scroll. Called from `about_to_wait`.
+    pub(super) fn tick_kinetic_scroll(&mut self) {
+        self.kinetic.retain(|wid, _| self.windows.contains_key(wid));
+        let cell = self.cell_size();
+        let now = Instant::now();
+        let moved: Vec<_> = self
+            .kinetic
+            .iter_mut()
+            .filter(|(_, k)| k.is_coasting())
+            .map(|(&wid, k)| (wid, k.tick(cell, now)))
+            .collect();
+        for (wid, (cols, lines)) in moved {
+            self.deliver_wheel(wid, cols, lines);
+            self.mark_active_grid_dirty(wid);
+            self.redraw.request(wid, RedrawReason::ANIMATION);
+        }
+    }
+
+    /// Vertical smooth-scroll offset for the grid renderer, in pixels.
+    /// Zero unless the wheel scrolls this window's view and the view has
+    /// room to move that way.
+    pub(super) fn smooth_scroll_offset(
+        &self,
+        window_id: WindowId,
+        mode: TermMode,
+        grid: &Grid,
+    ) -> f32 {
+        let Some(offset) = self.kinetic.get(&window_id).map(KineticScroll::offset_y) else {
+            return 0.0;
+        };
+        let to_app = mode.intersects(TermMode::ANY_MOUSE)
+            || mode.contains(TermMode::ALT_SCREEN | TermMode::ALTERNATE_SCROLL);
+        let room = if offset > 0.0 {
+            grid.display_offset < grid.scrollback.len()
+        } else {
+            grid.display_offset > 0
+        };
+        if room && (self.modifiers.shift_key() || !to_app) {
+            offset as f32
+        } else {
+            0.0
+        }
+    }
+
+    /// [`Self::smooth_scroll_offset`] of the window's active tab, so the
+    /// mouse maps to rows where they are drawn.
+    pub(super) fn active_scroll_offset(&self, window_id: WindowId) -> f64 {
+        // Skip the terminal lock on the usual mouse move, with no scroll
+        // in flight.
+        if !self.kinetic.get(&window_id).is_some_and(|k| k.offset_y() != 0.0) {
+            return 0.0;
+        }
+        let Some(tab) = self.active_tab_id(window_id).and_then(|tid| self.tabs.get(&tid)) else {
+            return 0.0;
+        };
+        let mode = tab.mode();
+        f64::from(self.smooth_scroll_offset(window_id, mode, &tab.grid()))
+    }
+
+    fn cell_size(&self) -> (f64, f64) {
+        (
+            self.font_collection.cell_width as f64,
+            self.font_collection.cell_height as f64,
+        )
+    }
+
+    /// Force the window's active tab to rebuild its grid instances.
+    pub(super) fn mark_active_grid_dirty(&self, window_id: WindowId) {
+        if let Some(tab) = self
+            .active_tab_id(window_id)
+            .and_then(|tid| self.tabs.get(&tid))
+        {
+            tab.set_grid_dirty(true);
+        }
+    }
+
+    fn deliver_wheel(&mut self, window_id: WindowId, cols: i32, lines: i32) {
+        if lines != 0 {
+            self.wheel_scroll(window_id, WheelAxis::Vertical, lines);
+        }
+        if cols != 0 {
+            self.wheel_scroll(window_id, WheelAxis::Horizontal, cols);
+        }
+    }
+
+    /// Deliver `steps` of wheel motion along `axis` to the active tab.
+    fn wheel_scroll(&mut self, window_id: WindowId, axis: WheelAxis, steps: i32) {
+        let Some(tid) = self.active_tab_id(window_id) else {
+            return;
+        };
+        let Some(mode) = self.tabs.get(&tid).map(Tab::mode) else {
+            return;
+        };
+        let count = steps.unsigned_abs() as usize;
+
+        // Shift bypasses the application and always scrolls the view.
+        if !self.modifiers.shift_key() {
+            // Mouse reporting: wheel buttons sent to PTY when mouse mode active
+            if mode.intersects(TermMode::ANY_MOUSE) {
+                let pos = self
+                    .cursor_pos
+                    .get(&window_id)
+                    .copied()
+                    .unwrap_or(PhysicalPosition::new(0.0, 0.0));
+                if let Some((col, line)) = self.pixel_to_cell(window_id, pos) {
+                    for _ in 0..count {
+                        self.send_mouse_report(tid, axis.button(steps), col, line, true);
+                    }
+                }
+                return;
+            }
+
+            // Alternate scroll: convert scroll to arrow keys in alt screen
+            if mode.contains(TermMode::ALT_SCREEN | TermMode::ALTERNATE_SCROLL) {
+                let seq = axis.arrow_key(steps, mode.contains(TermMode::APP_CURSOR));
+                if let Some(tab) = self.tabs.get(&tid) {
+                    for _ in 0..count {
+                        tab.send_pty(&seq);
+                    }
+                }
+                return;
+            }
+        }
+
+        // Normal scrollback
+        if axis == WheelAxis::Vertical {
+            if let Some(tab) = self.tabs.get(&tid) {
+                let before = tab.grid().display_offset;
+                tab.scroll_lines(steps);
+                // At either end of the scrollback: stop coasting into it.
+                if tab.grid().display_offset == before {
+                    self.kinetic.remove(&window_id);
+                }
+            }
+            self.redraw.request(window_id, RedrawReason::INPUT);
+        }
+    }
+}
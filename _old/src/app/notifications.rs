@@ -0,0 +1,235 @@
+//! Application badge: unseen notifications and bells across all tabs.
+//!
+//! A notification or bell counts as unseen until its tab is the active tab
+//! of the focused window. The app keeps a running total as tabs gain and
+//! lose unseen items, and shows it as a count on the taskbar button, dock
+//! icon, or launcher entry (see [`crate::badge`]). With `bell.urgent` on,
+//! the total growing while no oriterm window has focus also asks for the
+//! user's attention, and the request is withdrawn once everything has been
+//! seen. In the tab bar a tab with unseen notifications shows the same
+//! badge as a bell.
+//!
+//! Notifications are also shown as desktop toasts unless disabled in
+//! `[notifications]` or muted for their tab. Clicking a toast brings its
+//! tab to the front.
+
+use winit::window::{UserAttentionType, WindowId};
+
+use crate::log;
+use crate::tab::{Notification, NotificationResponse, TabId, Unseen};
+use crate::term_mode::TermMode;
+
+use super::{App, RedrawReason};
+
+/// How the OS attention cue should change after a badge update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Attention {
+    /// Ask for the user's attention.
+    Request,
+    /// Withdraw an earlier request.
+    Clear,
+}
+
+/// Running total of every tab's [`Unseen`] items: the app badge count.
+/// Each change to a tab's `unseen` goes through here, so the total never
+/// needs every tab walked.
+#[derive(Debug, Default)]
+pub(crate) struct UnseenTotal {
+    notifications: u32,
+    bells: u32,
+}
+
+impl UnseenTotal {
+    /// Set the bell badge in `unseen`.
+    pub(super) fn ring(&mut self, unseen: &mut Unseen) {
+        if !std::mem::replace(&mut unseen.bell, true) {
+            self.bells += 1;
+        }
+    }
+
+    /// Clear the bell badge in `unseen`.
+    pub(super) fn clear_bell(&mut self, unseen: &mut Unseen) {
+        if std::mem::take(&mut unseen.bell) {
+            self.bells = self.bells.saturating_sub(1);
+        }
+    }
+
+    /// Count one more notification into `unseen`.
+    pub(super) fn notify(&mut self, unseen: &mut Unseen) {
+        unseen.notifications = unseen.notifications.saturating_add(1);
+        self.notifications = self.notifications.saturating_add(1);
+    }
+
+    /// Mark everything in `unseen` seen. Returns whether anything was.
+    pub(super) fn clear(&mut self, unseen: &mut Unseen) -> bool {
+        let was = std::mem::take(unseen);
+        self.forget(was);
+        was.count() > 0
+    }
+
+    /// Drop the items of a tab that closed.
+    pub(super) fn forget(&mut self, unseen: Unseen) {
+        self.notifications = self.notifications.saturating_sub(unseen.notifications);
+        self.bells = self.bells.saturating_sub(u32::from(unseen.bell));
+    }
+
+    /// The app badge count.
+    pub(super) fn count(&self) -> u32 {
+        self.notifications.saturating_add(self.bells)
+    }
+
+    /// Whether any tab shows a bell badge.
+    pub(super) fn any_bell(&self) -> bool {
+        self.bells > 0
+    }
+}
+
+/// The attention change for a badge going from `prev` to `count`.
+pub(super) fn attention_change(prev: u32, count: u32, app_focused: bool) -> Option<Attention> {
+    if count == 0 && prev > 0 {
+        Some(Attention::Clear)
+    } else if count > prev && !app_focused {
+        Some(Attention::Request)
+    } else {
+        None
+    }
+}
+
+impl App {
+    /// Whether `tab_id` is the active tab of the focused window.
+    pub(super) fn is_tab_focused(&self, tab_id: TabId) -> bool {
+        self.focused_window
+            .and_then(|wid| self.windows.get(&wid))
+            .is_some_and(|tw| tw.active_tab_id() == Some(tab_id))
+    }
+
+    /// Show `notif` from `tab_id` as a desktop toast, unless toasts are off,
+    /// the tab is muted, or the tab is already in front (`is_focused`).
+    pub(super) fn show_desktop_notification(
+        &self,
+        tab_id: TabId,
+        notif: &Notification,
+        is_focused: bool,
+    ) {
+        if notif.title.is_empty() {
+            log(&format!("notification: {}", notif.body));
+        } else {
+            log(&format!("notification: {} — {}", notif.title, notif.body));
+        }
+        let cfg = &self.config.notifications;
+        let muted = self
+            .tabs
+            .get(&tab_id)
+            .is_none_or(|tab| tab.notifications_muted);
+        if cfg.enabled && !muted && (cfg.when_focused || !is_focused) {
+            self.notifier.show(notif, tab_id);
+        }
+    }
+
+    /// A toast from `tab_id` closed. Clicks focus the tab; OSC 99
+    /// notifications (`id`) get their activation and close reports.
+    pub(super) fn handle_notification_response(
+        &mut self,
+        tab_id: TabId,
+        id: Option<String>,
+        response: NotificationResponse,
+    ) {
+        let button = match response {
+            NotificationResponse::Clicked => None,
+            NotificationResponse::Button(n) => Some(n),
+            NotificationResponse::Dismissed => {
+                if let (Some(id), Some(tab)) = (id, self.tabs.get_mut(&tab_id)) {
+                    tab.notification_closed(&id);
+                }
+                return;
+            }
+        };
+        self.focus_tab(tab_id);
+        if let (Some(id), Some(tab)) = (id, self.tabs.get_mut(&tab_id)) {
+            tab.notification_activated(&id, button);
+            tab.notification_closed(&id);
+        }
+    }
+
+    /// A bell rang in `tab_id`. If its window is in the background, raise
+    /// it (DECSET 1043) or ask for attention (`bell.urgent` or DECSET 1042).
+    pub(super) fn bell_attention(&self, tab_id: TabId, mode: TermMode) {
+        let Some(wid) = self.window_containing_tab(tab_id) else {
+            return;
+        };
+        if self.focused_window == Some(wid) {
+            return;
+        }
+        let Some(tw) = self.windows.get(&wid) else {
+            return;
+        };
+        if mode.contains(TermMode::POP_ON_BELL) {
+            tw.window.set_minimized(false);
+            tw.window.focus_window();
+        } else if self.config.bell.urgent || mode.contains(TermMode::URGENT_BELL) {
+            tw.window
+                .request_user_attention(Some(UserAttentionType::Informational));
+        } else {
+            // Quiet bell: the tab bar badge is enough.
+        }
+    }
+
+    /// Make `tab_id` the active tab of its window and focus that window.
+    pub(super) fn focus_tab(&mut self, tab_id: TabId) {
+        let Some(wid) = self.window_containing_tab(tab_id) else {
+            return;
+        };
+        let Some(tw) = self.windows.get_mut(&wid) else {
+            return;
+        };
+        if let Some(idx) = tw.tab_index(tab_id) {
+            tw.active_tab = idx;
+        }
+        tw.window.set_minimized(false);
+        tw.window.focus_window();
+        self.tab_bar_dirty = true;
+        self.redraw.request(wid, RedrawReason::INPUT);
+    }
+
+    /// Mark the focused window's active tab as seen, then republish the
+    /// badge. With `bell.urgent` on, a badge appearing while no window has
+    /// focus also asks for attention.
+    pub(super) fn update_app_badge(&mut self) {
+        let focused_tab = self.focused_window.and_then(|wid| self.active_tab_id(wid));
+        if let Some(tab) = focused_tab.and_then(|tid| self.tabs.get_mut(&tid)) {
+            if self.unseen.clear(&mut tab.unseen) {
+                self.tab_bar_dirty = true;
+            }
+        }
+
+        let count = self.unseen.count();
+        let prev = std::mem::replace(&mut self.published_badge, count);
+        if count != prev {
+            crate::badge::publish(
+                &self.window_class,
+                self.windows.values().map(|tw| &*tw.window),
+                count,
+            );
+        }
+        match attention_change(prev, count, self.focused_window.is_some()) {
+            // Asking for attention is the same opt-in as for a bell.
+            Some(Attention::Request) if self.config.bell.urgent => {
+                let target = self.tabs.iter().find_map(|(&tid, tab)| {
+                    (tab.unseen.count() > 0)
+                        .then(|| self.window_containing_tab(tid))
+                        .flatten()
+                });
+                if let Some(tw) = target.and_then(|wid: WindowId| self.windows.get(&wid)) {
+                    tw.window
+                        .request_user_attention(Some(UserAttentionType::Informational));
+                }
+            }
+            Some(Attention::Clear) => {
+                for tw in self.windows.values() {
+                    tw.window.request_user_attention(None);
+                }
+            }
+            Some(Attention::Request) | None => {}
+        }
+    }
+}
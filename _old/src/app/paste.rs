@@ -0,0 +1,68 @@
+//! Pasting into the active tab: from the clipboard or the primary selection,
+//! wrapped in bracketed paste markers when the program asked for them. Mouse
+//! selections become the primary selection.
+
+use winit::window::WindowId;
+
+use crate::clipboard;
+use crate::tab::{Bracketing, Tab, TabId};
+use crate::term_mode::TermMode;
+
+use super::App;
+
+impl App {
+    pub(super) fn paste_from_clipboard(&mut self, window_id: WindowId) {
+        if let Some(text) = clipboard::get_text() {
+            self.paste_text(window_id, &text);
+        }
+    }
+
+    /// Paste the primary selection (middle-click on Linux).
+    pub(super) fn paste_primary(&mut self, window_id: WindowId) {
+        if let Some(text) = clipboard::get_primary() {
+            self.paste_text(window_id, &text);
+        }
+    }
+
+    /// Offer `tab_id`'s selection as the primary selection, for
+    /// middle-click paste here and in other programs.
+    pub(super) fn set_primary_from_selection(&self, tab_id: TabId) {
+        let Some(tab) = self.tabs.get(&tab_id) else {
+            return;
+        };
+        if let Some(sel) = &tab.selection {
+            let text = self.selection_text(&tab.grid(), sel);
+            if !text.is_empty() {
+                clipboard::set_primary(&text);
+            }
+        }
+    }
+
+    /// Cancel the active tab's paste, if one is in flight. Returns whether
+    /// there was one.
+    pub(super) fn cancel_paste(&mut self, window_id: WindowId) -> bool {
+        let Some(tab_id) = self.active_tab_id(window_id) else {
+            return false;
+        };
+        let cancelled = self.tabs.get_mut(&tab_id).is_some_and(Tab::cancel_paste);
+        if cancelled {
+            self.tab_bar_dirty = true;
+        }
+        cancelled
+    }
+
+    /// Paste `text` into `window_id`'s active tab.
+    pub(super) fn paste_text(&mut self, window_id: WindowId, text: &str) {
+        let Some(tab_id) = self.active_tab_id(window_id) else {
+            return;
+        };
+        if let Some(tab) = self.tabs.get_mut(&tab_id) {
+            let bracketing = if tab.mode().contains(TermMode::BRACKETED_PASTE) {
+                Bracketing::Bracketed
+            } else {
+                Bracketing::Plain
+            };
+            tab.paste(text, bracketing);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Per-window redraw scheduling.
+//!
+//! Every redraw cause (PTY output, blink timers, animations, hover, input)
+//! goes through [`RedrawScheduler::request`] instead of calling
+//! `Window::request_redraw` directly. Requests for a window merge into one
+//! set of [`RedrawReason`]s, which `about_to_wait` drains at most once per
+//! the window's frame interval. Requests for occluded or minimized windows
+//! are dropped; the window gets one full redraw when it becomes visible.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use bitflags::bitflags;
+use winit::window::WindowId;
+
+/// Frame interval when the display refresh rate is unknown or the surface
+/// doesn't wait for vblank (~120 Hz).
+pub(super) const DEFAULT_FRAME_INTERVAL: Duration = Duration::from_millis(8);
+
+/// Fastest frame pacing honoured (240 Hz).
+const MIN_FRAME_INTERVAL: Duration = Duration::from_micros(4_166);
+
+/// Slowest frame pacing honoured, so a bogus refresh rate can't stall input.
+const MAX_FRAME_INTERVAL: Duration = Duration::from_millis(33);
+
+bitflags! {
+    /// Why a window needs a new frame.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub(super) struct RedrawReason: u16 {
+        const PTY_OUTPUT   = 1;
+        const CURSOR_BLINK = 1 << 1;
+        const TEXT_BLINK   = 1 << 2;
+        const ANIMATION    = 1 << 3;
+        const HOVER        = 1 << 4;
+        const INPUT        = 1 << 5;
+        const TAB_BAR      = 1 << 6;
+        const RESIZE       = 1 << 7;
+        const CONFIG       = 1 << 8;
+        const EXPOSE       = 1 << 9;
+        /// The echo of a keystroke; drawn without waiting for the frame
+        /// interval (`terminal.immediate_echo`).
+        const ECHO         = 1 << 10;
+    }
+}
+
+/// Whether the OS reports a window as visible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Visibility {
+    Visible,
+    Hidden,
+}
+
+/// Minimum time between frames for a surface presented with `present_mode`
+/// on a display refreshing at `refresh_millihertz`.
+///
+/// Vsync'd modes can't show frames faster than the display refreshes, so
+/// rendering more often only blocks in `get_current_texture`.
+pub(super) fn frame_interval(
+    present_mode: wgpu::PresentMode,
+    refresh_millihertz: Option<u32>,
+) -> Duration {
+    let vsync = matches!(
+        present_mode,
+        wgpu::PresentMode::Fifo | wgpu::PresentMode::FifoRelaxed | wgpu::PresentMode::AutoVsync
+    );
+    match refresh_millihertz.filter(|&mhz| mhz > 0) {
+        Some(mhz) if vsync => Duration::from_nanos(1_000_000_000_000 / u64::from(mhz))
+            .clamp(MIN_FRAME_INTERVAL, MAX_FRAME_INTERVAL),
+        _ => DEFAULT_FRAME_INTERVAL,
+    }
+}
+
+/// Scheduling state for one window.
+#[derive(Debug)]
+struct WindowSlot {
+    pending: RedrawReason,
+    occlusion: Visibility,
+    /// Hidden while the window has a zero-size client area (minimized).
+    size: Visibility,
+    interval: Duration,
+    last_frame: Option<Instant>,
+}
+
+impl WindowSlot {
+    fn new() -> Self {
+        Self {
+            pending: RedrawReason::empty(),
+            occlusion: Visibility::Visible,
+            size: Visibility::Visible,
+            interval: DEFAULT_FRAME_INTERVAL,
+            last_frame: None,
+        }
+    }
+
+    fn is_visible(&self) -> bool {
+        self.occlusion == Visibility::Visible && self.size == Visibility::Visible
+    }
+
+    /// When this window may render its next frame. Echo frames are never
+    /// held back.
+    fn due_at(&self) -> Option<Instant> {
+        if self.pending.contains(RedrawReason::ECHO) {
+            return None;
+        }
+        self.last_frame.map(|t| t + self.interval)
+    }
+}
+
+/// Merges redraw requests per window and paces frames.
+#[derive(Debug, Default)]
+pub(crate) struct RedrawScheduler {
+    windows: HashMap<WindowId, WindowSlot>,
+    /// Frames rendered per reason since the last stats line.
+    reason_counts: [u32; RedrawReason::all().bits().count_ones() as usize],
+    /// Requests dropped because their window was hidden.
+    dropped: u32,
+}
+
+impl RedrawScheduler {
+    /// Ask for a frame of `wid` because of `reason`.
+    pub(super) fn request(&mut self, wid: WindowId, reason: RedrawReason) {
+        let slot = self.windows.entry(wid).or_insert_with(WindowSlot::new);
+        if slot.is_visible() {
+            slot.pending |= reason;
+        } else {
+            self.dropped += 1;
+        }
+    }
+
+    /// Ask for a frame of every window in `wids`.
+    pub(super) fn request_all(
+        &mut self,
+        wids: impl IntoIterator<Item = WindowId>,
+        reason: RedrawReason,
+    ) {
+        for wid in wids {
+            self.request(wid, reason);
+        }
+    }
+
+    /// Windows with a pending frame whose frame interval has elapsed.
+    pub(super) fn due(&self, now: Instant) -> Vec<WindowId> {
+        self.windows
+            .iter()
+            .filter(|(_, s)| !s.pending.is_empty() && s.due_at().is_none_or(|t| now >= t))
+            .map(|(&wid, _)| wid)
+            .collect()
+    }
+
+    /// Earliest instant a pending frame becomes due.
+    pub(super) fn next_deadline(&self) -> Option<Instant> {
+        self.windows
+            .values()
+            .filter(|s| !s.pending.is_empty())
+            .map(|s| s.due_at().unwrap_or_else(Instant::now))
+            .min()
+    }
+
+    /// Clear `wid`'s pending reasons as it renders at `now`.
+    pub(super) fn begin_frame(&mut self, wid: WindowId, now: Instant) {
+        let slot = self.windows.entry(wid).or_insert_with(WindowSlot::new);
+        slot.last_frame = Some(now);
+        let reasons = std::mem::take(&mut slot.pending);
+        for (count, flag) in self
+            .reason_counts
+            .iter_mut()
+            .zip(RedrawReason::all().iter())
+        {
+            *count += u32::from(reasons.contains(flag));
+        }
+    }
+
+    /// Record an occlusion change. A window becoming visible gets a frame.
+    pub(super) fn set_occlusion(&mut self, wid: WindowId, visibility: Visibility) {
+        self.update_visibility(wid, |slot| slot.occlusion = visibility);
+    }
+
+    /// Record a client-area size change; zero-size windows are minimized.
+    pub(super) fn set_size(&mut self, wid: WindowId, width: u32, height: u32) {
+        let visibility = if width == 0 || height == 0 {
+            Visibility::Hidden
+        } else {
+            Visibility::Visible
+        };
+        self.update_visibility(wid, |slot| slot.size = visibility);
+    }
+
+    fn update_visibility(&mut self, wid: WindowId, apply: impl FnOnce(&mut WindowSlot)) {
+        let slot = self.windows.entry(wid).or_insert_with(WindowSlot::new);
+        let was_visible = slot.is_visible();
+        apply(slot);
+        match (was_visible, slot.is_visible()) {
+            (_, false) => slot.pending = RedrawReason::empty(),
+            (false, true) => slot.pending |= RedrawReason::EXPOSE,
+            (true, true) => {}
+        }
+    }
+
+    /// Set the minimum time between frames of `wid` (see [`frame_interval`]).
+    pub(super) fn set_frame_interval(&mut self, wid: WindowId, interval: Duration) {
+        self.windows
+            .entry(wid)
+            .or_insert_with(WindowSlot::new)
+            .interval = interval;
+    }
+
+    /// Forget windows that no longer exist.
+    pub(super) fn retain(&mut self, mut keep: impl FnMut(&WindowId) -> bool) {
+        self.windows.retain(|wid, _| keep(wid));
+    }
+
+    /// Summary of frame reasons and dropped requests since the last call,
+    /// for the periodic stats log. Resets the counters.
+    pub(super) fn take_stats(&mut self) -> String {
+        let mut parts: Vec<String> = RedrawReason::all()
+            .iter_names()
+            .zip(self.reason_counts)
+            .filter(|&(_, count)| count > 0)
+            .map(|((name, _), count)| format!("{}={count}", name.to_ascii_lowercase()))
+            .collect();
+        if self.dropped > 0 {
+            parts.push(format!("dropped={}", self.dropped));
+        }
+        self.reason_counts = Default::default();
+        self.dropped = 0;
+        parts.join(" ")
+    }
+}
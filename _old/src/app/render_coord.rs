@@ -1,16 +1,21 @@
 //! Render coordination — frame building and context menu action dispatch.
 
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use winit::event_loop::ActiveEventLoop;
 use winit::window::WindowId;
 
 use super::App;
 use crate::context_menu::ContextAction;
-use crate::gpu::FrameParams;
+use crate::gpu::{FrameParams, GpuRenderer, InspectorOverlay};
+use crate::render::{BlinkPhase, CursorBlink, TextBlink};
+use crate::selection::{Selection, SelectionMode};
+use crate::term_mode::TermMode;
 use crate::log;
+use crate::config;
 use crate::palette;
-use crate::tab::{Tab, TabId};
+use crate::clipboard;
+use crate::tab::Tab;
 use crate::grid::{GRID_PADDING_BOTTOM, GRID_PADDING_LEFT, GRID_PADDING_TOP};
 use crate::tab_bar::TAB_BAR_HEIGHT;
 #[cfg(target_os = "windows")]
@@ -20,16 +25,21 @@ use crate::tab_bar::{
 };
 use crate::tab_bar::TabBarHit;
 
+/// Height of the band along the top edge that still drags the window in
+/// focus mode, counting the resize border above it (logical pixels).
+#[cfg(target_os = "windows")]
+const FOCUS_DRAG_HEIGHT: f64 = 2.0 * super::RESIZE_BORDER;
+
 impl App {
     /// Computes grid dimensions (cols, rows) for a given window size in physical pixels.
     pub(super) fn grid_dims_for_size(&self, width: u32, height: u32) -> (usize, usize) {
         let cw = self.font_collection.cell_width;
         let ch = self.font_collection.cell_height;
-        let grid_w = (width as usize).saturating_sub(self.scale_px(GRID_PADDING_LEFT));
+        let grid_w = (width as usize).saturating_sub(self.chrome_px(GRID_PADDING_LEFT));
         let grid_h = (height as usize).saturating_sub(
-            self.scale_px(TAB_BAR_HEIGHT)
-                + self.scale_px(GRID_PADDING_TOP)
-                + self.scale_px(GRID_PADDING_BOTTOM),
+            self.chrome_px(TAB_BAR_HEIGHT)
+                + self.chrome_px(GRID_PADDING_TOP)
+                + self.chrome_px(GRID_PADDING_BOTTOM),
         );
         let cols = if cw > 0 {
             grid_w / cw
@@ -44,36 +54,6 @@ impl App {
         (cols.max(2), rows.max(1))
     }
 
-    /// Rebuild cached tab bar data (titles + bell badges) for the given window.
-    fn rebuild_tab_bar_cache(&mut self, window_id: WindowId, active_tab_id: Option<TabId>) {
-        let (new_info, new_badges) = if let Some(tw) = self.windows.get(&window_id) {
-            let info: Vec<(TabId, String)> = tw
-                .tabs
-                .iter()
-                .map(|id| {
-                    let title = self
-                        .tabs
-                        .get(id)
-                        .map_or_else(|| "?".to_string(), Tab::effective_title);
-                    (*id, title)
-                })
-                .collect();
-            let badges: Vec<bool> = tw
-                .tabs
-                .iter()
-                .map(|id| {
-                    self.tabs.get(id).is_some_and(|t| t.has_bell_badge)
-                        && Some(*id) != active_tab_id
-                })
-                .collect();
-            (info, badges)
-        } else {
-            (Vec::new(), Vec::new())
-        };
-        self.cached_tab_info = new_info;
-        self.cached_bell_badges = new_badges;
-    }
-
     /// Update Windows `WM_NCHITTEST` rects so the OS knows which areas are
     /// interactive (`HTCLIENT`) vs draggable (`HTCAPTION`).
     #[cfg(target_os = "windows")]
@@ -83,19 +63,29 @@ impl App {
         };
         let sf = self.scale_factor;
         let twl = self.tab_width_lock_for(window_id);
-        let layout = TabBarLayout::compute(tab_count, bar_w, sf, twl);
+        let pinned = self.pinned_count(window_id);
+        let layout = TabBarLayout::compute(tab_count, pinned, bar_w, sf, twl);
         let h = self.scale_px(TAB_BAR_HEIGHT) as i32;
+        // Focus mode hides the tab bar: the caption band is grid, except a
+        // thin band along the top edge left as `HTCAPTION` so the window
+        // can still be moved.
+        if self.focus_mode {
+            let drag = (FOCUS_DRAG_HEIGHT * sf) as i32;
+            let grid = vec![[0, drag, bar_w as i32, h]];
+            crate::platform_windows::set_client_rects(&tw.window, grid);
+            return;
+        }
         let left_margin = self.scale_px(TAB_LEFT_MARGIN);
         let mut rects = Vec::new();
         // Individual tab rects
         for i in 0..layout.tab_count {
-            let left = (left_margin + i * layout.tab_width) as i32;
-            let right = left + layout.tab_width as i32;
+            let left = (left_margin + layout.tab_offset(i)) as i32;
+            let right = left + layout.width_of(i) as i32;
             rects.push([left, 0, right, h]);
         }
         // New tab button
         let new_tab_w = self.scale_px(NEW_TAB_BUTTON_WIDTH);
-        let tabs_end = left_margin + layout.tab_count * layout.tab_width;
+        let tabs_end = left_margin + layout.tabs_width();
         rects.push([tabs_end as i32, 0, (tabs_end + new_tab_w) as i32, h]);
         // Dropdown button
         let dropdown_w = self.scale_px(DROPDOWN_BUTTON_WIDTH);
@@ -108,17 +98,49 @@ impl App {
         crate::platform_windows::set_client_rects(&tw.window, rects);
     }
 
+    /// Cursor blink timing from the config.
+    pub(super) fn cursor_blink(&self) -> CursorBlink {
+        CursorBlink {
+            interval_ms: self.config.terminal.cursor_blink_interval_ms,
+            pause_ms: self.config.terminal.cursor_blink_pause_ms,
+        }
+    }
+
     /// Compute cursor blink visibility from elapsed time since last reset.
+    /// The cursor stays solid for the blink pause after each reset.
     pub(super) fn cursor_blink_visible(&self) -> bool {
         if self.config.terminal.cursor_blink {
             let elapsed_ms = self.cursor_blink_reset.elapsed().as_millis() as u64;
-            let interval = self.config.terminal.cursor_blink_interval_ms.max(1);
-            (elapsed_ms / interval).is_multiple_of(2)
+            self.cursor_blink().visible(elapsed_ms)
         } else {
             true
         }
     }
 
+    /// Current text blink phase, always visible when `text_blink = "off"`.
+    pub(super) fn text_blink_phase(&self) -> BlinkPhase {
+        if self.config.terminal.text_blink == TextBlink::Off {
+            return BlinkPhase::VISIBLE;
+        }
+        let elapsed_ms = self.text_blink_start.elapsed().as_millis() as u64;
+        BlinkPhase::at(elapsed_ms, self.config.terminal.text_blink_interval_ms)
+    }
+
+    /// When blinking text is on screen, the instant of its next toggle.
+    pub(super) fn next_text_blink_toggle(&self) -> Option<Instant> {
+        let blinking = self
+            .renderer
+            .as_ref()
+            .is_some_and(GpuRenderer::has_blinking_cells);
+        if !blinking || self.config.terminal.text_blink == TextBlink::Off {
+            return None;
+        }
+        let elapsed_ms = self.text_blink_start.elapsed().as_millis() as u64;
+        let wait =
+            BlinkPhase::until_next_toggle(elapsed_ms, self.config.terminal.text_blink_interval_ms);
+        Some(Instant::now() + Duration::from_millis(wait))
+    }
+
     /// Renders a single window, building frame params and dispatching to GPU renderer.
     pub(super) fn render_window(&mut self, window_id: WindowId) {
         if self.is_settings_window(window_id) {
@@ -142,8 +164,8 @@ impl App {
         if self.tab_bar_dirty {
             self.rebuild_tab_bar_cache(window_id, active_tab_id);
         }
-        let tab_info = &self.cached_tab_info;
-        let bell_badges = &self.cached_bell_badges;
+        let tab_info = &self.tab_bar_cache.info;
+        let bell_badges = &self.tab_bar_cache.bell_badges;
 
         if phys.width == 0 || phys.height == 0 {
             return;
@@ -169,27 +191,35 @@ impl App {
                         .map(|idx| (idx, px))
                 })
             });
-        let tab_offsets = self
-            .tab_anim_offsets
-            .get(&window_id)
-            .map_or(&[][..], |v| v.as_slice());
+        let tab_offsets = self.animations.tab_offsets(window_id);
 
         let any_bell_badge = bell_badges.iter().any(|&b| b);
         let bell_phase = if any_bell_badge {
-            let secs = self.last_anim_time.elapsed().as_secs_f32();
-            (secs * std::f32::consts::TAU * 0.5).sin() * 0.5 + 0.5
+            self.animations.pulse_phase(Instant::now())
         } else {
             0.0
         };
 
-        let cursor_visible = self.cursor_blink_visible();
-        let cursor_visible_changed = cursor_visible != self.prev_cursor_visible;
-        self.prev_cursor_visible = cursor_visible;
+        let blink_visible = self.cursor_blink_visible();
+        let cursor_visible_changed = blink_visible != self.prev_cursor_visible;
+        self.prev_cursor_visible = blink_visible;
+        // Unfocused windows draw a steady cursor in the unfocused style.
+        let focused = self.focused_window == Some(window_id);
+        let cursor_visible = blink_visible || !focused;
+
+        let blink_phase = self.text_blink_phase();
+        let blink_changed = blink_phase != self.prev_blink_phase
+            && self
+                .renderer
+                .as_ref()
+                .is_some_and(GpuRenderer::has_blinking_cells);
+        self.prev_blink_phase = blink_phase;
 
         let grid_dirty = active_tab_id
             .and_then(|id| self.tabs.get(&id))
             .is_some_and(Tab::grid_dirty)
-            || cursor_visible_changed;
+            || cursor_visible_changed
+            || blink_changed;
         let tab_bar_dirty = self.tab_bar_dirty;
         let twl = self.tab_width_lock_for(window_id);
 
@@ -204,35 +234,105 @@ impl App {
             return;
         };
         let term = tab.terminal.lock();
+        let mut mode = term.mode;
+        if !self.config.terminal.bidi {
+            mode.remove(TermMode::BIDI);
+        }
 
+        let filter_rows = tab
+            .search
+            .as_ref()
+            .and_then(|search| search.filter_rows(term.active_grid()));
+        let tab_rename = self.tab_rename_index(window_id);
+        let chord_label = self
+            .pending_chord
+            .as_ref()
+            .filter(|p| p.window == window_id)
+            .map(super::key_chord::PendingChord::label);
         let frame_params = FrameParams {
             width: phys.width,
             height: phys.height,
             grid: term.active_grid(),
             palette: &term.palette,
-            mode: term.mode,
-            cursor_shape: term.cursor_shape,
+            mode,
+            cursor_shape: if focused {
+                term.cursor_shape
+            } else {
+                config::parse_cursor_style(&self.config.terminal.cursor_unfocused_style)
+            },
+            cursor_color: self
+                .config
+                .terminal
+                .cursor_unfocused_color
+                .as_deref()
+                .filter(|_| !focused)
+                .and_then(palette::parse_hex_color),
             selection: tab.selection.as_ref(),
             search: tab.search.as_ref(),
+            filter_rows: filter_rows.as_deref(),
+            inspector: term.inspector.as_ref().map(|log| InspectorOverlay {
+                log,
+                unknown: &term.unknown_sequences,
+                filter: &self.inspector_filter,
+                focused: self.inspector_focus == Some(window_id),
+            }),
             tab_info,
             active_tab: active_idx,
+            tab_rename: tab_rename.zip(self.tab_rename.as_ref().map(|r| &r.input)),
+            tab_switcher: self
+                .tab_switcher
+                .as_ref()
+                .filter(|(wid, _)| *wid == window_id)
+                .map(|(_, switcher)| switcher),
             hover_hit: hover,
             is_maximized,
+            chrome: self.chrome_theme,
             context_menu: self.context_menu.as_ref(),
+            pending_chord: chord_label.as_deref(),
+            gutter_hover: self
+                .windows
+                .get(&window_id)
+                .and_then(|tw| tw.gutter_hover)
+                .zip(self.cursor_pos.get(&window_id).map(|p| (p.x as f32, p.y as f32))),
+            command_duration_min_ms: Some(self.config.terminal.command_duration_ms)
+                .filter(|&ms| ms > 0),
+            host_border: self.host_rule_border(active_id),
             opacity: self.config.window.effective_opacity(),
             hover_hyperlink: self
                 .hover_hyperlink
-                .as_ref()
                 .filter(|(wid, _)| *wid == window_id)
-                .map(|(_, uri)| uri.as_str()),
+                .map(|(_, link)| link),
             hover_url_range: self.hover_url_range.as_deref(),
+            focus_mode: self.focus_mode,
+            block_guides: tab
+                .selection
+                .as_ref()
+                .filter(|sel| self.left_mouse_down && sel.mode == SelectionMode::Block)
+                .map(Selection::block_columns),
             minimum_contrast: self.config.colors.effective_minimum_contrast(),
             alpha_blending: self.config.colors.alpha_blending,
             dragged_tab,
             tab_offsets,
             bell_badges,
             bell_phase,
+            text_blink: self.config.terminal.text_blink,
+            blink_phase,
+            unread_labels: &self.tab_bar_cache.unread_labels,
+            tab_progress: &self.tab_bar_cache.progress,
+            tab_colors: &self.tab_bar_cache.colors,
+            tab_activity: &self.tab_bar_cache.activity,
+            tab_icons: &self.tab_bar_cache.icons,
+            tab_broadcast: &self.tab_bar_cache.broadcast,
+            tab_pinned: &self.tab_bar_cache.pinned,
+            unread_badge_color: self
+                .config
+                .tab_badge
+                .color
+                .as_deref()
+                .and_then(palette::parse_hex_color),
             scale: self.scale_factor as f32,
+            scroll_offset_px: self.smooth_scroll_offset(window_id, mode, term.active_grid()),
+            preedit: self.ime_preedit.get(&window_id),
             cursor_visible,
             grid_dirty,
             tab_bar_dirty,
@@ -295,17 +395,40 @@ impl App {
                     .values()
                     .find_map(|tw| tw.tabs.get(idx).copied());
                 if let Some(tid) = tab_id {
-                    self.close_tab(tid, event_loop);
+                    self.request_close_tab(tid, event_loop);
                 }
             }
             ContextAction::DuplicateTab(idx) => {
                 self.duplicate_tab_at(idx);
             }
+            ContextAction::RenameTab(idx) => {
+                if let Some(wid) = self.focused_window {
+                    self.start_tab_rename(wid, idx);
+                }
+            }
             ContextAction::MoveTabToNewWindow(idx) => {
                 self.move_tab_to_new_window(idx, event_loop);
             }
+            ContextAction::ToggleNotificationMute(idx) => {
+                let tab_id = self
+                    .focused_window
+                    .and_then(|wid| self.windows.get(&wid))
+                    .and_then(|tw| tw.tabs.get(idx).copied());
+                if let Some(tab) = tab_id.and_then(|tid| self.tabs.get_mut(&tid)) {
+                    tab.notifications_muted = !tab.notifications_muted;
+                }
+            }
+            ContextAction::ToggleBroadcastTab(idx) => self.toggle_broadcast_tab(idx),
+            ContextAction::TogglePinTab(idx) => {
+                if let Some(wid) = self.focused_window {
+                    self.toggle_pin_tab(wid, idx);
+                }
+            }
             ContextAction::NewTab => {
-                if let Some(&wid) = self.windows.keys().next() {
+                let wid = self
+                    .focused_window
+                    .or_else(|| self.windows.keys().next().copied());
+                if let Some(wid) = wid {
                     self.new_tab_in_window(wid);
                 }
             }
@@ -317,6 +440,33 @@ impl App {
                     self.apply_scheme_to_all_tabs(scheme);
                 }
             }
+            ContextAction::ClipboardAccess(tab_id, access) => {
+                self.resolve_clipboard_prompt(tab_id, access);
+            }
+            ContextAction::OpenUrl(url) => Self::open_url(&url),
+            ContextAction::CopyUrl(url) => {
+                clipboard::set_text(&url);
+            }
+            ContextAction::Run(action) => {
+                if let Some(wid) = self.focused_window {
+                    self.execute_action(&action, wid, event_loop);
+                }
+            }
+            ContextAction::PasteHistory(index) => {
+                if let Some(wid) = self.focused_window {
+                    self.paste_history_entry(wid, index);
+                }
+            }
+            ContextAction::ConfirmCloseTab(tab_id) => {
+                if self.tabs.contains_key(&tab_id) {
+                    self.close_tab(tab_id, event_loop);
+                }
+            }
+            ContextAction::ConfirmCloseWindow(window_id) => {
+                if self.windows.contains_key(&window_id) {
+                    self.close_window(window_id, event_loop);
+                }
+            }
         }
     }
 }
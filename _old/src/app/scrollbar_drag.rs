@@ -0,0 +1,68 @@
+//! Scrollbar mouse handling: drag the thumb to scroll, or click the track
+//! to jump the thumb there.
+
+use winit::dpi::PhysicalPosition;
+use winit::window::WindowId;
+
+use crate::scrollbar::ScrollbarLayout;
+
+use super::{App, RedrawReason};
+
+impl App {
+    /// The active tab's scrollbar in `window_id`, as the renderer lays it out.
+    fn scrollbar_layout(&self, window_id: WindowId) -> Option<ScrollbarLayout> {
+        let tw = self.windows.get(&window_id)?;
+        let tab = self.tabs.get(&tw.active_tab_id()?)?;
+        let grid = tab.grid();
+        let top = self.grid_top() as f32;
+        let bottom = top + (grid.lines * self.font_collection.cell_height) as f32;
+        let width = tw.window.inner_size().width as f32;
+        ScrollbarLayout::compute(&grid, width, (top, bottom), self.scale_factor as f32)
+    }
+
+    /// Start a thumb drag if `pos` is on the scrollbar. A press on the track
+    /// off the thumb first centers the thumb there. Returns whether the
+    /// press was taken.
+    pub(super) fn start_scrollbar_drag(
+        &mut self,
+        window_id: WindowId,
+        pos: PhysicalPosition<f64>,
+    ) -> bool {
+        let (x, y) = (pos.x as f32, pos.y as f32);
+        let Some(bar) = self.scrollbar_layout(window_id).filter(|bar| bar.contains(x, y)) else {
+            return false;
+        };
+        let grab = if bar.thumb_contains(y) {
+            y - bar.thumb_top
+        } else {
+            bar.thumb_height / 2.0
+        };
+        self.scrollbar_drag = Some((window_id, grab));
+        self.scroll_to_thumb(window_id, bar, y - grab);
+        true
+    }
+
+    /// Follow the mouse with the dragged thumb. Returns whether a drag is in
+    /// progress in `window_id`.
+    pub(super) fn update_scrollbar_drag(
+        &mut self,
+        window_id: WindowId,
+        pos: PhysicalPosition<f64>,
+    ) -> bool {
+        let Some((_, grab)) = self.scrollbar_drag.filter(|(w, _)| *w == window_id) else {
+            return false;
+        };
+        if let Some(bar) = self.scrollbar_layout(window_id) {
+            self.scroll_to_thumb(window_id, bar, pos.y as f32 - grab);
+        }
+        true
+    }
+
+    fn scroll_to_thumb(&mut self, window_id: WindowId, bar: ScrollbarLayout, thumb_top: f32) {
+        let offset = bar.offset_for_thumb_top(thumb_top);
+        if let Some(tab) = self.active_tab_id(window_id).and_then(|tid| self.tabs.get(&tid)) {
+            tab.scroll_to_offset(offset);
+        }
+        self.redraw.request(window_id, RedrawReason::INPUT);
+    }
+}
@@ -1,35 +1,44 @@
 //! Search bar — open, close, key handling, match navigation.
 
+use winit::event::Ime;
 use winit::keyboard::{Key, NamedKey};
 use winit::window::WindowId;
 
-use super::App;
+use super::{App, RedrawReason};
+use crate::search::SearchState;
 use crate::tab::TabId;
+use crate::ui::text_input::KeyOutcome;
 
 impl App {
+    /// Open the search bar, or focus it again with its query selected if
+    /// the tab's search is still open.
     pub(super) fn open_search(&mut self, window_id: WindowId) {
         let Some(tab_id) = self.active_tab_id(window_id) else {
             return;
         };
         if let Some(tab) = self.tabs.get_mut(&tab_id) {
-            tab.open_search();
+            match tab.search.as_mut() {
+                Some(search) => search.input.select_all(),
+                None => tab.open_search(),
+            }
         }
         self.search_active = Some(window_id);
-        if let Some(tw) = self.windows.get(&window_id) {
-            tw.window.request_redraw();
-        }
+        self.search_history.reset_recall();
+        self.ime_preedit.remove(&window_id);
+        self.redraw.request(window_id, RedrawReason::INPUT);
     }
 
     pub(super) fn close_search(&mut self, window_id: WindowId) {
         if let Some(tid) = self.active_tab_id(window_id) {
             if let Some(tab) = self.tabs.get_mut(&tid) {
+                if let Some(search) = &tab.search {
+                    self.search_history.push(search.query());
+                }
                 tab.close_search();
             }
         }
         self.search_active = None;
-        if let Some(tw) = self.windows.get(&window_id) {
-            tw.window.request_redraw();
-        }
+        self.redraw.request(window_id, RedrawReason::INPUT);
     }
 
     pub(super) fn handle_search_key(
@@ -47,47 +56,166 @@ impl App {
             return;
         };
 
-        let mut needs_redraw = false;
-        match &event.logical_key {
-            Key::Named(NamedKey::Enter) => {
-                if let Some(tab) = self.tabs.get_mut(&tid) {
-                    if let Some(search) = &mut tab.search {
-                        if self.modifiers.shift_key() {
-                            search.prev_match();
-                        } else {
-                            search.next_match();
-                        }
-                    }
-                }
-                self.scroll_to_search_match(tid);
-                needs_redraw = true;
-            }
-            Key::Named(NamedKey::Backspace) => {
-                if let Some(tab) = self.tabs.get_mut(&tid) {
-                    if let Some(search) = &mut tab.search {
-                        search.query.pop();
-                    }
-                }
-                self.update_search(tid);
-                needs_redraw = true;
+        if event.logical_key == Key::Named(NamedKey::Enter) {
+            if self.modifiers.shift_key() {
+                self.step_search_match(window_id, SearchState::prev_match);
+            } else {
+                self.step_search_match(window_id, SearchState::next_match);
             }
-            Key::Character(c) => {
-                if let Some(tab) = self.tabs.get_mut(&tid) {
-                    if let Some(search) = &mut tab.search {
-                        search.query.push_str(c.as_str());
-                    }
-                }
-                self.update_search(tid);
-                needs_redraw = true;
-            }
-            _ => {}
+            return;
+        }
+
+        if self.recall_search(tid, &event.logical_key) {
+            self.update_search(tid);
+            self.redraw.request(window_id, RedrawReason::INPUT);
+            return;
+        }
+
+        if self.modifiers.alt_key()
+            && matches!(&event.logical_key, Key::Character(c) if c.eq_ignore_ascii_case("f"))
+        {
+            self.toggle_search_filter(window_id, tid);
+            return;
+        }
+
+        if self.modifiers.alt_key() && self.toggle_search_option(tid, &event.logical_key) {
+            self.update_search(tid);
+            self.redraw.request(window_id, RedrawReason::INPUT);
+            return;
         }
 
-        if needs_redraw {
-            if let Some(tw) = self.windows.get(&window_id) {
-                tw.window.request_redraw();
+        let outcome = self
+            .tabs
+            .get_mut(&tid)
+            .and_then(|t| t.search.as_mut())
+            .map_or(KeyOutcome::Ignored, |search| {
+                search.input.handle_key(&event.logical_key, self.modifiers)
+            });
+        self.search_input_changed(window_id, tid, outcome);
+    }
+
+    /// Move the focus to another match with `step` and center it. Works
+    /// whether or not the search bar has focus; returns `false` when the
+    /// active tab has no search open.
+    pub(super) fn step_search_match(
+        &mut self,
+        window_id: WindowId,
+        step: fn(&mut SearchState),
+    ) -> bool {
+        let Some(tid) = self.active_tab_id(window_id) else {
+            return false;
+        };
+        let Some(search) = self.tabs.get_mut(&tid).and_then(|t| t.search.as_mut()) else {
+            return false;
+        };
+        self.search_history.push(search.query());
+        step(search);
+        self.scroll_to_search_match(tid);
+        self.redraw.request(window_id, RedrawReason::INPUT);
+        true
+    }
+
+    /// A left press on the search bar focuses it; one elsewhere in the
+    /// window leaves it open, with its matches, but sends keys to the
+    /// terminal again.
+    pub(super) fn search_bar_click(&mut self, window_id: WindowId, y: f64) {
+        let has_search = self
+            .active_tab_id(window_id)
+            .and_then(|id| self.tabs.get(&id))
+            .is_some_and(|tab| tab.search.is_some());
+        let Some(tw) = self.windows.get(&window_id) else {
+            return;
+        };
+        if !has_search {
+            return;
+        }
+        let bar_h = self.ui_collection.cell_height as f64 + 12.0 * self.scale_factor;
+        let on_bar = y >= f64::from(tw.window.inner_size().height) - bar_h;
+        let focus = on_bar.then_some(window_id);
+        if self.search_active != focus {
+            self.search_active = focus;
+            self.redraw.request(window_id, RedrawReason::INPUT);
+        }
+    }
+
+    /// Put an earlier (Up) or later (Down) query from the history in the
+    /// search bar. Returns whether `key` recalled one.
+    fn recall_search(&mut self, tab_id: TabId, key: &Key) -> bool {
+        let Some(search) = self.tabs.get_mut(&tab_id).and_then(|t| t.search.as_mut()) else {
+            return false;
+        };
+        let query = match key {
+            Key::Named(NamedKey::ArrowUp) => self.search_history.older(search.input.text()),
+            Key::Named(NamedKey::ArrowDown) => self.search_history.newer(),
+            _ => None,
+        };
+        let Some(query) = query else {
+            return false;
+        };
+        search.input.set_text(query);
+        true
+    }
+
+    /// Turn filter mode on or off. Leaving it shows the focused match in
+    /// the full buffer.
+    fn toggle_search_filter(&mut self, window_id: WindowId, tab_id: TabId) {
+        let Some(tab) = self.tabs.get_mut(&tab_id) else {
+            return;
+        };
+        let Some(search) = tab.search.as_mut() else {
+            return;
+        };
+        search.filter = !search.filter;
+        let filtering = search.filter;
+        tab.set_grid_dirty(true);
+        if !filtering {
+            self.scroll_to_search_match(tab_id);
+        }
+        self.redraw.request(window_id, RedrawReason::INPUT);
+    }
+
+    /// Flip the search option Alt+`key` toggles: W whole word, C case, and
+    /// R regex. Returns whether `key` toggles one.
+    fn toggle_search_option(&mut self, tab_id: TabId, key: &Key) -> bool {
+        let Some(search) = self.tabs.get_mut(&tab_id).and_then(|t| t.search.as_mut()) else {
+            return false;
+        };
+        let Key::Character(c) = key else {
+            return false;
+        };
+        let options = &mut search.options;
+        let flag = match c.to_lowercase().as_str() {
+            "w" => &mut options.whole_word,
+            "c" => &mut options.case_sensitive,
+            "r" => &mut options.use_regex,
+            _ => return false,
+        };
+        *flag = !*flag;
+        true
+    }
+
+    /// IME input while the search bar has focus.
+    pub(super) fn handle_search_ime(&mut self, window_id: WindowId, ime: Ime) {
+        let Some(tid) = self.active_tab_id(window_id) else {
+            return;
+        };
+        let Some(search) = self.tabs.get_mut(&tid).and_then(|t| t.search.as_mut()) else {
+            return;
+        };
+        let outcome = search.input.handle_ime(ime);
+        self.search_input_changed(window_id, tid, outcome);
+    }
+
+    fn search_input_changed(&mut self, window_id: WindowId, tab_id: TabId, outcome: KeyOutcome) {
+        match outcome {
+            KeyOutcome::Ignored => return,
+            KeyOutcome::Moved => {}
+            KeyOutcome::Edited => {
+                self.search_history.reset_recall();
+                self.update_search(tab_id);
             }
         }
+        self.redraw.request(window_id, RedrawReason::INPUT);
     }
 
     pub(super) fn update_search(&mut self, tab_id: TabId) {
@@ -110,7 +238,7 @@ impl App {
             return;
         };
 
-        // Single lock: convert stable row → absolute, then scroll if needed.
+        // Single lock: convert stable row → absolute, then scroll.
         let mut term = tab.terminal.lock();
         let grid = term.active_grid_mut();
         let Some(target_row) = stable.to_absolute(grid) else {
@@ -118,12 +246,11 @@ impl App {
         };
         let sb_len = grid.scrollback.len();
         let lines = grid.lines;
-        let viewport_start = sb_len.saturating_sub(grid.display_offset);
-        let viewport_end = viewport_start + lines;
 
-        if target_row < viewport_start || target_row >= viewport_end {
-            let center_offset = sb_len.saturating_sub(target_row).saturating_sub(lines / 2);
-            grid.display_offset = center_offset.min(sb_len);
+        // Center the match, as far as the scrollback allows.
+        let center_offset = (sb_len + lines / 2).saturating_sub(target_row).min(sb_len);
+        if grid.display_offset != center_offset {
+            grid.display_offset = center_offset;
             drop(term);
             tab.set_grid_dirty(true);
         }
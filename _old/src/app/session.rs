@@ -0,0 +1,75 @@
+//! Session restore — save the open tabs on exit and reopen them on the
+//! next launch (`behavior.restore_session`).
+
+use winit::window::WindowId;
+
+use crate::config;
+use crate::log;
+use crate::session::{Session, SessionTab};
+
+use super::App;
+
+impl App {
+    /// Fill the first window: with the saved session's tabs when
+    /// `behavior.restore_session` is on and one was saved, else with one
+    /// new tab.
+    pub(super) fn open_first_tabs(&mut self, window_id: WindowId) {
+        let session = if self.config.behavior.restore_session {
+            Session::load(&config::session_path())
+        } else {
+            None
+        };
+        let mut opened = Vec::new();
+        if let Some(session) = &session {
+            for saved in &session.tabs {
+                let Some(id) = self.spawn_tab(window_id, saved.cwd.as_deref(), saved.history_text())
+                else {
+                    continue;
+                };
+                if let Some(tab) = self.tabs.get_mut(&id) {
+                    tab.custom_title.clone_from(&saved.name);
+                    tab.pinned = saved.pinned;
+                }
+                opened.push(id);
+            }
+        }
+        let Some(session) = session.filter(|_| !opened.is_empty()) else {
+            self.new_tab_in_window(window_id);
+            return;
+        };
+        log(&format!("session: restored {} tab(s)", opened.len()));
+        if let Some(tw) = self.windows.get_mut(&window_id) {
+            tw.active_tab = session.active.min(tw.tabs.len().saturating_sub(1));
+        }
+        // Tabs saved from several windows can leave pinned ones behind
+        // unpinned ones.
+        self.keep_pinned_first(window_id);
+    }
+
+    /// Save the open tabs, the focused window's first, for the next launch
+    /// when `behavior.restore_session` is on.
+    pub(super) fn save_session(&self) {
+        if !self.config.behavior.restore_session {
+            return;
+        }
+        let mut windows: Vec<_> = self.windows.iter().collect();
+        windows.sort_by_key(|&(&wid, _)| Some(wid) != self.focused_window);
+        let lines = self.config.behavior.session_scrollback_lines;
+        let mut session = Session::default();
+        for (_, tw) in windows {
+            // The first window's active tab is the one restored active.
+            if session.tabs.is_empty() {
+                session.active = tw.active_tab;
+            }
+            for tab in tw.tabs.iter().filter_map(|id| self.tabs.get(id)) {
+                let history = tab.terminal.lock().history_text(lines);
+                session.tabs.push(SessionTab {
+                    name: tab.custom_title.clone(),
+                    pinned: tab.pinned,
+                    ..SessionTab::new(tab.cwd(), &history)
+                });
+            }
+        }
+        session.save(&config::session_path());
+    }
+}
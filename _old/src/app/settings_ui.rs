@@ -1,22 +1,26 @@
-//! Settings window — theme picker lifecycle and rendering.
+//! Settings window — lifecycle, filter box, and rendering.
+//!
+//! Typing always goes to the filter box, which narrows the current page to
+//! the options whose label contains the text.
 
+use winit::event::{Ime, KeyEvent};
 use winit::event_loop::ActiveEventLoop;
+use winit::keyboard::{Key, NamedKey};
 use winit::window::WindowId;
 
-use super::App;
+use super::window_class::{WindowRole, with_window_class};
+use super::{App, LaunchMode, RedrawReason};
+use crate::gpu::SettingsContent;
 use crate::log;
-use crate::palette::{BUILTIN_SCHEMES, ColorScheme};
+use crate::palette::ColorScheme;
+use crate::palette::contrast::WCAG_AA;
+use crate::ui::settings_layout::{SettingsHit, SettingsLayout, SettingsPage};
+use crate::ui::text_input::KeyOutcome;
 use crate::window::TermWindow;
 
 /// Settings window close button size (pixels, top-right corner).
 const SETTINGS_CLOSE_SIZE: usize = 30;
 
-/// Settings window title area height (pixels).
-const SETTINGS_TITLE_HEIGHT: usize = 50;
-
-/// Settings window row height for scheme list items (pixels).
-const SETTINGS_ROW_HEIGHT: usize = 40;
-
 impl App {
     pub(super) fn is_settings_window(&self, window_id: WindowId) -> bool {
         self.settings_window == Some(window_id)
@@ -34,13 +38,14 @@ impl App {
         }
 
         let win_w: u32 = 300;
-        let win_h: u32 = 350;
+        let win_h: u32 = 450;
 
         let attrs = winit::window::Window::default_attributes()
             .with_title("Settings")
             .with_inner_size(winit::dpi::PhysicalSize::new(win_w, win_h))
             .with_decorations(false)
             .with_resizable(false);
+        let attrs = with_window_class(attrs, &self.window_class, WindowRole::Settings);
 
         let window = match event_loop.create_window(attrs) {
             Ok(w) => std::sync::Arc::new(w),
@@ -74,8 +79,10 @@ impl App {
             renderer.clear_surface(gpu, &tw.surface, bg, 1.0);
         }
 
+        window.set_ime_allowed(true);
         window.set_visible(true);
         self.windows.insert(id, tw);
+        self.update_frame_interval(id);
         self.settings_window = Some(id);
         log(&format!("created settings window {id:?}"));
     }
@@ -84,6 +91,8 @@ impl App {
         if let Some(wid) = self.settings_window.take() {
             self.windows.remove(&wid);
         }
+        self.settings_filter.clear();
+        self.relayout_settings(self.settings_layout.page);
     }
 
     pub(super) fn render_settings_window(&mut self, window_id: WindowId) {
@@ -112,13 +121,19 @@ impl App {
         let term_guard = self.tabs.values().next().map(|t| t.terminal.lock());
         let palette = term_guard.as_ref().map(|g| &g.palette);
 
+        let content = SettingsContent {
+            layout: &self.settings_layout,
+            filter: &self.settings_filter,
+            active_scheme: self.active_scheme,
+            minimum_contrast: self.config.colors.effective_minimum_contrast(),
+        };
         renderer.draw_settings_frame(
             gpu,
             &tw.surface,
             &tw.surface_config,
             phys.width,
             phys.height,
-            self.active_scheme,
+            &content,
             palette,
             &mut self.ui_collection,
         );
@@ -134,15 +149,86 @@ impl App {
             return;
         }
 
-        if y < SETTINGS_TITLE_HEIGHT {
-            return;
+        match self.settings_layout.hit(x as f32, y as f32) {
+            Some(SettingsHit::Page(page)) if page != self.settings_layout.page => {
+                self.relayout_settings(page);
+                self.redraw.request(window_id, RedrawReason::INPUT);
+            }
+            Some(SettingsHit::Scheme(scheme)) => self.apply_scheme_to_all_tabs(scheme),
+            Some(SettingsHit::ContrastToggle) => self.toggle_minimum_contrast(),
+            Some(SettingsHit::Page(_)) | None => {}
+        }
+    }
+
+    /// A key in the settings window. Escape clears the filter, or closes
+    /// the window when it is already empty; Enter applies the first
+    /// matching scheme; everything else edits the filter.
+    pub(super) fn handle_settings_key(&mut self, window_id: WindowId, event: &KeyEvent) {
+        let outcome = match &event.logical_key {
+            Key::Named(NamedKey::Escape) if self.settings_filter.is_empty() => {
+                self.close_settings_window();
+                return;
+            }
+            Key::Named(NamedKey::Escape) => {
+                self.settings_filter.clear();
+                KeyOutcome::Edited
+            }
+            Key::Named(NamedKey::Enter) => {
+                if let Some(&scheme) = self.settings_layout.schemes.first() {
+                    self.apply_scheme_to_all_tabs(scheme);
+                }
+                return;
+            }
+            key => self.settings_filter.handle_key(key, self.modifiers),
+        };
+        self.settings_filter_changed(window_id, outcome);
+    }
+
+    /// IME input for the settings filter box.
+    pub(super) fn handle_settings_ime(&mut self, window_id: WindowId, ime: Ime) {
+        let outcome = match ime {
+            Ime::Preedit(text, cursor) => {
+                self.settings_filter.set_preedit(&text, cursor);
+                KeyOutcome::Moved
+            }
+            Ime::Commit(text) => {
+                self.settings_filter.commit(&text);
+                KeyOutcome::Edited
+            }
+            Ime::Enabled | Ime::Disabled => {
+                self.settings_filter.set_preedit("", None);
+                KeyOutcome::Moved
+            }
+        };
+        self.settings_filter_changed(window_id, outcome);
+    }
+
+    fn settings_filter_changed(&mut self, window_id: WindowId, outcome: KeyOutcome) {
+        match outcome {
+            KeyOutcome::Ignored => return,
+            KeyOutcome::Moved => {}
+            KeyOutcome::Edited => self.relayout_settings(self.settings_layout.page),
         }
+        self.redraw.request(window_id, RedrawReason::INPUT);
+    }
 
-        let row_idx = (y - SETTINGS_TITLE_HEIGHT) / SETTINGS_ROW_HEIGHT;
-        if row_idx < BUILTIN_SCHEMES.len() {
-            let scheme = BUILTIN_SCHEMES[row_idx];
-            self.apply_scheme_to_all_tabs(scheme);
+    /// Recompute which options `page` shows for the current filter.
+    fn relayout_settings(&mut self, page: SettingsPage) {
+        self.settings_layout = SettingsLayout::compute(page, self.settings_filter.text());
+    }
+
+    /// Switch `colors.minimum_contrast` between off and WCAG AA (4.5:1),
+    /// so the renderer lifts low-contrast text to a readable ratio. The
+    /// value switched away from comes back when the toggle flips back.
+    fn toggle_minimum_contrast(&mut self) {
+        let current = self.config.colors.minimum_contrast;
+        self.config.colors.minimum_contrast =
+            toggled_contrast(current, self.contrast_before_toggle);
+        self.contrast_before_toggle = Some(current);
+        if self.launch_mode == LaunchMode::Normal {
+            self.config.save();
         }
+        self.redraw.request_all(self.windows.keys().copied(), RedrawReason::CONFIG);
     }
 
     pub(super) fn apply_scheme_to_all_tabs(&mut self, scheme: &'static ColorScheme) {
@@ -154,12 +240,23 @@ impl App {
                 self.config.behavior.bold_is_bright,
             );
         }
-        // Persist the scheme change
+        self.reapply_host_rules();
+        // Persist the scheme change (safe mode must not overwrite the user's file)
         scheme.name.clone_into(&mut self.config.colors.scheme);
-        self.config.save();
-        // Redraw all windows
-        for tw in self.windows.values() {
-            tw.window.request_redraw();
+        if self.launch_mode == LaunchMode::Normal {
+            self.config.save();
         }
+        // Redraw all windows
+        self.redraw.request_all(self.windows.keys().copied(), RedrawReason::CONFIG);
+    }
+}
+
+/// The minimum contrast after flipping the toggle from `current`: `saved`
+/// if it lies on the other side of WCAG AA, otherwise off or AA itself.
+pub(super) fn toggled_contrast(current: f32, saved: Option<f32>) -> f32 {
+    if current.clamp(1.0, 21.0) >= WCAG_AA {
+        saved.filter(|v| *v < WCAG_AA).unwrap_or(1.0)
+    } else {
+        saved.filter(|v| *v >= WCAG_AA).unwrap_or(WCAG_AA)
     }
 }
@@ -0,0 +1,82 @@
+//! Startup loading: the config and the fonts, each logged with how long
+//! it took.
+
+use std::time::Instant;
+
+use crate::config::Config;
+use crate::font::FontCollection;
+use crate::log;
+
+use super::{LaunchMode, UI_FONT_SCALE};
+
+/// Load the config, or the defaults in safe mode, and log its main
+/// settings.
+pub(super) fn load_config(mode: LaunchMode) -> Config {
+    let t0 = Instant::now();
+    let config = match mode {
+        LaunchMode::Normal => Config::load(),
+        LaunchMode::Safe => {
+            log("safe mode: ignoring config file");
+            Config::safe_mode()
+        }
+    };
+    log(&format!(
+        "config: font_size={}, scheme={}, shell={:?}, scrollback={}, cols={}, rows={}, \
+         opacity={}, tab_bar_opacity={}, blur={}, cursor={}, cursor_blink={}, \
+         cursor_blink_interval_ms={}, copy_on_select={}, bold_is_bright={} \
+         ({:.1}ms)",
+        config.font.size,
+        config.colors.scheme,
+        config.terminal.shell,
+        config.terminal.scrollback,
+        config.window.columns,
+        config.window.rows,
+        config.window.effective_opacity(),
+        config.window.effective_tab_bar_opacity(),
+        config.window.blur,
+        config.terminal.cursor_style,
+        config.terminal.cursor_blink,
+        config.terminal.cursor_blink_interval_ms,
+        config.behavior.copy_on_select,
+        config.behavior.bold_is_bright,
+        t0.elapsed().as_secs_f64() * 1000.0,
+    ));
+    config
+}
+
+/// Load the terminal font and the smaller UI font for the tab bar.
+pub(super) fn load_fonts(config: &Config) -> (FontCollection, FontCollection) {
+    let t0 = Instant::now();
+    let font_collection = FontCollection::load(
+        config.font.size,
+        config.font.family.as_deref(),
+        &FontCollection::parse_features(&config.font.features),
+        &config.font.fallback,
+        config.font.effective_weight(),
+    );
+    let ui_size = font_collection.size * UI_FONT_SCALE;
+    let ui_family = config.font.tab_bar_font_family.as_deref()
+        .or(config.font.family.as_deref());
+    let ui_collection = FontCollection::load(
+        ui_size,
+        ui_family,
+        &[],
+        &[],
+        config.font.effective_tab_bar_weight(),
+    );
+    log(&format!(
+        "font loaded: cell={}x{}, baseline={}, size={}, weight={}, wght_axis={} \
+         (ui: size={}, weight={}, wght_axis={}) ({:.1}ms)",
+        font_collection.cell_width,
+        font_collection.cell_height,
+        font_collection.baseline,
+        font_collection.size,
+        config.font.effective_weight(),
+        font_collection.has_wght_axis(),
+        ui_collection.size,
+        config.font.effective_tab_bar_weight(),
+        ui_collection.has_wght_axis(),
+        t0.elapsed().as_secs_f64() * 1000.0,
+    ));
+    (font_collection, ui_collection)
+}
@@ -0,0 +1,47 @@
+//! Periodic event loop and per-tab statistics for the log.
+
+use std::time::Instant;
+
+use crate::log;
+
+use super::App;
+
+impl App {
+    /// Log event rates since the last call, then each tab's PTY stats and
+    /// ignored sequences, and reset the counters.
+    pub(super) fn log_stats(&mut self) {
+        let secs = self.stats_log_time.elapsed().as_secs_f64();
+        let avg_ms = if self.render_count > 0 {
+            self.render_total_ms / f64::from(self.render_count)
+        } else {
+            0.0
+        };
+        log(&format!(
+            "stats: renders={}/s (avg {avg_ms:.1}ms, total {:.0}ms), \
+             pty_wakeups={}/s, \
+             win_events={}/s, cursor_moved={}/s, about_to_wait={}/s, \
+             redraw reasons: [{}]",
+            (f64::from(self.render_count) / secs) as u32,
+            self.render_total_ms,
+            (f64::from(self.pty_event_count) / secs) as u32,
+            (f64::from(self.window_event_count) / secs) as u32,
+            (f64::from(self.cursor_moved_count) / secs) as u32,
+            (f64::from(self.about_to_wait_count) / secs) as u32,
+            self.redraw.take_stats(),
+        ));
+        for (id, tab) in &self.tabs {
+            log(&format!("stats: tab {}: {}", id.0, tab.pty_stats()));
+            let unknown = tab.unknown_sequences();
+            if !unknown.is_empty() {
+                log(&format!("stats: tab {}: ignored sequences: {unknown}", id.0));
+            }
+        }
+        self.render_count = 0;
+        self.render_total_ms = 0.0;
+        self.pty_event_count = 0;
+        self.window_event_count = 0;
+        self.cursor_moved_count = 0;
+        self.about_to_wait_count = 0;
+        self.stats_log_time = Instant::now();
+    }
+}
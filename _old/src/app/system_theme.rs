@@ -0,0 +1,41 @@
+//! Following the OS light/dark preference and accent color in the chrome.
+
+use vte::ansi::Rgb;
+use winit::window::Theme;
+
+use crate::tab_bar::ChromeTheme;
+
+use super::{App, RedrawReason};
+
+impl App {
+    /// Re-read the OS appearance (`theme` when winit reported it) and
+    /// restyle every window.
+    pub(super) fn refresh_chrome_theme(&mut self, theme: Option<Theme>) {
+        let theme = theme
+            .or_else(|| self.windows.values().find_map(|tw| tw.window.theme()))
+            .unwrap_or(self.chrome_theme.theme);
+        #[cfg(target_os = "windows")]
+        for tw in self.windows.values() {
+            crate::platform_windows::set_dark_mode(&tw.window, theme);
+        }
+        let chrome = ChromeTheme {
+            theme,
+            accent: system_accent(),
+        };
+        if chrome != self.chrome_theme {
+            self.chrome_theme = chrome;
+            self.tab_bar_dirty = true;
+            self.redraw
+                .request_all(self.windows.keys().copied(), RedrawReason::CONFIG);
+        }
+    }
+}
+
+/// The OS accent color, where the platform exposes one.
+fn system_accent() -> Option<Rgb> {
+    #[cfg(target_os = "windows")]
+    let accent = crate::platform_windows::accent_color().map(|(r, g, b)| Rgb { r, g, b });
+    #[cfg(not(target_os = "windows"))]
+    let accent = None;
+    accent
+}
@@ -0,0 +1,116 @@
+//! Per-tab data the tab bar draws, cached until `tab_bar_dirty` is set.
+
+use vte::ansi::Rgb;
+use winit::window::WindowId;
+
+use super::App;
+use crate::icons::Icon;
+use crate::tab::{Tab, TabId, TaskProgress};
+
+/// Tab bar data for the window being drawn, one entry per tab in order.
+#[derive(Default)]
+pub(crate) struct TabBarCache {
+    pub(super) info: Vec<(TabId, String)>,
+    pub(super) bell_badges: Vec<bool>,
+    /// Unread output badge text (empty = no badge).
+    pub(super) unread_labels: Vec<String>,
+    /// OSC 9;4 progress.
+    pub(super) progress: Vec<TaskProgress>,
+    /// Accent color (OSC 6 or `[[host_rule]]` `tab_color`).
+    pub(super) colors: Vec<Option<Rgb>>,
+    /// Activity dot (background output since last viewed).
+    pub(super) activity: Vec<bool>,
+    /// Icon: busy while a command runs, else the shell icon when
+    /// `tab_badge.icons` is on or the tab is pinned.
+    pub(super) icons: Vec<Option<Icon>>,
+    /// Whether the tab receives the window's broadcast input.
+    pub(super) broadcast: Vec<bool>,
+    /// Whether the tab is pinned and drawn compact.
+    pub(super) pinned: Vec<bool>,
+}
+
+impl App {
+    /// Rebuild cached tab bar data (titles, bell and unread badges,
+    /// progress, accent colors, activity dots, icons, broadcast, pins)
+    /// for the given window.
+    pub(super) fn rebuild_tab_bar_cache(
+        &mut self,
+        window_id: WindowId,
+        active_tab_id: Option<TabId>,
+    ) {
+        // Viewing a tab clears its unread count.
+        if let Some(tab) = active_tab_id.and_then(|id| self.tabs.get_mut(&id)) {
+            tab.mark_output_seen();
+        }
+        let tab_ids = self
+            .windows
+            .get(&window_id)
+            .map_or_else(Vec::new, |tw| tw.tabs.clone());
+        let background = |id: &TabId| Some(*id) != active_tab_id;
+        let cache = &mut self.tab_bar_cache;
+        cache.info = tab_ids
+            .iter()
+            .map(|id| {
+                let title = self
+                    .tabs
+                    .get(id)
+                    .map_or_else(|| "?".to_string(), Tab::effective_title);
+                (*id, title)
+            })
+            .collect();
+        cache.bell_badges = tab_ids
+            .iter()
+            .map(|id| {
+                background(id)
+                    && self
+                        .tabs
+                        .get(id)
+                        .is_some_and(|t| t.unseen.count() > 0)
+            })
+            .collect();
+        cache.unread_labels = tab_ids
+            .iter()
+            .map(|id| {
+                self.tabs
+                    .get(id)
+                    .filter(|_| background(id))
+                    .and_then(|t| self.config.tab_badge.unread_label(t.unread_lines()))
+                    .unwrap_or_default()
+            })
+            .collect();
+        cache.progress = tab_ids
+            .iter()
+            .map(|id| self.tabs.get(id).map_or(TaskProgress::Hidden, Tab::shown_progress))
+            .collect();
+        cache.activity = tab_ids
+            .iter()
+            .map(|id| background(id) && self.tabs.get(id).is_some_and(|t| t.has_activity))
+            .collect();
+        self.tab_bar_cache.icons = self.tab_icons(&tab_ids);
+        self.tab_bar_cache.colors = tab_ids.iter().map(|&id| self.tab_accent(id)).collect();
+        let receivers = self.broadcast_receivers(window_id);
+        self.tab_bar_cache.broadcast = tab_ids.iter().map(|id| receivers.contains(id)).collect();
+        self.tab_bar_cache.pinned = tab_ids
+            .iter()
+            .map(|id| self.tabs.get(id).is_some_and(|t| t.pinned))
+            .collect();
+    }
+
+    /// Icon before each tab's title. A running command's busy icon
+    /// replaces the shell icon. Pinned tabs, which show nothing else,
+    /// always have one.
+    fn tab_icons(&self, tab_ids: &[TabId]) -> Vec<Option<Icon>> {
+        let icons = self.config.tab_badge.icons;
+        tab_ids
+            .iter()
+            .map(|id| {
+                let tab = self.tabs.get(id)?;
+                if tab.is_busy() {
+                    Some(Icon::Busy)
+                } else {
+                    (icons || tab.pinned).then(|| tab.icon())
+                }
+            })
+            .collect()
+    }
+}
@@ -11,7 +11,7 @@ use crate::tab_bar::{
     CONTROLS_ZONE_WIDTH, DROPDOWN_BUTTON_WIDTH, NEW_TAB_BUTTON_WIDTH, TAB_BAR_HEIGHT,
     TAB_LEFT_MARGIN, TabBarLayout,
 };
-use super::App;
+use super::{App, RedrawReason};
 
 impl App {
     /// Maximum drag X for a tab within a window's tab bar.
@@ -47,7 +47,7 @@ impl App {
             tw.remove_tab(tab_id);
             self.tab_bar_dirty = true;
             self.drag_visual_x = None;
-            tw.window.request_redraw();
+            self.redraw.request(source_wid, RedrawReason::TAB_BAR);
         }
 
         // The grab offset: where the cursor should appear within the new
@@ -200,47 +200,32 @@ impl App {
         }
         if let Some(tw) = self.windows.get_mut(&to_wid) {
             tw.insert_tab_at(tab_id, idx);
-            tw.window.request_redraw();
+            self.redraw.request(to_wid, RedrawReason::TAB_BAR);
         }
+        self.keep_pinned_first(to_wid);
     }
 
-    /// Decay tab animation offsets (time-based exponential decay).
-    /// Returns `true` if any animation is still active and needs further ticks.
-    pub(super) fn decay_tab_animations(&mut self) -> bool {
-        if self.tab_anim_offsets.is_empty() {
-            return false;
-        }
-        let now = Instant::now();
-        let dt = now.duration_since(self.last_anim_time).as_secs_f32();
-        self.last_anim_time = now;
-        let decay = (-dt * 15.0_f32).exp(); // ~67ms time constant
-
-        let mut any_active = false;
-        self.tab_anim_offsets.retain(|_wid, offsets| {
-            let mut all_zero = true;
-            for offset in offsets.iter_mut() {
-                *offset *= decay;
-                if offset.abs() < 0.5 {
-                    *offset = 0.0;
-                } else {
-                    all_zero = false;
-                }
-            }
-            if !all_zero {
-                any_active = true;
-            }
-            !all_zero
-        });
-
-        // Offsets changed — mark tab bar dirty so renderer rebuilds with new positions
-        if any_active {
-            self.tab_bar_dirty = true;
-            for tw in self.windows.values() {
-                tw.window.request_redraw();
-            }
-        }
-
-        any_active
+    /// Ease the tab just dropped in `window_id` from where it was drawn
+    /// during the drag into its slot, ending the drag's visual position.
+    pub(super) fn settle_dropped_tab(&mut self, window_id: WindowId, tab_id: TabId) {
+        let Some((wid, dragged_x)) = self.drag_visual_x.take() else {
+            return;
+        };
+        let Some(tw) = self.windows.get(&window_id).filter(|_| wid == window_id) else {
+            return;
+        };
+        let Some(idx) = tw.tab_index(tab_id) else {
+            return;
+        };
+        let count = tw.tabs.len();
+        let width = tw.window.inner_size().width as usize;
+        let pinned = self.pinned_count(window_id);
+        let layout = TabBarLayout::compute(count, pinned, width, self.scale_factor, None);
+        let slot_x = (self.scale_px(TAB_LEFT_MARGIN) + layout.tab_offset(idx)) as f32;
+        let offset = dragged_x - slot_x;
+        self.animations
+            .settle_tab(window_id, (idx, offset), count, Instant::now());
+        self.redraw.request(window_id, RedrawReason::ANIMATION);
     }
 
     pub(super) fn update_drag_in_bar(
@@ -252,33 +237,43 @@ impl App {
     ) {
         let sf = self.scale_factor;
         let left_margin = self.scale_px(TAB_LEFT_MARGIN) as f64;
-        let (tab_count, tab_w) = match self.windows.get(&window_id) {
+        let pinned = self.pinned_count(window_id);
+        let (layout, current) = match self.windows.get(&window_id) {
             Some(tw) => {
                 let layout = TabBarLayout::compute(
                     tw.tabs.len(),
+                    pinned,
                     tw.window.inner_size().width as usize,
                     sf,
                     None, // no lock during drag
                 );
-                (tw.tabs.len(), layout.tab_width)
+                (layout, tw.tab_index(tab_id).unwrap_or(0))
             }
             None => return,
         };
-
+        let tab_count = layout.tab_count;
+        let tab_w = layout.width_of(current);
         let tab_wf = tab_w as f64;
 
         // 1. Compute dragged tab visual X (pixel-perfect cursor tracking)
         let max_x = self.drag_max_x(window_id, tab_wf as f32);
         let dragged_x = ((position.x - mouse_offset_in_tab) as f32).clamp(0.0, max_x);
 
-        // 2. Compute insertion index from cursor center
-        let cursor_center = dragged_x as f64 + tab_wf / 2.0;
-        let new_idx =
-            ((cursor_center - left_margin) / tab_wf).clamp(0.0, (tab_count - 1) as f64) as usize;
+        // 2. Compute insertion index from cursor center, keeping pinned
+        // and unpinned tabs on their own sides
+        let cursor_center = (dragged_x as f64 + tab_wf / 2.0 - left_margin).max(0.0);
+        let slots = if current < pinned {
+            0..pinned
+        } else {
+            pinned..tab_count
+        };
+        let new_idx = layout
+            .tab_at(cursor_center as usize)
+            .unwrap_or(tab_count)
+            .clamp(slots.start, slots.end.saturating_sub(1).max(slots.start));
 
-        // 3. If index changed, swap tab in the model.
-        // Chrome-style: displaced tabs snap to new positions immediately during
-        // drag (no dodge animation). Animation only applies on drag-end.
+        // 3. If index changed, swap tab in the model. Displaced tabs slide
+        // from their old slots to their new ones.
         let mut swapped = false;
         if let Some(tw) = self.windows.get_mut(&window_id) {
             if let Some(current_idx) = tw.tab_index(tab_id) {
@@ -286,7 +281,13 @@ impl App {
                     tw.tabs.remove(current_idx);
                     tw.tabs.insert(new_idx, tab_id);
                     tw.active_tab = new_idx;
-                    self.tab_anim_offsets.remove(&window_id);
+                    self.animations.dodge_tabs(
+                        window_id,
+                        (current_idx, new_idx),
+                        tab_w as f32,
+                        tab_count,
+                        Instant::now(),
+                    );
                     swapped = true;
                 }
             }
@@ -301,9 +302,7 @@ impl App {
         if swapped {
             self.tab_bar_dirty = true;
         }
-        if let Some(tw) = self.windows.get(&window_id) {
-            tw.window.request_redraw();
-        }
+        self.redraw.request(window_id, RedrawReason::INPUT);
     }
 
     pub(super) fn window_containing_tab(&self, tab_id: TabId) -> Option<WindowId> {
@@ -329,14 +328,15 @@ impl App {
         let local_x = (screen_x - target_x) as usize;
         let layout = TabBarLayout::compute(
             tw.tabs.len(),
+            self.pinned_count(target_wid),
             tw.window.inner_size().width as usize,
             self.scale_factor,
             None,
         );
         let left_margin = self.scale_px(TAB_LEFT_MARGIN);
         let tab_x = local_x.saturating_sub(left_margin);
-        let raw = (tab_x + layout.tab_width / 2) / layout.tab_width.max(1);
-        raw.min(tw.tabs.len())
+        let raw = layout.tab_at(tab_x + layout.tab_width / 2).unwrap_or(tw.tabs.len());
+        raw.clamp(layout.pinned_count, tw.tabs.len())
     }
 
     /// Find a merge target using cursor screen coordinates (Chrome pattern).
@@ -470,7 +470,7 @@ impl App {
             // set_visible may not know the window was hidden).
             if let Some(tw) = self.windows.get(&torn_wid) {
                 crate::platform_windows::show_window(&tw.window);
-                tw.window.request_redraw();
+                self.redraw.request(torn_wid, RedrawReason::EXPOSE);
             }
             log("merge-check: no target window found, keeping torn-off");
         }
@@ -525,6 +525,7 @@ impl App {
         // until the next cursor_moved event.
         let layout = TabBarLayout::compute(
             tw.tabs.len(),
+            self.pinned_count(target_wid),
             tw.window.inner_size().width as usize,
             self.scale_factor,
             None,
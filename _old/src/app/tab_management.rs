@@ -1,5 +1,7 @@
 //! Tab lifecycle — creation, close, duplicate, move to new window.
 
+use std::sync::Arc;
+
 use winit::event_loop::ActiveEventLoop;
 use winit::window::WindowId;
 
@@ -8,16 +10,19 @@ use crate::log;
 use crate::palette;
 use crate::tab::{SpawnConfig, Tab, TabId};
 
-use super::App;
+use super::{App, RedrawReason};
 
 impl App {
+    /// Open a new tab in `window_id`, starting in the active tab's
+    /// directory when `behavior.inherit_cwd` is on.
     pub(super) fn new_tab_in_window(&mut self, window_id: WindowId) -> Option<TabId> {
         let inherit_cwd: Option<String> = self
             .active_tab_id(window_id)
+            .filter(|_| self.config.behavior.inherit_cwd)
             .and_then(|tid| self.tabs.get(&tid))
             .and_then(Tab::cwd);
 
-        let tab_id = self.spawn_tab(window_id, inherit_cwd.as_deref())?;
+        let tab_id = self.spawn_tab(window_id, inherit_cwd.as_deref(), None)?;
 
         // Clear width lock — adding a tab changes the count
         if self.tab_width_lock.is_some_and(|(wid, _)| wid == window_id) {
@@ -28,7 +33,21 @@ impl App {
         Some(tab_id)
     }
 
-    pub(super) fn close_tab(&mut self, tab_id: TabId, _event_loop: &ActiveEventLoop) {
+    /// Give focus to the active tab of the focused window and take it from
+    /// every other tab. Focus-out reports go out before the focus-in.
+    pub(super) fn sync_tab_focus(&mut self) {
+        let focused_tab = self.focused_window.and_then(|wid| self.active_tab_id(wid));
+        for (&tid, tab) in &mut self.tabs {
+            if Some(tid) != focused_tab {
+                tab.set_focused(false);
+            }
+        }
+        if let Some(tab) = focused_tab.and_then(|tid| self.tabs.get_mut(&tid)) {
+            tab.set_focused(true);
+        }
+    }
+
+    pub(super) fn close_tab(&mut self, tab_id: TabId, event_loop: &ActiveEventLoop) {
         // Remove the tab from its window first
         self.tab_bar_dirty = true;
         let mut empty_windows = Vec::new();
@@ -36,26 +55,27 @@ impl App {
             if tw.remove_tab(tab_id) {
                 empty_windows.push(*wid);
             } else {
-                tw.window.request_redraw();
+                self.redraw.request(*wid, RedrawReason::TAB_BAR);
             }
         }
 
-        // If this leaves a window with no tabs AND it's the last one, force-exit
-        // BEFORE dropping the Tab (ClosePseudoConsole would block).
+        // If this leaves a window with no tabs AND it's the last one, exit
+        // (which closes this tab along with the rest).
         for wid in &empty_windows {
             if self.windows.len() <= 1 {
-                self.exit_app();
+                self.exit_app(event_loop);
             }
             self.windows.remove(wid);
         }
 
-        // Shutdown and drop on a background thread. On Windows,
+        // Shutdown and close on a background thread. On Windows,
         // `ClosePseudoConsole` (called from `pty_master` drop) blocks until
         // the ConPTY reader thread exits. If child processes (htop, vim, etc.)
         // are still alive, this can take seconds — must not freeze the event loop.
         if let Some(mut tab) = self.tabs.remove(&tab_id) {
+            self.unseen.forget(tab.unseen);
             tab.shutdown();
-            std::thread::spawn(move || drop(tab));
+            std::thread::spawn(move || tab.close());
         }
     }
 
@@ -69,11 +89,17 @@ impl App {
             return;
         };
         let cwd = self.tabs.get(&source_tab_id).and_then(Tab::cwd);
-        self.spawn_tab(window_id, cwd.as_deref());
+        self.spawn_tab(window_id, cwd.as_deref(), None);
     }
 
-    /// Spawn a new tab in the given window with an optional working directory.
-    fn spawn_tab(&mut self, window_id: WindowId, cwd: Option<&str>) -> Option<TabId> {
+    /// Spawn a new tab in the given window with an optional working
+    /// directory and scrollback restored from the last session.
+    pub(super) fn spawn_tab(
+        &mut self,
+        window_id: WindowId,
+        cwd: Option<&str>,
+        history: Option<String>,
+    ) -> Option<TabId> {
         let default_cols = self.config.window.columns;
         let default_rows = self.config.window.rows;
         let (cols, rows) =
@@ -90,12 +116,16 @@ impl App {
             id: tab_id,
             cols,
             rows,
-            proxy: self.proxy.clone(),
+            events: Arc::new(self.proxy.clone()),
             shell: self.config.terminal.shell.clone(),
             max_scrollback: self.config.terminal.scrollback,
             cursor_shape,
             integration_dir: self.shell_integration_dir.clone(),
             cwd: cwd.map(String::from),
+            alt_screen_clip: self.config.terminal.alt_screen_clip,
+            answerback: self.config.terminal.answerback.clone(),
+            c1_controls: self.config.terminal.c1_controls,
+            history,
         }) {
             Ok(t) => t,
             Err(e) => {
@@ -111,7 +141,7 @@ impl App {
         self.tab_bar_dirty = true;
         if let Some(tw) = self.windows.get_mut(&window_id) {
             tw.add_tab(tab_id);
-            tw.window.request_redraw();
+            self.redraw.request(window_id, RedrawReason::TAB_BAR);
         }
 
         Some(tab_id)
@@ -144,7 +174,7 @@ impl App {
         if let Some(tw) = self.windows.get_mut(&src_wid) {
             tw.remove_tab(tab_id);
             self.tab_bar_dirty = true;
-            tw.window.request_redraw();
+            self.redraw.request(src_wid, RedrawReason::TAB_BAR);
         }
 
         // Create a new window and add the tab
@@ -152,7 +182,7 @@ impl App {
             if let Some(tw) = self.windows.get_mut(&new_wid) {
                 tw.add_tab(tab_id);
                 self.tab_bar_dirty = true;
-                tw.window.request_redraw();
+                self.redraw.request(new_wid, RedrawReason::TAB_BAR);
             }
         }
     }
@@ -0,0 +1,53 @@
+//! Pinned tabs: drawn compact (icon only) at the left of the tab bar.
+//!
+//! Pinned tabs always come before the others in their window. Pinning a
+//! tab moves it to the end of the pinned ones and unpinning moves it to
+//! the start of the rest; drags and keyboard moves stay on their side.
+//! Closing a pinned tab asks first, and the pin is saved with the session.
+
+use winit::window::WindowId;
+
+use super::{App, RedrawReason};
+
+impl App {
+    /// Pin tab `idx` of `window_id`, or unpin it.
+    pub(super) fn toggle_pin_tab(&mut self, window_id: WindowId, idx: usize) {
+        let tab_id = self
+            .windows
+            .get(&window_id)
+            .and_then(|tw| tw.tabs.get(idx).copied());
+        let Some(tab) = tab_id.and_then(|tid| self.tabs.get_mut(&tid)) else {
+            return;
+        };
+        tab.pinned = !tab.pinned;
+        self.animations.clear_tabs(window_id);
+        self.keep_pinned_first(window_id);
+        self.tab_bar_dirty = true;
+        self.redraw.request(window_id, RedrawReason::TAB_BAR);
+    }
+
+    /// How many of `window_id`'s leading tabs are pinned.
+    pub(super) fn pinned_count(&self, window_id: WindowId) -> usize {
+        self.windows.get(&window_id).map_or(0, |tw| {
+            tw.tabs
+                .iter()
+                .take_while(|tid| self.tabs.get(tid).is_some_and(|tab| tab.pinned))
+                .count()
+        })
+    }
+
+    /// Move `window_id`'s pinned tabs in front of the others, keeping the
+    /// order within each group and the same tab active.
+    pub(super) fn keep_pinned_first(&mut self, window_id: WindowId) {
+        let Some(tw) = self.windows.get_mut(&window_id) else {
+            return;
+        };
+        let active = tw.tabs.get(tw.active_tab).copied();
+        let tabs = &self.tabs;
+        tw.tabs
+            .sort_by_key(|tid| !tabs.get(tid).is_some_and(|tab| tab.pinned));
+        if let Some(idx) = active.and_then(|tid| tw.tab_index(tid)) {
+            tw.active_tab = idx;
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Tab renaming — a text field over the tab's title in the tab bar.
+//!
+//! Enter keeps the typed name, which then wins over OSC and directory
+//! titles; an empty name goes back to them. Escape, or a click anywhere
+//! else, leaves the field (a click keeps the name).
+
+use winit::event::{Ime, KeyEvent};
+use winit::keyboard::{Key, NamedKey};
+use winit::window::WindowId;
+
+use super::{App, RedrawReason};
+use crate::tab::TabId;
+use crate::ui::text_input::{KeyOutcome, TextInput};
+
+/// A tab being renamed.
+pub(crate) struct TabRename {
+    pub(super) window: WindowId,
+    pub(super) tab: TabId,
+    pub(super) input: TextInput,
+}
+
+impl App {
+    /// Open the rename field on the tab at `tab_index` in `window_id`,
+    /// holding its current title, selected.
+    pub(super) fn start_tab_rename(&mut self, window_id: WindowId, tab_index: usize) {
+        self.commit_tab_rename();
+        let Some(&tab_id) = self.windows.get(&window_id).and_then(|tw| tw.tabs.get(tab_index))
+        else {
+            return;
+        };
+        let Some(tab) = self.tabs.get(&tab_id) else {
+            return;
+        };
+        let mut input = TextInput::default();
+        // The name to edit, without the foreground process in front.
+        let title = tab
+            .custom_title
+            .clone()
+            .unwrap_or_else(|| tab.terminal.lock().effective_title());
+        input.set_text(&title);
+        input.select_all();
+        self.tab_rename = Some(TabRename {
+            window: window_id,
+            tab: tab_id,
+            input,
+        });
+        self.ime_preedit.remove(&window_id);
+        self.tab_rename_changed(window_id);
+    }
+
+    /// Whether the rename field in `window_id` has keyboard focus.
+    pub(super) fn is_renaming_tab(&self, window_id: WindowId) -> bool {
+        self.tab_rename.as_ref().is_some_and(|r| r.window == window_id)
+    }
+
+    /// Index of the tab being renamed in `window_id`, if any.
+    pub(super) fn tab_rename_index(&self, window_id: WindowId) -> Option<usize> {
+        let rename = self.tab_rename.as_ref().filter(|r| r.window == window_id)?;
+        self.windows.get(&window_id)?.tab_index(rename.tab)
+    }
+
+    pub(super) fn handle_tab_rename_key(&mut self, window_id: WindowId, event: &KeyEvent) {
+        match event.logical_key {
+            Key::Named(NamedKey::Enter) => self.commit_tab_rename(),
+            Key::Named(NamedKey::Escape) => self.cancel_tab_rename(),
+            _ => {
+                let Some(rename) = self.tab_rename.as_mut() else {
+                    return;
+                };
+                if rename.input.handle_key(&event.logical_key, self.modifiers)
+                    == KeyOutcome::Ignored
+                {
+                    return;
+                }
+                self.tab_rename_changed(window_id);
+            }
+        }
+    }
+
+    /// IME input while the rename field has focus.
+    pub(super) fn handle_tab_rename_ime(&mut self, window_id: WindowId, ime: Ime) {
+        let Some(rename) = self.tab_rename.as_mut() else {
+            return;
+        };
+        if rename.input.handle_ime(ime) != KeyOutcome::Ignored {
+            self.tab_rename_changed(window_id);
+        }
+    }
+
+    /// Close the rename field, giving the tab the typed name.
+    pub(super) fn commit_tab_rename(&mut self) {
+        let Some(rename) = self.tab_rename.take() else {
+            return;
+        };
+        if let Some(tab) = self.tabs.get_mut(&rename.tab) {
+            let name = rename.input.text().trim();
+            tab.custom_title = (!name.is_empty()).then(|| name.to_owned());
+        }
+        self.tab_rename_changed(rename.window);
+    }
+
+    /// Close the rename field, keeping the tab's name as it was.
+    pub(super) fn cancel_tab_rename(&mut self) {
+        if let Some(rename) = self.tab_rename.take() {
+            self.tab_rename_changed(rename.window);
+        }
+    }
+
+    fn tab_rename_changed(&mut self, window_id: WindowId) {
+        self.tab_bar_dirty = true;
+        self.redraw.request(window_id, RedrawReason::TAB_BAR);
+    }
+}
@@ -0,0 +1,96 @@
+//! Moving tabs from the keyboard: along the tab bar, or into another
+//! window.
+
+use std::time::Instant;
+
+use winit::window::WindowId;
+
+use crate::tab_bar::TabBarLayout;
+
+use super::{App, RedrawReason};
+
+impl App {
+    /// Move the window's active tab `delta` slots along the tab bar
+    /// (negative is left), stopping at either end of the pinned or
+    /// unpinned tabs. The tabs slide into their new slots as when dragged.
+    pub(super) fn move_active_tab(&mut self, window_id: WindowId, delta: isize) {
+        let pinned = self.pinned_count(window_id);
+        let Some(tw) = self.windows.get_mut(&window_id) else {
+            return;
+        };
+        let count = tw.tabs.len();
+        let from = tw.active_tab;
+        let (first, end) = if from < pinned { (0, pinned) } else { (pinned, count) };
+        let to = from
+            .saturating_add_signed(delta)
+            .clamp(first, end.saturating_sub(1).max(first));
+        if from == to || from >= count {
+            return;
+        }
+        let tab_id = tw.tabs.remove(from);
+        tw.tabs.insert(to, tab_id);
+        tw.active_tab = to;
+        let width = tw.window.inner_size().width as usize;
+        let layout = TabBarLayout::compute(count, pinned, width, self.scale_factor, None);
+        self.animations.slide_tab(
+            window_id,
+            (from, to),
+            layout.width_of(to) as f32,
+            count,
+            Instant::now(),
+        );
+        self.tab_bar_dirty = true;
+        self.redraw.request(window_id, RedrawReason::TAB_BAR);
+    }
+
+    /// Move the window's active tab to the end of window `n`, counting
+    /// from 1 in the order the windows opened, and focus it there. A
+    /// pinned tab goes after the pinned tabs already there. A window left
+    /// without tabs closes. Does nothing if there's no window
+    /// `n` or it's this one.
+    pub(super) fn move_active_tab_to_window(&mut self, window_id: WindowId, n: usize) {
+        let Some(target) = self.windows_in_order().get(n.wrapping_sub(1)).copied() else {
+            return;
+        };
+        let Some(tab_id) = self.active_tab_id(window_id).filter(|_| target != window_id) else {
+            return;
+        };
+        let source_empty = self
+            .windows
+            .get_mut(&window_id)
+            .is_some_and(|tw| tw.remove_tab(tab_id));
+        if source_empty {
+            self.ime_preedit.remove(&window_id);
+            self.windows.remove(&window_id);
+        } else {
+            self.redraw.request(window_id, RedrawReason::TAB_BAR);
+        }
+        self.animations.clear_tabs(window_id);
+        let Some(tw) = self.windows.get_mut(&target) else {
+            return;
+        };
+        tw.add_tab(tab_id);
+        tw.window.focus_window();
+        let size = tw.window.inner_size();
+        self.keep_pinned_first(target);
+        let (cols, rows) = self.grid_dims_for_size(size.width, size.height);
+        if let Some(tab) = self.tabs.get_mut(&tab_id) {
+            tab.resize(cols, rows, size.width as u16, size.height as u16);
+            tab.set_grid_dirty(true);
+        }
+        self.tab_bar_dirty = true;
+        self.redraw.request(target, RedrawReason::TAB_BAR);
+    }
+
+    /// Terminal windows in the order they opened.
+    fn windows_in_order(&self) -> Vec<WindowId> {
+        let mut windows: Vec<_> = self
+            .windows
+            .iter()
+            .filter(|&(&wid, _)| !self.is_settings_window(wid))
+            .map(|(&wid, tw)| (tw.opened, wid))
+            .collect();
+        windows.sort_by_key(|&(opened, _)| opened);
+        windows.into_iter().map(|(_, wid)| wid).collect()
+    }
+}
@@ -0,0 +1,115 @@
+//! The tab switcher overlay (`Action::OpenTabSwitcher`): every tab in
+//! every window, filtered as the user types.
+//!
+//! Up/Down (or Tab/Shift+Tab) move the highlight, Enter jumps to the
+//! highlighted tab, focusing its window, and Escape or a click closes the
+//! switcher. The tab list is captured when it opens.
+
+use winit::event::{Ime, KeyEvent};
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+use winit::window::WindowId;
+
+use super::{App, RedrawReason};
+use crate::grid::TextFormat;
+use crate::tab::{Tab, TabId};
+use crate::ui::tab_switcher::{self, SwitcherEntry, TabSwitcher};
+use crate::ui::text_input::KeyOutcome;
+
+/// Lines of each tab's screen shown as its preview.
+const PREVIEW_LINES: usize = 3;
+
+/// Rows above the cursor searched for the preview's non-blank lines.
+const PREVIEW_SCAN_ROWS: usize = 24;
+
+impl App {
+    /// Open the switcher over `window_id`, listing the focused window's
+    /// tabs first and highlighting its active tab.
+    pub(super) fn open_tab_switcher(&mut self, window_id: WindowId) {
+        self.commit_tab_rename();
+        let mut windows: Vec<_> = self.windows.keys().copied().collect();
+        windows.sort_by_key(|&wid| (wid != window_id, self.windows.get(&wid).map(|tw| tw.opened)));
+        let entries: Vec<SwitcherEntry> = windows
+            .iter()
+            .filter_map(|wid| self.windows.get(wid))
+            .flat_map(|tw| tw.tabs.iter())
+            .filter_map(|tid| self.tabs.get(tid).map(|tab| switcher_entry(*tid, tab)))
+            .collect();
+        let selected = self
+            .windows
+            .get(&window_id)
+            .map_or(0, |tw| tw.active_tab.min(entries.len().saturating_sub(1)));
+        self.tab_switcher = Some((window_id, TabSwitcher::new(entries, selected)));
+        self.ime_preedit.remove(&window_id);
+        self.redraw.request(window_id, RedrawReason::INPUT);
+    }
+
+    /// The switcher open over `window_id`, if any.
+    pub(super) fn tab_switcher_in(&self, window_id: WindowId) -> Option<&TabSwitcher> {
+        self.tab_switcher
+            .as_ref()
+            .filter(|(wid, _)| *wid == window_id)
+            .map(|(_, switcher)| switcher)
+    }
+
+    /// A key press while the switcher has focus.
+    pub(super) fn handle_tab_switcher_key(&mut self, window_id: WindowId, event: &KeyEvent) {
+        let Some((_, switcher)) = self.tab_switcher.as_mut() else {
+            return;
+        };
+        let shift = self.modifiers.contains(ModifiersState::SHIFT);
+        match &event.logical_key {
+            Key::Named(NamedKey::Escape) => self.close_tab_switcher(),
+            Key::Named(NamedKey::Enter) => {
+                let target = switcher.selected().map(|entry| entry.tab);
+                self.close_tab_switcher();
+                if let Some(tab_id) = target {
+                    self.focus_tab(tab_id);
+                }
+            }
+            Key::Named(NamedKey::ArrowUp) => switcher.move_selection(-1),
+            Key::Named(NamedKey::ArrowDown) => switcher.move_selection(1),
+            Key::Named(NamedKey::Tab) => switcher.move_selection(if shift { -1 } else { 1 }),
+            key => {
+                if switcher.input.handle_key(key, self.modifiers) == KeyOutcome::Ignored {
+                    return;
+                }
+                switcher.refilter();
+            }
+        }
+        self.redraw.request(window_id, RedrawReason::INPUT);
+    }
+
+    /// IME input while the switcher has focus.
+    pub(super) fn handle_tab_switcher_ime(&mut self, window_id: WindowId, ime: Ime) {
+        let Some((_, switcher)) = self.tab_switcher.as_mut() else {
+            return;
+        };
+        if switcher.input.handle_ime(ime) != KeyOutcome::Ignored {
+            switcher.refilter();
+            self.redraw.request(window_id, RedrawReason::INPUT);
+        }
+    }
+
+    /// Close the switcher without switching tabs.
+    pub(super) fn close_tab_switcher(&mut self) {
+        if let Some((wid, _)) = self.tab_switcher.take() {
+            self.redraw.request(wid, RedrawReason::INPUT);
+        }
+    }
+}
+
+/// How the switcher lists `tab`: its title, directory, and the last
+/// non-blank lines above and at its cursor.
+fn switcher_entry(tab_id: TabId, tab: &Tab) -> SwitcherEntry {
+    let text = {
+        let grid = tab.grid();
+        let cursor = grid.scrollback.len() + grid.cursor.row;
+        grid.serialize_rows(cursor.saturating_sub(PREVIEW_SCAN_ROWS), cursor, TextFormat::Plain)
+    };
+    SwitcherEntry {
+        tab: tab_id,
+        title: tab.effective_title(),
+        cwd: tab.cwd(),
+        preview: tab_switcher::preview_lines(&text, PREVIEW_LINES),
+    }
+}
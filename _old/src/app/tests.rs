@@ -0,0 +1,366 @@
+use std::time::{Duration, Instant};
+
+use winit::dpi::PhysicalPosition;
+use winit::window::WindowId;
+
+use super::animation::{Animations, Easing, Tween};
+use super::mouse_coord::cell_at;
+use super::redraw::{
+    DEFAULT_FRAME_INTERVAL, RedrawReason, RedrawScheduler, Visibility, frame_interval,
+};
+use super::settings_ui::toggled_contrast;
+
+fn wid(n: u64) -> WindowId {
+    WindowId::from(n)
+}
+
+#[test]
+fn redraw_requests_merge_per_window() {
+    let mut sched = RedrawScheduler::default();
+    sched.request(wid(1), RedrawReason::PTY_OUTPUT);
+    sched.request(wid(1), RedrawReason::CURSOR_BLINK);
+    sched.request(wid(1), RedrawReason::HOVER);
+
+    let now = Instant::now();
+    assert_eq!(sched.due(now), vec![wid(1)]);
+    sched.begin_frame(wid(1), now);
+    assert!(sched.due(now).is_empty());
+    assert_eq!(sched.next_deadline(), None);
+    assert_eq!(sched.take_stats(), "pty_output=1 cursor_blink=1 hover=1");
+    assert_eq!(sched.take_stats(), "");
+}
+
+#[test]
+fn redraw_paced_by_frame_interval() {
+    let mut sched = RedrawScheduler::default();
+    let start = Instant::now();
+    sched.request(wid(1), RedrawReason::PTY_OUTPUT);
+    sched.begin_frame(wid(1), start);
+
+    // More output right after a frame waits for the interval.
+    sched.request(wid(1), RedrawReason::PTY_OUTPUT);
+    assert!(sched.due(start).is_empty());
+    assert_eq!(sched.next_deadline(), Some(start + DEFAULT_FRAME_INTERVAL));
+    assert_eq!(sched.due(start + DEFAULT_FRAME_INTERVAL), vec![wid(1)]);
+}
+
+#[test]
+fn redraw_echo_skips_frame_pacing() {
+    let mut sched = RedrawScheduler::default();
+    let start = Instant::now();
+    sched.request(wid(1), RedrawReason::INPUT);
+    sched.begin_frame(wid(1), start);
+
+    sched.request(wid(1), RedrawReason::PTY_OUTPUT | RedrawReason::ECHO);
+    assert_eq!(sched.due(start), vec![wid(1)]);
+    sched.begin_frame(wid(1), start);
+
+    // Output after the echo frame is paced again.
+    sched.request(wid(1), RedrawReason::PTY_OUTPUT);
+    assert!(sched.due(start).is_empty());
+}
+
+#[test]
+fn redraw_dropped_while_hidden_and_exposed_after() {
+    let mut sched = RedrawScheduler::default();
+    sched.request(wid(1), RedrawReason::PTY_OUTPUT);
+    sched.set_occlusion(wid(1), Visibility::Hidden);
+    assert!(sched.due(Instant::now()).is_empty());
+
+    sched.request(wid(1), RedrawReason::CURSOR_BLINK);
+    assert!(sched.due(Instant::now()).is_empty());
+
+    // Minimized while occluded: still hidden after the occlusion clears.
+    sched.set_size(wid(1), 0, 0);
+    sched.set_occlusion(wid(1), Visibility::Visible);
+    assert!(sched.due(Instant::now()).is_empty());
+
+    sched.set_size(wid(1), 800, 600);
+    sched.begin_frame(wid(1), Instant::now());
+    assert_eq!(sched.take_stats(), "expose=1 dropped=1");
+}
+
+#[test]
+fn redraw_windows_paced_independently() {
+    let mut sched = RedrawScheduler::default();
+    let start = Instant::now();
+    sched.set_frame_interval(wid(2), Duration::from_millis(16));
+    sched.request_all([wid(1), wid(2)], RedrawReason::TAB_BAR);
+    for w in sched.due(start) {
+        sched.begin_frame(w, start);
+    }
+
+    sched.request_all([wid(1), wid(2)], RedrawReason::ANIMATION);
+    assert_eq!(sched.due(start + DEFAULT_FRAME_INTERVAL), vec![wid(1)]);
+
+    sched.retain(|w| *w != wid(1));
+    assert_eq!(
+        sched.next_deadline(),
+        Some(start + Duration::from_millis(16))
+    );
+}
+
+#[test]
+fn frame_interval_follows_vsync_refresh_rate() {
+    use wgpu::PresentMode;
+
+    let hz60 = frame_interval(PresentMode::Fifo, Some(60_000));
+    assert_eq!(hz60.as_micros(), 16_666);
+    let hz144 = frame_interval(PresentMode::Fifo, Some(144_000));
+    assert_eq!(hz144.as_micros(), 6_944);
+    // Unknown rate, or a mode that doesn't wait for vblank: default pacing.
+    assert_eq!(
+        frame_interval(PresentMode::Fifo, None),
+        DEFAULT_FRAME_INTERVAL
+    );
+    assert_eq!(
+        frame_interval(PresentMode::Fifo, Some(0)),
+        DEFAULT_FRAME_INTERVAL
+    );
+    assert_eq!(
+        frame_interval(PresentMode::Immediate, Some(60_000)),
+        DEFAULT_FRAME_INTERVAL
+    );
+    // Out-of-range rates are clamped.
+    assert_eq!(
+        frame_interval(PresentMode::Fifo, Some(1_000)).as_millis(),
+        33
+    );
+    assert_eq!(
+        frame_interval(PresentMode::Fifo, Some(1_000_000)).as_micros(),
+        4_166
+    );
+}
+
+#[test]
+fn unseen_total_follows_each_tab() {
+    use super::notifications::UnseenTotal;
+    use crate::tab::Unseen;
+
+    let mut total = UnseenTotal::default();
+    let (mut a, mut b) = (Unseen::default(), Unseen::default());
+    total.notify(&mut a);
+    total.notify(&mut a);
+    total.ring(&mut b);
+    total.ring(&mut b);
+    assert_eq!(total.count(), 3);
+    assert!(total.any_bell());
+
+    total.clear_bell(&mut b);
+    assert!(!total.any_bell());
+    assert!(total.clear(&mut a));
+    assert!(!total.clear(&mut a));
+    assert_eq!(total.count(), 0);
+
+    total.ring(&mut b);
+    total.forget(b);
+    assert_eq!(total.count(), 0);
+}
+
+#[test]
+fn attention_requested_only_while_unfocused() {
+    use super::notifications::{Attention, attention_change};
+
+    assert_eq!(attention_change(0, 1, false), Some(Attention::Request));
+    assert_eq!(attention_change(0, 1, true), None);
+    assert_eq!(attention_change(2, 1, false), None);
+    assert_eq!(attention_change(3, 0, true), Some(Attention::Clear));
+    assert_eq!(attention_change(0, 0, false), None);
+}
+
+#[test]
+fn notch_steps_scroll_lines_per_notch_and_ignore_the_idle_axis() {
+    use super::SCROLL_LINES;
+    use super::mouse_wheel::notch_steps;
+
+    let n = SCROLL_LINES as i32;
+    assert_eq!(notch_steps(1.0), n);
+    assert_eq!(notch_steps(-0.5), -n);
+    assert_eq!(notch_steps(0.0), 0);
+}
+
+#[test]
+fn touchpad_pixels_accumulate_and_leave_a_sub_cell_offset() {
+    use std::time::{Duration, Instant};
+
+    use super::mouse_wheel::KineticScroll;
+
+    let cell = (8.0, 16.0);
+    let t = Instant::now();
+    let mut k = KineticScroll::default();
+    assert_eq!(k.push(0.0, 6.0, cell, t), (0, 0));
+    assert_eq!(k.push(0.0, 6.0, cell, t + Duration::from_millis(10)), (0, 0));
+    assert_eq!(k.push(3.0, 6.0, cell, t + Duration::from_millis(20)), (0, 1));
+    assert!((k.offset_y() - 2.0).abs() < 1e-9);
+    // Scrolling back past the line boundary goes the other way.
+    assert_eq!(k.push(0.0, -20.0, cell, t + Duration::from_millis(30)), (0, -1));
+    assert!((k.offset_y() + 2.0).abs() < 1e-9);
+}
+
+#[test]
+fn touchpad_momentum_decays_to_a_stop_on_a_whole_line() {
+    use std::time::{Duration, Instant};
+
+    use super::mouse_wheel::KineticScroll;
+
+    let cell = (8.0, 16.0);
+    let t = Instant::now();
+    let mut k = KineticScroll::default();
+    let mut lines = 0;
+    for i in 0..5 {
+        lines += k.push(0.0, 20.0, cell, t + Duration::from_millis(10 * i)).1;
+    }
+    let lifted = t + Duration::from_millis(45);
+    lines += k.release(cell, lifted).1;
+    assert!(k.is_coasting());
+
+    let mut frames = 0;
+    while k.is_coasting() {
+        frames += 1;
+        assert!(frames < 1000, "momentum never stopped");
+        lines += k.tick(cell, lifted + Duration::from_millis(16 * frames)).1;
+    }
+    // 100 px of swipe plus the coast, ending on a line boundary.
+    assert!(lines > 100 / 16, "coast added nothing: {lines}");
+    assert!(k.offset_y().abs() < 1e-9);
+}
+
+#[test]
+fn touchpad_release_after_a_pause_does_not_coast() {
+    use std::time::{Duration, Instant};
+
+    use super::mouse_wheel::KineticScroll;
+
+    let cell = (8.0, 16.0);
+    let t = Instant::now();
+    let mut k = KineticScroll::default();
+    k.push(0.0, 20.0, cell, t);
+    k.push(0.0, 20.0, cell, t + Duration::from_millis(10));
+    // The fingers rested before lifting, so the swipe has no speed left.
+    assert_eq!(k.release(cell, t + Duration::from_millis(300)), (0, 1));
+    assert!(!k.is_coasting());
+    assert!(k.offset_y().abs() < 1e-9);
+}
+
+#[test]
+fn horizontal_wheel_reports_buttons_66_67_and_left_right_arrows() {
+    use super::mouse_wheel::WheelAxis;
+
+    assert_eq!(WheelAxis::Vertical.button(1), 64);
+    assert_eq!(WheelAxis::Vertical.button(-1), 65);
+    assert_eq!(WheelAxis::Horizontal.button(1), 66);
+    assert_eq!(WheelAxis::Horizontal.button(-1), 67);
+
+    assert_eq!(&WheelAxis::Horizontal.arrow_key(1, false), b"\x1b[D");
+    assert_eq!(&WheelAxis::Horizontal.arrow_key(-1, true), b"\x1bOC");
+    assert_eq!(&WheelAxis::Vertical.arrow_key(1, true), b"\x1bOA");
+}
+
+#[test]
+fn easings_run_from_zero_to_one() {
+    for easing in [Easing::OutCubic, Easing::OutBack] {
+        assert!(easing.apply(0.0).abs() < 1e-6);
+        assert!((easing.apply(1.0) - 1.0).abs() < 1e-6);
+        assert!((easing.apply(2.0) - 1.0).abs() < 1e-6);
+    }
+    // Out-back overshoots before settling; out-cubic never does.
+    assert!(Easing::OutBack.apply(0.7) > 1.0);
+    assert!(Easing::OutCubic.apply(0.7) < 1.0);
+}
+
+#[test]
+fn tween_eases_to_zero_by_its_duration() {
+    let start = Instant::now();
+    let tween = Tween::new(40.0, Duration::from_millis(100), Easing::OutCubic, start);
+    assert!((tween.value(start) - 40.0).abs() < 1e-6);
+    let mid = tween.value(start + Duration::from_millis(50));
+    assert!(mid > 0.0 && mid < 20.0);
+    assert!(!tween.is_done(start + Duration::from_millis(50)));
+    assert!(tween.value(start + Duration::from_millis(100)).abs() < 1e-6);
+    assert!(tween.is_done(start + Duration::from_millis(100)));
+}
+
+#[test]
+fn dodged_tabs_start_in_their_old_slots() {
+    let mut anims = Animations::new(Instant::now());
+    let now = Instant::now();
+    // Tab 0 dragged to slot 2: tabs 1 and 2 each move one slot left.
+    anims.dodge_tabs(wid(1), (0, 2), 100.0, 4, now);
+    assert_eq!(anims.tab_offsets(wid(1)), &[100.0, 100.0, 0.0, 0.0]);
+    // Dragged back to slot 1: the tab now in slot 2 came from slot 1.
+    anims.dodge_tabs(wid(1), (2, 1), 100.0, 4, now);
+    assert_eq!(anims.tab_offsets(wid(1)), &[100.0, 0.0, 0.0, 0.0]);
+    assert!(anims.tab_offsets(wid(2)).is_empty());
+
+    assert!(anims.tick(now + Duration::from_millis(100)));
+    // The last tick snaps tabs home; the next has nothing to move.
+    assert!(anims.tick(now + Duration::from_secs(1)));
+    assert!(anims.tab_offsets(wid(1)).is_empty());
+    assert!(!anims.tick(now + Duration::from_secs(1)));
+}
+
+#[test]
+fn slid_tab_starts_in_its_old_slot() {
+    let mut anims = Animations::new(Instant::now());
+    let now = Instant::now();
+    // Tab 1 moved right: it comes from the left, the tab it passed from
+    // the right.
+    anims.slide_tab(wid(1), (1, 2), 100.0, 3, now);
+    assert_eq!(anims.tab_offsets(wid(1)), &[0.0, 100.0, -100.0]);
+}
+
+#[test]
+fn dropped_tab_settles_and_clear_snaps() {
+    let mut anims = Animations::new(Instant::now());
+    let now = Instant::now();
+    anims.settle_tab(wid(1), (1, -30.0), 3, now);
+    assert_eq!(anims.tab_offsets(wid(1)), &[0.0, -30.0, 0.0]);
+    // Sub-pixel offsets aren't animated.
+    anims.settle_tab(wid(2), (0, 0.2), 3, now);
+    assert!(anims.tab_offsets(wid(2)).is_empty());
+    anims.clear_tabs(wid(1));
+    assert!(anims.tab_offsets(wid(1)).is_empty());
+}
+
+fn same(a: f32, b: f32) -> bool {
+    (a - b).abs() < f32::EPSILON
+}
+
+#[test]
+fn contrast_toggle_restores_the_value_it_replaced() {
+    // Off and on with nothing saved: 1.0 and WCAG AA.
+    assert!(same(toggled_contrast(1.0, None), 4.5));
+    assert!(same(toggled_contrast(4.5, None), 1.0));
+    // A custom 7:1 comes back after toggling off and on again.
+    let off = toggled_contrast(7.0, None);
+    assert!(same(off, 1.0));
+    assert!(same(toggled_contrast(off, Some(7.0)), 7.0));
+    // A custom 3:1 is below AA, so turning on gives AA and off gives 3:1.
+    let on = toggled_contrast(3.0, None);
+    assert!(same(on, 4.5));
+    assert!(same(toggled_contrast(on, Some(3.0)), 3.0));
+}
+
+#[test]
+fn mouse_follows_rows_drawn_mid_scroll() {
+    // Grid at (10, 40), 8x16 cells.
+    let at = |x, y, offset| cell_at(PhysicalPosition::new(x, y), (10, 40), (8, 16), offset);
+    assert_eq!(at(10.0, 40.0, 0.0), Some((0, 0)));
+    assert_eq!(at(9.0, 40.0, 0.0), None);
+    assert_eq!(at(10.0, 39.0, 0.0), None);
+
+    // Rows drawn 6px down: the top 6px are a gap, and the row boundary
+    // moves down with them.
+    assert_eq!(at(10.0, 45.0, 6.0), None);
+    assert_eq!(at(10.0, 46.0, 6.0), Some((0, 0)));
+    assert_eq!(at(10.0, 61.0, 6.0), Some((0, 0)));
+    assert_eq!(at(10.0, 62.0, 6.0), Some((0, 1)));
+
+    // Rows drawn 6px up: the first row is cut off at the top, and the
+    // bottom edge of the last of 4 rows is 6px higher, leaving a gap
+    // below it that maps past the grid.
+    assert_eq!(at(10.0, 40.0, -6.0), Some((0, 0)));
+    assert_eq!(at(10.0, 50.0, -6.0), Some((0, 1)));
+    assert_eq!(at(10.0, 97.0, -6.0), Some((0, 3)));
+    assert_eq!(at(10.0, 98.0, -6.0), Some((0, 4)));
+}
@@ -0,0 +1,47 @@
+//! Window class and role, so window manager rules can match oriterm's
+//! windows.
+
+use winit::window::WindowAttributes;
+
+/// What a window is for, so window manager rules can tell them apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum WindowRole {
+    /// A terminal window with tabs.
+    Terminal,
+    /// The settings window.
+    Settings,
+}
+
+impl WindowRole {
+    /// The `WM_CLASS` instance name for this role.
+    #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+    fn instance(self) -> &'static str {
+        match self {
+            Self::Terminal => "terminal",
+            Self::Settings => "settings",
+        }
+    }
+}
+
+/// Tag a new window with `app_id` and its `role`: `WM_CLASS` is
+/// (`role`, `app_id`) on X11 and the app ID is `app_id` on Wayland. Windows
+/// uses the process-wide `AppUserModelID` instead, and macOS has no
+/// equivalent.
+#[cfg_attr(
+    not(any(target_os = "linux", target_os = "freebsd")),
+    expect(unused_variables, reason = "only X11 and Wayland name windows")
+)]
+pub(super) fn with_window_class(
+    attrs: WindowAttributes,
+    app_id: &str,
+    role: WindowRole,
+) -> WindowAttributes {
+    #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+    let attrs = {
+        use winit::platform::wayland::WindowAttributesExtWayland;
+        use winit::platform::x11::WindowAttributesExtX11;
+        let attrs = WindowAttributesExtX11::with_name(attrs, app_id, role.instance());
+        WindowAttributesExtWayland::with_name(attrs, app_id, role.instance())
+    };
+    attrs
+}
@@ -11,13 +11,17 @@ use crate::config;
 use crate::gpu::{GpuRenderer, GpuState};
 use crate::log;
 use crate::palette;
-use crate::tab::TabId;
+use crate::tab::{SHUTDOWN_TIMEOUT, Tab, TabId, shutdown_tabs};
+#[cfg(target_os = "windows")]
+use crate::tab::TaskProgress;
 use crate::grid::{GRID_PADDING_BOTTOM, GRID_PADDING_LEFT, GRID_PADDING_TOP};
 use crate::tab_bar::TAB_BAR_HEIGHT;
 use crate::window::TermWindow;
 #[cfg(target_os = "windows")]
 use super::RESIZE_BORDER;
-use super::{App, UI_FONT_SCALE, apply_window_effects};
+use super::redraw;
+use super::window_class::{WindowRole, with_window_class};
+use super::{App, LaunchMode, RedrawReason, UI_FONT_SCALE, apply_window_effects};
 
 impl App {
     /// Begin a window drag.
@@ -67,14 +71,27 @@ impl App {
 
         for &tab_id in &tw.tabs.clone() {
             if let Some(tab) = self.tabs.get_mut(&tab_id) {
-                tab.clear_selection();
                 tab.resize(cols, rows, pixel_w, pixel_h);
                 tab.set_grid_dirty(true);
             }
         }
         self.tab_bar_dirty = true;
-        if let Some(tw) = self.windows.get(&window_id) {
-            tw.window.request_redraw();
+        self.redraw.request(window_id, RedrawReason::RESIZE);
+    }
+
+    /// Show or hide the tab bar and grid padding, then refit every window's
+    /// tabs to the new grid area.
+    pub(super) fn toggle_focus_mode(&mut self) {
+        self.focus_mode = !self.focus_mode;
+        log(&format!("focus mode: {}", self.focus_mode));
+        let window_ids: Vec<WindowId> = self
+            .windows
+            .keys()
+            .copied()
+            .filter(|&wid| !self.is_settings_window(wid))
+            .collect();
+        for wid in window_ids {
+            self.resize_all_tabs_in_window(wid);
         }
     }
 
@@ -100,7 +117,6 @@ impl App {
 
         let use_transparent = self.config.window.effective_opacity() < 1.0;
 
-        #[allow(unused_mut)]
         let mut attrs = Window::default_attributes()
             .with_title("oriterm")
             .with_inner_size(winit::dpi::PhysicalSize::new(win_w, win_h))
@@ -108,6 +124,7 @@ impl App {
             .with_visible(false)
             .with_transparent(use_transparent)
             .with_window_icon(Self::load_window_icon());
+        attrs = with_window_class(attrs, &self.window_class, WindowRole::Terminal);
 
         // On Windows, DX12 + DirectComposition needs WS_EX_NOREDIRECTIONBITMAP
         // so the compositor reads alpha from our swapchain.
@@ -217,12 +234,17 @@ impl App {
             window.set_outer_position(PhysicalPosition::new(state.x, state.y));
         }
 
+        // Input methods compose into the terminal and the search bar.
+        window.set_ime_allowed(true);
+
         // Now show the window with the dark frame already presented
         if visible {
             window.set_visible(true);
         }
 
         self.windows.insert(id, tw);
+        self.update_frame_interval(id);
+        self.refresh_chrome_theme(None);
         log(&format!(
             "window created: {id:?}, visible={visible} ({:.1}ms total create_window)",
             create_start.elapsed().as_secs_f64() * 1000.0
@@ -230,7 +252,7 @@ impl App {
         Some(id)
     }
 
-    pub(super) fn close_window(&mut self, window_id: WindowId, _event_loop: &ActiveEventLoop) {
+    pub(super) fn close_window(&mut self, window_id: WindowId, event_loop: &ActiveEventLoop) {
         // If closing the settings window, just remove it — don't exit
         if self.settings_window == Some(window_id) {
             self.close_settings_window();
@@ -244,11 +266,11 @@ impl App {
             .any(|wid| *wid != window_id && self.settings_window != Some(*wid));
 
         if !other_terminal_windows {
-            // Last terminal window — force-exit before Tab drops block.
-            self.exit_app();
+            // Last terminal window — close everything and stop the loop.
+            self.exit_app(event_loop);
         }
 
-        // Non-last window: shutdown tabs and drop on background threads.
+        // Non-last window: shutdown tabs and close them on background threads.
         // On Windows, `ClosePseudoConsole` blocks until reader threads exit.
         let tab_ids: Vec<TabId> = self
             .windows
@@ -257,30 +279,26 @@ impl App {
             .unwrap_or_default();
         for tid in tab_ids {
             if let Some(mut tab) = self.tabs.remove(&tid) {
+                self.unseen.forget(tab.unseen);
                 tab.shutdown();
-                std::thread::spawn(move || drop(tab));
+                std::thread::spawn(move || tab.close());
             }
         }
+        self.ime_preedit.remove(&window_id);
         self.windows.remove(&window_id);
     }
 
-    /// Shut everything down and exit the process immediately.
-    /// `ClosePseudoConsole` (`ConPTY` cleanup) blocks indefinitely when the
-    /// PTY reader thread still holds a pipe handle, so we kill children and
-    /// force-exit before any `Tab` drop runs. Same approach as Alacritty.
-    pub(super) fn exit_app(&mut self) {
-        // Save window position and size before exiting.
-        if let Some(tw) = self.windows.values().next() {
-            if let Ok(pos) = tw.window.outer_position() {
-                let size = tw.window.inner_size();
-                let state = config::WindowState {
-                    x: pos.x,
-                    y: pos.y,
-                    width: size.width,
-                    height: size.height,
-                };
-                state.save();
-            }
+    /// Save window state, close every tab, and stop the event loop.
+    ///
+    /// Tabs close on a worker thread with a timeout (see [`shutdown_tabs`]),
+    /// so a `ConPTY` that never finishes closing can't hang exit. Windows are
+    /// hidden first so the app disappears while that runs. [`App::run`]
+    /// returns once the loop has stopped.
+    pub(super) fn exit_app(&mut self, event_loop: &ActiveEventLoop) {
+        self.save_window_state();
+        self.save_session();
+        if self.config.behavior.save_search_history {
+            self.search_history.save(&config::search_history_path());
         }
 
         // Save Vulkan pipeline cache to disk for faster next launch
@@ -288,17 +306,46 @@ impl App {
             gpu.save_pipeline_cache();
         }
 
-        for tab in self.tabs.values_mut() {
-            tab.shutdown();
+        for tw in self.windows.values() {
+            tw.window.set_visible(false);
         }
+        let tabs: Vec<Tab> = self.tabs.drain().map(|(_, tab)| tab).collect();
+        let tab_count = tabs.len();
+        if shutdown_tabs(tabs, SHUTDOWN_TIMEOUT) {
+            log(&format!("shutdown: closed {tab_count} tab(s)"));
+        }
+        if let Some(monitor) = self.config_monitor.take() {
+            monitor.shutdown();
+        }
+        self.ipc_endpoint = None;
 
         // Release mouse capture so pending mouse-up events don't get
         // delivered to the window behind after our process exits.
         #[cfg(target_os = "windows")]
         crate::platform_windows::release_mouse_capture();
 
-        // Don't join threads — process::exit will clean them up.
-        std::process::exit(0);
+        event_loop.exit();
+    }
+
+    /// Remember the first window's position and size for the next launch.
+    /// Safe mode leaves `state.toml` alone.
+    fn save_window_state(&self) {
+        if self.launch_mode == LaunchMode::Safe {
+            return;
+        }
+        let Some(tw) = self.windows.values().next() else {
+            return;
+        };
+        if let Ok(pos) = tw.window.outer_position() {
+            let size = tw.window.inner_size();
+            let state = config::WindowState {
+                x: pos.x,
+                y: pos.y,
+                width: size.width,
+                height: size.height,
+            };
+            state.save();
+        }
     }
 
     pub(super) fn handle_resize(&mut self, window_id: WindowId, width: u32, height: u32) {
@@ -354,21 +401,89 @@ impl App {
 
         for &tab_id in &tw.tabs {
             if let Some(tab) = self.tabs.get_mut(&tab_id) {
-                tab.clear_selection();
                 tab.resize(cols, rows, pixel_w, pixel_h);
                 tab.set_grid_dirty(true);
             }
         }
     }
 
+    /// Send PTY resizes whose gesture has settled.
+    ///
+    /// Returns the earliest deadline among resizes still waiting to settle.
+    pub(super) fn flush_pty_resizes(&mut self) -> Option<Instant> {
+        let now = Instant::now();
+        self.tabs
+            .values_mut()
+            .filter_map(|tab| tab.flush_pty_resize(now))
+            .min()
+    }
+
+    /// Run the foreground checks that new output scheduled, dirtying the
+    /// tab bar if any tab's command or busy state changed.
+    ///
+    /// Returns the earliest deadline among checks not yet due.
+    pub(super) fn poll_foregrounds(&mut self) -> Option<Instant> {
+        let now = Instant::now();
+        for tab in self.tabs.values_mut() {
+            self.tab_bar_dirty |= tab.poll_foreground(now);
+        }
+        self.tabs.values().filter_map(Tab::foreground_due).min()
+    }
+
+    /// Set each window's OS title from its active tab per
+    /// `window.title_format`, skipping windows whose title is unchanged.
+    pub(super) fn update_window_titles(&mut self) {
+        for tw in self.windows.values_mut() {
+            let Some(tab) = tw.active_tab_id().and_then(|tid| self.tabs.get(&tid)) else {
+                continue;
+            };
+            let title = self
+                .config
+                .window
+                .format_title(&tab.effective_title(), tw.tabs.len());
+            if title != tw.os_title {
+                tw.window.set_title(&title);
+                tw.os_title = title;
+            }
+        }
+    }
+
+    /// Show each window's active tab progress on its taskbar button,
+    /// skipping windows whose progress is unchanged.
+    #[cfg(target_os = "windows")]
+    pub(super) fn update_taskbar_progress(&mut self) {
+        for tw in self.windows.values_mut() {
+            let progress = tw
+                .active_tab_id()
+                .and_then(|tid| self.tabs.get(&tid))
+                .map_or(TaskProgress::Hidden, Tab::shown_progress);
+            if progress != tw.taskbar_progress {
+                crate::taskbar::set_progress(&tw.window, progress);
+                tw.taskbar_progress = progress;
+            }
+        }
+    }
+
+    /// Pace `window_id`'s frames to its surface's present mode and the
+    /// refresh rate of the monitor it is on.
+    pub(super) fn update_frame_interval(&mut self, window_id: WindowId) {
+        let Some(tw) = self.windows.get(&window_id) else {
+            return;
+        };
+        let refresh = tw
+            .window
+            .current_monitor()
+            .and_then(|m| m.refresh_rate_millihertz());
+        let interval = redraw::frame_interval(tw.surface_config.present_mode, refresh);
+        self.redraw.set_frame_interval(window_id, interval);
+    }
+
     pub(super) fn handle_scale_factor_changed(&mut self, _window_id: WindowId, scale_factor: f64) {
         if (scale_factor - self.scale_factor).abs() < 0.01 {
             return;
         }
         self.scale_factor = scale_factor;
         // Request redraw on all windows so UI chrome rescales
-        for tw in self.windows.values() {
-            tw.window.request_redraw();
-        }
+        self.redraw.request_all(self.windows.keys().copied(), RedrawReason::RESIZE);
     }
 }
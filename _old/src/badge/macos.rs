@@ -0,0 +1,20 @@
+//! Dock tile badge through `NSApp.dockTile.badgeLabel`.
+
+use objc2::runtime::AnyObject;
+use objc2::{class, msg_send};
+use objc2_foundation::NSString;
+
+/// Set the dock tile's badge label, or clear it with `None`. Called on the
+/// main thread, as AppKit requires.
+#[allow(unsafe_code)]
+pub(super) fn set_label(label: Option<&str>) {
+    let label = label.map(NSString::from_str);
+    // SAFETY: `sharedApplication` exists once winit has started the event
+    // loop, `dockTile` is never nil, and `setBadgeLabel:` takes a nullable
+    // `NSString`.
+    unsafe {
+        let app: *mut AnyObject = msg_send![class!(NSApplication), sharedApplication];
+        let tile: *mut AnyObject = msg_send![app, dockTile];
+        let _: () = msg_send![tile, setBadgeLabel: label.as_deref()];
+    }
+}
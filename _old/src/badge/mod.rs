@@ -0,0 +1,110 @@
+//! Unseen count on the app's icon.
+//!
+//! Windows gets an overlay on the taskbar button, macOS the dock tile's
+//! badge label, and elsewhere the Unity `LauncherEntry` count that GNOME's
+//! dock extensions and KDE Plasma show. The Windows overlay is a small icon, so the count is drawn here: a red
+//! disc with white digits from a 3×5 pixel font.
+
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(all(unix, not(target_os = "macos")))]
+mod unity;
+
+#[cfg(test)]
+mod tests;
+
+use winit::window::Window;
+
+/// Side of the overlay icon, in pixels (`SM_CXSMICON` at 100%).
+pub const ICON_SIZE: usize = 16;
+
+/// Badge disc color, as BGR.
+const DISC: [u8; 3] = [0x30, 0x30, 0xE0];
+
+/// Digits `0`–`9` and `+`, one row of three pixels per byte, top first.
+const GLYPHS: [[u8; 5]; 11] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+    [0b000, 0b010, 0b111, 0b010, 0b000],
+];
+
+/// The text of the badge for `count`: `None` for no badge, and `99+` past
+/// two digits.
+pub fn label(count: u32) -> Option<String> {
+    match count {
+        0 => None,
+        1..=99 => Some(count.to_string()),
+        _ => Some("99+".to_owned()),
+    }
+}
+
+/// The overlay icon for a nonzero `count`.
+///
+/// [`ICON_SIZE`] squared pixels, top row first, as BGRA with straight
+/// alpha. One character is drawn at twice the font size.
+pub fn icon_bgra(count: u32) -> Vec<u8> {
+    let mut pixels = vec![0u8; ICON_SIZE * ICON_SIZE * 4];
+    let radius = ICON_SIZE as f32 / 2.0;
+    for (i, px) in pixels.chunks_exact_mut(4).enumerate() {
+        let (x, y) = ((i % ICON_SIZE) as f32 + 0.5, (i / ICON_SIZE) as f32 + 0.5);
+        let dist = (x - radius).hypot(y - radius);
+        let coverage = (radius - dist + 0.5).clamp(0.0, 1.0);
+        px[..3].copy_from_slice(&DISC);
+        px[3] = (coverage * 255.0).round() as u8;
+    }
+
+    let text = label(count).unwrap_or_default();
+    let scale = if text.len() == 1 { 2 } else { 1 };
+    let width = (text.len() * 4 - 1) * scale;
+    let (left, top) = ((ICON_SIZE - width) / 2, (ICON_SIZE - 5 * scale) / 2);
+    for (n, c) in text.chars().enumerate() {
+        let glyph = c.to_digit(10).map_or(10, |d| d as usize);
+        for (row, bits) in GLYPHS[glyph].iter().enumerate() {
+            for col in (0..3).filter(|col| bits & (0b100 >> col) != 0) {
+                let x = left + (n * 4 + col) * scale;
+                let y = top + row * scale;
+                for (dx, dy) in (0..scale).flat_map(|dx| (0..scale).map(move |dy| (dx, dy))) {
+                    let at = ((y + dy) * ICON_SIZE + x + dx) * 4;
+                    pixels[at..at + 4].copy_from_slice(&[0xFF; 4]);
+                }
+            }
+        }
+    }
+    pixels
+}
+
+/// Show `count` on the app's icon; zero removes the badge. `app_id` names
+/// the `.desktop` file for the launcher entry, and `windows` get the
+/// taskbar overlay.
+#[cfg_attr(
+    not(target_os = "windows"),
+    expect(
+        clippy::needless_pass_by_value,
+        reason = "only Windows draws per window"
+    )
+)]
+pub fn publish<'a>(app_id: &str, windows: impl Iterator<Item = &'a Window>, count: u32) {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = app_id;
+        crate::taskbar::set_overlay(windows, count);
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let _ = (app_id, windows);
+        macos::set_label(label(count).as_deref());
+    }
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        let _ = windows;
+        unity::update(app_id, count);
+    }
+}
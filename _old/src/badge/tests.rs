@@ -0,0 +1,43 @@
+use super::{ICON_SIZE, icon_bgra, label};
+
+/// The pixel at (`x`, `y`) as BGRA.
+fn pixel(icon: &[u8], x: usize, y: usize) -> &[u8] {
+    let at = (y * ICON_SIZE + x) * 4;
+    &icon[at..at + 4]
+}
+
+#[test]
+fn label_hides_zero_and_caps_at_two_digits() {
+    assert_eq!(label(0), None);
+    assert_eq!(label(7).as_deref(), Some("7"));
+    assert_eq!(label(99).as_deref(), Some("99"));
+    assert_eq!(label(100).as_deref(), Some("99+"));
+}
+
+#[test]
+fn icon_is_a_disc_with_transparent_corners() {
+    let icon = icon_bgra(5);
+    assert_eq!(icon.len(), ICON_SIZE * ICON_SIZE * 4);
+    assert_eq!(pixel(&icon, 0, 0)[3], 0);
+    assert_eq!(pixel(&icon, ICON_SIZE - 1, ICON_SIZE - 1)[3], 0);
+    assert_eq!(pixel(&icon, 1, ICON_SIZE / 2)[3], 255);
+}
+
+#[test]
+fn single_digit_is_drawn_double_size() {
+    let white = |icon: &[u8]| icon.chunks_exact(4).filter(|px| *px == [0xFF; 4]).count();
+    // `1` lights 8 of the 15 font pixels.
+    assert_eq!(white(&icon_bgra(1)), 8 * 4);
+    assert_eq!(white(&icon_bgra(11)), 16);
+}
+
+#[test]
+fn overflow_draws_three_characters_inside_the_icon() {
+    let icon = icon_bgra(250);
+    let lit: Vec<usize> = (0..ICON_SIZE)
+        .filter(|&x| (0..ICON_SIZE).any(|y| pixel(&icon, x, y) == [0xFF; 4]))
+        .collect();
+    // `9`, `9`, and `+` light three columns each.
+    assert_eq!(lit.len(), 9);
+    assert!(lit.iter().all(|&x| x > 0 && x < ICON_SIZE - 1));
+}
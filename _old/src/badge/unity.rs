@@ -0,0 +1,52 @@
+//! Launcher count through the Unity `LauncherEntry` D-Bus signal.
+//!
+//! There is no server to call: docks that support it (GNOME's Dash to Dock
+//! and Ubuntu Dock, KDE Plasma's task manager) listen for `Update` on the
+//! session bus and match its `application://` URI to a `.desktop` file.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use zbus::blocking::Connection;
+use zbus::names::BusName;
+use zbus::zvariant::Value;
+
+use crate::log;
+
+const PATH: &str = "/com/canonical/unity/launcherentry/oriterm";
+const INTERFACE: &str = "com.canonical.Unity.LauncherEntry";
+
+/// The session bus, connected on first use; `None` without one.
+fn connection() -> Option<&'static Connection> {
+    static CONNECTION: OnceLock<Option<Connection>> = OnceLock::new();
+    CONNECTION
+        .get_or_init(|| match Connection::session() {
+            Ok(conn) => Some(conn),
+            Err(e) => {
+                log(&format!("badge: no session bus: {e}"));
+                None
+            }
+        })
+        .as_ref()
+}
+
+/// Show `count` on `app_id`'s launcher entry, or hide it at zero.
+pub(super) fn update(app_id: &str, count: u32) {
+    let Some(conn) = connection() else {
+        return;
+    };
+    let uri = format!("application://{app_id}.desktop");
+    let props = HashMap::from([
+        ("count", Value::from(i64::from(count))),
+        ("count-visible", Value::from(count > 0)),
+    ]);
+    if let Err(e) = conn.emit_signal(
+        None::<BusName<'_>>,
+        PATH,
+        INTERFACE,
+        "Update",
+        &(uri, props),
+    ) {
+        log(&format!("badge: launcher update failed: {e}"));
+    }
+}
@@ -0,0 +1,138 @@
+//! Bidi character classes (UAX #9 table 4), approximated by code point range.
+//!
+//! Covers the right-to-left scripts (Hebrew, Arabic, Syriac, Thaana, N'Ko,
+//! and their presentation forms), digits, and the separators and neutrals
+//! that matter for the implicit rules. Other letters resolve to `L` and
+//! other symbols to `ON`, which matches the UCD for everything a terminal
+//! commonly shows.
+
+/// Bidi character class, limited to the classes the implicit rules use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[expect(
+    clippy::upper_case_acronyms,
+    reason = "Class names as spelled in UAX #9"
+)]
+pub(super) enum BidiClass {
+    /// Strong left-to-right.
+    L,
+    /// Strong right-to-left (Hebrew and other non-Arabic RTL scripts).
+    R,
+    /// Strong right-to-left, Arabic letter.
+    AL,
+    /// European number.
+    EN,
+    /// European number separator (`+`, `-`).
+    ES,
+    /// European number terminator (`#`, `$`, `%`, currency signs).
+    ET,
+    /// Arabic number.
+    AN,
+    /// Common number separator (`,`, `.`, `/`, `:`).
+    CS,
+    /// Non-spacing mark.
+    NSM,
+    /// Boundary neutral (zero-width formatting characters).
+    BN,
+    /// Segment separator (tab).
+    S,
+    /// Whitespace.
+    WS,
+    /// Other neutral.
+    ON,
+}
+
+impl BidiClass {
+    /// Whether this class starts or ends a right-to-left sequence for the
+    /// neutral rules (numbers count as `R`, per N1).
+    pub(super) fn is_rtl_for_neutrals(self) -> bool {
+        matches!(self, Self::R | Self::AL | Self::EN | Self::AN)
+    }
+
+    /// Whether this class is a neutral or separator for the N rules.
+    pub(super) fn is_neutral(self) -> bool {
+        matches!(self, Self::S | Self::WS | Self::ON | Self::BN)
+    }
+}
+
+/// Classify `c`.
+pub(super) fn bidi_class(c: char) -> BidiClass {
+    if c.is_ascii() {
+        return ascii_class(c);
+    }
+    match c as u32 {
+        0x00A0 | 0x060C | 0x202F | 0x2044 | 0xFE50 | 0xFE52 | 0xFE55 | 0xFF0C | 0xFF0E | 0xFF0F
+        | 0xFF1A => BidiClass::CS,
+        0x00A2..=0x00A5 | 0x00B0 | 0x00B1 | 0x066A | 0x2030..=0x2034 | 0x20A0..=0x20CF => {
+            BidiClass::ET
+        }
+        0x00B2
+        | 0x00B3
+        | 0x00B9
+        | 0x06F0..=0x06F9
+        | 0x2070..=0x2079
+        | 0x2080..=0x2089
+        | 0xFF10..=0xFF19 => BidiClass::EN,
+        0x0600..=0x0605 | 0x0660..=0x0669 | 0x066B | 0x066C | 0x06DD => BidiClass::AN,
+        0x00AD | 0x180E | 0x200B..=0x200D | 0x2060..=0x2064 | 0xFEFF => BidiClass::BN,
+        0x2000..=0x200A | 0x2028 | 0x205F | 0x3000 => BidiClass::WS,
+        // LEFT-TO-RIGHT MARK; RIGHT-TO-LEFT MARK is with the other R.
+        0x200E => BidiClass::L,
+        0x0300..=0x036F
+        | 0x0483..=0x0489
+        | 0x0591..=0x05BD
+        | 0x05BF
+        | 0x05C1
+        | 0x05C2
+        | 0x05C4
+        | 0x05C5
+        | 0x05C7
+        | 0x0610..=0x061A
+        | 0x064B..=0x065F
+        | 0x0670
+        | 0x06D6..=0x06DC
+        | 0x06DF..=0x06E4
+        | 0x06E7
+        | 0x06E8
+        | 0x06EA..=0x06ED
+        | 0x0711
+        | 0x0730..=0x074A
+        | 0x07A6..=0x07B0
+        | 0x07EB..=0x07F3
+        | 0x0816..=0x082D
+        | 0x0859..=0x085B
+        | 0x08D3..=0x08E1
+        | 0x08E3..=0x08FF
+        | 0x1AB0..=0x1AFF
+        | 0x1DC0..=0x1DFF
+        | 0x20D0..=0x20FF
+        | 0xFB1E
+        | 0xFE00..=0xFE0F
+        | 0xFE20..=0xFE2F => BidiClass::NSM,
+        0x0590..=0x05FF | 0x07C0..=0x085F | 0x200F | 0xFB1D..=0xFB4F | 0x10800..=0x10FFF => {
+            BidiClass::R
+        }
+        0x0600..=0x07BF | 0x0860..=0x08FF | 0xFB50..=0xFDFF | 0xFE70..=0xFEFE => BidiClass::AL,
+        _ if c.is_alphabetic() => BidiClass::L,
+        _ if c.is_whitespace() => BidiClass::WS,
+        _ => BidiClass::ON,
+    }
+}
+
+fn ascii_class(c: char) -> BidiClass {
+    match c {
+        '0'..='9' => BidiClass::EN,
+        '+' | '-' => BidiClass::ES,
+        '#' | '$' | '%' => BidiClass::ET,
+        ',' | '.' | '/' | ':' => BidiClass::CS,
+        '\t' => BidiClass::S,
+        ' ' | '\0' => BidiClass::WS,
+        _ if c.is_ascii_alphabetic() => BidiClass::L,
+        _ => BidiClass::ON,
+    }
+}
+
+/// Whether `c` can start right-to-left text: the cheap pre-check that lets
+/// rows without RTL characters skip resolution entirely.
+pub(super) fn is_rtl_char(c: char) -> bool {
+    matches!(bidi_class(c), BidiClass::R | BidiClass::AL | BidiClass::AN)
+}
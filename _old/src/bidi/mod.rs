@@ -0,0 +1,220 @@
+//! Bidirectional text: per-row UAX #9 resolution and visual reordering.
+//!
+//! Each grid row is resolved as its own paragraph with a left-to-right base
+//! direction, using the implicit rules only (W1–W7, N1–N2, I1–I2) and the
+//! line rules L1–L2. Explicit embedding controls are not interpreted; they
+//! are treated as boundary neutrals. Rows without right-to-left characters
+//! skip resolution entirely.
+//!
+//! The grid itself stays in logical order: selection, search, and the cursor
+//! keep using logical columns, and only the renderer maps visual columns
+//! back through [`BidiLine::logical_col`]. Applications that reorder text
+//! themselves turn the pass off with BDSM (`CSI 8 l`).
+
+mod class;
+#[cfg(test)]
+mod tests;
+
+use crate::cell::{Cell, CellFlags};
+
+use class::{BidiClass, bidi_class, is_rtl_char};
+
+/// Resolved visual layout of one row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BidiLine {
+    /// Logical column shown at each visual column.
+    visual_to_logical: Vec<usize>,
+    /// Whether each logical column is in a right-to-left run.
+    rtl: Vec<bool>,
+}
+
+impl BidiLine {
+    /// Resolve `row`. Returns `None` when it holds no right-to-left text,
+    /// so visual order equals logical order.
+    pub fn resolve(row: &[Cell]) -> Option<Self> {
+        if !row.iter().any(|cell| is_rtl_char(cell.c)) {
+            return None;
+        }
+        let units = units(row);
+        let classes = resolve_weak(units.iter().map(|u| bidi_class(row[u.col].c)).collect());
+        let levels = resolve_levels(&classes);
+
+        let mut rtl = vec![false; row.len()];
+        for (unit, &level) in units.iter().zip(&levels) {
+            rtl[unit.col..unit.col + unit.width].fill(level % 2 == 1);
+        }
+        let visual_to_logical = reorder(&units, &levels)
+            .into_iter()
+            .flat_map(|u| u.col..u.col + u.width)
+            .collect();
+        Some(Self {
+            visual_to_logical,
+            rtl,
+        })
+    }
+
+    /// Logical column displayed at `visual` column.
+    pub fn logical_col(&self, visual: usize) -> usize {
+        self.visual_to_logical
+            .get(visual)
+            .copied()
+            .unwrap_or(visual)
+    }
+
+    /// Whether logical column `col` belongs to a right-to-left run.
+    pub fn is_rtl(&self, col: usize) -> bool {
+        self.rtl.get(col).copied().unwrap_or(false)
+    }
+}
+
+/// One reorderable unit: a narrow cell, or a wide char with its spacer.
+#[derive(Debug, Clone, Copy)]
+struct Unit {
+    col: usize,
+    width: usize,
+}
+
+fn units(row: &[Cell]) -> Vec<Unit> {
+    let mut out = Vec::with_capacity(row.len());
+    let mut col = 0;
+    while col < row.len() {
+        let wide = row[col].flags.contains(CellFlags::WIDE_CHAR) && col + 1 < row.len();
+        let width = if wide { 2 } else { 1 };
+        out.push(Unit { col, width });
+        col += width;
+    }
+    out
+}
+
+/// Weak type rules W1–W7 with start/end of sequence both `L`.
+fn resolve_weak(mut classes: Vec<BidiClass>) -> Vec<BidiClass> {
+    use BidiClass::{AL, AN, CS, EN, ES, ET, L, NSM, ON, R};
+
+    // W1: marks take the class of what they follow. W2/W3: numbers after
+    // Arabic letters are Arabic numbers, and AL becomes R.
+    let mut prev = L;
+    let mut last_strong = L;
+    for class in &mut classes {
+        if *class == NSM {
+            *class = prev;
+        }
+        match *class {
+            L | R | AL => last_strong = *class,
+            EN if last_strong == AL => *class = AN,
+            _ => {}
+        }
+        prev = *class;
+    }
+    for class in &mut classes {
+        if *class == AL {
+            *class = R;
+        }
+    }
+
+    // W4: a single separator between two numbers of the same kind joins them.
+    for i in 1..classes.len().saturating_sub(1) {
+        let (before, after) = (classes[i - 1], classes[i + 1]);
+        let joined = match classes[i] {
+            ES => before == EN && after == EN,
+            CS => before == after && matches!(before, EN | AN),
+            _ => false,
+        };
+        if joined {
+            classes[i] = before;
+        }
+    }
+
+    // W5: terminators next to European numbers become numbers.
+    let mut i = 0;
+    while i < classes.len() {
+        if classes[i] != ET {
+            i += 1;
+            continue;
+        }
+        let end = classes[i..]
+            .iter()
+            .position(|&c| c != ET)
+            .map_or(classes.len(), |n| i + n);
+        let touches_en = (i > 0 && classes[i - 1] == EN) || classes.get(end) == Some(&EN);
+        if touches_en {
+            classes[i..end].fill(EN);
+        }
+        i = end;
+    }
+
+    // W6: leftover separators and terminators are neutral.
+    // W7: European numbers in a left-to-right context are L.
+    let mut last_strong = L;
+    for class in &mut classes {
+        match *class {
+            ES | ET | CS => *class = ON,
+            L | R => last_strong = *class,
+            EN if last_strong == L => *class = L,
+            _ => {}
+        }
+    }
+    classes
+}
+
+/// Neutral rules N1–N2, implicit levels I1, and trailing-whitespace L1.
+fn resolve_levels(classes: &[BidiClass]) -> Vec<u8> {
+    let mut levels = vec![0u8; classes.len()];
+    let mut i = 0;
+    while i < classes.len() {
+        let class = classes[i];
+        if !class.is_neutral() {
+            levels[i] = match class {
+                BidiClass::R => 1,
+                BidiClass::EN | BidiClass::AN => 2,
+                _ => 0,
+            };
+            i += 1;
+            continue;
+        }
+        // A run of neutrals is RTL only when RTL text surrounds it.
+        let end = classes[i..]
+            .iter()
+            .position(|c| !c.is_neutral())
+            .map_or(classes.len(), |n| i + n);
+        let before_rtl = i > 0 && classes[i - 1].is_rtl_for_neutrals();
+        let after_rtl = end < classes.len() && classes[end].is_rtl_for_neutrals();
+        levels[i..end].fill(u8::from(before_rtl && after_rtl));
+        i = end;
+    }
+
+    // L1: trailing whitespace and tabs return to the paragraph level.
+    for (level, class) in levels.iter_mut().zip(classes).rev() {
+        if !matches!(class, BidiClass::WS | BidiClass::S | BidiClass::BN) {
+            break;
+        }
+        *level = 0;
+    }
+    for (level, class) in levels.iter_mut().zip(classes) {
+        if *class == BidiClass::S {
+            *level = 0;
+        }
+    }
+    levels
+}
+
+/// L2: reverse every maximal run at each level from the highest down to 1.
+fn reorder(units: &[Unit], levels: &[u8]) -> Vec<Unit> {
+    let mut order: Vec<(Unit, u8)> = units.iter().copied().zip(levels.iter().copied()).collect();
+    let max = levels.iter().copied().max().unwrap_or(0);
+    for level in (1..=max).rev() {
+        let mut i = 0;
+        while i < order.len() {
+            if order[i].1 < level {
+                i += 1;
+                continue;
+            }
+            let end = order[i..]
+                .iter()
+                .position(|&(_, l)| l < level)
+                .map_or(order.len(), |n| i + n);
+            order[i..end].reverse();
+            i = end;
+        }
+    }
+    order.into_iter().map(|(unit, _)| unit).collect()
+}
@@ -0,0 +1,95 @@
+use unicode_width::UnicodeWidthChar;
+
+use crate::cell::{Cell, CellFlags};
+
+use super::BidiLine;
+
+/// Lay `text` out as grid cells, wide chars followed by their spacer.
+fn row(text: &str) -> Vec<Cell> {
+    let mut cells = Vec::new();
+    for c in text.chars() {
+        if c.width() == Some(2) {
+            cells.push(Cell {
+                c,
+                flags: CellFlags::WIDE_CHAR,
+                ..Cell::default()
+            });
+            cells.push(Cell {
+                flags: CellFlags::WIDE_CHAR_SPACER,
+                ..Cell::default()
+            });
+        } else {
+            cells.push(Cell {
+                c,
+                ..Cell::default()
+            });
+        }
+    }
+    cells
+}
+
+/// The row as displayed, left to right.
+fn visual(text: &str) -> String {
+    let cells = row(text);
+    let line = BidiLine::resolve(&cells).expect("row has RTL text");
+    (0..cells.len())
+        .map(|v| &cells[line.logical_col(v)])
+        .filter(|cell| !cell.flags.contains(CellFlags::WIDE_CHAR_SPACER))
+        .map(|cell| cell.c)
+        .collect()
+}
+
+#[test]
+fn ltr_rows_skip_resolution() {
+    assert_eq!(BidiLine::resolve(&row("plain ascii 123 text")), None);
+    assert_eq!(BidiLine::resolve(&row("日本語 ünïcödé")), None);
+}
+
+#[test]
+fn hebrew_run_reversed_within_ltr_line() {
+    assert_eq!(visual("abc אבג def"), "abc גבא def");
+    assert_eq!(visual("שלום"), "םולש");
+}
+
+#[test]
+fn numbers_keep_their_order_inside_rtl_text() {
+    assert_eq!(visual("אב 123 גד"), "דג 123 בא");
+    // A separator between digits joins them into one number (W4).
+    assert_eq!(visual("א 1.5 ב"), "ב 1.5 א");
+    // Arabic-Indic digits after Arabic letters are Arabic numbers (W2).
+    assert_eq!(visual("عدد ٤٢"), "٤٢ ددع");
+}
+
+#[test]
+fn neutrals_between_directions_follow_the_line() {
+    // Punctuation between RTL and LTR text takes the paragraph direction.
+    assert_eq!(visual("אב, cd"), "בא, cd");
+    // Trailing blanks stay at the end of the row (L1).
+    assert_eq!(visual("אב   "), "בא   ");
+}
+
+#[test]
+fn directional_marks_are_strong() {
+    // An LRM splits the Hebrew letters into two runs.
+    assert_eq!(visual("א\u{200E}ב"), "א\u{200E}ב");
+    // An RLM pulls the `!` into the Hebrew run.
+    assert_eq!(visual("א!\u{200F}"), "\u{200F}!א");
+}
+
+#[test]
+fn wide_chars_stay_paired_with_their_spacer() {
+    let cells = row("א😀ב");
+    let line = BidiLine::resolve(&cells).expect("row has RTL text");
+    let order: Vec<usize> = (0..cells.len()).map(|v| line.logical_col(v)).collect();
+    assert_eq!(order, [3, 1, 2, 0]);
+}
+
+#[test]
+fn rtl_flags_mark_runs_for_shaping() {
+    let line = BidiLine::resolve(&row("ab אב 12")).expect("row has RTL text");
+    let rtl: Vec<bool> = (0..8).map(|col| line.is_rtl(col)).collect();
+    // The space before the number joins the RTL run; the digits themselves
+    // are an even (LTR) level and shape left to right.
+    assert_eq!(rtl, [false, false, false, true, true, true, false, false]);
+    assert_eq!(visual("ab אב 12"), "ab 12 בא");
+}
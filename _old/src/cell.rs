@@ -3,12 +3,19 @@
 use std::sync::Arc;
 
 use bitflags::bitflags;
-use vte::ansi::{Color, Hyperlink};
+use unicode_width::UnicodeWidthStr;
+use vte::ansi::Color;
+
+use crate::hyperlink::Link;
+
+/// Variation Selector 16: requests emoji (two-cell) presentation of the
+/// preceding character.
+pub const VS16: char = '\u{FE0F}';
 
 bitflags! {
     /// Bitflags for cell text attributes and layout hints.
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
-    pub struct CellFlags: u16 {
+    pub struct CellFlags: u32 {
         const BOLD                    = 0b0000_0000_0001;
         const DIM                     = 0b0000_0000_0010;
         const ITALIC                  = 0b0000_0000_0100;
@@ -25,6 +32,8 @@ bitflags! {
         const WIDE_CHAR_SPACER        = 0b0010_0000_0000_0000;
         const WRAPLINE                = 0b0100_0000_0000_0000;
         const LEADING_WIDE_CHAR_SPACER = 0b1000_0000_0000_0000;
+        const OVERLINE                = 0b0001_0000_0000_0000_0000;
+        const BLINK_FAST              = 0b0010_0000_0000_0000_0000;
     }
 }
 
@@ -35,27 +44,48 @@ impl CellFlags {
         .union(Self::UNDERCURL)
         .union(Self::DOTTED_UNDERLINE)
         .union(Self::DASHED_UNDERLINE);
+
+    /// Slow (SGR 5) or fast (SGR 6) blink.
+    pub const ANY_BLINK: Self = Self::BLINK.union(Self::BLINK_FAST);
+
+    /// Each flag `oriterm_core` cells also carry, with its core name.
+    const CORE: [(Self, oriterm_core::CellFlags); 15] = [
+        (Self::BOLD, oriterm_core::CellFlags::BOLD),
+        (Self::DIM, oriterm_core::CellFlags::DIM),
+        (Self::ITALIC, oriterm_core::CellFlags::ITALIC),
+        (Self::UNDERLINE, oriterm_core::CellFlags::UNDERLINE),
+        (Self::DOUBLE_UNDERLINE, oriterm_core::CellFlags::DOUBLE_UNDERLINE),
+        (Self::UNDERCURL, oriterm_core::CellFlags::CURLY_UNDERLINE),
+        (Self::DOTTED_UNDERLINE, oriterm_core::CellFlags::DOTTED_UNDERLINE),
+        (Self::DASHED_UNDERLINE, oriterm_core::CellFlags::DASHED_UNDERLINE),
+        (Self::BLINK, oriterm_core::CellFlags::BLINK),
+        (Self::INVERSE, oriterm_core::CellFlags::INVERSE),
+        (Self::HIDDEN, oriterm_core::CellFlags::HIDDEN),
+        (Self::STRIKEOUT, oriterm_core::CellFlags::STRIKETHROUGH),
+        (Self::WIDE_CHAR, oriterm_core::CellFlags::WIDE_CHAR),
+        (Self::WIDE_CHAR_SPACER, oriterm_core::CellFlags::WIDE_CHAR_SPACER),
+        (Self::WRAPLINE, oriterm_core::CellFlags::WRAP),
+    ];
+
+    /// These flags as `oriterm_core` spells them; flags the core has no
+    /// name for are dropped.
+    pub fn to_core(self) -> oriterm_core::CellFlags {
+        Self::CORE
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .fold(oriterm_core::CellFlags::empty(), |acc, (_, core)| acc | *core)
+    }
 }
 
 /// Extended cell data stored out-of-line (combining marks, hyperlinks, custom underline color).
-#[derive(Debug, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct CellExtra {
     pub zerowidth: Vec<char>,
     pub underline_color: Option<Color>,
-    pub hyperlink: Option<Hyperlink>,
-}
-
-impl Clone for CellExtra {
-    fn clone(&self) -> Self {
-        Self {
-            zerowidth: self.zerowidth.clone(),
-            underline_color: self.underline_color,
-            hyperlink: self.hyperlink.as_ref().map(|h| Hyperlink {
-                id: h.id.clone(),
-                uri: h.uri.clone(),
-            }),
-        }
-    }
+    /// Interned OSC 8 link, shared with every other cell of the same link.
+    pub hyperlink: Option<Arc<Link>>,
+    /// OSC 66 text scale: the glyph fills a block this many rows tall.
+    pub text_scale: Option<u8>,
 }
 
 /// A single grid cell with character, colors, attributes, and optional extended data.
@@ -109,14 +139,30 @@ impl Cell {
         }
     }
 
+    /// Whether this cell is narrow but holds an emoji presentation sequence
+    /// (a base followed by [`VS16`], e.g. `❤️`) that renders two cells wide.
+    pub fn is_narrow_emoji_presentation(&self) -> bool {
+        if self.flags.contains(CellFlags::WIDE_CHAR) || !self.zerowidth().contains(&VS16) {
+            return false;
+        }
+        let mut cluster = String::from(self.c);
+        cluster.extend(self.zerowidth());
+        UnicodeWidthStr::width(cluster.as_str()) >= 2
+    }
+
     /// Returns the custom underline color if set.
     pub fn underline_color(&self) -> Option<Color> {
         self.extra.as_ref().and_then(|e| e.underline_color)
     }
 
+    /// OSC 66 text scale, 1 for normal text.
+    pub fn text_scale(&self) -> u8 {
+        self.extra.as_ref().and_then(|e| e.text_scale).unwrap_or(1)
+    }
+
     /// Returns the hyperlink associated with this cell.
-    pub fn hyperlink(&self) -> Option<&Hyperlink> {
-        self.extra.as_ref().and_then(|e| e.hyperlink.as_ref())
+    pub fn hyperlink(&self) -> Option<&Link> {
+        self.extra.as_ref().and_then(|e| e.hyperlink.as_deref())
     }
 
     // Operations
@@ -153,8 +199,20 @@ impl Cell {
         Arc::make_mut(extra).underline_color = color;
     }
 
+    /// Sets the OSC 66 text scale for this cell.
+    pub fn set_text_scale(&mut self, scale: u8) {
+        let scale = (scale > 1).then_some(scale);
+        if scale.is_none() && self.extra.is_none() {
+            return;
+        }
+        let extra = self
+            .extra
+            .get_or_insert_with(|| Arc::new(CellExtra::default()));
+        Arc::make_mut(extra).text_scale = scale;
+    }
+
     /// Sets the hyperlink for this cell.
-    pub fn set_hyperlink(&mut self, hyperlink: Option<Hyperlink>) {
+    pub fn set_hyperlink(&mut self, hyperlink: Option<Arc<Link>>) {
         if hyperlink.is_none() && self.extra.is_none() {
             return;
         }
@@ -177,7 +235,7 @@ mod tests {
     #[test]
     fn cell_size() {
         // Cell should be reasonably compact. With Option<Arc<CellExtra>> it's:
-        // char(4) + Color(4) + Color(4) + CellFlags(2) + padding(2) + Option<Arc>(8) = 24
+        // char(4) + Color(4) + Color(4) + CellFlags(4) + Option<Arc>(8) = 24
         assert!(
             size_of::<Cell>() <= 32,
             "Cell is {} bytes",
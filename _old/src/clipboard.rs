@@ -2,6 +2,9 @@
 //!
 //! On Windows, uses `clipboard-win` for real clipboard operations.
 //! On other platforms, uses `arboard` for real clipboard operations.
+//! Linux also has a primary selection, which middle-click pastes.
+
+use serde::{Deserialize, Serialize};
 
 /// Read text from the system clipboard.
 #[cfg(windows)]
@@ -28,3 +31,43 @@ pub fn set_text(text: &str) -> bool {
         .and_then(|mut cb| cb.set_text(text.to_owned()))
         .is_ok()
 }
+
+/// Read the primary selection (X11/Wayland).
+#[cfg(target_os = "linux")]
+pub fn get_primary() -> Option<String> {
+    use arboard::{GetExtLinux, LinuxClipboardKind};
+    let mut cb = arboard::Clipboard::new().ok()?;
+    cb.get().clipboard(LinuxClipboardKind::Primary).text().ok()
+}
+
+/// Read the primary selection: the clipboard, on platforms without one.
+#[cfg(not(target_os = "linux"))]
+pub fn get_primary() -> Option<String> {
+    get_text()
+}
+
+/// Offer `text` as the primary selection (X11/Wayland).
+#[cfg(target_os = "linux")]
+pub fn set_primary(text: &str) {
+    use arboard::{LinuxClipboardKind, SetExtLinux};
+    if let Ok(mut cb) = arboard::Clipboard::new() {
+        let _ = cb.set().clipboard(LinuxClipboardKind::Primary).text(text.to_owned());
+    }
+}
+
+/// No primary selection on this platform; nothing to do.
+#[cfg(not(target_os = "linux"))]
+pub fn set_primary(_text: &str) {}
+
+/// Whether programs may write the system clipboard with OSC 52.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipboardAccess {
+    /// Confirm the first write from each tab; the answer sticks to the tab.
+    #[default]
+    Ask,
+    /// Accept writes without asking.
+    Allow,
+    /// Ignore writes.
+    Deny,
+}
@@ -0,0 +1,81 @@
+//! In-memory ring of recently copied text, for pasting an earlier copy
+//! (`Action::PasteFromHistory`). Nothing is written to disk.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+/// Longest label shown for an entry in the history menu, in characters.
+const LABEL_CHARS: usize = 48;
+
+/// The most recent copies, newest first.
+#[derive(Debug, Default)]
+pub struct ClipboardHistory {
+    entries: VecDeque<String>,
+    /// Entries kept; 0 turns the history off.
+    capacity: usize,
+}
+
+impl ClipboardHistory {
+    /// An empty history keeping up to `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Change how many entries are kept, dropping the oldest as needed.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.entries.truncate(capacity);
+    }
+
+    /// Record a copy. Copying text already in the history moves it to the
+    /// front instead of keeping it twice.
+    pub fn push(&mut self, text: &str) {
+        if self.capacity == 0 || text.is_empty() {
+            return;
+        }
+        if let Some(i) = self.entries.iter().position(|e| e == text) {
+            if let Some(entry) = self.entries.remove(i) {
+                self.entries.push_front(entry);
+            }
+            return;
+        }
+        self.entries.push_front(text.to_owned());
+        self.entries.truncate(self.capacity);
+    }
+
+    /// Entry `index`, 0 being the most recent copy.
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(String::as_str)
+    }
+
+    /// Entries, newest first.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(String::as_str)
+    }
+
+    /// Whether nothing has been copied (or the history is off).
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// One-line label for `text` in the history menu: its first non-blank
+/// line, shortened, with the count of lines when there are more.
+pub fn entry_label(text: &str) -> String {
+    let first = text.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("");
+    let mut label: String = first.chars().take(LABEL_CHARS).collect();
+    if first.chars().count() > LABEL_CHARS {
+        label.push('…');
+    }
+    let lines = text.lines().count();
+    if lines > 1 {
+        let _ = write!(label, "  ({lines} lines)");
+    }
+    label
+}
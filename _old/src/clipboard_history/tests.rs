@@ -0,0 +1,35 @@
+//! Tests for the clipboard history ring and its menu labels.
+
+use super::{ClipboardHistory, entry_label};
+
+#[test]
+fn keeps_newest_first_up_to_capacity() {
+    let mut history = ClipboardHistory::new(2);
+    history.push("one");
+    history.push("two");
+    history.push("three");
+    assert_eq!(history.iter().collect::<Vec<_>>(), ["three", "two"]);
+
+    // A repeat moves to the front.
+    history.push("two");
+    assert_eq!(history.get(0), Some("two"));
+    assert_eq!(history.get(1), Some("three"));
+
+    history.set_capacity(1);
+    assert_eq!(history.iter().collect::<Vec<_>>(), ["two"]);
+}
+
+#[test]
+fn zero_capacity_keeps_nothing() {
+    let mut history = ClipboardHistory::new(0);
+    history.push("secret");
+    assert!(history.is_empty());
+}
+
+#[test]
+fn label_is_the_first_line() {
+    assert_eq!(entry_label("  ls -la  "), "ls -la");
+    assert_eq!(entry_label("\nfn main() {\n}\n"), "fn main() {  (3 lines)");
+    let long = "x".repeat(60);
+    assert_eq!(entry_label(&long), format!("{}…", "x".repeat(48)));
+}
@@ -0,0 +1,172 @@
+//! Dead-key and compose-key sequences for platforms that deliver raw keys
+//! instead of composed text.
+//!
+//! Windows and macOS compose dead keys themselves, and X11 does when an
+//! input method or compose table is available. Without one, winit reports
+//! a [`Key::Dead`] followed by the plain base letter, and accented letters
+//! can't be typed. [`Composer`] fills that gap: a dead key followed by a
+//! single ASCII letter is composed here, while text the platform already
+//! composed passes through untouched. A configurable compose key
+//! (`behavior.compose_key`) starts two-character sequences in the style of
+//! X11's `Multi_key`: `' e` is é, `o a` is å, `s s` is ß.
+
+mod table;
+
+use serde::{Deserialize, Serialize};
+use winit::keyboard::{Key, KeyLocation, NamedKey};
+
+use table::{Accent, compose_pair};
+
+/// Which key starts a compose sequence.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComposeKey {
+    /// No compose key; dead keys still work.
+    #[default]
+    Off,
+    /// The right Alt (or `AltGr`) key.
+    RightAlt,
+    /// The right Control key.
+    RightCtrl,
+    /// The context menu key.
+    Menu,
+    /// Scroll Lock.
+    ScrollLock,
+    /// Pause/Break.
+    Pause,
+    /// Caps Lock.
+    CapsLock,
+}
+
+impl ComposeKey {
+    /// Whether a key press is this compose key.
+    pub fn matches(self, key: &Key, location: KeyLocation) -> bool {
+        let Key::Named(named) = key else {
+            return false;
+        };
+        let right = location == KeyLocation::Right;
+        match self {
+            Self::Off => false,
+            Self::RightAlt => *named == NamedKey::AltGraph || (*named == NamedKey::Alt && right),
+            Self::RightCtrl => *named == NamedKey::Control && right,
+            Self::Menu => *named == NamedKey::ContextMenu,
+            Self::ScrollLock => *named == NamedKey::ScrollLock,
+            Self::Pause => *named == NamedKey::Pause,
+            Self::CapsLock => *named == NamedKey::CapsLock,
+        }
+    }
+}
+
+/// What the [`Composer`] did with a key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComposeOutcome {
+    /// Not part of a sequence; handle the key normally.
+    Pass,
+    /// Used by the sequence; nothing to type yet.
+    Consumed,
+    /// The sequence finished; type this text.
+    Commit(String),
+}
+
+/// Where a sequence stands.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Pending {
+    #[default]
+    Idle,
+    /// A dead key waits for its base letter.
+    Dead(Accent),
+    /// The compose key was pressed.
+    Compose,
+    /// The compose key and one character were pressed.
+    ComposeFirst(char),
+}
+
+/// Dead-key and compose-key state for one keyboard.
+#[derive(Debug, Default)]
+pub struct Composer {
+    pending: Pending,
+}
+
+impl Composer {
+    /// Whether a sequence is in progress.
+    pub fn is_composing(&self) -> bool {
+        self.pending != Pending::Idle
+    }
+
+    /// The compose key was pressed: start a sequence, or abandon the one in
+    /// progress.
+    pub fn compose_key(&mut self) -> ComposeOutcome {
+        self.pending = match self.pending {
+            Pending::Idle => Pending::Compose,
+            _ => Pending::Idle,
+        };
+        ComposeOutcome::Consumed
+    }
+
+    /// A dead key was pressed. `c` is the accent as winit reports it, in
+    /// spacing or combining form. Pressing the same dead key twice types
+    /// the accent itself.
+    pub fn dead_key(&mut self, c: Option<char>) -> ComposeOutcome {
+        let Some(accent) = c.and_then(Accent::from_dead) else {
+            return ComposeOutcome::Consumed;
+        };
+        match std::mem::take(&mut self.pending) {
+            Pending::Idle => {
+                self.pending = Pending::Dead(accent);
+                ComposeOutcome::Consumed
+            }
+            Pending::Dead(prev) => {
+                if prev != accent {
+                    self.pending = Pending::Dead(accent);
+                }
+                ComposeOutcome::Commit(prev.spacing().to_string())
+            }
+            Pending::Compose => {
+                self.pending = Pending::ComposeFirst(accent.spacing());
+                ComposeOutcome::Consumed
+            }
+            Pending::ComposeFirst(first) => finish_compose(first, accent.spacing()),
+        }
+    }
+
+    /// A key typed `text`.
+    pub fn text(&mut self, text: &str) -> ComposeOutcome {
+        let Some(c) = text.chars().next() else {
+            return ComposeOutcome::Pass;
+        };
+        match std::mem::take(&mut self.pending) {
+            Pending::Idle => ComposeOutcome::Pass,
+            // The platform composed it already (or typed the accent and
+            // the letter), so type exactly what it sent.
+            Pending::Dead(_) if text.len() > 1 || !c.is_ascii() => ComposeOutcome::Pass,
+            Pending::Dead(accent) => {
+                let typed = match accent.compose(c) {
+                    Some(composed) => composed.to_string(),
+                    None if c == ' ' => accent.spacing().to_string(),
+                    None => format!("{}{c}", accent.spacing()),
+                };
+                ComposeOutcome::Commit(typed)
+            }
+            Pending::Compose => {
+                self.pending = Pending::ComposeFirst(c);
+                ComposeOutcome::Consumed
+            }
+            Pending::ComposeFirst(first) => finish_compose(first, c),
+        }
+    }
+
+    /// Abandon the sequence in progress.
+    pub fn cancel(&mut self) {
+        self.pending = Pending::Idle;
+    }
+}
+
+/// End a compose sequence. Unknown sequences type nothing, as on X11.
+fn finish_compose(first: char, second: char) -> ComposeOutcome {
+    compose_pair(first, second).map_or(ComposeOutcome::Consumed, |c| {
+        ComposeOutcome::Commit(c.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests;
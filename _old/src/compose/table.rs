@@ -0,0 +1,158 @@
+//! Accents and compose sequences.
+
+/// A diacritic that a dead key or compose sequence adds to a letter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Accent {
+    Grave,
+    Acute,
+    Circumflex,
+    Tilde,
+    Diaeresis,
+    Cedilla,
+    Ring,
+    Caron,
+    Macron,
+    Breve,
+    Ogonek,
+    DoubleAcute,
+    DotAbove,
+}
+
+impl Accent {
+    /// The accent a dead key stands for. Platforms report either the
+    /// spacing or the combining form.
+    pub(super) fn from_dead(c: char) -> Option<Self> {
+        Some(match c {
+            '`' | '\u{300}' => Self::Grave,
+            '\'' | '´' | '\u{301}' => Self::Acute,
+            '^' | '\u{302}' => Self::Circumflex,
+            '~' | '\u{303}' => Self::Tilde,
+            '"' | '¨' | '\u{308}' => Self::Diaeresis,
+            ',' | '¸' | '\u{327}' => Self::Cedilla,
+            '°' | '˚' | '\u{30a}' => Self::Ring,
+            'ˇ' | '\u{30c}' => Self::Caron,
+            '¯' | '\u{304}' => Self::Macron,
+            '˘' | '\u{306}' => Self::Breve,
+            '˛' | '\u{328}' => Self::Ogonek,
+            '˝' | '\u{30b}' => Self::DoubleAcute,
+            '˙' | '\u{307}' => Self::DotAbove,
+            _ => return None,
+        })
+    }
+
+    /// The accent a character stands for in a compose sequence.
+    fn from_compose(c: char) -> Option<Self> {
+        Some(match c {
+            'o' | 'O' => Self::Ring,
+            '<' => Self::Caron,
+            '-' | '_' => Self::Macron,
+            'U' | 'u' => Self::Breve,
+            ';' => Self::Ogonek,
+            '=' => Self::DoubleAcute,
+            '.' => Self::DotAbove,
+            _ => return Self::from_dead(c),
+        })
+    }
+
+    /// The accent on its own, typed when it has nothing to combine with.
+    pub(super) fn spacing(self) -> char {
+        match self {
+            Self::Grave => '`',
+            Self::Acute => '´',
+            Self::Circumflex => '^',
+            Self::Tilde => '~',
+            Self::Diaeresis => '¨',
+            Self::Cedilla => '¸',
+            Self::Ring => '°',
+            Self::Caron => 'ˇ',
+            Self::Macron => '¯',
+            Self::Breve => '˘',
+            Self::Ogonek => '˛',
+            Self::DoubleAcute => '˝',
+            Self::DotAbove => '˙',
+        }
+    }
+
+    /// Base letters followed by their accented forms.
+    fn letters(self) -> &'static str {
+        match self {
+            Self::Grave => "aàeèiìoòuùnǹwẁyỳAÀEÈIÌOÒUÙNǸWẀYỲ",
+            Self::Acute => "aáeéiíoóuúyýcćgǵlĺnńrŕsśzźwẃAÁEÉIÍOÓUÚYÝCĆGǴLĹNŃRŔSŚZŹWẂ",
+            Self::Circumflex => "aâeêiîoôuûcĉgĝhĥjĵsŝwŵyŷAÂEÊIÎOÔUÛCĈGĜHĤJĴSŜWŴYŶ",
+            Self::Tilde => "aãnñoõiĩuũAÃNÑOÕIĨUŨ",
+            Self::Diaeresis => "aäeëiïoöuüyÿAÄEËIÏOÖUÜYŸ",
+            Self::Cedilla => "cçsşgģkķlļnņrŗtţCÇSŞGĢKĶLĻNŅRŖTŢ",
+            Self::Ring => "aåuůAÅUŮ",
+            Self::Caron => "cčdďeěnňrřsštťzžCČDĎEĚNŇRŘSŠTŤZŽ",
+            Self::Macron => "aāeēiīoōuūAĀEĒIĪOŌUŪ",
+            Self::Breve => "aăgğuŭAĂGĞUŬ",
+            Self::Ogonek => "aąeęiįuųAĄEĘIĮUŲ",
+            Self::DoubleAcute => "oőuűOŐUŰ",
+            Self::DotAbove => "cċeėgġzżCĊEĖGĠIİZŻ",
+        }
+    }
+
+    /// `base` with this accent, if that letter exists.
+    pub(super) fn compose(self, base: char) -> Option<char> {
+        let mut chars = self.letters().chars();
+        while let (Some(plain), Some(accented)) = (chars.next(), chars.next()) {
+            if plain == base {
+                return Some(accented);
+            }
+        }
+        None
+    }
+}
+
+/// Compose sequences that aren't an accent on a letter. Either order works.
+const SYMBOLS: &[(char, char, char)] = &[
+    ('s', 's', 'ß'),
+    ('a', 'e', 'æ'),
+    ('A', 'E', 'Æ'),
+    ('o', 'e', 'œ'),
+    ('O', 'E', 'Œ'),
+    ('o', '/', 'ø'),
+    ('O', '/', 'Ø'),
+    ('t', 'h', 'þ'),
+    ('T', 'H', 'Þ'),
+    ('d', 'h', 'ð'),
+    ('D', 'H', 'Ð'),
+    ('<', '<', '«'),
+    ('>', '>', '»'),
+    ('!', '!', '¡'),
+    ('?', '?', '¿'),
+    ('=', 'e', '€'),
+    ('=', 'E', '€'),
+    ('-', 'L', '£'),
+    ('=', 'Y', '¥'),
+    ('|', 'c', '¢'),
+    ('o', 'c', '©'),
+    ('o', 'r', '®'),
+    ('t', 'm', '™'),
+    ('o', 's', '§'),
+    ('p', '!', '¶'),
+    ('o', 'o', '°'),
+    ('+', '-', '±'),
+    ('x', 'x', '×'),
+    (':', '-', '÷'),
+    ('m', 'u', 'µ'),
+    ('1', '2', '½'),
+    ('1', '4', '¼'),
+    ('3', '4', '¾'),
+    ('.', '.', '…'),
+    ('^', '1', '¹'),
+    ('^', '2', '²'),
+    ('^', '3', '³'),
+];
+
+/// The character for compose key + `first` + `second`.
+pub(super) fn compose_pair(first: char, second: char) -> Option<char> {
+    let symbol = SYMBOLS
+        .iter()
+        .find(|&&(a, b, _)| (a, b) == (first, second) || (a, b) == (second, first));
+    if let Some(&(_, _, c)) = symbol {
+        return Some(c);
+    }
+    let accented = |accent: char, base: char| Accent::from_compose(accent)?.compose(base);
+    accented(first, second).or_else(|| accented(second, first))
+}
@@ -0,0 +1,80 @@
+use winit::keyboard::{Key, KeyLocation, NamedKey};
+
+use super::{ComposeKey, ComposeOutcome, Composer};
+
+fn commit(s: &str) -> ComposeOutcome {
+    ComposeOutcome::Commit(s.to_owned())
+}
+
+#[test]
+fn dead_key_composes_the_next_ascii_letter() {
+    let mut c = Composer::default();
+    assert_eq!(c.dead_key(Some('\'')), ComposeOutcome::Consumed);
+    assert!(c.is_composing());
+    assert_eq!(c.text("e"), commit("é"));
+    assert!(!c.is_composing());
+
+    // Combining forms are understood too.
+    c.dead_key(Some('\u{308}'));
+    assert_eq!(c.text("u"), commit("ü"));
+}
+
+#[test]
+fn dead_key_without_a_composition_types_the_accent() {
+    let mut c = Composer::default();
+    c.dead_key(Some('^'));
+    assert_eq!(c.text("q"), commit("^q"));
+    c.dead_key(Some('`'));
+    assert_eq!(c.text(" "), commit("`"));
+    c.dead_key(Some('~'));
+    assert_eq!(c.dead_key(Some('~')), commit("~"));
+    assert!(!c.is_composing());
+}
+
+#[test]
+fn text_the_platform_composed_passes_through() {
+    let mut c = Composer::default();
+    c.dead_key(Some('´'));
+    assert_eq!(c.text("é"), ComposeOutcome::Pass);
+    c.dead_key(Some('´'));
+    assert_eq!(c.text("´x"), ComposeOutcome::Pass);
+    assert!(!c.is_composing());
+    assert_eq!(c.text("a"), ComposeOutcome::Pass);
+}
+
+#[test]
+fn compose_key_sequences() {
+    let mut c = Composer::default();
+    let mut seq = |a: &str, b: &str| {
+        assert_eq!(c.compose_key(), ComposeOutcome::Consumed);
+        assert_eq!(c.text(a), ComposeOutcome::Consumed);
+        c.text(b)
+    };
+    assert_eq!(seq("'", "e"), commit("é"));
+    assert_eq!(seq("e", "'"), commit("é"));
+    assert_eq!(seq("o", "a"), commit("å"));
+    assert_eq!(seq("s", "s"), commit("ß"));
+    assert_eq!(seq("e", "="), commit("€"));
+    assert_eq!(seq("<", "s"), commit("š"));
+    assert_eq!(seq("q", "q"), ComposeOutcome::Consumed);
+}
+
+#[test]
+fn compose_key_twice_abandons_the_sequence() {
+    let mut c = Composer::default();
+    c.compose_key();
+    c.text("'");
+    c.compose_key();
+    assert!(!c.is_composing());
+    assert_eq!(c.text("e"), ComposeOutcome::Pass);
+}
+
+#[test]
+fn compose_key_matches_its_configured_key() {
+    let alt = Key::Named(NamedKey::Alt);
+    assert!(ComposeKey::RightAlt.matches(&alt, KeyLocation::Right));
+    assert!(!ComposeKey::RightAlt.matches(&alt, KeyLocation::Left));
+    let menu = Key::Named(NamedKey::ContextMenu);
+    assert!(ComposeKey::Menu.matches(&menu, KeyLocation::Standard));
+    assert!(!ComposeKey::Off.matches(&menu, KeyLocation::Standard));
+}
@@ -0,0 +1,154 @@
+//! User interaction behavior settings: selection, clicks, and keyboard.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::compose::ComposeKey;
+use crate::selection::ClickCycle;
+
+/// User interaction behavior configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+#[expect(
+    clippy::struct_excessive_bools,
+    reason = "Independent on/off behavior toggles"
+)]
+pub struct BehaviorConfig {
+    pub copy_on_select: bool,
+    /// Drop whitespace at the end of each copied line. When off, copies
+    /// keep the spaces programs printed, and block selections keep their
+    /// rectangular shape.
+    pub strip_trailing_whitespace: bool,
+    /// How many recent copies `PasteFromHistory` offers. Kept in memory
+    /// only; 0 turns the history off.
+    pub clipboard_history: usize,
+    /// Keep recent search queries in `search_history.txt` across launches,
+    /// not just for the session.
+    pub save_search_history: bool,
+    /// Reopen the tabs open at exit on the next launch, each in its last
+    /// directory.
+    pub restore_session: bool,
+    /// Lines of scrollback per tab saved with the session and restored as
+    /// history; 0 saves none.
+    pub session_scrollback_lines: usize,
+    pub bold_is_bright: bool,
+    pub shell_integration: bool,
+    /// Maximum delay between clicks of a double/triple/quadruple click.
+    pub double_click_ms: u64,
+    /// What a fifth rapid click does: `wrap` back to one, or `hold` at four.
+    pub click_cycle: ClickCycle,
+    /// Start new tabs in the active tab's OSC 7 directory.
+    pub inherit_cwd: bool,
+    /// Key that starts a compose sequence (`right_alt`, `menu`, ...).
+    pub compose_key: ComposeKey,
+    /// Keys whose OS auto-repeat is ignored, named as in `[[keybind]]`
+    /// (`Escape`, `F1`, `x`).
+    pub no_repeat: Vec<String>,
+    /// Command that opens a Ctrl+clicked file path, e.g.
+    /// `code -g {path}:{line}:{col}`. Files open with the system handler
+    /// when unset.
+    pub editor: Option<String>,
+    /// How long a keybinding prefix (e.g. Ctrl+A of Ctrl+A then C) waits
+    /// for the next key.
+    pub chord_timeout_ms: u64,
+    /// What middle-clicking a tab does.
+    pub middle_click_tab: MouseAction,
+    /// What double-clicking the empty part of the tab bar does.
+    pub double_click_tab_bar: MouseAction,
+    /// What middle-clicking the terminal does (unless the program reports
+    /// mouse clicks).
+    pub middle_click_grid: MouseAction,
+    /// Ask before closing a tab or window that is running a command.
+    pub confirm_close: bool,
+    /// Process names that make closing ask first (`vim`, `ssh`). Empty
+    /// asks for any command other than the shell.
+    pub confirm_close_processes: Vec<String>,
+}
+
+/// What a configurable mouse gesture does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MouseAction {
+    /// Nothing.
+    None,
+    /// Close the clicked tab (the active tab outside the tab bar).
+    CloseTab,
+    /// Open a new tab in the window.
+    NewTab,
+    /// Maximize or restore the window.
+    ToggleMaximize,
+    /// Paste the clipboard.
+    Paste,
+    /// Paste the primary selection: the text last selected in any program.
+    /// The clipboard where the platform has no primary selection.
+    PastePrimary,
+}
+
+impl MouseAction {
+    /// The default middle-click action in the terminal: paste the primary
+    /// selection on Linux, where that's the convention, else nothing.
+    fn default_middle_click_grid() -> Self {
+        if cfg!(target_os = "linux") {
+            Self::PastePrimary
+        } else {
+            Self::None
+        }
+    }
+}
+
+impl Default for BehaviorConfig {
+    fn default() -> Self {
+        Self {
+            copy_on_select: true,
+            strip_trailing_whitespace: true,
+            clipboard_history: 20,
+            save_search_history: false,
+            restore_session: false,
+            session_scrollback_lines: 0,
+            bold_is_bright: true,
+            shell_integration: true,
+            double_click_ms: 400,
+            click_cycle: ClickCycle::default(),
+            inherit_cwd: true,
+            compose_key: ComposeKey::default(),
+            no_repeat: Vec::new(),
+            editor: None,
+            chord_timeout_ms: 1500,
+            middle_click_tab: MouseAction::CloseTab,
+            double_click_tab_bar: MouseAction::NewTab,
+            middle_click_grid: MouseAction::default_middle_click_grid(),
+            confirm_close: true,
+            confirm_close_processes: Vec::new(),
+        }
+    }
+}
+
+impl BehaviorConfig {
+    /// Returns `double_click_ms` as a `Duration`, clamped to 50–2000 ms.
+    pub fn double_click_interval(&self) -> Duration {
+        Duration::from_millis(self.double_click_ms.clamp(50, 2000))
+    }
+
+    /// Returns `chord_timeout_ms` as a `Duration`, clamped to 200–10000 ms.
+    pub fn chord_timeout(&self) -> Duration {
+        Duration::from_millis(self.chord_timeout_ms.clamp(200, 10_000))
+    }
+
+    /// Whether closing a tab running `process` in the foreground asks
+    /// first. `None` is a command whose name the OS didn't report, which
+    /// only asks when no process list is configured.
+    pub fn confirms_close(&self, process: Option<&str>) -> bool {
+        if !self.confirm_close {
+            return false;
+        }
+        if self.confirm_close_processes.is_empty() {
+            return true;
+        }
+        process.is_some_and(|name| {
+            self.confirm_close_processes
+                .iter()
+                .any(|listed| listed.eq_ignore_ascii_case(name))
+        })
+    }
+}
@@ -0,0 +1,89 @@
+//! `[[host_rule]]` tables: restyle tabs connected to matching hosts.
+//!
+//! A rule matches a tab when every pattern it gives matches: `host`
+//! against the hostname the shell reports with OSC 7, `title` against the
+//! tab title (which names the running program on Windows and under shells
+//! that title tabs with it). The first matching rule applies.
+//!
+//! ```toml
+//! [[host_rule]]
+//! host = "*prod*"
+//! border = "#e0404a"
+//! tab_color = "#e0404a"
+//! scheme = "Dracula"
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// One `[[host_rule]]` entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HostRule {
+    /// Glob (`*`, `?`) matched against the OSC 7 hostname, ignoring case.
+    pub host: Option<String>,
+    /// Glob matched against the tab title, ignoring case.
+    pub title: Option<String>,
+    /// Color scheme the tab switches to while the rule matches.
+    pub scheme: Option<String>,
+    /// Color of a border drawn around the grid, as `#rrggbb`.
+    pub border: Option<String>,
+    /// Accent color of the tab in the tab bar, as `#rrggbb`. A color the
+    /// program sets with OSC 6 takes precedence.
+    pub tab_color: Option<String>,
+}
+
+impl HostRule {
+    /// Whether this rule applies to a tab on `host` titled `title`. A rule
+    /// without patterns never applies.
+    pub fn matches(&self, host: Option<&str>, title: &str) -> bool {
+        if self.host.is_none() && self.title.is_none() {
+            return false;
+        }
+        let host_ok = self
+            .host
+            .as_deref()
+            .is_none_or(|pattern| host.is_some_and(|h| glob_match(pattern, h)));
+        let title_ok = self
+            .title
+            .as_deref()
+            .is_none_or(|pattern| glob_match(pattern, title));
+        host_ok && title_ok
+    }
+}
+
+/// Index of the first rule in `rules` that applies to `host` and `title`.
+pub fn find_host_rule(rules: &[HostRule], host: Option<&str>, title: &str) -> Option<usize> {
+    rules.iter().position(|rule| rule.matches(host, title))
+}
+
+/// Match `text` against a glob where `*` is any run of characters and `?`
+/// any one character, ignoring ASCII case.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().map(|c| c.to_ascii_lowercase()).collect();
+    let text: Vec<char> = text.chars().map(|c| c.to_ascii_lowercase()).collect();
+    let (mut p, mut t) = (0, 0);
+    // Where the last `*` was and the text position it currently covers up to.
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                // Let the last `*` swallow one more character and retry.
+                Some((sp, st)) => {
+                    star = Some((sp, st + 1));
+                    p = sp + 1;
+                    t = st + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
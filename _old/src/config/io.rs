@@ -38,6 +38,18 @@ pub fn state_path() -> PathBuf {
     config_dir().join("state.toml")
 }
 
+/// Returns the path to the saved search history
+/// (`behavior.save_search_history`).
+pub fn search_history_path() -> PathBuf {
+    config_dir().join("search_history.txt")
+}
+
+/// Returns the path to the tabs saved for the next launch
+/// (`behavior.restore_session`).
+pub fn session_path() -> PathBuf {
+    config_dir().join("session.toml")
+}
+
 /// Persisted window geometry — saved on exit, restored on launch.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
 pub struct WindowState {
@@ -117,6 +129,8 @@ pub fn parse_cursor_style(s: &str) -> CursorShape {
     match s.to_ascii_lowercase().as_str() {
         "bar" | "beam" => CursorShape::Beam,
         "underline" => CursorShape::Underline,
+        "hollow" | "hollow_block" => CursorShape::HollowBlock,
+        "hidden" => CursorShape::Hidden,
         _ => CursorShape::Block,
     }
 }
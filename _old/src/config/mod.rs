@@ -1,16 +1,27 @@
 //! Configuration structures and loading logic.
 
+mod behavior;
+mod host_rule;
 mod io;
 pub mod monitor;
 
-pub use io::{WindowState, config_dir, config_path, parse_cursor_style, state_path};
+pub use behavior::{BehaviorConfig, MouseAction};
+pub use host_rule::{HostRule, find_host_rule};
+pub use io::{
+    WindowState, config_dir, config_path, parse_cursor_style, search_history_path, session_path,
+    state_path,
+};
 
 use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::keybindings::KeybindConfig;
-use crate::render;
+use crate::clipboard::ClipboardAccess;
+use crate::grid::ClipEdge;
+use crate::keybindings::{KeybindConfig, KeybindProfile};
+use crate::palette::CubeRanges;
+use crate::render::{self, TextBlink};
+use crate::url_detect::RuleConfig;
 
 /// Top-level configuration structure.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -22,8 +33,17 @@ pub struct Config {
     pub window: WindowConfig,
     pub behavior: BehaviorConfig,
     pub bell: BellConfig,
+    pub tab_badge: TabBadgeConfig,
+    pub notifications: NotificationConfig,
+    /// Platform whose default bindings `keybind` layers onto; `auto`
+    /// picks the one oriterm runs on.
+    pub keybind_profile: KeybindProfile,
     #[serde(default)]
     pub keybind: Vec<KeybindConfig>,
+    #[serde(default)]
+    pub rules: Vec<RuleConfig>,
+    #[serde(default)]
+    pub host_rule: Vec<HostRule>,
 }
 
 /// Per-fallback font configuration.
@@ -73,12 +93,54 @@ pub struct FontConfig {
 /// Terminal behavior configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
+#[expect(clippy::struct_excessive_bools, reason = "Independent on/off behavior toggles")]
 pub struct TerminalConfig {
     pub shell: Option<String>,
     pub scrollback: usize,
     pub cursor_style: String,
     pub cursor_blink: bool,
     pub cursor_blink_interval_ms: u64,
+    /// How long the cursor stays solid after a keystroke, output, or focus
+    /// before it starts blinking again.
+    pub cursor_blink_pause_ms: u64,
+    /// Cursor shape while the window is unfocused (same names as
+    /// `cursor_style`, plus `hollow` and `hidden`). It never blinks.
+    pub cursor_unfocused_style: String,
+    /// Cursor color while the window is unfocused, as "#RRGGBB". Uses the
+    /// focused cursor color when unset.
+    pub cursor_unfocused_color: Option<String>,
+    /// How blinking text (SGR 5/6) is drawn: `hide`, `dim`, or `off`.
+    pub text_blink: TextBlink,
+    /// Slow text blink half-period; fast blink toggles twice as often.
+    pub text_blink_interval_ms: u64,
+    /// Which edge of the alt screen is clipped when the window gets shorter:
+    /// `top` keeps the rows around the cursor, `bottom` keeps the top rows.
+    pub alt_screen_clip: ClipEdge,
+    /// String sent back when the program writes ENQ (0x05). Empty = no reply.
+    pub answerback: String,
+    /// Treat raw 8-bit C1 bytes (0x80–0x9F) in output as controls, for
+    /// programs that send S8C1T-style CSI, OSC, NEL, and the like. Off by
+    /// default, since such bytes in Latin-1 or binary output would start
+    /// sequences that swallow the text after them.
+    pub c1_controls: bool,
+    /// Whether copying a selection includes concealed (SGR 8) text. When
+    /// false, concealed cells are copied as spaces.
+    pub copy_concealed: bool,
+    /// Reorder right-to-left text (Arabic, Hebrew) for display. Programs
+    /// can still turn it off per terminal with BDSM (`CSI 8 l`).
+    pub bidi: bool,
+    /// Whether programs may write the clipboard with OSC 52: `ask`
+    /// (confirm once per tab), `allow`, or `deny`.
+    pub osc52: ClipboardAccess,
+    /// Largest OSC 52 write accepted, in bytes of decoded text.
+    pub osc52_max_bytes: usize,
+    /// Draw the echo of a keystroke as soon as it is parsed instead of
+    /// waiting for the window's next frame slot. Lowers typing latency at
+    /// the cost of extra frames.
+    pub immediate_echo: bool,
+    /// Shortest command, in milliseconds, whose run time is shown at the
+    /// right of its prompt ("took 3.2s"). `0` turns the annotation off.
+    pub command_duration_ms: u64,
 }
 
 /// Alpha blending mode for text rendering.
@@ -119,6 +181,11 @@ pub struct ColorConfig {
     /// Only indices present are overridden.
     #[serde(default)]
     pub bright: HashMap<String, String>,
+    /// Channel levels for the 6x6x6 color cube (indices 16-231).
+    /// Default: xterm's `[0, 95, 135, 175, 215, 255]`.
+    pub cube_levels: Option<[u8; 6]>,
+    /// Grayscale ramp (indices 232-255) as `[start, step]`. Default: `[8, 10]`.
+    pub grayscale_ramp: Option<[u8; 2]>,
 }
 
 impl Default for ColorConfig {
@@ -134,17 +201,41 @@ impl Default for ColorConfig {
             selection_background: None,
             ansi: HashMap::new(),
             bright: HashMap::new(),
+            cube_levels: None,
+            grayscale_ramp: None,
         }
     }
 }
 
 impl ColorConfig {
+    /// Returns the 256-color cube ranges, falling back to xterm's values.
+    pub fn cube_ranges(&self) -> CubeRanges {
+        let mut ranges = CubeRanges::default();
+        if let Some(levels) = self.cube_levels {
+            ranges.levels = levels;
+        }
+        if let Some([start, step]) = self.grayscale_ramp {
+            ranges.gray_start = start;
+            ranges.gray_step = step;
+        }
+        ranges
+    }
+
     /// Returns `minimum_contrast` clamped to [1.0, 21.0].
     pub fn effective_minimum_contrast(&self) -> f32 {
         self.minimum_contrast.clamp(1.0, 21.0)
     }
 }
 
+/// `window.title_format` placeholder for the active tab's title.
+const TITLE_ACTIVE_TITLE: &str = "{active_title}";
+
+/// `window.title_format` placeholder for the window's tab count.
+const TITLE_TAB_COUNT: &str = "{tab_count}";
+
+/// Default `window.app_id`.
+const DEFAULT_APP_ID: &str = "oriterm";
+
 /// Window size and opacity configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -154,6 +245,13 @@ pub struct WindowConfig {
     pub opacity: f32,
     pub tab_bar_opacity: Option<f32>,
     pub blur: bool,
+    /// OS window title. `{active_title}` is replaced by the active tab's
+    /// title and `{tab_count}` by the number of tabs in the window.
+    pub title_format: String,
+    /// Window class: `WM_CLASS` on X11, the app ID on Wayland, and the
+    /// `AppUserModelID` on Windows. Window managers and taskbars group and
+    /// match windows by it. `--class` overrides it.
+    pub app_id: String,
 }
 
 impl Default for WindowConfig {
@@ -164,11 +262,35 @@ impl Default for WindowConfig {
             opacity: 1.0,
             tab_bar_opacity: None,
             blur: true,
+            title_format: TITLE_ACTIVE_TITLE.to_owned(),
+            app_id: DEFAULT_APP_ID.to_owned(),
         }
     }
 }
 
+impl Config {
+    /// Configuration for `--safe` launches: defaults with transparency,
+    /// blur, and shell integration turned off.
+    pub fn safe_mode() -> Self {
+        let mut config = Self::default();
+        config.window.opacity = 1.0;
+        config.window.tab_bar_opacity = None;
+        config.window.blur = false;
+        config.behavior.shell_integration = false;
+        config
+    }
+}
+
 impl WindowConfig {
+    /// The window class to use: `window.app_id` without surrounding
+    /// whitespace, or "oriterm" when that leaves nothing.
+    pub fn effective_app_id(&self) -> &str {
+        match self.app_id.trim() {
+            "" => DEFAULT_APP_ID,
+            id => id,
+        }
+    }
+
     /// Returns opacity clamped to [0.0, 1.0].
     pub fn effective_opacity(&self) -> f32 {
         self.opacity.clamp(0.0, 1.0)
@@ -179,23 +301,19 @@ impl WindowConfig {
     pub fn effective_tab_bar_opacity(&self) -> f32 {
         self.tab_bar_opacity.unwrap_or(self.opacity).clamp(0.0, 1.0)
     }
-}
-
-/// User interaction behavior configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(default)]
-pub struct BehaviorConfig {
-    pub copy_on_select: bool,
-    pub bold_is_bright: bool,
-    pub shell_integration: bool,
-}
 
-impl Default for BehaviorConfig {
-    fn default() -> Self {
-        Self {
-            copy_on_select: true,
-            bold_is_bright: true,
-            shell_integration: true,
+    /// The OS window title for a window whose active tab is titled
+    /// `active_title`, per `title_format`. Falls back to "oriterm" when the
+    /// format expands to nothing.
+    pub fn format_title(&self, active_title: &str, tab_count: usize) -> String {
+        let title = self
+            .title_format
+            .replace(TITLE_ACTIVE_TITLE, active_title)
+            .replace(TITLE_TAB_COUNT, &tab_count.to_string());
+        if title.trim().is_empty() {
+            "oriterm".to_owned()
+        } else {
+            title
         }
     }
 }
@@ -210,6 +328,9 @@ pub struct BellConfig {
     pub duration_ms: u16,
     /// Flash color as "#RRGGBB" hex (default: white)
     pub color: Option<String>,
+    /// A bell in a background window requests attention (taskbar flash,
+    /// dock bounce). Programs can also turn this on with DECSET 1042.
+    pub urgent: bool,
 }
 
 impl Default for BellConfig {
@@ -218,7 +339,67 @@ impl Default for BellConfig {
             animation: "ease_out".into(),
             duration_ms: 150,
             color: None,
+            urgent: false,
+        }
+    }
+}
+
+/// Tab bar badge configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TabBadgeConfig {
+    /// Show a count of new output lines on unfocused tabs.
+    pub unread: bool,
+    /// Counts above this show as `<cap>+`.
+    pub unread_cap: u32,
+    /// Badge color as "#RRGGBB" hex (default: palette blue).
+    pub color: Option<String>,
+    /// Show a shell icon (a penguin for WSL) before each tab title.
+    pub icons: bool,
+}
+
+impl Default for TabBadgeConfig {
+    fn default() -> Self {
+        Self {
+            unread: true,
+            unread_cap: 99,
+            color: None,
+            icons: true,
+        }
+    }
+}
+
+/// Desktop notification (OSC 9/99/777) configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// Show notifications as native desktop toasts.
+    pub enabled: bool,
+    /// Also show toasts from the tab that is in front of the focused window.
+    pub when_focused: bool,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            when_focused: false,
+        }
+    }
+}
+
+impl TabBadgeConfig {
+    /// Badge text for `lines` of unread output, or `None` when no badge shows.
+    pub fn unread_label(&self, lines: u64) -> Option<String> {
+        if !self.unread || lines == 0 {
+            return None;
         }
+        let cap = u64::from(self.unread_cap.max(1));
+        Some(if lines > cap {
+            format!("{cap}+")
+        } else {
+            lines.to_string()
+        })
     }
 }
 
@@ -268,6 +449,20 @@ impl Default for TerminalConfig {
             cursor_style: "block".to_owned(),
             cursor_blink: true,
             cursor_blink_interval_ms: 530,
+            cursor_blink_pause_ms: 500,
+            cursor_unfocused_style: "hollow".to_owned(),
+            cursor_unfocused_color: None,
+            text_blink: TextBlink::default(),
+            text_blink_interval_ms: 600,
+            alt_screen_clip: ClipEdge::default(),
+            answerback: String::new(),
+            c1_controls: false,
+            copy_concealed: true,
+            bidi: false,
+            osc52: ClipboardAccess::default(),
+            osc52_max_bytes: 1 << 20,
+            immediate_echo: false,
+            command_duration_ms: 2000,
         }
     }
 }
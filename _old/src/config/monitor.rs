@@ -1,19 +1,55 @@
 //! Config file change monitor — watches TOML and sends reload events.
+//!
+//! Directories are watched rather than files, so editors that save by
+//! writing a backup and renaming it over the original (vim's default) keep
+//! triggering reloads after the first save.
 
+use std::path::PathBuf;
 use std::sync::mpsc;
 use std::thread::JoinHandle;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use notify::{RecursiveMode, Watcher};
+use notify::event::{AccessKind, AccessMode};
+use notify::{EventKind, RecursiveMode, Watcher};
 use winit::event_loop::EventLoopProxy;
 
 use super::config_path;
 use crate::log;
 use crate::tab::TermEvent;
 
+/// Quiet period that ends a burst of file events.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Longest a continuous burst may delay the reload.
+const MAX_DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// Files whose changes trigger a config reload.
+#[derive(Debug, Clone)]
+pub(super) struct WatchedFiles {
+    /// The main `config.toml`.
+    pub(super) config_file: PathBuf,
+}
+
+impl WatchedFiles {
+    /// Whether `event` changed the config file.
+    ///
+    /// Read-only accesses are ignored so that loading the config during a
+    /// reload does not trigger another one.
+    pub(super) fn is_relevant(&self, event: &notify::Event) -> bool {
+        let modifies = match event.kind {
+            EventKind::Access(kind) => kind == AccessKind::Close(AccessMode::Write),
+            EventKind::Any | EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => {
+                true
+            }
+            EventKind::Other => false,
+        };
+        modifies && event.paths.contains(&self.config_file)
+    }
+}
+
 /// Watches the config file's parent directory for changes and sends
-/// `TermEvent::ConfigReload` through the event loop proxy when the
-/// config file is modified.
+/// `TermEvent::ConfigReload` through the event loop proxy when the config
+/// file is modified.
 pub struct ConfigMonitor {
     shutdown_tx: mpsc::Sender<()>,
     thread: Option<JoinHandle<()>>,
@@ -55,13 +91,14 @@ impl ConfigMonitor {
 
         log(&format!("config_monitor: watching {}", parent.display()));
 
-        let config_file = path;
+        let files = WatchedFiles { config_file: path };
+
         let thread = std::thread::Builder::new()
             .name("config-watcher".into())
             .spawn(move || {
                 // Keep the watcher alive for the lifetime of this thread.
                 let _watcher = watcher;
-                Self::watch_loop(&config_file, &proxy, &notify_rx, &shutdown_rx);
+                Self::watch_loop(&files, &proxy, &notify_rx, &shutdown_rx);
             })
             .ok()?;
 
@@ -72,7 +109,7 @@ impl ConfigMonitor {
     }
 
     fn watch_loop(
-        config_file: &std::path::Path,
+        files: &WatchedFiles,
         proxy: &EventLoopProxy<TermEvent>,
         notify_rx: &mpsc::Receiver<Result<notify::Event, notify::Error>>,
         shutdown_rx: &mpsc::Receiver<()>,
@@ -83,19 +120,14 @@ impl ConfigMonitor {
                 return;
             }
 
-            // Check if the event concerns our config file.
-            let is_config_event = match &event {
-                Ok(ev) => ev.paths.iter().any(|p| p == config_file),
-                Err(_) => false,
-            };
-
-            if !is_config_event {
+            if !event.is_ok_and(|ev| files.is_relevant(&ev)) {
                 continue;
             }
 
-            // Debounce: drain any further events within 200ms.
-            let debounce = Duration::from_millis(200);
-            while notify_rx.recv_timeout(debounce).is_ok() {
+            // Debounce: drain further events until a quiet period, but never
+            // hold the reload back longer than `MAX_DEBOUNCE`.
+            let deadline = Instant::now() + MAX_DEBOUNCE;
+            while Instant::now() < deadline && notify_rx.recv_timeout(DEBOUNCE).is_ok() {
                 // Drain
             }
 
@@ -104,7 +136,7 @@ impl ConfigMonitor {
                 return;
             }
 
-            log("config_monitor: config file changed, sending reload event");
+            log("config_monitor: config changed, sending reload event");
             if proxy.send_event(TermEvent::ConfigReload).is_err() {
                 // Event loop closed.
                 return;
@@ -1,9 +1,14 @@
 //! Configuration unit tests.
 
+use std::time::Duration;
+
 use vte::ansi::CursorShape;
 
 use super::*;
+use crate::compose::ComposeKey;
+use crate::keybindings::KeybindProfile;
 use crate::render;
+use crate::selection::ClickCycle;
 
 #[test]
 fn default_config_roundtrip() {
@@ -99,6 +104,8 @@ fn parse_cursor_style_variants() {
     assert_eq!(parse_cursor_style("beam"), CursorShape::Beam);
     assert_eq!(parse_cursor_style("underline"), CursorShape::Underline);
     assert_eq!(parse_cursor_style("Underline"), CursorShape::Underline);
+    assert_eq!(parse_cursor_style("hollow"), CursorShape::HollowBlock);
+    assert_eq!(parse_cursor_style("hidden"), CursorShape::Hidden);
     assert_eq!(parse_cursor_style("unknown"), CursorShape::Block);
 }
 
@@ -454,3 +461,253 @@ fn color_overrides_roundtrip() {
     );
     assert!(parsed.colors.background.is_none());
 }
+
+#[test]
+fn alt_screen_clip_from_toml() {
+    assert_eq!(Config::default().terminal.alt_screen_clip, ClipEdge::Top);
+    let toml_str = r#"
+[terminal]
+alt_screen_clip = "bottom"
+"#;
+    let parsed: Config = toml::from_str(toml_str).expect("deserialize");
+    assert_eq!(parsed.terminal.alt_screen_clip, ClipEdge::Bottom);
+}
+
+#[test]
+fn click_policy_from_toml() {
+    let config: Config = toml::from_str(
+        "[behavior]\ndouble_click_ms = 250\nclick_cycle = \"hold\"\n",
+    )
+    .expect("valid config");
+    assert_eq!(config.behavior.click_cycle, ClickCycle::Hold);
+    assert_eq!(config.behavior.double_click_interval(), Duration::from_millis(250));
+    let defaults = BehaviorConfig::default();
+    assert_eq!(defaults.click_cycle, ClickCycle::Wrap);
+    assert_eq!(defaults.double_click_interval(), Duration::from_millis(400));
+}
+
+#[test]
+fn compose_key_from_toml() {
+    assert_eq!(BehaviorConfig::default().compose_key, ComposeKey::Off);
+    let config: Config =
+        toml::from_str("[behavior]\ncompose_key = \"right_alt\"\n").expect("valid config");
+    assert_eq!(config.behavior.compose_key, ComposeKey::RightAlt);
+}
+
+#[test]
+fn inherit_cwd_defaults_on() {
+    assert!(BehaviorConfig::default().inherit_cwd);
+    let config: Config =
+        toml::from_str("[behavior]\ninherit_cwd = false\n").expect("valid config");
+    assert!(!config.behavior.inherit_cwd);
+}
+
+#[test]
+fn mouse_gestures_from_toml() {
+    let defaults = BehaviorConfig::default();
+    assert_eq!(defaults.middle_click_tab, MouseAction::CloseTab);
+    assert_eq!(defaults.double_click_tab_bar, MouseAction::NewTab);
+    let toml_str = r#"
+[behavior]
+double_click_tab_bar = "toggle_maximize"
+middle_click_grid = "paste"
+"#;
+    let config: Config = toml::from_str(toml_str).expect("valid config");
+    assert_eq!(config.behavior.double_click_tab_bar, MouseAction::ToggleMaximize);
+    assert_eq!(config.behavior.middle_click_grid, MouseAction::Paste);
+    assert_eq!(config.behavior.middle_click_tab, MouseAction::CloseTab);
+}
+
+#[test]
+fn unread_badge_label_caps() {
+    let mut badge = TabBadgeConfig::default();
+    assert_eq!(badge.unread_label(0), None);
+    assert_eq!(badge.unread_label(7).as_deref(), Some("7"));
+    assert_eq!(badge.unread_label(100).as_deref(), Some("99+"));
+    badge.unread_cap = 9;
+    assert_eq!(badge.unread_label(10).as_deref(), Some("9+"));
+    badge.unread = false;
+    assert_eq!(badge.unread_label(10), None);
+}
+
+fn watched() -> monitor::WatchedFiles {
+    monitor::WatchedFiles {
+        config_file: std::path::PathBuf::from("/cfg/config.toml"),
+    }
+}
+
+fn fs_event(kind: notify::EventKind, path: &str) -> notify::Event {
+    notify::Event::new(kind).add_path(std::path::PathBuf::from(path))
+}
+
+#[test]
+fn monitor_reacts_to_config_writes() {
+    use notify::event::{CreateKind, ModifyKind, RenameMode};
+    use notify::EventKind;
+
+    let files = watched();
+    let modify = EventKind::Modify(ModifyKind::Any);
+    assert!(files.is_relevant(&fs_event(modify, "/cfg/config.toml")));
+    assert!(!files.is_relevant(&fs_event(modify, "/cfg/state.toml")));
+
+    // Editors that save by renaming a temp file over the config.
+    let rename = EventKind::Modify(ModifyKind::Name(RenameMode::To));
+    assert!(files.is_relevant(&fs_event(rename, "/cfg/config.toml")));
+    let create = EventKind::Create(CreateKind::File);
+    assert!(files.is_relevant(&fs_event(create, "/cfg/config.toml")));
+}
+
+#[test]
+fn monitor_ignores_read_access() {
+    use notify::event::{AccessKind, AccessMode};
+    use notify::EventKind;
+
+    let files = watched();
+    let open = EventKind::Access(AccessKind::Open(AccessMode::Any));
+    assert!(!files.is_relevant(&fs_event(open, "/cfg/config.toml")));
+    let close_read = EventKind::Access(AccessKind::Close(AccessMode::Read));
+    assert!(!files.is_relevant(&fs_event(close_read, "/cfg/config.toml")));
+    let close_write = EventKind::Access(AccessKind::Close(AccessMode::Write));
+    assert!(files.is_relevant(&fs_event(close_write, "/cfg/config.toml")));
+}
+
+#[test]
+fn osc52_policy_from_toml() {
+    let parsed: Config = toml::from_str("").expect("deserialize");
+    assert_eq!(parsed.terminal.osc52, ClipboardAccess::Ask);
+    assert_eq!(parsed.terminal.osc52_max_bytes, 1 << 20);
+
+    let toml_str = "[terminal]\nosc52 = \"deny\"\nosc52_max_bytes = 4096\n";
+    let parsed: Config = toml::from_str(toml_str).expect("deserialize");
+    assert_eq!(parsed.terminal.osc52, ClipboardAccess::Deny);
+    assert_eq!(parsed.terminal.osc52_max_bytes, 4096);
+}
+
+#[test]
+fn immediate_echo_defaults_off() {
+    let parsed: Config = toml::from_str("").expect("deserialize");
+    assert!(!parsed.terminal.immediate_echo);
+
+    let parsed: Config =
+        toml::from_str("[terminal]\nimmediate_echo = true\n").expect("deserialize");
+    assert!(parsed.terminal.immediate_echo);
+}
+
+#[test]
+fn c1_controls_default_off() {
+    let parsed: Config = toml::from_str("").expect("deserialize");
+    assert!(!parsed.terminal.c1_controls);
+
+    let parsed: Config = toml::from_str("[terminal]\nc1_controls = true\n").expect("deserialize");
+    assert!(parsed.terminal.c1_controls);
+}
+
+#[test]
+fn window_title_format_expands_placeholders() {
+    let mut window = WindowConfig::default();
+    assert_eq!(window.format_title("~/src", 3), "~/src");
+    window.title_format = "{active_title} ({tab_count}) — oriterm".to_owned();
+    assert_eq!(window.format_title("vim", 2), "vim (2) — oriterm");
+    window.title_format = "{active_title}".to_owned();
+    assert_eq!(window.format_title("", 1), "oriterm");
+}
+
+#[test]
+fn window_app_id_defaults_to_oriterm() {
+    let config: Config = toml::from_str("").expect("valid config");
+    assert_eq!(config.window.effective_app_id(), "oriterm");
+    let config: Config =
+        toml::from_str("[window]\napp_id = \" dev-term \"\n").expect("valid config");
+    assert_eq!(config.window.effective_app_id(), "dev-term");
+    let config: Config = toml::from_str("[window]\napp_id = \"\"\n").expect("valid config");
+    assert_eq!(config.window.effective_app_id(), "oriterm");
+}
+
+#[test]
+fn notifications_toast_only_for_background_tabs_by_default() {
+    let config: Config = toml::from_str("").expect("valid config");
+    assert!(config.notifications.enabled);
+    assert!(!config.notifications.when_focused);
+    let config: Config =
+        toml::from_str("[notifications]\nenabled = false\n").expect("valid config");
+    assert!(!config.notifications.enabled);
+}
+
+#[test]
+fn rules_from_toml() {
+    let toml_str = r#"
+[[rules]]
+regex = "[A-Z]+-\\d+"
+url = "https://issues.example.com/$0"
+
+[[rules]]
+regex = "[\\w./-]+\\.rs:\\d+"
+action = "run"
+program = "code"
+args = ["--goto"]
+"#;
+    let parsed: Config = toml::from_str(toml_str).expect("deserialize");
+    assert_eq!(parsed.rules.len(), 2);
+    assert_eq!(parsed.rules[0].action, crate::url_detect::RuleAction::Open);
+    assert_eq!(parsed.rules[1].action, crate::url_detect::RuleAction::Run);
+    assert_eq!(parsed.rules[1].args, vec!["--goto".to_string()]);
+    assert!(toml::from_str::<Config>("").expect("deserialize").rules.is_empty());
+}
+
+#[test]
+fn host_rules_match_host_and_title_globs() {
+    let toml_str = r##"
+        [[host_rule]]
+        host = "*prod*"
+        border = "#ff0000"
+        tab_color = "#00ff00"
+
+        [[host_rule]]
+        title = "ssh ?ev-*"
+        scheme = "Dracula"
+    "##;
+    let parsed: Config = toml::from_str(toml_str).expect("deserialize");
+    let rules = &parsed.host_rule;
+    assert_eq!(rules.len(), 2);
+    assert_eq!(rules[0].tab_color.as_deref(), Some("#00ff00"));
+    assert_eq!(find_host_rule(rules, Some("db-PROD-1"), "~"), Some(0));
+    assert_eq!(find_host_rule(rules, Some("dev-1"), "ssh dev-1"), Some(1));
+    assert_eq!(find_host_rule(rules, None, "ssh dev"), None);
+    // A rule without patterns never applies.
+    assert!(!HostRule::default().matches(Some("prod"), "prod"));
+}
+
+#[test]
+fn keybind_profile_defaults_to_auto() {
+    let parsed: Config = toml::from_str("").expect("deserialize");
+    assert_eq!(parsed.keybind_profile, KeybindProfile::Auto);
+    let parsed: Config = toml::from_str("keybind_profile = \"macos\"").expect("deserialize");
+    assert_eq!(parsed.keybind_profile, KeybindProfile::Macos);
+}
+
+#[test]
+fn command_duration_threshold_from_toml() {
+    assert_eq!(TerminalConfig::default().command_duration_ms, 2000);
+    let parsed: Config =
+        toml::from_str("[terminal]\ncommand_duration_ms = 0\n").expect("deserialize");
+    assert_eq!(parsed.terminal.command_duration_ms, 0);
+}
+
+#[test]
+fn confirm_close_process_list() {
+    let defaults = BehaviorConfig::default();
+    assert!(defaults.confirms_close(Some("vim")));
+    assert!(defaults.confirms_close(None));
+
+    let config: Config = toml::from_str(
+        "[behavior]\nconfirm_close_processes = [\"vim\", \"ssh\"]\n",
+    )
+    .expect("valid config");
+    assert!(config.behavior.confirms_close(Some("SSH")));
+    assert!(!config.behavior.confirms_close(Some("cargo")));
+    assert!(!config.behavior.confirms_close(None));
+
+    let off: Config =
+        toml::from_str("[behavior]\nconfirm_close = false\n").expect("valid config");
+    assert!(!off.behavior.confirms_close(Some("vim")));
+}
@@ -1,13 +1,25 @@
 //! Custom GPU-rendered context menus (cross-platform).
 //!
 //! Chrome-style popup menus drawn through the existing wgpu overlay pipeline.
-//! Three menu types:
+//! Menu types:
 //! - **Tab menu**: right-click on a tab → Close / Duplicate / Move to New Window
 //! - **Tab bar menu**: right-click on empty tab bar area → New Tab
 //! - **Dropdown menu**: dropdown button → Settings + Color Scheme submenu
+//! - **Clipboard prompt**: a program asked to write the clipboard (OSC 52)
+//! - **Link menu**: right-click on a hyperlink or URL → Open / Copy URL
+//! - **Grid menu**: right-click in the terminal → Copy / Paste / Select All /
+//!   Clear Scrollback / Search
+//! - **Clipboard history**: `PasteFromHistory` → one item per recent copy
+//! - **Close prompt**: closing a tab or window that is running a command
 
+use winit::window::WindowId;
+
+use crate::clipboard::ClipboardAccess;
+use crate::clipboard_history::{self, ClipboardHistory};
 use crate::font::FontCollection;
+use crate::keybindings::Action;
 use crate::palette::BUILTIN_SCHEMES;
+use crate::tab::TabId;
 
 /// Corner radius for the menu popup.
 const MENU_RADIUS: f32 = 8.0;
@@ -41,10 +53,28 @@ pub const CHECKMARK_GAP: f32 = 4.0;
 pub enum ContextAction {
     CloseTab(usize),
     DuplicateTab(usize),
+    RenameTab(usize),
     MoveTabToNewWindow(usize),
+    ToggleNotificationMute(usize),
+    /// Leave a tab out of its window's broadcast input, or put it back.
+    ToggleBroadcastTab(usize),
+    /// Pin a tab to the left of the tab bar, or unpin it.
+    TogglePinTab(usize),
     NewTab,
     OpenSettings,
     SelectScheme(String),
+    /// Answer the OSC 52 confirmation for a tab.
+    ClipboardAccess(TabId, ClipboardAccess),
+    OpenUrl(String),
+    CopyUrl(String),
+    /// Run a keybinding action in the focused window.
+    Run(Action),
+    /// Paste clipboard history entry `n` (0 = newest) in the focused window.
+    PasteHistory(usize),
+    /// Close a tab despite the command it's running.
+    ConfirmCloseTab(TabId),
+    /// Close a window despite the commands its tabs are running.
+    ConfirmCloseWindow(WindowId),
 }
 
 /// A single entry in a context menu.
@@ -61,6 +91,8 @@ pub enum MenuEntry {
         checked: bool,
         action: ContextAction,
     },
+    /// A dimmed line of text that can't be clicked.
+    Label { label: String },
     /// A visual separator line.
     Separator,
 }
@@ -68,20 +100,22 @@ pub enum MenuEntry {
 impl MenuEntry {
     fn label(&self) -> Option<&str> {
         match self {
-            Self::Item { label, .. } | Self::Check { label, .. } => Some(label),
+            Self::Item { label, .. } | Self::Check { label, .. } | Self::Label { label } => {
+                Some(label)
+            }
             Self::Separator => None,
         }
     }
 
     pub fn height(&self) -> f32 {
         match self {
-            Self::Item { .. } | Self::Check { .. } => ITEM_HEIGHT,
+            Self::Item { .. } | Self::Check { .. } | Self::Label { .. } => ITEM_HEIGHT,
             Self::Separator => SEPARATOR_HEIGHT,
         }
     }
 
     fn is_clickable(&self) -> bool {
-        !matches!(self, Self::Separator)
+        matches!(self, Self::Item { .. } | Self::Check { .. })
     }
 }
 
@@ -197,31 +231,72 @@ impl MenuOverlay {
             MenuEntry::Item { action, .. } | MenuEntry::Check { action, .. } => {
                 Some(action.clone())
             }
-            MenuEntry::Separator => None,
+            MenuEntry::Label { .. } | MenuEntry::Separator => None,
         }
     }
 }
 
+/// Check marks shown in the tab right-click menu.
+#[derive(Debug, Clone, Copy)]
+pub struct TabMenuChecks {
+    /// The tab's notifications are muted.
+    pub muted: bool,
+    /// The tab is pinned.
+    pub pinned: bool,
+    /// The window broadcasts input; `None` when it doesn't, otherwise
+    /// whether this tab receives it.
+    pub broadcast: Option<bool>,
+}
+
 /// Build the tab right-click menu.
-pub fn build_tab_menu(position: (f32, f32), tab_index: usize, scale: f32) -> MenuOverlay {
-    MenuOverlay::new(
-        vec![
-            MenuEntry::Item {
-                label: "Close Tab".into(),
-                action: ContextAction::CloseTab(tab_index),
-            },
-            MenuEntry::Item {
-                label: "Duplicate Tab".into(),
-                action: ContextAction::DuplicateTab(tab_index),
-            },
-            MenuEntry::Item {
-                label: "Move to New Window".into(),
-                action: ContextAction::MoveTabToNewWindow(tab_index),
-            },
-        ],
-        position,
-        scale,
-    )
+pub fn build_tab_menu(
+    position: (f32, f32),
+    tab_index: usize,
+    checks: TabMenuChecks,
+    scale: f32,
+) -> MenuOverlay {
+    let mut entries = vec![
+        MenuEntry::Item {
+            label: "Close Tab".into(),
+            action: ContextAction::CloseTab(tab_index),
+        },
+        MenuEntry::Item {
+            label: "Duplicate Tab".into(),
+            action: ContextAction::DuplicateTab(tab_index),
+        },
+        MenuEntry::Item {
+            label: "Rename Tab".into(),
+            action: ContextAction::RenameTab(tab_index),
+        },
+        MenuEntry::Item {
+            label: "Move to New Window".into(),
+            action: ContextAction::MoveTabToNewWindow(tab_index),
+        },
+        MenuEntry::Separator,
+        MenuEntry::Check {
+            label: "Pin Tab".into(),
+            checked: checks.pinned,
+            action: ContextAction::TogglePinTab(tab_index),
+        },
+        MenuEntry::Check {
+            label: "Mute Notifications".into(),
+            checked: checks.muted,
+            action: ContextAction::ToggleNotificationMute(tab_index),
+        },
+        MenuEntry::Check {
+            label: "Broadcast Input to All Tabs".into(),
+            checked: checks.broadcast.is_some(),
+            action: ContextAction::Run(Action::ToggleBroadcast),
+        },
+    ];
+    if let Some(receives) = checks.broadcast {
+        entries.push(MenuEntry::Check {
+            label: "Receive Broadcast Input".into(),
+            checked: receives,
+            action: ContextAction::ToggleBroadcastTab(tab_index),
+        });
+    }
+    MenuOverlay::new(entries, position, scale)
 }
 
 /// Build the tab bar right-click menu (empty area).
@@ -256,3 +331,124 @@ pub fn build_dropdown_menu(position: (f32, f32), active_scheme: &str, scale: f32
 
     MenuOverlay::new(entries, position, scale)
 }
+
+/// Build the OSC 52 confirmation shown when a tab's program first tries to
+/// write `len` bytes to the clipboard.
+pub fn build_clipboard_prompt(
+    position: (f32, f32),
+    tab_id: TabId,
+    len: usize,
+    scale: f32,
+) -> MenuOverlay {
+    MenuOverlay::new(
+        vec![
+            MenuEntry::Item {
+                label: format!("Allow Program to Copy {len} Bytes"),
+                action: ContextAction::ClipboardAccess(tab_id, ClipboardAccess::Allow),
+            },
+            MenuEntry::Separator,
+            MenuEntry::Item {
+                label: "Block Clipboard Writes in This Tab".into(),
+                action: ContextAction::ClipboardAccess(tab_id, ClipboardAccess::Deny),
+            },
+        ],
+        position,
+        scale,
+    )
+}
+
+/// What a close prompt closes.
+#[derive(Debug, Clone, Copy)]
+pub enum CloseTarget {
+    Tab(TabId),
+    Window(WindowId),
+}
+
+/// Build the confirmation shown before closing `target`, with a line for
+/// each of the `reasons` it asks.
+pub fn build_close_prompt(
+    position: (f32, f32),
+    target: CloseTarget,
+    reasons: &[String],
+    scale: f32,
+) -> MenuOverlay {
+    let mut entries: Vec<MenuEntry> = reasons
+        .iter()
+        .map(|reason| MenuEntry::Label {
+            label: reason.clone(),
+        })
+        .collect();
+    let (label, action) = match target {
+        CloseTarget::Tab(tab_id) => ("Close Tab", ContextAction::ConfirmCloseTab(tab_id)),
+        CloseTarget::Window(window_id) => {
+            ("Close Window", ContextAction::ConfirmCloseWindow(window_id))
+        }
+    };
+    entries.extend([
+        MenuEntry::Separator,
+        MenuEntry::Item {
+            label: label.into(),
+            action,
+        },
+        MenuEntry::Item {
+            label: "Cancel".into(),
+            action: ContextAction::Run(Action::None),
+        },
+    ]);
+    MenuOverlay::new(entries, position, scale)
+}
+
+/// Build the menu for a right-click on a hyperlink or detected URL.
+pub fn build_link_menu(position: (f32, f32), url: &str, scale: f32) -> MenuOverlay {
+    MenuOverlay::new(
+        vec![
+            MenuEntry::Item {
+                label: "Open URL".into(),
+                action: ContextAction::OpenUrl(url.to_owned()),
+            },
+            MenuEntry::Item {
+                label: "Copy URL".into(),
+                action: ContextAction::CopyUrl(url.to_owned()),
+            },
+        ],
+        position,
+        scale,
+    )
+}
+
+/// Build the menu for a right-click in the terminal grid.
+pub fn build_grid_menu(position: (f32, f32), scale: f32) -> MenuOverlay {
+    let item = |label: &str, action| MenuEntry::Item {
+        label: label.into(),
+        action: ContextAction::Run(action),
+    };
+    MenuOverlay::new(
+        vec![
+            item("Copy", Action::Copy),
+            item("Paste", Action::Paste),
+            item("Select All", Action::SelectAll),
+            MenuEntry::Separator,
+            item("Clear Scrollback", Action::ClearScrollback),
+            item("Search", Action::OpenSearch),
+        ],
+        position,
+        scale,
+    )
+}
+
+/// Build the clipboard history picker: one item per entry, newest first.
+pub fn build_history_menu(
+    position: (f32, f32),
+    history: &ClipboardHistory,
+    scale: f32,
+) -> MenuOverlay {
+    let entries = history
+        .iter()
+        .enumerate()
+        .map(|(i, text)| MenuEntry::Item {
+            label: clipboard_history::entry_label(text),
+            action: ContextAction::PasteHistory(i),
+        })
+        .collect();
+    MenuOverlay::new(entries, position, scale)
+}
@@ -0,0 +1,135 @@
+//! Freedesktop notifications over D-Bus (`org.freedesktop.Notifications`).
+//!
+//! `Notify` returns the server's id for each toast. A listener thread
+//! watches the `ActionInvoked` and `NotificationClosed` signals and answers
+//! the tab that showed the toast.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use winit::event_loop::EventLoopProxy;
+use zbus::MatchRule;
+use zbus::blocking::{Connection, MessageIterator};
+use zbus::message::Type;
+use zbus::names::MemberName;
+use zbus::zvariant::Value;
+
+use crate::log;
+use crate::tab::{NotificationResponse, TabId, TermEvent};
+
+use super::{APP_NAME, Toast, response, summary};
+
+/// Bus name, object path, and interface of the notification server.
+const SERVER: &str = "org.freedesktop.Notifications";
+const SERVER_PATH: &str = "/org/freedesktop/Notifications";
+
+/// Toasts still open, by server id: the tab and OSC 99 id to answer.
+type Open = Arc<Mutex<HashMap<u32, (TabId, Option<String>)>>>;
+
+/// Session bus connection, or `None` without a session bus.
+pub(super) struct Backend {
+    conn: Option<Connection>,
+    open: Open,
+}
+
+impl Backend {
+    pub(super) fn new(_app_id: &str, proxy: EventLoopProxy<TermEvent>) -> Self {
+        let open = Open::default();
+        let conn = match Connection::session() {
+            Ok(conn) => conn,
+            Err(e) => {
+                log(&format!("notification: no session bus: {e}"));
+                return Self { conn: None, open };
+            }
+        };
+        listen(&conn, Arc::clone(&open), proxy);
+        Self {
+            conn: Some(conn),
+            open,
+        }
+    }
+
+    pub(super) fn show(&self, toast: Toast) {
+        let Some(conn) = &self.conn else {
+            return;
+        };
+        let hints: HashMap<&str, Value<'_>> = HashMap::new();
+        let body = (
+            APP_NAME,
+            0u32,
+            "",
+            summary(&toast.notif),
+            toast.notif.body.as_str(),
+            actions(&toast.notif.buttons),
+            hints,
+            -1i32,
+        );
+        let id = conn
+            .call_method(Some(SERVER), SERVER_PATH, Some(SERVER), "Notify", &body)
+            .and_then(|reply| reply.body().deserialize::<u32>());
+        match id {
+            Ok(id) => {
+                self.open.lock().insert(id, (toast.tab_id, toast.notif.id));
+            }
+            Err(e) => log(&format!("notification: Notify failed: {e}")),
+        }
+    }
+}
+
+/// `Notify` action list of key, label pairs: the toast body (`default`)
+/// plus one action per OSC 99 button, keyed 1, 2, ….
+pub(super) fn actions(buttons: &[String]) -> Vec<String> {
+    let mut actions = vec!["default".to_owned(), "Focus".to_owned()];
+    for (i, label) in buttons.iter().enumerate() {
+        actions.push((i + 1).to_string());
+        actions.push(label.clone());
+    }
+    actions
+}
+
+/// Forward how our toasts close to the event loop, from a listener thread.
+fn listen(conn: &Connection, open: Open, proxy: EventLoopProxy<TermEvent>) {
+    let messages = MatchRule::builder()
+        .msg_type(Type::Signal)
+        .interface(SERVER)
+        .and_then(|rule| MessageIterator::for_match_rule(rule.build(), conn, None));
+    let messages = match messages {
+        Ok(messages) => messages,
+        Err(e) => {
+            log(&format!("notification: can't watch for replies: {e}"));
+            return;
+        }
+    };
+    let spawned = std::thread::Builder::new()
+        .name("notification replies".into())
+        .spawn(move || {
+            for msg in messages.flatten() {
+                let header = msg.header();
+                let closed = match header.member().map(MemberName::as_str) {
+                    Some("ActionInvoked") => msg
+                        .body()
+                        .deserialize::<(u32, String)>()
+                        .map(|(id, key)| (id, response(&key))),
+                    Some("NotificationClosed") => msg
+                        .body()
+                        .deserialize::<(u32, u32)>()
+                        .map(|(id, _reason)| (id, NotificationResponse::Dismissed)),
+                    _ => continue,
+                };
+                let Ok((id, response)) = closed else {
+                    continue;
+                };
+                // A click also closes the toast; only the first signal counts.
+                let toast = open.lock().remove(&id);
+                if let Some((tab_id, notif_id)) = toast {
+                    let _ = proxy.send_event(TermEvent::NotificationResponse(
+                        tab_id, notif_id, response,
+                    ));
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        log(&format!("notification: failed to spawn listener: {e}"));
+    }
+}
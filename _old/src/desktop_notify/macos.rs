@@ -0,0 +1,35 @@
+//! macOS notifications through the user notification center.
+//!
+//! The notification center doesn't tell a background sender how its toast
+//! was closed, so toasts here are fire-and-forget.
+
+use winit::event_loop::EventLoopProxy;
+
+use crate::log;
+use crate::tab::TermEvent;
+
+use super::{APP_NAME, Toast, summary};
+
+pub(super) struct Backend;
+
+impl Backend {
+    pub(super) fn new(_app_id: &str, _proxy: EventLoopProxy<TermEvent>) -> Self {
+        // Toasts are attributed to an application bundle: ours when installed.
+        let bundle = mac_notification_sys::get_bundle_identifier_or_default(APP_NAME);
+        if let Err(e) = mac_notification_sys::set_application(&bundle) {
+            log(&format!("notification: can't post as {bundle}: {e}"));
+        }
+        Self
+    }
+
+    pub(super) fn show(&self, toast: Toast) {
+        let sent = mac_notification_sys::Notification::new()
+            .title(summary(&toast.notif))
+            .message(&toast.notif.body)
+            .asynchronous(true)
+            .send();
+        if let Err(e) = sent {
+            log(&format!("notification: {e}"));
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Native desktop notifications for OSC 9, 99, and 777.
+//!
+//! Toasts go through one worker thread that talks to the platform's
+//! notification service directly: the freedesktop notification server over
+//! D-Bus on Linux and the BSDs, the Windows Runtime `ToastNotificationManager`
+//! under the app's own `AppUserModelID` on Windows, and the user
+//! notification center on macOS. The worker shows at most one batch of
+//! toasts per [`MIN_INTERVAL`], keeping only the newest toast of each tab,
+//! so a program printing notifications in a loop can't flood the desktop.
+//! Linux and Windows report how a toast was closed, so click-to-focus and
+//! OSC 99 activation reports work there; on macOS a toast is fire-and-forget.
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod freedesktop;
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(test)]
+mod tests;
+#[cfg(target_os = "windows")]
+mod winrt;
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::{Duration, Instant};
+
+use winit::event_loop::EventLoopProxy;
+
+use crate::log;
+use crate::tab::{Notification, NotificationResponse, TabId, TermEvent};
+
+#[cfg(all(unix, not(target_os = "macos")))]
+use freedesktop::Backend;
+#[cfg(target_os = "macos")]
+use macos::Backend;
+#[cfg(target_os = "windows")]
+use winrt::Backend;
+
+/// Application name shown on toasts.
+const APP_NAME: &str = "oriterm";
+
+/// Shortest time between two batches of toasts.
+const MIN_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A notification waiting for the worker.
+struct Toast {
+    tab_id: TabId,
+    notif: Notification,
+}
+
+/// Hands notifications to the toast worker thread.
+pub struct Notifier {
+    /// `None` if the worker couldn't be started.
+    tx: Option<Sender<Toast>>,
+}
+
+impl Notifier {
+    /// Start the worker. Toasts are attributed to `app_id` where the
+    /// platform needs one, and close reports arrive through `proxy` as
+    /// [`TermEvent::NotificationResponse`].
+    pub fn new(app_id: &str, proxy: EventLoopProxy<TermEvent>) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let app_id = app_id.to_owned();
+        let spawned = std::thread::Builder::new()
+            .name("notifications".into())
+            .spawn(move || {
+                let backend = Backend::new(&app_id, proxy);
+                run(&rx, |toast| backend.show(toast));
+            });
+        match spawned {
+            Ok(_) => Self { tx: Some(tx) },
+            Err(e) => {
+                log(&format!("notification: failed to spawn worker: {e}"));
+                Self { tx: None }
+            }
+        }
+    }
+
+    /// Queue `notif` from `tab_id` as a desktop toast.
+    pub fn show(&self, notif: &Notification, tab_id: TabId) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(Toast {
+                tab_id,
+                notif: notif.clone(),
+            });
+        }
+    }
+}
+
+/// Show toasts from `rx` until every sender is gone, waiting out
+/// [`MIN_INTERVAL`] after each batch and collecting what arrives meanwhile.
+fn run(rx: &Receiver<Toast>, mut show: impl FnMut(Toast)) {
+    let mut next_batch = Instant::now();
+    while let Ok(first) = rx.recv() {
+        let mut batch = vec![first];
+        while let Some(wait) = next_batch.checked_duration_since(Instant::now()) {
+            match rx.recv_timeout(wait) {
+                Ok(toast) => batch.push(toast),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        batch.extend(rx.try_iter());
+        coalesce(batch).into_iter().for_each(&mut show);
+        next_batch = Instant::now() + MIN_INTERVAL;
+    }
+}
+
+/// The newest toast of each tab in `batch`, in arrival order.
+fn coalesce(batch: Vec<Toast>) -> Vec<Toast> {
+    let newest: HashMap<TabId, usize> = batch
+        .iter()
+        .enumerate()
+        .map(|(i, toast)| (toast.tab_id, i))
+        .collect();
+    batch
+        .into_iter()
+        .enumerate()
+        .filter(|(i, toast)| newest.get(&toast.tab_id) == Some(i))
+        .map(|(_, toast)| toast)
+        .collect()
+}
+
+/// Toast title: OSC 9 notifications carry only a body.
+fn summary(notif: &Notification) -> &str {
+    if notif.title.is_empty() {
+        APP_NAME
+    } else {
+        &notif.title
+    }
+}
+
+/// The response for the action key a toast was closed with: `default` for
+/// a click on the toast itself, or an OSC 99 button number (1, 2, …).
+#[cfg(not(target_os = "macos"))]
+fn response(key: &str) -> NotificationResponse {
+    match key {
+        "default" => NotificationResponse::Clicked,
+        key => key
+            .parse()
+            .map_or(NotificationResponse::Dismissed, NotificationResponse::Button),
+    }
+}
@@ -0,0 +1,74 @@
+use std::sync::mpsc;
+
+use super::{Toast, coalesce, run};
+use crate::tab::{Notification, TabId};
+
+fn toast(tab: u64, body: &str) -> Toast {
+    Toast {
+        tab_id: TabId(tab),
+        notif: Notification {
+            body: body.into(),
+            ..Notification::default()
+        },
+    }
+}
+
+fn bodies(toasts: &[Toast]) -> Vec<&str> {
+    toasts.iter().map(|t| t.notif.body.as_str()).collect()
+}
+
+#[test]
+fn a_burst_keeps_the_newest_toast_of_each_tab() {
+    let batch = vec![toast(1, "a"), toast(1, "b"), toast(2, "c"), toast(1, "d")];
+    assert_eq!(bodies(&coalesce(batch)), ["c", "d"]);
+}
+
+#[test]
+fn queued_toasts_are_shown_as_one_batch() {
+    let (tx, rx) = mpsc::channel();
+    for t in [toast(1, "a"), toast(2, "b"), toast(1, "c")] {
+        tx.send(t).expect("worker side is open");
+    }
+    drop(tx);
+    let mut shown = Vec::new();
+    run(&rx, |t| shown.push(t));
+    assert_eq!(bodies(&shown), ["b", "c"]);
+}
+
+#[cfg(not(target_os = "macos"))]
+#[test]
+fn action_keys_map_to_responses() {
+    use super::response;
+    use crate::tab::NotificationResponse;
+
+    assert_eq!(response("default"), NotificationResponse::Clicked);
+    assert_eq!(response("2"), NotificationResponse::Button(2));
+    assert_eq!(response(""), NotificationResponse::Dismissed);
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+#[test]
+fn notify_offers_the_body_and_each_button() {
+    let buttons = ["Retry".to_owned(), "Ignore".to_owned()];
+    assert_eq!(
+        super::freedesktop::actions(&buttons),
+        ["default", "Focus", "1", "Retry", "2", "Ignore"]
+    );
+}
+
+#[cfg(target_os = "windows")]
+#[test]
+fn toast_xml_escapes_text_and_numbers_buttons() {
+    let notif = Notification {
+        title: "a < b".into(),
+        body: "\"x\" & y".into(),
+        buttons: vec!["Retry".into()],
+        ..Notification::default()
+    };
+    assert_eq!(
+        super::winrt::toast_xml(&notif),
+        "<toast launch=\"default\"><visual><binding template=\"ToastGeneric\">\
+         <text>a &lt; b</text><text>&quot;x&quot; &amp; y</text></binding></visual>\
+         <actions><action content=\"Retry\" arguments=\"1\"/></actions></toast>"
+    );
+}
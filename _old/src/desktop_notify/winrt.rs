@@ -0,0 +1,148 @@
+//! Windows toasts through the Windows Runtime `ToastNotificationManager`.
+//!
+//! Toasts are posted under the app's own `AppUserModelID`, the one the
+//! process already sets for taskbar grouping. An unpackaged app's id only
+//! works once it is registered with a display name, which happens on start.
+
+use std::fmt::Write as _;
+
+use windows::Data::Xml::Dom::XmlDocument;
+use windows::Foundation::TypedEventHandler;
+use windows::UI::Notifications::{
+    ToastActivatedEventArgs, ToastNotification, ToastNotificationManager, ToastNotifier,
+};
+use windows::core::{HSTRING, IInspectable, Interface, Ref};
+use winit::event_loop::EventLoopProxy;
+
+use crate::log;
+use crate::tab::{Notification, NotificationResponse, TermEvent};
+
+use super::{APP_NAME, Toast, response, summary};
+
+/// Notifier for the app's id, or `None` if the system refused one.
+pub(super) struct Backend {
+    notifier: Option<ToastNotifier>,
+    proxy: EventLoopProxy<TermEvent>,
+}
+
+impl Backend {
+    pub(super) fn new(app_id: &str, proxy: EventLoopProxy<TermEvent>) -> Self {
+        register_app_id(app_id);
+        let notifier = ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(app_id))
+            .map_err(|e| log(&format!("notification: no toast notifier for {app_id}: {e}")))
+            .ok();
+        Self { notifier, proxy }
+    }
+
+    pub(super) fn show(&self, toast: Toast) {
+        let Some(notifier) = &self.notifier else {
+            return;
+        };
+        if let Err(e) = show_toast(notifier, &self.proxy, toast) {
+            log(&format!("notification: {e}"));
+        }
+    }
+}
+
+/// Build the toast for `toast`, hook up its close reports, and show it.
+fn show_toast(
+    notifier: &ToastNotifier,
+    proxy: &EventLoopProxy<TermEvent>,
+    toast: Toast,
+) -> windows::core::Result<()> {
+    let xml = XmlDocument::new()?;
+    xml.LoadXml(&HSTRING::from(toast_xml(&toast.notif)))?;
+    let shown = ToastNotification::CreateToastNotification(&xml)?;
+    let (tab_id, id) = (toast.tab_id, toast.notif.id);
+
+    let (activated_proxy, activated_id) = (proxy.clone(), id.clone());
+    shown.Activated(&TypedEventHandler::new(
+        move |_, args: Ref<'_, IInspectable>| {
+            let key = args
+                .as_ref()
+                .and_then(|args| args.cast::<ToastActivatedEventArgs>().ok())
+                .and_then(|args| args.Arguments().ok())
+                .map(|key| key.to_string())
+                .unwrap_or_default();
+            let event = TermEvent::NotificationResponse(tab_id, activated_id.clone(), response(&key));
+            let _ = activated_proxy.send_event(event);
+            Ok(())
+        },
+    ))?;
+    let dismissed_proxy = proxy.clone();
+    shown.Dismissed(&TypedEventHandler::new(move |_, _| {
+        let event = TermEvent::NotificationResponse(tab_id, id.clone(), NotificationResponse::Dismissed);
+        let _ = dismissed_proxy.send_event(event);
+        Ok(())
+    }))?;
+    notifier.Show(&shown)
+}
+
+/// Toast XML: title and body lines, launched with `default`, plus one
+/// action per OSC 99 button carrying its number.
+pub(super) fn toast_xml(notif: &Notification) -> String {
+    let mut xml = format!(
+        "<toast launch=\"default\"><visual><binding template=\"ToastGeneric\">\
+         <text>{}</text><text>{}</text></binding></visual>",
+        escape(summary(notif)),
+        escape(&notif.body),
+    );
+    if !notif.buttons.is_empty() {
+        xml.push_str("<actions>");
+        for (i, label) in notif.buttons.iter().enumerate() {
+            let _ = write!(
+                xml,
+                "<action content=\"{}\" arguments=\"{}\"/>",
+                escape(label),
+                i + 1
+            );
+        }
+        xml.push_str("</actions>");
+    }
+    xml.push_str("</toast>");
+    xml
+}
+
+/// `text` with XML's special characters escaped.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Register `app_id` for toasts under
+/// `HKCU\Software\Classes\AppUserModelId`, naming it [`APP_NAME`].
+#[allow(unsafe_code)]
+fn register_app_id(app_id: &str) {
+    use windows_sys::Win32::Foundation::ERROR_SUCCESS;
+    use windows_sys::Win32::System::Registry::{HKEY_CURRENT_USER, REG_SZ, RegSetKeyValueW};
+
+    let wide = |s: &str| -> Vec<u16> { s.encode_utf16().chain(std::iter::once(0)).collect() };
+    let key = wide(&format!("Software\\Classes\\AppUserModelId\\{app_id}"));
+    let name = wide("DisplayName");
+    let value = wide(APP_NAME);
+    // SAFETY: All three strings are NUL-terminated UTF-16 that outlive the
+    // call, and `cbdata` is the byte length of `value`.
+    let status = unsafe {
+        RegSetKeyValueW(
+            HKEY_CURRENT_USER,
+            key.as_ptr(),
+            name.as_ptr(),
+            REG_SZ,
+            value.as_ptr().cast(),
+            (value.len() * 2) as u32,
+        )
+    };
+    if status != ERROR_SUCCESS {
+        log(&format!("notification: can't register {app_id}: error {status}"));
+    }
+}
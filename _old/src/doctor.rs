@@ -0,0 +1,117 @@
+//! `oriterm doctor` — prints a diagnostic report for bug reports.
+//!
+//! Covers the config file, fonts resolved for each style, GPU adapters, and
+//! shell-integration status. Everything is probed the same way a normal
+//! launch would, without opening a window.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::config::{self, Config};
+use crate::font::FontCollection;
+use crate::gpu::GpuState;
+use crate::palette;
+use crate::shell_integration;
+use crate::tab::Tab;
+
+/// Style names in `FontCollection::style_paths` order.
+const STYLE_NAMES: [&str; 4] = ["regular", "bold", "italic", "bold italic"];
+
+/// Build the full diagnostic report.
+pub fn report() -> String {
+    let mut out = format!("oriterm {}\n", env!("CARGO_PKG_VERSION"));
+    let config = config_section(&mut out);
+    font_section(&mut out, &config);
+    gpu_section(&mut out);
+    shell_section(&mut out, &config);
+    out
+}
+
+/// Report config loading; returns the config a launch would use.
+fn config_section(out: &mut String) -> Config {
+    let path = config::config_path();
+    let _ = writeln!(out, "\n[config]\npath: {}", path.display());
+    let config = if path.exists() {
+        match Config::try_load() {
+            Ok(config) => {
+                let _ = writeln!(out, "status: ok");
+                config
+            }
+            Err(e) => {
+                let _ = writeln!(out, "status: error, using defaults\n  {e}");
+                Config::default()
+            }
+        }
+    } else {
+        let _ = writeln!(out, "status: not found, using defaults");
+        Config::default()
+    };
+    if palette::find_scheme(&config.colors.scheme).is_none() {
+        let _ = writeln!(out, "warning: unknown color scheme {:?}", config.colors.scheme);
+    }
+    config
+}
+
+fn font_section(out: &mut String, config: &Config) {
+    let fonts = FontCollection::load(
+        config.font.size,
+        config.font.family.as_deref(),
+        &FontCollection::parse_features(&config.font.features),
+        &config.font.fallback,
+        config.font.effective_weight(),
+    );
+    let _ = writeln!(
+        out,
+        "\n[fonts]\nfamily: {}\nsize: {}pt, weight: {}, cell: {}x{}",
+        config.font.family.as_deref().unwrap_or("(default)"),
+        fonts.size,
+        config.font.effective_weight(),
+        fonts.cell_width,
+        fonts.cell_height,
+    );
+    for (name, path) in STYLE_NAMES.iter().zip(fonts.style_paths()) {
+        let source = path
+            .as_deref()
+            .map_or_else(|| "synthesized / embedded".to_owned(), |p| p.display().to_string());
+        let _ = writeln!(out, "{name}: {source}");
+    }
+    for path in fonts.fallback_paths() {
+        let _ = writeln!(out, "fallback: {}", path.display());
+    }
+}
+
+fn gpu_section(out: &mut String) {
+    let _ = writeln!(out, "\n[gpu]");
+    let adapters = GpuState::describe_adapters();
+    if adapters.is_empty() {
+        let _ = writeln!(out, "no adapters found");
+    }
+    for adapter in adapters {
+        let _ = writeln!(out, "adapter: {adapter}");
+    }
+}
+
+fn shell_section(out: &mut String, config: &Config) {
+    let shell_line = config.terminal.shell.clone().unwrap_or_else(Tab::default_shell);
+    let program = shell_line.split_whitespace().next().unwrap_or("sh");
+    let detected = shell_integration::detect_shell(program)
+        .map_or_else(|| "unsupported".to_owned(), |s| format!("{s:?}"));
+    let dir = config::config_dir().join("shell-integration");
+    let _ = writeln!(
+        out,
+        "\n[shell integration]\nenabled: {}\nshell: {program} ({detected})\nscripts: {}",
+        config.behavior.shell_integration,
+        scripts_status(&dir),
+    );
+}
+
+fn scripts_status(dir: &Path) -> String {
+    let state = if shell_integration::scripts_up_to_date(dir) {
+        "up to date"
+    } else if dir.exists() {
+        "outdated or incomplete (rewritten on next launch)"
+    } else {
+        "not installed"
+    };
+    format!("{} ({state})", dir.display())
+}
@@ -0,0 +1,299 @@
+//! Embedding API: a terminal (grid, VTE parser, and PTY) without the
+//! oriterm window.
+//!
+//! A host GUI creates an [`EmbeddedTerminal`], forwards input to it as
+//! bytes or text, and calls [`EmbeddedTerminal::poll`] whenever the wake
+//! callback fires. [`EmbeddedTerminal::frame`] then returns the visible
+//! cells with resolved colors plus the rows that changed since the last
+//! frame, which the host draws however it likes. The frame types are
+//! `oriterm_core`'s, so a host that drives `oriterm_core::Term` itself gets
+//! the same [`Frame`] from `Term::frame`; this module adds the PTY. Nothing here touches
+//! winit or wgpu; hosts that use winit can encode keys with
+//! [`crate::key_encoding`] and send the result with
+//! [`EmbeddedTerminal::send_bytes`].
+
+use std::sync::{Arc, mpsc};
+use std::time::Instant;
+
+use oriterm_core::FrameSource;
+use oriterm_core::grid::CursorShape as CoreCursorShape;
+use vte::ansi::CursorShape;
+
+use crate::grid::{ClipEdge, Grid};
+use crate::palette::Palette;
+use crate::tab::{
+    Bracketing, EventSink, NotificationEvent, SpawnConfig, Tab, TabId, TaskProgress, TermEvent,
+};
+use crate::term_mode::TermMode;
+
+pub use oriterm_core::{Frame, FrameCell, FrameCursor};
+
+/// How to start an [`EmbeddedTerminal`].
+#[derive(Debug, Clone)]
+pub struct EmbedOptions {
+    /// Grid width in cells.
+    pub cols: usize,
+    /// Grid height in cells.
+    pub rows: usize,
+    /// Command line to run; the user's shell when `None`.
+    pub shell: Option<String>,
+    /// Working directory for the shell.
+    pub cwd: Option<String>,
+    /// Scrollback limit in rows.
+    pub max_scrollback: usize,
+}
+
+impl Default for EmbedOptions {
+    fn default() -> Self {
+        Self {
+            cols: 80,
+            rows: 24,
+            shell: None,
+            cwd: None,
+            max_scrollback: 10_000,
+        }
+    }
+}
+
+/// What happened since the previous [`EmbeddedTerminal::poll`].
+#[derive(Debug, Default)]
+pub struct Activity {
+    /// The child wrote output; a new [`Frame`] is likely available.
+    pub output: bool,
+    /// The bell rang.
+    pub bell: bool,
+    /// The title changed (see [`EmbeddedTerminal::title`]).
+    pub title_changed: bool,
+    /// Latest OSC 9;4 task progress, if it changed.
+    pub progress: Option<TaskProgress>,
+    /// OSC 9/99/777 notification requests, in order.
+    pub notifications: Vec<NotificationEvent>,
+    /// Text the application asked to put on the clipboard (OSC 52). The
+    /// host decides whether to honor it.
+    pub clipboard_write: Option<String>,
+    /// When to poll again to deliver a settled resize to the child.
+    pub wake_at: Option<Instant>,
+}
+
+/// Capture `grid` as a core [`Frame`] and reset its damage. Returns `None`
+/// when nothing changed since the last capture.
+pub fn capture(
+    grid: &mut Grid,
+    palette: &Palette,
+    mode: TermMode,
+    shape: CursorShape,
+) -> Option<Frame> {
+    Frame::capture(&mut Screen {
+        grid,
+        palette,
+        mode,
+        shape,
+    })
+}
+
+/// The prototype's [`Grid`] with the state its frame depends on.
+struct Screen<'a> {
+    grid: &'a mut Grid,
+    palette: &'a Palette,
+    mode: TermMode,
+    shape: CursorShape,
+}
+
+impl FrameSource for Screen<'_> {
+    fn frame_size(&self) -> (usize, usize) {
+        (self.grid.cols, self.grid.lines)
+    }
+
+    fn push_frame_row(&self, line: usize, out: &mut Vec<FrameCell>) {
+        let row = self.grid.visible_row(line);
+        out.extend((0..self.grid.cols).map(|col| {
+            let cell = &row[col];
+            FrameCell {
+                ch: cell.c,
+                zerowidth: cell.zerowidth().to_vec(),
+                fg: self.palette.resolve_fg(cell.fg, cell.bg, cell.flags),
+                bg: self.palette.resolve_bg(cell.fg, cell.bg, cell.flags),
+                flags: cell.flags.to_core(),
+            }
+        }));
+    }
+
+    fn frame_cursor(&self) -> Option<FrameCursor> {
+        let visible = self.mode.contains(TermMode::SHOW_CURSOR) && self.grid.display_offset == 0;
+        let shape = match self.shape {
+            CursorShape::Block => CoreCursorShape::Block,
+            CursorShape::Underline => CoreCursorShape::Underline,
+            CursorShape::Beam => CoreCursorShape::Bar,
+            CursorShape::HollowBlock => CoreCursorShape::HollowBlock,
+            CursorShape::Hidden => return None,
+        };
+        visible.then_some(FrameCursor {
+            line: self.grid.cursor.row,
+            col: self.grid.cursor.col,
+            shape,
+        })
+    }
+
+    fn take_damage(&mut self) -> Vec<usize> {
+        let damage = (0..self.grid.lines)
+            .filter(|&line| self.grid.dirty.is_dirty(line))
+            .collect();
+        self.grid.dirty.clear();
+        damage
+    }
+}
+
+/// Forwards tab events to the host's [`EmbeddedTerminal`] and wakes it.
+struct Channel {
+    tx: mpsc::Sender<TermEvent>,
+    wake: Box<dyn Fn() + Send + Sync>,
+}
+
+impl EventSink for Channel {
+    fn send(&self, event: TermEvent) {
+        if self.tx.send(event).is_ok() {
+            (self.wake)();
+        }
+    }
+}
+
+/// A running terminal session owned by a host application.
+pub struct EmbeddedTerminal {
+    tab: Tab,
+    events: mpsc::Receiver<TermEvent>,
+    exited: bool,
+}
+
+impl EmbeddedTerminal {
+    /// Start `opts.shell` in a new PTY. `wake` is called from background
+    /// threads whenever [`Self::poll`] has something to report; it should
+    /// only schedule a poll on the host's UI thread.
+    pub fn spawn(
+        opts: EmbedOptions,
+        wake: impl Fn() + Send + Sync + 'static,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let (tx, events) = mpsc::channel();
+        let tab = Tab::spawn(SpawnConfig {
+            id: TabId(0),
+            cols: opts.cols,
+            rows: opts.rows,
+            events: Arc::new(Channel {
+                tx,
+                wake: Box::new(wake),
+            }),
+            shell: opts.shell,
+            max_scrollback: opts.max_scrollback,
+            cursor_shape: CursorShape::Block,
+            integration_dir: None,
+            cwd: opts.cwd,
+            alt_screen_clip: ClipEdge::default(),
+            answerback: String::new(),
+            c1_controls: false,
+            history: None,
+        })?;
+        Ok(Self {
+            tab,
+            events,
+            exited: false,
+        })
+    }
+
+    /// Handle everything the background threads reported since the last
+    /// call.
+    pub fn poll(&mut self) -> Activity {
+        let mut activity = Activity::default();
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                TermEvent::Wakeup(_) => activity.output = true,
+                TermEvent::PtyExited(_) => self.exited = true,
+                TermEvent::Progress(_, progress) => activity.progress = Some(progress),
+                TermEvent::Paste(_) => self.tab.reap_paste(),
+                TermEvent::ConfigReload
+                | TermEvent::NotificationResponse(..)
+                | TermEvent::Search(..)
+                | TermEvent::Ipc(_) => {}
+            }
+        }
+        if activity.output {
+            self.tab.clear_wakeup();
+            let mut term = self.tab.terminal.lock();
+            activity.title_changed = std::mem::take(&mut term.title_dirty);
+            activity.bell = term.take_new_bell();
+            activity.notifications = term.drain_notifications();
+            activity.clipboard_write = term.clipboard_request.take();
+        }
+        activity.wake_at = self.tab.flush_pty_resize(Instant::now());
+        activity
+    }
+
+    /// The screen as of now, or `None` if nothing changed since the last
+    /// frame.
+    pub fn frame(&self) -> Option<Frame> {
+        let mut guard = self.tab.terminal.lock();
+        let term = &mut *guard;
+        let grid = if term.active_is_alt {
+            &mut term.alt_grid
+        } else {
+            &mut term.primary_grid
+        };
+        capture(grid, &term.palette, term.mode, term.cursor_shape)
+    }
+
+    /// Whether the child process has exited.
+    pub fn has_exited(&self) -> bool {
+        self.exited
+    }
+
+    /// The window title the application set, or the shell name.
+    pub fn title(&self) -> String {
+        self.tab.effective_title()
+    }
+
+    /// Write raw input (encoded keys, mouse reports) to the child.
+    pub fn send_bytes(&self, bytes: &[u8]) {
+        self.tab.send_input(bytes);
+    }
+
+    /// Type `text` as if entered on the keyboard.
+    pub fn send_text(&self, text: &str) {
+        self.tab.send_input(text.as_bytes());
+    }
+
+    /// Paste `text`, bracketed when the application enabled DECSET 2004.
+    /// Large pastes are written in the background; keep polling.
+    pub fn paste(&mut self, text: &str) {
+        let bracketing = if self.tab.mode().contains(TermMode::BRACKETED_PASTE) {
+            Bracketing::Bracketed
+        } else {
+            Bracketing::Plain
+        };
+        self.tab.paste(text, bracketing);
+    }
+
+    /// Resize the grid. The child is told once the size settles; keep
+    /// polling until [`Activity::wake_at`] is `None`.
+    pub fn resize(&mut self, cols: usize, rows: usize, pixel_width: u16, pixel_height: u16) {
+        self.tab.resize(cols, rows, pixel_width, pixel_height);
+    }
+
+    /// Tell the application whether the host widget has keyboard focus
+    /// (DECSET 1004 focus reports).
+    pub fn set_focused(&mut self, focused: bool) {
+        self.tab.set_focused(focused);
+    }
+
+    /// Scroll the viewport by `delta` lines (positive is up, into history).
+    pub fn scroll_lines(&self, delta: i32) {
+        self.tab.scroll_lines(delta);
+        self.tab.grid().dirty.mark_all();
+    }
+}
+
+impl Drop for EmbeddedTerminal {
+    fn drop(&mut self) {
+        self.tab.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests;
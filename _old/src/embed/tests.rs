@@ -0,0 +1,58 @@
+use vte::ansi::{Color, CursorShape, NamedColor};
+
+use super::capture;
+use crate::cell::CellFlags;
+use crate::grid::Grid;
+use crate::palette::Palette;
+use crate::term_mode::TermMode;
+
+#[test]
+fn capture_reports_cells_damage_and_cursor_once() {
+    let palette = Palette::new();
+    let mut grid = Grid::new(4, 2);
+    grid.put_char('h');
+    grid.put_char('i');
+
+    let frame = capture(&mut grid, &palette, TermMode::default(), CursorShape::Beam)
+        .expect("a new grid is fully damaged");
+    assert_eq!((frame.cols, frame.rows, frame.cells.len()), (4, 2, 8));
+    assert_eq!(frame.damage, [0, 1]);
+    let text: String = frame.row(0).iter().map(|c| c.ch).collect();
+    assert_eq!(text, "hi  ");
+    let default_fg = palette.resolve(Color::Named(NamedColor::Foreground), CellFlags::empty());
+    assert_eq!(frame.row(1)[0].fg, default_fg);
+    let cursor = frame.cursor.expect("cursor shown by default");
+    assert_eq!(
+        (cursor.line, cursor.col, cursor.shape),
+        (0, 2, oriterm_core::CursorShape::Bar)
+    );
+
+    // Nothing changed since: no frame.
+    assert_eq!(
+        capture(&mut grid, &palette, TermMode::default(), CursorShape::Beam),
+        None
+    );
+
+    // Only the touched row is damaged; DECTCEM off hides the cursor.
+    grid.cursor.row = 1;
+    grid.cursor.col = 0;
+    grid.put_char('x');
+    let frame = capture(&mut grid, &palette, TermMode::empty(), CursorShape::Beam)
+        .expect("row 1 changed");
+    assert_eq!(frame.damage, [1]);
+    assert_eq!(frame.row(1)[0].ch, 'x');
+    assert_eq!(frame.cursor, None);
+}
+
+#[test]
+fn capture_carries_combining_marks() {
+    let palette = Palette::new();
+    let mut grid = Grid::new(4, 2);
+    grid.put_char('e');
+    grid.row_mut(0)[0].push_zerowidth('\u{301}');
+
+    let frame = capture(&mut grid, &palette, TermMode::default(), CursorShape::Block)
+        .expect("a new grid is fully damaged");
+    assert_eq!(frame.row(0)[0].zerowidth, ['\u{301}']);
+    assert!(frame.row(0)[1].zerowidth.is_empty());
+}
@@ -13,6 +13,8 @@ use swash::zeno::Format;
 use crate::config::FallbackFontConfig;
 use crate::gpu::atlas::GlyphBitmap;
 use crate::render::font_discovery::FontFamily;
+
+use super::DecorationMetrics;
 #[cfg(target_os = "windows")]
 use crate::render::font_discovery::{
     resolve_fallback_paths_dwrite, resolve_family_paths_dwrite, resolve_user_fallback,
@@ -106,6 +108,8 @@ pub struct FontCollection {
     pub cell_height: usize,
     /// Baseline offset from top of cell.
     pub baseline: usize,
+    /// Strikethrough placement from the primary Regular font.
+    pub decoration: DecorationMetrics,
     /// CSS-style font weight (100–900) used during font discovery.
     weight: u16,
     /// Reusable scale context for swash rasterization.
@@ -128,6 +132,7 @@ impl FontCollection {
         weight: u16,
     ) -> Self {
         let (cell_width, cell_height, baseline) = compute_metrics(&bytes, face_index, size);
+        let decoration = decoration_metrics(&bytes, face_index, size, cell_height, baseline);
         let arc_bytes = Arc::new(bytes);
         let primary_cap_height_px = cap_height_px(&arc_bytes, face_index, size);
         Self {
@@ -155,6 +160,7 @@ impl FontCollection {
             cell_width,
             cell_height,
             baseline,
+            decoration,
             weight,
             scale_context: ScaleContext::new(),
         }
@@ -309,6 +315,17 @@ impl FontCollection {
         Cow::Owned(result)
     }
 
+    /// Font file for each primary style (Regular/Bold/Italic/BoldItalic),
+    /// `None` when the style is synthesized or the font is embedded.
+    pub fn style_paths(&self) -> &[Option<PathBuf>; 4] {
+        &self.font_paths
+    }
+
+    /// System fallback font files, in priority order.
+    pub fn fallback_paths(&self) -> &[PathBuf] {
+        &self.fallback_paths
+    }
+
     /// Returns true if the primary Regular font has a `wght` variation axis.
     pub fn has_wght_axis(&self) -> bool {
         self.primary[0]
@@ -443,9 +460,21 @@ impl FontCollection {
         face_idx: FaceIdx,
         glyph_id: u16,
         scale_ctx: &mut ScaleContext,
+    ) -> Option<GlyphBitmap> {
+        self.rasterize_scaled_glyph_with(face_idx, glyph_id, 1, scale_ctx)
+    }
+
+    /// Rasterize a glyph `scale` times the collection's size, for OSC 66
+    /// text that fills a block `scale` cells tall.
+    pub fn rasterize_scaled_glyph_with(
+        &self,
+        face_idx: FaceIdx,
+        glyph_id: u16,
+        scale: u8,
+        scale_ctx: &mut ScaleContext,
     ) -> Option<GlyphBitmap> {
         let fd = self.face(face_idx)?;
-        let size = self.effective_size(face_idx);
+        let size = self.effective_size(face_idx) * f32::from(scale);
         let wght = weight_variation_for(face_idx, self.weight);
         rasterize_from_face(fd, glyph_id, size, wght, scale_ctx)
     }
@@ -919,6 +948,35 @@ fn compute_metrics(bytes: &[u8], face_index: u32, size: f32) -> (usize, usize, u
     (cell_width, cell_height, baseline)
 }
 
+/// Compute strikethrough placement from the font's OS/2 strikeout metrics.
+///
+/// Fonts without the metric get a stroke through the middle of the cell.
+fn decoration_metrics(
+    bytes: &[u8],
+    face_index: u32,
+    size: f32,
+    cell_height: usize,
+    baseline: usize,
+) -> DecorationMetrics {
+    let Some(fr) = FontRef::from_index(bytes, face_index as usize) else {
+        return DecorationMetrics {
+            strikeout_y: (cell_height / 2) as f32,
+            thickness: 1.0,
+        };
+    };
+    let metrics = fr.metrics(&[]).scale(size);
+    let thickness = metrics.stroke_size.round().max(1.0);
+    let strikeout_y = if metrics.strikeout_offset > 0.0 {
+        (baseline as f32 - metrics.strikeout_offset).round()
+    } else {
+        (cell_height / 2) as f32
+    };
+    DecorationMetrics {
+        strikeout_y: strikeout_y.clamp(0.0, cell_height as f32 - thickness),
+        thickness,
+    }
+}
+
 /// Compute the cap height in pixels for a font at the given size.
 ///
 /// Reads `capital_height` from the OS/2 table via `ttf-parser`. Falls back to
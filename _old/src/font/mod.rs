@@ -9,7 +9,10 @@ mod collection;
 mod shaper;
 
 pub use collection::FontCollection;
-pub use shaper::{ShapingRun, UiShapedGlyph, prepare_line, shape_line, shape_prepared_runs, shape_text_string};
+pub use shaper::{
+    ShapingRun, UiShapedGlyph, prepare_line, shape_line, shape_prepared_runs, shape_text_string,
+    split_runs_by_direction,
+};
 
 use crate::render::FontStyle;
 
@@ -38,6 +41,15 @@ impl FaceIdx {
     }
 }
 
+/// Vertical placement of text decorations, in pixels from the cell top.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecorationMetrics {
+    /// Top of the strikethrough stroke.
+    pub strikeout_y: f32,
+    /// Stroke thickness recommended by the font (at least 1px).
+    pub thickness: f32,
+}
+
 /// A shaped glyph ready for atlas lookup and GPU rendering.
 #[derive(Debug, Clone, Copy)]
 pub struct ShapedGlyph {
@@ -1,6 +1,7 @@
 //! Text shaping via `rustybuzz` — segments grid rows into runs, shapes each run,
 //! and maps shaped glyphs back to grid columns.
 
+use crate::bidi::BidiLine;
 use crate::cell::{Cell, CellFlags, is_builtin_glyph};
 use crate::render::FontStyle;
 
@@ -81,6 +82,42 @@ pub struct ShapingRun {
     col_start: usize,
     /// Mapping from byte offset in `text` to grid column index.
     byte_to_col: Vec<usize>,
+    /// Shape right to left (the run is in an odd bidi level).
+    rtl: bool,
+}
+
+/// Split runs where the bidi direction changes and mark right-to-left runs,
+/// so Arabic and Hebrew are shaped in logical order with the right joining
+/// forms and mirrored brackets.
+pub fn split_runs_by_direction(runs: &mut Vec<ShapingRun>, bidi: &BidiLine) {
+    let mut out = Vec::with_capacity(runs.len());
+    for run in runs.drain(..) {
+        let mut start = 0;
+        let mut rtl = bidi.is_rtl(run.col_start);
+        for (offset, _) in run.text.char_indices() {
+            let col = run.byte_to_col[offset];
+            if bidi.is_rtl(col) != rtl && offset > start {
+                out.push(run.slice(start, offset, rtl));
+                start = offset;
+            }
+            rtl = bidi.is_rtl(col);
+        }
+        out.push(run.slice(start, run.text.len(), rtl));
+    }
+    *runs = out;
+}
+
+impl ShapingRun {
+    /// The bytes `start..end` of this run as a run of their own.
+    fn slice(&self, start: usize, end: usize, rtl: bool) -> Self {
+        Self {
+            text: self.text.get(start..end).unwrap_or_default().to_owned(),
+            face_idx: self.face_idx,
+            col_start: self.byte_to_col[start],
+            byte_to_col: self.byte_to_col[start..end].to_vec(),
+            rtl,
+        }
+    }
 }
 
 /// Segment a row of cells into shaping runs.
@@ -150,6 +187,7 @@ fn segment_runs(
                 face_idx,
                 col_start: col,
                 byte_to_col,
+                rtl: false,
             });
         }
 
@@ -172,7 +210,11 @@ fn shape_run(
 
     let mut buffer = rustybuzz::UnicodeBuffer::new();
     buffer.push_str(&run.text);
-    buffer.set_direction(rustybuzz::Direction::LeftToRight);
+    buffer.set_direction(if run.rtl {
+        rustybuzz::Direction::RightToLeft
+    } else {
+        rustybuzz::Direction::LeftToRight
+    });
 
     let features = collection.features_for_face(run.face_idx);
     let glyph_buffer = rustybuzz::shape(face, features, buffer);
@@ -411,4 +453,30 @@ mod tests {
             assert_eq!(a.col_span, b.col_span);
         }
     }
+
+    #[test]
+    fn runs_split_at_bidi_direction_changes() {
+        let mut fc = FontCollection::load(FONT_SIZE, None, &[], &[], 400);
+        fc.ensure_all_loaded();
+        let cells = make_cells("ab\u{5D0}\u{5D1}cd");
+        let bidi = BidiLine::resolve(&cells).expect("row has RTL text");
+
+        let mut runs = Vec::new();
+        prepare_line(&cells, cells.len(), &fc, &mut runs);
+        split_runs_by_direction(&mut runs, &bidi);
+
+        for run in &runs {
+            assert!(
+                run.byte_to_col.iter().all(|&col| bidi.is_rtl(col) == run.rtl),
+                "run mixes directions"
+            );
+        }
+        let rtl_cols: Vec<usize> = runs
+            .iter()
+            .filter(|run| run.rtl)
+            .flat_map(|run| run.byte_to_col.iter().copied())
+            .collect();
+        assert_eq!(rtl_cols.first(), Some(&2));
+        assert_eq!(rtl_cols.last(), Some(&3));
+    }
 }
@@ -7,8 +7,13 @@
 
 use std::collections::HashMap;
 
+mod packer;
+#[cfg(test)]
+mod tests;
+
 use crate::font::FaceIdx;
-use crate::icons::Icon;
+use crate::icons::{Icon, IconBitmap};
+use packer::RectPacker;
 
 /// Atlas page size in pixels (width = height).
 const PAGE_SIZE: u32 = 2048;
@@ -30,133 +35,6 @@ pub fn size_key(size: f32) -> u32 {
     (size * 64.0).round() as u32
 }
 
-// Axis-aligned rectangle for the packer's free-space tracking.
-#[derive(Debug, Clone, Copy)]
-struct Rect {
-    x: u32,
-    y: u32,
-    w: u32,
-    h: u32,
-}
-
-/// 2D rectangle bin packer using the Guillotine best-short-side-fit algorithm.
-///
-/// Maintains a list of free rectangles within a fixed-size page. When a glyph
-/// is packed, the best-fitting free rectangle is split into two smaller ones
-/// along the shorter leftover axis.
-///
-/// Reference: Jukka Jylanki, "A Thousand Ways to Pack the Bin" (2010).
-struct RectPacker {
-    width: u32,
-    height: u32,
-    free_rects: Vec<Rect>,
-}
-
-impl RectPacker {
-    fn new(width: u32, height: u32) -> Self {
-        Self {
-            width,
-            height,
-            free_rects: vec![Rect {
-                x: 0,
-                y: 0,
-                w: width,
-                h: height,
-            }],
-        }
-    }
-
-    /// Find space for a glyph of the given dimensions.
-    ///
-    /// Returns the top-left position `(x, y)` within the page, or `None`
-    /// if no free rectangle can fit the glyph.
-    ///
-    /// Uses best-short-side-fit: chooses the free rectangle where the shorter
-    /// leftover side after placement is minimized, breaking ties by the longer
-    /// leftover side. After placement, the chosen rectangle is split via the
-    /// Guillotine method (split along the shorter leftover axis).
-    fn pack(&mut self, glyph_w: u32, glyph_h: u32) -> Option<(u32, u32)> {
-        let mut best_idx = None;
-        let mut best_short = u32::MAX;
-        let mut best_long = u32::MAX;
-
-        for (i, r) in self.free_rects.iter().enumerate() {
-            if r.w >= glyph_w && r.h >= glyph_h {
-                let leftover_w = r.w - glyph_w;
-                let leftover_h = r.h - glyph_h;
-                let short = leftover_w.min(leftover_h);
-                let long = leftover_w.max(leftover_h);
-                if short < best_short || (short == best_short && long < best_long) {
-                    best_idx = Some(i);
-                    best_short = short;
-                    best_long = long;
-                }
-            }
-        }
-
-        let idx = best_idx?;
-        let r = self.free_rects[idx];
-        let pos = (r.x, r.y);
-
-        // Guillotine split: remove the chosen rect and add up to two children.
-        self.free_rects.swap_remove(idx);
-        let leftover_w = r.w - glyph_w;
-        let leftover_h = r.h - glyph_h;
-
-        // Split along the shorter leftover axis for better packing.
-        if leftover_w < leftover_h {
-            // Horizontal split.
-            if leftover_w > 0 {
-                self.free_rects.push(Rect {
-                    x: r.x + glyph_w,
-                    y: r.y,
-                    w: leftover_w,
-                    h: glyph_h,
-                });
-            }
-            if leftover_h > 0 {
-                self.free_rects.push(Rect {
-                    x: r.x,
-                    y: r.y + glyph_h,
-                    w: r.w,
-                    h: leftover_h,
-                });
-            }
-        } else {
-            // Vertical split.
-            if leftover_h > 0 {
-                self.free_rects.push(Rect {
-                    x: r.x,
-                    y: r.y + glyph_h,
-                    w: glyph_w,
-                    h: leftover_h,
-                });
-            }
-            if leftover_w > 0 {
-                self.free_rects.push(Rect {
-                    x: r.x + glyph_w,
-                    y: r.y,
-                    w: leftover_w,
-                    h: r.h,
-                });
-            }
-        }
-
-        Some(pos)
-    }
-
-    /// Reset the packer to a single free rectangle covering the full page.
-    fn reset(&mut self) {
-        self.free_rects.clear();
-        self.free_rects.push(Rect {
-            x: 0,
-            y: 0,
-            w: self.width,
-            h: self.height,
-        });
-    }
-}
-
 /// A single page within the atlas texture array.
 struct AtlasPage {
     packer: RectPacker,
@@ -215,6 +93,8 @@ pub struct GlyphAtlas {
     /// Monotonically increasing frame counter for LRU tracking.
     frame_counter: u64,
     icon_entries: HashMap<(Icon, u16), AtlasEntry>,
+    /// Anti-aliased built-in glyphs keyed by character and cell size.
+    builtin_entries: HashMap<(char, u16, u16), AtlasEntry>,
     /// Cache for shaped glyphs (glyph ID + face index + size + collection ID).
     shaped_entries: HashMap<ShapedGlyphKey, AtlasEntry>,
 }
@@ -240,6 +120,7 @@ impl GlyphAtlas {
             max_pages: MAX_PAGES,
             frame_counter: 0,
             icon_entries: HashMap::new(),
+            builtin_entries: HashMap::new(),
             shaped_entries: HashMap::new(),
         }
     }
@@ -259,19 +140,35 @@ impl GlyphAtlas {
         let key = (icon, size_px);
         if !self.icon_entries.contains_key(&key) {
             let bmp = icon.rasterize(u32::from(size_px));
-            let metrics = GlyphMetrics {
-                width: bmp.width as usize,
-                height: bmp.height as usize,
-                left: 0,
-                top: 0,
-                advance_width: bmp.width as f32,
-            };
-            let entry = self.upload_bitmap(&metrics, &bmp.data, queue);
+            let entry = self.upload_icon_bitmap(&bmp, queue);
             self.icon_entries.insert(key, entry);
         }
-        self.icon_entries
-            .get(&key)
-            .expect("icon entry just inserted")
+        self.icon_entries.entry(key).or_insert_with(AtlasEntry::empty)
+    }
+
+    /// Look up an anti-aliased built-in glyph rasterized at the given cell
+    /// size, inserting it if missing.
+    #[allow(clippy::map_entry, reason = "Entry API unusable: upload_bitmap() borrows &mut self")]
+    pub fn get_or_insert_builtin(
+        &mut self,
+        c: char,
+        size: (u16, u16),
+        rasterize: impl FnOnce() -> Option<IconBitmap>,
+        queue: &wgpu::Queue,
+    ) -> &AtlasEntry {
+        let key = (c, size.0, size.1);
+        if !self.builtin_entries.contains_key(&key) {
+            let entry = match rasterize() {
+                Some(bmp) => self.upload_icon_bitmap(&bmp, queue),
+                None => AtlasEntry::empty(),
+            };
+            self.builtin_entries.insert(key, entry);
+        }
+        let entry = self.builtin_entries.entry(key).or_insert_with(AtlasEntry::empty);
+        if let Some(page) = self.pages.get_mut(entry.page as usize) {
+            page.last_used_frame = self.frame_counter;
+        }
+        entry
     }
 
     /// Look up a shaped glyph in the atlas, inserting it if missing.
@@ -307,7 +204,7 @@ impl GlyphAtlas {
             }
         }
 
-        let entry = self.shaped_entries.get(&key).expect("shaped entry just inserted");
+        let entry = self.shaped_entries.entry(key).or_insert_with(AtlasEntry::empty);
         if let Some(page) = self.pages.get_mut(entry.page as usize) {
             page.last_used_frame = self.frame_counter;
         }
@@ -324,6 +221,7 @@ impl GlyphAtlas {
     /// Call this when font size changes (atlas needs to be rebuilt).
     pub fn clear(&mut self) {
         self.icon_entries.clear();
+        self.builtin_entries.clear();
         self.shaped_entries.clear();
         for page in &mut self.pages {
             page.packer.reset();
@@ -332,6 +230,18 @@ impl GlyphAtlas {
     }
 
     /// Allocate atlas space, upload bitmap, and return the entry.
+    /// Upload an icon-style alpha bitmap drawn from its top-left corner.
+    fn upload_icon_bitmap(&mut self, bmp: &IconBitmap, queue: &wgpu::Queue) -> AtlasEntry {
+        let metrics = GlyphMetrics {
+            width: bmp.width as usize,
+            height: bmp.height as usize,
+            left: 0,
+            top: 0,
+            advance_width: bmp.width as f32,
+        };
+        self.upload_bitmap(&metrics, &bmp.data, queue)
+    }
+
     fn upload_bitmap(
         &mut self,
         metrics: &GlyphMetrics,
@@ -433,6 +343,7 @@ impl GlyphAtlas {
 
         // Remove all entries pointing to the evicted page.
         self.icon_entries.retain(|_, e| e.page as usize != lru_idx);
+        self.builtin_entries.retain(|_, e| e.page as usize != lru_idx);
         self.shaped_entries.retain(|_, e| e.page as usize != lru_idx);
 
         let pos = self.pages[lru_idx]
@@ -479,83 +390,3 @@ impl AtlasEntry {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn size_key_26_6_precision() {
-        // 16.0pt → 1024
-        assert_eq!(size_key(16.0), 1024);
-        // 16.5pt → 1056
-        assert_eq!(size_key(16.5), 1056);
-        // Fractional DPI sizes that would collide with old 0.1pt precision.
-        assert_ne!(size_key(13.95), size_key(14.05));
-        // Old key: (13.95*10).round()=140, (14.05*10).round()=141 — barely distinct.
-        // New key: (13.95*64).round()=893, (14.05*64).round()=899 — clearly distinct.
-    }
-
-    #[test]
-    fn rect_packer_single_glyph() {
-        let mut p = RectPacker::new(2048, 2048);
-        let pos = p.pack(16, 20);
-        assert_eq!(pos, Some((0, 0)));
-    }
-
-    #[test]
-    fn rect_packer_multiple_no_overlap() {
-        let mut p = RectPacker::new(256, 256);
-        let mut packed = Vec::new();
-        for _ in 0..50 {
-            if let Some((x, y)) = p.pack(16, 20) {
-                packed.push((x, y, 16u32, 20u32));
-            }
-        }
-        // Verify no overlaps.
-        for (i, a) in packed.iter().enumerate() {
-            for b in &packed[i + 1..] {
-                let overlap_x = a.0 < b.0 + b.2 && b.0 < a.0 + a.2;
-                let overlap_y = a.1 < b.1 + b.3 && b.1 < a.1 + a.3;
-                assert!(
-                    !(overlap_x && overlap_y),
-                    "overlap: ({},{} {}x{}) vs ({},{} {}x{})",
-                    a.0, a.1, a.2, a.3, b.0, b.1, b.2, b.3,
-                );
-            }
-        }
-    }
-
-    #[test]
-    fn rect_packer_page_full() {
-        let mut p = RectPacker::new(32, 32);
-        // Fill until pack returns None.
-        let mut count = 0;
-        while p.pack(16, 16).is_some() {
-            count += 1;
-            // Safety valve — 32x32 can fit at most 4 of 16x16.
-            assert!(count <= 4, "packed too many");
-        }
-        assert_eq!(count, 4);
-    }
-
-    #[test]
-    fn rect_packer_best_short_side_fit() {
-        let mut p = RectPacker::new(100, 100);
-        // Pack a glyph that leaves a large area, then pack another.
-        let pos1 = p.pack(60, 40);
-        assert!(pos1.is_some());
-        // Next pack should go to the rectangle with the best short-side fit.
-        let pos2 = p.pack(30, 30);
-        assert!(pos2.is_some());
-        assert_ne!(pos1, pos2);
-    }
-
-    #[test]
-    fn rect_packer_reset() {
-        let mut p = RectPacker::new(32, 32);
-        while p.pack(16, 16).is_some() {}
-        assert!(p.pack(16, 16).is_none());
-        p.reset();
-        assert!(p.pack(16, 16).is_some());
-    }
-}
@@ -0,0 +1,128 @@
+//! Guillotine rectangle packing for atlas pages.
+
+// Axis-aligned rectangle for the packer's free-space tracking.
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+/// 2D rectangle bin packer using the Guillotine best-short-side-fit algorithm.
+///
+/// Maintains a list of free rectangles within a fixed-size page. When a glyph
+/// is packed, the best-fitting free rectangle is split into two smaller ones
+/// along the shorter leftover axis.
+///
+/// Reference: Jukka Jylanki, "A Thousand Ways to Pack the Bin" (2010).
+pub(super) struct RectPacker {
+    width: u32,
+    height: u32,
+    free_rects: Vec<Rect>,
+}
+
+impl RectPacker {
+    pub(super) fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            free_rects: vec![Rect {
+                x: 0,
+                y: 0,
+                w: width,
+                h: height,
+            }],
+        }
+    }
+
+    /// Find space for a glyph of the given dimensions.
+    ///
+    /// Returns the top-left position `(x, y)` within the page, or `None`
+    /// if no free rectangle can fit the glyph.
+    ///
+    /// Uses best-short-side-fit: chooses the free rectangle where the shorter
+    /// leftover side after placement is minimized, breaking ties by the longer
+    /// leftover side. After placement, the chosen rectangle is split via the
+    /// Guillotine method (split along the shorter leftover axis).
+    pub(super) fn pack(&mut self, glyph_w: u32, glyph_h: u32) -> Option<(u32, u32)> {
+        let mut best_idx = None;
+        let mut best_short = u32::MAX;
+        let mut best_long = u32::MAX;
+
+        for (i, r) in self.free_rects.iter().enumerate() {
+            if r.w >= glyph_w && r.h >= glyph_h {
+                let leftover_w = r.w - glyph_w;
+                let leftover_h = r.h - glyph_h;
+                let short = leftover_w.min(leftover_h);
+                let long = leftover_w.max(leftover_h);
+                if short < best_short || (short == best_short && long < best_long) {
+                    best_idx = Some(i);
+                    best_short = short;
+                    best_long = long;
+                }
+            }
+        }
+
+        let idx = best_idx?;
+        let r = self.free_rects[idx];
+        let pos = (r.x, r.y);
+
+        // Guillotine split: remove the chosen rect and add up to two children.
+        self.free_rects.swap_remove(idx);
+        let leftover_w = r.w - glyph_w;
+        let leftover_h = r.h - glyph_h;
+
+        // Split along the shorter leftover axis for better packing.
+        if leftover_w < leftover_h {
+            // Horizontal split.
+            if leftover_w > 0 {
+                self.free_rects.push(Rect {
+                    x: r.x + glyph_w,
+                    y: r.y,
+                    w: leftover_w,
+                    h: glyph_h,
+                });
+            }
+            if leftover_h > 0 {
+                self.free_rects.push(Rect {
+                    x: r.x,
+                    y: r.y + glyph_h,
+                    w: r.w,
+                    h: leftover_h,
+                });
+            }
+        } else {
+            // Vertical split.
+            if leftover_h > 0 {
+                self.free_rects.push(Rect {
+                    x: r.x,
+                    y: r.y + glyph_h,
+                    w: glyph_w,
+                    h: leftover_h,
+                });
+            }
+            if leftover_w > 0 {
+                self.free_rects.push(Rect {
+                    x: r.x + glyph_w,
+                    y: r.y,
+                    w: leftover_w,
+                    h: r.h,
+                });
+            }
+        }
+
+        Some(pos)
+    }
+
+    /// Reset the packer to a single free rectangle covering the full page.
+    pub(super) fn reset(&mut self) {
+        self.free_rects.clear();
+        self.free_rects.push(Rect {
+            x: 0,
+            y: 0,
+            w: self.width,
+            h: self.height,
+        });
+    }
+}
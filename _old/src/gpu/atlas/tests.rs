@@ -0,0 +1,80 @@
+//! Tests for the glyph atlas size key and rectangle packer.
+
+use super::packer::RectPacker;
+use super::size_key;
+
+#[test]
+fn size_key_26_6_precision() {
+    // 16.0pt → 1024
+    assert_eq!(size_key(16.0), 1024);
+    // 16.5pt → 1056
+    assert_eq!(size_key(16.5), 1056);
+    // Fractional DPI sizes that would collide with old 0.1pt precision.
+    assert_ne!(size_key(13.95), size_key(14.05));
+    // Old key: (13.95*10).round()=140, (14.05*10).round()=141 — barely distinct.
+    // New key: (13.95*64).round()=893, (14.05*64).round()=899 — clearly distinct.
+}
+
+#[test]
+fn rect_packer_single_glyph() {
+    let mut p = RectPacker::new(2048, 2048);
+    let pos = p.pack(16, 20);
+    assert_eq!(pos, Some((0, 0)));
+}
+
+#[test]
+fn rect_packer_multiple_no_overlap() {
+    let mut p = RectPacker::new(256, 256);
+    let mut packed = Vec::new();
+    for _ in 0..50 {
+        if let Some((x, y)) = p.pack(16, 20) {
+            packed.push((x, y, 16u32, 20u32));
+        }
+    }
+    // Verify no overlaps.
+    for (i, a) in packed.iter().enumerate() {
+        for b in &packed[i + 1..] {
+            let overlap_x = a.0 < b.0 + b.2 && b.0 < a.0 + a.2;
+            let overlap_y = a.1 < b.1 + b.3 && b.1 < a.1 + a.3;
+            assert!(
+                !(overlap_x && overlap_y),
+                "overlap: ({},{} {}x{}) vs ({},{} {}x{})",
+                a.0, a.1, a.2, a.3, b.0, b.1, b.2, b.3,
+            );
+        }
+    }
+}
+
+#[test]
+fn rect_packer_page_full() {
+    let mut p = RectPacker::new(32, 32);
+    // Fill until pack returns None.
+    let mut count = 0;
+    while p.pack(16, 16).is_some() {
+        count += 1;
+        // Safety valve — 32x32 can fit at most 4 of 16x16.
+        assert!(count <= 4, "packed too many");
+    }
+    assert_eq!(count, 4);
+}
+
+#[test]
+fn rect_packer_best_short_side_fit() {
+    let mut p = RectPacker::new(100, 100);
+    // Pack a glyph that leaves a large area, then pack another.
+    let pos1 = p.pack(60, 40);
+    assert!(pos1.is_some());
+    // Next pack should go to the rectangle with the best short-side fit.
+    let pos2 = p.pack(30, 30);
+    assert!(pos2.is_some());
+    assert_ne!(pos1, pos2);
+}
+
+#[test]
+fn rect_packer_reset() {
+    let mut p = RectPacker::new(32, 32);
+    while p.pack(16, 16).is_some() {}
+    assert!(p.pack(16, 16).is_none());
+    p.reset();
+    assert!(p.pack(16, 16).is_some());
+}
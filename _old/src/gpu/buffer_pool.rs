@@ -0,0 +1,141 @@
+//! Persistent GPU instance buffers, uploaded through a staging belt.
+//!
+//! Each draw list (grid bg/fg, overlays, settings window) owns one vertex
+//! buffer that lives as long as the renderer and only grows, in power-of-two
+//! steps, so tab and window switches never recreate buffers. Uploads go
+//! through `wgpu::util::StagingBelt`: instance bytes are written into mapped
+//! staging chunks and copied on the GPU timeline, and chunks still in flight
+//! are re-mapped asynchronously while the next frame writes into another one.
+
+use std::num::NonZeroU64;
+
+use wgpu::util::StagingBelt;
+
+use super::pipeline::INSTANCE_STRIDE;
+
+/// Smallest instance buffer allocated (64 KiB, ~800 instances).
+const MIN_CAPACITY: u64 = 64 * 1024;
+
+/// Staging chunk size. Typical full-screen grids fit in one chunk; larger
+/// uploads get a dedicated chunk from the belt.
+const STAGING_CHUNK: u64 = 4 << 20;
+
+/// Draw list an instance buffer belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Slot {
+    Bg,
+    Fg,
+    OverlayBg,
+    OverlayFg,
+    SettingsBg,
+    SettingsFg,
+}
+
+impl Slot {
+    const COUNT: usize = 6;
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Bg => "bg_instances",
+            Self::Fg => "fg_instances",
+            Self::OverlayBg => "overlay_bg",
+            Self::OverlayFg => "overlay_fg",
+            Self::SettingsBg => "settings_bg",
+            Self::SettingsFg => "settings_fg",
+        }
+    }
+}
+
+/// Capacity to allocate for `needed` bytes: the next power of two, at
+/// least [`MIN_CAPACITY`], so small growth doesn't reallocate every frame.
+pub(super) fn grown_capacity(needed: u64) -> u64 {
+    needed
+        .max(INSTANCE_STRIDE)
+        .next_power_of_two()
+        .max(MIN_CAPACITY)
+}
+
+/// One persistent vertex buffer per [`Slot`] plus the shared staging belt.
+pub(super) struct InstanceBuffers {
+    buffers: [Option<wgpu::Buffer>; Slot::COUNT],
+    belt: StagingBelt,
+    /// Buffers created or grown since [`InstanceBuffers::take_allocations`].
+    allocations: u32,
+}
+
+impl InstanceBuffers {
+    pub(super) fn new(device: &wgpu::Device) -> Self {
+        Self {
+            buffers: Default::default(),
+            belt: StagingBelt::new(device.clone(), STAGING_CHUNK),
+            allocations: 0,
+        }
+    }
+
+    /// Stage `data` for `slot`'s buffer, recording the copy in `encoder`.
+    ///
+    /// Returns the buffer to draw from. Hand `encoder` to
+    /// [`InstanceBuffers::submit`] so the staged copies are flushed.
+    pub(super) fn upload(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        slot: Slot,
+        data: &[u8],
+    ) -> wgpu::Buffer {
+        let buffer = self.ensure_capacity(device, slot, data.len() as u64);
+        if let Some(size) = NonZeroU64::new(data.len() as u64) {
+            self.belt
+                .write_buffer(encoder, &buffer, 0, size)
+                .copy_from_slice(data);
+        }
+        buffer
+    }
+
+    /// Stage the overlay's bg and fg instances, or drop both buffers when
+    /// the overlay is empty.
+    pub(super) fn upload_overlay(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        bg: &[u8],
+        fg: &[u8],
+    ) -> (Option<wgpu::Buffer>, Option<wgpu::Buffer>) {
+        if bg.is_empty() && fg.is_empty() {
+            return (None, None);
+        }
+        (
+            Some(self.upload(device, encoder, Slot::OverlayBg, bg)),
+            Some(self.upload(device, encoder, Slot::OverlayFg, fg)),
+        )
+    }
+
+    fn ensure_capacity(&mut self, device: &wgpu::Device, slot: Slot, needed: u64) -> wgpu::Buffer {
+        let entry = &mut self.buffers[slot as usize];
+        if let Some(buffer) = entry.as_ref().filter(|b| b.size() >= needed) {
+            return buffer.clone();
+        }
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(slot.label()),
+            size: grown_capacity(needed),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        self.allocations += 1;
+        *entry = Some(buffer.clone());
+        buffer
+    }
+
+    /// Close this frame's staging chunks, submit `encoder`, and re-map the
+    /// chunks once the GPU is done with them.
+    pub(super) fn submit(&mut self, queue: &wgpu::Queue, encoder: wgpu::CommandEncoder) {
+        self.belt.finish();
+        queue.submit(std::iter::once(encoder.finish()));
+        self.belt.recall();
+    }
+
+    /// Number of buffer allocations since the last call.
+    pub(super) fn take_allocations(&mut self) -> u32 {
+        std::mem::take(&mut self.allocations)
+    }
+}
@@ -1,8 +1,9 @@
-//! Built-in pixel-perfect rendering for box drawing, block elements, braille, and Powerline glyphs.
+//! Built-in pixel-perfect rendering for box drawing, block elements, and braille.
 //!
 //! These bypass the font pipeline entirely, producing geometrically precise
 //! results regardless of which font is loaded. Configurable via `builtin_glyphs`
-//! config option (default: on).
+//! config option (default: on). Rounded corners, diagonals, and Powerline
+//! separators are anti-aliased bitmaps instead (see [`super::builtin_smooth`]).
 
 use super::instance_writer::InstanceWriter;
 
@@ -23,9 +24,6 @@ pub(super) fn draw_builtin_glyph(
         '\u{2500}'..='\u{257F}' => draw_box_drawing(c, x, y, w, h, fg, bg),
         '\u{2580}'..='\u{259F}' => draw_block_element(c, x, y, w, h, fg, bg),
         '\u{2800}'..='\u{28FF}' => draw_braille(c, x, y, w, h, fg, bg),
-        '\u{E0A0}'..='\u{E0A3}' | '\u{E0B0}'..='\u{E0D4}' => {
-            draw_powerline(c, x, y, w, h, fg, bg)
-        }
         _ => false,
     }
 }
@@ -198,7 +196,7 @@ const BOX_DRAWING_TABLE: [[u8; 4]; 128] = [
     [1,1,3,3], // 256A ╪  vertical single and horizontal double
     [3,3,1,1], // 256B ╫  vertical double and horizontal single
     [3,3,3,3], // 256C ╬  double vertical and horizontal
-    [0,0,0,0], // 256D ╭  arc down and right (fallback: corner)
+    [0,0,0,0], // 256D ╭  arc down and right (anti-aliased bitmap)
     [0,0,0,0], // 256E ╮  arc down and left
     [0,0,0,0], // 256F ╯  arc up and left
     // U+2570-U+257F
@@ -230,19 +228,6 @@ fn draw_box_drawing(
     fg: [f32; 4],
     bg: &mut InstanceWriter,
 ) -> bool {
-    let idx = c as u32 - 0x2500;
-
-    // Rounded corners (U+256D-U+2570): render as simple right-angle corners.
-    // True arc rendering would need the shader; this is a reasonable fallback.
-    if (0x6D..=0x70).contains(&idx) {
-        return draw_rounded_corner(c, x, y, w, h, fg, bg);
-    }
-
-    // Diagonals (U+2571-U+2573): render as thin angled lines via small rects.
-    if (0x71..=0x73).contains(&idx) {
-        return draw_diagonal(c, x, y, w, h, fg, bg);
-    }
-
     let [left, right, up, down] = box_segments(c);
     if !left.is_some() && !right.is_some() && !up.is_some() && !down.is_some() {
         return false;
@@ -334,95 +319,6 @@ fn draw_v_segment(
     }
 }
 
-/// Draw rounded corners (U+256D-U+2570) as right-angle segments.
-#[expect(clippy::many_single_char_names, reason = "Geometric drawing with standard x/y/w/h/c names")]
-fn draw_rounded_corner(
-    c: char,
-    x: f32,
-    y: f32,
-    w: f32,
-    h: f32,
-    fg: [f32; 4],
-    bg: &mut InstanceWriter,
-) -> bool {
-    let thin = 1.0f32.max((w / 8.0).round());
-    let thick = thin * 3.0;
-    let cx = x + (w / 2.0).floor();
-    let cy = y + (h / 2.0).floor();
-    // Render as simple corners (matching the straight-line versions).
-    match c {
-        '\u{256D}' => {
-            // Arc down and right → ┌
-            draw_h_segment(Weight::Light, x + w, cx, cy, thin, thick, fg, bg);
-            draw_v_segment(Weight::Light, y + h, cy, cx, thin, thick, fg, bg);
-        }
-        '\u{256E}' => {
-            // Arc down and left → ┐
-            draw_h_segment(Weight::Light, cx, x, cy, thin, thick, fg, bg);
-            draw_v_segment(Weight::Light, y + h, cy, cx, thin, thick, fg, bg);
-        }
-        '\u{256F}' => {
-            // Arc up and left → ┘
-            draw_h_segment(Weight::Light, cx, x, cy, thin, thick, fg, bg);
-            draw_v_segment(Weight::Light, cy, y, cx, thin, thick, fg, bg);
-        }
-        '\u{2570}' => {
-            // Arc up and right → └
-            draw_h_segment(Weight::Light, x + w, cx, cy, thin, thick, fg, bg);
-            draw_v_segment(Weight::Light, cy, y, cx, thin, thick, fg, bg);
-        }
-        _ => return false,
-    }
-    true
-}
-
-/// Draw diagonal lines (U+2571-U+2573) as small rectangular steps.
-#[expect(clippy::many_single_char_names, reason = "Geometric drawing")]
-fn draw_diagonal(
-    c: char,
-    x: f32,
-    y: f32,
-    w: f32,
-    h: f32,
-    fg: [f32; 4],
-    bg: &mut InstanceWriter,
-) -> bool {
-    let steps = h as usize;
-    let thin = 1.0f32.max((w / 8.0).round());
-
-    match c {
-        '\u{2571}' => {
-            // ╱ upper right to lower left
-            for i in 0..steps {
-                let frac = i as f32 / h;
-                let px = x + w - w * frac - thin;
-                let py = y + i as f32;
-                bg.push_rect(px, py, thin, 1.0, fg);
-            }
-        }
-        '\u{2572}' => {
-            // ╲ upper left to lower right
-            for i in 0..steps {
-                let frac = i as f32 / h;
-                let px = x + w * frac;
-                let py = y + i as f32;
-                bg.push_rect(px, py, thin, 1.0, fg);
-            }
-        }
-        '\u{2573}' => {
-            // ╳ diagonal cross (both diagonals)
-            for i in 0..steps {
-                let frac = i as f32 / h;
-                let py = y + i as f32;
-                bg.push_rect(x + w - w * frac - thin, py, thin, 1.0, fg);
-                bg.push_rect(x + w * frac, py, thin, 1.0, fg);
-            }
-        }
-        _ => return false,
-    }
-    true
-}
-
 // Block Elements (U+2580-U+259F)
 
 #[expect(clippy::many_single_char_names, reason = "Geometric drawing with standard x/y/w/h/c names")]
@@ -555,101 +451,6 @@ fn draw_braille(
     true
 }
 
-// Powerline Glyphs (U+E0A0-U+E0A3, U+E0B0-U+E0D4)
-
-#[expect(clippy::many_single_char_names, reason = "Geometric drawing")]
-fn draw_powerline(
-    c: char,
-    x: f32,
-    y: f32,
-    w: f32,
-    h: f32,
-    fg: [f32; 4],
-    bg: &mut InstanceWriter,
-) -> bool {
-    match c {
-        // U+E0B0, U+E0B4: right-pointing solid triangle / rounded separator
-        '\u{E0B0}' | '\u{E0B4}' => draw_triangle_right(x, y, w, h, fg, bg),
-        // U+E0B1: right-pointing triangle (outline)
-        '\u{E0B1}' => draw_triangle_right_thin(x, y, w, h, fg, bg),
-        // U+E0B2, U+E0B6: left-pointing solid triangle / rounded separator
-        '\u{E0B2}' | '\u{E0B6}' => draw_triangle_left(x, y, w, h, fg, bg),
-        // U+E0B3: left-pointing triangle (outline)
-        '\u{E0B3}' => draw_triangle_left_thin(x, y, w, h, fg, bg),
-        // Everything else (icons, unhandled extra glyphs) — use font path.
-        _ => return false,
-    }
-    true
-}
-
-/// Solid right-pointing triangle filling the entire cell.
-fn draw_triangle_right(x: f32, y: f32, w: f32, h: f32, fg: [f32; 4], bg: &mut InstanceWriter) {
-    let steps = h as usize;
-    let mid = h / 2.0;
-    for i in 0..steps {
-        let frac = (i as f32 - mid).abs() / mid;
-        let line_w = w * (1.0 - frac);
-        if line_w > 0.0 {
-            bg.push_rect(x, y + i as f32, line_w, 1.0, fg);
-        }
-    }
-}
-
-/// Thin right-pointing triangle (outline only).
-fn draw_triangle_right_thin(
-    x: f32,
-    y: f32,
-    w: f32,
-    h: f32,
-    fg: [f32; 4],
-    bg: &mut InstanceWriter,
-) {
-    let steps = h as usize;
-    let mid = h / 2.0;
-    let thin = 1.0f32.max((w / 8.0).round());
-    for i in 0..steps {
-        let frac = (i as f32 - mid).abs() / mid;
-        let edge_x = w * (1.0 - frac);
-        if edge_x > 0.0 {
-            bg.push_rect(x + edge_x - thin, y + i as f32, thin, 1.0, fg);
-        }
-    }
-}
-
-/// Solid left-pointing triangle filling the entire cell.
-fn draw_triangle_left(x: f32, y: f32, w: f32, h: f32, fg: [f32; 4], bg: &mut InstanceWriter) {
-    let steps = h as usize;
-    let mid = h / 2.0;
-    for i in 0..steps {
-        let frac = (i as f32 - mid).abs() / mid;
-        let line_w = w * (1.0 - frac);
-        if line_w > 0.0 {
-            bg.push_rect(x + w - line_w, y + i as f32, line_w, 1.0, fg);
-        }
-    }
-}
-
-/// Thin left-pointing triangle (outline only).
-fn draw_triangle_left_thin(
-    x: f32,
-    y: f32,
-    w: f32,
-    h: f32,
-    fg: [f32; 4],
-    bg: &mut InstanceWriter,
-) {
-    let steps = h as usize;
-    let mid = h / 2.0;
-    let thin = 1.0f32.max((w / 8.0).round());
-    for i in 0..steps {
-        let frac = (i as f32 - mid).abs() / mid;
-        let edge_x = w * (1.0 - frac);
-        if edge_x > 0.0 {
-            bg.push_rect(x + w - edge_x, y + i as f32, thin, 1.0, fg);
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
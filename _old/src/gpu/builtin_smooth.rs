@@ -0,0 +1,152 @@
+//! Anti-aliased built-in glyphs: rounded corners, diagonals, and Powerline
+//! separators.
+//!
+//! Curves and slopes can't be built from axis-aligned rectangles without
+//! stair-stepping, so these glyphs are rasterized to alpha bitmaps at the
+//! exact cell size (which already includes the scale factor) and cached in
+//! the glyph atlas. Straight arms use the same thickness and pixel offsets
+//! as [`super::builtin_glyphs`], so they join the neighbouring box-drawing
+//! lines without a seam.
+
+use std::f32::consts::FRAC_PI_2;
+
+use crate::icons::{IconBitmap, rasterize_filled, rasterize_segments};
+
+use super::atlas::GlyphAtlas;
+use super::instance_writer::InstanceWriter;
+
+/// Line segments used to approximate a quarter arc.
+const ARC_STEPS: usize = 12;
+
+/// Whether `c` is drawn from an anti-aliased bitmap rather than rectangles.
+pub(super) fn is_smooth_glyph(c: char) -> bool {
+    matches!(
+        c,
+        '\u{256D}'..='\u{2573}' | '\u{E0B0}'..='\u{E0B4}' | '\u{E0B6}'
+    )
+}
+
+/// Draw a smooth built-in glyph filling the `size` cell at (`x`, `y`).
+#[expect(
+    clippy::too_many_arguments,
+    reason = "Renderer function requires full context for drawing"
+)]
+pub(super) fn push_smooth_glyph(
+    atlas: &mut GlyphAtlas,
+    fg: &mut InstanceWriter,
+    c: char,
+    x: f32,
+    y: f32,
+    size: (f32, f32),
+    color: [f32; 4],
+    queue: &wgpu::Queue,
+) {
+    let key = (size.0 as u16, size.1 as u16);
+    let entry = atlas.get_or_insert_builtin(
+        c,
+        key,
+        || rasterize(c, u32::from(key.0), u32::from(key.1)),
+        queue,
+    );
+    if entry.metrics.width > 0 && entry.metrics.height > 0 {
+        let (gw, gh) = (entry.metrics.width as f32, entry.metrics.height as f32);
+        fg.push_glyph(
+            x,
+            y,
+            gw,
+            gh,
+            entry.uv_pos,
+            entry.uv_size,
+            color,
+            [0.0; 4],
+            entry.page,
+        );
+    }
+}
+
+/// Rasterize a smooth built-in glyph into a `w`×`h` alpha bitmap.
+pub(super) fn rasterize(c: char, w: u32, h: u32) -> Option<IconBitmap> {
+    let (wf, hf) = (w as f32, h as f32);
+    let thin = 1.0f32.max((wf / 8.0).round());
+    let bmp = match c {
+        '\u{256D}'..='\u{2570}' => {
+            rasterize_segments(w, h, &arc_corner(c, wf, hf, thin), thin / 2.0)
+        }
+        '\u{2571}'..='\u{2573}' => rasterize_segments(w, h, &diagonals(c, wf, hf), thin / 2.0),
+        '\u{E0B0}' => rasterize_filled(w, h, |x, y| x <= wf * (1.0 - tip(y, hf))),
+        '\u{E0B2}' => rasterize_filled(w, h, |x, y| wf - x <= wf * (1.0 - tip(y, hf))),
+        '\u{E0B1}' => rasterize_segments(w, h, &chevron(0.0, wf, hf), thin / 2.0),
+        '\u{E0B3}' => rasterize_segments(w, h, &chevron(wf, 0.0, hf), thin / 2.0),
+        '\u{E0B4}' => rasterize_filled(w, h, |x, y| in_half_ellipse(x, y, wf, hf)),
+        '\u{E0B6}' => rasterize_filled(w, h, |x, y| in_half_ellipse(wf - x, y, wf, hf)),
+        _ => return None,
+    };
+    Some(bmp)
+}
+
+/// Center of a box-drawing line along an axis of length `len`, matching
+/// where the rectangle renderer puts it.
+fn line_center(len: f32, thin: f32) -> f32 {
+    (len / 2.0).floor() - (thin / 2.0).floor() + thin / 2.0
+}
+
+/// Segments for a rounded corner: a quarter arc plus straight arms that
+/// run out past the cell edges.
+fn arc_corner(c: char, w: f32, h: f32, thin: f32) -> Vec<(f32, f32, f32, f32)> {
+    // Direction of the horizontal and vertical arms.
+    let (sx, sy) = match c {
+        '\u{256D}' => (1.0, 1.0),
+        '\u{256E}' => (-1.0, 1.0),
+        '\u{256F}' => (-1.0, -1.0),
+        _ => (1.0, -1.0),
+    };
+    let (xc, yc) = (line_center(w, thin), line_center(h, thin));
+    // Leave a short straight run before each edge so the arms enter the
+    // neighbouring cells exactly on their line.
+    let radius = ((w - xc).min(h - yc) - thin).max(thin);
+    let (ox, oy) = (xc + sx * radius, yc + sy * radius);
+    let point = |i: usize| {
+        let a = FRAC_PI_2 * i as f32 / ARC_STEPS as f32;
+        (ox - sx * radius * a.cos(), oy - sy * radius * a.sin())
+    };
+    let mut segments: Vec<_> = (0..ARC_STEPS)
+        .map(|i| {
+            let ((ax, ay), (bx, by)) = (point(i), point(i + 1));
+            (ax, ay, bx, by)
+        })
+        .collect();
+    let far_x = if sx > 0.0 { w + thin } else { -thin };
+    let far_y = if sy > 0.0 { h + thin } else { -thin };
+    segments.push((ox, yc, far_x, yc));
+    segments.push((xc, oy, xc, far_y));
+    segments
+}
+
+/// Corner-to-corner segments for ╱, ╲, and ╳.
+fn diagonals(c: char, w: f32, h: f32) -> Vec<(f32, f32, f32, f32)> {
+    let rising = (0.0, h, w, 0.0);
+    let falling = (0.0, 0.0, w, h);
+    match c {
+        '\u{2571}' => vec![rising],
+        '\u{2572}' => vec![falling],
+        _ => vec![rising, falling],
+    }
+}
+
+/// How far `y` is from the vertical middle, as a fraction of half the height.
+fn tip(y: f32, h: f32) -> f32 {
+    let mid = h / 2.0;
+    (y - mid).abs() / mid
+}
+
+/// The outline of a Powerline arrow from the `base` edge to its `point`.
+fn chevron(base: f32, point: f32, h: f32) -> Vec<(f32, f32, f32, f32)> {
+    vec![(base, 0.0, point, h / 2.0), (point, h / 2.0, base, h)]
+}
+
+/// Whether (`x`, `y`) is inside the half ellipse bulging right from the
+/// left edge.
+fn in_half_ellipse(x: f32, y: f32, w: f32, h: f32) -> bool {
+    let (dx, dy) = (x / w, (y - h / 2.0) / (h / 2.0));
+    dx * dx + dy * dy <= 1.0
+}
@@ -3,6 +3,9 @@
 //! All colors are in linear space unless noted otherwise.
 //! Constants use `rgb_const` for compile-time sRGB-to-linear approximation.
 
+use vte::ansi::Rgb;
+use winit::window::Theme;
+
 use crate::palette::Palette;
 
 // Close button hover (red background, white icon)
@@ -17,6 +20,36 @@ pub(super) const UI_SEPARATOR: [f32; 4] = rgb_const(0x3D, 0x3F, 0x43);
 pub(super) const UI_TEXT: [f32; 4] = rgb_const(0xE8, 0xEB, 0xED);
 pub(super) const UI_TEXT_DIM: [f32; 4] = rgb_const(0xA6, 0xA8, 0xAB);
 
+/// Context menu colors for the OS light or dark preference.
+pub(super) struct MenuColors {
+    pub(super) bg: [f32; 4],
+    pub(super) hover: [f32; 4],
+    pub(super) separator: [f32; 4],
+    pub(super) text: [f32; 4],
+    pub(super) text_dim: [f32; 4],
+}
+
+impl MenuColors {
+    pub(super) fn for_theme(theme: Theme) -> Self {
+        match theme {
+            Theme::Dark => Self {
+                bg: UI_BG,
+                hover: UI_BG_HOVER,
+                separator: UI_SEPARATOR,
+                text: UI_TEXT,
+                text_dim: UI_TEXT_DIM,
+            },
+            Theme::Light => Self {
+                bg: rgb_const(0xF9, 0xF9, 0xF9),
+                hover: rgb_const(0xE5, 0xE5, 0xE5),
+                separator: rgb_const(0xD6, 0xD6, 0xD6),
+                text: rgb_const(0x1A, 0x1A, 0x1A),
+                text_dim: rgb_const(0x6E, 0x6E, 0x6E),
+            },
+        }
+    }
+}
+
 /// Tab bar colors derived dynamically from the palette.
 ///
 /// The bar is dark, inactive tabs are slightly lighter (solid, fully opaque),
@@ -35,10 +68,12 @@ pub(super) struct TabBarColors {
     pub(super) control_fg: [f32; 4],
     pub(super) control_fg_dim: [f32; 4],
     pub(super) control_hover_bg: [f32; 4],
+    /// OS accent color, drawn along the top of the active tab.
+    pub(super) accent: Option<[f32; 4]>,
 }
 
 impl TabBarColors {
-    pub(super) fn from_palette(palette: &Palette) -> Self {
+    pub(super) fn from_palette(palette: &Palette, accent: Option<Rgb>) -> Self {
         let base = vte_rgb_to_rgba(palette.default_bg());
         let fg = vte_rgb_to_rgba(palette.default_fg());
 
@@ -86,6 +121,7 @@ impl TabBarColors {
             control_fg,
             control_fg_dim,
             control_hover_bg,
+            accent: accent.map(vte_rgb_to_rgba),
         }
     }
 }
@@ -116,7 +152,7 @@ pub(crate) fn srgb_to_linear(c: f32) -> f32 {
 }
 
 /// Convert VTE RGB to [f32; 4] RGBA in **linear** space (alpha=1.0).
-pub(super) fn vte_rgb_to_rgba(rgb: vte::ansi::Rgb) -> [f32; 4] {
+pub(super) fn vte_rgb_to_rgba(rgb: Rgb) -> [f32; 4] {
     [
         srgb_to_linear(f32::from(rgb.r) / 255.0),
         srgb_to_linear(f32::from(rgb.g) / 255.0),
@@ -0,0 +1,81 @@
+//! Frame-time instrumentation for the main render path.
+//!
+//! Logs a line for every frame slower than [`SPIKE`] (with whether it
+//! rebuilt instances and how many GPU buffers it allocated), plus a summary
+//! every [`REPORT_INTERVAL`].
+
+use std::time::{Duration, Instant};
+
+use crate::log;
+
+/// CPU frame time above which a frame is logged individually.
+const SPIKE: Duration = Duration::from_millis(16);
+
+/// Period between summary log lines.
+const REPORT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// What a single frame did, for correlating slow frames with their cause.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct FrameSample {
+    /// CPU time spent building, uploading, and encoding the frame.
+    pub(super) elapsed: Duration,
+    /// Whether instance data was rebuilt (vs. the cached frame reused).
+    pub(super) rebuilt: bool,
+    /// GPU buffers created or grown during the frame.
+    pub(super) allocations: u32,
+}
+
+/// Rolling frame-time statistics.
+#[derive(Debug)]
+pub(super) struct FrameStats {
+    window_start: Instant,
+    frames: u32,
+    rebuilds: u32,
+    allocations: u32,
+    total: Duration,
+    worst: Duration,
+}
+
+impl FrameStats {
+    pub(super) fn new() -> Self {
+        Self {
+            window_start: Instant::now(),
+            frames: 0,
+            rebuilds: 0,
+            allocations: 0,
+            total: Duration::ZERO,
+            worst: Duration::ZERO,
+        }
+    }
+
+    /// Record one frame, logging it if it was a spike and emitting the
+    /// periodic summary when due.
+    pub(super) fn record(&mut self, sample: FrameSample) {
+        if sample.elapsed > SPIKE {
+            log(&format!(
+                "frame spike: {:.1}ms (rebuild={}, buffer allocations={})",
+                sample.elapsed.as_secs_f64() * 1000.0,
+                sample.rebuilt,
+                sample.allocations,
+            ));
+        }
+        self.frames += 1;
+        self.rebuilds += u32::from(sample.rebuilt);
+        self.allocations += sample.allocations;
+        self.total += sample.elapsed;
+        self.worst = self.worst.max(sample.elapsed);
+
+        if self.window_start.elapsed() >= REPORT_INTERVAL {
+            log(&format!(
+                "frame stats: {} frames, avg {:.2}ms, worst {:.2}ms, {} rebuilds, \
+                 {} buffer allocations",
+                self.frames,
+                self.total.as_secs_f64() * 1000.0 / f64::from(self.frames),
+                self.worst.as_secs_f64() * 1000.0,
+                self.rebuilds,
+                self.allocations,
+            ));
+            *self = Self::new();
+        }
+    }
+}
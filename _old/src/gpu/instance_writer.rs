@@ -2,39 +2,6 @@
 
 use super::pipeline::INSTANCE_STRIDE;
 
-/// Reuse an existing GPU buffer if it has enough capacity, otherwise create a new one.
-///
-/// When `existing` is `Some` and its size >= the data length, writes data
-/// into the existing buffer via `queue.write_buffer()` (no allocation).
-/// Otherwise creates a fresh buffer.
-pub(super) fn reuse_or_create_buffer(
-    device: &wgpu::Device,
-    queue: &wgpu::Queue,
-    existing: Option<wgpu::Buffer>,
-    data: &[u8],
-    label: &str,
-) -> wgpu::Buffer {
-    let needed = (data.len() as u64).max(INSTANCE_STRIDE);
-    if let Some(buf) = existing {
-        if buf.size() >= needed {
-            if !data.is_empty() {
-                queue.write_buffer(&buf, 0, data);
-            }
-            return buf;
-        }
-    }
-    let buf = device.create_buffer(&wgpu::BufferDescriptor {
-        label: Some(label),
-        size: needed,
-        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-        mapped_at_creation: false,
-    });
-    if !data.is_empty() {
-        queue.write_buffer(&buf, 0, data);
-    }
-    buf
-}
-
 /// Writes cell instance data to a byte buffer without unsafe code.
 pub(super) struct InstanceWriter {
     data: Vec<u8>,
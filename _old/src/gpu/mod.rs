@@ -1,17 +1,33 @@
 //! GPU rendering: wgpu state, atlas, pipelines, and domain-specific renderers.
 
 pub mod atlas;
+mod buffer_pool;
 pub(crate) mod builtin_glyphs;
+mod builtin_smooth;
 mod color_util;
+mod frame_stats;
 mod instance_writer;
 pub mod pipeline;
 mod render_grid;
+mod render_commands;
+mod render_filter;
+mod render_inspector;
 mod render_overlay;
+mod render_scaled_glyph;
+mod render_scrollbar;
 mod render_settings;
+mod render_tab_badges;
 mod render_tab_bar;
+mod render_tab_switcher;
+mod render_text_input;
 pub mod renderer;
 pub mod state;
 
 pub(crate) use color_util::srgb_to_linear;
+pub use render_inspector::InspectorOverlay;
+pub use render_settings::SettingsContent;
 pub use renderer::{FrameParams, GpuRenderer};
 pub use state::GpuState;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,152 @@
+//! Shell integration annotations on prompt rows: a red or green bar in the
+//! left padding gutter beside each finished command's prompt, a tooltip with
+//! its exit code and duration while the mouse is over one, and a
+//! right-aligned "took 3.2s" for slow commands.
+
+use crate::font::FontCollection;
+use crate::grid::Grid;
+use crate::grid::row::{CommandStatus, Row};
+
+use super::color_util::{TabBarColors, lerp_color, vte_rgb_to_rgba};
+use super::instance_writer::InstanceWriter;
+use super::render_grid::grid_origin;
+use super::renderer::{FrameParams, GpuRenderer};
+
+/// Width of an exit status mark, in logical pixels.
+const MARK_WIDTH: f32 = 2.0;
+/// How far a mark's color is blended from the background toward red/green.
+const MARK_STRENGTH: f32 = 0.7;
+/// How far the duration label is blended from the background toward the
+/// foreground, so it reads as an annotation rather than output.
+const DURATION_STRENGTH: f32 = 0.5;
+
+/// How the command whose prompt is on viewport `line` finished, if known.
+pub(super) fn command_status(grid: &Grid, line: usize) -> Option<CommandStatus> {
+    grid.visible_row(line).meta.as_ref()?.command
+}
+
+/// A duration as the tooltip and label show it: `850ms`, `3.2s`, or `4m 5s`.
+pub(super) fn format_duration(ms: u64) -> String {
+    match ms {
+        0..1_000 => format!("{ms}ms"),
+        1_000..60_000 => format!("{:.1}s", ms as f64 / 1000.0),
+        _ => format!("{}m {}s", ms / 60_000, ms / 1000 % 60),
+    }
+}
+
+/// Tooltip text for a finished command, e.g. `exit 1 · 3.2s`.
+pub(super) fn tooltip_text(status: CommandStatus) -> String {
+    let code = status
+        .exit_code
+        .map_or_else(|| "finished".to_owned(), |code| format!("exit {code}"));
+    match status.duration_ms {
+        Some(ms) => format!("{code} · {}", format_duration(ms)),
+        None => code,
+    }
+}
+
+/// Draw a mark in the gutter beside every visible prompt whose command
+/// reported an exit code: green for success, red for failure.
+pub(super) fn push_exit_marks(bg: &mut InstanceWriter, params: &FrameParams<'_>, cell_h: usize) {
+    let (gutter, top) = grid_origin(params);
+    if gutter == 0 {
+        return;
+    }
+    let palette = params.palette;
+    let base = vte_rgb_to_rgba(palette.default_bg());
+    let mark_w = MARK_WIDTH * params.scale;
+    let x = (gutter as f32 - mark_w) / 2.0;
+    for line in 0..params.grid.lines {
+        let Some(code) = command_status(params.grid, line).and_then(|s| s.exit_code) else {
+            continue;
+        };
+        let Some(rgb) = palette.color(if code == 0 { 2 } else { 1 }) else {
+            continue;
+        };
+        let color = lerp_color(base, vte_rgb_to_rgba(rgb), MARK_STRENGTH);
+        let y = (line * cell_h + top) as f32 + params.scroll_offset_px;
+        bg.push_rect(x, y, mark_w, cell_h as f32, color);
+    }
+}
+
+/// Whether `row` is blank in the columns right of `right - width` pixels,
+/// so a label drawn there covers no text.
+fn right_end_is_blank(row: &Row, width: f32, cell_w: usize) -> bool {
+    let cols = (width / cell_w as f32).ceil() as usize + 1;
+    let len = row.len();
+    (len.saturating_sub(cols)..len).all(|col| matches!(row[col].c, ' ' | '\0'))
+}
+
+impl GpuRenderer {
+    /// "took 3.2s" at the right end of each visible prompt row whose
+    /// command ran at least `terminal.command_duration_ms`, unless text
+    /// already fills that end of the row.
+    pub(super) fn build_duration_labels(
+        &mut self,
+        fg: &mut InstanceWriter,
+        params: &FrameParams<'_>,
+        collection: &mut FontCollection,
+        queue: &wgpu::Queue,
+    ) {
+        let Some(min_ms) = params.command_duration_min_ms else {
+            return;
+        };
+        let grid = params.grid;
+        let (cw, ch) = (collection.cell_width, collection.cell_height);
+        if cw == 0 {
+            return;
+        }
+        let (left, top) = grid_origin(params);
+        let right = (left + grid.cols * cw) as f32;
+        let palette = params.palette;
+        let color = lerp_color(
+            vte_rgb_to_rgba(palette.default_bg()),
+            vte_rgb_to_rgba(palette.default_fg()),
+            DURATION_STRENGTH,
+        );
+        for line in 0..grid.lines {
+            let Some(ms) = command_status(grid, line)
+                .and_then(|s| s.duration_ms)
+                .filter(|&ms| ms >= min_ms)
+            else {
+                continue;
+            };
+            let label = format!("took {}", format_duration(ms));
+            let w = collection.text_advance(&label);
+            if !right_end_is_blank(grid.visible_row(line), w, cw) {
+                continue;
+            }
+            let y = (line * ch + top) as f32 + params.scroll_offset_px;
+            self.push_text_instances(fg, &label, right - w, y, color, collection, queue);
+        }
+    }
+
+    /// The exit code and duration of the command whose gutter mark is under
+    /// the mouse, next to the pointer.
+    pub(super) fn build_command_tooltip(
+        &mut self,
+        bg: &mut InstanceWriter,
+        fg: &mut InstanceWriter,
+        params: &FrameParams<'_>,
+        tc: &TabBarColors,
+        glyphs: &mut FontCollection,
+        queue: &wgpu::Queue,
+    ) {
+        let Some((line, (mx, my))) = params.gutter_hover else {
+            return;
+        };
+        let Some(status) = command_status(params.grid, line) else {
+            return;
+        };
+        let label = tooltip_text(status);
+        let sc = params.scale;
+        let pad = 6.0 * sc;
+        let w = glyphs.text_advance(&label) + pad * 2.0;
+        let h = glyphs.cell_height as f32 + pad;
+        // Below and right of the pointer, kept inside the window.
+        let x = (mx + 12.0 * sc).min(params.width as f32 - w);
+        let y = (my + 16.0 * sc).min(params.height as f32 - h);
+        bg.push_all_rounded_rect(x, y, w, h, tc.bar_bg, 4.0 * sc);
+        self.push_text_instances(fg, &label, x + pad, y + pad / 2.0, tc.text_fg, glyphs, queue);
+    }
+}
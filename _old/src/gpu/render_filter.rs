@@ -0,0 +1,46 @@
+//! Search filter mode: each filtered row's line number, at the right end of
+//! the row, so it's clear where in the buffer the row comes from.
+
+use crate::font::FontCollection;
+use crate::grid::StableRowIndex;
+
+use super::color_util::{lerp_color, vte_rgb_to_rgba};
+use super::instance_writer::InstanceWriter;
+use super::render_grid::grid_origin;
+use super::renderer::{FrameParams, GpuRenderer};
+
+/// How far the line numbers are blended from the background toward the
+/// foreground.
+const NUMBER_STRENGTH: f32 = 0.5;
+
+impl GpuRenderer {
+    /// Label each filtered row with its line number: its stable index, 1
+    /// for the first line the tab ever printed.
+    pub(super) fn build_filter_line_numbers(
+        &mut self,
+        bg: &mut InstanceWriter,
+        fg: &mut InstanceWriter,
+        params: &FrameParams<'_>,
+        collection: &mut FontCollection,
+        queue: &wgpu::Queue,
+    ) {
+        let Some(rows) = params.filter_rows else {
+            return;
+        };
+        let grid = params.grid;
+        let (cw, ch) = (collection.cell_width, collection.cell_height);
+        let (left, top) = grid_origin(params);
+        let right = (left + grid.cols * cw) as f32;
+        let palette = params.palette;
+        let back = vte_rgb_to_rgba(palette.default_bg());
+        let color = lerp_color(back, vte_rgb_to_rgba(palette.default_fg()), NUMBER_STRENGTH);
+        for (line, &abs) in rows.iter().enumerate() {
+            let number = StableRowIndex::from_absolute(grid, abs).0 + 1;
+            let label = format!(" {number}");
+            let w = collection.text_advance(&label);
+            let y = (line * ch + top) as f32;
+            bg.push_rect(right - w, y, w, ch as f32, back);
+            self.push_text_instances(fg, &label, right - w, y, color, collection, queue);
+        }
+    }
+}
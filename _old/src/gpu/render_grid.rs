@@ -1,20 +1,24 @@
 //! Grid cell instance building — characters, cursors, underlines, block elements.
 
-use vte::ansi::CursorShape;
+use vte::ansi::{CursorShape, Rgb};
 
+use crate::bidi::BidiLine;
 use crate::cell::CellFlags;
-use crate::font::{FontCollection, prepare_line, shape_prepared_runs};
+use crate::font::{FontCollection, prepare_line, shape_prepared_runs, split_runs_by_direction};
+use crate::grid::row::Row;
 use crate::grid::{GRID_PADDING_LEFT, GRID_PADDING_TOP, StableRowIndex};
-use crate::render::FontStyle;
+use crate::render::{FontStyle, TextBlink};
 use crate::search::MatchType;
 use crate::tab_bar::TAB_BAR_HEIGHT;
 use crate::term_mode::TermMode;
-use super::atlas::size_key;
-use super::builtin_glyphs;
-use super::color_util::vte_rgb_to_rgba;
+use super::{builtin_glyphs, builtin_smooth, render_scaled_glyph};
+use super::color_util::{lerp_color, vte_rgb_to_rgba};
 use super::instance_writer::InstanceWriter;
 use super::renderer::{FrameParams, GpuRenderer};
 
+/// Width of the host rule border, in logical pixels.
+const HOST_BORDER_WIDTH: f32 = 3.0;
+
 impl GpuRenderer {
     #[expect(clippy::too_many_lines, reason = "Flat per-cell rendering loop; further extraction would just scatter sequential logic")]
     pub(super) fn build_grid_instances(
@@ -28,26 +32,40 @@ impl GpuRenderer {
     ) {
         let grid = params.grid;
         let palette = params.palette;
+        let cursor_rgb = params.cursor_color.unwrap_or_else(|| palette.cursor_color());
         let cw = collection.cell_width;
         let ch = collection.cell_height;
         let baseline = collection.baseline;
+        let decoration = collection.decoration;
         let synthetic_bold = collection.needs_synthetic_bold();
         let sc = params.scale;
-        let x_offset = (GRID_PADDING_LEFT as f32 * sc).round() as usize;
-        let y_offset = ((TAB_BAR_HEIGHT + GRID_PADDING_TOP) as f32 * sc).round() as usize;
-        let size_q6 = size_key(collection.size);
+        let (x_offset, y_offset) = grid_origin(params);
 
         let default_bg_u32 = crate::palette::rgb_to_u32(palette.default_bg());
 
         // Ensure all font variants and fallbacks are loaded before creating faces.
         collection.ensure_all_loaded();
         let faces = collection.create_shaping_faces();
+        self.color_memo.clear();
+        self.has_blinking_cells = false;
 
         for line in 0..grid.lines {
-            let row = grid.visible_row(line);
+            let Some((row, stable_row)) = view_row(params, line) else {
+                continue;
+            };
+
+            // Resolve bidi order; rows without RTL text draw in logical order.
+            let bidi = params
+                .mode
+                .contains(TermMode::BIDI)
+                .then(|| BidiLine::resolve(row.as_slice()))
+                .flatten();
 
             // Segment and shape this line using pre-created faces and scratch buffers.
             prepare_line(row.as_slice(), grid.cols, collection, &mut self.runs_scratch);
+            if let Some(bidi) = &bidi {
+                split_runs_by_direction(&mut self.runs_scratch, bidi);
+            }
             shape_prepared_runs(&self.runs_scratch, &faces, collection, &mut self.shaped_scratch);
             let shaped = &self.shaped_scratch;
             self.col_glyph_map.clear();
@@ -56,10 +74,13 @@ impl GpuRenderer {
                 self.col_glyph_map[g.col_start] = Some(i);
             }
 
-            for col in 0..grid.cols {
+            // Iterate visual columns; everything but position uses the
+            // logical column the cell lives at.
+            for vcol in 0..grid.cols {
+                let col = bidi.as_ref().map_or(vcol, |b| b.logical_col(vcol));
                 let cell = &row[col];
-                let x0 = (col * cw + x_offset) as f32;
-                let y0 = (line * ch + y_offset) as f32;
+                let x0 = (vcol * cw + x_offset) as f32;
+                let y0 = (line * ch + y_offset) as f32 + params.scroll_offset_px;
 
                 // Skip wide char spacers
                 if cell.flags.contains(CellFlags::WIDE_CHAR_SPACER) {
@@ -67,38 +88,17 @@ impl GpuRenderer {
                 }
 
                 // Resolve colors
-                let mut fg_rgb = palette.resolve_fg(cell.fg, cell.bg, cell.flags);
-                let mut bg_rgb = palette.resolve_bg(cell.fg, cell.bg, cell.flags);
-
-                // Compute stable row index for search/selection
-                let stable_row = StableRowIndex::from_visible(grid, line);
+                let (mut fg_rgb, mut bg_rgb) = self.color_memo.resolve(palette, cell);
 
                 // Search match highlighting
                 if let Some(search) = params.search {
-                    match search.cell_match_type(stable_row, col) {
-                        MatchType::FocusedMatch => {
-                            bg_rgb = vte::ansi::Rgb {
-                                r: 200,
-                                g: 120,
-                                b: 30,
-                            };
-                            fg_rgb = vte::ansi::Rgb { r: 0, g: 0, b: 0 };
-                        }
-                        MatchType::Match => {
-                            bg_rgb = vte::ansi::Rgb {
-                                r: 80,
-                                g: 80,
-                                b: 20,
-                            };
-                        }
-                        MatchType::None => {}
-                    }
+                    (fg_rgb, bg_rgb) = match_colors(search.cell_match_type(stable_row, col), fg_rgb, bg_rgb);
                 }
 
                 // Selection highlight
                 let is_selected = params
                     .selection
-                    .is_some_and(|sel| sel.contains(stable_row, col));
+                    .is_some_and(|sel| sel.contains_cell(stable_row, col, row.as_slice()));
                 if is_selected {
                     let (sel_fg, sel_bg) = palette.selection_colors(fg_rgb, bg_rgb);
                     fg_rgb = sel_fg;
@@ -106,7 +106,7 @@ impl GpuRenderer {
                 }
 
                 let bg_u32 = crate::palette::rgb_to_u32(bg_rgb);
-                let fg_rgba = vte_rgb_to_rgba(fg_rgb);
+                let mut fg_rgba = vte_rgb_to_rgba(fg_rgb);
                 let bg_rgba = vte_rgb_to_rgba(bg_rgb);
 
                 let cell_w = if cell.flags.contains(CellFlags::WIDE_CHAR) {
@@ -122,11 +122,12 @@ impl GpuRenderer {
 
                 // Cursor
                 let is_cursor = grid.display_offset == 0
+                    && params.filter_rows.is_none()
                     && params.mode.contains(TermMode::SHOW_CURSOR)
                     && line == grid.cursor.row
                     && col == grid.cursor.col;
                 if is_cursor && params.cursor_visible {
-                    let cursor_color = vte_rgb_to_rgba(palette.cursor_color());
+                    let cursor_color = vte_rgb_to_rgba(cursor_rgb);
                     match params.cursor_shape {
                         CursorShape::Beam => {
                             // 2px vertical bar at left edge
@@ -142,13 +143,41 @@ impl GpuRenderer {
                                 cursor_color,
                             );
                         }
-                        _ => {
+                        CursorShape::HollowBlock => {
+                            // 1px outline of the cell
+                            let t = sc.round().max(1.0);
+                            let (w, h) = (cw as f32, ch as f32);
+                            bg.push_rect(x0, y0, w, t, cursor_color);
+                            bg.push_rect(x0, y0 + h - t, w, t, cursor_color);
+                            bg.push_rect(x0, y0, t, h, cursor_color);
+                            bg.push_rect(x0 + w - t, y0, t, h, cursor_color);
+                        }
+                        CursorShape::Hidden => {}
+                        CursorShape::Block => {
                             // Block (default): filled rect
                             bg.push_rect(x0, y0, cw as f32, ch as f32, cursor_color);
                         }
                     }
                 }
 
+                // Concealed text (SGR 8) draws no glyph or decorations unless
+                // the selection reveals it.
+                if cell.flags.contains(CellFlags::HIDDEN) && !is_selected {
+                    continue;
+                }
+
+                // Blinking text in its "off" phase: hide or dim glyph and decorations.
+                if cell.flags.intersects(CellFlags::ANY_BLINK) {
+                    self.has_blinking_cells = true;
+                    if params.blink_phase.is_off(cell.flags) {
+                        match params.text_blink {
+                            TextBlink::Hide => continue,
+                            TextBlink::Dim => fg_rgba = lerp_color(fg_rgba, bg_rgba, 0.5),
+                            TextBlink::Off => {}
+                        }
+                    }
+                }
+
                 // Underline and hyperlink decorations
                 let abs_row = grid.viewport_to_absolute(line);
                 draw_underlines(
@@ -156,10 +185,19 @@ impl GpuRenderer {
                     palette, params, abs_row, col,
                 );
 
-                // Strikethrough
+                // Strikethrough (SGR 9) at the font's strikeout position, overline (SGR 53)
+                let rule = LineDecoration {
+                    x: x0,
+                    y: y0 + decoration.strikeout_y,
+                    w: cell_w,
+                    thickness: decoration.thickness,
+                    color: fg_rgba,
+                };
                 if cell.flags.contains(CellFlags::STRIKEOUT) {
-                    let strike_y = y0 + ch as f32 / 2.0;
-                    bg.push_rect(x0, strike_y, cell_w, 1.0, fg_rgba);
+                    draw_solid_line(bg, rule);
+                }
+                if cell.flags.contains(CellFlags::OVERLINE) {
+                    draw_solid_line(bg, LineDecoration { y: y0, ..rule });
                 }
 
                 // Glyph (skip space/null)
@@ -167,8 +205,18 @@ impl GpuRenderer {
                     continue;
                 }
 
+                // Anti-aliased built-ins (arcs, diagonals, Powerline) come
+                // from the atlas, rasterized at the exact cell size.
+                if builtin_smooth::is_smooth_glyph(cell.c) {
+                    let size = (cell_w, ch as f32);
+                    builtin_smooth::push_smooth_glyph(
+                        &mut self.atlas, fg, cell.c, x0, y0, size, fg_rgba, queue,
+                    );
+                    continue;
+                }
+
                 // Built-in glyph rendering (pixel-perfect, no font glyph).
-                // Covers box drawing, block elements, braille, and Powerline.
+                // Covers box drawing, block elements, and braille.
                 // Temporarily force opacity=1.0 so built-in glyphs match atlas
                 // text brightness. The bg writer premultiplies color by opacity,
                 // but text glyphs on the fg writer are fully opaque.
@@ -186,14 +234,13 @@ impl GpuRenderer {
                     continue;
                 };
                 let glyph = shaped[glyph_idx];
-                let face_idx = glyph.face_idx;
-                let gid = glyph.glyph_id;
-                let entry = self.atlas.get_or_insert_shaped(
-                    gid,
-                    face_idx,
-                    size_q6,
-                    0, // grid collection
-                    || collection.rasterize_glyph_with(face_idx, gid, &mut self.scale_context),
+                let text_scale = cell.text_scale();
+                let entry = render_scaled_glyph::glyph_entry(
+                    &mut self.atlas,
+                    &mut self.scale_context,
+                    collection,
+                    glyph,
+                    text_scale,
                     queue,
                 );
 
@@ -201,11 +248,15 @@ impl GpuRenderer {
                     continue;
                 }
 
-                // Glyph position (shaper offsets applied for combining/ligature positioning)
-                let gx = x0 + entry.metrics.left as f32 + glyph.x_offset;
-                let gy = y0 + baseline as f32
-                    - entry.metrics.top as f32
-                    + glyph.y_offset;
+                // Glyph position (shaper offsets applied for combining/ligature positioning).
+                let text_scale = f32::from(text_scale);
+                let [gx, gy, gw, gh] = render_scaled_glyph::glyph_rect(
+                    entry,
+                    glyph,
+                    (x0, y0),
+                    baseline as f32,
+                    text_scale,
+                );
 
                 // Only invert text color for block cursor (beam/underline don't cover the glyph)
                 let is_block_cursor = is_cursor
@@ -219,7 +270,7 @@ impl GpuRenderer {
 
                 // Effective background behind this glyph (for contrast/correction)
                 let glyph_bg = if is_block_cursor {
-                    vte_rgb_to_rgba(palette.cursor_color())
+                    vte_rgb_to_rgba(cursor_rgb)
                 } else if bg_u32 != default_bg_u32 || is_selected {
                     bg_rgba
                 } else {
@@ -229,8 +280,8 @@ impl GpuRenderer {
                 fg.push_glyph(
                     gx,
                     gy,
-                    entry.metrics.width as f32,
-                    entry.metrics.height as f32,
+                    gw,
+                    gh,
                     entry.uv_pos,
                     entry.uv_size,
                     glyph_fg,
@@ -243,10 +294,10 @@ impl GpuRenderer {
                     let style = FontStyle::from_cell_flags(cell.flags);
                     if style == FontStyle::Bold || style == FontStyle::BoldItalic {
                         fg.push_glyph(
-                            gx + 1.0,
+                            gx + text_scale,
                             gy,
-                            entry.metrics.width as f32,
-                            entry.metrics.height as f32,
+                            gw,
+                            gh,
                             entry.uv_pos,
                             entry.uv_size,
                             glyph_fg,
@@ -257,14 +308,109 @@ impl GpuRenderer {
                 }
             }
         }
+
+        // Block selection edges while Alt-dragging.
+        if let Some((left, right)) = params.block_guides {
+            let color = vte_rgb_to_rgba(palette.cursor_color());
+            let width = sc.round().max(1.0);
+            let height = (grid.lines * ch) as f32;
+            for col in [left, right + 1] {
+                let x = (col.min(grid.cols) * cw + x_offset) as f32;
+                bg.push_rect(x - (width / 2.0).floor(), y_offset as f32, width, height, color);
+            }
+        }
     }
 }
 
-/// Draw a dotted underline: 1px rectangles every other pixel.
-fn draw_dotted_line(bg: &mut InstanceWriter, x: f32, y: f32, w: f32, color: [f32; 4]) {
-    let steps = w as usize;
-    for dx in (0..steps).step_by(2) {
-        bg.push_rect(x + dx as f32, y, 1.0, 1.0, color);
+/// Top-left pixel of the grid: past the tab bar and padding, or the
+/// window's corner in focus mode.
+pub(super) fn grid_origin(params: &FrameParams<'_>) -> (usize, usize) {
+    if params.focus_mode {
+        return (0, 0);
+    }
+    let sc = params.scale;
+    (
+        (GRID_PADDING_LEFT as f32 * sc).round() as usize,
+        ((TAB_BAR_HEIGHT + GRID_PADDING_TOP) as f32 * sc).round() as usize,
+    )
+}
+
+/// Frame the grid area in the active tab's `[[host_rule]]` border color.
+pub(super) fn push_host_border(bg: &mut InstanceWriter, params: &FrameParams<'_>) {
+    let Some(color) = params.host_border else {
+        return;
+    };
+    let color = vte_rgb_to_rgba(color);
+    let (w, h) = (params.width as f32, params.height as f32);
+    let top = if params.focus_mode {
+        0.0
+    } else {
+        TAB_BAR_HEIGHT as f32 * params.scale
+    };
+    let bw = HOST_BORDER_WIDTH * params.scale;
+    bg.push_rect(0.0, top, w, bw, color);
+    bg.push_rect(0.0, h - bw, w, bw, color);
+    bg.push_rect(0.0, top, bw, h - top, color);
+    bg.push_rect(w - bw, top, bw, h - top, color);
+}
+
+/// Geometry and color of one horizontal cell decoration line.
+#[derive(Clone, Copy)]
+struct LineDecoration {
+    x: f32,
+    y: f32,
+    w: f32,
+    /// Line thickness in physical pixels (scaled with DPI, at least 1).
+    thickness: f32,
+    color: [f32; 4],
+}
+
+/// Draw a solid line.
+fn draw_solid_line(bg: &mut InstanceWriter, d: LineDecoration) {
+    bg.push_rect(d.x, d.y, d.w, d.thickness, d.color);
+}
+
+/// Draw a dotted line: square dots separated by gaps of equal size.
+fn draw_dotted_line(bg: &mut InstanceWriter, d: LineDecoration) {
+    let step = (2.0 * d.thickness) as usize;
+    for dx in (0..d.w as usize).step_by(step.max(2)) {
+        let w = d.thickness.min(d.w - dx as f32);
+        bg.push_rect(d.x + dx as f32, d.y, w, d.thickness, d.color);
+    }
+}
+
+/// Draw a dashed line: dashes 3 units long, gaps 2 units, scaled by thickness.
+fn draw_dashed_line(bg: &mut InstanceWriter, d: LineDecoration) {
+    let unit = d.thickness.max(1.0);
+    for dx in (0..d.w as usize).step_by((5.0 * unit) as usize) {
+        let w = (3.0 * unit).min(d.w - dx as f32);
+        bg.push_rect(d.x + dx as f32, d.y, w, d.thickness, d.color);
+    }
+}
+
+/// Draw a curly line: one sine period per cell, amplitude scaled by thickness.
+fn draw_curly_line(bg: &mut InstanceWriter, d: LineDecoration) {
+    let amplitude = 2.0 * d.thickness;
+    for dx in 0..d.w as usize {
+        let phase = (dx as f32 / d.w) * std::f32::consts::TAU;
+        let offset = (phase.sin() * amplitude).round();
+        bg.push_rect(d.x + dx as f32, d.y + offset, 1.0, d.thickness, d.color);
+    }
+}
+
+/// Draw the SGR 4 underline style selected by `flags`.
+fn draw_styled_underline(bg: &mut InstanceWriter, flags: CellFlags, d: LineDecoration) {
+    if flags.contains(CellFlags::UNDERCURL) {
+        draw_curly_line(bg, d);
+    } else if flags.contains(CellFlags::DOUBLE_UNDERLINE) {
+        draw_solid_line(bg, d);
+        draw_solid_line(bg, LineDecoration { y: d.y - 2.0 * d.thickness, ..d });
+    } else if flags.contains(CellFlags::DOTTED_UNDERLINE) {
+        draw_dotted_line(bg, d);
+    } else if flags.contains(CellFlags::DASHED_UNDERLINE) {
+        draw_dashed_line(bg, d);
+    } else {
+        draw_solid_line(bg, d);
     }
 }
 
@@ -284,52 +430,30 @@ fn draw_underlines(
     abs_row: usize,
     col: usize,
 ) {
-    let underline_y = y0 + ch as f32 - 2.0;
-
-    // VTE underline decorations (UNDERLINE, DOUBLE, DOTTED, DASHED, UNDERCURL)
+    let thickness = params.scale.round().max(1.0);
+    let line = LineDecoration {
+        x: x0,
+        y: y0 + ch as f32 - 2.0 * thickness,
+        w: cell_w,
+        thickness,
+        color: fg_rgba,
+    };
+
+    // SGR 4 underline styles, in the SGR 58 underline color when set.
     if cell.flags.intersects(CellFlags::ANY_UNDERLINE) {
-        let ul_color = if let Some(ul) = cell.underline_color() {
+        let color = cell.underline_color().map_or(fg_rgba, |ul| {
             vte_rgb_to_rgba(palette.resolve(ul, CellFlags::empty()))
-        } else {
-            fg_rgba
-        };
-
-        if cell.flags.contains(CellFlags::UNDERCURL) {
-            let steps = cell_w as usize;
-            for dx in 0..steps {
-                let phase = (dx as f32 / cell_w) * std::f32::consts::TAU;
-                let offset = (phase.sin() * 2.0).round();
-                bg.push_rect(x0 + dx as f32, underline_y + offset, 1.0, 1.0, ul_color);
-            }
-        } else if cell.flags.contains(CellFlags::DOUBLE_UNDERLINE) {
-            bg.push_rect(x0, underline_y, cell_w, 1.0, ul_color);
-            bg.push_rect(x0, underline_y - 2.0, cell_w, 1.0, ul_color);
-        } else if cell.flags.contains(CellFlags::DOTTED_UNDERLINE) {
-            draw_dotted_line(bg, x0, underline_y, cell_w, ul_color);
-        } else if cell.flags.contains(CellFlags::DASHED_UNDERLINE) {
-            let steps = cell_w as usize;
-            for dx in 0..steps {
-                if dx % 5 < 3 {
-                    bg.push_rect(x0 + dx as f32, underline_y, 1.0, 1.0, ul_color);
-                }
-            }
-        } else if cell.flags.contains(CellFlags::UNDERLINE) {
-            bg.push_rect(x0, underline_y, cell_w, 1.0, ul_color);
-        } else {
-            // No matching underline flag (shouldn't happen with ANY_UNDERLINE guard).
-        }
+        });
+        draw_styled_underline(bg, cell.flags, LineDecoration { color, ..line });
         return; // VTE underline takes precedence — skip hyperlink/URL underlines.
     }
 
     // OSC 8 hyperlink underline
-    if cell.hyperlink().is_some() {
-        let is_hovered = params.hover_hyperlink.is_some_and(|hover_uri| {
-            cell.hyperlink().is_some_and(|h| h.uri == hover_uri)
-        });
-        if is_hovered {
-            bg.push_rect(x0, underline_y, cell_w, 1.0, fg_rgba);
+    if let Some(link) = cell.hyperlink() {
+        if params.hover_hyperlink == Some(link.id) {
+            draw_solid_line(bg, line);
         } else {
-            draw_dotted_line(bg, x0, underline_y, cell_w, fg_rgba);
+            draw_dotted_line(bg, line);
         }
         return; // Explicit hyperlink takes precedence over implicit URL.
     }
@@ -340,8 +464,32 @@ fn draw_underlines(
             .iter()
             .any(|&(r, sc, ec)| abs_row == r && col >= sc && col <= ec);
         if in_url {
-            bg.push_rect(x0, underline_y, cell_w, 1.0, fg_rgba);
+            draw_solid_line(bg, line);
         }
     }
 }
 
+/// The row drawn on viewport `line` and its stable index: the grid's
+/// visible row, or in filter mode the `line`th filtered row.
+fn view_row<'a>(params: &FrameParams<'a>, line: usize) -> Option<(&'a Row, StableRowIndex)> {
+    let grid = params.grid;
+    match params.filter_rows {
+        Some(rows) => {
+            let abs = *rows.get(line)?;
+            Some((grid.absolute_row(abs)?, StableRowIndex::from_absolute(grid, abs)))
+        }
+        None => Some((grid.visible_row(line), StableRowIndex::from_visible(grid, line))),
+    }
+}
+
+/// Colors of a cell highlighted as `kind` of search match.
+fn match_colors(kind: MatchType, fg: Rgb, bg: Rgb) -> (Rgb, Rgb) {
+    const BLACK: Rgb = Rgb { r: 0, g: 0, b: 0 };
+    match kind {
+        MatchType::FocusedMatch => (BLACK, Rgb { r: 200, g: 120, b: 30 }),
+        MatchType::FocusedCapture => (BLACK, Rgb { r: 240, g: 170, b: 60 }),
+        MatchType::Match => (fg, Rgb { r: 80, g: 80, b: 20 }),
+        MatchType::Capture => (fg, Rgb { r: 120, g: 120, b: 40 }),
+        MatchType::None => (fg, bg),
+    }
+}
@@ -0,0 +1,160 @@
+//! Escape sequence inspector panel, drawn over the bottom of the grid.
+//!
+//! A header row holds the filter field and a count of shown and recorded
+//! sequences, followed by a summary of ignored sequences once there are
+//! any; below them the newest matching records fill the panel, oldest at
+//! the top. Sequences the terminal ignores are drawn in the palette's
+//! red so they stand out.
+
+use crate::font::FontCollection;
+use crate::tab::{SeqRecord, SequenceLog, UnknownSequences};
+use crate::ui::text_input::TextInput;
+
+use super::color_util::{TabBarColors, lighten, vte_rgb_to_rgba};
+use super::instance_writer::InstanceWriter;
+use super::render_text_input::TextInputColors;
+use super::renderer::{FrameParams, GpuRenderer};
+
+/// Fraction of the window height the panel covers.
+const PANEL_FRACTION: f32 = 0.4;
+
+/// Palette index of the color for ignored sequences.
+const IGNORED_COLOR_INDEX: usize = 1;
+
+/// The active tab's inspector, as the renderer sees it.
+pub struct InspectorOverlay<'a> {
+    pub log: &'a SequenceLog,
+    /// Ignored sequence counts, listed under the header.
+    pub unknown: &'a UnknownSequences,
+    pub filter: &'a TextInput,
+    /// The filter field has keyboard focus.
+    pub focused: bool,
+}
+
+impl GpuRenderer {
+    pub(super) fn build_inspector_overlay(
+        &mut self,
+        bg: &mut InstanceWriter,
+        fg: &mut InstanceWriter,
+        params: &FrameParams<'_>,
+        tc: &TabBarColors,
+        glyphs: &mut FontCollection,
+        queue: &wgpu::Queue,
+    ) {
+        let Some(inspector) = &params.inspector else {
+            return;
+        };
+        let sc = params.scale;
+        let (w, h) = (params.width as f32, params.height as f32);
+        let line_h = glyphs.cell_height as f32;
+        let pad = 8.0 * sc;
+        let panel_h = (h * PANEL_FRACTION).max(line_h * 3.0 + pad * 2.0);
+        let panel_y = h - panel_h;
+
+        bg.push_rect(0.0, panel_y, w, panel_h, tc.bar_bg);
+        bg.push_rect(0.0, panel_y, w, 1.0 * sc, lighten(tc.bar_bg, 0.25));
+
+        // Newest matching records that fit below the header and summary.
+        let summary_rows = 1 + usize::from(!inspector.unknown.is_empty());
+        let rows = (((panel_h - pad * 2.0) / line_h) as usize).saturating_sub(summary_rows);
+        let filter = inspector.filter.text();
+        let mut shown: Vec<_> = inspector.log.filtered(filter).rev().take(rows).collect();
+        shown.reverse();
+
+        let mut y = panel_y + pad;
+        let count = format!("{} shown, {} recorded", shown.len(), inspector.log.total());
+        self.push_inspector_header(bg, fg, inspector, &count, y, params, tc, glyphs, queue);
+        y += line_h;
+
+        let ignored = params
+            .palette
+            .color(IGNORED_COLOR_INDEX)
+            .map_or(tc.text_fg, vte_rgb_to_rgba);
+        if !inspector.unknown.is_empty() {
+            let summary = format!("Ignored: {}", inspector.unknown);
+            let summary = glyphs.truncate_to_pixel_width(&summary, w - pad * 2.0);
+            self.push_text_instances(fg, &summary, pad, y, ignored, glyphs, queue);
+            y += line_h;
+        }
+        self.push_inspector_records(fg, &shown, pad, y, ignored, tc, glyphs, queue);
+    }
+
+    /// Draw the filter field at the left of the header row at `y` and
+    /// `count` at its right.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "Renderer function requires full context for drawing"
+    )]
+    fn push_inspector_header(
+        &mut self,
+        bg: &mut InstanceWriter,
+        fg: &mut InstanceWriter,
+        inspector: &InspectorOverlay<'_>,
+        count: &str,
+        y: f32,
+        params: &FrameParams<'_>,
+        tc: &TabBarColors,
+        glyphs: &mut FontCollection,
+        queue: &wgpu::Queue,
+    ) {
+        let sc = params.scale;
+        let pad = 8.0 * sc;
+        let label = "Filter: ";
+        self.push_text_instances(fg, label, pad, y, tc.inactive_text, glyphs, queue);
+        let colors = TextInputColors {
+            text: tc.text_fg,
+            selection: lighten(tc.bar_bg, 0.35),
+        };
+        let filter_x = pad + glyphs.text_advance(label);
+        let cursor_x = self.push_text_input(
+            bg,
+            fg,
+            inspector.filter,
+            filter_x,
+            y,
+            colors,
+            glyphs,
+            queue,
+            sc,
+        );
+        if inspector.focused {
+            let line_h = glyphs.cell_height as f32;
+            bg.push_rect(cursor_x, y, 2.0 * sc, line_h, tc.text_fg);
+        }
+        let count_x = params.width as f32 - glyphs.text_advance(count) - pad;
+        self.push_text_instances(fg, count, count_x, y, tc.inactive_text, glyphs, queue);
+    }
+
+    /// Draw `records` one per line from (`x`, `y`): names in one column and
+    /// sequence text in the next, ignored sequences in `ignored`.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "Renderer function requires full context for drawing"
+    )]
+    fn push_inspector_records(
+        &mut self,
+        fg: &mut InstanceWriter,
+        records: &[&SeqRecord],
+        x: f32,
+        mut y: f32,
+        ignored: [f32; 4],
+        tc: &TabBarColors,
+        glyphs: &mut FontCollection,
+        queue: &wgpu::Queue,
+    ) {
+        let name_w = records
+            .iter()
+            .map(|r| glyphs.text_advance(r.name.unwrap_or("ignored")))
+            .fold(0.0, f32::max);
+        let text_x = x + name_w + glyphs.text_advance("  ");
+        for record in records {
+            let (name, color) = match record.name {
+                Some(name) => (name, tc.text_fg),
+                None => ("ignored", ignored),
+            };
+            self.push_text_instances(fg, name, x, y, tc.inactive_text, glyphs, queue);
+            self.push_text_instances(fg, &record.text, text_x, y, color, glyphs, queue);
+            y += glyphs.cell_height as f32;
+        }
+    }
+}
@@ -1,18 +1,22 @@
-//! Overlay instance building — dragged tab, context menu, and search bar.
+//! Overlay instance building — dragged tab, context menu, search bar, IME
+//! composition, and the pending keybinding chord.
 //!
 //! Overlays are rendered in a separate pass after the main bg+fg, so they
 //! appear on top of all grid and tab bar content.
 
 use crate::context_menu::MenuEntry;
 use crate::font::FontCollection;
+use crate::search::SearchState;
 use crate::tab_bar::{
     DROPDOWN_BUTTON_WIDTH, NEW_TAB_BUTTON_WIDTH, TAB_BAR_HEIGHT, TAB_LEFT_MARGIN, TAB_TOP_MARGIN,
-    TabBarLayout,
 };
 use super::color_util::{
-    lighten, TabBarColors, UI_BG, UI_BG_HOVER, UI_SEPARATOR, UI_TEXT, UI_TEXT_DIM,
+    lighten, vte_rgb_to_rgba, MenuColors, TabBarColors,
 };
 use super::instance_writer::InstanceWriter;
+use super::render_grid::grid_origin;
+use super::render_tab_bar::frame_layout;
+use super::render_text_input::TextInputColors;
 use super::renderer::{FrameParams, GpuRenderer};
 
 impl GpuRenderer {
@@ -35,13 +39,8 @@ impl GpuRenderer {
         };
 
         let s = params.scale;
-        let layout = TabBarLayout::compute(
-            params.tab_info.len(),
-            params.width as usize,
-            s as f64,
-            params.tab_width_lock,
-        );
-        let tab_w = layout.tab_width;
+        let layout = frame_layout(params);
+        let tab_w = layout.width_of(drag_idx);
         let tab_wf = tab_w as f32;
         let cell_h = glyphs.cell_height;
         let top = TAB_TOP_MARGIN as f32 * s;
@@ -60,8 +59,7 @@ impl GpuRenderer {
         // + and dropdown buttons — rendered in overlay during drag so they
         // move with the dragged tab via the fast path.
         let left_margin = (TAB_LEFT_MARGIN as f32 * s) as usize;
-        let tab_count = params.tab_info.len();
-        let default_plus_x = (left_margin + tab_count * tab_w) as f32;
+        let default_plus_x = (left_margin + layout.tabs_width()) as f32;
         let plus_x = default_plus_x.max(drag_x + tab_wf);
 
         let new_tab_w = NEW_TAB_BUTTON_WIDTH as f32 * s;
@@ -102,6 +100,39 @@ impl GpuRenderer {
         );
     }
 
+    /// The IME composition over the cells at the terminal cursor: default
+    /// colors, underlined, with a caret where the IME puts its cursor.
+    pub(super) fn build_preedit_overlay(
+        &mut self,
+        bg: &mut InstanceWriter,
+        fg: &mut InstanceWriter,
+        params: &FrameParams<'_>,
+        glyphs: &mut FontCollection,
+        queue: &wgpu::Queue,
+    ) {
+        let Some(preedit) = params.preedit else {
+            return;
+        };
+        let grid = params.grid;
+        if grid.display_offset != 0 || params.filter_rows.is_some() {
+            return;
+        }
+        let (x_offset, y_offset) = grid_origin(params);
+        let x = (x_offset + grid.cursor.col * glyphs.cell_width) as f32;
+        let y = (y_offset + grid.cursor.row * glyphs.cell_height) as f32 + params.scroll_offset_px;
+        let cell_h = glyphs.cell_height as f32;
+        let width = glyphs.text_advance(&preedit.text);
+        let text = vte_rgb_to_rgba(params.palette.default_fg());
+        let line = params.scale.round().max(1.0);
+
+        bg.push_rect(x, y, width, cell_h, vte_rgb_to_rgba(params.palette.default_bg()));
+        bg.push_rect(x, y + cell_h - line, width, line, text);
+        if let Some(before) = preedit.cursor.and_then(|i| preedit.text.get(..i)) {
+            bg.push_rect(x + glyphs.text_advance(before), y, line, cell_h, text);
+        }
+        self.push_text_instances(fg, &preedit.text, x, y, text, glyphs, queue);
+    }
+
     pub(super) fn build_context_menu_overlay(
         &mut self,
         bg: &mut InstanceWriter,
@@ -120,11 +151,12 @@ impl GpuRenderer {
         let mh = menu.height;
         let cell_h = glyphs.cell_height as f32;
 
-        let menu_bg = UI_BG;
-        let hover_bg = UI_BG_HOVER;
-        let separator_color = UI_SEPARATOR;
-        let text_color = UI_TEXT;
-        let text_dim = UI_TEXT_DIM;
+        let colors = MenuColors::for_theme(params.chrome.theme);
+        let menu_bg = colors.bg;
+        let hover_bg = colors.hover;
+        let separator_color = colors.separator;
+        let text_color = colors.text;
+        let text_dim = colors.text_dim;
 
         // 1. Shadow (offset down-right, all corners rounded)
         let shadow_offset = 2.0 * menu.scale;
@@ -193,6 +225,11 @@ impl GpuRenderer {
                     let color = if *checked { text_color } else { text_dim };
                     self.push_text_instances(fg, label, label_x, ty, color, glyphs, queue);
                 }
+                MenuEntry::Label { label } => {
+                    let tx = mx + menu.item_padding_x();
+                    let ty = y + (item_h - cell_h) / 2.0;
+                    self.push_text_instances(fg, label, tx, ty, text_dim, glyphs, queue);
+                }
                 MenuEntry::Separator => {
                     let sep_y = y + (item_h - menu.separator_thickness()) / 2.0;
                     let sep_mx = menu.separator_margin_x();
@@ -253,48 +290,102 @@ impl GpuRenderer {
             queue,
         );
 
-        // Query text
+        // Query text with its selection, IME preedit, and cursor
         let query_x = prefix_x + glyphs.text_advance(prefix);
-        if !search.query.is_empty() {
-            self.push_text_instances(
-                fg,
-                &search.query,
-                query_x,
-                text_y,
-                tc.text_fg,
-                glyphs,
-                queue,
-            );
-        }
-
-        // Cursor (blinking rect after query text)
-        let cursor_x = query_x + glyphs.text_advance(&search.query);
+        let colors = TextInputColors {
+            text: tc.text_fg,
+            selection: lighten(tc.bar_bg, 0.35),
+        };
+        let cursor_x = self.push_text_input(
+            bg, fg, &search.input, query_x, text_y, colors, glyphs, queue, sc,
+        );
         bg.push_rect(cursor_x, text_y, 2.0 * sc, cell_h as f32, tc.text_fg);
 
-        // Match count on the right — avoid heap allocation by using a stack buffer.
-        if search.matches.is_empty() {
-            if !search.query.is_empty() {
-                let text = "No matches";
-                let count_w = glyphs.text_advance(text);
-                let count_x = w - count_w - 12.0 * sc;
-                self.push_text_instances(
-                    fg, text, count_x, text_y, tc.inactive_text, glyphs, queue,
-                );
-            }
-        } else {
-            let mut buf = [0u8; 32];
-            let len = {
-                use std::io::Write;
-                let mut cursor = &mut buf[..];
-                let _ = write!(cursor, "{} of {}", search.focused + 1, search.matches.len());
-                32 - cursor.len()
+        self.push_search_status(bg, fg, search, text_y, params, tc, glyphs, queue);
+    }
+
+    /// Draw the match count at the right of the search bar, and the option
+    /// toggles to its left. While a background search runs, the count is a
+    /// spinner and the matches so far.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "Renderer function requires full context for drawing"
+    )]
+    fn push_search_status(
+        &mut self,
+        bg: &mut InstanceWriter,
+        fg: &mut InstanceWriter,
+        search: &SearchState,
+        text_y: f32,
+        params: &FrameParams<'_>,
+        tc: &TabBarColors,
+        glyphs: &mut FontCollection,
+        queue: &wgpu::Queue,
+    ) {
+        let sc = params.scale;
+        // Written to a stack buffer to avoid a heap allocation.
+        let mut buf = [0u8; 48];
+        let len = {
+            use std::io::Write;
+            let mut cursor = &mut buf[..];
+            let count = search.matches.len();
+            let _ = match search.spinner() {
+                Some(frame) => write!(cursor, "{frame} {count} found"),
+                None if count == 0 && search.input.is_empty() => Ok(()),
+                None if count == 0 => write!(cursor, "No matches"),
+                None if search.wrapped => write!(cursor, "{}/{count} (wrapped)", search.focused + 1),
+                None => write!(cursor, "{}/{count}", search.focused + 1),
             };
-            let text = std::str::from_utf8(&buf[..len]).unwrap_or("");
-            let count_w = glyphs.text_advance(text);
-            let count_x = w - count_w - 12.0 * sc;
-            self.push_text_instances(
-                fg, text, count_x, text_y, tc.inactive_text, glyphs, queue,
-            );
+            48 - cursor.len()
+        };
+        let text = std::str::from_utf8(&buf[..len]).unwrap_or("");
+        let mut x = params.width as f32 - glyphs.text_advance(text) - 12.0 * sc;
+        self.push_text_instances(fg, text, x, text_y, tc.inactive_text, glyphs, queue);
+
+        let options = search.options;
+        let toggles = [
+            ("&", search.filter),
+            (".*", options.use_regex),
+            ("W", options.whole_word),
+            ("Aa", options.case_sensitive),
+        ];
+        let pad = 4.0 * sc;
+        x -= 8.0 * sc;
+        for (label, on) in toggles {
+            let label_w = glyphs.text_advance(label);
+            x -= label_w + pad * 2.0;
+            if on {
+                let h = glyphs.cell_height as f32;
+                bg.push_rect(x, text_y, label_w + pad * 2.0, h, lighten(tc.bar_bg, 0.35));
+            }
+            let color = if on { tc.text_fg } else { tc.inactive_text };
+            self.push_text_instances(fg, label, x + pad, text_y, color, glyphs, queue);
+            x -= pad;
         }
     }
+
+    /// The keys of a pending keybinding chord, in a badge at the top right
+    /// of the grid.
+    pub(super) fn build_chord_overlay(
+        &mut self,
+        bg: &mut InstanceWriter,
+        fg: &mut InstanceWriter,
+        params: &FrameParams<'_>,
+        tc: &TabBarColors,
+        glyphs: &mut FontCollection,
+        queue: &wgpu::Queue,
+    ) {
+        let Some(label) = params.pending_chord else {
+            return;
+        };
+        let sc = params.scale;
+        let pad = 8.0 * sc;
+        let (_, top) = grid_origin(params);
+        let badge_w = glyphs.text_advance(label) + pad * 2.0;
+        let badge_h = glyphs.cell_height as f32 + pad;
+        let x = params.width as f32 - badge_w - pad;
+        let y = top as f32 + pad;
+        bg.push_all_rounded_rect(x, y, badge_w, badge_h, tc.bar_bg, 6.0 * sc);
+        self.push_text_instances(fg, label, x + pad, y + pad / 2.0, tc.text_fg, glyphs, queue);
+    }
 }
@@ -0,0 +1,57 @@
+//! Shaped glyphs for grid cells, including OSC 66 scaled text.
+//!
+//! Scaled text is rasterized at its block's size rather than stretched, so
+//! a glyph 3 cells tall stays as sharp as ordinary text. Its baseline and
+//! shaper offsets grow with the block; the bitmap's own bearings are
+//! already at the larger size.
+
+use swash::scale::ScaleContext;
+
+use crate::font::{FontCollection, ShapedGlyph};
+
+use super::atlas::{AtlasEntry, GlyphAtlas, size_key};
+
+/// The atlas entry for `glyph` at `text_scale` times the font size (1 for
+/// ordinary text), rasterizing it on first use.
+pub(super) fn glyph_entry<'a>(
+    atlas: &'a mut GlyphAtlas,
+    scale_context: &mut ScaleContext,
+    collection: &FontCollection,
+    glyph: ShapedGlyph,
+    text_scale: u8,
+    queue: &wgpu::Queue,
+) -> &'a AtlasEntry {
+    atlas.get_or_insert_shaped(
+        glyph.glyph_id,
+        glyph.face_idx,
+        size_key(collection.size * f32::from(text_scale)),
+        0, // grid collection
+        || {
+            collection.rasterize_scaled_glyph_with(
+                glyph.face_idx,
+                glyph.glyph_id,
+                text_scale,
+                scale_context,
+            )
+        },
+        queue,
+    )
+}
+
+/// The quad `[x, y, w, h]` for `entry` in the cell whose top left is
+/// `origin`, with `baseline` measured at the unscaled font size.
+pub(super) fn glyph_rect(
+    entry: &AtlasEntry,
+    glyph: ShapedGlyph,
+    origin: (f32, f32),
+    baseline: f32,
+    text_scale: f32,
+) -> [f32; 4] {
+    let (x0, y0) = origin;
+    [
+        x0 + entry.metrics.left as f32 + glyph.x_offset * text_scale,
+        y0 + (baseline + glyph.y_offset) * text_scale - entry.metrics.top as f32,
+        entry.metrics.width as f32,
+        entry.metrics.height as f32,
+    ]
+}
@@ -0,0 +1,61 @@
+//! Overlay scrollbar at the right edge: thumb, prompt markers (OSC 133;A),
+//! and search match markers.
+
+use crate::scrollbar::ScrollbarLayout;
+
+use super::color_util::{lerp_color, vte_rgb_to_rgba};
+use super::instance_writer::InstanceWriter;
+use super::render_grid::grid_origin;
+use super::renderer::FrameParams;
+
+/// Thumb color, blended this far from the background toward the foreground.
+const THUMB_STRENGTH: f32 = 0.3;
+/// Prompt marker color, blended this far toward the foreground.
+const PROMPT_STRENGTH: f32 = 0.7;
+/// Search match marker color (the search highlight's orange).
+const MATCH_COLOR: [f32; 4] = [200.0 / 255.0, 120.0 / 255.0, 30.0 / 255.0, 1.0];
+/// Marker height, in logical pixels.
+const MARKER_HEIGHT: f32 = 2.0;
+
+/// Draw the thumb, then a marker for every prompt and search match.
+pub(super) fn push_scrollbar(bg: &mut InstanceWriter, params: &FrameParams<'_>, cell_h: usize) {
+    let grid = params.grid;
+    let (_, top) = grid_origin(params);
+    let track = (top as f32, (top + grid.lines * cell_h) as f32);
+    let Some(bar) = ScrollbarLayout::compute(grid, params.width as f32, track, params.scale) else {
+        return;
+    };
+    let palette = params.palette;
+    let base = vte_rgb_to_rgba(palette.default_bg());
+    let fg = vte_rgb_to_rgba(palette.default_fg());
+    let thumb = lerp_color(base, fg, THUMB_STRENGTH);
+    let radius = bar.width / 2.0;
+    bg.push_all_rounded_rect(bar.x, bar.thumb_top, bar.width, bar.thumb_height, thumb, radius);
+
+    let marker_h = MARKER_HEIGHT * params.scale;
+    let prompts = grid.prompt_rows();
+    let prompt_color = lerp_color(base, fg, PROMPT_STRENGTH);
+    push_markers(bg, &bar, prompts, (marker_h, prompt_color));
+    if let Some(search) = params.search {
+        let matches = search.matches.iter().filter_map(|m| m.start_row.to_absolute(grid));
+        push_markers(bg, &bar, matches, (marker_h, MATCH_COLOR));
+    }
+}
+
+/// One marker per row in `rows` (ascending); rows that land on the same
+/// pixel share a marker.
+fn push_markers(
+    bg: &mut InstanceWriter,
+    bar: &ScrollbarLayout,
+    rows: impl Iterator<Item = usize>,
+    (height, color): (f32, [f32; 4]),
+) {
+    let mut last_y = f32::NEG_INFINITY;
+    for row in rows {
+        let y = bar.row_y(row).round();
+        if y > last_y {
+            bg.push_rect(bar.x, y - height / 2.0, bar.width, height, color);
+            last_y = y;
+        }
+    }
+}
@@ -1,14 +1,49 @@
-//! Settings window rendering — theme picker with color swatches.
+//! Settings window rendering — filter box, theme picker with color
+//! swatches, and the color contrast audit.
 
 use crate::font::FontCollection;
-use crate::palette::{Palette, BUILTIN_SCHEMES};
+use crate::palette::contrast::{self, Readability, WCAG_AA};
+use crate::palette::{ColorScheme, Palette};
+use crate::ui::settings_layout::{
+    CONTRAST_ROW_HEIGHT, CONTRAST_TOGGLE_LABEL, FILTER_HEIGHT, PAGE_TAB_WIDTH, PAGES,
+    SettingsLayout, SettingsPage, THEME_ROW_HEIGHT, TITLE_HEIGHT,
+};
+use crate::ui::text_input::TextInput;
 use super::color_util::{
-    darken, lerp_color, lighten, ortho_projection, srgb_to_linear, vte_rgb_to_rgba,
+    darken, lerp_color, lighten, ortho_projection, rgb_const, srgb_to_linear, vte_rgb_to_rgba,
 };
 use super::instance_writer::InstanceWriter;
+use super::render_text_input::TextInputColors;
 use super::renderer::GpuRenderer;
 use super::state::GpuState;
 
+/// Label color for colors that only pass for large text.
+const WARN_FG: [f32; 4] = rgb_const(0xf9, 0xe2, 0xaf);
+
+/// Label color for unreadable colors.
+const BAD_FG: [f32; 4] = rgb_const(0xf3, 0x8b, 0xa8);
+
+/// What the settings window shows.
+#[derive(Debug, Clone, Copy)]
+pub struct SettingsContent<'a> {
+    /// The visible page and the options that match the filter.
+    pub layout: &'a SettingsLayout,
+    /// The filter box.
+    pub filter: &'a TextInput,
+    /// Name of the scheme in use.
+    pub active_scheme: &'a str,
+    /// `colors.minimum_contrast` after clamping.
+    pub minimum_contrast: f32,
+}
+
+/// Chrome colors shared by every page.
+struct PageColors {
+    win_bg: [f32; 4],
+    text: [f32; 4],
+    hover: [f32; 4],
+    border: [f32; 4],
+}
+
 impl GpuRenderer {
     /// Render the settings window frame.
     #[expect(
@@ -22,7 +57,7 @@ impl GpuRenderer {
         config: &wgpu::SurfaceConfiguration,
         width: u32,
         height: u32,
-        active_scheme: &str,
+        content: &SettingsContent<'_>,
         palette: Option<&Palette>,
         glyphs: &mut FontCollection, // UI font
     ) {
@@ -37,38 +72,42 @@ impl GpuRenderer {
         let mut fg = InstanceWriter::new();
 
         // Derive colors from palette or use defaults
-        let (win_bg, title_fg, row_fg, row_hover, border_c) = if let Some(pal) = palette {
+        let colors = if let Some(pal) = palette {
             let base = vte_rgb_to_rgba(pal.default_bg());
-            let text = vte_rgb_to_rgba(pal.default_fg());
             let bg_dark = darken(base, 0.20);
-            let hover = lighten(bg_dark, 0.15);
-            let brd = lighten(bg_dark, 0.25);
-            (bg_dark, text, text, hover, brd)
+            PageColors {
+                win_bg: bg_dark,
+                text: vte_rgb_to_rgba(pal.default_fg()),
+                hover: lighten(bg_dark, 0.15),
+                border: lighten(bg_dark, 0.25),
+            }
         } else {
             let s = srgb_to_linear;
-            let bg_dark = [s(0.08), s(0.08), s(0.12), 1.0];
-            let text = [s(0.8), s(0.84), s(0.96), 1.0];
-            let hover = [s(0.18), s(0.18), s(0.25), 1.0];
-            let brd = [s(0.3), s(0.3), s(0.4), 1.0];
-            (bg_dark, text, text, hover, brd)
+            PageColors {
+                win_bg: [s(0.08), s(0.08), s(0.12), 1.0],
+                text: [s(0.8), s(0.84), s(0.96), 1.0],
+                hover: [s(0.18), s(0.18), s(0.25), 1.0],
+                border: [s(0.3), s(0.3), s(0.4), 1.0],
+            }
         };
 
         // Full background
-        bg.push_rect(0.0, 0.0, w, h, win_bg);
+        bg.push_rect(0.0, 0.0, w, h, colors.win_bg);
 
         // 1px border
-        bg.push_rect(0.0, 0.0, w, 1.0, border_c);
-        bg.push_rect(0.0, h - 1.0, w, 1.0, border_c);
-        bg.push_rect(0.0, 0.0, 1.0, h, border_c);
-        bg.push_rect(w - 1.0, 0.0, 1.0, h, border_c);
+        bg.push_rect(0.0, 0.0, w, 1.0, colors.border);
+        bg.push_rect(0.0, h - 1.0, w, 1.0, colors.border);
+        bg.push_rect(0.0, 0.0, 1.0, h, colors.border);
+        bg.push_rect(w - 1.0, 0.0, 1.0, h, colors.border);
 
-        let cell_h = glyphs.cell_height;
-
-        // Title "Theme"
-        let title_y = (50.0 - cell_h as f32) / 2.0;
-        self.push_text_instances(
-            &mut fg, "Theme", 16.0, title_y, title_fg, glyphs, &gpu.queue,
-        );
+        // Page tabs in the title bar
+        let title_y = (TITLE_HEIGHT - glyphs.cell_height as f32) / 2.0;
+        let dim_fg = lerp_color(colors.win_bg, colors.text, 0.6);
+        for (i, (page, label)) in PAGES.iter().enumerate() {
+            let color = if *page == content.layout.page { colors.text } else { dim_fg };
+            let x = 16.0 + i as f32 * PAGE_TAB_WIDTH;
+            self.push_text_instances(&mut fg, label, x, title_y, color, glyphs, &gpu.queue);
+        }
 
         // Close button — vector icon
         let close_cx = w - 30.0 + 15.0;
@@ -80,20 +119,94 @@ impl GpuRenderer {
             close_cy,
             10.0,
             1.0,
-            row_fg,
+            colors.text,
             &gpu.queue,
         );
 
-        // Scheme rows
-        let title_h: f32 = 50.0;
-        let row_h: f32 = 40.0;
+        self.push_filter_box(&mut bg, &mut fg, w, content.filter, &colors, glyphs, gpu);
+
+        let layout = content.layout;
+        match (layout.page, palette) {
+            (SettingsPage::Theme, _) => {
+                let schemes = &layout.schemes;
+                let active = content.active_scheme;
+                self.push_theme_page(&mut bg, &mut fg, w, schemes, active, &colors, glyphs, gpu);
+            }
+            (SettingsPage::Contrast, Some(pal)) => {
+                let min = content.minimum_contrast;
+                self.push_contrast_page(
+                    &mut bg, &mut fg, w, layout, pal, min, &colors, glyphs, gpu,
+                );
+            }
+            (SettingsPage::Contrast, None) => {}
+        }
+
+        // Submit render
+        self.submit_simple_frame(gpu, surface, config, &bg, &fg, colors.win_bg);
+    }
+
+    /// The filter box below the title bar, with a placeholder while empty.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "Renderer function requires full context for drawing"
+    )]
+    fn push_filter_box(
+        &mut self,
+        bg: &mut InstanceWriter,
+        fg: &mut InstanceWriter,
+        w: f32,
+        filter: &TextInput,
+        colors: &PageColors,
+        glyphs: &mut FontCollection,
+        gpu: &GpuState,
+    ) {
+        let cell_h = glyphs.cell_height as f32;
+        let (x0, y0, box_h) = (8.0, TITLE_HEIGHT + 4.0, FILTER_HEIGHT - 8.0);
+        bg.push_rect(x0, y0, w - 16.0, box_h, colors.border);
+        bg.push_rect(x0 + 1.0, y0 + 1.0, w - 18.0, box_h - 2.0, colors.win_bg);
+
+        let text_x = x0 + 8.0;
+        let text_y = y0 + (box_h - cell_h) / 2.0;
+        if filter.is_empty() && filter.preedit().is_empty() {
+            let dim = lerp_color(colors.win_bg, colors.text, 0.45);
+            self.push_text_instances(fg, "Filter", text_x, text_y, dim, glyphs, &gpu.queue);
+        }
+        let input_colors = TextInputColors {
+            text: colors.text,
+            selection: colors.hover,
+        };
+        let caret_x = self.push_text_input(
+            bg, fg, filter, text_x, text_y, input_colors, glyphs, &gpu.queue, 1.0,
+        );
+        bg.push_rect(caret_x, text_y, 2.0, cell_h, colors.text);
+    }
+
+    /// One row per scheme matching the filter: background swatch, name, and
+    /// a check on the active one.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "Renderer function requires full context for drawing"
+    )]
+    fn push_theme_page(
+        &mut self,
+        bg: &mut InstanceWriter,
+        fg: &mut InstanceWriter,
+        w: f32,
+        schemes: &[&ColorScheme],
+        active_scheme: &str,
+        colors: &PageColors,
+        glyphs: &mut FontCollection,
+        gpu: &GpuState,
+    ) {
+        let cell_h = glyphs.cell_height as f32;
+        let row_h = THEME_ROW_HEIGHT;
 
-        for (i, scheme) in BUILTIN_SCHEMES.iter().enumerate() {
-            let y0 = title_h + i as f32 * row_h;
+        for (i, scheme) in schemes.iter().enumerate() {
+            let y0 = SettingsLayout::content_top() + i as f32 * row_h;
             let is_active = scheme.name == active_scheme;
 
             if is_active {
-                bg.push_rect(4.0, y0, w - 8.0, row_h, row_hover);
+                bg.push_rect(4.0, y0, w - 8.0, row_h, colors.hover);
             }
 
             // Color preview swatch (small square of the scheme's bg)
@@ -102,21 +215,21 @@ impl GpuRenderer {
             let swatch_y = y0 + (row_h - 16.0) / 2.0;
             bg.push_rect(swatch_x, swatch_y, 16.0, 16.0, swatch_color);
             // Swatch border
-            bg.push_rect(swatch_x, swatch_y, 16.0, 1.0, border_c);
-            bg.push_rect(swatch_x, swatch_y + 15.0, 16.0, 1.0, border_c);
-            bg.push_rect(swatch_x, swatch_y, 1.0, 16.0, border_c);
-            bg.push_rect(swatch_x + 15.0, swatch_y, 1.0, 16.0, border_c);
+            bg.push_rect(swatch_x, swatch_y, 16.0, 1.0, colors.border);
+            bg.push_rect(swatch_x, swatch_y + 15.0, 16.0, 1.0, colors.border);
+            bg.push_rect(swatch_x, swatch_y, 1.0, 16.0, colors.border);
+            bg.push_rect(swatch_x + 15.0, swatch_y, 1.0, 16.0, colors.border);
 
             // Scheme name
             let text_x: f32 = 40.0;
-            let text_y = y0 + (row_h - cell_h as f32) / 2.0;
+            let text_y = y0 + (row_h - cell_h) / 2.0;
             let name_color = if is_active {
-                title_fg
+                colors.text
             } else {
-                lerp_color(win_bg, row_fg, 0.75)
+                lerp_color(colors.win_bg, colors.text, 0.75)
             };
             self.push_text_instances(
-                &mut fg,
+                fg,
                 scheme.name,
                 text_x,
                 text_y,
@@ -130,19 +243,77 @@ impl GpuRenderer {
                 let check_cx = w - 30.0 + 5.0;
                 let check_cy = y0 + row_h / 2.0;
                 self.push_icon(
-                    &mut fg,
+                    fg,
                     crate::icons::Icon::Checkmark,
                     check_cx,
                     check_cy,
                     10.0,
                     1.0,
-                    title_fg,
+                    colors.text,
                     &gpu.queue,
                 );
             }
         }
+    }
 
-        // Submit render
-        self.submit_simple_frame(gpu, surface, config, &bg, &fg, win_bg);
+    /// The ANSI colors matching the filter in two columns: an "Aa" sample on
+    /// the scheme background, the index, and the contrast ratio, tinted when
+    /// it fails WCAG AA. Below them, a toggle that enforces a 4.5:1 minimum.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "Renderer function requires full context for drawing"
+    )]
+    fn push_contrast_page(
+        &mut self,
+        bg: &mut InstanceWriter,
+        fg: &mut InstanceWriter,
+        w: f32,
+        layout: &SettingsLayout,
+        palette: &Palette,
+        minimum_contrast: f32,
+        colors: &PageColors,
+        glyphs: &mut FontCollection,
+        gpu: &GpuState,
+    ) {
+        let cell_h = glyphs.cell_height as f32;
+        let row_h = CONTRAST_ROW_HEIGHT;
+        let col_w = (w - 24.0) / 2.0;
+        let scheme_bg = vte_rgb_to_rgba(palette.default_bg());
+        let audit = contrast::audit(palette);
+        let visible = layout.colors.iter().filter_map(|&i| audit.get(i));
+
+        for (pos, entry) in visible.enumerate() {
+            let (col, row) = layout.color_slot(pos);
+            let x0 = 8.0 + col as f32 * (col_w + 8.0);
+            let y0 = SettingsLayout::content_top() + row as f32 * row_h;
+            let text_y = y0 + (row_h - cell_h) / 2.0;
+
+            bg.push_rect(x0, y0 + 2.0, 44.0, row_h - 4.0, scheme_bg);
+            let sample = vte_rgb_to_rgba(entry.color);
+            self.push_text_instances(fg, "Aa", x0 + 8.0, text_y, sample, glyphs, &gpu.queue);
+
+            let label_fg = match entry.readability() {
+                Readability::Good => colors.text,
+                Readability::LargeOnly => WARN_FG,
+                Readability::Unreadable => BAD_FG,
+            };
+            let label = format!("{:>2}  {:>4.1}", entry.index, entry.ratio);
+            self.push_text_instances(fg, &label, x0 + 52.0, text_y, label_fg, glyphs, &gpu.queue);
+        }
+
+        // Minimum contrast toggle
+        let Some(y0) = layout.toggle_top() else {
+            return;
+        };
+        let enforced = minimum_contrast >= WCAG_AA;
+        if enforced {
+            bg.push_rect(4.0, y0, w - 8.0, THEME_ROW_HEIGHT, colors.hover);
+            let check_cy = y0 + THEME_ROW_HEIGHT / 2.0;
+            let icon = crate::icons::Icon::Checkmark;
+            self.push_icon(fg, icon, 21.0, check_cy, 10.0, 1.0, colors.text, &gpu.queue);
+        }
+        let text_y = y0 + (THEME_ROW_HEIGHT - cell_h) / 2.0;
+        let label = CONTRAST_TOGGLE_LABEL;
+        self.push_text_instances(fg, label, 40.0, text_y, colors.text, glyphs, &gpu.queue);
     }
 }
@@ -0,0 +1,108 @@
+//! Per-tab indicators in the tab bar: the unread badge, the activity
+//! dot, and the progress strip.
+
+use vte::ansi::{Color, NamedColor};
+
+use crate::cell::CellFlags;
+use crate::font::FontCollection;
+use crate::tab::TaskProgress;
+use crate::tab_bar::{TAB_BAR_HEIGHT, TAB_TOP_MARGIN};
+use super::color_util::{vte_rgb_to_rgba, TabBarColors};
+use super::instance_writer::InstanceWriter;
+use super::renderer::{FrameParams, GpuRenderer};
+
+impl GpuRenderer {
+    /// Draw an unread-output count pill ending at `right` and return the
+    /// horizontal space it takes, including its left gap.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "Badge drawing needs both writers, colors, and font context"
+    )]
+    pub(super) fn push_unread_badge(
+        &mut self,
+        bg: &mut InstanceWriter,
+        fg: &mut InstanceWriter,
+        label: &str,
+        right: f32,
+        tc: &TabBarColors,
+        params: &FrameParams<'_>,
+        glyphs: &mut FontCollection,
+        queue: &wgpu::Queue,
+    ) -> f32 {
+        let s = params.scale;
+        let top = TAB_TOP_MARGIN as f32 * s;
+        let tab_h = TAB_BAR_HEIGHT as f32 * s - top;
+        let cell_h = glyphs.cell_height as f32;
+        let pad = 6.0 * s;
+        let gap = 4.0 * s;
+
+        let pill_w = glyphs.text_advance(label) + pad * 2.0;
+        let pill_h = cell_h + 2.0 * s;
+        let pill_x = right - gap - pill_w;
+        let pill_y = top + (tab_h - pill_h) / 2.0;
+
+        bg.push_rounded_rect(pill_x, pill_y, pill_w, pill_h, badge_color(params), pill_h / 2.0);
+        let text_y = pill_y + (pill_h - cell_h) / 2.0;
+        self.push_text_instances(fg, label, pill_x + pad, text_y, tc.active_bg, glyphs, queue);
+        pill_w + gap * 2.0
+    }
+}
+
+/// Draw the activity dot ending at `right` and return the horizontal
+/// space it takes, including its gaps.
+pub(super) fn push_activity_dot(bg: &mut InstanceWriter, right: f32, params: &FrameParams<'_>) -> f32 {
+    let s = params.scale;
+    let top = TAB_TOP_MARGIN as f32 * s;
+    let tab_h = TAB_BAR_HEIGHT as f32 * s - top;
+    let d = 6.0 * s;
+    let gap = 4.0 * s;
+    let y = top + (tab_h - d) / 2.0;
+    bg.push_rounded_rect(right - gap - d, y, d, d, badge_color(params), d / 2.0);
+    d + gap * 2.0
+}
+
+/// Outline a tab that receives its window's broadcast input, in the
+/// palette's red so typing into several tabs at once is hard to miss.
+pub(super) fn push_broadcast_outline(
+    bg: &mut InstanceWriter,
+    x0: f32,
+    tab_w: f32,
+    params: &FrameParams<'_>,
+) {
+    let s = params.scale;
+    let top = TAB_TOP_MARGIN as f32 * s;
+    let tab_h = TAB_BAR_HEIGHT as f32 * s - top;
+    let w = 2.0 * s;
+    let color = params
+        .palette
+        .resolve(Color::Named(NamedColor::Red), CellFlags::empty());
+    let color = vte_rgb_to_rgba(color);
+    bg.push_rect(x0, top, tab_w, w, color);
+    bg.push_rect(x0, top + tab_h - w, tab_w, w, color);
+    bg.push_rect(x0, top, w, tab_h, color);
+    bg.push_rect(x0 + tab_w - w, top, w, tab_h, color);
+}
+
+/// Fill of the unread badge and activity dot: `tab_badge.color`, else
+/// the palette's blue.
+fn badge_color(params: &FrameParams<'_>) -> [f32; 4] {
+    let color = params.unread_badge_color.unwrap_or_else(|| {
+        params
+            .palette
+            .resolve(Color::Named(NamedColor::Blue), CellFlags::empty())
+    });
+    vte_rgb_to_rgba(color)
+}
+
+/// Filled fraction and color of a tab's progress strip, if it shows one.
+/// Indeterminate progress fills the whole strip.
+pub(super) fn progress_strip(progress: TaskProgress) -> Option<(f32, NamedColor)> {
+    let percent = |p: u8| f32::from(p) / 100.0;
+    match progress {
+        TaskProgress::Hidden => None,
+        TaskProgress::Normal(p) => Some((percent(p), NamedColor::Green)),
+        TaskProgress::Error(p) => Some((percent(p), NamedColor::Red)),
+        TaskProgress::Indeterminate => Some((1.0, NamedColor::Blue)),
+        TaskProgress::Paused(p) => Some((percent(p), NamedColor::Yellow)),
+    }
+}
@@ -1,20 +1,27 @@
 //! Tab bar instance building — inactive/active tabs, close buttons, window controls.
 
+use vte::ansi::Color;
+
+use crate::cell::CellFlags;
 use crate::font::FontCollection;
+use crate::icons::Icon;
 use crate::tab_bar::{
     TabBarHit, TabBarLayout, CLOSE_BUTTON_RIGHT_PAD, CLOSE_BUTTON_WIDTH, CONTROLS_ZONE_WIDTH,
     DROPDOWN_BUTTON_WIDTH, ICON_SIZE, NEW_TAB_BUTTON_WIDTH, TAB_BAR_HEIGHT, TAB_LEFT_MARGIN,
-    TAB_PADDING, TAB_TOP_MARGIN,
+    TAB_ICON_SIZE, TAB_PADDING, TAB_TOP_MARGIN,
 };
 #[cfg(target_os = "windows")]
 use crate::tab_bar::CONTROL_BUTTON_WIDTH;
 #[cfg(not(target_os = "windows"))]
 use crate::tab_bar::{CONTROL_BUTTON_DIAMETER, CONTROL_BUTTON_MARGIN, CONTROL_BUTTON_SPACING};
 use super::color_util::{
-    lerp_color, lighten, TabBarColors, CONTROL_CLOSE_HOVER_BG, CONTROL_CLOSE_HOVER_FG,
+    lerp_color, lighten, vte_rgb_to_rgba, TabBarColors, CONTROL_CLOSE_HOVER_BG,
+    CONTROL_CLOSE_HOVER_FG,
 };
 use super::instance_writer::InstanceWriter;
 use super::renderer::{FrameParams, GpuRenderer};
+use super::render_tab_badges::{progress_strip, push_activity_dot, push_broadcast_outline};
+use super::render_text_input::TextInputColors;
 
 #[cfg(not(target_os = "windows"))]
 const CONTROL_CIRCLE_ALPHA: f32 = 0.3;
@@ -36,14 +43,7 @@ impl GpuRenderer {
         // Full-width bar background (darkest layer)
         bg.push_rect(0.0, 0.0, w, tab_bar_h, tc.bar_bg);
 
-        let tab_count = params.tab_info.len();
-        let layout = TabBarLayout::compute(
-            tab_count,
-            params.width as usize,
-            s as f64,
-            params.tab_width_lock,
-        );
-        let tab_w = layout.tab_width;
+        let layout = frame_layout(params);
 
         let cell_h = glyphs.cell_height;
 
@@ -51,8 +51,6 @@ impl GpuRenderer {
         let top = TAB_TOP_MARGIN as f32 * s;
         let tab_h = tab_bar_h - top;
 
-        let tab_wf = tab_w as f32;
-
         // Pass 1: inactive tabs (drawn first, behind active)
         let left_margin = (TAB_LEFT_MARGIN as f32 * s) as usize;
         for (i, (_id, title)) in params.tab_info.iter().enumerate() {
@@ -60,7 +58,9 @@ impl GpuRenderer {
                 continue;
             }
 
-            let base_x = (left_margin + i * tab_w) as f32;
+            let tab_w = layout.width_of(i);
+            let tab_wf = tab_w as f32;
+            let base_x = (left_margin + layout.tab_offset(i)) as f32;
             let x0 = base_x + params.tab_offsets.get(i).copied().unwrap_or(0.0);
             let is_hovered = params.hover_hit == TabBarHit::Tab(i);
 
@@ -111,7 +111,9 @@ impl GpuRenderer {
         // Pass 2: active tab (drawn on top of inactive)
         if let Some((_id, title)) = params.tab_info.get(params.active_tab) {
             if Some(params.active_tab) != dragged_idx {
-                let base_x = (left_margin + params.active_tab * tab_w) as f32;
+                let tab_w = layout.width_of(params.active_tab);
+                let tab_wf = tab_w as f32;
+                let base_x = (left_margin + layout.tab_offset(params.active_tab)) as f32;
                 let x0 = base_x
                     + params
                         .tab_offsets
@@ -120,6 +122,9 @@ impl GpuRenderer {
                         .unwrap_or(0.0);
 
                 bg.push_rounded_rect(x0, top, tab_wf, tab_h, tc.active_bg, 8.0 * s);
+                if let Some(accent) = tc.accent {
+                    bg.push_rect(x0 + 8.0 * s, top, tab_wf - 16.0 * s, 2.0 * s, accent);
+                }
 
                 self.render_tab_content(
                     bg,
@@ -145,7 +150,7 @@ impl GpuRenderer {
         // in the overlay pass so they move with the dragged tab (fast path).
         if params.dragged_tab.is_none() {
             let new_tab_w = NEW_TAB_BUTTON_WIDTH as f32 * s;
-            let plus_x = (left_margin + tab_count * tab_w) as f32;
+            let plus_x = (left_margin + layout.tabs_width()) as f32;
             let plus_hovered = params.hover_hit == TabBarHit::NewTab;
             let plus_bg = if plus_hovered {
                 tc.button_hover_bg
@@ -157,7 +162,7 @@ impl GpuRenderer {
             let plus_cy = top + tab_h / 2.0;
             self.push_icon(
                 fg,
-                crate::icons::Icon::Plus,
+                Icon::Plus,
                 plus_cx,
                 plus_cy,
                 10.0,
@@ -180,7 +185,7 @@ impl GpuRenderer {
             let dd_cy = top + tab_h / 2.0;
             self.push_icon(
                 fg,
-                crate::icons::Icon::ChevronDown,
+                Icon::ChevronDown,
                 dd_cx,
                 dd_cy,
                 10.0,
@@ -193,7 +198,7 @@ impl GpuRenderer {
         // Window control buttons
         let controls_zone_w = (CONTROLS_ZONE_WIDTH as f32 * s) as usize;
         let controls_start = (params.width as usize).saturating_sub(controls_zone_w) as f32;
-        self.build_window_controls(bg, controls_start, params, tc);
+        self.build_window_controls(bg, fg, controls_start, params, tc, queue);
     }
 
     /// Render a single tab's text content and close button.
@@ -222,18 +227,89 @@ impl GpuRenderer {
         let tab_padding = (TAB_PADDING as f32 * s) as usize;
         let close_btn_w = (CLOSE_BUTTON_WIDTH as f32 * s) as usize;
 
+        // Task progress strip (OSC 9;4) along the bottom edge
+        let progress = params.tab_progress.get(tab_idx).copied().unwrap_or_default();
+        if let Some((fraction, color)) = progress_strip(progress) {
+            let inset = 8.0 * s;
+            let strip_h = 2.0 * s;
+            let strip_w = (tab_w as f32 - inset * 2.0) * fraction;
+            let color = params.palette.resolve(Color::Named(color), CellFlags::empty());
+            let strip_y = top + tab_h - strip_h;
+            bg.push_rect(x0 + inset, strip_y, strip_w, strip_h, vte_rgb_to_rgba(color));
+        }
+
+        // Accent color (OSC 6 or a host rule) along the top edge
+        if let Some(color) = params.tab_colors.get(tab_idx).copied().flatten() {
+            let inset = 8.0 * s;
+            let strip_w = tab_w as f32 - inset * 2.0;
+            bg.push_rect(x0 + inset, top, strip_w, 3.0 * s, vte_rgb_to_rgba(color));
+        }
+
+        if params.tab_broadcast.get(tab_idx).copied().unwrap_or(false) {
+            push_broadcast_outline(bg, x0, tab_w as f32, params);
+        }
+
+        let label = params.unread_labels.get(tab_idx).map_or("", String::as_str);
+        let activity = params.tab_activity.get(tab_idx).copied().unwrap_or(false);
+        if params.tab_pinned.get(tab_idx).copied().unwrap_or(false) {
+            // Pinned: the icon alone, with a dot for any unread output
+            let icon = params.tab_icons.get(tab_idx).copied().flatten();
+            if let Some(icon) = icon {
+                let cx = x0 + tab_w as f32 / 2.0;
+                let size = TAB_ICON_SIZE as f32;
+                self.push_icon(fg, icon, cx, top + tab_h / 2.0, size, s, text_fg, queue);
+            }
+            if !label.is_empty() || activity {
+                push_activity_dot(bg, x0 + tab_w as f32, params);
+            }
+            return;
+        }
+
+        // Unread output badge, right-aligned just before the close button
+        let close_right_pad = CLOSE_BUTTON_RIGHT_PAD as f32 * s;
+        let close_x = x0 + tab_w as f32 - close_btn_w as f32 - close_right_pad;
+        let badge_w = if !label.is_empty() {
+            self.push_unread_badge(bg, fg, label, close_x, tc, params, glyphs, queue)
+        } else if activity {
+            push_activity_dot(bg, close_x, params)
+        } else {
+            0.0
+        };
+
+        // Shell icon, then the title after it
+        let mut text_x = x0 + tab_padding as f32;
+        let mut icon_w = 0.0;
+        if let Some(icon) = params.tab_icons.get(tab_idx).copied().flatten() {
+            let size = TAB_ICON_SIZE as f32;
+            let icon_cx = text_x + size * s / 2.0;
+            self.push_icon(fg, icon, icon_cx, top + tab_h / 2.0, size, s, text_fg, queue);
+            icon_w = (size + 6.0) * s;
+            text_x += icon_w;
+        }
+
         // Title text — only truncated if it overflows the available space
-        let max_text_px = (tab_w - tab_padding * 2 - close_btn_w) as f32;
+        let max_text_px = (tab_w - tab_padding * 2 - close_btn_w) as f32 - badge_w - icon_w;
         let display_title = glyphs.truncate_to_pixel_width(title, max_text_px);
 
-        let text_x = x0 + tab_padding as f32;
         let text_y = top + (tab_h - cell_h as f32) / 2.0;
-        self.push_text_instances(fg, &display_title, text_x, text_y, text_fg, glyphs, queue);
+        match params.tab_rename {
+            Some((idx, input)) if idx == tab_idx => {
+                let colors = TextInputColors {
+                    text: tc.text_fg,
+                    selection: lighten(tc.bar_bg, 0.35),
+                };
+                let caret_x = self.push_text_input(
+                    bg, fg, input, text_x, text_y, colors, glyphs, queue, s,
+                );
+                bg.push_rect(caret_x, text_y, 2.0 * s, cell_h as f32, tc.text_fg);
+            }
+            _ => {
+                self.push_text_instances(fg, &display_title, text_x, text_y, text_fg, glyphs, queue);
+            }
+        }
 
         // Close button — vector icon
         let close_btn_wf = close_btn_w as f32;
-        let close_right_pad = CLOSE_BUTTON_RIGHT_PAD as f32 * s;
-        let close_x = x0 + tab_w as f32 - close_btn_wf - close_right_pad;
         let close_hovered = params.hover_hit == TabBarHit::CloseTab(tab_idx);
         if close_hovered {
             let sq_y = top + (tab_h - close_btn_wf) / 2.0;
@@ -254,7 +330,7 @@ impl GpuRenderer {
         let icon_cy = top + tab_h / 2.0;
         self.push_icon(
             fg,
-            crate::icons::Icon::Close,
+            Icon::Close,
             icon_cx,
             icon_cy,
             10.0,
@@ -266,110 +342,82 @@ impl GpuRenderer {
 
     #[cfg(target_os = "windows")]
     fn build_window_controls(
-        &self,
+        &mut self,
         bg: &mut InstanceWriter,
+        fg: &mut InstanceWriter,
         controls_start: f32,
         params: &FrameParams<'_>,
         tc: &TabBarColors,
+        queue: &wgpu::Queue,
     ) {
         let sc = params.scale;
         let btn_w = CONTROL_BUTTON_WIDTH as f32 * sc;
         let bar_h = TAB_BAR_HEIGHT as f32 * sc;
-        let icon_sz = ICON_SIZE as f32 * sc;
-        let line_t = sc;
-
-        // Minimize button (geometric horizontal line)
-        {
-            let btn_x = controls_start;
-            let hovered = params.hover_hit == TabBarHit::Minimize;
-            if hovered {
-                bg.push_rect(btn_x, 0.0, btn_w, bar_h, tc.control_hover_bg);
-            }
-            let fg_color = if hovered {
-                tc.control_fg
-            } else {
-                tc.control_fg_dim
-            };
-            let line_w: f32 = 10.0 * sc;
-            let line_x = btn_x + (btn_w - line_w) / 2.0;
-            let line_y = bar_h / 2.0;
-            bg.push_rect(line_x, line_y, line_w, line_t, fg_color);
-        }
-
-        // Maximize/Restore button
-        {
-            let btn_x = controls_start + btn_w;
-            let hovered = params.hover_hit == TabBarHit::Maximize;
-            if hovered {
-                bg.push_rect(btn_x, 0.0, btn_w, bar_h, tc.control_hover_bg);
-            }
-            let fg_color = if hovered {
-                tc.control_fg
-            } else {
-                tc.control_fg_dim
-            };
-            let icon_x = btn_x + (btn_w - icon_sz) / 2.0;
-            let icon_y = (bar_h - icon_sz) / 2.0;
-            let erase_bg = if hovered {
-                tc.control_hover_bg
-            } else {
-                tc.bar_bg
-            };
-            draw_maximize_icon(bg, icon_x, icon_y, icon_sz, sc, line_t, fg_color, erase_bg, params.is_maximized);
-        }
+        let maximize = if params.is_maximized {
+            Icon::Restore
+        } else {
+            Icon::Maximize
+        };
+        let buttons = [
+            (TabBarHit::Minimize, Icon::Minimize),
+            (TabBarHit::Maximize, maximize),
+            (TabBarHit::CloseWindow, Icon::WindowClose),
+        ];
 
-        // Close button (Windows 11 style: geometric x drawn with small rects)
-        {
-            let btn_x = controls_start + btn_w * 2.0;
-            let hovered = params.hover_hit == TabBarHit::CloseWindow;
+        // Windows 11 style: full-height hover fill, close turns red.
+        for (i, (hit, icon)) in buttons.into_iter().enumerate() {
+            let btn_x = controls_start + btn_w * i as f32;
+            let hovered = params.hover_hit == hit;
+            let is_close = hit == TabBarHit::CloseWindow;
             if hovered {
-                bg.push_rect(btn_x, 0.0, btn_w, bar_h, CONTROL_CLOSE_HOVER_BG);
+                let hover_bg = if is_close {
+                    CONTROL_CLOSE_HOVER_BG
+                } else {
+                    tc.control_hover_bg
+                };
+                bg.push_rect(btn_x, 0.0, btn_w, bar_h, hover_bg);
             }
-            let close_fg = if hovered {
-                CONTROL_CLOSE_HOVER_FG
-            } else {
-                tc.control_fg_dim
+            let fg_color = match (hovered, is_close) {
+                (true, true) => CONTROL_CLOSE_HOVER_FG,
+                (true, false) => tc.control_fg,
+                (false, _) => tc.control_fg_dim,
             };
-            let x_size: f32 = 10.0 * sc;
-            let cx = btn_x + (btn_w - x_size) / 2.0;
-            let cy = (bar_h - x_size) / 2.0;
-            draw_x_icon(bg, cx, cy, x_size, sc, close_fg);
+            let cx = btn_x + btn_w / 2.0;
+            self.push_icon(fg, icon, cx, bar_h / 2.0, ICON_SIZE as f32, sc, fg_color, queue);
         }
     }
 
     #[cfg(not(target_os = "windows"))]
     fn build_window_controls(
-        &self,
-        bg: &mut InstanceWriter,
+        &mut self,
+        _bg: &mut InstanceWriter,
+        fg: &mut InstanceWriter,
         controls_start: f32,
         params: &FrameParams<'_>,
         tc: &TabBarColors,
+        queue: &wgpu::Queue,
     ) {
         let sc = params.scale;
-        let bar_h = TAB_BAR_HEIGHT as f32 * sc;
+        let cy = TAB_BAR_HEIGHT as f32 * sc / 2.0;
         let r = CONTROL_BUTTON_DIAMETER as f32 * sc / 2.0;
-        let cy = bar_h / 2.0;
-        let icon_s = ICON_SIZE as f32 * sc;
         let margin = CONTROL_BUTTON_MARGIN as f32 * sc;
-        let diameter = CONTROL_BUTTON_DIAMETER as f32 * sc;
-        let spacing = CONTROL_BUTTON_SPACING as f32 * sc;
-        let line_t = sc;
-
-        // Button center X positions: minimize, maximize, close (left to right)
-        let btn_cx = [
-            controls_start + margin + r,
-            controls_start + margin + diameter + spacing + r,
-            controls_start + margin + 2.0 * (diameter + spacing) + r,
-        ];
-        let hits = [
-            TabBarHit::Minimize,
-            TabBarHit::Maximize,
-            TabBarHit::CloseWindow,
+        let pitch = (CONTROL_BUTTON_DIAMETER + CONTROL_BUTTON_SPACING) as f32 * sc;
+        let maximize = if params.is_maximized {
+            Icon::Restore
+        } else {
+            Icon::Maximize
+        };
+        let buttons = [
+            (TabBarHit::Minimize, Icon::Minimize),
+            (TabBarHit::Maximize, maximize),
+            (TabBarHit::CloseWindow, Icon::WindowClose),
         ];
 
-        for (i, &bcx) in btn_cx.iter().enumerate() {
-            let hovered = params.hover_hit == hits[i];
-            let is_close = i == 2;
+        // GNOME style: icons on anti-aliased circles, left to right.
+        for (i, (hit, icon)) in buttons.into_iter().enumerate() {
+            let bcx = controls_start + margin + r + pitch * i as f32;
+            let hovered = params.hover_hit == hit;
+            let is_close = hit == TabBarHit::CloseWindow;
 
             // Circle background (blended with bar bg for semi-transparency)
             let circle_fg = if hovered && is_close {
@@ -380,96 +428,27 @@ impl GpuRenderer {
                 tc.control_fg_dim
             };
             let circle_bg = lerp_color(tc.bar_bg, circle_fg, CONTROL_CIRCLE_ALPHA);
-            // Draw filled circle as horizontal slices
-            let d = (CONTROL_BUTTON_DIAMETER as f32 * sc) as i32;
-            for row in 0..d {
-                let dy = row as f32 - r + 0.5;
-                let half_w = (r * r - dy * dy).sqrt();
-                let x0 = bcx - half_w;
-                let w = half_w * 2.0;
-                bg.push_rect(x0, cy - r + row as f32, w, 1.0, circle_bg);
-            }
+            let diameter = CONTROL_BUTTON_DIAMETER as f32;
+            self.push_icon(fg, Icon::Circle, bcx, cy, diameter, sc, circle_bg, queue);
 
-            // Icon foreground
             let fg_color = if hovered && is_close {
                 CONTROL_CLOSE_HOVER_FG
             } else {
                 tc.control_fg
             };
-            let ix = bcx - icon_s / 2.0;
-            let iy = cy - icon_s / 2.0;
-
-            match i {
-                0 => {
-                    bg.push_rect(ix, cy, icon_s, line_t, fg_color);
-                }
-                1 => {
-                    draw_maximize_icon(bg, ix, iy, icon_s, sc, line_t, fg_color, circle_bg, params.is_maximized);
-                }
-                _ => {
-                    draw_x_icon(bg, ix, iy, icon_s, sc, fg_color);
-                }
-            }
+            self.push_icon(fg, icon, bcx, cy, ICON_SIZE as f32, sc, fg_color, queue);
         }
     }
 }
 
-/// Draw a maximize (single square) or restore (two overlapping squares) icon.
-/// `erase_bg` is used to punch out the overlap area in restore mode.
-#[expect(clippy::too_many_arguments, reason = "Geometric drawing primitive with explicit coordinates")]
-fn draw_maximize_icon(
-    bg: &mut InstanceWriter,
-    x: f32,
-    y: f32,
-    size: f32,
-    sc: f32,
-    line_t: f32,
-    fg: [f32; 4],
-    erase_bg: [f32; 4],
-    is_maximized: bool,
-) {
-    if is_maximized {
-        let sm = size - 2.0 * sc;
-        // Back square (offset up-right)
-        draw_square_outline(bg, x + 2.0 * sc, y, sm, line_t, fg);
-        // Front square (offset down-left)
-        draw_square_outline(bg, x, y + 2.0 * sc, sm, line_t, fg);
-        // Erase overlap between the two squares
-        bg.push_rect(x + line_t, y + 3.0 * sc, sm - 2.0 * line_t, sm - 2.0 * line_t, erase_bg);
-    } else {
-        draw_square_outline(bg, x, y, size, line_t, fg);
-    }
-}
-
-/// Draw a square outline (4 edges) as rectangles.
-fn draw_square_outline(
-    bg: &mut InstanceWriter,
-    x: f32,
-    y: f32,
-    size: f32,
-    line_t: f32,
-    fg: [f32; 4],
-) {
-    bg.push_rect(x, y, size, line_t, fg);                  // top
-    bg.push_rect(x, y + size - line_t, size, line_t, fg);  // bottom
-    bg.push_rect(x, y, line_t, size, fg);                  // left
-    bg.push_rect(x + size - line_t, y, line_t, size, fg);  // right
-}
-
-/// Draw an X icon as 1px diagonal squares.
-fn draw_x_icon(
-    bg: &mut InstanceWriter,
-    x: f32,
-    y: f32,
-    size: f32,
-    sc: f32,
-    fg: [f32; 4],
-) {
-    let steps = size as usize;
-    let step = size / steps as f32;
-    for i in 0..steps {
-        let fi = i as f32 * step;
-        bg.push_rect(x + fi, y + fi, sc, sc, fg);
-        bg.push_rect(x + size - sc - fi, y + fi, sc, sc, fg);
-    }
+/// The frame's tab bar layout, with its leading pinned tabs drawn compact.
+pub(super) fn frame_layout(params: &FrameParams<'_>) -> TabBarLayout {
+    let pinned = params.tab_pinned.iter().take_while(|&&p| p).count();
+    TabBarLayout::compute(
+        params.tab_info.len(),
+        pinned,
+        params.width as usize,
+        f64::from(params.scale),
+        params.tab_width_lock,
+    )
 }
@@ -0,0 +1,145 @@
+//! The tab switcher overlay: a panel below the tab bar with the filter
+//! query on top and one row per matching tab. Each row has the tab's
+//! title, its directory at the right, and a thumbnail of the last lines on
+//! its screen. Rows that don't fit scroll to keep the highlight visible.
+
+use crate::font::FontCollection;
+use crate::tab_bar::TAB_BAR_HEIGHT;
+use crate::ui::tab_switcher::SwitcherEntry;
+
+use super::color_util::{MenuColors, lerp_color, vte_rgb_to_rgba};
+use super::instance_writer::InstanceWriter;
+use super::render_text_input::TextInputColors;
+use super::renderer::{FrameParams, GpuRenderer};
+
+/// Widest the panel gets, in logical pixels.
+const PANEL_MAX_WIDTH: f32 = 640.0;
+/// Space around the panel and inside it, in logical pixels.
+const PANEL_PADDING: f32 = 12.0;
+/// Corner radius of the panel and the highlight, in logical pixels.
+const PANEL_RADIUS: f32 = 8.0;
+/// Preview lines each row has room for.
+const PREVIEW_ROWS: usize = 3;
+
+/// Where a switcher row is drawn.
+struct RowRect {
+    x: f32,
+    y: f32,
+    w: f32,
+}
+
+impl GpuRenderer {
+    pub(super) fn build_tab_switcher_overlay(
+        &mut self,
+        bg: &mut InstanceWriter,
+        fg: &mut InstanceWriter,
+        params: &FrameParams<'_>,
+        glyphs: &mut FontCollection,
+        queue: &wgpu::Queue,
+    ) {
+        let Some(switcher) = params.tab_switcher else {
+            return;
+        };
+        let s = params.scale;
+        let pad = PANEL_PADDING * s;
+        let cell_h = glyphs.cell_height as f32;
+        let colors = MenuColors::for_theme(params.chrome.theme);
+
+        let width = params.width as f32;
+        let panel_w = (PANEL_MAX_WIDTH * s).min(width - pad * 2.0).max(0.0);
+        let panel_x = (width - panel_w) / 2.0;
+        let panel_y = TAB_BAR_HEIGHT as f32 * s + pad;
+        let query_h = cell_h + pad;
+        let row_h = cell_h * (1 + PREVIEW_ROWS) as f32 + pad * 1.5;
+        let room = params.height as f32 - panel_y - pad * 3.0 - query_h;
+        let fit = ((room / row_h).floor() as usize).max(1);
+        let count = switcher.shown().count();
+        let rows = count.min(fit);
+        let panel_h = query_h + pad * 2.0 + rows as f32 * row_h;
+
+        bg.push_all_rounded_rect(panel_x, panel_y, panel_w, panel_h, colors.bg, PANEL_RADIUS * s);
+
+        // Query, with a prompt like the search bar's
+        let text_y = panel_y + pad;
+        let prefix = "> ";
+        self.push_text_instances(fg, prefix, panel_x + pad, text_y, colors.text_dim, glyphs, queue);
+        let input_colors = TextInputColors {
+            text: colors.text,
+            selection: colors.hover,
+        };
+        let query_x = panel_x + pad + glyphs.text_advance(prefix);
+        let caret_x = self.push_text_input(
+            bg, fg, &switcher.input, query_x, text_y, input_colors, glyphs, queue, s,
+        );
+        bg.push_rect(caret_x, text_y, 2.0 * s, cell_h, colors.text);
+        if count == 0 {
+            let x = panel_x + panel_w - pad - glyphs.text_advance("No tabs");
+            self.push_text_instances(fg, "No tabs", x, text_y, colors.text_dim, glyphs, queue);
+        }
+        bg.push_rect(panel_x, panel_y + query_h + pad / 2.0, panel_w, s, colors.separator);
+
+        // Rows, scrolled to keep the highlight in view
+        let selected = switcher.selected_index();
+        let first = (selected + 1).saturating_sub(rows);
+        let mut y = panel_y + query_h + pad;
+        for (i, entry) in switcher.shown().enumerate().skip(first).take(rows) {
+            let rect = RowRect {
+                x: panel_x + pad / 2.0,
+                y,
+                w: panel_w - pad,
+            };
+            if i == selected {
+                bg.push_all_rounded_rect(rect.x, y, rect.w, row_h, colors.hover, PANEL_RADIUS * s);
+            }
+            self.push_switcher_row(bg, fg, entry, &rect, &colors, params, glyphs, queue);
+            y += row_h;
+        }
+    }
+
+    /// Draw one row: the title, the directory right-aligned on the same
+    /// line, and the preview in a thumbnail below.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "Renderer function requires full context for drawing"
+    )]
+    fn push_switcher_row(
+        &mut self,
+        bg: &mut InstanceWriter,
+        fg: &mut InstanceWriter,
+        entry: &SwitcherEntry,
+        rect: &RowRect,
+        colors: &MenuColors,
+        params: &FrameParams<'_>,
+        glyphs: &mut FontCollection,
+        queue: &wgpu::Queue,
+    ) {
+        let s = params.scale;
+        let pad = PANEL_PADDING * s;
+        let cell_h = glyphs.cell_height as f32;
+        let inner_x = rect.x + pad / 2.0;
+        let inner_w = rect.w - pad;
+        let title_y = rect.y + pad / 2.0;
+
+        let cwd = entry.cwd.as_deref().unwrap_or("");
+        let cwd = glyphs.truncate_to_pixel_width(cwd, inner_w / 2.0).into_owned();
+        let cwd_w = glyphs.text_advance(&cwd);
+        let cwd_x = inner_x + inner_w - cwd_w;
+        self.push_text_instances(fg, &cwd, cwd_x, title_y, colors.text_dim, glyphs, queue);
+        let title_w = inner_w - cwd_w - pad;
+        let title = glyphs.truncate_to_pixel_width(&entry.title, title_w).into_owned();
+        self.push_text_instances(fg, &title, inner_x, title_y, colors.text, glyphs, queue);
+
+        // Thumbnail: the screen's background with its last lines
+        let thumb_y = title_y + cell_h + pad / 4.0;
+        let thumb_h = cell_h * PREVIEW_ROWS as f32;
+        let screen_bg = vte_rgb_to_rgba(params.palette.default_bg());
+        bg.push_all_rounded_rect(inner_x, thumb_y, inner_w, thumb_h, screen_bg, 4.0 * s);
+        let screen_fg = vte_rgb_to_rgba(params.palette.default_fg());
+        let text = lerp_color(screen_bg, screen_fg, 0.6);
+        for (row, line) in entry.preview.iter().take(PREVIEW_ROWS).enumerate() {
+            let line = glyphs.truncate_to_pixel_width(line, inner_w - pad).into_owned();
+            let line_y = thumb_y + row as f32 * cell_h;
+            self.push_text_instances(fg, &line, inner_x + pad / 2.0, line_y, text, glyphs, queue);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Text field drawing shared by the search bar and the settings window.
+
+use crate::font::FontCollection;
+use crate::ui::text_input::TextInput;
+use super::instance_writer::InstanceWriter;
+use super::renderer::GpuRenderer;
+
+/// Colors for a text field.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct TextInputColors {
+    pub(super) text: [f32; 4],
+    pub(super) selection: [f32; 4],
+}
+
+impl GpuRenderer {
+    /// Draw `input` at (`x`, `y`): the selection highlight, the text, and an
+    /// underlined IME preedit at the cursor. Returns the cursor's X so the
+    /// caller can draw a caret in its own style.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "Renderer function requires full context for drawing"
+    )]
+    pub(super) fn push_text_input(
+        &mut self,
+        bg: &mut InstanceWriter,
+        fg: &mut InstanceWriter,
+        input: &TextInput,
+        x: f32,
+        y: f32,
+        colors: TextInputColors,
+        glyphs: &mut FontCollection,
+        queue: &wgpu::Queue,
+        scale: f32,
+    ) -> f32 {
+        let cell_h = glyphs.cell_height as f32;
+        let text = input.text();
+        if let Some(range) = input.selection() {
+            let sel_x = x + glyphs.text_advance(text.get(..range.start).unwrap_or(""));
+            let sel_w = glyphs.text_advance(text.get(range).unwrap_or(""));
+            bg.push_rect(sel_x, y, sel_w, cell_h, colors.selection);
+        }
+
+        let (before, after) = input.split_at_cursor();
+        let preedit = input.preedit();
+        let preedit_x = x + glyphs.text_advance(before);
+        let after_x = preedit_x + glyphs.text_advance(preedit);
+        for (s, sx) in [(before, x), (preedit, preedit_x), (after, after_x)] {
+            if !s.is_empty() {
+                self.push_text_instances(fg, s, sx, y, colors.text, glyphs, queue);
+            }
+        }
+        if preedit.is_empty() {
+            return preedit_x;
+        }
+
+        // Composition underline, with the caret where the IME puts it
+        let underline_h = scale.max(1.0);
+        let underline_w = after_x - preedit_x;
+        bg.push_rect(preedit_x, y + cell_h - underline_h, underline_w, underline_h, colors.text);
+        input.preedit_cursor().map_or(after_x, |i| {
+            preedit_x + glyphs.text_advance(preedit.get(..i).unwrap_or(preedit))
+        })
+    }
+}
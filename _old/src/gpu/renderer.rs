@@ -10,11 +10,14 @@ use vte::ansi::CursorShape;
 use crate::config::AlphaBlending;
 use crate::font::{FontCollection, UiShapedGlyph, shape_text_string};
 use crate::grid::Grid;
+use crate::hyperlink::LinkId;
+use crate::icons::Icon;
 use crate::palette::Palette;
 use crate::search::SearchState;
 use crate::selection::Selection;
-use crate::tab::TabId;
-use crate::tab_bar::TabBarHit;
+use crate::tab::{TabId, TaskProgress};
+use crate::tab_bar::{ChromeTheme, TabBarHit};
+use crate::ui::text_input::{Preedit, TextInput};
 #[cfg(target_os = "windows")]
 use crate::tab_bar::{WINDOW_BORDER_COLOR, WINDOW_BORDER_WIDTH};
 use crate::term_mode::TermMode;
@@ -23,8 +26,14 @@ use super::atlas::GlyphAtlas;
 #[cfg(target_os = "windows")]
 use super::color_util::u32_to_rgba;
 use super::color_util::{ortho_projection, vte_rgb_to_rgba, TabBarColors};
-use super::instance_writer::{reuse_or_create_buffer, InstanceWriter};
+use super::buffer_pool::{InstanceBuffers, Slot};
+use super::frame_stats::{FrameSample, FrameStats};
+use super::instance_writer::InstanceWriter;
 use super::pipeline;
+use super::render_grid;
+use super::render_commands;
+use super::render_scrollbar;
+use super::render_inspector::InspectorOverlay;
 use super::state::GpuState;
 
 /// Frame data needed to build a frame.
@@ -36,16 +45,46 @@ pub struct FrameParams<'a> {
     pub palette: &'a Palette,
     pub mode: TermMode,
     pub cursor_shape: CursorShape,
+    /// Cursor color override (the unfocused cursor color), else the
+    /// palette's cursor color.
+    pub cursor_color: Option<vte::ansi::Rgb>,
     pub selection: Option<&'a Selection>,
     pub search: Option<&'a SearchState>,
+    /// Search filter mode: the absolute rows drawn in place of the
+    /// viewport, top to bottom.
+    pub filter_rows: Option<&'a [usize]>,
+    /// Escape sequence inspector panel for the active tab.
+    pub inspector: Option<InspectorOverlay<'a>>,
     pub tab_info: &'a [(TabId, String)],
     pub active_tab: usize,
+    /// Tab being renamed: its index and the name field drawn over its title.
+    pub tab_rename: Option<(usize, &'a TextInput)>,
     pub hover_hit: TabBarHit,
     pub is_maximized: bool,
+    /// OS light/dark preference and accent color for the chrome.
+    pub chrome: ChromeTheme,
     pub context_menu: Option<&'a crate::context_menu::MenuOverlay>,
+    /// Tab switcher drawn over this window.
+    pub tab_switcher: Option<&'a crate::ui::tab_switcher::TabSwitcher>,
+    /// Keys of a keybinding chord waiting for its next key.
+    pub pending_chord: Option<&'a str>,
+    /// Viewport line of the exit status mark under the mouse, and the
+    /// mouse position its tooltip is drawn beside.
+    pub gutter_hover: Option<(usize, (f32, f32))>,
+    /// Shortest command run time labeled on its prompt row; `None` hides
+    /// the labels.
+    pub command_duration_min_ms: Option<u64>,
+    /// Border around the grid from the active tab's `[[host_rule]]`.
+    pub host_border: Option<vte::ansi::Rgb>,
     pub opacity: f32,
-    pub hover_hyperlink: Option<&'a str>,
+    /// Hovered OSC 8 link: all of its cells get a solid underline.
+    pub hover_hyperlink: Option<LinkId>,
     pub hover_url_range: Option<&'a [(usize, usize, usize)]>,
+    /// Focus mode: no tab bar, and the grid starts at the window's corner.
+    pub focus_mode: bool,
+    /// Block selection (left, right) columns while Alt-dragging; guide
+    /// lines are drawn at both edges.
+    pub block_guides: Option<(usize, usize)>,
     pub minimum_contrast: f32,
     pub alpha_blending: AlphaBlending,
     /// Dragged tab: (index, pixel X). Rendered at this X, drawn on top.
@@ -56,8 +95,33 @@ pub struct FrameParams<'a> {
     pub bell_badges: &'a [bool],
     /// Sine pulse phase for bell badge animation (0.0-1.0).
     pub bell_phase: f32,
+    /// Per-tab unread output badge text; empty for tabs without a badge.
+    pub unread_labels: &'a [String],
+    /// Per-tab OSC 9;4 progress, drawn as a strip along each tab's bottom.
+    pub tab_progress: &'a [TaskProgress],
+    /// Per-tab accent color, drawn as a strip along the tab's top edge.
+    pub tab_colors: &'a [Option<vte::ansi::Rgb>],
+    /// Per-tab activity dot, for background tabs with unviewed output.
+    pub tab_activity: &'a [bool],
+    /// Per-tab icon drawn before the title.
+    pub tab_icons: &'a [Option<Icon>],
+    /// Per-tab broadcast input, drawn as an outline around the tab.
+    pub tab_broadcast: &'a [bool],
+    /// Per-tab pin; pinned tabs lead the bar and show only their icon.
+    pub tab_pinned: &'a [bool],
+    /// How blinking text is drawn in its "off" phase.
+    pub text_blink: crate::render::TextBlink,
+    /// Current slow/fast blink visibility.
+    pub blink_phase: crate::render::BlinkPhase,
+    /// Unread badge color override (`tab_badge.color`); palette blue otherwise.
+    pub unread_badge_color: Option<vte::ansi::Rgb>,
     /// Display scale factor for `HiDPI` (1.0 = normal, 2.0 = Retina).
     pub scale: f32,
+    /// Touchpad sub-cell scroll offset: every grid row moves down by this
+    /// many pixels (up when negative).
+    pub scroll_offset_px: f32,
+    /// IME composition drawn at the terminal cursor.
+    pub preedit: Option<&'a Preedit>,
     /// Whether the cursor should be visible (false when blink is in off phase).
     pub cursor_visible: bool,
     /// True when grid content changed and instances need rebuild.
@@ -100,6 +164,11 @@ pub struct GpuRenderer {
     cached_frame: Option<PreparedFrame>,
     /// Tracks which window was last rendered; invalidates cache on window switch.
     last_rendered_window: Option<WindowId>,
+    /// Persistent instance buffers and their staging belt, kept across frames
+    /// (and cache invalidations) so buffers are only ever grown.
+    instance_buffers: InstanceBuffers,
+    /// Frame-time instrumentation for the main render path.
+    frame_stats: FrameStats,
     // Reusable byte buffers for instance building — avoids per-frame heap allocation.
     buf_bg: Vec<u8>,
     buf_fg: Vec<u8>,
@@ -111,6 +180,10 @@ pub struct GpuRenderer {
     pub(super) shaped_scratch: Vec<crate::font::ShapedGlyph>,
     /// Scratch buffer for shaping runs, reused across lines to avoid allocation.
     pub(super) runs_scratch: Vec<crate::font::ShapingRun>,
+    /// Whether the last grid build drew any SGR 5/6 blinking cell.
+    pub(super) has_blinking_cells: bool,
+    /// Per-frame memo of resolved indexed cell colors.
+    pub(super) color_memo: crate::palette::ColorMemo,
     /// Reusable swash scale context for glyph rasterization.
     pub(super) scale_context: swash::scale::ScaleContext,
     /// Scratch buffer for UI text shaped glyphs, reused across calls.
@@ -180,6 +253,8 @@ impl GpuRenderer {
             render_format: format,
             cached_frame: None,
             last_rendered_window: None,
+            instance_buffers: InstanceBuffers::new(device),
+            frame_stats: FrameStats::new(),
             buf_bg: Vec::new(),
             buf_fg: Vec::new(),
             buf_overlay_bg: Vec::new(),
@@ -187,11 +262,19 @@ impl GpuRenderer {
             col_glyph_map: Vec::new(),
             shaped_scratch: Vec::new(),
             runs_scratch: Vec::new(),
+            has_blinking_cells: false,
+            color_memo: crate::palette::ColorMemo::default(),
             scale_context: swash::scale::ScaleContext::new(),
             ui_shaped_scratch: Vec::new(),
         }
     }
 
+    /// Whether the last rendered grid contained blinking text, so the blink
+    /// timer needs to keep redrawing.
+    pub fn has_blinking_cells(&self) -> bool {
+        self.has_blinking_cells
+    }
+
     /// Rebuild the atlas after font size change.
     pub fn rebuild_atlas(&mut self, gpu: &GpuState) {
         self.atlas = GlyphAtlas::new(&gpu.device);
@@ -284,38 +367,40 @@ impl GpuRenderer {
         collection: &mut FontCollection,
         ui_collection: &mut FontCollection,
     ) {
-        self.prepare_frame(gpu, params, collection, ui_collection);
-        let Some(prepared) = self.cached_frame.as_ref() else {
-            return;
-        };
-
-        // Get surface texture
-        let frame = match surface.get_current_texture() {
-            Ok(f) => f,
-            Err(wgpu::SurfaceError::Lost) => {
-                surface.configure(&gpu.device, config);
-                return;
-            }
-            Err(e) => {
-                crate::log(&format!("surface error: {e}"));
-                return;
-            }
-        };
-
-        let view = frame
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
-
+        let start = std::time::Instant::now();
+        let rebuilt = params.grid_dirty
+            || params.tab_bar_dirty
+            || self.cached_frame.is_none()
+            || self.last_rendered_window != Some(params.window_id);
+
+        // Instance uploads are recorded into this encoder, so it is submitted
+        // even when the surface can't be drawn this frame.
         let mut encoder = gpu
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
                 label: Some("frame_encoder"),
             });
+        self.prepare_frame(gpu, &mut encoder, params, collection, ui_collection);
+
+        let frame = gpu.acquire_frame(surface, config).and_then(|frame| {
+            let prepared = self.cached_frame.as_ref()?;
+            let view = frame
+                .texture
+                .create_view(&wgpu::TextureViewDescriptor::default());
+            self.encode_render_pass(&mut encoder, &view, prepared);
+            Some(frame)
+        });
 
-        self.encode_render_pass(&mut encoder, &view, prepared);
+        self.instance_buffers.submit(&gpu.queue, encoder);
+        if let Some(frame) = frame {
+            frame.present();
+        }
 
-        gpu.queue.submit(std::iter::once(encoder.finish()));
-        frame.present();
+        self.frame_stats.record(FrameSample {
+            elapsed: start.elapsed(),
+            rebuilt,
+            allocations: self.instance_buffers.take_allocations(),
+        });
     }
 
     /// Build all instance data for a frame and cache the `PreparedFrame`.
@@ -325,6 +410,7 @@ impl GpuRenderer {
     fn prepare_frame(
         &mut self,
         gpu: &GpuState,
+        encoder: &mut wgpu::CommandEncoder,
         params: &FrameParams<'_>,
         collection: &mut FontCollection,
         ui_collection: &mut FontCollection,
@@ -357,7 +443,7 @@ impl GpuRenderer {
         );
 
         // Compute tab bar colors once — used by tab bar, dragged tab, and search bar.
-        let tc = TabBarColors::from_palette(params.palette);
+        let tc = TabBarColors::from_palette(params.palette, params.chrome.accent);
 
         // Damage tracking: reuse cached frame (GPU buffers + all) when nothing changed.
         let needs_rebuild =
@@ -370,6 +456,10 @@ impl GpuRenderer {
             // buffer creation) is skipped entirely.
             let needs_overlay = params.dragged_tab.is_some()
                 || params.context_menu.is_some()
+                || params.tab_switcher.is_some()
+                || params.preedit.is_some()
+                || params.pending_chord.is_some()
+                || params.gutter_hover.is_some()
                 || self
                     .cached_frame
                     .as_ref()
@@ -389,6 +479,13 @@ impl GpuRenderer {
                     ui_collection,
                     &gpu.queue,
                 );
+                self.build_preedit_overlay(
+                    &mut overlay_bg_w,
+                    &mut overlay_fg_w,
+                    params,
+                    collection,
+                    &gpu.queue,
+                );
                 self.build_context_menu_overlay(
                     &mut overlay_bg_w,
                     &mut overlay_fg_w,
@@ -396,34 +493,39 @@ impl GpuRenderer {
                     ui_collection,
                     &gpu.queue,
                 );
+                self.build_tab_switcher_overlay(
+                    &mut overlay_bg_w,
+                    &mut overlay_fg_w,
+                    params,
+                    ui_collection,
+                    &gpu.queue,
+                );
+                self.build_chord_overlay(
+                    &mut overlay_bg_w,
+                    &mut overlay_fg_w,
+                    params,
+                    &tc,
+                    ui_collection,
+                    &gpu.queue,
+                );
+                self.build_command_tooltip(
+                    &mut overlay_bg_w,
+                    &mut overlay_fg_w,
+                    params,
+                    &tc,
+                    ui_collection,
+                    &gpu.queue,
+                );
 
                 let overlay_bg_bytes = overlay_bg_w.as_bytes();
                 let overlay_fg_bytes = overlay_fg_w.as_bytes();
                 let overlay_bg_count = overlay_bg_w.count();
                 let overlay_fg_count = overlay_fg_w.count();
-                let has_overlay = !overlay_bg_bytes.is_empty() || !overlay_fg_bytes.is_empty();
 
                 if let Some(ref mut cached) = self.cached_frame {
-                    if has_overlay {
-                        // Reuse existing GPU buffer if it fits, else create a new one.
-                        cached.overlay_bg_buffer = Some(reuse_or_create_buffer(
-                            &gpu.device,
-                            &gpu.queue,
-                            cached.overlay_bg_buffer.take(),
-                            overlay_bg_bytes,
-                            "overlay_bg",
-                        ));
-                        cached.overlay_fg_buffer = Some(reuse_or_create_buffer(
-                            &gpu.device,
-                            &gpu.queue,
-                            cached.overlay_fg_buffer.take(),
-                            overlay_fg_bytes,
-                            "overlay_fg",
-                        ));
-                    } else {
-                        cached.overlay_bg_buffer = None;
-                        cached.overlay_fg_buffer = None;
-                    }
+                    (cached.overlay_bg_buffer, cached.overlay_fg_buffer) = self
+                        .instance_buffers
+                        .upload_overlay(&gpu.device, encoder, overlay_bg_bytes, overlay_fg_bytes);
                     cached.overlay_bg_count = overlay_bg_count;
                     cached.overlay_fg_count = overlay_fg_count;
                 }
@@ -435,17 +537,7 @@ impl GpuRenderer {
             return;
         }
 
-        // Extract old GPU buffers for reuse before building new instance data.
-        let old = self.cached_frame.take();
-        let (old_bg_buf, old_fg_buf, old_overlay_bg_buf, old_overlay_fg_buf) = match old {
-            Some(f) => (
-                Some(f.bg_buffer),
-                Some(f.fg_buffer),
-                f.overlay_bg_buffer,
-                f.overlay_fg_buffer,
-            ),
-            None => (None, None, None, None),
-        };
+        self.cached_frame = None;
 
         // Build instance data — reuse byte buffers from previous frame.
         // Tab bar and window border are fully opaque (opacity=1.0).
@@ -457,28 +549,42 @@ impl GpuRenderer {
         let default_bg = vte_rgb_to_rgba(params.palette.default_bg());
 
         // 1. Tab bar (always fully opaque — individual tabs carry their own alpha)
-        self.build_tab_bar_instances(&mut bg, &mut fg, params, &tc, ui_collection, &gpu.queue);
+        if !params.focus_mode {
+            self.build_tab_bar_instances(&mut bg, &mut fg, params, &tc, ui_collection, &gpu.queue);
+        }
 
         // Switch to transparent for grid content
         bg.opacity = params.opacity;
 
         // 2. Grid cells (semi-transparent — glass shows through, shaped font)
         self.build_grid_instances(&mut bg, &mut fg, params, collection, &gpu.queue, &default_bg);
+        if params.filter_rows.is_some() {
+            self.build_filter_line_numbers(&mut bg, &mut fg, params, collection, &gpu.queue);
+        } else {
+            render_commands::push_exit_marks(&mut bg, params, collection.cell_height);
+            self.build_duration_labels(&mut fg, params, collection, &gpu.queue);
+            render_scrollbar::push_scrollbar(&mut bg, params, collection.cell_height);
+        }
 
         // 3. Search bar overlay (at bottom of grid, UI font)
         self.build_search_bar_overlay(&mut bg, &mut fg, params, &tc, ui_collection, &gpu.queue);
+        self.build_inspector_overlay(&mut bg, &mut fg, params, &tc, ui_collection, &gpu.queue);
 
         // 4. Window border (opaque, Windows only — Linux WM draws its own)
         bg.opacity = 1.0;
         #[cfg(target_os = "windows")]
         if !params.is_maximized {
-            let border_color = u32_to_rgba(WINDOW_BORDER_COLOR);
+            let border_color = params
+                .chrome
+                .accent
+                .map_or_else(|| u32_to_rgba(WINDOW_BORDER_COLOR), vte_rgb_to_rgba);
             let bw = WINDOW_BORDER_WIDTH as f32 * params.scale;
             bg.push_rect(0.0, 0.0, w, bw, border_color);
             bg.push_rect(0.0, h - bw, w, bw, border_color);
             bg.push_rect(0.0, 0.0, bw, h, border_color);
             bg.push_rect(w - bw, 0.0, bw, h, border_color);
         }
+        render_grid::push_host_border(&mut bg, params);
 
         // 5. Overlay pass (drawn after main bg+fg — dragged tab + dropdown)
         let mut overlay_bg_w =
@@ -493,6 +599,13 @@ impl GpuRenderer {
             ui_collection,
             &gpu.queue,
         );
+        self.build_preedit_overlay(
+            &mut overlay_bg_w,
+            &mut overlay_fg_w,
+            params,
+            collection,
+            &gpu.queue,
+        );
         self.build_context_menu_overlay(
             &mut overlay_bg_w,
             &mut overlay_fg_w,
@@ -500,6 +613,29 @@ impl GpuRenderer {
             ui_collection,
             &gpu.queue,
         );
+        self.build_tab_switcher_overlay(
+            &mut overlay_bg_w,
+            &mut overlay_fg_w,
+            params,
+            ui_collection,
+            &gpu.queue,
+        );
+        self.build_chord_overlay(
+            &mut overlay_bg_w,
+            &mut overlay_fg_w,
+            params,
+            &tc,
+            ui_collection,
+            &gpu.queue,
+        );
+        self.build_command_tooltip(
+            &mut overlay_bg_w,
+            &mut overlay_fg_w,
+            params,
+            &tc,
+            ui_collection,
+            &gpu.queue,
+        );
 
         let bg_bytes = bg.as_bytes();
         let fg_bytes = fg.as_bytes();
@@ -519,33 +655,14 @@ impl GpuRenderer {
         let overlay_fg_bytes = overlay_fg_w.as_bytes();
         let overlay_bg_count = overlay_bg_w.count();
         let overlay_fg_count = overlay_fg_w.count();
-        let has_overlay = !overlay_bg_bytes.is_empty() || !overlay_fg_bytes.is_empty();
-
-        // Reuse GPU buffers from previous frame when capacity suffices.
-        let bg_buffer =
-            reuse_or_create_buffer(&gpu.device, &gpu.queue, old_bg_buf, bg_bytes, "bg_instances");
-        let fg_buffer =
-            reuse_or_create_buffer(&gpu.device, &gpu.queue, old_fg_buf, fg_bytes, "fg_instances");
-
-        let (overlay_bg_buffer, overlay_fg_buffer) = if has_overlay {
-            let bg_buf = reuse_or_create_buffer(
-                &gpu.device,
-                &gpu.queue,
-                old_overlay_bg_buf,
-                overlay_bg_bytes,
-                "overlay_bg",
-            );
-            let fg_buf = reuse_or_create_buffer(
-                &gpu.device,
-                &gpu.queue,
-                old_overlay_fg_buf,
-                overlay_fg_bytes,
-                "overlay_fg",
-            );
-            (Some(bg_buf), Some(fg_buf))
-        } else {
-            (None, None)
-        };
+
+        // Upload into the persistent per-slot buffers (grown only when needed).
+        let buffers = &mut self.instance_buffers;
+        let bg_buffer = buffers.upload(&gpu.device, encoder, Slot::Bg, bg_bytes);
+        let fg_buffer = buffers.upload(&gpu.device, encoder, Slot::Fg, fg_bytes);
+
+        let (overlay_bg_buffer, overlay_fg_buffer) =
+            buffers.upload_overlay(&gpu.device, encoder, overlay_bg_bytes, overlay_fg_bytes);
 
         self.cached_frame = Some(PreparedFrame {
             default_bg,
@@ -636,7 +753,7 @@ impl GpuRenderer {
     /// Submit a simple two-pass frame (bg + fg) to a surface.
     /// Used by `draw_settings_frame` and any other single-shot window rendering.
     pub(super) fn submit_simple_frame(
-        &self,
+        &mut self,
         gpu: &GpuState,
         surface: &wgpu::Surface<'_>,
         config: &wgpu::SurfaceConfiguration,
@@ -651,33 +768,25 @@ impl GpuRenderer {
             return;
         }
 
-        let bg_buffer =
-            reuse_or_create_buffer(&gpu.device, &gpu.queue, None, bg_bytes, "simple_bg");
-        let fg_buffer =
-            reuse_or_create_buffer(&gpu.device, &gpu.queue, None, fg_bytes, "simple_fg");
+        let mut encoder = gpu
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("simple_encoder"),
+            });
+        let buffers = &mut self.instance_buffers;
+        let bg_buffer = buffers.upload(&gpu.device, &mut encoder, Slot::SettingsBg, bg_bytes);
+        let fg_buffer = buffers.upload(&gpu.device, &mut encoder, Slot::SettingsFg, fg_bytes);
 
-        let frame = match surface.get_current_texture() {
-            Ok(f) => f,
-            Err(wgpu::SurfaceError::Lost) => {
-                surface.configure(&gpu.device, config);
-                return;
-            }
-            Err(e) => {
-                crate::log(&format!("surface error: {e}"));
-                return;
-            }
+        let Some(frame) = gpu.acquire_frame(surface, config) else {
+            // Submit the staged uploads anyway so the belt can recall them.
+            self.instance_buffers.submit(&gpu.queue, encoder);
+            return;
         };
 
         let view = frame
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
 
-        let mut encoder = gpu
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("simple_encoder"),
-            });
-
         {
             let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("simple_pass"),
@@ -717,7 +826,7 @@ impl GpuRenderer {
             }
         }
 
-        gpu.queue.submit(std::iter::once(encoder.finish()));
+        self.instance_buffers.submit(&gpu.queue, encoder);
         frame.present();
     }
 
@@ -792,7 +901,7 @@ impl GpuRenderer {
     pub(super) fn push_icon(
         &mut self,
         fg: &mut InstanceWriter,
-        icon: crate::icons::Icon,
+        icon: Icon,
         cx: f32,
         cy: f32,
         size: f32,
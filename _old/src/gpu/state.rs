@@ -53,6 +53,29 @@ impl GpuState {
             .expect("failed to initialize GPU with any backend")
     }
 
+    /// One line per GPU adapter visible to any backend, for diagnostics.
+    /// The first discrete GPU is what a window prefers (surface permitting).
+    pub fn describe_adapters() -> Vec<String> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::all(),
+            ..Default::default()
+        });
+        pollster::block_on(instance.enumerate_adapters(wgpu::Backends::all()))
+            .iter()
+            .map(|a| {
+                let info = a.get_info();
+                let driver = format!("{} {}", info.driver, info.driver_info);
+                format!(
+                    "{} ({:?}, {:?}, driver: {})",
+                    info.name,
+                    info.backend,
+                    info.device_type,
+                    driver.trim()
+                )
+            })
+            .collect()
+    }
+
     fn try_init(window: &Arc<Window>, backends: wgpu::Backends, dcomp: bool) -> Option<Self> {
         let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
             backends,
@@ -292,4 +315,24 @@ impl GpuState {
         surface.configure(&self.device, &config);
         Some((surface, config))
     }
+
+    /// The surface's next texture, reconfiguring a lost surface; `None`
+    /// skips this frame.
+    pub fn acquire_frame(
+        &self,
+        surface: &wgpu::Surface<'_>,
+        config: &wgpu::SurfaceConfiguration,
+    ) -> Option<wgpu::SurfaceTexture> {
+        match surface.get_current_texture() {
+            Ok(frame) => Some(frame),
+            Err(wgpu::SurfaceError::Lost) => {
+                surface.configure(&self.device, config);
+                None
+            }
+            Err(e) => {
+                crate::log(&format!("surface error: {e}"));
+                None
+            }
+        }
+    }
 }
@@ -0,0 +1,84 @@
+use crate::grid::row::CommandStatus;
+use crate::icons::{Icon, IconBitmap};
+
+use super::buffer_pool::grown_capacity;
+use super::builtin_smooth;
+use super::pipeline::INSTANCE_STRIDE;
+use super::render_commands::tooltip_text;
+
+fn alpha(bmp: &IconBitmap, x: u32, y: u32) -> u8 {
+    bmp.data[(y * bmp.width + x) as usize]
+}
+
+#[test]
+fn instance_buffers_grow_in_power_of_two_steps() {
+    // Small frames share the minimum allocation.
+    assert_eq!(grown_capacity(0), 64 * 1024);
+    assert_eq!(grown_capacity(INSTANCE_STRIDE * 10), 64 * 1024);
+    // A frame just past a boundary doubles rather than fitting exactly, so
+    // a few more instances next frame don't reallocate again.
+    assert_eq!(grown_capacity(64 * 1024 + 1), 128 * 1024);
+    assert_eq!(grown_capacity(100_000 * INSTANCE_STRIDE), 8 * 1024 * 1024);
+}
+
+#[test]
+fn caption_icons_cover_whole_pixels_at_every_scale() {
+    // 10 px at 100 %, 150 %, and 200 %: straight strokes never straddle
+    // two pixels, so they stay sharp instead of turning grey.
+    for size in [10, 15, 20] {
+        for icon in [Icon::Minimize, Icon::Maximize, Icon::Restore] {
+            let bmp = icon.rasterize(size);
+            assert!(
+                bmp.data.iter().all(|&a| a == 0 || a == 255),
+                "{icon:?} at {size}px has partial pixels"
+            );
+        }
+    }
+    // Strokes thicken with the scale factor.
+    let thin = Icon::Maximize.rasterize(10);
+    let thick = Icon::Maximize.rasterize(20);
+    assert_eq!((alpha(&thin, 5, 0), alpha(&thin, 5, 1)), (255, 0));
+    assert_eq!((alpha(&thick, 10, 1), alpha(&thick, 10, 2)), (255, 0));
+}
+
+#[test]
+fn penguin_icon_has_a_hollow_belly() {
+    let bmp = Icon::Penguin.rasterize(20);
+    // Solid at the body's side, empty in the middle of the belly and
+    // outside the silhouette.
+    assert_eq!(alpha(&bmp, 4, 13), 255);
+    assert_eq!(alpha(&bmp, 10, 14), 0);
+    assert_eq!(alpha(&bmp, 0, 0), 0);
+}
+
+#[test]
+fn rounded_corner_meets_neighbouring_lines() {
+    // ╭ in an 8×16 cell: the arms leave through the right and bottom edges
+    // where ─ and │ put their lines, and the top-left corner stays empty.
+    let bmp = builtin_smooth::rasterize('\u{256D}', 8, 16).expect("smooth glyph");
+    assert_eq!(alpha(&bmp, 7, 8), 255);
+    assert_eq!(alpha(&bmp, 4, 15), 255);
+    assert_eq!(alpha(&bmp, 0, 0), 0);
+    assert_eq!(alpha(&bmp, 4, 8), 0);
+}
+
+#[test]
+fn powerline_separators_are_anti_aliased() {
+    let bmp = builtin_smooth::rasterize('\u{E0B0}', 8, 16).expect("smooth glyph");
+    assert_eq!(alpha(&bmp, 0, 8), 255);
+    assert_eq!(alpha(&bmp, 7, 0), 0);
+    assert!(bmp.data.iter().any(|&a| a > 0 && a < 255));
+    assert!(builtin_smooth::rasterize('\u{E0A0}', 8, 16).is_none());
+}
+
+#[test]
+fn command_tooltip_shows_exit_code_and_duration() {
+    let status = |exit_code, duration_ms| CommandStatus {
+        exit_code,
+        duration_ms,
+    };
+    assert_eq!(tooltip_text(status(Some(1), Some(3_240))), "exit 1 · 3.2s");
+    assert_eq!(tooltip_text(status(Some(0), Some(850))), "exit 0 · 850ms");
+    assert_eq!(tooltip_text(status(Some(130), Some(245_000))), "exit 130 · 4m 5s");
+    assert_eq!(tooltip_text(status(None, None)), "finished");
+}
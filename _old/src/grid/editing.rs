@@ -7,6 +7,28 @@ use crate::cell::CellFlags;
 use super::Grid;
 
 impl Grid {
+    /// Column of the cell last written on the cursor's row, skipping a
+    /// wide char's spacer to its base cell.
+    ///
+    /// Accounts for `input_needs_wrap`: when set, `cursor.col` already
+    /// points at the last written cell rather than the cell after it.
+    pub fn prev_base_col(&self) -> Option<usize> {
+        let row = self.cursor.row;
+        let col = if self.cursor.input_needs_wrap {
+            self.cursor.col
+        } else {
+            self.cursor.col.checked_sub(1)?
+        };
+        if row >= self.lines || col >= self.cols {
+            return None;
+        }
+        if col > 0 && self.row(row)[col].flags.contains(CellFlags::WIDE_CHAR_SPACER) {
+            Some(col - 1)
+        } else {
+            Some(col)
+        }
+    }
+
     pub fn put_char(&mut self, c: char) {
         if self.cursor.input_needs_wrap {
             self.wrap_cursor();
@@ -41,7 +63,8 @@ impl Grid {
         cell.fg = self.cursor.template.fg;
         cell.bg = self.cursor.template.bg;
         cell.flags = self.cursor.template.flags;
-        cell.extra = None;
+        // Underline color and hyperlink come from the template (Arc clone).
+        cell.extra.clone_from(&self.cursor.template.extra);
 
         if col >= self.viewport[row].occ {
             self.viewport[row].occ = col + 1;
@@ -81,7 +104,7 @@ impl Grid {
         cell.fg = self.cursor.template.fg;
         cell.bg = self.cursor.template.bg;
         cell.flags = self.cursor.template.flags | CellFlags::WIDE_CHAR;
-        cell.extra = None;
+        cell.extra.clone_from(&self.cursor.template.extra);
 
         // Write spacer in next column
         let spacer = &mut self.viewport[row][col + 1];
@@ -104,6 +127,54 @@ impl Grid {
         }
     }
 
+    /// Write `c` as a block `scale` rows tall and `width` columns wide (OSC 66
+    /// text sizing). The char and its scale go in the top-left cell, the rest
+    /// of the top row becomes spacers, and the rows below are cleared. The
+    /// cursor wraps first if the block doesn't fit on the line, and the
+    /// region scrolls up if it doesn't fit below. Blocks larger than the
+    /// grid are dropped.
+    ///
+    /// Returns the block's column; its row is the cursor's.
+    pub fn put_scaled_char(&mut self, c: char, scale: usize, width: usize) -> Option<usize> {
+        if width == 0 || width > self.cols || scale > self.lines {
+            return None;
+        }
+        if self.cursor.input_needs_wrap || self.cursor.col + width > self.cols {
+            self.wrap_cursor();
+        }
+        let overflow = (self.cursor.row + scale)
+            .saturating_sub(self.scroll_bottom + 1)
+            .min(self.cursor.row);
+        if overflow > 0 {
+            self.scroll_up(overflow);
+            self.cursor.row -= overflow;
+        }
+
+        let (row, col) = (self.cursor.row, self.cursor.col);
+        let template = self.cursor.template.clone();
+        for line in row..(row + scale).min(self.lines) {
+            for cell in self.viewport[line].iter_mut().skip(col).take(width) {
+                cell.reset(&template);
+            }
+            self.dirty.mark_row(line);
+        }
+        let top = &mut self.viewport[row];
+        top[col].c = c;
+        top[col].extra.clone_from(&template.extra);
+        top[col].set_text_scale(scale as u8);
+        for spacer in top.iter_mut().skip(col + 1).take(width - 1) {
+            spacer.flags.insert(CellFlags::WIDE_CHAR_SPACER);
+        }
+        top.occ = top.occ.max(col + width);
+
+        self.cursor.col += width;
+        if self.cursor.col >= self.cols {
+            self.cursor.input_needs_wrap = true;
+            self.cursor.col = self.cols - 1;
+        }
+        Some(col)
+    }
+
     pub(super) fn wrap_cursor(&mut self) {
         // Set WRAPLINE flag on current row
         let row = self.cursor.row;
@@ -156,6 +227,9 @@ impl Grid {
                 self.dirty.mark_all();
             }
             ClearMode::Saved => {
+                // Count the cleared rows as evicted so stable indices of
+                // the screen rows don't shift onto other content.
+                self.total_evicted += self.scrollback.len();
                 self.scrollback.clear();
                 self.display_offset = 0;
                 self.dirty.mark_all();
@@ -163,6 +237,25 @@ impl Grid {
         }
     }
 
+    /// Clear the scrollback and the screen except rows `keep_from` through
+    /// the cursor row, which move to the top along with the cursor.
+    ///
+    /// Keeps the prompt being edited while everything before it goes.
+    pub fn clear_history_keeping(&mut self, keep_from: usize) {
+        let keep_from = keep_from.min(self.cursor.row);
+        let bottom = self.lines.saturating_sub(1);
+        for _ in 0..keep_from {
+            self.viewport.remove_insert(0, bottom, self.cols);
+        }
+        self.total_evicted += keep_from;
+        self.cursor.row -= keep_from;
+        let template = &self.cursor.template;
+        for r in (self.cursor.row + 1)..self.lines {
+            self.viewport[r].reset(template);
+        }
+        self.erase_display(ClearMode::Saved);
+    }
+
     #[allow(clippy::needless_pass_by_value, reason = "VTE trait requires consuming enum parameter")]
     pub fn erase_line(&mut self, mode: LineClearMode) {
         let template = &self.cursor.template;
@@ -5,13 +5,19 @@ pub mod dirty;
 mod editing;
 mod navigation;
 mod reflow;
+mod restore;
 pub mod ring;
 pub mod row;
 mod scroll;
+mod serialize;
 pub mod stable_index;
+mod zones;
 
 pub use navigation::WrapDetection;
+pub use reflow::{ClipEdge, EmojiPresentation};
+pub use serialize::TextFormat;
 pub use stable_index::StableRowIndex;
+pub use zones::{Zone, ZoneKind, Zones};
 
 #[cfg(test)]
 mod tests;
@@ -46,6 +52,10 @@ pub struct Grid {
     pub display_offset: usize,
     /// Total number of rows evicted from scrollback (for absolute row tracking).
     total_evicted: usize,
+    /// Rows scrolled up by output since creation (monotonic, for unread counts).
+    scrolled_lines: u64,
+    /// Rows given a prompt mark, oldest first (see [`Grid::prompt_rows`]).
+    prompt_rows: Vec<StableRowIndex>,
     /// Row-level dirty tracking for incremental GPU updates.
     pub dirty: DirtyTracker,
 }
@@ -71,6 +81,8 @@ impl Grid {
             max_scrollback,
             display_offset: 0,
             total_evicted: 0,
+            scrolled_lines: 0,
+            prompt_rows: Vec::new(),
             dirty: DirtyTracker::new(lines),
         }
     }
@@ -119,6 +131,18 @@ impl Grid {
         self.scrollback.len().saturating_sub(self.display_offset) + line
     }
 
+    /// Mutable access to a row by absolute index.
+    pub fn absolute_row_mut(&mut self, abs_row: usize) -> Option<&mut Row> {
+        let sb_len = self.scrollback.len();
+        if abs_row < sb_len {
+            self.scrollback.get_mut(abs_row)
+        } else if abs_row - sb_len < self.lines {
+            Some(&mut self.viewport[abs_row - sb_len])
+        } else {
+            None
+        }
+    }
+
     /// Access a row by absolute index (scrollback row 0 = oldest).
     pub fn absolute_row(&self, abs_row: usize) -> Option<&Row> {
         let sb_len = self.scrollback.len();
@@ -139,6 +163,11 @@ impl Grid {
         self.total_evicted
     }
 
+    /// Total rows scrolled up (full screen or within a region) since creation.
+    pub fn scrolled_lines(&self) -> u64 {
+        self.scrolled_lines
+    }
+
     pub fn clear_all(&mut self) {
         let template = Cell::default();
         for r in 0..self.lines {
@@ -150,6 +179,28 @@ impl Grid {
         self.dirty.mark_all();
     }
 
+    /// DECSTR: reset cursor attributes, margins, and the saved cursor while
+    /// keeping screen contents.
+    pub fn soft_reset(&mut self) {
+        self.cursor.reset_attrs();
+        self.cursor.input_needs_wrap = false;
+        self.saved_cursor = Some(Cursor::default());
+        self.scroll_top = 0;
+        self.scroll_bottom = self.lines.saturating_sub(1);
+    }
+
+    /// RIS: reset the cursor and margins and clear the screen, scrollback,
+    /// and tab stops.
+    pub fn hard_reset(&mut self) {
+        self.cursor = Cursor::default();
+        self.saved_cursor = None;
+        self.scroll_top = 0;
+        self.scroll_bottom = self.lines.saturating_sub(1);
+        self.clear_all();
+        self.erase_display(vte::ansi::ClearMode::Saved);
+        self.tab_stops = Self::build_tab_stops(self.cols);
+    }
+
     pub fn decaln(&mut self) {
         let default = Cell::default();
         for r in 0..self.lines {
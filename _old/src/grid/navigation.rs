@@ -1,11 +1,12 @@
-//! Cursor movement, tab stops, save/restore cursor, scroll region, and logical line detection.
+//! Cursor movement, tab stops, save/restore cursor, scroll region, logical line detection,
+//! and commands located by shell integration marks.
 
 use vte::ansi::TabulationClearMode;
 
 use crate::cell::CellFlags;
 
 use super::Grid;
-use super::row::Row;
+use super::row::{CommandStatus, Row, RowFlags};
 
 /// How to detect that a row continues onto the next row.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -76,6 +77,11 @@ impl Grid {
         }
     }
 
+    /// First row of the scroll region.
+    pub fn scroll_top(&self) -> usize {
+        self.scroll_top
+    }
+
     pub fn set_tab_stop(&mut self) {
         if self.cursor.col < self.cols {
             self.tab_stops[self.cursor.col] = true;
@@ -149,6 +155,55 @@ impl Grid {
         }
         r
     }
+
+    /// Absolute rows `(first, last)` of the output of the most recent
+    /// command, from its OSC 133;C mark to the row before its OSC 133;D (or
+    /// the next prompt). Output of a command still running ends at the
+    /// cursor. `None` without shell integration marks.
+    ///
+    /// Scans back from the cursor, so only the rows of the last command are
+    /// read.
+    pub fn last_output_rows(&self) -> Option<(usize, usize)> {
+        let cursor = self.scrollback.len() + self.cursor.row;
+        let start = (0..=cursor)
+            .rev()
+            .find(|&r| self.row_flags(r).contains(RowFlags::OUTPUT_START))?;
+        if self.row_flags(start).contains(RowFlags::COMMAND_END) {
+            return Some((start, start));
+        }
+        let end = (start + 1..=cursor)
+            .find(|&r| self.row_flags(r).intersects(RowFlags::SEMANTIC))
+            .map_or(cursor, |next| next - 1);
+        Some((start, end))
+    }
+
+    /// Record how the command at the cursor finished (OSC 133;D) on its
+    /// prompt row. The duration runs from the timestamp on its OSC 133;B
+    /// row to `now_ms`. Does nothing before the first prompt mark.
+    pub fn finish_command(&mut self, exit_code: Option<i32>, now_ms: u64) {
+        let cursor = self.scrollback.len() + self.cursor.row;
+        // The nearest prompt at or above the cursor, and the last input
+        // mark after it.
+        let mut input = None;
+        let prompt = (0..=cursor).rev().find(|&r| {
+            let flags = self.row_flags(r);
+            if flags.contains(RowFlags::COMMAND_START) && input.is_none() {
+                input = Some(r);
+            }
+            flags.contains(RowFlags::PROMPT_START)
+        });
+        let Some(prompt) = prompt else {
+            return;
+        };
+        let started = input.and_then(|r| self.absolute_row(r)?.meta.as_ref()?.timestamp_ms);
+        let status = CommandStatus {
+            exit_code,
+            duration_ms: started.map(|t| now_ms.saturating_sub(t)),
+        };
+        if let Some(row) = self.absolute_row_mut(prompt) {
+            row.meta_mut().command = Some(status);
+        }
+    }
 }
 
 /// Check whether a row's content continues onto the next row.
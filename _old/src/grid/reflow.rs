@@ -1,10 +1,37 @@
 //! Grid resize and column reflow (Ghostty-style cell-by-cell rewriting).
 
+use serde::{Deserialize, Serialize};
+
 use crate::cell::{Cell, CellFlags};
 
 use super::Grid;
+use super::StableRowIndex;
 use super::ring::ViewportRing;
-use super::row::Row;
+use super::row::{Row, RowFlags};
+
+/// Which edge of a non-reflowed grid loses rows when it shrinks vertically.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipEdge {
+    /// Drop rows from the top (trailing blank rows are trimmed first), keeping
+    /// the content around the cursor visible.
+    #[default]
+    Top,
+    /// Drop rows from the bottom, keeping the top of the screen anchored.
+    Bottom,
+}
+
+/// How a reflow lays out a narrow cell holding an emoji presentation
+/// sequence (a base followed by VS16, see
+/// [`Cell::is_narrow_emoji_presentation`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmojiPresentation {
+    /// Keep it one cell wide, as the app wrote it.
+    Narrow,
+    /// Widen it to two cells, as mode 2027 (grapheme clusters) lays out new
+    /// output.
+    Wide,
+}
 
 impl Grid {
     /// Resize the grid to new dimensions.
@@ -13,6 +40,32 @@ impl Grid {
     /// column width (Ghostty-style cell-by-cell rewriting). When false, rows
     /// are simply truncated or extended (for alt screen).
     pub fn resize(&mut self, new_cols: usize, new_lines: usize, reflow: bool) {
+        self.resize_carrying(new_cols, new_lines, reflow, EmojiPresentation::Narrow, &mut []);
+    }
+
+    /// Resize with reflow, moving each `(row, column)` in `points` along
+    /// with the cell it sits on, as the cursor is. Used to keep a
+    /// selection on its text. A point on a row already evicted from
+    /// scrollback lands on the oldest row.
+    pub fn reflow_with_points(
+        &mut self,
+        new_cols: usize,
+        new_lines: usize,
+        emoji: EmojiPresentation,
+        points: &mut [(StableRowIndex, usize)],
+    ) {
+        self.resize_carrying(new_cols, new_lines, true, emoji, points);
+    }
+
+    /// [`Grid::resize`], with `points` carried through the reflow.
+    fn resize_carrying(
+        &mut self,
+        new_cols: usize,
+        new_lines: usize,
+        reflow: bool,
+        emoji: EmojiPresentation,
+        points: &mut [(StableRowIndex, usize)],
+    ) {
         if new_cols == 0 || new_lines == 0 {
             return;
         }
@@ -23,14 +76,14 @@ impl Grid {
         if reflow && new_cols != self.cols {
             if new_cols > self.cols {
                 // Growing cols: reflow first (unwrap), then adjust rows
-                self.reflow_cols(new_cols);
+                self.reflow_cols(new_cols, emoji, points);
                 self.cols = new_cols;
                 self.tab_stops = Self::build_tab_stops(new_cols);
                 self.resize_rows(new_lines);
             } else {
                 // Shrinking cols: adjust rows first, then reflow (wrap)
                 self.resize_rows(new_lines);
-                self.reflow_cols(new_cols);
+                self.reflow_cols(new_cols, emoji, points);
                 self.cols = new_cols;
                 self.tab_stops = Self::build_tab_stops(new_cols);
             }
@@ -49,7 +102,30 @@ impl Grid {
         }
 
         self.lines = new_lines;
+        self.finish_resize();
+    }
+
+    /// Resize without reflow, clipping rows from the given edge on shrink.
+    ///
+    /// Used for the alt screen, where full-screen apps repaint themselves and
+    /// only the transient layout during the resize gesture is affected.
+    pub fn resize_clipped(&mut self, new_cols: usize, new_lines: usize, clip: ClipEdge) {
+        if clip == ClipEdge::Bottom && new_lines > 0 && new_lines < self.lines {
+            let mut rows = self.viewport.drain_logical();
+            rows.truncate(new_lines);
+            self.viewport = ViewportRing::from_vec(rows);
+            self.lines = new_lines;
+            self.dirty.resize(new_lines);
+            if new_cols == self.cols {
+                self.finish_resize();
+                return;
+            }
+        }
+        self.resize(new_cols, new_lines, false);
+    }
 
+    /// Reset scroll region and clamp cursor/display state after a resize.
+    fn finish_resize(&mut self) {
         // Reset scroll region
         self.scroll_top = 0;
         self.scroll_bottom = self.lines.saturating_sub(1);
@@ -64,6 +140,9 @@ impl Grid {
 
         // Resize dirty tracker to match new viewport dimensions.
         self.dirty.resize(self.lines);
+
+        // Reflow moves marked rows.
+        self.rebuild_prompt_rows();
     }
 
     pub(super) fn resize_rows(&mut self, new_lines: usize) {
@@ -88,6 +167,7 @@ impl Grid {
                     let row = rows.remove(0);
                     if self.scrollback.len() >= self.max_scrollback {
                         self.scrollback.pop_front();
+                        self.total_evicted += 1;
                     }
                     self.scrollback.push_back(row);
                     self.cursor.row = self.cursor.row.saturating_sub(1);
@@ -139,9 +219,15 @@ impl Grid {
     /// Unified function that handles both growing (unwrapping) and shrinking
     /// (wrapping) columns, inspired by Ghostty's reflow approach. Iterates all
     /// cells from all rows (scrollback + visible) and writes them into new
-    /// output rows at the target width.
+    /// output rows at the target width. The cursor and `points` move with
+    /// the cells they sit on.
     #[allow(clippy::else_if_without_else, reason = "Wrapped row conditional, no else needed")]
-    fn reflow_cols(&mut self, new_cols: usize) {
+    fn reflow_cols(
+        &mut self,
+        new_cols: usize,
+        emoji: EmojiPresentation,
+        points: &mut [(StableRowIndex, usize)],
+    ) {
         let old_cols = self.cols;
         if old_cols == new_cols || new_cols == 0 {
             return;
@@ -153,20 +239,24 @@ impl Grid {
         let visible_rows = self.viewport.drain_logical();
         all_rows.extend(visible_rows);
 
-        // Cursor position in the unified list
-        let cursor_abs = visible_start + self.cursor.row;
-        let cursor_col = self.cursor.col;
-        let mut new_cursor_abs = 0usize;
-        let mut new_cursor_col = 0usize;
+        // Cursor, then each point, as positions in the unified list. `dst`
+        // collects where each one lands.
+        let evicted = self.total_evicted as u64;
+        let cursor = (visible_start + self.cursor.row, self.cursor.col);
+        let src: Vec<(usize, usize)> = std::iter::once(cursor)
+            .chain(points.iter().map(|&(row, col)| (row.0.saturating_sub(evicted) as usize, col)))
+            .collect();
+        let mut dst = vec![(0usize, 0usize); src.len()];
 
         let mut result: Vec<Row> = Vec::with_capacity(all_rows.len());
         let mut out_row = Row::new(new_cols);
         let mut out_col = 0usize;
 
         for (src_idx, src_row) in all_rows.iter().enumerate() {
-            // Transfer prompt_start flag to the current output row.
-            if src_row.prompt_start {
-                out_row.prompt_start = true;
+            // Shell integration marks and their metadata follow the text.
+            out_row.flags |= src_row.flags & RowFlags::SEMANTIC;
+            if let Some(meta) = &src_row.meta {
+                out_row.meta = Some(meta.clone());
             }
 
             // A row is wrapped if WRAPLINE is set at the old column boundary
@@ -188,22 +278,23 @@ impl Grid {
 
                 // Skip generated spacer cells (will be regenerated)
                 if cell.flags.contains(CellFlags::WIDE_CHAR_SPACER) {
-                    if src_idx == cursor_abs && src_col == cursor_col {
-                        new_cursor_abs = result.len();
-                        new_cursor_col = out_col.saturating_sub(1);
-                    }
+                    let to = (result.len(), out_col.saturating_sub(1));
+                    carry(&src, &mut dst, |p| p == (src_idx, src_col), to);
                     continue;
                 }
                 if cell.flags.contains(CellFlags::LEADING_WIDE_CHAR_SPACER) {
-                    if src_idx == cursor_abs && src_col == cursor_col {
-                        new_cursor_abs = result.len();
-                        new_cursor_col = out_col.min(new_cols.saturating_sub(1));
-                    }
+                    let to = (result.len(), out_col.min(new_cols.saturating_sub(1)));
+                    carry(&src, &mut dst, |p| p == (src_idx, src_col), to);
                     continue;
                 }
 
-                // Treat wide chars as narrow if grid too narrow (new_cols < 2)
-                let is_wide = cell.flags.contains(CellFlags::WIDE_CHAR) && new_cols >= 2;
+                // Narrow VS16 emoji (written with mode 2027 off) are laid out
+                // wide once the mode is on. Treat wide chars as narrow if grid
+                // too narrow (new_cols < 2).
+                let is_wide = (cell.flags.contains(CellFlags::WIDE_CHAR)
+                    || (emoji == EmojiPresentation::Wide
+                        && cell.is_narrow_emoji_presentation()))
+                    && new_cols >= 2;
                 let cell_width = if is_wide { 2 } else { 1 };
 
                 // Wrap to next output row if cell doesn't fit
@@ -222,16 +313,15 @@ impl Grid {
                     out_col = 0;
                 }
 
-                // Track cursor position
-                if src_idx == cursor_abs && src_col == cursor_col {
-                    new_cursor_abs = result.len();
-                    new_cursor_col = out_col;
-                }
+                // Track cursor and points
+                carry(&src, &mut dst, |p| p == (src_idx, src_col), (result.len(), out_col));
 
                 // Write cell (strip old WRAPLINE flag)
                 let mut new_cell = cell.clone();
                 new_cell.flags.remove(CellFlags::WRAPLINE);
-                if !is_wide && cell.flags.contains(CellFlags::WIDE_CHAR) {
+                if is_wide {
+                    new_cell.flags.insert(CellFlags::WIDE_CHAR);
+                } else if cell.flags.contains(CellFlags::WIDE_CHAR) {
                     // Wide char forced narrow due to new_cols < 2
                     new_cell.flags.remove(CellFlags::WIDE_CHAR);
                 }
@@ -251,20 +341,22 @@ impl Grid {
                 out_row.occ = out_col;
             }
 
-            // End of source row
-            if !wrapped {
-                // Non-wrapped: finalize output row
-                if src_idx == cursor_abs && cursor_col >= content_len {
-                    new_cursor_abs = result.len();
-                    new_cursor_col = cursor_col.min(new_cols.saturating_sub(1));
+            // End of source row: positions past the content
+            let past_content = |(row, col): (usize, usize)| row == src_idx && col >= content_len;
+            if wrapped {
+                // Wrapped row: continue where the content stops
+                let to = (result.len(), out_col.min(new_cols.saturating_sub(1)));
+                carry(&src, &mut dst, past_content, to);
+            } else {
+                // Non-wrapped: keep the column, then finalize output row
+                for (&from, landed) in src.iter().zip(dst.iter_mut()) {
+                    if past_content(from) {
+                        *landed = (result.len(), from.1.min(new_cols.saturating_sub(1)));
+                    }
                 }
                 result.push(out_row);
                 out_row = Row::new(new_cols);
                 out_col = 0;
-            } else if src_idx == cursor_abs && cursor_col >= content_len {
-                // Wrapped row with cursor past content
-                new_cursor_abs = result.len();
-                new_cursor_col = out_col.min(new_cols.saturating_sub(1));
             }
         }
 
@@ -300,7 +392,11 @@ impl Grid {
 
         self.viewport = ViewportRing::from_vec(result);
 
-        // Update cursor
+        // Update cursor and points
+        for (point, &(row, col)) in points.iter_mut().zip(&dst[1..]) {
+            *point = (StableRowIndex(evicted + row as u64), col);
+        }
+        let (new_cursor_abs, new_cursor_col) = dst[0];
         let sb_len = self.scrollback.len();
         self.cursor.row = if new_cursor_abs >= sb_len {
             (new_cursor_abs - sb_len).min(self.lines.saturating_sub(1))
@@ -332,3 +428,17 @@ impl Grid {
         count
     }
 }
+
+/// Move every position in `src` that `hit` matches to `to` in `dst`.
+fn carry(
+    src: &[(usize, usize)],
+    dst: &mut [(usize, usize)],
+    hit: impl Fn((usize, usize)) -> bool,
+    to: (usize, usize),
+) {
+    for (&from, landed) in src.iter().zip(dst.iter_mut()) {
+        if hit(from) {
+            *landed = to;
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Writing text from [`Grid::serialize_rows`] back into the grid.
+//!
+//! This reads only what serialization produces: characters, newlines, SGR
+//! escapes, and the APC strings holding line attributes. Other escapes and
+//! control characters are skipped, so saved text can't move the cursor,
+//! change modes, or send replies.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use unicode_width::UnicodeWidthChar;
+use vte::ansi::{Color, NamedColor, Rgb};
+
+use crate::cell::{Cell, CellFlags};
+
+use super::Grid;
+use super::row::{RowFlags, RowMeta};
+use super::serialize::FLAG_CODES;
+
+/// A row's line attributes, as [`oriterm_core::decode_attrs`] reads them.
+type RowAttrs = (RowFlags, Option<RowMeta>);
+
+/// The 16 named colors in SGR order (30–37, then 90–97).
+const NAMED: [NamedColor; 16] = [
+    NamedColor::Black,
+    NamedColor::Red,
+    NamedColor::Green,
+    NamedColor::Yellow,
+    NamedColor::Blue,
+    NamedColor::Magenta,
+    NamedColor::Cyan,
+    NamedColor::White,
+    NamedColor::BrightBlack,
+    NamedColor::BrightRed,
+    NamedColor::BrightGreen,
+    NamedColor::BrightYellow,
+    NamedColor::BrightBlue,
+    NamedColor::BrightMagenta,
+    NamedColor::BrightCyan,
+    NamedColor::BrightWhite,
+];
+
+impl Grid {
+    /// Write `text` from [`Grid::serialize_rows`] at the cursor, starting a
+    /// new line at each `\n`. Line attributes go to the row that the next
+    /// character lands on. The cursor's attribute template is left as it
+    /// was.
+    pub fn restore_rows(&mut self, text: &str) {
+        let saved = self.cursor.template.clone();
+        let mut pending = None;
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\n' => {
+                    self.set_row_attrs(pending.take());
+                    self.carriage_return();
+                    self.linefeed();
+                }
+                '\x1b' => pending = self.restore_escape(&mut chars).or(pending),
+                c if c.is_control() => {}
+                c => {
+                    self.restore_char(c);
+                    self.set_row_attrs(pending.take());
+                }
+            }
+        }
+        self.set_row_attrs(pending);
+        self.cursor.template = saved;
+    }
+
+    /// Give the cursor's row `attrs`, if there are any.
+    fn set_row_attrs(&mut self, attrs: Option<RowAttrs>) {
+        if let Some((flags, meta)) = attrs {
+            let row = self.cursor.row;
+            let row = self.row_mut(row);
+            row.flags = flags;
+            row.meta = meta.map(Box::new);
+        }
+    }
+
+    fn restore_char(&mut self, c: char) {
+        match c.width() {
+            Some(2) => self.put_wide_char(c),
+            Some(0) => {
+                if let Some(col) = self.prev_base_col() {
+                    let row = self.cursor.row;
+                    self.row_mut(row)[col].push_zerowidth(c);
+                }
+            }
+            _ => self.put_char(c),
+        }
+    }
+
+    /// Apply an SGR escape from `chars`, which start after the ESC, or
+    /// return the line attributes in an APC string. Other CSI sequences and
+    /// OSC strings are consumed and ignored, as is the char after any other
+    /// ESC.
+    fn restore_escape(&mut self, chars: &mut std::str::Chars<'_>) -> Option<RowAttrs> {
+        match chars.next() {
+            Some('[') => {}
+            Some('_') => return row_attrs(chars),
+            Some(']') => {
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if c == '\x07' || (prev == '\x1b' && c == '\\') {
+                        return None;
+                    }
+                    prev = c;
+                }
+                return None;
+            }
+            _ => return None,
+        }
+        let mut params = String::new();
+        for c in chars.by_ref() {
+            if ('\x40'..='\x7e').contains(&c) {
+                if c == 'm' {
+                    apply_sgr(&mut self.cursor.template, &params);
+                }
+                return None;
+            }
+            params.push(c);
+        }
+        None
+    }
+}
+
+/// Line attributes from an APC string in `chars`, which start after the
+/// `ESC _`. The string is consumed up to its `ESC \` either way.
+fn row_attrs(chars: &mut std::str::Chars<'_>) -> Option<RowAttrs> {
+    let mut body = String::new();
+    for c in chars.by_ref() {
+        if c == '\x1b' {
+            chars.next();
+            break;
+        }
+        body.push(c);
+    }
+    let bytes = BASE64.decode(body).ok()?;
+    let (flags, meta, _) = oriterm_core::decode_attrs(&bytes)?;
+    Some((flags, meta))
+}
+
+/// Set `pen`'s attributes from SGR `params`, as [`Grid::serialize_rows`]
+/// writes them.
+fn apply_sgr(pen: &mut Cell, params: &str) {
+    let mut codes = params.split(';');
+    while let Some(code) = codes.next() {
+        if let Some(&(flag, _)) = FLAG_CODES.iter().find(|(_, c)| *c == code) {
+            pen.flags.insert(flag);
+            continue;
+        }
+        let Ok(n) = code.parse::<usize>() else {
+            if code.is_empty() {
+                reset(pen);
+            }
+            continue;
+        };
+        match n {
+            0 => reset(pen),
+            53 => pen.flags.insert(CellFlags::OVERLINE),
+            30..=37 => pen.fg = Color::Named(NAMED[n - 30]),
+            90..=97 => pen.fg = Color::Named(NAMED[n - 82]),
+            40..=47 => pen.bg = Color::Named(NAMED[n - 40]),
+            100..=107 => pen.bg = Color::Named(NAMED[n - 92]),
+            38 => pen.fg = extended_color(&mut codes).unwrap_or(pen.fg),
+            48 => pen.bg = extended_color(&mut codes).unwrap_or(pen.bg),
+            58 => {
+                if let Some(color) = extended_color(&mut codes) {
+                    pen.set_underline_color(Some(color));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Reset `pen` to default colors and no attributes.
+fn reset(pen: &mut Cell) {
+    let blank = Cell::default();
+    pen.fg = blank.fg;
+    pen.bg = blank.bg;
+    pen.flags = blank.flags;
+    pen.set_underline_color(None);
+}
+
+/// The color after a 38, 48, or 58: `5;<index>` or `2;<r>;<g>;<b>`.
+fn extended_color<'a>(codes: &mut impl Iterator<Item = &'a str>) -> Option<Color> {
+    let mut next = || codes.next().and_then(|c| c.parse::<u8>().ok());
+    match next()? {
+        5 => next().map(Color::Indexed),
+        2 => Some(Color::Spec(Rgb {
+            r: next()?,
+            g: next()?,
+            b: next()?,
+        })),
+        _ => None,
+    }
+}
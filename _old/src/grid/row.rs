@@ -1,15 +1,22 @@
 //! A single row in the terminal grid, storing cells and metadata.
+//!
+//! Line attributes are `oriterm_core`'s: a one-byte [`RowFlags`] word on
+//! every row, and a boxed [`RowMeta`] side table for the rare ones.
 
 use std::ops::{Index, IndexMut};
 
 use crate::cell::{Cell, CellFlags};
 
+pub use oriterm_core::{CommandStatus, RowFlags, RowMeta};
+
 #[derive(Debug, Clone)]
 pub struct Row {
     inner: Vec<Cell>,
     pub(super) occ: usize,
-    /// True if this row is the start of a shell prompt (OSC 133;A).
-    pub prompt_start: bool,
+    /// Line-level flags (prompt marks, double-width/height).
+    pub flags: RowFlags,
+    /// Rare attributes; `None` for almost every row.
+    pub meta: Option<Box<RowMeta>>,
 }
 
 impl Row {
@@ -18,10 +25,16 @@ impl Row {
         Self {
             inner: vec![Cell::default(); cols],
             occ: 0,
-            prompt_start: false,
+            flags: RowFlags::empty(),
+            meta: None,
         }
     }
 
+    /// Mutable rare attributes, allocating the side table on first use.
+    pub fn meta_mut(&mut self) -> &mut RowMeta {
+        self.meta.get_or_insert_default()
+    }
+
     /// Returns the number of columns in this row.
     pub fn len(&self) -> usize {
         self.inner.len()
@@ -81,6 +94,13 @@ impl Row {
         self.occ = self.content_len();
     }
 
+    /// Columns up to and including the last one written to, typed spaces
+    /// included. Unlike [`Row::content_len`] this keeps trailing whitespace
+    /// the program printed.
+    pub fn occupied_len(&self) -> usize {
+        self.occ.min(self.inner.len())
+    }
+
     /// Returns the rightmost non-blank column index + 1 (content length).
     /// A blank cell is one with char ' ' or '\0' and no flags of interest.
     pub fn content_len(&self) -> usize {
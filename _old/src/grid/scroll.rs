@@ -52,6 +52,7 @@ impl Grid {
             return;
         }
         let count = count.min(bottom - top + 1);
+        self.scrolled_lines += count as u64;
 
         // Full-screen scroll with top == 0: use ring rotation (O(1) per row).
         if top == 0 && bottom == self.lines.saturating_sub(1) {
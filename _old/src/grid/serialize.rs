@@ -0,0 +1,209 @@
+//! Grid rows as text, optionally with SGR escapes for their attributes.
+//!
+//! Soft-wrapped rows join the next row without a newline. Each line ends
+//! with an SGR reset when it changed attributes, so lines can be used on
+//! their own. The saved form also starts each row that has line attributes
+//! with an APC string (`ESC _ … ESC \`) holding them, base64-encoded.
+
+use std::fmt::Write as _;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use vte::ansi::{Color, NamedColor};
+
+use crate::cell::{Cell, CellFlags};
+
+use super::Grid;
+use super::row::Row;
+
+/// How [`Grid::serialize_rows`] writes cell attributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextFormat {
+    /// Characters only.
+    Plain,
+    /// Characters with SGR escapes for colors and attributes.
+    Ansi,
+    /// [`Self::Ansi`] plus each row's line attributes, as saved with the
+    /// session for [`Grid::restore_rows`].
+    Saved,
+}
+
+/// Flags that SGR can express; the rest describe layout.
+const STYLE_FLAGS: CellFlags = CellFlags::BOLD
+    .union(CellFlags::DIM)
+    .union(CellFlags::ITALIC)
+    .union(CellFlags::ANY_UNDERLINE)
+    .union(CellFlags::ANY_BLINK)
+    .union(CellFlags::INVERSE)
+    .union(CellFlags::HIDDEN)
+    .union(CellFlags::STRIKEOUT)
+    .union(CellFlags::OVERLINE);
+
+/// SGR codes for each style flag, in output order.
+pub(super) const FLAG_CODES: [(CellFlags, &str); 13] = [
+    (CellFlags::BOLD, "1"),
+    (CellFlags::DIM, "2"),
+    (CellFlags::ITALIC, "3"),
+    (CellFlags::UNDERLINE, "4"),
+    (CellFlags::DOUBLE_UNDERLINE, "4:2"),
+    (CellFlags::UNDERCURL, "4:3"),
+    (CellFlags::DOTTED_UNDERLINE, "4:4"),
+    (CellFlags::DASHED_UNDERLINE, "4:5"),
+    (CellFlags::BLINK, "5"),
+    (CellFlags::BLINK_FAST, "6"),
+    (CellFlags::INVERSE, "7"),
+    (CellFlags::HIDDEN, "8"),
+    (CellFlags::STRIKEOUT, "9"),
+];
+
+/// The attributes SGR sets for a run of cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Pen {
+    fg: Color,
+    bg: Color,
+    flags: CellFlags,
+    underline: Option<Color>,
+}
+
+impl Default for Pen {
+    fn default() -> Self {
+        Self::of(&Cell::default())
+    }
+}
+
+impl Pen {
+    fn of(cell: &Cell) -> Self {
+        Self {
+            fg: cell.fg,
+            bg: cell.bg,
+            flags: cell.flags & STYLE_FLAGS,
+            underline: cell.underline_color(),
+        }
+    }
+
+    /// Write the SGR sequence that sets this pen from a reset state.
+    fn write(self, out: &mut String) {
+        out.push_str("\x1b[0");
+        for (flag, code) in FLAG_CODES {
+            if self.flags.contains(flag) {
+                out.push(';');
+                out.push_str(code);
+            }
+        }
+        if self.flags.contains(CellFlags::OVERLINE) {
+            out.push_str(";53");
+        }
+        push_color(out, self.fg, 30);
+        push_color(out, self.bg, 40);
+        if let Some(color) = self.underline {
+            push_color(out, color, 50);
+        }
+        out.push('m');
+    }
+}
+
+/// Append `;<code>` for `color` as a foreground (`base` 30), background
+/// (40), or underline (50) color. Default colors add nothing.
+fn push_color(out: &mut String, color: Color, base: usize) {
+    match color {
+        Color::Named(named) => {
+            let index = named as usize;
+            if index >= 16 {
+                return;
+            }
+            if base == 50 {
+                let _ = write!(out, ";58;5;{index}");
+            } else if index < 8 {
+                let _ = write!(out, ";{}", base + index);
+            } else {
+                let _ = write!(out, ";{}", base + 60 + index - 8);
+            }
+        }
+        Color::Indexed(index) => {
+            let _ = write!(out, ";{};5;{index}", base + 8);
+        }
+        Color::Spec(rgb) => {
+            let _ = write!(out, ";{};2;{};{};{}", base + 8, rgb.r, rgb.g, rgb.b);
+        }
+    }
+}
+
+/// Cells of `row` worth writing: up to the last visible character, or in
+/// ANSI up to the last cell whose background or attributes show.
+fn line_end(row: &Row, format: TextFormat) -> usize {
+    let default_bg = Color::Named(NamedColor::Background);
+    let shows = |cell: &Cell| {
+        format != TextFormat::Plain
+            && (cell.bg != default_bg || cell.flags.intersects(CellFlags::INVERSE))
+    };
+    let content = row.content_len();
+    row.as_slice()
+        .iter()
+        .rposition(shows)
+        .map_or(content, |i| content.max(i + 1))
+}
+
+impl Grid {
+    /// The text of absolute rows `first..=last`, stopping at the last row
+    /// that exists.
+    pub fn serialize_rows(&self, first: usize, last: usize, format: TextFormat) -> String {
+        let mut out = String::new();
+        for abs_row in first..=last {
+            let Some(row) = self.absolute_row(abs_row) else {
+                break;
+            };
+            let wrapped = row
+                .as_slice()
+                .last()
+                .is_some_and(|c| c.flags.contains(CellFlags::WRAPLINE));
+            let end = if wrapped {
+                row.len()
+            } else {
+                line_end(row, format)
+            };
+            if format == TextFormat::Saved {
+                push_row_attrs(row, &mut out);
+            }
+            serialize_cells(&row.as_slice()[..end], format, &mut out);
+            if !wrapped && abs_row < last {
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+/// Append `row`'s line attributes as an APC string, if it has any.
+fn push_row_attrs(row: &Row, out: &mut String) {
+    if row.flags.is_empty() && row.meta.is_none() {
+        return;
+    }
+    let mut bytes = Vec::new();
+    oriterm_core::encode_attrs(row.flags, row.meta.as_deref(), &mut bytes);
+    out.push_str("\x1b_");
+    BASE64.encode_string(bytes, out);
+    out.push_str("\x1b\\");
+}
+
+/// Append `cells`, switching the pen where attributes change and resetting
+/// it at the end.
+fn serialize_cells(cells: &[Cell], format: TextFormat, out: &mut String) {
+    let mut pen = Pen::default();
+    for cell in cells {
+        if cell
+            .flags
+            .intersects(CellFlags::WIDE_CHAR_SPACER | CellFlags::LEADING_WIDE_CHAR_SPACER)
+        {
+            continue;
+        }
+        if format != TextFormat::Plain && Pen::of(cell) != pen {
+            pen = Pen::of(cell);
+            pen.write(out);
+        }
+        out.push(if cell.c == '\0' { ' ' } else { cell.c });
+        out.extend(cell.zerowidth());
+    }
+    if pen != Pen::default() {
+        out.push_str("\x1b[0m");
+    }
+}
@@ -147,6 +147,37 @@ fn resize_shrink_rows() {
     assert!(g.cursor.row < 3);
 }
 
+fn lettered_rows(g: &mut Grid) {
+    for row in 0..g.lines {
+        g.cursor.row = row;
+        g.cursor.col = 0;
+        g.put_char(char::from(b'A' + row as u8));
+    }
+}
+
+#[test]
+fn resize_clipped_top_keeps_bottom_rows() {
+    let mut g = Grid::with_max_scrollback(10, 5, 0);
+    lettered_rows(&mut g);
+    g.resize_clipped(10, 3, ClipEdge::Top);
+    assert_eq!(g.lines, 3);
+    assert_eq!(g.row(0)[0].c, 'C');
+    assert_eq!(g.row(2)[0].c, 'E');
+}
+
+#[test]
+fn resize_clipped_bottom_keeps_top_rows() {
+    let mut g = Grid::with_max_scrollback(10, 5, 0);
+    lettered_rows(&mut g);
+    g.resize_clipped(8, 3, ClipEdge::Bottom);
+    assert_eq!(g.lines, 3);
+    assert_eq!(g.cols, 8);
+    assert_eq!(g.row(0)[0].c, 'A');
+    assert_eq!(g.row(2)[0].c, 'C');
+    assert_eq!(g.cursor.row, 2);
+    assert!(g.scrollback.is_empty());
+}
+
 #[test]
 fn insert_blank_chars() {
     let mut g = Grid::new(10, 1);
@@ -199,6 +230,43 @@ fn reflow_shrink_wraps_long_line() {
     assert_eq!(g.row(0)[1].c, 'D');
 }
 
+/// "❤️xa️" written narrow (mode 2027 off): the heart overlaps the `x`.
+fn narrow_vs16_row() -> Grid {
+    let mut g = Grid::new(10, 3);
+    g.put_char('❤');
+    g.row_mut(0)[0].push_zerowidth('\u{FE0F}');
+    g.put_char('x');
+    // A text-presentation base with VS16 stays narrow.
+    g.put_char('a');
+    g.row_mut(0)[2].push_zerowidth('\u{FE0F}');
+    g
+}
+
+#[test]
+fn reflow_keeps_narrow_vs16_emoji_without_mode_2027() {
+    let mut g = narrow_vs16_row();
+    g.reflow_with_points(8, 3, EmojiPresentation::Narrow, &mut []);
+    let row = g.row(0);
+    assert_eq!(row[0].c, '❤');
+    assert!(!row[0].flags.contains(CellFlags::WIDE_CHAR));
+    assert_eq!(row[1].c, 'x');
+    assert_eq!(row[2].c, 'a');
+}
+
+#[test]
+fn reflow_widens_narrow_vs16_emoji() {
+    let mut g = narrow_vs16_row();
+    g.reflow_with_points(8, 3, EmojiPresentation::Wide, &mut []);
+    let row = g.row(0);
+    assert_eq!(row[0].c, '❤');
+    assert!(row[0].flags.contains(CellFlags::WIDE_CHAR));
+    assert_eq!(row[0].zerowidth(), &['\u{FE0F}']);
+    assert!(row[1].flags.contains(CellFlags::WIDE_CHAR_SPACER));
+    assert_eq!(row[2].c, 'x');
+    assert_eq!(row[3].c, 'a');
+    assert!(!row[3].flags.contains(CellFlags::WIDE_CHAR));
+}
+
 #[test]
 fn reflow_grow_unwraps_line() {
     // Create a wrapped line by writing "ABCDEFGH" in 5 cols, then grow to 10
@@ -257,6 +325,26 @@ fn reflow_shrink_preserves_cursor() {
     assert_eq!(g.cursor.col, 2);
 }
 
+#[test]
+fn reflow_carries_points_with_their_cells() {
+    let mut g = Grid::new(10, 3);
+    for c in "ABCDEFGHIJ".chars() {
+        g.put_char(c);
+    }
+    // 'C' and 'H', plus a point past the content of the blank row below.
+    let mut points = [(StableRowIndex(0), 2), (StableRowIndex(0), 7), (StableRowIndex(1), 4)];
+
+    g.reflow_with_points(5, 3, EmojiPresentation::Narrow, &mut points);
+    // "ABCDE" + "FGHIJ" take a row more: row 0 goes to scrollback.
+    assert_eq!(g.scrollback.len(), 1);
+    assert_eq!(points, [(StableRowIndex(0), 2), (StableRowIndex(1), 2), (StableRowIndex(2), 4)]);
+    assert_eq!(g.scrollback[0][2].c, 'C');
+    assert_eq!(g.row(0)[2].c, 'H');
+
+    g.reflow_with_points(10, 3, EmojiPresentation::Narrow, &mut points);
+    assert_eq!(points, [(StableRowIndex(0), 2), (StableRowIndex(0), 7), (StableRowIndex(1), 4)]);
+}
+
 #[test]
 fn reflow_shrink_overflow_to_scrollback() {
     // When shrink creates more rows than visible, extra goes to scrollback
@@ -335,3 +423,210 @@ fn zerowidth_on_wide_char() {
     assert_eq!(g.row(0)[0].zerowidth(), &['\u{0301}']);
     assert!(g.row(0)[1].zerowidth().is_empty());
 }
+
+#[test]
+fn scrolled_lines_counts_output_scrolls() {
+    let mut grid = Grid::new(10, 3);
+    grid.linefeed();
+    grid.linefeed();
+    assert_eq!(grid.scrolled_lines(), 0);
+    grid.linefeed();
+    grid.linefeed();
+    assert_eq!(grid.scrolled_lines(), 2);
+    grid.scroll_up(3);
+    assert_eq!(grid.scrolled_lines(), 5);
+}
+
+#[test]
+fn clear_history_keeping_moves_the_prompt_to_the_top() {
+    let mut g = Grid::new(10, 4);
+    for (i, line) in ["a", "b", "c", "d", "$ ls", "> x"].iter().enumerate() {
+        if i > 0 {
+            g.newline();
+            g.carriage_return();
+        }
+        for c in line.chars() {
+            g.put_char(c);
+        }
+    }
+    assert_eq!(g.scrollback.len(), 2);
+    let prompt = StableRowIndex::from_absolute(&g, g.scrollback.len() + 2);
+
+    g.clear_history_keeping(2);
+    assert!(g.scrollback.is_empty());
+    assert_eq!(g.row(0)[0].c, '$');
+    assert_eq!(g.row(1)[0].c, '>');
+    assert_eq!(g.row(2)[0].c, ' ');
+    assert_eq!((g.cursor.row, g.cursor.col), (1, 3));
+    assert_eq!(StableRowIndex::from_absolute(&g, 0), prompt);
+}
+
+#[test]
+fn serialize_rows_writes_sgr_for_attributes() {
+    let mut grid = Grid::new(10, 2);
+    grid.cursor.template.flags = CellFlags::BOLD;
+    grid.cursor.template.fg = vte::ansi::Color::Indexed(196);
+    grid.put_char('a');
+    grid.cursor.template = Cell::default();
+    grid.put_char('b');
+
+    assert_eq!(grid.serialize_rows(0, 0, TextFormat::Plain), "ab");
+    assert_eq!(
+        grid.serialize_rows(0, 1, TextFormat::Ansi),
+        "\x1b[0;1;38;5;196ma\x1b[0mb\n"
+    );
+}
+
+#[test]
+fn restore_rows_reads_back_serialized_rows() {
+    let mut grid = Grid::new(10, 3);
+    grid.cursor.template.flags = CellFlags::BOLD | CellFlags::UNDERCURL;
+    grid.cursor.template.fg = vte::ansi::Color::Indexed(196);
+    grid.cursor.template.bg = vte::ansi::Color::Named(vte::ansi::NamedColor::BrightBlue);
+    grid.put_char('a');
+    grid.cursor.template = Cell::default();
+    grid.put_wide_char('好');
+    grid.put_char('e');
+    grid.row_mut(0)[3].push_zerowidth('\u{301}');
+    let text = grid.serialize_rows(0, 0, TextFormat::Ansi);
+
+    let mut restored = Grid::new(10, 3);
+    restored.restore_rows(&text);
+    assert_eq!(restored.serialize_rows(0, 0, TextFormat::Ansi), text);
+    assert_eq!(restored.row(0)[3].zerowidth(), ['\u{301}']);
+    assert_eq!(restored.cursor.template.flags, CellFlags::empty());
+}
+
+#[test]
+fn saved_rows_keep_their_line_attributes() {
+    let mut grid = Grid::new(4, 4);
+    for c in "$ lsout".chars() {
+        grid.put_char(c);
+    }
+    grid.carriage_return();
+    grid.linefeed();
+    grid.row_mut(0).flags = row::RowFlags::PROMPT_START;
+    grid.row_mut(0).meta_mut().command = Some(row::CommandStatus {
+        exit_code: Some(2),
+        duration_ms: Some(40),
+    });
+    // The continuation row of the wrapped line, and a blank row.
+    grid.row_mut(1).flags = row::RowFlags::OUTPUT_START;
+    grid.row_mut(2).flags = row::RowFlags::COMMAND_END | row::RowFlags::DOUBLE_WIDTH;
+    grid.row_mut(2).meta_mut().exit_status = Some(2);
+    let text = grid.serialize_rows(0, 2, TextFormat::Saved);
+    assert_eq!(grid.serialize_rows(0, 2, TextFormat::Ansi), "$ lsout\n");
+
+    let mut restored = Grid::new(4, 4);
+    restored.restore_rows(&text);
+    for row in 0..3 {
+        assert_eq!(restored.row(row).flags, grid.row(row).flags);
+        assert_eq!(restored.row(row).meta, grid.row(row).meta);
+    }
+    assert_eq!(restored.row(3).flags, row::RowFlags::empty());
+    assert_eq!(restored.serialize_rows(0, 2, TextFormat::Saved), text);
+}
+
+#[test]
+fn restore_rows_ignores_escapes_other_than_sgr() {
+    let mut grid = Grid::new(10, 3);
+    grid.restore_rows("a\x1b[2J\x1b[5;5Hb\x1b]0;title\x07\r\nc");
+    assert_eq!(grid.serialize_rows(0, 1, TextFormat::Plain), "ab\nc");
+    assert_eq!((grid.cursor.row, grid.cursor.col), (1, 1));
+}
+
+#[test]
+fn last_output_rows_follow_shell_integration_marks() {
+    let mut g = Grid::new(10, 6);
+    assert_eq!(g.last_output_rows(), None);
+    // Prompt, output on rows 1-2, then the next prompt where D and A land.
+    g.row_mut(0).flags |= row::RowFlags::PROMPT_START;
+    g.row_mut(1).flags |= row::RowFlags::OUTPUT_START;
+    g.row_mut(3).flags |= row::RowFlags::COMMAND_END | row::RowFlags::PROMPT_START;
+    g.goto(3, 2);
+    assert_eq!(g.last_output_rows(), Some((1, 2)));
+
+    // A command still running: its output so far, up to the cursor.
+    g.row_mut(4).flags |= row::RowFlags::OUTPUT_START;
+    g.goto(5, 0);
+    assert_eq!(g.last_output_rows(), Some((4, 5)));
+
+    // A command without output ends where its output would start.
+    g.row_mut(5).flags |= row::RowFlags::OUTPUT_START | row::RowFlags::COMMAND_END;
+    assert_eq!(g.last_output_rows(), Some((5, 5)));
+}
+
+#[test]
+fn finish_command_records_status_on_the_prompt_row() {
+    let mut g = Grid::new(10, 6);
+    // No prompt yet: nothing to attach the status to.
+    g.finish_command(Some(1), 5_000);
+    assert!(g.row(0).meta.is_none());
+
+    g.row_mut(1).flags |= row::RowFlags::PROMPT_START | row::RowFlags::COMMAND_START;
+    g.row_mut(1).meta_mut().timestamp_ms = Some(1_000);
+    g.row_mut(2).flags |= row::RowFlags::OUTPUT_START;
+    g.goto(4, 0);
+    g.finish_command(Some(2), 4_200);
+    let status = g.row(1).meta.as_ref().and_then(|m| m.command);
+    assert_eq!(
+        status,
+        Some(row::CommandStatus {
+            exit_code: Some(2),
+            duration_ms: Some(3_200),
+        })
+    );
+
+    // Without a B mark the duration is unknown.
+    g.row_mut(5).flags |= row::RowFlags::PROMPT_START;
+    g.goto(5, 3);
+    g.finish_command(Some(0), 9_000);
+    let status = g.row(5).meta.as_ref().and_then(|m| m.command);
+    assert_eq!(status.map(|s| (s.exit_code, s.duration_ms)), Some((Some(0), None)));
+}
+
+#[test]
+fn prompt_rows_follow_marks_through_scrolling_and_eviction() {
+    let mut g = Grid::with_max_scrollback(10, 3, 2);
+    g.mark_row(0, row::RowFlags::PROMPT_START);
+    g.mark_row(2, row::RowFlags::PROMPT_START);
+    g.mark_row(2, row::RowFlags::OUTPUT_START);
+    assert_eq!(g.prompt_rows().collect::<Vec<_>>(), [0, 2]);
+
+    g.scroll_up(2);
+    assert_eq!(g.prompt_rows().collect::<Vec<_>>(), [0, 2]);
+    // The first prompt scrolls out of the two rows of scrollback.
+    g.scroll_up(1);
+    assert_eq!(g.prompt_rows().collect::<Vec<_>>(), [1]);
+
+    g.resize(5, 3, true);
+    assert_eq!(g.prompt_rows().collect::<Vec<_>>(), [1]);
+    g.erase_display(ClearMode::Saved);
+    assert_eq!(g.prompt_rows().next(), None);
+}
+
+#[test]
+fn zones_split_each_command_into_prompt_input_and_output() {
+    let mut g = Grid::new(10, 8);
+    assert_eq!(g.zones().next(), None);
+    // `$ ls` on row 0, output on rows 1-2, the next prompt on row 3 where
+    // D lands, and its command still running with output from row 4.
+    g.row_mut(0).flags |= row::RowFlags::PROMPT_START | row::RowFlags::COMMAND_START;
+    g.row_mut(1).flags |= row::RowFlags::OUTPUT_START;
+    g.row_mut(3).flags |= row::RowFlags::COMMAND_END | row::RowFlags::PROMPT_START;
+    g.row_mut(3).flags |= row::RowFlags::COMMAND_START;
+    g.row_mut(4).flags |= row::RowFlags::OUTPUT_START;
+    g.goto(5, 0);
+    let zones: Vec<_> = g.zones().map(|z| (z.kind, z.start, z.end)).collect();
+    assert_eq!(
+        zones,
+        [
+            (ZoneKind::Prompt, 0, 0),
+            (ZoneKind::Input, 0, 0),
+            (ZoneKind::Output, 1, 2),
+            (ZoneKind::Prompt, 3, 3),
+            (ZoneKind::Input, 3, 3),
+            (ZoneKind::Output, 4, 5),
+        ]
+    );
+}
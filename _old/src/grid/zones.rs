@@ -0,0 +1,142 @@
+//! Semantic zones: the prompt, command input, and output rows of each
+//! command, derived from the OSC 133 marks on rows.
+//!
+//! A, B, and C each start a zone on their row and end the one before it;
+//! D ends the output. A zone that ends on a row where the next one starts
+//! keeps that row when it has no other (a prompt and the command typed after
+//! it share a row). The zone still open at the bottom ends at the cursor.
+
+use super::row::{Row, RowFlags};
+use super::{Grid, StableRowIndex};
+
+/// What part of a command a zone holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZoneKind {
+    /// The shell prompt (OSC 133;A).
+    Prompt,
+    /// The command line being typed (OSC 133;B).
+    Input,
+    /// The command's output (OSC 133;C until D).
+    Output,
+}
+
+/// Rows `start..=end` (absolute) holding one part of a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Zone {
+    pub kind: ZoneKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Marks in the order they apply within one row; D starts no zone.
+const MARKS: [(RowFlags, Option<ZoneKind>); 4] = [
+    (RowFlags::PROMPT_START, Some(ZoneKind::Prompt)),
+    (RowFlags::COMMAND_START, Some(ZoneKind::Input)),
+    (RowFlags::OUTPUT_START, Some(ZoneKind::Output)),
+    (RowFlags::COMMAND_END, None),
+];
+
+/// Iterator over a grid's zones, oldest first (see [`Grid::zones`]).
+pub struct Zones<'a> {
+    grid: &'a Grid,
+    /// Row being scanned and the next of its [`MARKS`] to check.
+    row: usize,
+    mark: usize,
+    /// One past the last row with marks to scan.
+    total: usize,
+    /// Where the still-open zone ends if nothing closes it.
+    cursor: usize,
+    open: Option<(ZoneKind, usize)>,
+}
+
+impl Iterator for Zones<'_> {
+    type Item = Zone;
+
+    fn next(&mut self) -> Option<Zone> {
+        while self.row < self.total {
+            let flags = self.grid.row_flags(self.row);
+            while let Some(&(flag, kind)) = MARKS.get(self.mark) {
+                self.mark += 1;
+                if !flags.contains(flag) {
+                    continue;
+                }
+                let row = self.row;
+                let closed = self.open.take().map(|(kind, start)| Zone {
+                    kind,
+                    start,
+                    end: row.saturating_sub(1).max(start),
+                });
+                self.open = kind.map(|kind| (kind, row));
+                if closed.is_some() {
+                    return closed;
+                }
+            }
+            self.mark = 0;
+            self.row += 1;
+        }
+        let cursor = self.cursor;
+        self.open.take().map(|(kind, start)| Zone {
+            kind,
+            start,
+            end: cursor.max(start),
+        })
+    }
+}
+
+impl Grid {
+    /// The marks on absolute row `abs_row`; none past the end of the grid.
+    pub(super) fn row_flags(&self, abs_row: usize) -> RowFlags {
+        self.absolute_row(abs_row).map_or(RowFlags::empty(), |r| r.flags)
+    }
+
+    /// Set the shell integration mark `flag` on screen row `line`. Prompt
+    /// marks are also remembered for [`Grid::prompt_rows`].
+    pub fn mark_row(&mut self, line: usize, flag: RowFlags) -> &mut Row {
+        if flag.contains(RowFlags::PROMPT_START) {
+            let first = StableRowIndex::from_absolute(self, 0);
+            let row = StableRowIndex::from_absolute(self, self.scrollback.len() + line);
+            // Drop the rows that scrolled out while here anyway.
+            self.prompt_rows.retain(|&r| r >= first);
+            if let Err(at) = self.prompt_rows.binary_search(&row) {
+                self.prompt_rows.insert(at, row);
+            }
+        }
+        let row = self.row_mut(line);
+        row.flags.insert(flag);
+        row
+    }
+
+    /// Absolute rows with a prompt mark, oldest first. Reads the rows
+    /// remembered by [`Grid::mark_row`] rather than scanning the grid, so
+    /// it is cheap enough to call every frame.
+    pub fn prompt_rows(&self) -> impl Iterator<Item = usize> + '_ {
+        self.prompt_rows
+            .iter()
+            .filter_map(|row| row.to_absolute(self))
+            // Erased or scrolled-over rows lose their mark.
+            .filter(|&abs| self.row_flags(abs).contains(RowFlags::PROMPT_START))
+    }
+
+    /// Find the prompt rows again after a resize moved them.
+    pub(super) fn rebuild_prompt_rows(&mut self) {
+        let total = self.scrollback.len() + self.lines;
+        self.prompt_rows = (0..total)
+            .filter(|&abs| self.row_flags(abs).contains(RowFlags::PROMPT_START))
+            .map(|abs| StableRowIndex::from_absolute(self, abs))
+            .collect();
+    }
+
+    /// The prompt, input, and output zones of every command in scrollback
+    /// and on screen, oldest first. Rows before the first mark, and between
+    /// a D and the next A, are in no zone.
+    pub fn zones(&self) -> Zones<'_> {
+        Zones {
+            grid: self,
+            row: 0,
+            mark: 0,
+            total: self.scrollback.len() + self.lines,
+            cursor: self.scrollback.len() + self.cursor.row,
+            open: None,
+        }
+    }
+}
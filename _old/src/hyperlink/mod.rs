@@ -0,0 +1,82 @@
+//! Interned OSC 8 hyperlinks.
+//!
+//! Each `OSC 8 ; params ; uri ST` opens a link that later printed cells
+//! carry. Links with an `id=` parameter are interned by (id, URI), so every
+//! cell printed under that id shares one [`Link`] — across wrapped lines,
+//! repaints, and separate regions of the screen — and highlights as a unit
+//! on hover. Links without an id get a fresh identity per OSC 8, so only the
+//! cells written before the closing OSC 8 belong together.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+
+use vte::ansi::Hyperlink;
+
+/// Fewest interned ids kept before dead entries are pruned.
+const MIN_PRUNE_LEN: usize = 64;
+
+/// Identity of one hyperlink.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LinkId(u64);
+
+/// A hyperlink shared by every cell that belongs to it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Link {
+    pub id: LinkId,
+    pub uri: String,
+}
+
+/// Interns hyperlinks by their OSC 8 `id` parameter.
+///
+/// Entries hold weak references, so a link lives exactly as long as some
+/// cell (or the cursor template) still carries it.
+#[derive(Debug, Default)]
+pub struct LinkTable {
+    interned: HashMap<(String, String), Weak<Link>>,
+    next_id: u64,
+    /// Table length that triggers the next pruning pass.
+    prune_at: usize,
+}
+
+impl LinkTable {
+    /// The shared link for `hyperlink`, created on first use of its id.
+    pub fn intern(&mut self, hyperlink: Hyperlink) -> Arc<Link> {
+        let Some(id) = hyperlink.id else {
+            return self.fresh(hyperlink.uri);
+        };
+        let key = (id, hyperlink.uri);
+        if let Some(link) = self.interned.get(&key).and_then(Weak::upgrade) {
+            return link;
+        }
+        let link = self.fresh(key.1.clone());
+        self.interned.insert(key, Arc::downgrade(&link));
+        if self.interned.len() >= self.prune_at {
+            self.interned.retain(|_, link| link.strong_count() > 0);
+            self.prune_at = (self.interned.len() * 2).max(MIN_PRUNE_LEN);
+        }
+        link
+    }
+
+    /// Number of ids currently interned (live or not yet pruned).
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.interned.len()
+    }
+
+    /// Whether no ids are interned.
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.interned.is_empty()
+    }
+
+    fn fresh(&mut self, uri: String) -> Arc<Link> {
+        self.next_id += 1;
+        Arc::new(Link {
+            id: LinkId(self.next_id),
+            uri,
+        })
+    }
+}
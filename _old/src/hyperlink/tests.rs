@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use vte::ansi::Hyperlink;
+
+use super::LinkTable;
+
+fn osc8(id: Option<&str>, uri: &str) -> Hyperlink {
+    Hyperlink {
+        id: id.map(str::to_owned),
+        uri: uri.to_owned(),
+    }
+}
+
+#[test]
+fn same_id_and_uri_share_one_link() {
+    let mut table = LinkTable::default();
+    let a = table.intern(osc8(Some("1"), "https://a.example"));
+    let b = table.intern(osc8(Some("1"), "https://a.example"));
+    assert!(Arc::ptr_eq(&a, &b));
+
+    // The id is scoped to its URI.
+    let c = table.intern(osc8(Some("1"), "https://c.example"));
+    assert_ne!(a.id, c.id);
+}
+
+#[test]
+fn links_without_id_are_distinct_per_sequence() {
+    let mut table = LinkTable::default();
+    let a = table.intern(osc8(None, "https://a.example"));
+    let b = table.intern(osc8(None, "https://a.example"));
+    assert_ne!(a.id, b.id);
+    assert!(table.is_empty());
+}
+
+#[test]
+fn dropped_links_are_pruned() {
+    let mut table = LinkTable::default();
+    let kept = table.intern(osc8(Some("kept"), "https://a.example"));
+    for i in 0..200 {
+        table.intern(osc8(Some(&i.to_string()), "https://a.example"));
+    }
+    assert!(table.len() < 100);
+    let again = table.intern(osc8(Some("kept"), "https://a.example"));
+    assert!(Arc::ptr_eq(&kept, &again));
+}
@@ -1,8 +1,13 @@
 //! Vector icon rasterizer.
 //!
-//! Each icon is defined as line segments and rasterized to an alpha bitmap
-//! at any pixel size using signed-distance anti-aliasing. Icons are cached
-//! in the glyph atlas and rendered through the existing textured-quad pipeline.
+//! Each icon is defined as line segments (or, for caption buttons,
+//! pixel-aligned rectangles) and rasterized to an alpha bitmap at any pixel
+//! size using signed-distance anti-aliasing. Icons are cached
+//! in the glyph atlas per device-pixel size and rendered through the
+//! existing textured-quad pipeline, so they are redrawn from geometry at
+//! every scale factor instead of being stretched from a fixed raster.
+//! Horizontal and vertical strokes are snapped to whole pixels so thin
+//! chrome stays sharp at 150 % and 200 %.
 
 /// Available icons.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -11,6 +16,19 @@ pub enum Icon {
     ChevronDown,
     Plus,
     Checkmark,
+    /// Caption-button glyphs, drawn with 1 px strokes at 100 %.
+    Minimize,
+    Maximize,
+    Restore,
+    WindowClose,
+    /// Filled disc (the round caption buttons on Linux).
+    Circle,
+    /// Shell prompt (`>_`), the icon of an ordinary shell tab.
+    Prompt,
+    /// Penguin silhouette, the icon of a WSL tab.
+    Penguin,
+    /// Ring with a gap, shown in place of the tab icon while a command runs.
+    Busy,
 }
 
 /// Rasterized icon bitmap (R8 alpha channel, row-major).
@@ -28,6 +46,14 @@ impl Icon {
             Self::ChevronDown => rasterize_chevron_down(size),
             Self::Plus => rasterize_plus(size),
             Self::Checkmark => rasterize_checkmark(size),
+            Self::Minimize => rasterize_minimize(size),
+            Self::Maximize => rasterize_maximize(size),
+            Self::Restore => rasterize_restore(size),
+            Self::WindowClose => rasterize_window_close(size),
+            Self::Circle => rasterize_circle(size),
+            Self::Prompt => rasterize_prompt(size),
+            Self::Penguin => rasterize_penguin(size),
+            Self::Busy => rasterize_busy(size),
         }
     }
 }
@@ -46,25 +72,13 @@ fn dist_to_segment(px: f32, py: f32, ax: f32, ay: f32, bx: f32, by: f32) -> f32
     (px - nx).hypot(py - ny)
 }
 
-/// Rasterize line segments into an alpha bitmap using SDF anti-aliasing.
-fn rasterize_segments(
-    w: u32,
-    h: u32,
-    segments: &[(f32, f32, f32, f32)],
-    half_thickness: f32,
-) -> IconBitmap {
+/// Rasterize an alpha bitmap from a per-pixel coverage function evaluated
+/// at pixel centers.
+fn rasterize_with(w: u32, h: u32, coverage: impl Fn(f32, f32) -> f32) -> IconBitmap {
     let mut data = vec![0u8; (w * h) as usize];
     for y in 0..h {
         for x in 0..w {
-            let px = x as f32 + 0.5;
-            let py = y as f32 + 0.5;
-            let mut alpha = 0.0_f32;
-            for &(ax, ay, bx, by) in segments {
-                let d = dist_to_segment(px, py, ax, ay, bx, by);
-                // Smooth falloff: full coverage inside, linear ramp over ~1px at edge
-                let a = (half_thickness + 0.5 - d).clamp(0.0, 1.0);
-                alpha = alpha.max(a);
-            }
+            let alpha = coverage(x as f32 + 0.5, y as f32 + 0.5).clamp(0.0, 1.0);
             data[(y * w + x) as usize] = (alpha * 255.0).round() as u8;
         }
     }
@@ -75,6 +89,37 @@ fn rasterize_segments(
     }
 }
 
+/// Rasterize line segments into an alpha bitmap using SDF anti-aliasing.
+pub(crate) fn rasterize_segments(
+    w: u32,
+    h: u32,
+    segments: &[(f32, f32, f32, f32)],
+    half_thickness: f32,
+) -> IconBitmap {
+    rasterize_with(w, h, |px, py| {
+        segments
+            .iter()
+            .map(|&(ax, ay, bx, by)| {
+                // Smooth falloff: full coverage inside, linear ramp over ~1px at edge
+                half_thickness + 0.5 - dist_to_segment(px, py, ax, ay, bx, by)
+            })
+            .fold(0.0, f32::max)
+    })
+}
+
+/// Rasterize a filled shape, anti-aliased by 4×4 supersampling of `inside`.
+pub(crate) fn rasterize_filled(w: u32, h: u32, inside: impl Fn(f32, f32) -> bool) -> IconBitmap {
+    const SAMPLES: [f32; 4] = [-0.375, -0.125, 0.125, 0.375];
+    rasterize_with(w, h, |px, py| {
+        let hits = SAMPLES
+            .iter()
+            .flat_map(|&dy| SAMPLES.iter().map(move |&dx| (px + dx, py + dy)))
+            .filter(|&(x, y)| inside(x, y))
+            .count();
+        hits as f32 / 16.0
+    })
+}
+
 /// Close icon (×): two diagonal lines.
 fn rasterize_close(size: u32) -> IconBitmap {
     let s = size as f32;
@@ -121,3 +166,121 @@ fn rasterize_checkmark(size: u32) -> IconBitmap {
     ];
     rasterize_segments(size, size, &segments, thickness / 2.0)
 }
+
+/// Stroke width of the caption-button glyphs: 1 px at 100 %, 2 px at 200 %.
+fn caption_stroke(s: f32) -> f32 {
+    (s / 10.0).round().max(1.0)
+}
+
+/// Rasterize axis-aligned filled rectangles `(x, y, w, h)`. Each pixel is
+/// covered by the area of it the rectangles overlap, so rectangles on
+/// whole-pixel bounds come out with hard edges and square corners.
+fn rasterize_rects(size: u32, rects: &[(f32, f32, f32, f32)]) -> IconBitmap {
+    let overlap =
+        |lo: f32, hi: f32, from: f32, len: f32| (hi.min(from + len) - lo.max(from)).max(0.0);
+    rasterize_with(size, size, |px, py| {
+        rects
+            .iter()
+            .map(|&(x, y, w, h)| {
+                overlap(px - 0.5, px + 0.5, x, w) * overlap(py - 0.5, py + 0.5, y, h)
+            })
+            .fold(0.0, f32::max)
+    })
+}
+
+/// Edges of a `side`-pixel square outline whose top-left pixel is at
+/// (`x`, `y`).
+fn square_outline(x: f32, y: f32, side: f32, t: f32) -> [(f32, f32, f32, f32); 4] {
+    [
+        (x, y, side, t),
+        (x, y + side - t, side, t),
+        (x, y, t, side),
+        (x + side - t, y, t, side),
+    ]
+}
+
+/// Minimize icon (—): one horizontal stroke across the middle.
+fn rasterize_minimize(size: u32) -> IconBitmap {
+    let s = size as f32;
+    let t = caption_stroke(s);
+    rasterize_rects(size, &[(0.0, ((s - t) / 2.0).round(), s, t)])
+}
+
+/// Maximize icon (□): a square outline.
+fn rasterize_maximize(size: u32) -> IconBitmap {
+    let s = size as f32;
+    let t = caption_stroke(s);
+    rasterize_rects(size, &square_outline(0.0, 0.0, s, t))
+}
+
+/// Restore icon (⧉): a front square with a second one peeking out behind it.
+fn rasterize_restore(size: u32) -> IconBitmap {
+    let s = size as f32;
+    let t = caption_stroke(s);
+    let off = (s * 0.2).round().max(t + 1.0);
+    let side = s - off;
+    let mut rects = square_outline(0.0, off, side, t).to_vec();
+    // The back square's edges that show above and to the right of the front.
+    rects.extend([
+        (off, 0.0, s - off, t),
+        (s - t, 0.0, t, side),
+        (off, 0.0, t, off),
+        (side, side - t, off, t),
+    ]);
+    rasterize_rects(size, &rects)
+}
+
+/// Window close icon (×): thin diagonals corner to corner.
+fn rasterize_window_close(size: u32) -> IconBitmap {
+    let s = size as f32;
+    let t = caption_stroke(s);
+    let (a, b) = (t / 2.0, s - t / 2.0);
+    rasterize_segments(size, size, &[(a, a, b, b), (b, a, a, b)], t / 2.0)
+}
+
+/// Filled circle touching all four edges.
+fn rasterize_circle(size: u32) -> IconBitmap {
+    let r = size as f32 / 2.0;
+    rasterize_with(size, size, |px, py| r + 0.5 - (px - r).hypot(py - r))
+}
+
+/// Prompt icon (`>_`): a right chevron followed by an underscore.
+fn rasterize_prompt(size: u32) -> IconBitmap {
+    let s = size as f32;
+    let thickness = (s * 0.14).max(1.2);
+    let segments = [
+        (s * 0.12, s * 0.22, s * 0.45, s * 0.50),
+        (s * 0.45, s * 0.50, s * 0.12, s * 0.78),
+        (s * 0.55, s * 0.80, s * 0.88, s * 0.80),
+    ];
+    rasterize_segments(size, size, &segments, thickness / 2.0)
+}
+
+/// Penguin icon: head and body with the belly and eyes cut out.
+fn rasterize_penguin(size: u32) -> IconBitmap {
+    let s = size as f32;
+    let in_ellipse = |x: f32, y: f32, cx: f32, cy: f32, rx: f32, ry: f32| {
+        ((x - cx) / rx).powi(2) + ((y - cy) / ry).powi(2) <= 1.0
+    };
+    rasterize_filled(size, size, |x, y| {
+        let head = in_ellipse(x, y, s * 0.5, s * 0.26, s * 0.2, s * 0.2);
+        let body = in_ellipse(x, y, s * 0.5, s * 0.63, s * 0.32, s * 0.35);
+        let belly = in_ellipse(x, y, s * 0.5, s * 0.68, s * 0.18, s * 0.25);
+        let eye = in_ellipse(x, y, s * 0.42, s * 0.24, s * 0.05, s * 0.05)
+            || in_ellipse(x, y, s * 0.58, s * 0.24, s * 0.05, s * 0.05);
+        (head || body) && !belly && !eye
+    })
+}
+
+/// Busy icon: a ring open in its top-right quarter.
+fn rasterize_busy(size: u32) -> IconBitmap {
+    let c = size as f32 / 2.0;
+    let half_thickness = (c * 0.18).max(0.7);
+    let r = c - half_thickness - 0.5;
+    rasterize_with(size, size, |px, py| {
+        if px > c && py < c {
+            return 0.0;
+        }
+        half_thickness + 0.5 - ((px - c).hypot(py - c) - r).abs()
+    })
+}
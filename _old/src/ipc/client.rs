@@ -0,0 +1,55 @@
+//! The `oriterm cli` side: sends one command to the running terminal and
+//! prints its reply.
+
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, Shutdown, TcpStream};
+
+use super::{Request, endpoint_path};
+
+/// Run `oriterm cli` with the arguments after `cli`. Returns the process
+/// exit code.
+pub fn run(args: &[String]) -> i32 {
+    match call(args) {
+        Ok(body) => {
+            print!("{body}");
+            0
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            1
+        }
+    }
+}
+
+fn call(args: &[String]) -> Result<String, String> {
+    // Reject a malformed command before looking for a terminal.
+    Request::parse(args)?;
+    let endpoint = std::fs::read_to_string(endpoint_path())
+        .map_err(|e| format!("no running oriterm found: {e}"))?;
+    let (port, token) = parse_endpoint(&endpoint).ok_or("malformed endpoint file")?;
+    let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port))
+        .map_err(|e| format!("cannot reach oriterm: {e}"))?;
+
+    let mut request = format!("{token}\n");
+    for arg in args {
+        request.push_str(arg);
+        request.push('\n');
+    }
+    let mut response = String::new();
+    stream
+        .write_all(request.as_bytes())
+        .and_then(|()| stream.shutdown(Shutdown::Write))
+        .and_then(|()| stream.read_to_string(&mut response))
+        .map_err(|e| format!("connection failed: {e}"))?;
+    match response.split_once('\n') {
+        Some(("ok", body)) => Ok(body.to_owned()),
+        Some(("error", message)) => Err(message.to_owned()),
+        _ => Err("malformed reply".to_owned()),
+    }
+}
+
+/// Split the endpoint file into port and token.
+pub(super) fn parse_endpoint(endpoint: &str) -> Option<(u16, &str)> {
+    let (port, token) = endpoint.trim().split_once(' ')?;
+    Some((port.parse().ok()?, token))
+}
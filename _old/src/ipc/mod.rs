@@ -0,0 +1,126 @@
+//! Scripting a running oriterm from outside: `oriterm cli <command>`.
+//!
+//! The terminal listens on a loopback TCP port and writes the port and a
+//! random token to [`endpoint_path`]; a client must send the token before
+//! its command. Lines are addressed by [`StableRowIndex`], so a line number
+//! keeps naming the same line as output scrolls it into history.
+//!
+//! Commands:
+//!
+//! - `get-text [--start-line N] [--end-line M] [--ansi]`: the text of lines
+//!   `N..=M` of the active tab (by default the screen), with SGR escapes
+//!   for colors and attributes when `--ansi` is given.
+//! - `get-cursor`: the cursor's line and column, e.g. `1042 7`.
+//! - `get-stats`: the tab's PTY throughput, parse load, and input-to-echo
+//!   latency.
+
+pub mod client;
+mod server;
+#[cfg(test)]
+mod tests;
+
+use std::path::PathBuf;
+use std::sync::mpsc;
+
+use crate::config;
+use crate::grid::{Grid, StableRowIndex, TextFormat};
+use crate::tab::PtyStatsSnapshot;
+
+pub use server::{Endpoint, serve};
+
+/// Usage line shown for a malformed command.
+const USAGE: &str =
+    "usage: oriterm cli get-text [--start-line N] [--end-line M] [--ansi] | get-cursor | get-stats";
+
+/// A command from a client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    /// Lines `start..=end`; a missing end defaults to the screen's edge.
+    GetText {
+        start: Option<u64>,
+        end: Option<u64>,
+        format: TextFormat,
+    },
+    GetCursor,
+    GetStats,
+}
+
+/// A request on its way to the event loop, with the channel for its reply.
+#[derive(Debug)]
+pub struct IpcCall {
+    pub request: Request,
+    pub reply: mpsc::Sender<Result<String, String>>,
+}
+
+/// Where the running terminal publishes its port and token. The first
+/// terminal to start keeps it until it exits.
+pub fn endpoint_path() -> PathBuf {
+    config::config_dir().join("ipc")
+}
+
+impl Request {
+    /// Parse a command line such as `get-text --start-line 10 --ansi`.
+    pub fn parse<S: AsRef<str>>(args: &[S]) -> Result<Self, String> {
+        let mut args = args.iter().map(AsRef::as_ref);
+        let request = match args.next() {
+            Some("get-text") => parse_get_text(&mut args)?,
+            Some("get-cursor") => Self::GetCursor,
+            Some("get-stats") => Self::GetStats,
+            Some(cmd) => return Err(format!("unknown command {cmd:?}\n{USAGE}")),
+            None => return Err(USAGE.to_owned()),
+        };
+        match args.next() {
+            Some(arg) => Err(format!("unexpected argument {arg:?}\n{USAGE}")),
+            None => Ok(request),
+        }
+    }
+
+    /// Answer the request from the active tab's grid and PTY statistics.
+    pub fn answer(&self, grid: &Grid, stats: PtyStatsSnapshot) -> Result<String, String> {
+        let top = StableRowIndex::from_absolute(grid, grid.scrollback.len()).0;
+        match *self {
+            Self::GetStats => Ok(format!("{} bytes, {stats}\n", stats.total_bytes)),
+            Self::GetCursor => Ok(format!(
+                "{} {}\n",
+                top + grid.cursor.row as u64,
+                grid.cursor.col
+            )),
+            Self::GetText { start, end, format } => {
+                let bottom = top + grid.lines.saturating_sub(1) as u64;
+                let (start, end) = (start.unwrap_or(top), end.unwrap_or(bottom));
+                if start > end {
+                    return Err("--start-line is after --end-line".to_owned());
+                }
+                // Lines already evicted from history read as nothing.
+                let oldest = grid.total_evicted() as u64;
+                if end < oldest {
+                    return Ok(String::new());
+                }
+                let to_abs = |line: u64| usize::try_from(line - oldest).unwrap_or(usize::MAX);
+                let mut text = grid.serialize_rows(to_abs(start.max(oldest)), to_abs(end), format);
+                text.push('\n');
+                Ok(text)
+            }
+        }
+    }
+}
+
+/// Parse the flags of `get-text`.
+fn parse_get_text<'a>(args: &mut impl Iterator<Item = &'a str>) -> Result<Request, String> {
+    let (mut start, mut end, mut format) = (None, None, TextFormat::Plain);
+    while let Some(arg) = args.next() {
+        match arg {
+            "--start-line" => start = Some(parse_line(arg, args.next())?),
+            "--end-line" => end = Some(parse_line(arg, args.next())?),
+            "--ansi" => format = TextFormat::Ansi,
+            _ => return Err(format!("unexpected argument {arg:?}\n{USAGE}")),
+        }
+    }
+    Ok(Request::GetText { start, end, format })
+}
+
+fn parse_line(flag: &str, value: Option<&str>) -> Result<u64, String> {
+    value
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| format!("{flag} needs a line number"))
+}
@@ -0,0 +1,178 @@
+//! The terminal's side: accepts clients on a background thread and hands
+//! their requests to the event loop.
+
+use std::fmt::Write as _;
+use std::io::{self, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use winit::event_loop::EventLoopProxy;
+
+use super::client::parse_endpoint;
+use super::{IpcCall, Request, endpoint_path};
+use crate::log;
+use crate::tab::TermEvent;
+
+/// Longest request accepted, in bytes.
+const MAX_REQUEST: u64 = 4096;
+
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long to wait for the event loop to answer.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long a client may take to read the reply.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long to try an existing endpoint's port before calling it stale.
+const PROBE_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// The endpoint file this terminal published. Dropping it removes the
+/// file, unless another terminal has since replaced it.
+#[derive(Debug)]
+pub struct Endpoint {
+    path: PathBuf,
+    token: String,
+}
+
+impl Drop for Endpoint {
+    fn drop(&mut self) {
+        let ours = std::fs::read_to_string(&self.path)
+            .is_ok_and(|text| parse_endpoint(&text).is_some_and(|(_, t)| t == self.token));
+        if ours {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Start listening for `oriterm cli` clients. Failures are logged; the
+/// terminal runs without IPC. A terminal already serving the endpoint
+/// keeps it, so only the first running terminal answers.
+pub fn serve(proxy: EventLoopProxy<TermEvent>) -> Option<Endpoint> {
+    match try_serve(proxy) {
+        Ok(endpoint) => Some(endpoint),
+        Err(e) => {
+            log(&format!("ipc: not listening: {e}"));
+            None
+        }
+    }
+}
+
+fn try_serve(proxy: EventLoopProxy<TermEvent>) -> io::Result<Endpoint> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let port = listener.local_addr()?.port();
+    let token = new_token()?;
+    let endpoint = claim_endpoint(endpoint_path(), port, token.clone())?;
+    thread::Builder::new().name("ipc".into()).spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let (token, proxy) = (token.clone(), proxy.clone());
+            // A slow client holds only its own thread, not the listener.
+            let spawned = thread::Builder::new()
+                .name("ipc-client".into())
+                .spawn(move || {
+                    if let Err(e) = handle_client(stream, &token, &proxy) {
+                        log(&format!("ipc: client failed: {e}"));
+                    }
+                });
+            if let Err(e) = spawned {
+                log(&format!("ipc: cannot handle client: {e}"));
+            }
+        }
+    })?;
+    log(&format!("ipc: listening on port {port}"));
+    Ok(endpoint)
+}
+
+/// Read one request, answer it, and close the connection.
+fn handle_client(
+    mut stream: TcpStream,
+    token: &str,
+    proxy: &EventLoopProxy<TermEvent>,
+) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let mut input = String::new();
+    (&stream).take(MAX_REQUEST).read_to_string(&mut input)?;
+    let mut lines = input.lines();
+    let result = if lines.next() == Some(token) {
+        let args: Vec<&str> = lines.collect();
+        Request::parse(&args).and_then(|request| dispatch(request, proxy))
+    } else {
+        Err("bad token".to_owned())
+    };
+    let response = match result {
+        Ok(body) => format!("ok\n{body}"),
+        Err(e) => format!("error\n{e}"),
+    };
+    stream.write_all(response.as_bytes())
+}
+
+/// Have the event loop answer `request`.
+fn dispatch(request: Request, proxy: &EventLoopProxy<TermEvent>) -> Result<String, String> {
+    let (reply, answer) = mpsc::channel();
+    proxy
+        .send_event(TermEvent::Ipc(IpcCall { request, reply }))
+        .map_err(|_closed| "the terminal is shutting down".to_owned())?;
+    answer
+        .recv_timeout(REPLY_TIMEOUT)
+        .map_err(|_timeout| "the terminal did not reply".to_owned())?
+}
+
+/// 128 random bits from the OS, in hex.
+fn new_token() -> io::Result<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).map_err(|e| io::Error::other(e.to_string()))?;
+    Ok(bytes.iter().fold(String::new(), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+    }))
+}
+
+/// Publish `port` and `token` at `path` for clients, readable only by this
+/// user. An endpoint whose port still accepts connections belongs to a
+/// running terminal and is left alone; a stale one is replaced.
+pub(super) fn claim_endpoint(path: PathBuf, port: u16, token: String) -> io::Result<Endpoint> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = match create_endpoint(&path) {
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+            if endpoint_is_live(&path) {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    "another oriterm is serving the endpoint",
+                ));
+            }
+            std::fs::remove_file(&path)?;
+            create_endpoint(&path)?
+        }
+        file => file?,
+    };
+    let endpoint = Endpoint { path, token };
+    file.write_all(format!("{port} {}\n", endpoint.token).as_bytes())?;
+    Ok(endpoint)
+}
+
+/// Create the endpoint file, failing if it already exists.
+fn create_endpoint(path: &Path) -> io::Result<std::fs::File> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)
+}
+
+/// Whether the terminal that wrote the endpoint at `path` still listens.
+fn endpoint_is_live(path: &Path) -> bool {
+    let Ok(text) = std::fs::read_to_string(path) else {
+        return false;
+    };
+    parse_endpoint(&text).is_some_and(|(port, _)| {
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        TcpStream::connect_timeout(&addr, PROBE_TIMEOUT).is_ok()
+    })
+}
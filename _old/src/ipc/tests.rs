@@ -0,0 +1,132 @@
+//! Tests for IPC request parsing and answers.
+
+use std::net::{Ipv4Addr, TcpListener};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::grid::{Grid, TextFormat};
+use crate::tab::PtyStatsSnapshot;
+
+use super::Request;
+use super::client::parse_endpoint;
+use super::server::claim_endpoint;
+
+const STATS: PtyStatsSnapshot = PtyStatsSnapshot {
+    total_bytes: 4096,
+    bytes_per_sec: 2048.0,
+    parse_load: 0.25,
+    echo_latency: Some(Duration::from_millis(3)),
+    echo_latency_max: Duration::from_millis(12),
+};
+
+fn write(grid: &mut Grid, text: &str) {
+    for c in text.chars() {
+        grid.put_char(c);
+    }
+}
+
+#[test]
+fn parse_get_text_flags() {
+    assert_eq!(
+        Request::parse(&["get-text", "--start-line", "3", "--ansi"]),
+        Ok(Request::GetText {
+            start: Some(3),
+            end: None,
+            format: TextFormat::Ansi,
+        })
+    );
+    assert_eq!(Request::parse(&["get-cursor"]), Ok(Request::GetCursor));
+    assert_eq!(Request::parse(&["get-stats"]), Ok(Request::GetStats));
+    assert!(Request::parse(&["get-text", "--end-line"]).is_err());
+    assert!(Request::parse(&["get-cursor", "--ansi"]).is_err());
+    assert!(Request::parse::<&str>(&[]).is_err());
+}
+
+#[test]
+fn endpoint_holds_port_and_token() {
+    assert_eq!(parse_endpoint("4242 abc\n"), Some((4242, "abc")));
+    assert_eq!(parse_endpoint("abc"), None);
+}
+
+#[test]
+fn lines_keep_their_numbers_as_history_is_evicted() {
+    let mut grid = Grid::with_max_scrollback(10, 2, 1);
+    write(&mut grid, "one");
+    for line in ["two", "three", "four"] {
+        grid.carriage_return();
+        grid.newline();
+        write(&mut grid, line);
+    }
+    // "one" (line 0) was evicted; "two" is line 1 in history.
+    let text = |start, end| {
+        let request = Request::GetText {
+            start,
+            end,
+            format: TextFormat::Plain,
+        };
+        request.answer(&grid, STATS)
+    };
+    assert_eq!(text(Some(0), Some(0)), Ok(String::new()));
+    assert_eq!(text(Some(0), Some(3)), Ok("two\nthree\nfour\n".to_owned()));
+    assert_eq!(text(None, None), Ok("three\nfour\n".to_owned()));
+    assert!(text(Some(3), Some(2)).is_err());
+    assert_eq!(Request::GetCursor.answer(&grid, STATS), Ok("3 4\n".to_owned()));
+}
+
+#[test]
+fn stats_report_the_tab_pty_counters() {
+    let grid = Grid::new(10, 2);
+    assert_eq!(
+        Request::GetStats.answer(&grid, STATS),
+        Ok("4096 bytes, 2.0 KB/s, parse 25.0%, echo 3.0ms (max 12.0ms)\n".to_owned())
+    );
+}
+
+fn endpoint_file(name: &str) -> PathBuf {
+    std::env::temp_dir()
+        .join(format!("oriterm-ipc-{}", std::process::id()))
+        .join(name)
+}
+
+#[test]
+fn live_endpoint_is_not_replaced() {
+    let path = endpoint_file("live");
+    let first = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).expect("bind");
+    let port = first.local_addr().expect("addr").port();
+    let endpoint = claim_endpoint(path.clone(), port, "first".to_owned()).expect("claim");
+
+    assert!(claim_endpoint(path.clone(), 1, "second".to_owned()).is_err());
+    let text = std::fs::read_to_string(&path).expect("endpoint");
+    assert_eq!(parse_endpoint(&text), Some((port, "first")));
+
+    drop(endpoint);
+    assert!(!path.exists());
+}
+
+#[test]
+fn stale_endpoint_is_replaced() {
+    let path = endpoint_file("stale");
+    let port = {
+        let gone = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).expect("bind");
+        gone.local_addr().expect("addr").port()
+    };
+    std::fs::create_dir_all(path.parent().expect("dir")).expect("create dir");
+    std::fs::write(&path, format!("{port} old\n")).expect("write stale endpoint");
+
+    let endpoint = claim_endpoint(path.clone(), 2, "new".to_owned()).expect("claim");
+    let text = std::fs::read_to_string(&path).expect("endpoint");
+    assert_eq!(parse_endpoint(&text), Some((2, "new")));
+    drop(endpoint);
+}
+
+#[test]
+fn exit_leaves_another_terminals_endpoint() {
+    let path = endpoint_file("replaced");
+    let endpoint = claim_endpoint(path.clone(), 3, "mine".to_owned()).expect("claim");
+    std::fs::write(&path, "4 theirs\n").expect("replace endpoint");
+
+    drop(endpoint);
+    let text = std::fs::read_to_string(&path).expect("endpoint kept");
+    assert_eq!(parse_endpoint(&text), Some((4, "theirs")));
+    let _ = std::fs::remove_file(&path);
+}
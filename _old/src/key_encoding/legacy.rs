@@ -184,7 +184,7 @@ pub(super) fn encode_legacy(
 /// Map a Ctrl+key combination to its C0 control byte.
 ///
 /// Handles a-z, `[`, `\\`, `]`, `^`, `_`, and 2-8 (xterm-compatible).
-fn ctrl_key_byte(s: &str) -> Option<u8> {
+pub(super) fn ctrl_key_byte(s: &str) -> Option<u8> {
     let bytes = s.as_bytes();
     if bytes.len() != 1 {
         return None;
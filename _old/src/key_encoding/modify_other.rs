@@ -0,0 +1,69 @@
+//! xterm modifyOtherKeys encoding (`CSI 27 ; mod ; code ~`).
+//!
+//! Level 1 leaves keys with a well-known legacy form alone: Ctrl or Shift
+//! with characters 64–127 (`^A`, `A`), the Ctrl+digit control aliases,
+//! Ctrl+Space, Alt's ESC prefix, and Shift+Tab. Level 2 encodes every
+//! modified key except Shift with a printable character, so applications
+//! like emacs can tell Ctrl+I from Tab and Ctrl+Shift+A from Ctrl+A.
+
+use winit::keyboard::{Key, NamedKey};
+
+use super::Modifiers;
+use super::legacy::ctrl_key_byte;
+
+/// How much of the keyboard modifyOtherKeys covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Level {
+    /// `CSI > 4 ; 1 m`.
+    ExceptWellDefined,
+    /// `CSI > 4 ; 2 m`.
+    All,
+}
+
+/// Encode `key` as `CSI 27 ; mod ; code ~`, or `None` to fall back to the
+/// legacy encoding.
+pub(super) fn encode(key: &Key, mods: Modifiers, level: Level) -> Option<Vec<u8>> {
+    if mods.is_empty() {
+        return None;
+    }
+    let code = key_code(key)?;
+    let printable = (0x20..0x7f).contains(&code) || code > 0x7f;
+    let shift_tab = mods == Modifiers::SHIFT && code == u32::from(b'\t');
+    if (mods == Modifiers::SHIFT && printable) || shift_tab {
+        return None;
+    }
+    if level == Level::ExceptWellDefined && well_defined(key, mods, code) {
+        return None;
+    }
+    Some(format!("\x1b[27;{};{code}~", mods.xterm_param()).into_bytes())
+}
+
+/// Keys whose modified form level 1 leaves to the legacy encoder.
+fn well_defined(key: &Key, mods: Modifiers, code: u32) -> bool {
+    let ctrl_alias = match key {
+        Key::Character(s) => ctrl_key_byte(s).is_some(),
+        Key::Named(NamedKey::Space) => true,
+        _ => false,
+    };
+    let control_or_shift = mods == Modifiers::CONTROL || mods == Modifiers::SHIFT;
+    (control_or_shift && (64..=127).contains(&code))
+        || (mods == Modifiers::CONTROL && ctrl_alias)
+        || mods == Modifiers::ALT
+}
+
+/// The character a key reports, as xterm's `code` parameter.
+fn key_code(key: &Key) -> Option<u32> {
+    match key {
+        Key::Character(s) => {
+            let mut chars = s.chars();
+            let c = chars.next()?;
+            chars.next().is_none().then_some(u32::from(c))
+        }
+        Key::Named(NamedKey::Enter) => Some(0x0d),
+        Key::Named(NamedKey::Tab) => Some(0x09),
+        Key::Named(NamedKey::Backspace) => Some(0x7f),
+        Key::Named(NamedKey::Escape) => Some(0x1b),
+        Key::Named(NamedKey::Space) => Some(0x20),
+        _ => None,
+    }
+}
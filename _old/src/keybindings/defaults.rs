@@ -1,139 +1,167 @@
-//! Built-in default keybindings.
+//! Built-in default keybindings, one table per platform profile.
 
+use serde::{Deserialize, Serialize};
 use winit::keyboard::NamedKey;
 
 use crate::key_encoding::Modifiers;
 
 use super::{Action, BindingKey, KeyBinding};
 
-/// All built-in default keybindings. More-specific modifier combos come first
-/// so that e.g. Ctrl+Shift+C (Copy) is checked before Ctrl+C (`SmartCopy`).
-pub fn default_bindings() -> Vec<KeyBinding> {
-    let ch = |s: &str| BindingKey::Character(s.to_owned());
-    let named = BindingKey::Named;
+/// Which platform's default bindings user `[[keybind]]` entries layer onto
+/// (`keybind_profile`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeybindProfile {
+    /// The profile of the platform oriterm runs on.
+    #[default]
+    Auto,
+    /// Ctrl-based; Ctrl+C copies when there's a selection and Ctrl+V
+    /// pastes, as in Windows Terminal.
+    Windows,
+    /// Ctrl+Shift-based, leaving Ctrl+C, Ctrl+V, and Ctrl+W to the shell.
+    Linux,
+    /// Cmd (Super)-based, leaving every Ctrl chord to the shell.
+    Macos,
+}
+
+impl KeybindProfile {
+    /// The concrete profile, with `Auto` resolved for this platform.
+    #[must_use]
+    pub fn resolve(self) -> Self {
+        match self {
+            Self::Auto if cfg!(target_os = "windows") => Self::Windows,
+            Self::Auto if cfg!(target_os = "macos") => Self::Macos,
+            Self::Auto => Self::Linux,
+            profile => profile,
+        }
+    }
+}
+
+fn ch(s: &str) -> BindingKey {
+    BindingKey::Character(s.to_owned())
+}
+
+fn named(key: NamedKey) -> BindingKey {
+    BindingKey::Named(key)
+}
+
+/// The default keybindings of `profile`. More-specific modifier combos come
+/// first so that e.g. Ctrl+Shift+C (Copy) is checked before Ctrl+C
+/// (`SmartCopy`).
+pub fn default_bindings(profile: KeybindProfile) -> Vec<KeyBinding> {
+    let mut bindings = match profile.resolve() {
+        KeybindProfile::Macos => macos_bindings(),
+        KeybindProfile::Linux => linux_bindings(),
+        _ => windows_bindings(),
+    };
+    bindings.extend(shared_bindings());
+    bindings
+}
+
+/// Bindings every profile has: scrollback, search match stepping, and
+/// Ctrl+Tab tab cycling.
+fn shared_bindings() -> Vec<KeyBinding> {
     let ctrl = Modifiers::CONTROL;
     let shift = Modifiers::SHIFT;
-    let ctrl_shift = ctrl | shift;
+    vec![
+        KeyBinding::new(named(NamedKey::F3), Modifiers::empty(), Action::NextMatch),
+        KeyBinding::new(named(NamedKey::F3), shift, Action::PreviousMatch),
+        KeyBinding::new(named(NamedKey::Tab), ctrl, Action::NextTab),
+        KeyBinding::new(named(NamedKey::Tab), ctrl | shift, Action::PrevTab),
+        KeyBinding::new(named(NamedKey::PageUp), shift, Action::ScrollPageUp),
+        KeyBinding::new(named(NamedKey::PageDown), shift, Action::ScrollPageDown),
+        KeyBinding::new(named(NamedKey::Home), shift, Action::ScrollToTop),
+        KeyBinding::new(named(NamedKey::End), shift, Action::ScrollToBottom),
+    ]
+}
 
+/// Ctrl+Shift bindings for terminal features, shared by Windows and Linux.
+fn ctrl_shift_bindings() -> Vec<KeyBinding> {
+    let ctrl = Modifiers::CONTROL;
+    let ctrl_shift = ctrl | Modifiers::SHIFT;
     vec![
         // Explicit copy / paste (Ctrl+Shift+C/V)
-        KeyBinding {
-            key: ch("c"),
-            mods: ctrl_shift,
-            action: Action::Copy,
-        },
-        KeyBinding {
-            key: ch("v"),
-            mods: ctrl_shift,
-            action: Action::Paste,
-        },
+        KeyBinding::new(ch("c"), ctrl_shift, Action::Copy),
+        KeyBinding::new(ch("v"), ctrl_shift, Action::Paste),
         // Ctrl+Insert / Shift+Insert
-        KeyBinding {
-            key: named(NamedKey::Insert),
-            mods: ctrl,
-            action: Action::Copy,
-        },
-        KeyBinding {
-            key: named(NamedKey::Insert),
-            mods: shift,
-            action: Action::Paste,
-        },
+        KeyBinding::new(named(NamedKey::Insert), ctrl, Action::Copy),
+        KeyBinding::new(named(NamedKey::Insert), Modifiers::SHIFT, Action::Paste),
         // Config / search
-        KeyBinding {
-            key: ch("r"),
-            mods: ctrl_shift,
-            action: Action::ReloadConfig,
-        },
-        KeyBinding {
-            key: ch("f"),
-            mods: ctrl_shift,
-            action: Action::OpenSearch,
-        },
+        KeyBinding::new(ch("r"), ctrl_shift, Action::ReloadConfig),
+        KeyBinding::new(ch("f"), ctrl_shift, Action::OpenSearch),
+        KeyBinding::new(ch("i"), ctrl_shift, Action::ToggleInspector),
+        KeyBinding::new(ch("k"), ctrl_shift, Action::ClearScreenAndScrollback),
+        KeyBinding::new(ch("a"), ctrl_shift, Action::SelectAll),
         // Zoom
-        KeyBinding {
-            key: ch("="),
-            mods: ctrl,
-            action: Action::ZoomIn,
-        },
-        KeyBinding {
-            key: ch("+"),
-            mods: ctrl,
-            action: Action::ZoomIn,
-        },
-        KeyBinding {
-            key: ch("-"),
-            mods: ctrl,
-            action: Action::ZoomOut,
-        },
-        KeyBinding {
-            key: ch("0"),
-            mods: ctrl,
-            action: Action::ZoomReset,
-        },
-        // Tabs
-        KeyBinding {
-            key: ch("t"),
-            mods: ctrl,
-            action: Action::NewTab,
-        },
-        KeyBinding {
-            key: ch("w"),
-            mods: ctrl,
-            action: Action::CloseTab,
-        },
-        KeyBinding {
-            key: named(NamedKey::Tab),
-            mods: ctrl,
-            action: Action::NextTab,
-        },
-        KeyBinding {
-            key: named(NamedKey::Tab),
-            mods: ctrl_shift,
-            action: Action::PrevTab,
-        },
-        // Scrollback
-        KeyBinding {
-            key: named(NamedKey::PageUp),
-            mods: shift,
-            action: Action::ScrollPageUp,
-        },
-        KeyBinding {
-            key: named(NamedKey::PageDown),
-            mods: shift,
-            action: Action::ScrollPageDown,
-        },
-        KeyBinding {
-            key: named(NamedKey::Home),
-            mods: shift,
-            action: Action::ScrollToTop,
-        },
-        KeyBinding {
-            key: named(NamedKey::End),
-            mods: shift,
-            action: Action::ScrollToBottom,
-        },
+        KeyBinding::new(ch("="), ctrl, Action::ZoomIn),
+        KeyBinding::new(ch("+"), ctrl, Action::ZoomIn),
+        KeyBinding::new(ch("-"), ctrl, Action::ZoomOut),
+        KeyBinding::new(ch("0"), ctrl, Action::ZoomReset),
         // Prompt navigation
-        KeyBinding {
-            key: named(NamedKey::ArrowUp),
-            mods: ctrl_shift,
-            action: Action::PreviousPrompt,
-        },
-        KeyBinding {
-            key: named(NamedKey::ArrowDown),
-            mods: ctrl_shift,
-            action: Action::NextPrompt,
-        },
+        KeyBinding::new(named(NamedKey::ArrowUp), ctrl_shift, Action::PreviousPrompt),
+        KeyBinding::new(named(NamedKey::ArrowDown), ctrl_shift, Action::NextPrompt),
+        // Tab switcher and moving
+        KeyBinding::new(ch("o"), ctrl_shift, Action::OpenTabSwitcher),
+        KeyBinding::new(named(NamedKey::PageUp), ctrl_shift, Action::MoveTabLeft),
+        KeyBinding::new(named(NamedKey::PageDown), ctrl_shift, Action::MoveTabRight),
+    ]
+}
+
+fn windows_bindings() -> Vec<KeyBinding> {
+    let ctrl = Modifiers::CONTROL;
+    let mut bindings = ctrl_shift_bindings();
+    bindings.extend([
+        KeyBinding::new(ch("t"), ctrl, Action::NewTab),
+        KeyBinding::new(ch("w"), ctrl, Action::CloseTab),
         // Smart copy/paste (Ctrl+C/V without Shift) — must come AFTER
         // Ctrl+Shift variants so those match first.
-        KeyBinding {
-            key: ch("c"),
-            mods: ctrl,
-            action: Action::SmartCopy,
-        },
-        KeyBinding {
-            key: ch("v"),
-            mods: ctrl,
-            action: Action::SmartPaste,
-        },
+        KeyBinding::new(ch("c"), ctrl, Action::SmartCopy),
+        KeyBinding::new(ch("v"), ctrl, Action::SmartPaste),
+    ]);
+    bindings
+}
+
+fn linux_bindings() -> Vec<KeyBinding> {
+    let ctrl = Modifiers::CONTROL;
+    let ctrl_shift = ctrl | Modifiers::SHIFT;
+    let mut bindings = ctrl_shift_bindings();
+    bindings.extend([
+        KeyBinding::new(ch("t"), ctrl_shift, Action::NewTab),
+        KeyBinding::new(ch("w"), ctrl_shift, Action::CloseTab),
+        KeyBinding::new(named(NamedKey::PageDown), ctrl, Action::NextTab),
+        KeyBinding::new(named(NamedKey::PageUp), ctrl, Action::PrevTab),
+    ]);
+    bindings
+}
+
+fn macos_bindings() -> Vec<KeyBinding> {
+    let cmd = Modifiers::SUPER;
+    let cmd_shift = cmd | Modifiers::SHIFT;
+    vec![
+        KeyBinding::new(ch("c"), cmd, Action::Copy),
+        KeyBinding::new(ch("v"), cmd, Action::Paste),
+        KeyBinding::new(ch("a"), cmd, Action::SelectAll),
+        KeyBinding::new(ch("f"), cmd, Action::OpenSearch),
+        KeyBinding::new(ch("g"), cmd, Action::NextMatch),
+        KeyBinding::new(ch("g"), cmd_shift, Action::PreviousMatch),
+        KeyBinding::new(ch("k"), cmd, Action::ClearScreenAndScrollback),
+        KeyBinding::new(ch("r"), cmd_shift, Action::ReloadConfig),
+        KeyBinding::new(ch("i"), cmd_shift, Action::ToggleInspector),
+        KeyBinding::new(ch("t"), cmd, Action::NewTab),
+        KeyBinding::new(ch("w"), cmd, Action::CloseTab),
+        // Cmd+Shift+] and Cmd+Shift+[ arrive as the shifted characters.
+        KeyBinding::new(ch("}"), cmd_shift, Action::NextTab),
+        KeyBinding::new(ch("{"), cmd_shift, Action::PrevTab),
+        KeyBinding::new(ch("o"), cmd_shift, Action::OpenTabSwitcher),
+        KeyBinding::new(ch("="), cmd, Action::ZoomIn),
+        KeyBinding::new(ch("+"), cmd, Action::ZoomIn),
+        KeyBinding::new(ch("-"), cmd, Action::ZoomOut),
+        KeyBinding::new(ch("0"), cmd, Action::ZoomReset),
+        KeyBinding::new(named(NamedKey::ArrowUp), cmd, Action::PreviousPrompt),
+        KeyBinding::new(named(NamedKey::ArrowDown), cmd, Action::NextPrompt),
+        KeyBinding::new(named(NamedKey::PageUp), cmd_shift, Action::MoveTabLeft),
+        KeyBinding::new(named(NamedKey::PageDown), cmd_shift, Action::MoveTabRight),
+        KeyBinding::new(named(NamedKey::Home), cmd, Action::ScrollToTop),
+        KeyBinding::new(named(NamedKey::End), cmd, Action::ScrollToBottom),
     ]
 }
@@ -1,16 +1,24 @@
 //! Keybinding system — map key+modifiers to actions.
+//!
+//! A binding may have a `prefix` of chords pressed before its key, so
+//! tmux-style sequences like Ctrl+A then C can be bound directly, and a
+//! `when` condition on the terminal state.
 
 mod defaults;
 mod parse;
 #[cfg(test)]
 mod tests;
+mod when;
+
+use std::fmt;
 
 use winit::keyboard::{Key, NamedKey};
 
 use crate::key_encoding::Modifiers;
 
-pub use defaults::default_bindings;
+pub use defaults::{KeybindProfile, default_bindings};
 pub use parse::{merge_bindings, parse_action, parse_key, parse_mods};
+pub use when::{KeyContext, When, parse_when};
 
 /// Identifies a key independent of modifiers.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -48,29 +56,158 @@ pub enum Action {
     NextPrompt,
     /// Duplicate the current tab (spawn new tab with same CWD).
     DuplicateTab,
+    /// Name the current tab, in a text field over its title.
+    RenameTab,
     /// Move the current tab into a new window.
     MoveTabToNewWindow,
-    /// Send literal bytes to the PTY.
+    /// Pin the current tab to the left of the tab bar, or unpin it.
+    TogglePinTab,
+    /// List the tabs of every window, filtered as you type, to jump to one.
+    OpenTabSwitcher,
+    /// Move the current tab one slot left in the tab bar.
+    MoveTabLeft,
+    /// Move the current tab one slot right in the tab bar.
+    MoveTabRight,
+    /// Move the current tab to the end of window `n`, counting from 1 in
+    /// the order the windows opened.
+    MoveTabToWindow(usize),
+    /// Hide the tab bar and grid padding in every window. Stands in for
+    /// pane zoom until tabs can be split.
+    ToggleFocusMode,
+    /// Show or hide the escape sequence inspector for the active tab.
+    ToggleInspector,
+    /// Send keys typed in the window to all of its tabs, or stop.
+    ToggleBroadcast,
+    /// Clear the scrollback and screen, keeping the current prompt.
+    ClearScreenAndScrollback,
+    /// Clear the scrollback, leaving the screen as it is.
+    ClearScrollback,
+    /// Select the scrollback and the screen.
+    SelectAll,
+    /// Copy the output of the most recent command (OSC 133;C to D).
+    CopyLastOutput,
+    /// Select the output of the most recent command.
+    SelectLastOutput,
+    /// Pick a recent copy to paste (`behavior.clipboard_history`).
+    PasteFromHistory,
+    /// Focus the next search match, with or without the search bar
+    /// focused. Falls through to the PTY when no search is open.
+    NextMatch,
+    /// Focus the previous search match (see `NextMatch`).
+    PreviousMatch,
+    /// Send text to the PTY, after escapes like `\e` and `\u{...}`.
     SendText(String),
+    /// Send raw bytes to the PTY, given in hex.
+    SendHex(Vec<u8>),
     /// Explicitly unbinds a default binding.
     None,
 }
 
+/// One key pressed with modifiers; a step of a chord sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyChord {
+    pub key: BindingKey,
+    pub mods: Modifiers,
+}
+
+impl fmt::Display for KeyChord {
+    /// Formats as e.g. `Ctrl+Shift+A` or `Alt+PageUp`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (flag, name) in [
+            (Modifiers::CONTROL, "Ctrl"),
+            (Modifiers::ALT, "Alt"),
+            (Modifiers::SHIFT, "Shift"),
+            (Modifiers::SUPER, "Super"),
+        ] {
+            if self.mods.contains(flag) {
+                write!(f, "{name}+")?;
+            }
+        }
+        match &self.key {
+            BindingKey::Named(key) => write!(f, "{key:?}"),
+            BindingKey::Character(c) => write!(f, "{}", c.to_uppercase()),
+        }
+    }
+}
+
 /// A resolved keybinding: key + modifiers → action.
 #[derive(Debug, Clone)]
 pub struct KeyBinding {
     pub key: BindingKey,
     pub mods: Modifiers,
     pub action: Action,
+    /// Chords that must be pressed, in order, before `key`. Empty for a
+    /// plain binding.
+    pub prefix: Vec<KeyChord>,
+    /// States the binding is limited to.
+    pub when: When,
+}
+
+impl KeyBinding {
+    /// A plain, unconditional binding.
+    pub fn new(key: BindingKey, mods: Modifiers, action: Action) -> Self {
+        Self {
+            key,
+            mods,
+            action,
+            prefix: Vec::new(),
+            when: When::default(),
+        }
+    }
+
+    /// Whether this binding's full sequence is `pending` followed by `chord`.
+    fn completes(&self, pending: &[KeyChord], chord: &KeyChord) -> bool {
+        self.prefix == pending && self.key == chord.key && self.mods == chord.mods
+    }
+
+    /// Whether `pending` followed by `chord` is a proper start of this
+    /// binding's sequence.
+    fn continues(&self, pending: &[KeyChord], chord: &KeyChord) -> bool {
+        self.prefix.len() > pending.len()
+            && self.prefix.starts_with(pending)
+            && self.prefix[pending.len()] == *chord
+    }
 }
 
 /// TOML-serializable keybinding entry.
+///
+/// ```toml
+/// [[keybind]]
+/// prefix = [{ key = "a", mods = "Ctrl" }]
+/// key = "c"
+/// action = "NewTab"
+/// ```
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct KeybindConfig {
     pub key: String,
     #[serde(default)]
     pub mods: String,
     pub action: String,
+    /// Chords pressed before `key`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prefix: Vec<KeyChordConfig>,
+    /// Condition on the terminal state (see [`parse_when`]).
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub when: String,
+}
+
+/// One chord of a [`KeybindConfig`] prefix.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct KeyChordConfig {
+    pub key: String,
+    #[serde(default)]
+    pub mods: String,
+}
+
+/// What a key press means, given the chords pressed before it.
+#[derive(Debug, PartialEq, Eq)]
+pub enum KeyMatch<'a> {
+    /// The press completes a binding.
+    Action(&'a Action),
+    /// The press starts or continues a chord sequence; wait for the next key.
+    Pending,
+    /// Nothing is bound to the sequence.
+    None,
 }
 
 /// Convert a winit `Key` to a `BindingKey`, normalizing characters to lowercase.
@@ -89,17 +226,36 @@ pub fn key_to_binding_key(key: &Key) -> Option<BindingKey> {
     }
 }
 
-/// Find the first binding matching the given key and modifiers.
-pub fn find_binding<'a>(
+/// Whether `key` is one of `names`, written as in `[[keybind]]`.
+pub fn key_in_list(key: &Key, names: &[String]) -> bool {
+    let Some(key) = key_to_binding_key(key) else {
+        return false;
+    };
+    names
+        .iter()
+        .filter_map(|name| parse_key(name))
+        .any(|k| k == key)
+}
+
+/// Match `chord` after the `pending` chords of a sequence in progress,
+/// considering only bindings whose `when` holds in `ctx`.
+///
+/// A chord that starts a longer sequence waits for the rest even if a
+/// shorter binding uses the same keys, so a prefix like Ctrl+Shift+T takes
+/// over from the binding it shadows. Likewise a conditional binding wins
+/// over an unconditional one for the same keys.
+pub fn match_key<'a>(
     bindings: &'a [KeyBinding],
-    key: &BindingKey,
-    mods: Modifiers,
-) -> Option<&'a Action> {
-    bindings.iter().find_map(|b| {
-        if b.key == *key && b.mods == mods {
-            Some(&b.action)
-        } else {
-            None
-        }
-    })
+    pending: &[KeyChord],
+    chord: &KeyChord,
+    ctx: KeyContext,
+) -> KeyMatch<'a> {
+    let live = || bindings.iter().filter(move |b| b.when.matches(ctx));
+    if live().any(|b| b.continues(pending, chord)) {
+        return KeyMatch::Pending;
+    }
+    live()
+        .filter(|b| b.completes(pending, chord))
+        .min_by_key(|b| b.when.is_always())
+        .map_or(KeyMatch::None, |b| KeyMatch::Action(&b.action))
 }
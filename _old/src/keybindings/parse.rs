@@ -4,37 +4,69 @@ use winit::keyboard::NamedKey;
 
 use crate::key_encoding::Modifiers;
 
-use super::{Action, BindingKey, KeyBinding, KeybindConfig};
-use super::defaults::default_bindings;
+use super::defaults::{KeybindProfile, default_bindings};
+use super::{Action, BindingKey, KeyBinding, KeyChord, KeybindConfig, parse_when};
 
-/// Merge user keybinding overrides with defaults. User bindings replace
-/// defaults that share the same (key, mods). `Action::None` removes a binding.
-pub fn merge_bindings(user: &[KeybindConfig]) -> Vec<KeyBinding> {
-    let mut bindings = default_bindings();
+/// Merge user keybinding overrides with the defaults of `profile`. User
+/// bindings replace defaults that share the same (prefix, key, mods, when).
+/// `Action::None` removes a binding.
+pub fn merge_bindings(user: &[KeybindConfig], profile: KeybindProfile) -> Vec<KeyBinding> {
+    let mut bindings = default_bindings(profile);
 
     for cfg in user {
         let Some(key) = parse_key(&cfg.key) else {
             crate::log(&format!("keybindings: unknown key {:?}", cfg.key));
             continue;
         };
+        let Some(prefix) = parse_prefix(cfg) else {
+            continue;
+        };
+        let Some(when) = parse_when(&cfg.when) else {
+            crate::log(&format!("keybindings: unknown condition {:?}", cfg.when));
+            continue;
+        };
         let mods = parse_mods(&cfg.mods);
         let Some(action) = parse_action(&cfg.action) else {
             crate::log(&format!("keybindings: unknown action {:?}", cfg.action));
             continue;
         };
 
-        // Remove any existing binding with the same key+mods.
-        bindings.retain(|b| !(b.key == key && b.mods == mods));
+        // Remove any existing binding with the same sequence.
+        bindings
+            .retain(|b| !(b.key == key && b.mods == mods && b.prefix == prefix && b.when == when));
 
         // Action::None means "unbind" — don't add a replacement.
         if action != Action::None {
-            bindings.push(KeyBinding { key, mods, action });
+            bindings.push(KeyBinding {
+                key,
+                mods,
+                action,
+                prefix,
+                when,
+            });
         }
     }
 
     bindings
 }
 
+/// Parse the prefix chords of `cfg`, logging the first unknown key.
+fn parse_prefix(cfg: &KeybindConfig) -> Option<Vec<KeyChord>> {
+    cfg.prefix
+        .iter()
+        .map(|c| {
+            let Some(key) = parse_key(&c.key) else {
+                crate::log(&format!("keybindings: unknown prefix key {:?}", c.key));
+                return None;
+            };
+            Some(KeyChord {
+                key,
+                mods: parse_mods(&c.mods),
+            })
+        })
+        .collect()
+}
+
 /// Parse a key string from TOML config. Single characters are lowercased.
 /// Named keys: Tab, `PageUp`, `PageDown`, Home, End, Insert,
 /// Delete, Escape, Enter, Backspace, Space, F1–F24.
@@ -116,11 +148,26 @@ pub fn parse_mods(s: &str) -> Modifiers {
     mods
 }
 
-/// Parse an action string. Supports `SendText:...` for literal text with
-/// escape sequences (`\x1b`, `\n`, `\r`, `\t`, `\\`).
+/// Parse an action string.
+///
+/// Supports `SendText:...` for text with escape sequences (see
+/// [`unescape_send_text`]) and `SendHex:...` for raw bytes as pairs of hex
+/// digits, optionally separated by spaces (`1b 5b 41`), and
+/// `MoveTabToWindow:n` for window `n` counting from 1.
 pub fn parse_action(s: &str) -> Option<Action> {
     if let Some(text) = s.strip_prefix("SendText:") {
-        return Some(Action::SendText(unescape_send_text(text)));
+        return unescape_send_text(text).map(Action::SendText);
+    }
+    if let Some(hex) = s.strip_prefix("SendHex:") {
+        return parse_hex_bytes(hex).map(Action::SendHex);
+    }
+    if let Some(n) = s.strip_prefix("MoveTabToWindow:") {
+        return n
+            .trim()
+            .parse()
+            .ok()
+            .filter(|&n| n > 0)
+            .map(Action::MoveTabToWindow);
     }
 
     Some(match s {
@@ -144,41 +191,82 @@ pub fn parse_action(s: &str) -> Option<Action> {
         "PreviousPrompt" => Action::PreviousPrompt,
         "NextPrompt" => Action::NextPrompt,
         "DuplicateTab" => Action::DuplicateTab,
+        "RenameTab" => Action::RenameTab,
         "MoveTabToNewWindow" => Action::MoveTabToNewWindow,
+        "TogglePinTab" => Action::TogglePinTab,
+        "OpenTabSwitcher" => Action::OpenTabSwitcher,
+        "MoveTabLeft" => Action::MoveTabLeft,
+        "MoveTabRight" => Action::MoveTabRight,
+        "ToggleFocusMode" => Action::ToggleFocusMode,
+        "ToggleInspector" => Action::ToggleInspector,
+        "ToggleBroadcast" => Action::ToggleBroadcast,
+        "ClearScreenAndScrollback" => Action::ClearScreenAndScrollback,
+        "ClearScrollback" => Action::ClearScrollback,
+        "SelectAll" => Action::SelectAll,
+        "CopyLastOutput" => Action::CopyLastOutput,
+        "SelectLastOutput" => Action::SelectLastOutput,
+        "PasteFromHistory" => Action::PasteFromHistory,
+        "NextMatch" => Action::NextMatch,
+        "PreviousMatch" => Action::PreviousMatch,
         "None" => Action::None,
         _ => return None,
     })
 }
 
-/// Process escape sequences in `SendText` values:
-/// `\x1b` → ESC, `\n` → newline, `\r` → CR, `\t` → tab, `\\` → backslash.
-pub(super) fn unescape_send_text(s: &str) -> String {
+/// Process escape sequences in `SendText` values.
+///
+/// `\e` → ESC, `\xHH` → the character U+00HH, `\u{H...}` → any character,
+/// `\n` → newline, `\r` → CR, `\t` → tab, `\\` → backslash. Other escapes
+/// stay as written. Returns `None` for a malformed `\x` or `\u` escape.
+pub(super) fn unescape_send_text(s: &str) -> Option<String> {
     let mut out = String::with_capacity(s.len());
     let mut chars = s.chars();
     while let Some(c) = chars.next() {
-        if c == '\\' {
-            match chars.next() {
-                Some('x') => {
-                    // Parse \xHH hex escape.
-                    let hi = chars.next().unwrap_or('0');
-                    let lo = chars.next().unwrap_or('0');
-                    let hex: String = [hi, lo].iter().collect();
-                    if let Ok(byte) = u8::from_str_radix(&hex, 16) {
-                        out.push(byte as char);
-                    }
-                }
-                Some('n') => out.push('\n'),
-                Some('r') => out.push('\r'),
-                Some('t') => out.push('\t'),
-                Some('\\') | None => out.push('\\'),
-                Some(other) => {
-                    out.push('\\');
-                    out.push(other);
-                }
-            }
-        } else {
+        if c != '\\' {
             out.push(c);
+            continue;
         }
+        match chars.next() {
+            Some('x') => {
+                let rest = chars.as_str();
+                out.push(char::from(u8::try_from(hex_value(rest.get(..2)?)?).ok()?));
+                chars = rest.get(2..)?.chars();
+            }
+            Some('u') => {
+                let (hex, rest) = chars.as_str().strip_prefix('{')?.split_once('}')?;
+                out.push(char::from_u32(hex_value(hex)?)?);
+                chars = rest.chars();
+            }
+            Some('e') => out.push('\x1b'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('t') => out.push('\t'),
+            Some('\\') | None => out.push('\\'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+        }
+    }
+    Some(out)
+}
+
+/// Parse `SendHex` digits: whitespace-separated or run-together pairs.
+fn parse_hex_bytes(s: &str) -> Option<Vec<u8>> {
+    let digits: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+    if digits.is_empty() || !digits.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::try_from(hex_value(digits.get(i..i + 2)?)?).ok())
+        .collect()
+}
+
+/// The value of a non-empty run of hex digits.
+fn hex_value(digits: &str) -> Option<u32> {
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
     }
-    out
+    u32::from_str_radix(digits, 16).ok()
 }
@@ -6,16 +6,99 @@ use crate::key_encoding::Modifiers;
 
 use super::*;
 
+/// The action of the plain binding for `key` with `mods`, if any.
+fn find_binding<'a>(
+    bindings: &'a [KeyBinding],
+    key: &BindingKey,
+    mods: Modifiers,
+) -> Option<&'a Action> {
+    let chord = KeyChord {
+        key: key.clone(),
+        mods,
+    };
+    match match_key(bindings, &[], &chord, KeyContext::empty()) {
+        KeyMatch::Action(action) => Some(action),
+        KeyMatch::Pending | KeyMatch::None => None,
+    }
+}
+
 #[test]
 fn default_bindings_not_empty() {
-    let bindings = default_bindings();
+    let bindings = default_bindings(KeybindProfile::Windows);
     assert!(!bindings.is_empty());
     assert!(bindings.len() >= 20);
 }
 
+#[test]
+fn profiles_bind_platform_modifiers() {
+    let c = BindingKey::Character("c".to_owned());
+    let t = BindingKey::Character("t".to_owned());
+    let ctrl_shift = Modifiers::CONTROL | Modifiers::SHIFT;
+
+    // Linux leaves Ctrl+C and Ctrl+T to the shell.
+    let linux = default_bindings(KeybindProfile::Linux);
+    assert_eq!(find_binding(&linux, &c, Modifiers::CONTROL), None);
+    assert_eq!(find_binding(&linux, &t, Modifiers::CONTROL), None);
+    assert_eq!(find_binding(&linux, &c, ctrl_shift), Some(&Action::Copy));
+    assert_eq!(find_binding(&linux, &t, ctrl_shift), Some(&Action::NewTab));
+
+    // macOS binds Cmd and leaves every Ctrl chord alone.
+    let macos = default_bindings(KeybindProfile::Macos);
+    assert_eq!(
+        find_binding(&macos, &c, Modifiers::SUPER),
+        Some(&Action::Copy)
+    );
+    assert_eq!(
+        find_binding(&macos, &t, Modifiers::SUPER),
+        Some(&Action::NewTab)
+    );
+    assert!(
+        macos
+            .iter()
+            .all(|b| !b.mods.contains(Modifiers::CONTROL)
+                || b.key == BindingKey::Named(NamedKey::Tab))
+    );
+}
+
+#[test]
+fn tab_switcher_leaves_tab_cycling_alone() {
+    let tab = BindingKey::Named(NamedKey::Tab);
+    let o = BindingKey::Character("o".to_owned());
+    let page_up = BindingKey::Named(NamedKey::PageUp);
+    let ctrl_shift = Modifiers::CONTROL | Modifiers::SHIFT;
+    for profile in [KeybindProfile::Windows, KeybindProfile::Linux] {
+        let bindings = default_bindings(profile);
+        assert_eq!(
+            find_binding(&bindings, &tab, ctrl_shift),
+            Some(&Action::PrevTab)
+        );
+        assert_eq!(
+            find_binding(&bindings, &o, ctrl_shift),
+            Some(&Action::OpenTabSwitcher)
+        );
+    }
+    // Ctrl+PageUp cycles tabs only on Linux; Windows leaves it to the shell.
+    let linux = default_bindings(KeybindProfile::Linux);
+    let windows = default_bindings(KeybindProfile::Windows);
+    assert_eq!(
+        find_binding(&linux, &page_up, Modifiers::CONTROL),
+        Some(&Action::PrevTab)
+    );
+    assert_eq!(find_binding(&windows, &page_up, Modifiers::CONTROL), None);
+}
+
+#[test]
+fn auto_profile_resolves_to_a_platform() {
+    assert_ne!(KeybindProfile::Auto.resolve(), KeybindProfile::Auto);
+    assert_eq!(KeybindProfile::Macos.resolve(), KeybindProfile::Macos);
+    let auto = default_bindings(KeybindProfile::Auto);
+    let resolved = default_bindings(KeybindProfile::Auto.resolve());
+    assert_eq!(auto.len(), resolved.len());
+}
+
 #[test]
 fn find_binding_ctrl_t() {
-    let bindings = default_bindings();
+    let bindings = default_bindings(KeybindProfile::Windows);
     let key = BindingKey::Character("t".to_owned());
     let action = find_binding(&bindings, &key, Modifiers::CONTROL);
     assert_eq!(action, Some(&Action::NewTab));
@@ -23,7 +106,7 @@ fn find_binding_ctrl_t() {
 
 #[test]
 fn find_binding_no_match() {
-    let bindings = default_bindings();
+    let bindings = default_bindings(KeybindProfile::Windows);
     let key = BindingKey::Character("z".to_owned());
     let action = find_binding(&bindings, &key, Modifiers::CONTROL);
     assert_eq!(action, None);
@@ -35,8 +118,10 @@ fn merge_user_override() {
         key: "t".to_owned(),
         mods: "Ctrl".to_owned(),
         action: "CloseTab".to_owned(),
+        prefix: Vec::new(),
+        when: String::new(),
     }];
-    let bindings = merge_bindings(&user);
+    let bindings = merge_bindings(&user, KeybindProfile::Windows);
     let key = BindingKey::Character("t".to_owned());
     let action = find_binding(&bindings, &key, Modifiers::CONTROL);
     assert_eq!(action, Some(&Action::CloseTab));
@@ -48,8 +133,10 @@ fn merge_user_unbind() {
         key: "t".to_owned(),
         mods: "Ctrl".to_owned(),
         action: "None".to_owned(),
+        prefix: Vec::new(),
+        when: String::new(),
     }];
-    let bindings = merge_bindings(&user);
+    let bindings = merge_bindings(&user, KeybindProfile::Windows);
     let key = BindingKey::Character("t".to_owned());
     let action = find_binding(&bindings, &key, Modifiers::CONTROL);
     assert_eq!(action, None);
@@ -61,8 +148,10 @@ fn merge_preserves_unaffected() {
         key: "t".to_owned(),
         mods: "Ctrl".to_owned(),
         action: "None".to_owned(),
+        prefix: Vec::new(),
+        when: String::new(),
     }];
-    let bindings = merge_bindings(&user);
+    let bindings = merge_bindings(&user, KeybindProfile::Windows);
     // Ctrl+W should still be CloseTab.
     let key = BindingKey::Character("w".to_owned());
     let action = find_binding(&bindings, &key, Modifiers::CONTROL);
@@ -92,11 +181,53 @@ fn parse_key_variants() {
     assert_eq!(parse_key("F1"), Some(BindingKey::Named(NamedKey::F1)));
 }
 
+#[test]
+fn key_in_list_matches_named_and_character_keys() {
+    let names = vec!["Escape".to_owned(), "X".to_owned(), "Bogus".to_owned()];
+    assert!(key_in_list(&Key::Named(NamedKey::Escape), &names));
+    assert!(key_in_list(&Key::Character("x".into()), &names));
+    assert!(!key_in_list(&Key::Named(NamedKey::Enter), &names));
+    assert!(!key_in_list(&Key::Named(NamedKey::Escape), &[]));
+}
+
 #[test]
 fn parse_action_variants() {
     assert_eq!(parse_action("Copy"), Some(Action::Copy));
     assert_eq!(parse_action("Paste"), Some(Action::Paste));
     assert_eq!(parse_action("NewTab"), Some(Action::NewTab));
+    assert_eq!(parse_action("RenameTab"), Some(Action::RenameTab));
+    assert_eq!(
+        parse_action("ToggleFocusMode"),
+        Some(Action::ToggleFocusMode)
+    );
+    assert_eq!(
+        parse_action("ToggleInspector"),
+        Some(Action::ToggleInspector)
+    );
+    assert_eq!(
+        parse_action("ToggleBroadcast"),
+        Some(Action::ToggleBroadcast)
+    );
+    assert_eq!(
+        parse_action("ClearScreenAndScrollback"),
+        Some(Action::ClearScreenAndScrollback)
+    );
+    assert_eq!(
+        parse_action("ClearScrollback"),
+        Some(Action::ClearScrollback)
+    );
+    assert_eq!(parse_action("SelectAll"), Some(Action::SelectAll));
+    assert_eq!(parse_action("CopyLastOutput"), Some(Action::CopyLastOutput));
+    assert_eq!(
+        parse_action("SelectLastOutput"),
+        Some(Action::SelectLastOutput)
+    );
+    assert_eq!(
+        parse_action("PasteFromHistory"),
+        Some(Action::PasteFromHistory)
+    );
+    assert_eq!(parse_action("NextMatch"), Some(Action::NextMatch));
+    assert_eq!(parse_action("PreviousMatch"), Some(Action::PreviousMatch));
     assert_eq!(parse_action("None"), Some(Action::None));
     assert_eq!(
         parse_action("SendText:\\x1b[A"),
@@ -115,7 +246,7 @@ fn key_normalization() {
 
 #[test]
 fn smart_copy_distinct_from_copy() {
-    let bindings = default_bindings();
+    let bindings = default_bindings(KeybindProfile::Windows);
     let key = BindingKey::Character("c".to_owned());
 
     // Ctrl+C → SmartCopy
@@ -130,7 +261,202 @@ fn smart_copy_distinct_from_copy() {
 #[test]
 fn unescape_sequences() {
     use super::parse::unescape_send_text;
-    assert_eq!(unescape_send_text("\\x1b[15~"), "\x1b[15~");
-    assert_eq!(unescape_send_text("a\\nb"), "a\nb");
-    assert_eq!(unescape_send_text("\\r\\t\\\\"), "\r\t\\");
+    let unescape = |s| unescape_send_text(s).unwrap_or_default();
+    assert_eq!(unescape("\\x1b[15~"), "\x1b[15~");
+    assert_eq!(unescape("a\\nb"), "a\nb");
+    assert_eq!(unescape("\\r\\t\\\\"), "\r\t\\");
+    assert_eq!(unescape("\\e[1m\\u{1F600}"), "\x1b[1m\u{1F600}");
+    assert_eq!(unescape("\\q"), "\\q");
+    // Malformed escapes reject the whole action.
+    assert_eq!(unescape_send_text("\\x1"), None);
+    assert_eq!(unescape_send_text("\\u{110000}"), None);
+    assert_eq!(unescape_send_text("\\u{41"), None);
+}
+
+#[test]
+fn send_hex_bytes() {
+    assert_eq!(
+        parse_action("SendHex:1b 5b 41"),
+        Some(Action::SendHex(vec![0x1b, 0x5b, 0x41]))
+    );
+    assert_eq!(
+        parse_action("SendHex:ff00"),
+        Some(Action::SendHex(vec![0xff, 0]))
+    );
+    assert_eq!(parse_action("SendHex:1b5"), None);
+    assert_eq!(parse_action("SendHex:zz"), None);
+    assert_eq!(parse_action("SendHex:"), None);
+}
+
+#[test]
+fn move_tab_actions() {
+    assert_eq!(parse_action("MoveTabLeft"), Some(Action::MoveTabLeft));
+    assert_eq!(
+        parse_action("OpenTabSwitcher"),
+        Some(Action::OpenTabSwitcher)
+    );
+    assert_eq!(parse_action("MoveTabRight"), Some(Action::MoveTabRight));
+    assert_eq!(parse_action("TogglePinTab"), Some(Action::TogglePinTab));
+    assert_eq!(
+        parse_action("MoveTabToWindow:2"),
+        Some(Action::MoveTabToWindow(2))
+    );
+    assert_eq!(parse_action("MoveTabToWindow:0"), None);
+    assert_eq!(parse_action("MoveTabToWindow:x"), None);
+}
+
+#[test]
+fn chord_prefix_waits_for_the_next_key() {
+    let user = vec![KeybindConfig {
+        key: "c".to_owned(),
+        mods: String::new(),
+        action: "NewTab".to_owned(),
+        prefix: vec![KeyChordConfig {
+            key: "a".to_owned(),
+            mods: "Ctrl".to_owned(),
+        }],
+        when: String::new(),
+    }];
+    let bindings = merge_bindings(&user, KeybindProfile::Windows);
+    let ctrl_a = KeyChord {
+        key: BindingKey::Character("a".to_owned()),
+        mods: Modifiers::CONTROL,
+    };
+    let c = KeyChord {
+        key: BindingKey::Character("c".to_owned()),
+        mods: Modifiers::empty(),
+    };
+
+    assert_eq!(
+        match_key(&bindings, &[], &ctrl_a, KeyContext::empty()),
+        KeyMatch::Pending
+    );
+    assert_eq!(
+        match_key(
+            &bindings,
+            std::slice::from_ref(&ctrl_a),
+            &c,
+            KeyContext::empty()
+        ),
+        KeyMatch::Action(&Action::NewTab)
+    );
+    // The second key alone and other keys after the prefix are unbound.
+    assert_eq!(
+        match_key(&bindings, &[], &c, KeyContext::empty()),
+        KeyMatch::None
+    );
+    assert_eq!(
+        match_key(
+            &bindings,
+            std::slice::from_ref(&ctrl_a),
+            &ctrl_a,
+            KeyContext::empty()
+        ),
+        KeyMatch::None
+    );
+    assert_eq!(ctrl_a.to_string(), "Ctrl+A");
+}
+
+#[test]
+fn chord_prefix_shadows_a_plain_binding() {
+    let user = vec![KeybindConfig {
+        key: "Tab".to_owned(),
+        mods: String::new(),
+        action: "NextTab".to_owned(),
+        prefix: vec![KeyChordConfig {
+            key: "t".to_owned(),
+            mods: "Ctrl".to_owned(),
+        }],
+        when: String::new(),
+    }];
+    let bindings = merge_bindings(&user, KeybindProfile::Windows);
+    let ctrl_t = KeyChord {
+        key: BindingKey::Character("t".to_owned()),
+        mods: Modifiers::CONTROL,
+    };
+    assert_eq!(
+        match_key(&bindings, &[], &ctrl_t, KeyContext::empty()),
+        KeyMatch::Pending
+    );
+
+    // Unbinding the chord restores the plain binding.
+    let mut unbind = user.clone();
+    unbind[0].action = "None".to_owned();
+    let both = [user[0].clone(), unbind[0].clone()];
+    let bindings = merge_bindings(&both, KeybindProfile::Windows);
+    assert_eq!(
+        match_key(&bindings, &[], &ctrl_t, KeyContext::empty()),
+        KeyMatch::Action(&Action::NewTab)
+    );
+}
+
+#[test]
+fn when_limits_a_binding_to_a_screen() {
+    let user = vec![KeybindConfig {
+        key: "PageUp".to_owned(),
+        mods: String::new(),
+        action: "ScrollPageUp".to_owned(),
+        prefix: Vec::new(),
+        when: "!alt_screen".to_owned(),
+    }];
+    let bindings = merge_bindings(&user, KeybindProfile::Windows);
+    let page_up = KeyChord {
+        key: BindingKey::Named(NamedKey::PageUp),
+        mods: Modifiers::empty(),
+    };
+    assert_eq!(
+        match_key(&bindings, &[], &page_up, KeyContext::empty()),
+        KeyMatch::Action(&Action::ScrollPageUp)
+    );
+    assert_eq!(
+        match_key(&bindings, &[], &page_up, KeyContext::ALT_SCREEN),
+        KeyMatch::None
+    );
+}
+
+#[test]
+fn search_context_only_matches_search_bindings() {
+    let user = vec![KeybindConfig {
+        key: "t".to_owned(),
+        mods: "Ctrl".to_owned(),
+        action: "CloseTab".to_owned(),
+        prefix: Vec::new(),
+        when: "search".to_owned(),
+    }];
+    let bindings = merge_bindings(&user, KeybindProfile::Windows);
+    let ctrl_t = KeyChord {
+        key: BindingKey::Character("t".to_owned()),
+        mods: Modifiers::CONTROL,
+    };
+    // The conditional binding wins where it applies; the default stays
+    // outside search.
+    assert_eq!(
+        match_key(&bindings, &[], &ctrl_t, KeyContext::SEARCH),
+        KeyMatch::Action(&Action::CloseTab)
+    );
+    assert_eq!(
+        match_key(&bindings, &[], &ctrl_t, KeyContext::empty()),
+        KeyMatch::Action(&Action::NewTab)
+    );
+    let ctrl_w = KeyChord {
+        key: BindingKey::Character("w".to_owned()),
+        mods: Modifiers::CONTROL,
+    };
+    assert_eq!(
+        match_key(&bindings, &[], &ctrl_w, KeyContext::SEARCH),
+        KeyMatch::None
+    );
+}
+
+#[test]
+fn parse_when_conditions() {
+    assert_eq!(parse_when(""), Some(When::default()));
+    assert_eq!(
+        parse_when("selection && !alt_screen"),
+        Some(When {
+            all: KeyContext::SELECTION,
+            none: KeyContext::ALT_SCREEN,
+        })
+    );
+    assert_eq!(parse_when("copy_mode"), None);
 }
@@ -0,0 +1,71 @@
+//! `when` conditions that limit a binding to some terminal states.
+//!
+//! ```toml
+//! [[keybind]]
+//! key = "PageUp"
+//! action = "ScrollPageUp"
+//! when = "!alt_screen"
+//! ```
+
+use bitflags::bitflags;
+
+bitflags! {
+    /// The states a key press happens in.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct KeyContext: u8 {
+        /// The active tab shows the alternate screen.
+        const ALT_SCREEN = 0b001;
+        /// The active tab has a selection.
+        const SELECTION  = 0b010;
+        /// The search bar has keyboard focus. Only bindings whose `when`
+        /// requires `search` apply; other keys edit the query.
+        const SEARCH     = 0b100;
+    }
+}
+
+/// A binding's condition: states that must all hold and states that must
+/// all be absent. The default always holds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct When {
+    pub all: KeyContext,
+    pub none: KeyContext,
+}
+
+impl When {
+    /// Whether a binding with this condition applies in `ctx`.
+    pub fn matches(self, ctx: KeyContext) -> bool {
+        if ctx.contains(KeyContext::SEARCH) && !self.all.contains(KeyContext::SEARCH) {
+            return false;
+        }
+        ctx.contains(self.all) && !ctx.intersects(self.none)
+    }
+
+    /// Whether this condition holds everywhere.
+    pub fn is_always(self) -> bool {
+        self == Self::default()
+    }
+}
+
+/// Parse a `when` string: state names joined by `&&`, each optionally
+/// negated with `!`, e.g. `"selection && !alt_screen"`. Empty means always.
+pub fn parse_when(s: &str) -> Option<When> {
+    let mut when = When::default();
+    for term in s.split("&&").map(str::trim).filter(|t| !t.is_empty()) {
+        let (negated, name) = match term.strip_prefix('!') {
+            Some(name) => (true, name.trim()),
+            None => (false, term),
+        };
+        let state = match name {
+            "alt_screen" => KeyContext::ALT_SCREEN,
+            "selection" => KeyContext::SELECTION,
+            "search" => KeyContext::SEARCH,
+            _ => return None,
+        };
+        if negated {
+            when.none |= state;
+        } else {
+            when.all |= state;
+        }
+    }
+    Some(when)
+}
@@ -1,32 +1,46 @@
 //! GPU-accelerated terminal emulator library.
 
 pub mod app;
+pub mod badge;
+pub mod bidi;
 pub mod cell;
 pub mod clipboard;
+pub mod clipboard_history;
+pub mod compose;
 pub mod config;
 pub mod context_menu;
+pub mod desktop_notify;
+pub mod doctor;
 pub mod drag;
+pub mod embed;
 pub mod font;
 pub mod gpu;
 pub mod grid;
+pub mod hyperlink;
 pub mod icons;
+pub mod ipc;
 pub mod key_encoding;
 pub mod keybindings;
 pub mod palette;
 pub mod render;
+pub mod scrollbar;
 pub mod search;
 pub mod selection;
+pub mod session;
 pub mod shell_integration;
 pub mod sync;
 pub mod tab;
 pub mod tab_bar;
 pub mod term_handler;
 pub mod term_mode;
+pub mod ui;
 pub mod url_detect;
 pub mod window;
 
 #[cfg(target_os = "windows")]
 pub mod platform_windows;
+#[cfg(target_os = "windows")]
+pub mod taskbar;
 
 use std::io::Write;
 
@@ -2,9 +2,14 @@
 
 #![windows_subsystem = "windows"]
 
+use ori_term::app::LaunchMode;
+
 fn main() {
     let args: Vec<String> = std::env::args().collect();
 
+    #[cfg(target_os = "windows")]
+    let has_console = prints_to_console(&args) && attach_console();
+
     if args.iter().any(|a| a == "--print-config") {
         let config = ori_term::config::Config::default();
         match toml::to_string_pretty(&config) {
@@ -22,19 +27,88 @@ fn main() {
         return;
     }
 
+    if args.get(1).is_some_and(|a| a == "cli") {
+        std::process::exit(ori_term::ipc::client::run(&args[2..]));
+    }
+
+    if args.get(1).is_some_and(|a| a == "doctor") {
+        let report = ori_term::doctor::report();
+        // Started from Explorer or a shortcut there is no console to print
+        // to, so the report goes to a file instead.
+        #[cfg(target_os = "windows")]
+        if !has_console {
+            let _ = std::fs::write(DOCTOR_REPORT, report);
+            return;
+        }
+        print!("{report}");
+        return;
+    }
+
     if args.iter().any(|a| a == "--help" || a == "-h") {
         println!("oriterm {}", env!("CARGO_PKG_VERSION"));
         println!("A GPU-accelerated terminal emulator\n");
         println!("USAGE:");
-        println!("    oriterm [OPTIONS]\n");
+        println!("    oriterm [OPTIONS]");
+        println!("    oriterm doctor    Print a diagnostic report (GPU, fonts, config, shell)");
+        println!("    oriterm cli get-text [--start-line N] [--end-line M] [--ansi]");
+        println!("                      Print lines of the running terminal's active tab");
+        println!("    oriterm cli get-cursor");
+        println!("                      Print the cursor's line and column");
+        println!("    oriterm cli get-stats");
+        println!("                      Print PTY throughput, parse load, and echo latency\n");
         println!("OPTIONS:");
+        println!("    --safe            Start with default config, no transparency or blur,");
+        println!("                      and no shell integration");
+        println!("    --class <NAME>    Window class / app ID (overrides window.app_id)");
         println!("    --print-config    Print the default configuration to stdout");
         println!("    --version, -V     Print version information");
         println!("    --help, -h        Print this help message");
         return;
     }
 
-    if let Err(e) = ori_term::app::App::run() {
+    let mode = if args.iter().any(|a| a == "--safe") {
+        LaunchMode::Safe
+    } else {
+        LaunchMode::Normal
+    };
+    if let Err(e) = ori_term::app::App::run(mode, class_arg(&args)) {
         let _ = std::fs::write("oriterm_error.log", format!("{e:?}"));
     }
 }
+
+/// Where `oriterm doctor` writes its report when there is no console.
+#[cfg(target_os = "windows")]
+const DOCTOR_REPORT: &str = "oriterm_doctor.txt";
+
+/// Whether `args` ask for output rather than a terminal window.
+#[cfg(target_os = "windows")]
+fn prints_to_console(args: &[String]) -> bool {
+    matches!(args.get(1).map(String::as_str), Some("cli" | "doctor"))
+        || args
+            .iter()
+            .any(|a| matches!(a.as_str(), "--print-config" | "--version" | "-V" | "--help" | "-h"))
+}
+
+/// Attach to the console of the process that started oriterm, so `print!`
+/// reaches it: a GUI subsystem binary starts without one. Returns `false`
+/// if there is no such console.
+#[cfg(target_os = "windows")]
+fn attach_console() -> bool {
+    use windows_sys::Win32::System::Console::{ATTACH_PARENT_PROCESS, AttachConsole};
+    // SAFETY: AttachConsole has no preconditions; it fails harmlessly
+    // when the parent has no console.
+    #[allow(unsafe_code)]
+    let attached = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+    attached != 0
+}
+
+/// The value of `--class NAME` or `--class=NAME`.
+fn class_arg(args: &[String]) -> Option<String> {
+    args.iter().enumerate().find_map(|(i, a)| {
+        if a == "--class" {
+            args.get(i + 1).cloned()
+        } else {
+            a.strip_prefix("--class=").map(str::to_owned)
+        }
+    })
+}
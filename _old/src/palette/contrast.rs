@@ -0,0 +1,101 @@
+//! WCAG 2.0 contrast audit of the 16 ANSI colors against the background.
+
+use vte::ansi::Rgb;
+
+use super::Palette;
+
+/// WCAG AA minimum for normal-size text.
+pub const WCAG_AA: f32 = 4.5;
+
+/// WCAG AA minimum for large text. Below this a color is hard to read at
+/// any size.
+pub const WCAG_AA_LARGE: f32 = 3.0;
+
+/// Names of the 16 ANSI colors, by index.
+pub const ANSI_NAMES: [&str; 16] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "cyan",
+    "white",
+    "bright black",
+    "bright red",
+    "bright green",
+    "bright yellow",
+    "bright blue",
+    "bright magenta",
+    "bright cyan",
+    "bright white",
+];
+
+/// How readable a color is as text on the background.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Readability {
+    /// Meets WCAG AA for normal text.
+    Good,
+    /// Meets WCAG AA for large text only.
+    LargeOnly,
+    /// Fails WCAG AA even for large text.
+    Unreadable,
+}
+
+/// One ANSI color's contrast against the scheme background.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContrastEntry {
+    /// ANSI color index (0–15).
+    pub index: usize,
+    /// The color as configured.
+    pub color: Rgb,
+    /// WCAG contrast ratio against the default background (1–21).
+    pub ratio: f32,
+}
+
+impl ContrastEntry {
+    /// The WCAG rating for this entry's ratio.
+    pub fn readability(&self) -> Readability {
+        if self.ratio >= WCAG_AA {
+            Readability::Good
+        } else if self.ratio >= WCAG_AA_LARGE {
+            Readability::LargeOnly
+        } else {
+            Readability::Unreadable
+        }
+    }
+}
+
+/// WCAG relative luminance of an sRGB color.
+pub fn relative_luminance(c: Rgb) -> f32 {
+    let channel = |v: u8| {
+        let v = f32::from(v) / 255.0;
+        if v <= 0.04045 {
+            v / 12.92
+        } else {
+            ((v + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(c.r) + 0.7152 * channel(c.g) + 0.0722 * channel(c.b)
+}
+
+/// WCAG contrast ratio between two colors, from 1 (identical) to 21.
+pub fn contrast_ratio(a: Rgb, b: Rgb) -> f32 {
+    let (la, lb) = (relative_luminance(a), relative_luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+/// Contrast of ANSI colors 0–15 against the palette's default background.
+pub fn audit(palette: &Palette) -> Vec<ContrastEntry> {
+    let bg = palette.default_bg();
+    (0..16)
+        .filter_map(|index| {
+            let color = palette.color(index)?;
+            Some(ContrastEntry {
+                index,
+                color,
+                ratio: contrast_ratio(color, bg),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,68 @@
+//! Per-frame memo for resolved cell colors.
+
+use vte::ansi::{Color, Rgb};
+
+use crate::cell::{Cell, CellFlags};
+
+use super::{Palette, dim_color};
+
+/// Resolved `Color::Indexed` entries for one frame.
+///
+/// Indexed colors are looked up once per frame in a fixed table; named
+/// colors depend on the cell's bold flag and RGB colors need no lookup, so
+/// neither is memoized. Call [`ColorMemo::clear`] at the start of each frame
+/// so palette changes are picked up.
+#[derive(Debug)]
+pub struct ColorMemo {
+    indexed: [Option<Rgb>; 256],
+}
+
+impl Default for ColorMemo {
+    fn default() -> Self {
+        Self {
+            indexed: [None; 256],
+        }
+    }
+}
+
+impl ColorMemo {
+    /// Drops all memoized entries.
+    pub fn clear(&mut self) {
+        self.indexed = [None; 256];
+    }
+
+    /// Resolves the foreground and background of a cell, as
+    /// [`Palette::resolve_fg`] and [`Palette::resolve_bg`] would.
+    pub fn resolve(&mut self, palette: &Palette, cell: &Cell) -> (Rgb, Rgb) {
+        let fg = self.color(palette, cell.fg, cell.flags);
+        let bg = self.color(palette, cell.bg, CellFlags::empty());
+        if cell.flags.contains(CellFlags::INVERSE) {
+            (bg, fg)
+        } else if cell.flags.contains(CellFlags::DIM) {
+            (dim_color(fg), bg)
+        } else {
+            (fg, bg)
+        }
+    }
+
+    /// `color` resolved against `palette`, memoized when indexed.
+    fn color(&mut self, palette: &Palette, color: Color, flags: CellFlags) -> Rgb {
+        match color {
+            Color::Indexed(index) => *self.indexed[usize::from(index)]
+                .get_or_insert_with(|| palette.resolve(color, flags)),
+            _ => palette.resolve(color, flags),
+        }
+    }
+
+    /// Number of memoized indexed colors.
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.indexed.iter().flatten().count()
+    }
+
+    /// Whether no indexed colors are memoized.
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
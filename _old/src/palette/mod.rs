@@ -1,7 +1,11 @@
 //! Color palette management — 270-entry RGB table plus color scheme definitions.
 
+pub mod contrast;
+mod memo;
 mod schemes;
 
+pub use memo::ColorMemo;
+
 pub use schemes::{
     ColorScheme, find_scheme, BUILTIN_SCHEMES, CATPPUCCIN_LATTE, CATPPUCCIN_MOCHA, DRACULA,
     ONE_DARK, SOLARIZED_DARK, SOLARIZED_LIGHT, TOKYO_NIGHT, WEZTERM_DEFAULT,
@@ -14,6 +18,32 @@ use crate::config::ColorConfig;
 
 pub const NUM_COLORS: usize = 270;
 
+/// Channel levels for the 256-color cube (16-231) and grayscale ramp (232-255).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CubeRanges {
+    /// Intensity of each of the 6 steps per RGB channel.
+    pub levels: [u8; 6],
+    /// Value of the first grayscale entry (232).
+    pub gray_start: u8,
+    /// Increment between consecutive grayscale entries.
+    pub gray_step: u8,
+}
+
+impl CubeRanges {
+    /// The xterm cube: `0, 95, 135, 175, 215, 255`; grays `8, 18, ..., 238`.
+    pub const XTERM: Self = Self {
+        levels: [0, 95, 135, 175, 215, 255],
+        gray_start: 8,
+        gray_step: 10,
+    };
+}
+
+impl Default for CubeRanges {
+    fn default() -> Self {
+        Self::XTERM
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Palette {
     colors: [Rgb; NUM_COLORS],
@@ -38,25 +68,8 @@ impl Palette {
             colors[i] = c;
         }
 
-        // 16-231: 6x6x6 color cube
-        for r in 0..6u8 {
-            for g in 0..6u8 {
-                for b in 0..6u8 {
-                    let idx = 16 + (r as usize * 36) + (g as usize * 6) + b as usize;
-                    colors[idx] = Rgb {
-                        r: if r == 0 { 0 } else { 55 + r * 40 },
-                        g: if g == 0 { 0 } else { 55 + g * 40 },
-                        b: if b == 0 { 0 } else { 55 + b * 40 },
-                    };
-                }
-            }
-        }
-
-        // 232-255: grayscale ramp
-        for i in 0..24u8 {
-            let v = 8 + i * 10;
-            colors[232 + i as usize] = Rgb { r: v, g: v, b: v };
-        }
+        // 16-255: 6x6x6 color cube and grayscale ramp
+        fill_cube(&mut colors, CubeRanges::default());
 
         // 256+: semantic colors from scheme
         colors[NamedColor::Foreground as usize] = scheme.fg;
@@ -96,6 +109,7 @@ impl Palette {
     /// Apply config color overrides on top of the current scheme.
     /// Call this after `set_scheme()`.
     pub fn apply_overrides(&mut self, colors: &ColorConfig) {
+        self.rebuild_cube(colors.cube_ranges());
         self.override_named(NamedColor::Foreground, colors.foreground.as_deref());
         self.override_named(NamedColor::Background, colors.background.as_deref());
         self.override_named(NamedColor::Cursor, colors.cursor.as_deref());
@@ -188,9 +202,6 @@ impl Palette {
         if flags.contains(CellFlags::INVERSE) {
             return resolved_bg;
         }
-        if flags.contains(CellFlags::HIDDEN) {
-            return resolved_bg;
-        }
         resolved_fg
     }
 
@@ -216,6 +227,11 @@ impl Palette {
         self.colors[NamedColor::Cursor as usize]
     }
 
+    /// Current value of palette entry `idx`, or `None` past the table.
+    pub fn color(&self, idx: usize) -> Option<Rgb> {
+        self.colors.get(idx).copied()
+    }
+
     pub fn set_color(&mut self, idx: usize, rgb: Rgb) {
         if idx < NUM_COLORS {
             self.colors[idx] = rgb;
@@ -227,6 +243,19 @@ impl Palette {
             self.colors[idx] = self.defaults[idx];
         }
     }
+
+    /// Regenerate the 256-color cube and grayscale ramp from `ranges`.
+    ///
+    /// The new values also become the defaults, so OSC 104 restores them.
+    pub fn rebuild_cube(&mut self, ranges: CubeRanges) {
+        fill_cube(&mut self.colors, ranges);
+        fill_cube(&mut self.defaults, ranges);
+    }
+
+    /// Restore every entry to its scheme/config default.
+    pub fn reset_all(&mut self) {
+        self.colors = self.defaults;
+    }
 }
 
 impl Default for Palette {
@@ -264,6 +293,23 @@ pub fn rgb_to_u32(rgb: Rgb) -> u32 {
     (rgb.r as u32) << 16 | (rgb.g as u32) << 8 | rgb.b as u32
 }
 
+/// Write the 6x6x6 cube (16-231) and grayscale ramp (232-255) into `colors`.
+fn fill_cube(colors: &mut [Rgb; NUM_COLORS], ranges: CubeRanges) {
+    for (i, slot) in colors[16..232].iter_mut().enumerate() {
+        *slot = Rgb {
+            r: ranges.levels[i / 36],
+            g: ranges.levels[(i / 6) % 6],
+            b: ranges.levels[i % 6],
+        };
+    }
+    for (i, slot) in colors[232..256].iter_mut().enumerate() {
+        let v = ranges
+            .gray_start
+            .saturating_add(ranges.gray_step.saturating_mul(i as u8));
+        *slot = Rgb { r: v, g: v, b: v };
+    }
+}
+
 fn dim_color(c: Rgb) -> Rgb {
     Rgb {
         r: (c.r as u16 * 2 / 3) as u8,
@@ -4,7 +4,7 @@ use std::collections::HashMap;
 
 use vte::ansi::{Color, NamedColor, Rgb};
 
-use crate::cell::CellFlags;
+use crate::cell::{Cell, CellFlags};
 use crate::config::ColorConfig;
 
 use super::*;
@@ -113,6 +113,17 @@ fn inverse_swaps() {
     assert_eq!(resolved_bg, p.resolve(fg, flags));
 }
 
+#[test]
+fn hidden_keeps_real_foreground() {
+    // Concealment is applied by the renderer, so a revealing selection can
+    // still show the text in its own colour.
+    let p = Palette::new();
+    let fg = Color::Named(NamedColor::Red);
+    let bg = Color::Named(NamedColor::Blue);
+    let resolved_fg = p.resolve_fg(fg, bg, CellFlags::HIDDEN);
+    assert_eq!(resolved_fg, p.resolve(fg, CellFlags::empty()));
+}
+
 #[test]
 fn rgb_to_u32_conversion() {
     assert_eq!(
@@ -363,3 +374,116 @@ fn apply_overrides_recalculates_dim() {
     });
     assert_eq!(p.colors[NamedColor::DimBlack as usize], expected_dim);
 }
+
+#[test]
+fn default_cube_matches_xterm() {
+    let p = Palette::new();
+    assert_eq!(p.colors[16], Rgb { r: 0, g: 0, b: 0 });
+    assert_eq!(p.colors[17], Rgb { r: 0, g: 0, b: 95 });
+    assert_eq!(p.colors[231], Rgb { r: 255, g: 255, b: 255 });
+}
+
+#[test]
+fn rebuild_cube_updates_colors_and_defaults() {
+    let mut p = Palette::new();
+    let ranges = CubeRanges {
+        levels: [0, 51, 102, 153, 204, 255],
+        gray_start: 10,
+        gray_step: 9,
+    };
+    p.rebuild_cube(ranges);
+    assert_eq!(p.colors[16 + 36 + 6 * 2 + 3], Rgb { r: 51, g: 102, b: 153 });
+    assert_eq!(p.colors[232], Rgb { r: 10, g: 10, b: 10 });
+    assert_eq!(p.colors[255], Rgb { r: 217, g: 217, b: 217 });
+
+    p.set_color(20, Rgb { r: 1, g: 2, b: 3 });
+    p.reset_color(20);
+    assert_eq!(p.colors[20], Rgb { r: 0, g: 0, b: 204 });
+}
+
+#[test]
+fn config_cube_levels_applied_by_overrides() {
+    let mut p = Palette::new();
+    let colors = ColorConfig {
+        cube_levels: Some([0, 1, 2, 3, 4, 5]),
+        grayscale_ramp: Some([0, 1]),
+        ..ColorConfig::default()
+    };
+    p.apply_overrides(&colors);
+    assert_eq!(p.colors[231], Rgb { r: 5, g: 5, b: 5 });
+    assert_eq!(p.colors[255], Rgb { r: 23, g: 23, b: 23 });
+}
+
+#[test]
+fn color_memo_matches_direct_resolution() {
+    let p = Palette::new();
+    let mut memo = ColorMemo::default();
+    let colors = [
+        (Color::Named(NamedColor::Red), Color::Indexed(200)),
+        (Color::Indexed(3), Color::Spec(Rgb { r: 1, g: 2, b: 3 })),
+    ];
+    let styles = [
+        CellFlags::empty(),
+        CellFlags::BOLD,
+        CellFlags::DIM,
+        CellFlags::INVERSE,
+        CellFlags::DIM | CellFlags::INVERSE,
+    ];
+    for (fg, bg) in colors {
+        for flags in styles {
+            let cell = Cell { fg, bg, flags, ..Cell::default() };
+            let expected = (p.resolve_fg(fg, bg, flags), p.resolve_bg(fg, bg, flags));
+            assert_eq!(memo.resolve(&p, &cell), expected);
+            assert_eq!(memo.resolve(&p, &cell), expected);
+        }
+    }
+    assert_eq!(memo.len(), 2);
+}
+
+#[test]
+fn color_memo_keeps_only_indexed_colors() {
+    let p = Palette::new();
+    let mut memo = ColorMemo::default();
+    let mut cell = Cell {
+        fg: Color::Named(NamedColor::Green),
+        bg: Color::Spec(Rgb { r: 9, g: 9, b: 9 }),
+        ..Cell::default()
+    };
+    memo.resolve(&p, &cell);
+    assert!(memo.is_empty());
+    cell.fg = Color::Indexed(42);
+    memo.resolve(&p, &cell);
+    assert_eq!(memo.len(), 1);
+    memo.clear();
+    assert!(memo.is_empty());
+}
+
+#[test]
+fn contrast_ratio_matches_wcag_reference_values() {
+    let black = Rgb { r: 0, g: 0, b: 0 };
+    let white = Rgb { r: 255, g: 255, b: 255 };
+    assert!((contrast::contrast_ratio(black, white) - 21.0).abs() < 0.01);
+    assert!((contrast::contrast_ratio(white, white) - 1.0).abs() < 0.001);
+    // #777 on white is the classic just-failing gray (4.48:1).
+    let gray = Rgb { r: 0x77, g: 0x77, b: 0x77 };
+    assert!((contrast::contrast_ratio(gray, white) - 4.48).abs() < 0.01);
+}
+
+#[test]
+fn contrast_audit_flags_unreadable_ansi_colors() {
+    let mut p = Palette::new();
+    p.set_color(NamedColor::Background as usize, Rgb { r: 0, g: 0, b: 0 });
+    p.set_color(1, Rgb { r: 0x20, g: 0, b: 0 });
+    p.set_color(2, Rgb { r: 0x80, g: 0x80, b: 0x80 });
+    p.set_color(3, Rgb { r: 0xff, g: 0xff, b: 0xff });
+
+    let audit = contrast::audit(&p);
+    assert_eq!(audit.len(), 16);
+    assert_eq!(audit[1].readability(), contrast::Readability::Unreadable);
+    assert_eq!(audit[2].readability(), contrast::Readability::Good);
+    assert_eq!(audit[3].readability(), contrast::Readability::Good);
+    assert!((audit[3].ratio - 21.0).abs() < 0.01);
+
+    p.set_color(2, Rgb { r: 0x60, g: 0x60, b: 0x60 });
+    assert_eq!(contrast::audit(&p)[2].readability(), contrast::Readability::LargeOnly);
+}
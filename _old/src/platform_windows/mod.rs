@@ -0,0 +1,238 @@
+//! Windows snap support — `WndProc` subclass for borderless windows.
+//!
+//! Adds `WS_THICKFRAME | WS_MAXIMIZEBOX | WS_MINIMIZEBOX | WS_CAPTION` back to a
+//! borderless window, hides the OS title bar via `DwmExtendFrameIntoClientArea`, and
+//! installs a subclass that handles `WM_NCCALCSIZE` and `WM_NCHITTEST` to enable
+//! Aero Snap (drag-to-edge, Win+Arrow, snap layouts).
+//!
+//! This is the standard approach used by Chrome, `WezTerm`, and Windows Terminal.
+//! The subclass also notices accent and theme broadcasts (see [`theme`]).
+
+mod subclass;
+mod theme;
+mod window;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use windows_sys::Win32::Foundation::HWND;
+use windows_sys::Win32::Graphics::Dwm::DwmExtendFrameIntoClientArea;
+use windows_sys::Win32::UI::Controls::MARGINS;
+use windows_sys::Win32::UI::Shell::SetWindowSubclass;
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    GWL_STYLE, GetWindowLongPtrW, SWP_FRAMECHANGED, SWP_NOMOVE, SWP_NOSIZE, SWP_NOZORDER,
+    SetWindowLongPtrW, SetWindowPos, WS_CAPTION, WS_MAXIMIZEBOX, WS_MINIMIZEBOX, WS_THICKFRAME,
+};
+
+pub use theme::{accent_color, set_dark_mode, take_appearance_changed};
+pub use window::{
+    cursor_screen_pos, release_mouse_capture, set_app_user_model_id, show_window,
+    visible_frame_bounds,
+};
+
+use subclass::subclass_proc;
+
+const SUBCLASS_ID: usize = 0xBEEF;
+
+/// Configuration for an OS drag session, passed to `begin_os_drag()`.
+pub struct OsDragConfig {
+    /// Cursor-to-window-origin offset at the moment the drag started.
+    /// `WM_MOVING` corrects the proposed rect every frame: `pos = cursor - grab_offset`.
+    pub grab_offset: (i32, i32),
+    /// Tab bar zones of other windows in screen coordinates.
+    /// Each entry: `[left, top, right, tab_bar_bottom]`.
+    /// Cursor-based merge: if `GetCursorPos()` falls within a zone, merge is triggered.
+    pub merge_rects: Vec<[i32; 4]>,
+    /// Number of `WM_MOVING` frames to skip merge detection after tear-off.
+    /// Position correction still runs on every frame.
+    pub skip_count: i32,
+}
+
+/// Result of an OS drag session, consumed by `take_os_drag_result()`.
+pub enum OsDragResult {
+    /// OS drag ended normally (user released mouse). Cursor at end position.
+    DragEnded { cursor: (i32, i32) },
+    /// `WM_MOVING` detected cursor in a merge target's tab bar zone.
+    /// Window was hidden + `ReleaseCapture` called. Cursor captured at detection.
+    MergeDetected { cursor: (i32, i32) },
+}
+
+/// Mutable state for an active OS drag session, stored behind `Mutex` in `SnapData`.
+///
+/// Created by `begin_os_drag()`, consumed by `WM_MOVING`/`WM_EXITSIZEMOVE` handlers,
+/// and read by `take_os_drag_result()`.
+struct OsDragState {
+    grab_offset: (i32, i32),
+    merge_rects: Vec<[i32; 4]>,
+    skip_remaining: i32,
+    result: Option<OsDragResult>,
+}
+
+struct SnapData {
+    resize_border: i32,
+    caption_height: i32,
+    client_rects: Mutex<Vec<[i32; 4]>>,
+    /// DPI from the most recent `WM_DPICHANGED` message.  Since we eat
+    /// `WM_DPICHANGED` (don't pass it to `DefSubclassProc`), winit never fires
+    /// `ScaleFactorChanged`.  The app reads this in `handle_resize` to update
+    /// `self.scale_factor`.  0 means no DPI change has been received yet.
+    last_dpi: AtomicU32,
+    /// Active OS drag session state. `Some` while `drag_window()` is in progress,
+    /// `None` otherwise. Replaces the previous 13 atomics.
+    os_drag: Mutex<Option<OsDragState>>,
+}
+
+/// Global map from HWND (as usize) → `SnapData` pointer so `set_client_rects` can
+/// find it. Raw pointers don't implement `Hash`, so we store HWND as `usize`.
+static SNAP_PTRS: OnceLock<Mutex<HashMap<usize, usize>>> = OnceLock::new();
+
+/// Install snap support on a borderless window.
+///
+/// Adds `WS_THICKFRAME | WS_MAXIMIZEBOX | WS_MINIMIZEBOX | WS_CAPTION` so that
+/// Windows recognizes the window for Aero Snap, hides the OS title bar via DWM,
+/// and installs a `WndProc` subclass for `WM_NCCALCSIZE` and `WM_NCHITTEST`.
+#[allow(unsafe_code)]
+pub fn enable_snap(window: &winit::window::Window, resize_border: i32, caption_height: i32) {
+    let Some(hwnd) = hwnd_from_window(window) else {
+        return;
+    };
+
+    // SAFETY: All calls are standard Win32 API functions operating on a valid HWND
+    // obtained from winit. The SnapData pointer is heap-allocated and will be freed
+    // in the WM_NCDESTROY handler.
+    unsafe {
+        // 1. Add snap-enabling styles back to the borderless window
+        let style = GetWindowLongPtrW(hwnd, GWL_STYLE);
+        let snap_bits = (WS_THICKFRAME | WS_MAXIMIZEBOX | WS_MINIMIZEBOX | WS_CAPTION) as isize;
+        SetWindowLongPtrW(hwnd, GWL_STYLE, style | snap_bits);
+
+        // Force Windows to re-evaluate the frame after style change
+        SetWindowPos(
+            hwnd,
+            std::ptr::null_mut(),
+            0,
+            0,
+            0,
+            0,
+            SWP_FRAMECHANGED | SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER,
+        );
+
+        // 2. Hide OS title bar — 1px top margin keeps DWM shadow + snap preview
+        let margins = MARGINS {
+            cxLeftWidth: 0,
+            cxRightWidth: 0,
+            cyTopHeight: 1,
+            cyBottomHeight: 0,
+        };
+        DwmExtendFrameIntoClientArea(hwnd, &raw const margins);
+
+        // 3. Install `WndProc` subclass
+        let data = Box::new(SnapData {
+            resize_border,
+            caption_height,
+            client_rects: Mutex::new(Vec::new()),
+            last_dpi: AtomicU32::new(0),
+            os_drag: Mutex::new(None),
+        });
+        let data_ptr = Box::into_raw(data);
+        SetWindowSubclass(hwnd, Some(subclass_proc), SUBCLASS_ID, data_ptr as usize);
+
+        // Register pointer so set_client_rects can find it
+        if let Ok(mut map) = snap_ptrs().lock() {
+            map.insert(hwnd as usize, data_ptr as usize);
+        }
+    }
+}
+
+/// Update the interactive regions that should receive mouse clicks (`HTCLIENT`)
+/// rather than being treated as caption/drag area (`HTCAPTION`).
+///
+/// Each rect is `[left, top, right, bottom]` in client coordinates.
+/// Call this whenever the tab bar layout changes (resize, tab add/remove).
+pub fn set_client_rects(window: &winit::window::Window, rects: Vec<[i32; 4]>) {
+    let Some(data) = snap_data_for_window(window) else {
+        return;
+    };
+    if let Ok(mut rects_lock) = data.client_rects.lock() {
+        *rects_lock = rects;
+    }
+}
+
+/// Read the DPI stored by the last `WM_DPICHANGED` message.
+///
+/// Returns the scale factor (DPI / 96.0), or `None` if no DPI change has been
+/// received since `enable_snap` was called.
+pub fn get_current_dpi(window: &winit::window::Window) -> Option<f64> {
+    let data = snap_data_for_window(window)?;
+    let dpi = data.last_dpi.load(Ordering::Relaxed);
+    if dpi == 0 {
+        None
+    } else {
+        Some(dpi as f64 / 96.0)
+    }
+}
+
+/// Begin an OS drag session for tab tear-off or single-tab window drag.
+///
+/// Stores `OsDragState` so the `WM_MOVING` handler can correct window position
+/// and detect cursor-based merges. Call this before `drag_window()`.
+pub fn begin_os_drag(window: &winit::window::Window, config: OsDragConfig) {
+    let Some(data) = snap_data_for_window(window) else {
+        return;
+    };
+    if let Ok(mut lock) = data.os_drag.lock() {
+        *lock = Some(OsDragState {
+            grab_offset: config.grab_offset,
+            merge_rects: config.merge_rects,
+            skip_remaining: config.skip_count,
+            result: None,
+        });
+    }
+}
+
+/// If an OS drag session completed (either normal end or merge detection),
+/// return the result and clear the drag state.
+///
+/// Returns `None` if no drag session is active or it hasn't ended yet.
+pub fn take_os_drag_result(window: &winit::window::Window) -> Option<OsDragResult> {
+    let data = snap_data_for_window(window)?;
+    let mut lock = data.os_drag.lock().ok()?;
+    let state = lock.as_mut()?;
+    let result = state.result.take()?;
+    // Drag session is complete — clear the entire state.
+    *lock = None;
+    Some(result)
+}
+
+fn snap_ptrs() -> &'static Mutex<HashMap<usize, usize>> {
+    SNAP_PTRS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Look up the `SnapData` pointer for a window.
+///
+/// # Safety
+///
+/// The returned reference is valid until `WM_NCDESTROY` fires for the window.
+/// Callers must not hold the reference across event loop iterations.
+#[allow(unsafe_code)]
+fn snap_data_for_window(window: &winit::window::Window) -> Option<&'static SnapData> {
+    let hwnd = hwnd_from_window(window)?;
+    let ptr = {
+        let map = snap_ptrs().lock().ok()?;
+        *map.get(&(hwnd as usize))?
+    };
+    // SAFETY: The pointer was created by Box::into_raw in enable_snap and remains
+    // valid until WM_NCDESTROY frees it.
+    Some(unsafe { &*(ptr as *const SnapData) })
+}
+
+/// Extract HWND from a winit Window.
+pub(crate) fn hwnd_from_window(window: &winit::window::Window) -> Option<HWND> {
+    use winit::raw_window_handle::{HasWindowHandle, RawWindowHandle};
+    let handle = window.window_handle().ok()?;
+    match handle.as_raw() {
+        RawWindowHandle::Win32(h) => Some(h.hwnd.get() as HWND),
+        _ => None,
+    }
+}
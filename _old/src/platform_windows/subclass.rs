@@ -0,0 +1,271 @@
+//! The `WndProc` subclass: borderless frame, hit testing for snap and
+//! resize, DPI changes, and OS drag tracking.
+
+use std::sync::atomic::Ordering;
+
+use windows_sys::Win32::Foundation::{HWND, LRESULT, RECT};
+use windows_sys::Win32::UI::Input::KeyboardAndMouse::ReleaseCapture;
+use windows_sys::Win32::UI::Shell::{DefSubclassProc, RemoveWindowSubclass};
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    GetCursorPos, GetSystemMetrics, GetWindowRect, HTBOTTOM, HTBOTTOMLEFT, HTBOTTOMRIGHT,
+    HTCAPTION, HTCLIENT, HTLEFT, HTRIGHT, HTTOP, HTTOPLEFT, HTTOPRIGHT, IsZoomed,
+    NCCALCSIZE_PARAMS, SM_CXFRAME, SM_CXPADDEDBORDER, SM_CYFRAME, SW_HIDE, SWP_NOACTIVATE,
+    SWP_NOZORDER, SetWindowPos, ShowWindow, WM_DPICHANGED, WM_DWMCOLORIZATIONCOLORCHANGED,
+    WM_EXITSIZEMOVE, WM_MOVING, WM_NCCALCSIZE, WM_NCDESTROY, WM_NCHITTEST, WM_SETTINGCHANGE,
+};
+
+use super::{OsDragResult, SUBCLASS_ID, SnapData, snap_ptrs, theme};
+
+fn get_x_lparam(lp: isize) -> i32 {
+    #[allow(clippy::cast_possible_truncation, reason = "LPARAM low/high word extraction is inherently truncating")]
+    let v = (lp & 0xFFFF) as i16;
+    i32::from(v)
+}
+
+fn get_y_lparam(lp: isize) -> i32 {
+    #[allow(clippy::cast_possible_truncation, reason = "LPARAM low/high word extraction is inherently truncating")]
+    let v = ((lp >> 16) & 0xFFFF) as i16;
+    i32::from(v)
+}
+
+/// `WndProc` subclass callback — handles `WM_NCCALCSIZE`, `WM_NCHITTEST`, `WM_NCDESTROY`,
+/// and notes appearance broadcasts.
+///
+/// # Safety
+///
+/// Called by Windows as a subclass procedure. `ref_data` must be a valid pointer to
+/// a `SnapData` allocated by `enable_snap`.
+#[allow(unsafe_code)]
+pub(super) unsafe extern "system" fn subclass_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: usize,
+    lparam: isize,
+    _uid: usize,
+    ref_data: usize,
+) -> LRESULT {
+    // SAFETY: All operations within use valid Win32 API calls on a valid HWND.
+    // The ref_data pointer was allocated by enable_snap and remains valid until
+    // WM_NCDESTROY frees it.
+    unsafe {
+        match msg {
+            WM_NCCALCSIZE => {
+                if wparam == 1 {
+                    // wparam=TRUE: lparam points to NCCALCSIZE_PARAMS.
+                    // Return 0 so the entire window is client area (no OS frame).
+                    // When maximized, inset by frame thickness to prevent overflow.
+                    if IsZoomed(hwnd) != 0 {
+                        let params = &mut *(lparam as *mut NCCALCSIZE_PARAMS);
+                        let frame_x =
+                            GetSystemMetrics(SM_CXFRAME) + GetSystemMetrics(SM_CXPADDEDBORDER);
+                        let frame_y =
+                            GetSystemMetrics(SM_CYFRAME) + GetSystemMetrics(SM_CXPADDEDBORDER);
+                        params.rgrc[0].left += frame_x;
+                        params.rgrc[0].top += frame_y;
+                        params.rgrc[0].right -= frame_x;
+                        params.rgrc[0].bottom -= frame_y;
+                    }
+                    return 0;
+                }
+                DefSubclassProc(hwnd, msg, wparam, lparam)
+            }
+
+            WM_NCHITTEST => {
+                let data = &*(ref_data as *const SnapData);
+
+                // Cursor position in screen coordinates
+                let cursor_x = get_x_lparam(lparam);
+                let cursor_y = get_y_lparam(lparam);
+
+                // Window rect in screen coordinates
+                let mut rect = RECT {
+                    left: 0,
+                    top: 0,
+                    right: 0,
+                    bottom: 0,
+                };
+                GetWindowRect(hwnd, &raw mut rect);
+
+                // Convert to client-relative coordinates
+                let x = cursor_x - rect.left;
+                let y = cursor_y - rect.top;
+                let w = rect.right - rect.left;
+                let h = rect.bottom - rect.top;
+
+                let border = data.resize_border;
+                let not_maximized = IsZoomed(hwnd) == 0;
+
+                // Caption area (tab bar) — interactive elements take priority
+                // over resize borders so buttons at window edges stay clickable.
+                if y < data.caption_height {
+                    // Check interactive regions first (tabs, buttons, controls)
+                    if let Ok(rects) = data.client_rects.lock() {
+                        for r in rects.iter() {
+                            if x >= r[0] && y >= r[1] && x < r[2] && y < r[3] {
+                                return HTCLIENT as LRESULT;
+                            }
+                        }
+                    }
+                    // Resize edges in non-interactive caption area
+                    if not_maximized {
+                        let on_left = x < border;
+                        let on_right = x >= w - border;
+                        let on_top = y < border;
+
+                        if on_top && on_left {
+                            return HTTOPLEFT as LRESULT;
+                        }
+                        if on_top && on_right {
+                            return HTTOPRIGHT as LRESULT;
+                        }
+                        if on_left {
+                            return HTLEFT as LRESULT;
+                        }
+                        if on_right {
+                            return HTRIGHT as LRESULT;
+                        }
+                        if on_top {
+                            return HTTOP as LRESULT;
+                        }
+                    }
+                    return HTCAPTION as LRESULT;
+                }
+
+                // Below caption — resize edges
+                if not_maximized {
+                    let on_left = x < border;
+                    let on_right = x >= w - border;
+                    let on_bottom = y >= h - border;
+
+                    if on_bottom && on_left {
+                        return HTBOTTOMLEFT as LRESULT;
+                    }
+                    if on_bottom && on_right {
+                        return HTBOTTOMRIGHT as LRESULT;
+                    }
+                    if on_left {
+                        return HTLEFT as LRESULT;
+                    }
+                    if on_right {
+                        return HTRIGHT as LRESULT;
+                    }
+                    if on_bottom {
+                        return HTBOTTOM as LRESULT;
+                    }
+                }
+
+                HTCLIENT as LRESULT
+            }
+
+            WM_DPICHANGED => {
+                let data = &*(ref_data as *const SnapData);
+
+                // Store the new DPI so the app can pick it up in handle_resize.
+                // HIWORD(wParam) = new Y-axis DPI (X and Y are always equal).
+                let new_dpi = ((wparam >> 16) & 0xFFFF) as u32;
+                data.last_dpi.store(new_dpi, Ordering::Relaxed);
+
+                // Apply the suggested rect from Windows (lParam → RECT*).
+                // Windows calculates this rect to prevent DPI oscillation.
+                // We handle it ourselves instead of letting winit do its own
+                // (potentially incorrect) resize that causes oscillation at
+                // per-monitor DPI boundaries.
+                let suggested = &*(lparam as *const RECT);
+                SetWindowPos(
+                    hwnd,
+                    std::ptr::null_mut(),
+                    suggested.left,
+                    suggested.top,
+                    suggested.right - suggested.left,
+                    suggested.bottom - suggested.top,
+                    SWP_NOZORDER | SWP_NOACTIVATE,
+                );
+                0
+            }
+
+            WM_MOVING => {
+                let data = &*(ref_data as *const SnapData);
+                if let Ok(mut lock) = data.os_drag.lock() {
+                    if let Some(state) = lock.as_mut() {
+                        let proposed = &mut *(lparam as *mut RECT);
+                        let w = proposed.right - proposed.left;
+                        let h = proposed.bottom - proposed.top;
+
+                        // 1. Always correct position (Chrome pattern).
+                        // winit's drag_window() doesn't accept a drag_offset,
+                        // so the OS picks up whatever offset exists. We
+                        // compensate by correcting the proposed rect every
+                        // frame: window origin = cursor - grab_offset.
+                        let mut pt =
+                            windows_sys::Win32::Foundation::POINT { x: 0, y: 0 };
+                        GetCursorPos(&raw mut pt);
+                        let (gx, gy) = state.grab_offset;
+                        proposed.left = pt.x - gx;
+                        proposed.top = pt.y - gy;
+                        proposed.right = proposed.left + w;
+                        proposed.bottom = proposed.top + h;
+
+                        // 2. Skip merge check only (position still corrected).
+                        if state.skip_remaining > 0 {
+                            state.skip_remaining -= 1;
+                            return DefSubclassProc(hwnd, msg, wparam, lparam);
+                        }
+
+                        // 3. Cursor-based merge (Chrome's DoesTabStripContain).
+                        // Check if the cursor falls within any target tab bar zone.
+                        for &[cl, ct, cr, ctb] in &state.merge_rects {
+                            if pt.x >= cl && pt.x < cr && pt.y >= ct && pt.y < ctb {
+                                state.result = Some(OsDragResult::MergeDetected {
+                                    cursor: (pt.x, pt.y),
+                                });
+                                // Hide window before ending loop (Chrome does this
+                                // to prevent snap-back visual artifact).
+                                ShowWindow(hwnd, SW_HIDE);
+                                ReleaseCapture();
+                                return DefSubclassProc(hwnd, msg, wparam, lparam);
+                            }
+                        }
+                    }
+                }
+                DefSubclassProc(hwnd, msg, wparam, lparam)
+            }
+
+            WM_EXITSIZEMOVE => {
+                let data = &*(ref_data as *const SnapData);
+                if let Ok(mut lock) = data.os_drag.lock() {
+                    if let Some(state) = lock.as_mut() {
+                        // Only store DragEnded if WM_MOVING didn't already set a
+                        // MergeDetected result.
+                        if state.result.is_none() {
+                            let mut pt =
+                                windows_sys::Win32::Foundation::POINT { x: 0, y: 0 };
+                            GetCursorPos(&raw mut pt);
+                            state.result = Some(OsDragResult::DragEnded {
+                                cursor: (pt.x, pt.y),
+                            });
+                        }
+                    }
+                }
+                DefSubclassProc(hwnd, msg, wparam, lparam)
+            }
+
+            WM_DWMCOLORIZATIONCOLORCHANGED | WM_SETTINGCHANGE => {
+                theme::note_appearance_changed();
+                DefSubclassProc(hwnd, msg, wparam, lparam)
+            }
+
+            WM_NCDESTROY => {
+                RemoveWindowSubclass(hwnd, Some(subclass_proc), SUBCLASS_ID);
+                // Remove from global map
+                if let Ok(mut map) = snap_ptrs().lock() {
+                    map.remove(&(hwnd as usize));
+                }
+                // Free the SnapData
+                drop(Box::from_raw(ref_data as *mut SnapData));
+                DefSubclassProc(hwnd, msg, wparam, lparam)
+            }
+
+            _ => DefSubclassProc(hwnd, msg, wparam, lparam),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! System appearance: the DWM accent color and dark-mode window frames.
+//!
+//! Windows broadcasts `WM_DWMCOLORIZATIONCOLORCHANGED` when the accent
+//! color changes and `WM_SETTINGCHANGE` when the light/dark preference
+//! does. The snap subclass procedure records either one here, and the app
+//! re-reads the appearance on its next pass through the event loop.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use windows_sys::Win32::Graphics::Dwm::{
+    DWMWA_USE_IMMERSIVE_DARK_MODE, DwmGetColorizationColor, DwmSetWindowAttribute,
+};
+use windows_sys::core::BOOL;
+use winit::window::{Theme, Window};
+
+use super::hwnd_from_window;
+
+/// Set when Windows broadcasts an appearance change.
+static APPEARANCE_CHANGED: AtomicBool = AtomicBool::new(false);
+
+/// The accent color DWM paints window frames with, as RGB.
+#[allow(unsafe_code)]
+pub fn accent_color() -> Option<(u8, u8, u8)> {
+    let mut argb: u32 = 0;
+    let mut opaque: BOOL = 0;
+    // SAFETY: Both out-pointers refer to live locals.
+    let hr = unsafe { DwmGetColorizationColor(&raw mut argb, &raw mut opaque) };
+    let [_, r, g, b] = argb.to_be_bytes();
+    (hr >= 0).then_some((r, g, b))
+}
+
+/// Ask DWM to draw `window`'s frame, system menu, and snap flyouts in the
+/// dark or light style.
+#[allow(unsafe_code)]
+pub fn set_dark_mode(window: &Window, theme: Theme) {
+    let Some(hwnd) = hwnd_from_window(window) else {
+        return;
+    };
+    let dark: BOOL = BOOL::from(theme == Theme::Dark);
+    // SAFETY: `hwnd` is a live window and the attribute points to a BOOL of
+    // the size passed.
+    unsafe {
+        DwmSetWindowAttribute(
+            hwnd,
+            DWMWA_USE_IMMERSIVE_DARK_MODE as u32,
+            (&raw const dark).cast(),
+            size_of::<BOOL>() as u32,
+        );
+    }
+}
+
+/// Whether Windows broadcast an accent or theme change since the last call.
+pub fn take_appearance_changed() -> bool {
+    APPEARANCE_CHANGED.swap(false, Ordering::Relaxed)
+}
+
+/// Record an appearance broadcast (called from the subclass procedure).
+pub(super) fn note_appearance_changed() {
+    APPEARANCE_CHANGED.store(true, Ordering::Relaxed);
+}
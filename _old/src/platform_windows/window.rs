@@ -0,0 +1,86 @@
+//! Win32 window and cursor helpers that bypass winit.
+
+use windows_sys::Win32::Foundation::RECT;
+use windows_sys::Win32::Graphics::Dwm::{DWMWA_EXTENDED_FRAME_BOUNDS, DwmGetWindowAttribute};
+use windows_sys::Win32::UI::Input::KeyboardAndMouse::ReleaseCapture;
+use windows_sys::Win32::UI::Shell::SetCurrentProcessExplicitAppUserModelID;
+use windows_sys::Win32::UI::WindowsAndMessaging::{GetCursorPos, SW_SHOW, ShowWindow};
+
+use super::hwnd_from_window;
+
+/// Get the current screen cursor position via Win32 `GetCursorPos`.
+#[allow(unsafe_code)]
+pub fn cursor_screen_pos() -> (i32, i32) {
+    let mut pt = windows_sys::Win32::Foundation::POINT { x: 0, y: 0 };
+    // SAFETY: Standard Win32 API call with valid output pointer.
+    unsafe {
+        GetCursorPos(&raw mut pt);
+    }
+    (pt.x, pt.y)
+}
+
+/// Returns the visible frame bounds of a window, excluding the invisible
+/// DWM extended frame that `GetWindowRect` / `outer_position()` include.
+///
+/// Returns `(left, top, right, bottom)` in screen coordinates, or `None`
+/// if the query fails.
+#[allow(unsafe_code)]
+pub fn visible_frame_bounds(window: &winit::window::Window) -> Option<(i32, i32, i32, i32)> {
+    let hwnd = hwnd_from_window(window)?;
+    let mut rect = RECT {
+        left: 0,
+        top: 0,
+        right: 0,
+        bottom: 0,
+    };
+    // DWMWA_EXTENDED_FRAME_BOUNDS is defined as i32 in windows-sys but
+    // DwmGetWindowAttribute expects u32.
+    #[allow(clippy::cast_sign_loss, reason = "DWMWA constant is always non-negative")]
+    let attr = DWMWA_EXTENDED_FRAME_BOUNDS as u32;
+    // SAFETY: Standard Win32 API call with valid HWND and properly sized output buffer.
+    let hr = unsafe {
+        DwmGetWindowAttribute(hwnd, attr, (&raw mut rect).cast(), size_of::<RECT>() as u32)
+    };
+    if hr == 0 {
+        Some((rect.left, rect.top, rect.right, rect.bottom))
+    } else {
+        None
+    }
+}
+
+/// Show a window that was hidden via raw Win32 `ShowWindow(SW_HIDE)`.
+///
+/// Uses raw Win32 `ShowWindow(SW_SHOW)` to bypass winit's internal visibility
+/// tracking. Necessary because `WM_MOVING` hides the window directly (for
+/// Chrome-style merge), and winit's `set_visible(true)` may not undo that.
+#[allow(unsafe_code)]
+pub fn show_window(window: &winit::window::Window) {
+    if let Some(hwnd) = hwnd_from_window(window) {
+        // SAFETY: Standard Win32 API call with valid HWND.
+        unsafe {
+            ShowWindow(hwnd, SW_SHOW);
+        }
+    }
+}
+
+/// Set the process's `AppUserModelID` (`window.app_id` or `--class`), which
+/// the taskbar uses to group windows and pin shortcuts. Must run before the
+/// first window is created.
+#[allow(unsafe_code)]
+pub fn set_app_user_model_id(app_id: &str) {
+    let wide: Vec<u16> = app_id.encode_utf16().chain(std::iter::once(0)).collect();
+    // SAFETY: `wide` is a NUL-terminated UTF-16 string that outlives the call.
+    unsafe {
+        SetCurrentProcessExplicitAppUserModelID(wide.as_ptr());
+    }
+}
+
+/// Release mouse capture before process exit to prevent orphaned mouse-up
+/// events from being delivered to the window behind (click-through on exit).
+#[allow(unsafe_code)]
+pub fn release_mouse_capture() {
+    // SAFETY: Standard Win32 API call, no preconditions.
+    unsafe {
+        ReleaseCapture();
+    }
+}
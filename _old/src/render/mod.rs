@@ -2,6 +2,8 @@
 
 pub(crate) mod font_discovery;
 
+use serde::{Deserialize, Serialize};
+
 use crate::cell::CellFlags;
 
 pub const FONT_SIZE: f32 = 16.0;
@@ -31,4 +33,81 @@ impl FontStyle {
     }
 }
 
+/// How blinking text (SGR 5/6) is drawn during its "off" phase.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextBlink {
+    /// Hide the glyph and its decorations, keeping the cell background.
+    #[default]
+    Hide,
+    /// Draw the glyph at half intensity.
+    Dim,
+    /// Ignore blink: text is always fully visible.
+    Off,
+}
+
+/// Visibility of slow (SGR 5) and fast (SGR 6) blinking text at one instant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlinkPhase {
+    pub slow_on: bool,
+    pub fast_on: bool,
+}
+
+impl BlinkPhase {
+    /// Both blink rates in their "on" phase.
+    pub const VISIBLE: Self = Self {
+        slow_on: true,
+        fast_on: true,
+    };
+
+    /// Phase `elapsed_ms` after the blink clock started. Slow blink toggles
+    /// every `interval_ms`, fast blink twice as often.
+    pub fn at(elapsed_ms: u64, interval_ms: u64) -> Self {
+        let slow = interval_ms.max(2);
+        Self {
+            slow_on: (elapsed_ms / slow).is_multiple_of(2),
+            fast_on: (elapsed_ms / (slow / 2)).is_multiple_of(2),
+        }
+    }
+
+    /// Milliseconds from `elapsed_ms` until either blink rate toggles.
+    pub fn until_next_toggle(elapsed_ms: u64, interval_ms: u64) -> u64 {
+        let fast = interval_ms.max(2) / 2;
+        fast - elapsed_ms % fast
+    }
+
+    /// Whether a cell with `flags` is in its "off" phase.
+    pub fn is_off(self, flags: CellFlags) -> bool {
+        (flags.contains(CellFlags::BLINK) && !self.slow_on)
+            || (flags.contains(CellFlags::BLINK_FAST) && !self.fast_on)
+    }
+}
+
+/// Cursor blink timing: solid for `pause_ms` after the last reset
+/// (keystroke, output, focus), then toggling every `interval_ms`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CursorBlink {
+    pub interval_ms: u64,
+    pub pause_ms: u64,
+}
+
+impl CursorBlink {
+    /// Whether the cursor is drawn `elapsed_ms` after the last reset.
+    pub fn visible(self, elapsed_ms: u64) -> bool {
+        elapsed_ms
+            .checked_sub(self.pause_ms)
+            .is_none_or(|t| (t / self.interval_ms.max(1)).is_multiple_of(2))
+    }
+
+    /// Milliseconds from `elapsed_ms` until the cursor next toggles.
+    pub fn until_next_toggle(self, elapsed_ms: u64) -> u64 {
+        let interval = self.interval_ms.max(1);
+        match elapsed_ms.checked_sub(self.pause_ms) {
+            Some(t) => interval - t % interval,
+            None => self.pause_ms - elapsed_ms + interval,
+        }
+    }
+}
 
+#[cfg(test)]
+mod tests;
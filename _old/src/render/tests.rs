@@ -0,0 +1,54 @@
+//! Tests for shared text rendering types.
+
+use crate::cell::CellFlags;
+
+use super::{BlinkPhase, CursorBlink};
+
+#[test]
+fn blink_phase_rates() {
+    let at = |ms| BlinkPhase::at(ms, 600);
+    assert_eq!(at(0), BlinkPhase::VISIBLE);
+    assert!(at(300).slow_on && !at(300).fast_on);
+    assert!(!at(600).slow_on && at(600).fast_on);
+    assert!(!at(900).slow_on && !at(900).fast_on);
+    assert_eq!(at(1200), BlinkPhase::VISIBLE);
+}
+
+#[test]
+fn blink_phase_cell_visibility() {
+    let phase = BlinkPhase {
+        slow_on: true,
+        fast_on: false,
+    };
+    assert!(!phase.is_off(CellFlags::BLINK));
+    assert!(phase.is_off(CellFlags::BLINK_FAST));
+    assert!(!phase.is_off(CellFlags::BOLD));
+    assert!(!BlinkPhase::VISIBLE.is_off(CellFlags::BLINK | CellFlags::BLINK_FAST));
+}
+
+#[test]
+fn blink_next_toggle() {
+    assert_eq!(BlinkPhase::until_next_toggle(0, 600), 300);
+    assert_eq!(BlinkPhase::until_next_toggle(450, 600), 150);
+}
+
+#[test]
+fn cursor_blink_stays_solid_through_the_pause() {
+    let blink = CursorBlink {
+        interval_ms: 500,
+        pause_ms: 300,
+    };
+    assert!(blink.visible(0));
+    assert!(blink.visible(799));
+    assert!(!blink.visible(800));
+    assert!(blink.visible(1300));
+    assert_eq!(blink.until_next_toggle(100), 700);
+    assert_eq!(blink.until_next_toggle(900), 400);
+
+    let no_pause = CursorBlink {
+        interval_ms: 500,
+        pause_ms: 0,
+    };
+    assert!(!no_pause.visible(500));
+    assert_eq!(no_pause.until_next_toggle(0), 500);
+}
@@ -0,0 +1,95 @@
+//! Overlay scrollbar geometry: where the thumb and the prompt and search
+//! markers sit on the track, and which scroll position a thumb drag picks.
+
+use crate::grid::Grid;
+
+/// Width of the scrollbar along the right edge of the window, in logical
+/// pixels.
+pub const SCROLLBAR_WIDTH: usize = 8;
+/// Shortest thumb, in logical pixels, so it stays grabbable in long
+/// scrollback.
+const MIN_THUMB_HEIGHT: f32 = 24.0;
+
+/// Scrollbar position for one grid, in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrollbarLayout {
+    /// Left edge of the track.
+    pub x: f32,
+    /// Track width.
+    pub width: f32,
+    /// Top of the track.
+    pub top: f32,
+    /// Track height.
+    pub height: f32,
+    /// Top of the thumb.
+    pub thumb_top: f32,
+    /// Thumb height: the share of all rows that is on screen.
+    pub thumb_height: f32,
+    /// Rows of scrollback.
+    history: usize,
+    /// Rows of scrollback plus rows on screen.
+    total: usize,
+}
+
+impl ScrollbarLayout {
+    /// Layout for `grid` in a window `window_w` pixels wide whose grid spans
+    /// `top` to `bottom`. `None` without scrollback: nothing to scroll.
+    pub fn compute(
+        grid: &Grid,
+        window_w: f32,
+        (top, bottom): (f32, f32),
+        scale: f32,
+    ) -> Option<Self> {
+        let history = grid.scrollback.len();
+        let height = bottom - top;
+        if history == 0 || height <= 0.0 {
+            return None;
+        }
+        let total = history + grid.lines;
+        let thumb_height = (height * grid.lines as f32 / total as f32)
+            .max(MIN_THUMB_HEIGHT * scale)
+            .min(height);
+        let first_row = history - grid.display_offset.min(history);
+        let travel = height - thumb_height;
+        let width = SCROLLBAR_WIDTH as f32 * scale;
+        Some(Self {
+            x: window_w - width,
+            width,
+            top,
+            height,
+            thumb_top: top + travel * first_row as f32 / history as f32,
+            thumb_height,
+            history,
+            total,
+        })
+    }
+
+    /// Whether the point is on the track.
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x && y >= self.top && y < self.top + self.height
+    }
+
+    /// Whether `y` is on the thumb.
+    pub fn thumb_contains(&self, y: f32) -> bool {
+        y >= self.thumb_top && y < self.thumb_top + self.thumb_height
+    }
+
+    /// Where absolute row `abs_row` sits on the track, for its marker.
+    pub fn row_y(&self, abs_row: usize) -> f32 {
+        self.top + self.height * (abs_row as f32 + 0.5) / self.total as f32
+    }
+
+    /// The display offset that puts the thumb's top at `thumb_top`.
+    pub fn offset_for_thumb_top(&self, thumb_top: f32) -> usize {
+        let travel = self.height - self.thumb_height;
+        let frac = if travel > 0.0 {
+            ((thumb_top - self.top) / travel).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        self.history - (frac * self.history as f32).round() as usize
+    }
+}
+
+#[cfg(test)]
+mod tests;
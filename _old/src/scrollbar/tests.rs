@@ -0,0 +1,38 @@
+use super::*;
+
+fn grid_with_history(history: usize) -> Grid {
+    let mut grid = Grid::new(10, 10);
+    for _ in 0..history {
+        grid.scroll_up(1);
+    }
+    grid
+}
+
+#[test]
+fn no_scrollbar_without_history() {
+    assert_eq!(ScrollbarLayout::compute(&Grid::new(10, 10), 800.0, (0.0, 400.0), 1.0), None);
+}
+
+#[test]
+fn thumb_tracks_the_viewport() {
+    let mut grid = grid_with_history(30);
+    // 10 of 40 rows on screen: a quarter of the track, at the bottom.
+    let at_bottom = ScrollbarLayout::compute(&grid, 800.0, (0.0, 400.0), 1.0).expect("layout");
+    assert!((at_bottom.thumb_height - 100.0).abs() < 1e-3);
+    assert!((at_bottom.thumb_top - 300.0).abs() < 1e-3);
+    assert!((at_bottom.x - 792.0).abs() < 1e-3);
+
+    grid.display_offset = 30;
+    let at_top = ScrollbarLayout::compute(&grid, 800.0, (0.0, 400.0), 1.0).expect("layout");
+    assert!(at_top.thumb_top.abs() < 1e-3);
+    assert!((at_top.row_y(20) - 205.0).abs() < 1e-3);
+}
+
+#[test]
+fn dragging_the_thumb_picks_an_offset() {
+    let grid = grid_with_history(30);
+    let layout = ScrollbarLayout::compute(&grid, 800.0, (0.0, 400.0), 1.0).expect("layout");
+    assert_eq!(layout.offset_for_thumb_top(300.0), 0);
+    assert_eq!(layout.offset_for_thumb_top(-50.0), 30);
+    assert_eq!(layout.offset_for_thumb_top(150.0), 15);
+}
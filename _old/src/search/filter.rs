@@ -0,0 +1,36 @@
+//! Filter mode: the viewport shows only the rows with a match, like
+//! `&pattern` in `less`, scrolled to keep the focused match in view.
+
+use crate::grid::Grid;
+
+use super::SearchState;
+
+impl SearchState {
+    /// In filter mode, the absolute rows to show in place of the viewport,
+    /// top to bottom: up to a screenful of the rows holding matches, around
+    /// the focused one. `None` outside filter mode.
+    pub fn filter_rows(&self, grid: &Grid) -> Option<Vec<usize>> {
+        if !self.filter {
+            return None;
+        }
+        let mut rows: Vec<usize> = self
+            .matches
+            .iter()
+            .filter_map(|m| m.start_row.to_absolute(grid))
+            .collect();
+        rows.dedup();
+        let focused = self
+            .focused_match()
+            .and_then(|m| m.start_row.to_absolute(grid))
+            .and_then(|row| rows.binary_search(&row).ok())
+            .unwrap_or(0);
+        // Keep the focused row in the middle, but never leave blank rows at
+        // the bottom while there are more above.
+        let first = focused
+            .saturating_sub(grid.lines / 2)
+            .min(rows.len().saturating_sub(grid.lines));
+        rows.truncate(first + grid.lines);
+        rows.drain(..first);
+        Some(rows)
+    }
+}
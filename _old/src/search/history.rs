@@ -0,0 +1,113 @@
+//! Recent search queries, recalled with Up and Down in the search bar.
+//!
+//! The history is shared by every tab. It lives for the session, and is
+//! kept in `search_history.txt`, one query per line, when
+//! `behavior.save_search_history` is on.
+
+use std::collections::VecDeque;
+use std::path::Path;
+
+use crate::log;
+
+/// Queries kept.
+const CAPACITY: usize = 100;
+
+/// Recent search queries, newest first, and where Up/Down recall is.
+#[derive(Debug, Default)]
+pub struct SearchHistory {
+    entries: VecDeque<String>,
+    /// The entry shown by recall, `None` while editing a new query.
+    recalled: Option<usize>,
+    /// The query being typed when recall started, restored by Down past
+    /// the newest entry.
+    draft: String,
+}
+
+impl SearchHistory {
+    /// Read the history saved at `path`. Empty if there is none.
+    pub fn load(path: &Path) -> Self {
+        let entries = match std::fs::read_to_string(path) {
+            Ok(data) => data
+                .lines()
+                .filter(|l| !l.is_empty())
+                .take(CAPACITY)
+                .map(str::to_owned)
+                .collect(),
+            Err(e) => {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    log(&format!(
+                        "search history: failed to read {}: {e}",
+                        path.display()
+                    ));
+                }
+                VecDeque::new()
+            }
+        };
+        Self {
+            entries,
+            ..Self::default()
+        }
+    }
+
+    /// Write the history to `path`, newest first.
+    pub fn save(&self, path: &Path) {
+        let mut data = String::new();
+        for entry in &self.entries {
+            data.push_str(entry);
+            data.push('\n');
+        }
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        if let Err(e) = std::fs::write(path, data) {
+            log(&format!(
+                "search history: failed to write {}: {e}",
+                path.display()
+            ));
+        }
+    }
+
+    /// Record a searched query. A repeat moves to the front.
+    pub fn push(&mut self, query: &str) {
+        self.reset_recall();
+        if query.is_empty() || query.contains('\n') {
+            return;
+        }
+        self.entries.retain(|e| e != query);
+        self.entries.push_front(query.to_owned());
+        self.entries.truncate(CAPACITY);
+    }
+
+    /// Leave recall, e.g. when the query is edited or the bar reopens.
+    pub fn reset_recall(&mut self) {
+        self.recalled = None;
+        self.draft.clear();
+    }
+
+    /// The query before the one shown (Up), remembering `current` as the
+    /// draft when recall starts. `None` at the oldest entry.
+    pub fn older(&mut self, current: &str) -> Option<&str> {
+        let next = self.recalled.map_or(0, |i| i + 1);
+        let entry = self.entries.get(next)?;
+        if self.recalled.is_none() {
+            current.clone_into(&mut self.draft);
+        }
+        self.recalled = Some(next);
+        Some(entry)
+    }
+
+    /// The query after the one shown (Down); past the newest, the draft.
+    /// `None` when not recalling.
+    pub fn newer(&mut self) -> Option<&str> {
+        match self.recalled? {
+            0 => {
+                self.recalled = None;
+                Some(&self.draft)
+            }
+            i => {
+                self.recalled = Some(i - 1);
+                self.entries.get(i - 1).map(String::as_str)
+            }
+        }
+    }
+}
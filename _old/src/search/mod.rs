@@ -1,13 +1,40 @@
 //! Search functionality — plain text and regex search across grid content.
+//!
+//! Matches follow new output: [`SearchState::refresh`] re-searches only the
+//! rows that can have changed since the last search (the screen, and rows
+//! that scrolled off it), so the scrollback is searched once per query.
+//! Long scrollback is searched in the background (see [`worker`]).
+//! Queries are compiled and matched by `oriterm_core`'s search; this module
+//! feeds it this grid's rows and keeps the session state.
 
-mod find;
+mod filter;
+mod history;
 #[cfg(test)]
 mod tests;
 mod text;
+mod worker;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use oriterm_core::search::SearchQuery;
+use worker::SearchJob;
 
 use crate::grid::{Grid, StableRowIndex};
+use crate::sync::FairMutex;
+use crate::tab::terminal_state::TerminalState;
+use crate::ui::text_input::TextInput;
+
+pub use history::SearchHistory;
+pub use worker::SearchBatch;
+
+/// Frames of the spinner shown while a background search runs.
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+/// How long each spinner frame shows.
+const SPINNER_FRAME: Duration = Duration::from_millis(100);
 
 pub(crate) use text::extract_row_text;
+pub use oriterm_core::search::{SearchMatch, SearchOptions};
 
 /// Type of match at a cell position.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -15,25 +42,56 @@ pub enum MatchType {
     None,
     Match,
     FocusedMatch,
+    /// Inside a regex capture group of a match.
+    Capture,
+    /// Inside a capture group of the focused match.
+    FocusedCapture,
+}
+
+/// What the last search covered, for [`SearchState::refresh`].
+#[derive(Debug, Clone, Copy)]
+struct Scanned {
+    /// The row that was at the top of the screen. It and every row after it
+    /// may have changed since; the rows before it are scrollback and can't.
+    screen_top: StableRowIndex,
+    /// Grid size: a resize reflows the rows and renumbers them.
+    cols: usize,
+    lines: usize,
 }
 
-/// A single search match span in stable grid coordinates.
-#[derive(Debug, Clone)]
-pub struct SearchMatch {
-    pub start_row: StableRowIndex,
-    pub start_col: usize,
-    pub end_row: StableRowIndex,
-    pub end_col: usize,
+impl Scanned {
+    /// Where `grid` stands now.
+    fn of(grid: &Grid) -> Self {
+        Self {
+            screen_top: StableRowIndex::from_absolute(grid, grid.scrollback.len()),
+            cols: grid.cols,
+            lines: grid.lines,
+        }
+    }
 }
 
 /// State for an active search session, including query, matches, and navigation.
 #[derive(Default)]
 pub struct SearchState {
-    pub query: String,
+    /// The query field.
+    pub input: TextInput,
     pub matches: Vec<SearchMatch>,
     pub focused: usize,
-    pub case_sensitive: bool,
-    pub use_regex: bool,
+    pub options: SearchOptions,
+    /// Filter mode (Alt+F): show only the rows with matches.
+    pub filter: bool,
+    /// Whether the last step to the next or previous match wrapped around
+    /// the end of the matches.
+    pub wrapped: bool,
+    /// Whether the matches are of the alternate screen; switching screens
+    /// needs a full search.
+    pub on_alt_screen: bool,
+    matcher: Option<SearchQuery>,
+    scanned: Option<Scanned>,
+    /// The background search in progress, if any.
+    job: Option<SearchJob>,
+    /// Bumped per query, to tell a stale [`SearchBatch`] from a current one.
+    generation: u64,
 }
 
 impl SearchState {
@@ -42,17 +100,24 @@ impl SearchState {
         Self::default()
     }
 
+    /// The query text.
+    pub fn query(&self) -> &str {
+        self.input.text()
+    }
+
     /// Advance to the next match, wrapping around.
     pub fn next_match(&mut self) {
         if !self.matches.is_empty() {
             self.focused = (self.focused + 1) % self.matches.len();
+            self.wrapped = self.focused == 0;
         }
     }
 
     /// Go to the previous match, wrapping around.
     pub fn prev_match(&mut self) {
         if !self.matches.is_empty() {
-            if self.focused == 0 {
+            self.wrapped = self.focused == 0;
+            if self.wrapped {
                 self.focused = self.matches.len() - 1;
             } else {
                 self.focused -= 1;
@@ -62,18 +127,133 @@ impl SearchState {
 
     /// Re-run the search with the current query settings.
     pub fn update_query(&mut self, grid: &Grid) {
-        if self.query.is_empty() {
-            self.matches.clear();
-            self.focused = 0;
-            return;
-        }
-        self.matches = find::find_matches(grid, &self.query, self.case_sensitive, self.use_regex);
+        self.reset_query();
+        self.rescan_from(grid, 0);
         // Clamp focused index
-        if self.matches.is_empty() {
-            self.focused = 0;
+        self.focused = self.focused.min(self.matches.len().saturating_sub(1));
+    }
+
+    /// Re-run the search with the current query settings on a background
+    /// thread, which hands its results to `send` for [`Self::apply_batch`].
+    pub fn update_query_in_background(
+        &mut self,
+        terminal: &Arc<FairMutex<TerminalState>>,
+        send: impl Fn(SearchBatch) + Send + 'static,
+    ) {
+        self.reset_query();
+        if let Some(matcher) = self.matcher.clone() {
+            let terminal = Arc::clone(terminal);
+            self.job = Some(SearchJob::start(terminal, matcher, self.generation, send));
+        }
+    }
+
+    /// Compile the query and forget the old matches.
+    fn reset_query(&mut self) {
+        self.matcher = if self.input.is_empty() {
+            None
         } else {
-            self.focused = self.focused.min(self.matches.len() - 1);
+            SearchQuery::new(self.input.text(), self.options)
+        };
+        self.matches.clear();
+        self.scanned = None;
+        self.job = None;
+        self.wrapped = false;
+        self.generation += 1;
+    }
+
+    /// Take in a batch of background search results. Once the last one is
+    /// in, catches up with output that arrived meanwhile. Returns whether
+    /// the focus moved to another match.
+    pub fn apply_batch(&mut self, batch: SearchBatch, grid: &Grid) -> bool {
+        if batch.generation != self.generation || self.job.is_none() {
+            return false;
+        }
+        let focused = self.focused_match().map(|m| (m.start_row, m.start_col));
+        self.matches.retain(|m| m.start_row < batch.from);
+        self.matches.extend(batch.matches);
+        let moved = self.refocus(focused);
+        if let Some(scanned) = batch.done {
+            self.scanned = Some(scanned);
+            self.job = None;
+            return self.refresh(grid) || moved;
+        }
+        moved
+    }
+
+    /// Spinner frame while a background search runs, `None` otherwise.
+    pub fn spinner(&self) -> Option<char> {
+        let job = self.job.as_ref()?;
+        let frame = job.started.elapsed().as_millis() / SPINNER_FRAME.as_millis();
+        SPINNER.get(frame as usize % SPINNER.len()).copied()
+    }
+
+    /// Bring the matches up to date with new output, searching only the
+    /// rows that may have changed. The focused match stays focused while it
+    /// still matches. Returns whether the focus moved to another match.
+    pub fn refresh(&mut self, grid: &Grid) -> bool {
+        if self.job.is_some() {
+            // The background search reaches the screen last and catches up.
+            return false;
+        }
+        let focused = self.focused_match().map(|m| (m.start_row, m.start_col));
+        let from = match self.scanned {
+            Some(scanned) if scanned.cols == grid.cols && scanned.lines == grid.lines => {
+                // The old screen top was evicted: everything left is newer.
+                scanned.screen_top.to_absolute(grid).unwrap_or(0)
+            }
+            _ => 0,
+        };
+        let stale = StableRowIndex::from_absolute(grid, from);
+        let first = StableRowIndex::from_absolute(grid, 0);
+        self.matches.retain(|m| m.start_row >= first && m.start_row < stale);
+        self.rescan_from(grid, from);
+        self.refocus(focused)
+    }
+
+    /// Search absolute rows `from..` and append their matches.
+    fn rescan_from(&mut self, grid: &Grid, from: usize) {
+        let total = grid.scrollback.len() + grid.lines;
+        if let Some(matcher) = &self.matcher {
+            text::find_in_rows(matcher, grid, from..total, &mut self.matches);
+        }
+        self.scanned = Some(Scanned::of(grid));
+    }
+
+    /// Focus the match starting at `focused` again, or the first one after
+    /// it if it no longer matches. Returns whether the focus moved.
+    fn refocus(&mut self, focused: Option<(StableRowIndex, usize)>) -> bool {
+        let Some(last) = self.matches.len().checked_sub(1) else {
+            self.focused = 0;
+            return false;
+        };
+        let Some(start) = focused else {
+            self.focused = 0;
+            return true;
+        };
+        let idx = self
+            .matches
+            .partition_point(|m| (m.start_row, m.start_col) < start)
+            .min(last);
+        self.focused = idx;
+        self.matches
+            .get(idx)
+            .is_none_or(|m| (m.start_row, m.start_col) != start)
+    }
+
+    /// Drop the matches that start in rows no longer in `grid` (evicted or
+    /// cleared scrollback). Returns whether any were dropped.
+    pub fn drop_evicted(&mut self, grid: &Grid) -> bool {
+        let first = StableRowIndex::from_absolute(grid, 0);
+        let gone = self.matches.partition_point(|m| m.start_row < first);
+        if gone == 0 {
+            return false;
         }
+        self.matches.drain(..gone);
+        self.focused = self
+            .focused
+            .saturating_sub(gone)
+            .min(self.matches.len().saturating_sub(1));
+        true
     }
 
     /// Returns the currently focused match, if any.
@@ -93,10 +273,12 @@ impl SearchState {
         for i in idx.saturating_sub(1)..self.matches.len().min(idx + 2) {
             let m = &self.matches[i];
             if cell_in_match(m, stable_row, col) {
-                return if i == self.focused {
-                    MatchType::FocusedMatch
-                } else {
-                    MatchType::Match
+                let in_capture = m.captures.iter().any(|&(start, end)| (start..=end).contains(&col));
+                return match (i == self.focused, in_capture) {
+                    (true, true) => MatchType::FocusedCapture,
+                    (true, false) => MatchType::FocusedMatch,
+                    (false, true) => MatchType::Capture,
+                    (false, false) => MatchType::Match,
                 };
             }
         }
@@ -1,10 +1,31 @@
 //! Tests for search functionality.
 
+use oriterm_core::search::SearchQuery;
+
 use crate::grid::{Grid, StableRowIndex};
 
-use super::find::find_matches;
+use super::text::find_in_rows;
 use super::*;
 
+/// Find all matches in the grid for the given query, earliest first.
+fn find_matches(
+    grid: &Grid,
+    query: &str,
+    case_sensitive: bool,
+    use_regex: bool,
+) -> Vec<SearchMatch> {
+    let options = SearchOptions {
+        case_sensitive,
+        use_regex,
+        whole_word: false,
+    };
+    let mut matches = Vec::new();
+    if let Some(query) = SearchQuery::new(query, options) {
+        find_in_rows(&query, grid, 0..grid.scrollback.len() + grid.lines, &mut matches);
+    }
+    matches
+}
+
 /// Helper to create a `StableRowIndex` from a raw value for tests.
 fn sri(n: u64) -> StableRowIndex {
     StableRowIndex(n)
@@ -84,7 +105,7 @@ fn search_invalid_regex() {
 fn search_empty_query() {
     let mut state = SearchState::new();
     let grid = Grid::new(20, 1);
-    state.query = String::new();
+    state.input.clear();
     state.update_query(&grid);
     assert!(state.matches.is_empty());
 }
@@ -98,18 +119,21 @@ fn search_next_prev() {
             start_col: 0,
             end_row: sri(0),
             end_col: 2,
+            captures: Vec::new(),
         },
         SearchMatch {
             start_row: sri(1),
             start_col: 0,
             end_row: sri(1),
             end_col: 2,
+            captures: Vec::new(),
         },
         SearchMatch {
             start_row: sri(2),
             start_col: 0,
             end_row: sri(2),
             end_col: 2,
+            captures: Vec::new(),
         },
     ];
     state.focused = 0;
@@ -136,12 +160,14 @@ fn cell_match_type_check() {
             start_col: 5,
             end_row: sri(0),
             end_col: 9,
+            captures: Vec::new(),
         },
         SearchMatch {
             start_row: sri(2),
             start_col: 0,
             end_row: sri(2),
             end_col: 3,
+            captures: Vec::new(),
         },
     ];
     state.focused = 0;
@@ -153,3 +179,178 @@ fn cell_match_type_check() {
     assert_eq!(state.cell_match_type(sri(2), 1), MatchType::Match);
     assert_eq!(state.cell_match_type(sri(1), 0), MatchType::None);
 }
+
+/// Write `text` at the start of screen row `line`.
+fn put_line(grid: &mut Grid, line: usize, text: &str) {
+    for (i, c) in text.chars().enumerate() {
+        grid.goto(line, i);
+        grid.put_char(c);
+    }
+}
+
+fn searching(query: &str, grid: &Grid) -> SearchState {
+    let mut state = SearchState::new();
+    state.input.commit(query);
+    state.update_query(grid);
+    state
+}
+
+#[test]
+fn refresh_finds_new_output_and_keeps_focus() {
+    let mut grid = Grid::new(20, 3);
+    put_line(&mut grid, 0, "err one");
+    put_line(&mut grid, 1, "err two");
+    let mut state = searching("err", &grid);
+    state.next_match();
+    assert_eq!(state.focused_match().map(|m| m.start_row), Some(sri(1)));
+
+    // New output scrolls the first row off the screen.
+    grid.scroll_up(1);
+    put_line(&mut grid, 2, "no match");
+    put_line(&mut grid, 1, "err three");
+    assert!(!state.refresh(&grid));
+    let rows: Vec<_> = state.matches.iter().map(|m| m.start_row).collect();
+    assert_eq!(rows, [sri(0), sri(1), sri(2)]);
+    assert_eq!(state.focused_match().map(|m| m.start_row), Some(sri(1)));
+
+    // The focused match is overwritten: the focus moves to the next one.
+    put_line(&mut grid, 0, "ok  two");
+    assert!(state.refresh(&grid));
+    assert_eq!(state.focused_match().map(|m| m.start_row), Some(sri(2)));
+}
+
+#[test]
+fn regex_captures_are_highlighted() {
+    let mut grid = Grid::new(20, 1);
+    put_line(&mut grid, 0, "key=value");
+    let mut state = SearchState::new();
+    state.options.use_regex = true;
+    state.input.commit(r"(\w+)=(\w+)");
+    state.update_query(&grid);
+    assert_eq!(state.matches.len(), 1);
+    assert_eq!(state.matches[0].captures, [(0, 2), (4, 8)]);
+    assert_eq!(state.cell_match_type(sri(0), 1), MatchType::FocusedCapture);
+    assert_eq!(state.cell_match_type(sri(0), 3), MatchType::FocusedMatch);
+}
+
+#[test]
+fn plain_search_steps_over_multibyte_characters() {
+    let mut grid = Grid::new(20, 1);
+    put_line(&mut grid, 0, "ééé");
+    assert_eq!(find_matches(&grid, "éé", true, false).len(), 2);
+}
+
+#[test]
+fn background_search_streams_every_match() {
+    use std::sync::Arc;
+    use std::sync::mpsc;
+
+    use vte::ansi::CursorShape;
+
+    use crate::sync::FairMutex;
+    use crate::tab::terminal_state::TerminalState;
+
+    let mut term = TerminalState::new(10, 3, 5000, CursorShape::Block, String::new(), false);
+    for i in 0..4500 {
+        put_line(&mut term.primary_grid, 2, if i % 3 == 0 { "hit" } else { "miss" });
+        term.primary_grid.scroll_up(1);
+    }
+    let expected = find_matches(&term.primary_grid, "hit", false, false).len();
+    let terminal = Arc::new(FairMutex::new(term));
+
+    let mut state = SearchState::new();
+    state.input.commit("hit");
+    let (tx, rx) = mpsc::channel();
+    state.update_query_in_background(&terminal, move |batch| {
+        let _ = tx.send(batch);
+    });
+    let mut batches = 0;
+    while state.spinner().is_some() {
+        let batch = rx.recv().expect("batch");
+        batches += 1;
+        state.apply_batch(batch, terminal.lock().active_grid());
+    }
+    assert!(batches > 1);
+    assert_eq!(state.matches.len(), expected);
+    assert_eq!(expected, 1500);
+}
+
+#[test]
+fn whole_word_skips_partial_words() {
+    let mut grid = Grid::new(30, 1);
+    put_line(&mut grid, 0, "cat concat cat_x -cat- Cat");
+    let mut state = searching("cat", &grid);
+    assert_eq!(state.matches.len(), 5);
+
+    state.options.whole_word = true;
+    state.update_query(&grid);
+    let cols: Vec<_> = state.matches.iter().map(|m| m.start_col).collect();
+    assert_eq!(cols, [0, 18, 23]);
+
+    // A query ending in punctuation ("cat-") still matches a whole word.
+    state.input.commit("-");
+    state.update_query(&grid);
+    assert_eq!(state.matches.iter().map(|m| m.start_col).collect::<Vec<_>>(), [18]);
+}
+
+#[test]
+fn stepping_past_the_end_is_flagged_as_wrapped() {
+    let mut grid = Grid::new(20, 2);
+    put_line(&mut grid, 0, "a");
+    put_line(&mut grid, 1, "a");
+    let mut state = searching("a", &grid);
+    state.next_match();
+    assert!(!state.wrapped);
+    state.next_match();
+    assert!(state.wrapped);
+    state.prev_match();
+    assert!(state.wrapped);
+    state.prev_match();
+    assert!(!state.wrapped);
+}
+
+#[test]
+fn history_recalls_older_then_newer_queries() {
+    let mut history = SearchHistory::default();
+    history.push("first");
+    history.push("second");
+    history.push("first");
+
+    assert_eq!(history.older("draft"), Some("first"));
+    assert_eq!(history.older("first"), Some("second"));
+    assert_eq!(history.older("second"), None);
+    assert_eq!(history.newer(), Some("first"));
+    assert_eq!(history.newer(), Some("draft"));
+    assert_eq!(history.newer(), None);
+}
+
+#[test]
+fn history_round_trips_through_a_file() {
+    let path = std::env::temp_dir().join(format!("oriterm-search-{}.txt", std::process::id()));
+    let mut history = SearchHistory::default();
+    history.push("old");
+    history.push("new");
+    history.save(&path);
+
+    let mut loaded = SearchHistory::load(&path);
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(loaded.older(""), Some("new"));
+    assert_eq!(loaded.older("new"), Some("old"));
+}
+
+#[test]
+fn filter_shows_matching_rows_around_the_focus() {
+    let mut grid = Grid::new(10, 3);
+    for i in 0..9 {
+        put_line(&mut grid, 2, if i % 2 == 0 { "hit" } else { "miss" });
+        grid.scroll_up(1);
+    }
+    let mut state = searching("hit", &grid);
+    assert_eq!(state.filter_rows(&grid), None);
+
+    state.filter = true;
+    // Rows 2, 4, 6, 8, and 10 match; three fill the screen.
+    assert_eq!(state.filter_rows(&grid), Some(vec![2, 4, 6]));
+    state.focused = 3;
+    assert_eq!(state.filter_rows(&grid), Some(vec![6, 8, 10]));
+}
@@ -1,10 +1,19 @@
 //! Row text extraction utilities for search and URL detection.
 
+use std::ops::Range;
+
+use oriterm_core::search::SearchQuery;
+
+use super::SearchMatch;
 use crate::cell::CellFlags;
+use crate::grid::row::Row;
+use crate::grid::{Grid, StableRowIndex};
 
-/// Extract text from a single grid row, returning the text and a mapping
-/// from character index (in the returned string) to column index.
-pub(crate) fn extract_row_text(row: &crate::grid::row::Row) -> (String, Vec<usize>) {
+/// Extract text from a single grid row, returning the text and, for each
+/// byte of it, the column it came from: the byte-to-column map
+/// `oriterm_core`'s search expects. Combining marks map to their base
+/// character's column.
+pub(crate) fn extract_row_text(row: &Row) -> (String, Vec<usize>) {
     let mut text = String::new();
     let mut col_map = Vec::new();
     for (col, cell) in row.iter().enumerate() {
@@ -13,59 +22,26 @@ pub(crate) fn extract_row_text(row: &crate::grid::row::Row) -> (String, Vec<usiz
         {
             continue;
         }
-        let c = if cell.c == '\0' { ' ' } else { cell.c };
-        text.push(c);
-        col_map.push(col);
-        for &zw in cell.zerowidth() {
-            text.push(zw);
-        }
+        text.push(if cell.c == '\0' { ' ' } else { cell.c });
+        text.extend(cell.zerowidth());
+        col_map.resize(text.len(), col);
     }
     (text, col_map)
 }
 
-/// Convert a byte span in the extracted text to column indices.
-/// Returns `(start_col, end_col)` inclusive, or `None` if the span is empty.
-pub(crate) fn byte_span_to_cols(
-    text: &str,
-    col_map: &[usize],
-    byte_start: usize,
-    byte_end: usize,
-) -> Option<(usize, usize)> {
-    if byte_start >= byte_end {
-        return None;
-    }
-
-    let char_idx_start = char_index_at_byte(text, byte_start);
-    let char_idx_end = char_index_containing_byte(text, byte_end);
-
-    // col_map only has entries for non-spacer cells, so char indices
-    // from zero-width chars may exceed col_map length.
-    let start_col = col_map.get(char_idx_start).copied()?;
-    let end_col = col_map.get(char_idx_end).copied()?;
-    Some((start_col, end_col))
-}
-
-/// Return the char index of the first character starting at or after `byte_offset`.
-fn char_index_at_byte(text: &str, byte_offset: usize) -> usize {
-    let mut pos = 0;
-    for (i, ch) in text.chars().enumerate() {
-        if pos >= byte_offset {
-            return i;
-        }
-        pos += ch.len_utf8();
-    }
-    text.chars().count()
-}
-
-/// Return the char index of the character whose encoding contains `byte_offset`
-/// (i.e., the last character whose cumulative byte position reaches `byte_offset`).
-fn char_index_containing_byte(text: &str, byte_offset: usize) -> usize {
-    let mut pos = 0;
-    for (i, ch) in text.chars().enumerate() {
-        pos += ch.len_utf8();
-        if pos >= byte_offset {
-            return i;
+/// Append the matches of `query` in absolute rows `rows` of `grid` to
+/// `out`, in order.
+pub(super) fn find_in_rows(
+    query: &SearchQuery,
+    grid: &Grid,
+    rows: Range<usize>,
+    out: &mut Vec<SearchMatch>,
+) {
+    for abs_row in rows {
+        if let Some(row) = grid.absolute_row(abs_row) {
+            let (text, col_map) = extract_row_text(row);
+            let stable = StableRowIndex::from_absolute(grid, abs_row);
+            query.find_in_text(&text, &col_map, stable, out);
         }
     }
-    text.chars().count().saturating_sub(1)
 }
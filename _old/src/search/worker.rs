@@ -0,0 +1,126 @@
+//! Background search over long scrollback, so typing a query never blocks
+//! the event loop. The worker searches a batch of rows per terminal lock
+//! and streams each batch's matches back as it goes.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Instant;
+
+use oriterm_core::search::SearchQuery;
+
+use super::text::find_in_rows;
+use super::{Scanned, SearchMatch};
+use crate::grid::{Grid, StableRowIndex};
+use crate::sync::FairMutex;
+use crate::tab::terminal_state::TerminalState;
+
+/// Scrollback rows searched per terminal lock.
+const BATCH_ROWS: usize = 2000;
+
+/// Matches from one batch of rows of a background search.
+#[derive(Debug)]
+pub struct SearchBatch {
+    /// The query this batch answers; batches of an older query are dropped.
+    pub(super) generation: u64,
+    /// First row searched. Matches from it on replace those found before,
+    /// which matters when the search restarts.
+    pub(super) from: StableRowIndex,
+    pub(super) matches: Vec<SearchMatch>,
+    /// On the last batch, what the whole search covered.
+    pub(super) done: Option<Scanned>,
+}
+
+/// A background search. Dropping it stops the worker after its batch.
+pub(super) struct SearchJob {
+    cancel: Arc<AtomicBool>,
+    /// When it started, for the spinner.
+    pub(super) started: Instant,
+}
+
+impl Drop for SearchJob {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+impl SearchJob {
+    /// Search the active grid of `terminal` on a background thread,
+    /// handing each batch to `send`.
+    pub(super) fn start(
+        terminal: Arc<FairMutex<TerminalState>>,
+        matcher: SearchQuery,
+        generation: u64,
+        send: impl Fn(SearchBatch) + Send + 'static,
+    ) -> Self {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let stop = Arc::clone(&cancel);
+        thread::spawn(move || {
+            let mut worker = Worker {
+                matcher,
+                generation,
+                next: None,
+                shape: None,
+            };
+            while !stop.load(Ordering::Relaxed) {
+                let batch = worker.search_batch(&terminal.lock());
+                let done = batch.done.is_some();
+                send(batch);
+                if done {
+                    break;
+                }
+            }
+        });
+        Self {
+            cancel,
+            started: Instant::now(),
+        }
+    }
+}
+
+/// The worker thread's progress through the grid.
+struct Worker {
+    matcher: SearchQuery,
+    generation: u64,
+    /// The next row to search.
+    next: Option<StableRowIndex>,
+    /// Which screen and size the rows searched so far were on; a change
+    /// renumbers the rows, so the search starts over.
+    shape: Option<(bool, usize, usize)>,
+}
+
+impl Worker {
+    fn search_batch(&mut self, term: &TerminalState) -> SearchBatch {
+        let grid = term.active_grid();
+        let shape = Some((term.active_is_alt, grid.cols, grid.lines));
+        if self.shape != shape {
+            self.shape = shape;
+            self.next = None;
+        }
+        let history = grid.scrollback.len();
+        let total = history + grid.lines;
+        let start = self.next.map_or(0, |next| first_row_from(grid, next)).min(total);
+        // Once the screen is in reach, finish in this lock: the screen is
+        // what changes, and `done` records where it was.
+        let end = if start + BATCH_ROWS >= history {
+            total
+        } else {
+            start + BATCH_ROWS
+        };
+        let mut matches = Vec::new();
+        find_in_rows(&self.matcher, grid, start..end, &mut matches);
+        self.next = Some(StableRowIndex::from_absolute(grid, end));
+        SearchBatch {
+            generation: self.generation,
+            from: StableRowIndex::from_absolute(grid, start),
+            matches,
+            done: (end == total).then(|| Scanned::of(grid)),
+        }
+    }
+}
+
+/// Absolute row of `row`, or of the oldest row left if it was evicted.
+fn first_row_from(grid: &Grid, row: StableRowIndex) -> usize {
+    let oldest = StableRowIndex::from_absolute(grid, 0);
+    usize::try_from(row.0.saturating_sub(oldest.0)).unwrap_or(usize::MAX)
+}
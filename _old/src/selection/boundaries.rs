@@ -1,7 +1,8 @@
 //! Word and line boundary detection for selection.
 
 use crate::cell::CellFlags;
-use crate::grid::{Grid, WrapDetection};
+use crate::grid::{Grid, WrapDetection, ZoneKind};
+use crate::url_detect::{MatchAction, UrlDetectCache};
 
 /// Find word boundaries around (`abs_row`, `col`) in the grid.
 /// Returns (`start_col`, `end_col`) inclusive.
@@ -67,6 +68,30 @@ pub fn word_boundaries(grid: &Grid, abs_row: usize, col: usize) -> (usize, usize
     (start, end)
 }
 
+/// Find the cells selected by a double click at (`abs_row`, `col`).
+///
+/// Inside a URL that `url_detect` finds this is the whole URL, which may
+/// continue onto soft-wrapped rows; elsewhere it is the word from
+/// [`word_boundaries`]. Returns inclusive ((`start_row`, `start_col`),
+/// (`end_row`, `end_col`)) in absolute rows.
+pub fn word_span(
+    grid: &Grid,
+    urls: &mut UrlDetectCache,
+    abs_row: usize,
+    col: usize,
+) -> ((usize, usize), (usize, usize)) {
+    let url = urls
+        .match_at(grid, abs_row, col)
+        .filter(|m| matches!(m.action, MatchAction::Open(_)));
+    if let Some(segments) = url.map(|m| m.segments) {
+        if let (Some(&(sr, sc, _)), Some(&(er, _, ec))) = (segments.first(), segments.last()) {
+            return ((sr, sc), (er, ec));
+        }
+    }
+    let (start, end) = word_boundaries(grid, abs_row, col);
+    ((abs_row, start), (abs_row, end))
+}
+
 /// Classify characters for word boundary detection.
 fn char_class(c: char) -> u8 {
     if c.is_alphanumeric() || c == '_' {
@@ -87,3 +112,41 @@ pub fn logical_line_start(grid: &Grid, abs_row: usize) -> usize {
 pub fn logical_line_end(grid: &Grid, abs_row: usize) -> usize {
     grid.logical_line_end(abs_row, WrapDetection::WrapFlag)
 }
+
+/// Find the rows selected by a quadruple click at `abs_row`.
+///
+/// With shell-integration prompt marks this is the whole command block: from
+/// the prompt at or above `abs_row` to the row before the next prompt.
+/// Without marks it is the paragraph of non-blank rows around `abs_row`.
+/// Returns (`start_row`, `end_row`) inclusive.
+pub fn block_bounds(grid: &Grid, abs_row: usize) -> (usize, usize) {
+    let total = grid.scrollback.len() + grid.lines;
+    let prompts = || {
+        grid.zones()
+            .filter(|z| z.kind == ZoneKind::Prompt)
+            .map(|z| z.start)
+    };
+    let is_blank = |r: usize| grid.absolute_row(r).is_none_or(|row| row.content_len() == 0);
+
+    if let Some(start) = prompts().take_while(|&r| r <= abs_row).last() {
+        let end = match prompts().find(|&r| r > abs_row) {
+            Some(next) => next - 1,
+            // Current command: stop at the last row with content.
+            None => (abs_row..total).rev().find(|&r| !is_blank(r)).unwrap_or(abs_row),
+        };
+        return (start, end);
+    }
+
+    if is_blank(abs_row) {
+        return (abs_row, abs_row);
+    }
+    let mut start = abs_row;
+    while start > 0 && !is_blank(start - 1) {
+        start -= 1;
+    }
+    let mut end = abs_row;
+    while end + 1 < total && !is_blank(end + 1) {
+        end += 1;
+    }
+    (start, end)
+}
@@ -0,0 +1,86 @@
+//! Multi-click detection: double, triple, and quadruple clicks.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use winit::window::WindowId;
+
+/// Highest click count reported: 1 char, 2 word, 3 line, 4 command block.
+pub const MAX_CLICKS: u8 = 4;
+
+/// What a rapid click past [`MAX_CLICKS`] does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClickCycle {
+    /// Start over at a single click.
+    #[default]
+    Wrap,
+    /// Keep reporting the highest click count.
+    Hold,
+}
+
+/// Where and when the previous click landed.
+#[derive(Debug, Clone, Copy)]
+struct LastClick {
+    time: Instant,
+    window: WindowId,
+    cell: Option<(usize, usize)>,
+}
+
+/// Counts rapid clicks at the same spot.
+///
+/// A click continues the sequence when it lands in the same window and cell
+/// (`None` for non-grid targets like the tab bar) within the interval.
+#[derive(Debug, Clone)]
+pub struct ClickTracker {
+    interval: Duration,
+    cycle: ClickCycle,
+    last: Option<LastClick>,
+    count: u8,
+}
+
+impl ClickTracker {
+    /// Creates a tracker with the given multi-click interval and cycle policy.
+    pub fn new(interval: Duration, cycle: ClickCycle) -> Self {
+        Self {
+            interval,
+            cycle,
+            last: None,
+            count: 0,
+        }
+    }
+
+    /// Replaces the interval and cycle policy (config reload).
+    pub fn set_policy(&mut self, interval: Duration, cycle: ClickCycle) {
+        self.interval = interval;
+        self.cycle = cycle;
+    }
+
+    /// Records a press and returns its click count, from 1 to [`MAX_CLICKS`].
+    pub fn click(&mut self, now: Instant, window: WindowId, cell: Option<(usize, usize)>) -> u8 {
+        let continues = self.last.is_some_and(|last| {
+            last.window == window
+                && last.cell == cell
+                && now.saturating_duration_since(last.time) < self.interval
+        });
+
+        self.count = if !continues {
+            1
+        } else if self.count < MAX_CLICKS {
+            self.count + 1
+        } else {
+            match self.cycle {
+                ClickCycle::Wrap => 1,
+                ClickCycle::Hold => MAX_CLICKS,
+            }
+        };
+        self.last = Some(LastClick { time: now, window, cell });
+        self.count
+    }
+
+    /// Forgets the previous click so the next one counts as a single click.
+    pub fn reset(&mut self) {
+        self.last = None;
+        self.count = 0;
+    }
+}
@@ -1,14 +1,19 @@
 //! Selection — 3-point model (anchor, pivot, end) with char/word/line/block modes.
 
 mod boundaries;
+mod click;
 #[cfg(test)]
 mod tests;
 mod text;
 
-pub use boundaries::{logical_line_end, logical_line_start, word_boundaries};
-pub use text::extract_text;
+pub use boundaries::{
+    block_bounds, logical_line_end, logical_line_start, word_boundaries, word_span,
+};
+pub use click::{ClickCycle, ClickTracker, MAX_CLICKS};
+pub use text::{Concealed, TrailingWhitespace, extract_text};
 
-use crate::grid::StableRowIndex;
+use crate::cell::{Cell, CellFlags};
+use crate::grid::{Grid, StableRowIndex};
 
 /// Sub-cell precision for selection boundaries.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -125,6 +130,56 @@ impl Selection {
         }
     }
 
+    /// Create a selection of everything in `grid`, from the oldest
+    /// scrollback row through the last row of the screen.
+    pub fn all(grid: &Grid) -> Self {
+        let last_row = grid.scrollback.len() + grid.lines.saturating_sub(1);
+        Self::rows(grid, 0, last_row)
+    }
+
+    /// Create a selection of the output of the most recent command marked
+    /// with OSC 133 in `grid`.
+    pub fn last_output(grid: &Grid) -> Option<Self> {
+        let (first, last) = grid.last_output_rows()?;
+        Some(Self::rows(grid, first, last))
+    }
+
+    /// Create a selection of whole absolute rows `first..=last`.
+    fn rows(grid: &Grid, first: usize, last: usize) -> Self {
+        let anchor = SelectionPoint {
+            row: StableRowIndex::from_absolute(grid, first),
+            col: 0,
+            side: Side::Left,
+        };
+        let end = SelectionPoint {
+            row: StableRowIndex::from_absolute(grid, last),
+            col: grid.cols.saturating_sub(1),
+            side: Side::Right,
+        };
+        Self {
+            mode: SelectionMode::Char,
+            anchor,
+            pivot: anchor,
+            end,
+        }
+    }
+
+    /// Anchor, pivot, and end as `(row, column)`, for carrying them through
+    /// a reflow (see [`Grid::reflow_with_points`]).
+    pub fn positions(&self) -> [(StableRowIndex, usize); 3] {
+        [self.anchor, self.pivot, self.end].map(|p| (p.row, p.col))
+    }
+
+    /// Move anchor, pivot, and end to `positions`, in [`Self::positions`]
+    /// order. Sides are kept.
+    pub fn set_positions(&mut self, positions: [(StableRowIndex, usize); 3]) {
+        let points = [&mut self.anchor, &mut self.pivot, &mut self.end];
+        for (point, (row, col)) in points.into_iter().zip(positions) {
+            point.row = row;
+            point.col = col;
+        }
+    }
+
     /// Returns the normalized (start, end) range including the pivot.
     pub fn ordered(&self) -> (SelectionPoint, SelectionPoint) {
         let mut points = [self.anchor, self.pivot, self.end];
@@ -137,8 +192,7 @@ impl Selection {
         let (start, end) = self.ordered();
 
         if self.mode == SelectionMode::Block {
-            let min_col = start.col.min(end.col);
-            let max_col = start.col.max(end.col);
+            let (min_col, max_col) = self.block_columns();
             stable_row >= start.row
                 && stable_row <= end.row
                 && col >= min_col
@@ -161,6 +215,36 @@ impl Selection {
         }
     }
 
+    /// Like [`contains`](Self::contains), but a block selection that covers
+    /// either half of a wide char in `row` covers the whole char.
+    pub fn contains_cell(&self, stable_row: StableRowIndex, col: usize, row: &[Cell]) -> bool {
+        if self.mode != SelectionMode::Block {
+            return self.contains(stable_row, col);
+        }
+        let (start, end) = self.ordered();
+        let (min_col, max_col) = self.block_columns_in(row);
+        stable_row >= start.row && stable_row <= end.row && col >= min_col && col <= max_col
+    }
+
+    /// Leftmost and rightmost columns of a block selection.
+    pub fn block_columns(&self) -> (usize, usize) {
+        let (start, end) = self.ordered();
+        (start.col.min(end.col), start.col.max(end.col))
+    }
+
+    /// Block columns widened so they never split a wide char in `row`.
+    pub fn block_columns_in(&self, row: &[Cell]) -> (usize, usize) {
+        let (mut min_col, mut max_col) = self.block_columns();
+        let flags = |col: usize| row.get(col).map_or(CellFlags::empty(), |c| c.flags);
+        if min_col > 0 && flags(min_col).contains(CellFlags::WIDE_CHAR_SPACER) {
+            min_col -= 1;
+        }
+        if flags(max_col).contains(CellFlags::WIDE_CHAR) && max_col + 1 < row.len() {
+            max_col += 1;
+        }
+        (min_col, max_col)
+    }
+
     /// Returns true if this selection has zero area (anchor == end for Char mode).
     pub fn is_empty(&self) -> bool {
         self.mode == SelectionMode::Char && self.anchor == self.end
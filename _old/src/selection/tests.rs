@@ -1,6 +1,13 @@
 //! Tests for selection types, boundaries, and text extraction.
 
+use std::time::{Duration, Instant};
+
+use winit::window::WindowId;
+
+use crate::cell::CellFlags;
+use crate::grid::row::RowFlags;
 use crate::grid::{Grid, StableRowIndex};
+use crate::url_detect::UrlDetectCache;
 
 use super::*;
 
@@ -130,6 +137,42 @@ fn block_selection_contains() {
     assert!(!sel.contains(sri(6), 5));
 }
 
+/// Block selection from (`row0`, `col0`) to (`row1`, `col1`).
+fn block(row0: StableRowIndex, col0: usize, row1: StableRowIndex, col1: usize) -> Selection {
+    let anchor = SelectionPoint { row: row0, col: col0, side: Side::Left };
+    Selection {
+        mode: SelectionMode::Block,
+        anchor,
+        pivot: anchor,
+        end: SelectionPoint { row: row1, col: col1, side: Side::Right },
+    }
+}
+
+#[test]
+fn block_selection_snaps_over_wide_chars() {
+    // Row 0: [a, 漢, spacer, b, c]; row 1: "abcde".
+    let mut grid = Grid::new(10, 2);
+    grid.put_char('a');
+    grid.put_wide_char('漢');
+    grid.put_char('b');
+    grid.put_char('c');
+    write_row(&mut grid, 1, "abcde");
+    let top = StableRowIndex::from_absolute(&grid, 0);
+    let bottom = StableRowIndex::from_absolute(&grid, 1);
+
+    // Left edge on the spacer pulls in the whole char.
+    let sel = block(top, 2, bottom, 3);
+    assert_eq!(sel.block_columns(), (2, 3));
+    assert_eq!(sel.block_columns_in(grid.absolute_row(0).unwrap().as_slice()), (1, 3));
+    assert!(sel.contains_cell(top, 1, grid.absolute_row(0).unwrap().as_slice()));
+    assert!(!sel.contains_cell(bottom, 1, grid.absolute_row(1).unwrap().as_slice()));
+    assert_eq!(extract_text(&grid, &sel, Concealed::Include, TrailingWhitespace::Strip), "漢b\ncd");
+
+    // Right edge on the char pulls in its spacer.
+    let sel = block(top, 0, bottom, 1);
+    assert_eq!(extract_text(&grid, &sel, Concealed::Include, TrailingWhitespace::Strip), "a漢\nab");
+}
+
 #[test]
 fn word_boundaries_on_grid() {
     let mut grid = Grid::new(20, 1);
@@ -152,6 +195,20 @@ fn word_boundaries_on_grid() {
     assert_eq!(e, 5);
 }
 
+#[test]
+fn word_span_covers_a_whole_wrapped_url() {
+    let mut grid = Grid::new(20, 2);
+    for c in "go https://example.com/long/path ok".chars() {
+        grid.put_char(c);
+    }
+    let mut urls = UrlDetectCache::default();
+    // Clicking the second row of the URL selects all of it.
+    assert_eq!(word_span(&grid, &mut urls, 1, 2), ((0, 3), (1, 11)));
+    assert_eq!(word_span(&grid, &mut urls, 0, 8), ((0, 3), (1, 11)));
+    // Outside the URL it is an ordinary word.
+    assert_eq!(word_span(&grid, &mut urls, 1, 14), ((1, 13), (1, 14)));
+}
+
 #[test]
 fn word_boundaries_wide_char() {
     // Grid: "漢字 test" = [漢, spacer, 字, spacer, space, t, e, s, t]
@@ -220,7 +277,7 @@ fn extract_text_with_zerowidth() {
             side: Side::Right,
         },
     };
-    let text = extract_text(&grid, &sel);
+    let text = extract_text(&grid, &sel, Concealed::Include, TrailingWhitespace::Strip);
     assert_eq!(text, "e\u{0301}x");
 }
 
@@ -254,6 +311,146 @@ fn extract_text_simple() {
             side: Side::Right,
         },
     };
-    let text = extract_text(&grid, &sel);
+    let text = extract_text(&grid, &sel, Concealed::Include, TrailingWhitespace::Strip);
     assert_eq!(text, "Hello\nWorld");
 }
+
+#[test]
+fn extract_text_concealed_include_or_blank() {
+    let mut grid = Grid::new(10, 1);
+    grid.put_char('p');
+    grid.cursor.template.flags.insert(CellFlags::HIDDEN);
+    grid.put_char('w');
+    grid.cursor.template.flags.remove(CellFlags::HIDDEN);
+    grid.put_char('x');
+
+    let sel = Selection::new_char(sri(0), 0, Side::Left);
+    let sel = Selection {
+        end: SelectionPoint {
+            row: sri(0),
+            col: 2,
+            side: Side::Right,
+        },
+        ..sel
+    };
+    assert_eq!(extract_text(&grid, &sel, Concealed::Include, TrailingWhitespace::Strip), "pwx");
+    assert_eq!(extract_text(&grid, &sel, Concealed::Blank, TrailingWhitespace::Strip), "p x");
+}
+
+fn write_row(grid: &mut Grid, line: usize, text: &str) {
+    for (i, c) in text.chars().enumerate() {
+        grid.goto(line, i);
+        grid.put_char(c);
+    }
+}
+
+#[test]
+fn block_bounds_paragraph() {
+    let mut grid = Grid::new(10, 6);
+    write_row(&mut grid, 0, "one");
+    write_row(&mut grid, 1, "two");
+    write_row(&mut grid, 3, "three");
+    write_row(&mut grid, 4, "four");
+    assert_eq!(block_bounds(&grid, 1), (0, 1));
+    assert_eq!(block_bounds(&grid, 3), (3, 4));
+    assert_eq!(block_bounds(&grid, 2), (2, 2));
+}
+
+#[test]
+fn block_bounds_command_output() {
+    let mut grid = Grid::new(10, 6);
+    write_row(&mut grid, 0, "$ ls");
+    write_row(&mut grid, 1, "a");
+    write_row(&mut grid, 3, "b");
+    write_row(&mut grid, 4, "$ pwd");
+    grid.row_mut(0).flags.insert(RowFlags::PROMPT_START);
+    grid.row_mut(4).flags.insert(RowFlags::PROMPT_START);
+    // Blank lines inside the output do not split the block.
+    assert_eq!(block_bounds(&grid, 3), (0, 3));
+    // Last command runs to the last row with content.
+    assert_eq!(block_bounds(&grid, 4), (4, 4));
+}
+
+#[test]
+fn click_tracker_counts_and_wraps() {
+    let window = WindowId::dummy();
+    let t0 = Instant::now();
+    let ms = Duration::from_millis;
+    let mut clicks = ClickTracker::new(ms(400), ClickCycle::Wrap);
+    let counts: Vec<u8> = (0..5)
+        .map(|i| clicks.click(t0 + ms(100 * i), window, Some((1, 1))))
+        .collect();
+    assert_eq!(counts, [1, 2, 3, 4, 1]);
+    // Too slow, or a different cell, starts over.
+    assert_eq!(clicks.click(t0 + ms(1000), window, Some((1, 1))), 1);
+    assert_eq!(clicks.click(t0 + ms(1100), window, Some((2, 1))), 1);
+}
+
+#[test]
+fn click_tracker_hold_policy() {
+    let window = WindowId::dummy();
+    let t0 = Instant::now();
+    let ms = Duration::from_millis;
+    let mut clicks = ClickTracker::new(ms(400), ClickCycle::Hold);
+    let counts: Vec<u8> = (0..6)
+        .map(|i| clicks.click(t0 + ms(50 * i), window, None))
+        .collect();
+    assert_eq!(counts, [1, 2, 3, 4, 4, 4]);
+    clicks.reset();
+    assert_eq!(clicks.click(t0 + ms(300), window, None), 1);
+}
+
+#[test]
+fn select_all_spans_scrollback_and_screen() {
+    let mut grid = Grid::new(10, 2);
+    for line in ["one", "two", "three"] {
+        for c in line.chars() {
+            grid.put_char(c);
+        }
+        grid.carriage_return();
+        grid.newline();
+    }
+    grid.put_char('4');
+    assert_eq!(grid.scrollback.len(), 2);
+
+    let sel = Selection::all(&grid);
+    assert_eq!(sel.ordered().0.row, StableRowIndex::from_absolute(&grid, 0));
+    let text = extract_text(&grid, &sel, Concealed::Include, TrailingWhitespace::Strip);
+    assert_eq!(text, "one\ntwo\nthree\n4");
+}
+
+#[test]
+fn trailing_whitespace_strip_or_keep() {
+    // "ab" plus two printed spaces, then "c" and "de".
+    let mut grid = Grid::new(6, 3);
+    for line in ["ab  ", "c"] {
+        for c in line.chars() {
+            grid.put_char(c);
+        }
+        grid.carriage_return();
+        grid.newline();
+    }
+    grid.put_char('d');
+    grid.put_char('e');
+
+    let all = Selection::all(&grid);
+    let text = |sel, trailing| extract_text(&grid, sel, Concealed::Include, trailing);
+    assert_eq!(text(&all, TrailingWhitespace::Strip), "ab\nc\nde");
+    assert_eq!(text(&all, TrailingWhitespace::Keep), "ab  \nc\nde");
+
+    // Block selections keep their rectangle.
+    let sel = block(sri(0), 0, sri(2), 2);
+    assert_eq!(text(&sel, TrailingWhitespace::Strip), "ab\nc\nde");
+    assert_eq!(text(&sel, TrailingWhitespace::Keep), "ab \nc  \nde ");
+}
+
+#[test]
+fn soft_wrapped_lines_join_when_keeping_whitespace() {
+    let mut grid = Grid::new(3, 2);
+    for c in "abcd ".chars() {
+        grid.put_char(c);
+    }
+
+    let all = Selection::all(&grid);
+    assert_eq!(extract_text(&grid, &all, Concealed::Include, TrailingWhitespace::Keep), "abcd ");
+}
@@ -1,75 +1,131 @@
 //! Text extraction from grid selection.
 
+use std::ops::RangeInclusive;
+
 use crate::cell::CellFlags;
 use crate::grid::Grid;
 use crate::grid::row::Row;
 
-use super::{Selection, SelectionMode};
+use super::{Selection, SelectionMode, SelectionPoint};
+
+/// How concealed (SGR 8) cells are copied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Concealed {
+    /// Copy the hidden characters as they are.
+    Include,
+    /// Copy each hidden cell as a space.
+    Blank,
+}
+
+/// What happens to whitespace at the end of each copied line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrailingWhitespace {
+    /// Drop it.
+    Strip,
+    /// Keep the spaces the program printed, and pad block selections out
+    /// to the full rectangle.
+    Keep,
+}
+
+impl TrailingWhitespace {
+    fn apply(self, line: &str) -> &str {
+        match self {
+            Self::Strip => line.trim_end(),
+            Self::Keep => line,
+        }
+    }
+}
 
 /// Extract selected text from the grid.
-pub fn extract_text(grid: &Grid, selection: &Selection) -> String {
+///
+/// Soft-wrapped rows (`WRAPLINE`) join without a newline; block selections
+/// put each row on its own line.
+pub fn extract_text(
+    grid: &Grid,
+    selection: &Selection,
+    concealed: Concealed,
+    trailing: TrailingWhitespace,
+) -> String {
     let (start, end) = selection.ordered();
-    let mut result = String::new();
 
     // Convert stable indices to absolute for iteration.
-    let Some(start_abs) = start.row.to_absolute(grid) else {
-        return result;
-    };
-    let Some(end_abs) = end.row.to_absolute(grid) else {
-        return result;
+    let (Some(start_abs), Some(end_abs)) = (start.row.to_absolute(grid), end.row.to_absolute(grid))
+    else {
+        return String::new();
     };
 
     if selection.mode == SelectionMode::Block {
-        let min_col = start.col.min(end.col);
-        let max_col = start.col.max(end.col);
-
-        for abs_row in start_abs..=end_abs {
-            if let Some(row) = grid.absolute_row(abs_row) {
-                let line = cells_to_text(row, min_col, max_col);
-                result.push_str(line.trim_end());
-            }
-            if abs_row < end_abs {
-                result.push('\n');
-            }
-        }
+        let lines: Vec<String> = (start_abs..=end_abs)
+            .map(|abs_row| {
+                grid.absolute_row(abs_row).map_or_else(String::new, |row| {
+                    let (min_col, max_col) = selection.block_columns_in(row.as_slice());
+                    let line = cells_to_text(row, min_col, max_col, concealed);
+                    trailing.apply(&line).to_owned()
+                })
+            })
+            .collect();
+        lines.join("\n")
     } else {
-        for abs_row in start_abs..=end_abs {
-            if let Some(row) = grid.absolute_row(abs_row) {
-                let row_start = if abs_row == start_abs {
-                    start.effective_start_col()
-                } else {
-                    0
-                };
-                let row_end = if abs_row == end_abs {
-                    end.effective_end_col()
-                } else {
-                    row.len().saturating_sub(1)
-                };
+        linear_text(grid, (start, end), start_abs..=end_abs, concealed, trailing)
+    }
+}
 
-                let line = cells_to_text(row, row_start, row_end);
+/// Text of a char, word, or line selection from `start` to `end`, which sit
+/// on absolute rows `rows`.
+fn linear_text(
+    grid: &Grid,
+    (start, end): (SelectionPoint, SelectionPoint),
+    rows: RangeInclusive<usize>,
+    concealed: Concealed,
+    trailing: TrailingWhitespace,
+) -> String {
+    let (start_abs, end_abs) = (*rows.start(), *rows.end());
+    let mut result = String::new();
+    for abs_row in rows {
+        let Some(row) = grid.absolute_row(abs_row) else {
+            continue;
+        };
+        let row_start = if abs_row == start_abs {
+            start.effective_start_col()
+        } else {
+            0
+        };
+        let row_end = if abs_row == end_abs {
+            end.effective_end_col()
+        } else {
+            row.len().saturating_sub(1)
+        };
 
-                // Soft-wrapped rows continue without a newline or trailing-space trim.
-                let is_wrapped =
-                    !row.is_empty() && row[row.len() - 1].flags.contains(CellFlags::WRAPLINE);
+        // Soft-wrapped rows continue without a newline or trailing-space trim.
+        let is_wrapped = !row.is_empty() && row[row.len() - 1].flags.contains(CellFlags::WRAPLINE);
 
-                if is_wrapped && abs_row < end_abs {
-                    result.push_str(&line);
-                } else {
-                    result.push_str(line.trim_end());
-                    if abs_row < end_abs {
-                        result.push('\n');
-                    }
-                }
+        // Kept whitespace ends where the program stopped writing, not at the
+        // right edge of the screen.
+        let written = row.occupied_len();
+        let line = if trailing == TrailingWhitespace::Strip || is_wrapped {
+            cells_to_text(row, row_start, row_end, concealed)
+        } else if row_start < written {
+            cells_to_text(row, row_start, row_end.min(written - 1), concealed)
+        } else {
+            String::new()
+        };
+
+        if is_wrapped && abs_row < end_abs {
+            result.push_str(&line);
+        } else {
+            result.push_str(trailing.apply(&line));
+            if abs_row < end_abs {
+                result.push('\n');
             }
         }
     }
-
     result
 }
 
 /// Collect visible cell characters from `col_start..=col_end` into a string.
-/// Skips wide-char spacers and replaces null chars with spaces.
-fn cells_to_text(row: &Row, col_start: usize, col_end: usize) -> String {
+/// Skips wide-char spacers and replaces null chars (and blanked concealed
+/// cells) with spaces.
+fn cells_to_text(row: &Row, col_start: usize, col_end: usize, concealed: Concealed) -> String {
     let mut text = String::new();
     let last = col_end.min(row.len().saturating_sub(1));
     for col in col_start..=last {
@@ -79,6 +135,10 @@ fn cells_to_text(row: &Row, col_start: usize, col_end: usize) -> String {
         {
             continue;
         }
+        if concealed == Concealed::Blank && cell.flags.contains(CellFlags::HIDDEN) {
+            text.push(' ');
+            continue;
+        }
         let c = if cell.c == '\0' { ' ' } else { cell.c };
         text.push(c);
         for &zw in cell.zerowidth() {
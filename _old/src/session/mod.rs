@@ -0,0 +1,152 @@
+//! Tabs saved on exit and reopened on the next launch.
+//!
+//! With `behavior.restore_session` on, each tab reopens in its last
+//! directory and, with `behavior.session_scrollback_lines` set, with the
+//! end of its scrollback replayed as history. Kept in `session.toml`.
+
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use flate2::Compression;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use serde::{Deserialize, Serialize};
+
+use crate::log;
+
+/// The tabs open when oriterm last exited.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// Index into `tabs` of the tab that was active.
+    pub active: usize,
+    #[serde(rename = "tab")]
+    pub tabs: Vec<SessionTab>,
+}
+
+/// One saved tab.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionTab {
+    /// Name the user gave the tab, if they renamed it.
+    pub name: Option<String>,
+    /// Working directory last reported by the shell (OSC 7).
+    pub cwd: Option<String>,
+    /// The tab was pinned.
+    pub pinned: bool,
+    /// The end of the scrollback as text with SGR escapes, deflated and
+    /// base64-encoded. Empty when none was saved.
+    pub history: String,
+}
+
+impl Session {
+    /// Read the session saved at `path`. `None` if there is none or it
+    /// can't be read.
+    pub fn load(path: &Path) -> Option<Self> {
+        let data = match std::fs::read_to_string(path) {
+            Ok(data) => data,
+            Err(e) => {
+                if e.kind() != io::ErrorKind::NotFound {
+                    log(&format!("session: failed to read {}: {e}", path.display()));
+                }
+                return None;
+            }
+        };
+        match toml::from_str(&data) {
+            Ok(session) => Some(session),
+            Err(e) => {
+                log(&format!("session: parse error in {}: {e}", path.display()));
+                None
+            }
+        }
+    }
+
+    /// Write the session to `path`, creating its directory if needed.
+    ///
+    /// The file holds scrollback, so it is readable only by this user, and
+    /// it is replaced whole: written to a temporary file, then renamed.
+    pub fn save(&self, path: &Path) {
+        let data = match toml::to_string(self) {
+            Ok(data) => data,
+            Err(e) => {
+                log(&format!("session: serialize error: {e}"));
+                return;
+            }
+        };
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let temp = path.with_extension("toml.tmp");
+        if let Err(e) = write_private(&temp, &data).and_then(|()| std::fs::rename(&temp, path)) {
+            let _ = std::fs::remove_file(&temp);
+            log(&format!("session: failed to write {}: {e}", path.display()));
+        }
+    }
+}
+
+impl SessionTab {
+    /// A saved tab in `cwd` whose scrollback ends with `history`.
+    pub fn new(cwd: Option<String>, history: &str) -> Self {
+        Self {
+            name: None,
+            cwd,
+            pinned: false,
+            history: pack(history),
+        }
+    }
+
+    /// The saved scrollback text, if any was saved and it decodes.
+    pub fn history_text(&self) -> Option<String> {
+        if self.history.is_empty() {
+            return None;
+        }
+        let text = unpack(&self.history);
+        if text.is_none() {
+            log("session: dropping unreadable scrollback");
+        }
+        text
+    }
+}
+
+/// Write `data` to a new file at `path` that only this user can read.
+fn write_private(path: &Path, data: &str) -> io::Result<()> {
+    // A leftover file would keep its old permissions.
+    let _ = std::fs::remove_file(path);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    file.write_all(data.as_bytes())?;
+    file.sync_all()
+}
+
+/// Deflate and base64-encode `text`. Empty text stays empty.
+fn pack(text: &str) -> String {
+    if text.is_empty() {
+        return String::new();
+    }
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    match encoder.write_all(text.as_bytes()).and_then(|()| encoder.finish()) {
+        Ok(bytes) => BASE64.encode(bytes),
+        Err(e) => {
+            log(&format!("session: failed to compress scrollback: {e}"));
+            String::new()
+        }
+    }
+}
+
+/// Reverse [`pack`].
+fn unpack(packed: &str) -> Option<String> {
+    let bytes = BASE64.decode(packed).ok()?;
+    let mut text = String::new();
+    DeflateDecoder::new(bytes.as_slice())
+        .read_to_string(&mut text)
+        .ok()?;
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests;
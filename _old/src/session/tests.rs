@@ -0,0 +1,67 @@
+use super::{Session, SessionTab};
+
+#[test]
+fn history_round_trips_through_the_session_file() {
+    let text = "\x1b[0;1;31merror\x1b[0m: build failed\nline two";
+    let session = Session {
+        active: 1,
+        tabs: vec![
+            SessionTab {
+                name: Some("build".to_owned()),
+                pinned: true,
+                ..SessionTab::new(Some("/home/user/project".to_owned()), text)
+            },
+            SessionTab::new(None, ""),
+        ],
+    };
+    let toml = toml::to_string(&session).expect("serialize");
+    let loaded: Session = toml::from_str(&toml).expect("parse");
+
+    assert_eq!(loaded.active, 1);
+    assert_eq!(loaded.tabs[0].name.as_deref(), Some("build"));
+    assert_eq!(loaded.tabs[1].name, None);
+    assert!(loaded.tabs[0].pinned);
+    assert!(!loaded.tabs[1].pinned);
+    assert_eq!(loaded.tabs[0].cwd.as_deref(), Some("/home/user/project"));
+    assert_eq!(loaded.tabs[0].history_text().as_deref(), Some(text));
+    assert_eq!(loaded.tabs[1].history_text(), None);
+}
+
+#[test]
+fn long_history_is_compressed() {
+    let text = "the same line of output\n".repeat(1000);
+    let tab = SessionTab::new(None, &text);
+    assert!(tab.history.len() < text.len() / 10);
+}
+
+#[test]
+fn corrupt_history_is_dropped() {
+    let tab = SessionTab {
+        history: "not base64!".to_owned(),
+        ..SessionTab::default()
+    };
+    assert_eq!(tab.history_text(), None);
+}
+
+#[test]
+fn save_replaces_the_file_and_keeps_it_private() {
+    let dir = std::env::temp_dir().join(format!("oriterm-session-{}", std::process::id()));
+    let path = dir.join("session.toml");
+    let session = |active| Session {
+        active,
+        tabs: vec![SessionTab::new(None, "output"); 3],
+    };
+
+    session(1).save(&path);
+    session(2).save(&path);
+    let loaded = Session::load(&path).expect("saved session loads");
+    assert_eq!(loaded.active, 2);
+    assert!(!path.with_extension("toml.tmp").exists());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).expect("metadata").permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
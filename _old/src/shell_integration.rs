@@ -45,64 +45,133 @@ pub fn detect_shell(program: &str) -> Option<Shell> {
     }
 }
 
-/// Write the embedded shell integration scripts to `base/shell-integration/`.
+/// How a new shell's working directory has to be expressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CwdTarget {
+    /// A Linux path inside WSL, passed as `wsl --cd`.
+    Wsl,
+    /// A drive path for a native Windows shell.
+    Windows,
+    /// The path as reported.
+    Unix,
+}
+
+impl CwdTarget {
+    /// The target for a detected `shell` on this platform.
+    pub fn for_shell(shell: Option<Shell>) -> Self {
+        if shell == Some(Shell::Wsl) {
+            Self::Wsl
+        } else if cfg!(windows) {
+            Self::Windows
+        } else {
+            Self::Unix
+        }
+    }
+}
+
+/// Translate an OSC 7 directory for a shell spawned at `target`.
 ///
-/// Returns the path to the `shell-integration/` directory on success.
-/// Uses a version stamp to skip writes when scripts are already up to date.
-pub fn ensure_scripts_on_disk(base: &Path) -> Result<PathBuf, std::io::Error> {
-    let dir = base.join("shell-integration");
-    let version = env!("CARGO_PKG_VERSION");
-    let stamp_path = dir.join(".version");
+/// A native Windows shell reports `/C:/Users/...`; WSL reports a Linux path
+/// such as `/home/...` or `/mnt/c/...`. Returns `None` when the directory
+/// has no equivalent at `target` (a path inside the WSL file system for a
+/// native Windows shell).
+pub fn translate_cwd(path: &str, target: CwdTarget) -> Option<String> {
+    match target {
+        CwdTarget::Unix => Some(path.to_owned()),
+        CwdTarget::Wsl => Some(match drive_path(path) {
+            Some((drive, rest)) => {
+                format!("/mnt/{}{}", drive.to_ascii_lowercase(), rest.replace('\\', "/"))
+            }
+            None => path.to_owned(),
+        }),
+        CwdTarget::Windows => {
+            let (drive, rest) = drive_path(path).or_else(|| wsl_mount_path(path))?;
+            let rest = if rest.is_empty() {
+                "\\".to_owned()
+            } else {
+                rest.replace('/', "\\")
+            };
+            Some(format!("{}:{rest}", drive.to_ascii_uppercase()))
+        }
+    }
+}
+
+/// Split `C:\dir`, `C:/dir`, or `/C:/dir` into the drive letter and the rest.
+fn drive_path(path: &str) -> Option<(char, &str)> {
+    let mut chars = path.strip_prefix('/').unwrap_or(path).chars();
+    let drive = chars.next().filter(char::is_ascii_alphabetic)?;
+    let rest = chars.as_str().strip_prefix(':')?;
+    (rest.is_empty() || rest.starts_with(['/', '\\'])).then_some((drive, rest))
+}
+
+/// Split `/mnt/c/dir` into the drive letter and `/dir`.
+fn wsl_mount_path(path: &str) -> Option<(char, &str)> {
+    let mut chars = path.strip_prefix("/mnt/")?.chars();
+    let drive = chars.next().filter(char::is_ascii_alphabetic)?;
+    let rest = chars.as_str();
+    (rest.is_empty() || rest.starts_with('/')).then_some((drive, rest))
+}
 
-    // Skip if scripts are already written for this version.
-    if let Ok(existing) = std::fs::read_to_string(&stamp_path) {
-        if existing.trim() == version {
-            return Ok(dir);
+/// Start `cmd` in `cwd`. WSL takes the directory as `--cd` (Linux paths
+/// don't work as a Windows CWD) and defaults to `~`; other shells keep the
+/// parent's directory when `cwd` is `None`.
+pub fn apply_cwd(cmd: &mut CommandBuilder, cwd: Option<&str>, target: CwdTarget) {
+    match (target, cwd) {
+        (CwdTarget::Wsl, _) => {
+            cmd.arg("--cd");
+            cmd.arg(cwd.unwrap_or("~"));
         }
+        (_, Some(dir)) => cmd.cwd(dir),
+        (_, None) => {}
     }
+}
 
-    // Bash
-    let bash_dir = dir.join("bash");
-    std::fs::create_dir_all(&bash_dir)?;
-    std::fs::write(
-        bash_dir.join("oriterm.bash"),
-        include_str!("../shell-integration/bash/oriterm.bash"),
-    )?;
-    std::fs::write(
-        bash_dir.join("bash-preexec.sh"),
-        include_str!("../shell-integration/bash/bash-preexec.sh"),
-    )?;
-
-    // Zsh
-    let zsh_dir = dir.join("zsh");
-    std::fs::create_dir_all(&zsh_dir)?;
-    std::fs::write(
-        zsh_dir.join(".zshenv"),
-        include_str!("../shell-integration/zsh/.zshenv"),
-    )?;
-    std::fs::write(
-        zsh_dir.join("oriterm-integration"),
+/// Embedded integration scripts, as paths relative to `shell-integration/`.
+const SCRIPTS: &[(&str, &str)] = &[
+    ("bash/oriterm.bash", include_str!("../shell-integration/bash/oriterm.bash")),
+    ("bash/bash-preexec.sh", include_str!("../shell-integration/bash/bash-preexec.sh")),
+    ("zsh/.zshenv", include_str!("../shell-integration/zsh/.zshenv")),
+    (
+        "zsh/oriterm-integration",
         include_str!("../shell-integration/zsh/oriterm-integration"),
-    )?;
-
-    // Fish
-    let fish_dir = dir.join("fish").join("vendor_conf.d");
-    std::fs::create_dir_all(&fish_dir)?;
-    std::fs::write(
-        fish_dir.join("oriterm-shell-integration.fish"),
+    ),
+    (
+        "fish/vendor_conf.d/oriterm-shell-integration.fish",
         include_str!("../shell-integration/fish/vendor_conf.d/oriterm-shell-integration.fish"),
-    )?;
+    ),
+    ("powershell/oriterm.ps1", include_str!("../shell-integration/powershell/oriterm.ps1")),
+];
+
+/// Whether `dir` holds every script, stamped with this build's version.
+pub fn scripts_up_to_date(dir: &Path) -> bool {
+    let stamped = std::fs::read_to_string(dir.join(".version"))
+        .is_ok_and(|s| s.trim() == env!("CARGO_PKG_VERSION"));
+    stamped && SCRIPTS.iter().all(|(rel, _)| dir.join(rel).is_file())
+}
+
+/// Write the embedded shell integration scripts to `base/shell-integration/`.
+///
+/// Returns the path to the `shell-integration/` directory on success.
+/// Uses a version stamp to skip writes when scripts are already up to date,
+/// but rewrites everything if any script has been removed since.
+pub fn ensure_scripts_on_disk(base: &Path) -> Result<PathBuf, std::io::Error> {
+    let dir = base.join("shell-integration");
+
+    // Skip if scripts are already written for this version and all present.
+    if scripts_up_to_date(&dir) {
+        return Ok(dir);
+    }
 
-    // PowerShell
-    let ps_dir = dir.join("powershell");
-    std::fs::create_dir_all(&ps_dir)?;
-    std::fs::write(
-        ps_dir.join("oriterm.ps1"),
-        include_str!("../shell-integration/powershell/oriterm.ps1"),
-    )?;
+    for (rel, contents) in SCRIPTS {
+        let path = dir.join(rel);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, contents)?;
+    }
 
     // Stamp the version so subsequent launches skip writes.
-    std::fs::write(&stamp_path, version)?;
+    std::fs::write(dir.join(".version"), env!("CARGO_PKG_VERSION"))?;
 
     Ok(dir)
 }
@@ -110,13 +179,11 @@ pub fn ensure_scripts_on_disk(base: &Path) -> Result<PathBuf, std::io::Error> {
 /// Configure the command environment for shell integration injection.
 ///
 /// Sets env vars on `cmd` so the target shell will source our scripts.
-/// `cwd` is the inherited working directory (if any) for WSL `--cd`.
 /// Returns an optional extra argument to append (e.g. `--posix` for bash).
 pub fn setup_injection(
     cmd: &mut CommandBuilder,
     shell: Shell,
     integration_dir: &Path,
-    cwd: Option<&str>,
 ) -> Option<&'static str> {
     // Common env vars for all shells.
     cmd.env("ORITERM", "1");
@@ -183,9 +250,6 @@ pub fn setup_injection(
             // We only propagate simple string env vars via WSLENV — no
             // path injection (ZDOTDIR etc.) across the WSL boundary.
             // Users source the integration script from their shell rc file.
-            cmd.arg("--cd");
-            cmd.arg(cwd.unwrap_or("~"));
-
             let mut wslenv = std::env::var("WSLENV").unwrap_or_default();
             if !wslenv.is_empty() {
                 wslenv.push(':');
@@ -232,6 +296,19 @@ mod tests {
         assert_eq!(detect_shell("wsl.exe"), Some(Shell::Wsl));
     }
 
+    #[test]
+    fn translate_cwd_between_windows_and_wsl() {
+        let wsl = |p| translate_cwd(p, CwdTarget::Wsl);
+        let win = |p| translate_cwd(p, CwdTarget::Windows);
+        assert_eq!(wsl("/C:/Users/me").as_deref(), Some("/mnt/c/Users/me"));
+        assert_eq!(wsl("/home/me").as_deref(), Some("/home/me"));
+        assert_eq!(win("/C:/Users/me").as_deref(), Some("C:\\Users\\me"));
+        assert_eq!(win("/mnt/d/src").as_deref(), Some("D:\\src"));
+        assert_eq!(win("/mnt/c").as_deref(), Some("C:\\"));
+        assert_eq!(win("/home/me"), None);
+        assert_eq!(translate_cwd("/home/me", CwdTarget::Unix).as_deref(), Some("/home/me"));
+    }
+
     #[test]
     fn detect_shell_unknown() {
         assert_eq!(detect_shell("cmd.exe"), None);
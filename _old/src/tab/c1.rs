@@ -0,0 +1,61 @@
+//! Translation of raw 8-bit C1 controls into their 7-bit escape forms.
+//!
+//! The VTE parser decodes output as UTF-8, so a lone `0x9B` (CSI) or `0x9D`
+//! (OSC) never starts a sequence. Programs that emit S8C1T-style output
+//! (EBCDIC bridges, some old UNIX tools) rely on those bytes, so with
+//! `terminal.c1_controls` on they are rewritten to `ESC` + `byte - 0x40`
+//! before parsing. Bytes that continue a UTF-8 sequence are left alone, even
+//! across read boundaries.
+
+use std::borrow::Cow;
+
+/// Stateful C1 → 7-bit rewriter for the PTY output stream.
+#[derive(Debug, Default)]
+pub(super) struct C1Translator {
+    /// UTF-8 continuation bytes still expected from the previous chunk.
+    pending: u8,
+}
+
+impl C1Translator {
+    /// Rewrite raw C1 controls in `data`.
+    ///
+    /// Borrows the input unchanged in the common case where no raw C1 byte
+    /// is present.
+    pub(super) fn translate<'a>(&mut self, data: &'a [u8]) -> Cow<'a, [u8]> {
+        let mut out: Option<Vec<u8>> = None;
+        for (i, &byte) in data.iter().enumerate() {
+            if self.pending > 0 && (0x80..=0xBF).contains(&byte) {
+                self.pending -= 1;
+                if let Some(out) = &mut out {
+                    out.push(byte);
+                }
+                continue;
+            }
+            self.pending = utf8_continuations(byte);
+
+            match (&mut out, byte) {
+                (_, 0x80..=0x9F) => {
+                    let out = out.get_or_insert_with(|| {
+                        let mut v = Vec::with_capacity(data.len() + 16);
+                        v.extend_from_slice(&data[..i]);
+                        v
+                    });
+                    out.extend_from_slice(&[0x1B, byte - 0x40]);
+                }
+                (Some(out), _) => out.push(byte),
+                (None, _) => {}
+            }
+        }
+        out.map_or(Cow::Borrowed(data), Cow::Owned)
+    }
+}
+
+/// Number of continuation bytes that follow a UTF-8 lead byte.
+fn utf8_continuations(lead: u8) -> u8 {
+    match lead {
+        0xC2..=0xDF => 1,
+        0xE0..=0xEF => 2,
+        0xF0..=0xF4 => 3,
+        _ => 0,
+    }
+}
@@ -0,0 +1,73 @@
+//! Clearing the scrollback, and dropping the selection and search matches
+//! that pointed into rows which are gone.
+
+use vte::ansi::ClearMode;
+
+use super::{PromptState, Tab};
+use crate::grid::StableRowIndex;
+use crate::grid::row::RowFlags;
+
+impl Tab {
+    /// Clear the scrollback and the screen, moving the prompt being edited
+    /// to the top (`Action::ClearScreenAndScrollback`).
+    ///
+    /// At a shell-integration prompt the whole prompt from its `OSC 133;A`
+    /// row is kept; otherwise just the cursor row. On the alt screen only
+    /// the scrollback is cleared, since the program owns the screen.
+    pub fn clear_screen_and_scrollback(&mut self) {
+        let mut term = self.terminal.lock();
+        let at_prompt = matches!(
+            term.prompt_state,
+            PromptState::PromptStart | PromptState::CommandStart
+        );
+        let alt = term.active_is_alt;
+        let grid = &mut term.primary_grid;
+        if alt {
+            grid.erase_display(ClearMode::Saved);
+        } else {
+            let cursor = grid.cursor.row;
+            let prompt = (0..=cursor)
+                .rev()
+                .find(|&r| grid.row(r).flags.contains(RowFlags::PROMPT_START));
+            let keep_from = prompt.filter(|_| at_prompt).unwrap_or(cursor);
+            grid.clear_history_keeping(keep_from);
+        }
+        drop(term);
+        self.forget_cleared_rows();
+        self.set_grid_dirty(true);
+    }
+
+    /// Clear the primary screen's scrollback and scroll to the bottom
+    /// (`Action::ClearScrollback`).
+    pub fn clear_scrollback(&mut self) {
+        self.terminal
+            .lock()
+            .primary_grid
+            .erase_display(ClearMode::Saved);
+        self.forget_cleared_rows();
+        self.set_grid_dirty(true);
+    }
+
+    /// Drop the selection and search matches that start in rows no longer
+    /// in the grid, after `ED 3` or the scrollback limit removed them.
+    pub fn forget_cleared_rows(&mut self) {
+        let term = self.terminal.lock();
+        let grid = term.active_grid();
+        let first = StableRowIndex::from_absolute(grid, 0);
+        let selection_gone = self
+            .selection
+            .as_ref()
+            .is_some_and(|sel| sel.ordered().0.row < first);
+        let matches_gone = self
+            .search
+            .as_mut()
+            .is_some_and(|search| search.drop_evicted(grid));
+        drop(term);
+        if selection_gone {
+            self.selection = None;
+        }
+        if selection_gone || matches_gone {
+            self.set_grid_dirty(true);
+        }
+    }
+}
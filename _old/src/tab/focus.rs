@@ -0,0 +1,54 @@
+//! Focus reporting (DECSET 1004).
+//!
+//! A tab has focus when it is the active tab of the focused window. Each
+//! tab remembers the focus it last had, so a report goes out only when that
+//! changes: a tab switch sends `CSI O` to the old tab and `CSI I` to the new
+//! one, and losing and regaining window focus reaches only the active tab.
+
+use crate::term_mode::TermMode;
+
+use super::Tab;
+
+/// Focus in (`CSI I`).
+const FOCUS_IN: &[u8] = b"\x1b[I";
+/// Focus out (`CSI O`).
+const FOCUS_OUT: &[u8] = b"\x1b[O";
+
+/// The focus a tab last had, and the reports its changes produce.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FocusState {
+    focused: bool,
+}
+
+impl FocusState {
+    /// Whether the tab currently has focus.
+    pub fn is_focused(self) -> bool {
+        self.focused
+    }
+
+    /// Record that the tab has (or lacks) focus. Returns the report to send
+    /// when focus changed and the application asked for reports in `mode`.
+    pub fn update(&mut self, focused: bool, mode: TermMode) -> Option<&'static [u8]> {
+        if std::mem::replace(&mut self.focused, focused) == focused {
+            return None;
+        }
+        if !mode.contains(TermMode::FOCUS_IN_OUT) {
+            return None;
+        }
+        Some(if focused { FOCUS_IN } else { FOCUS_OUT })
+    }
+}
+
+impl Tab {
+    /// Give or take this tab's focus, reporting the change to the
+    /// application if it enabled focus events.
+    pub fn set_focused(&mut self, focused: bool) {
+        if self.focus.is_focused() != focused {
+            // The cursor switches between focused and unfocused styles.
+            self.set_grid_dirty(true);
+        }
+        if let Some(report) = self.focus.update(focused, self.mode()) {
+            self.send_pty(report);
+        }
+    }
+}
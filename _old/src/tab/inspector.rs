@@ -0,0 +1,296 @@
+//! Escape sequence inspector: a live log of the sequences a tab receives.
+//!
+//! The raw parser runs through a [`Tee`] that forwards every callback to
+//! the [`RawInterceptor`](super::interceptor) and counts the sequences the
+//! terminal ignores. While the inspector is open it also records a
+//! [`SeqRecord`] for each control, escape, CSI, OSC, and DCS sequence.
+//! Records keep the most recent [`LOG_CAPACITY`] entries.
+
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+
+use vte::{Params, Perform};
+
+use super::Tab;
+use super::seq_names::{control_name, csi_name, esc_name, osc_name};
+use super::unknown_seq::UnknownSequences;
+
+/// Records kept per tab; older ones are dropped.
+const LOG_CAPACITY: usize = 1000;
+
+/// Longest sequence text recorded, in bytes; the rest is elided.
+const MAX_TEXT: usize = 160;
+
+/// The kind of sequence a record holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeqKind {
+    Control,
+    Esc,
+    Csi,
+    Osc,
+    Dcs,
+}
+
+/// One sequence as the parser dispatched it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeqRecord {
+    pub kind: SeqKind,
+    /// Mnemonic or description, or `None` if the terminal ignores it.
+    pub name: Option<&'static str>,
+    /// The sequence in caret/`ESC` notation.
+    pub text: String,
+}
+
+impl SeqRecord {
+    /// Whether the terminal acts on this sequence.
+    pub fn is_handled(&self) -> bool {
+        self.name.is_some()
+    }
+
+    /// Whether every whitespace-separated word of `filter` appears in the
+    /// record's name or text, ignoring case. `ignored` matches the
+    /// sequences the terminal drops.
+    pub fn matches(&self, filter: &str) -> bool {
+        let line = self.to_string().to_lowercase();
+        filter
+            .split_whitespace()
+            .all(|word| line.contains(&word.to_lowercase()))
+    }
+}
+
+impl fmt::Display for SeqRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:<10} {}", self.name.unwrap_or("ignored"), self.text)
+    }
+}
+
+/// The recorded sequences of one tab, oldest first.
+#[derive(Debug, Default)]
+pub struct SequenceLog {
+    records: VecDeque<SeqRecord>,
+    /// Sequences recorded since the inspector opened, including dropped ones.
+    total: u64,
+    /// A DCS is open; `put` bytes extend the newest record.
+    in_dcs: bool,
+}
+
+impl SequenceLog {
+    /// The records matching `filter` (see [`SeqRecord::matches`]), oldest
+    /// first.
+    pub fn filtered<'a>(
+        &'a self,
+        filter: &'a str,
+    ) -> impl DoubleEndedIterator<Item = &'a SeqRecord> {
+        self.records.iter().filter(move |r| r.matches(filter))
+    }
+
+    /// Sequences recorded since the inspector opened.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    fn push(&mut self, kind: SeqKind, name: Option<&'static str>, text: String) {
+        if self.records.len() == LOG_CAPACITY {
+            self.records.pop_front();
+        }
+        self.records.push_back(SeqRecord { kind, name, text });
+        self.total += 1;
+    }
+}
+
+/// A `Perform` that counts the sequences the terminal ignores and, while the
+/// inspector is open, records every sequence into `log` before handing it
+/// to `inner`.
+pub(super) struct Tee<'a, P: Perform> {
+    pub inner: &'a mut P,
+    pub log: Option<&'a mut SequenceLog>,
+    pub unknown: &'a mut UnknownSequences,
+}
+
+impl<P: Perform> Tee<'_, P> {
+    /// Record a sequence, building its text only if the inspector is open.
+    fn record(&mut self, kind: SeqKind, name: Option<&'static str>, text: impl FnOnce() -> String) {
+        if let Some(log) = self.log.as_deref_mut() {
+            log.push(kind, name, text());
+        }
+    }
+}
+
+impl<P: Perform> Perform for Tee<'_, P> {
+    fn print(&mut self, c: char) {
+        self.inner.print(c);
+    }
+
+    fn execute(&mut self, byte: u8) {
+        // Line breaks, backspace, and tab are part of ordinary text and
+        // would push everything else out of the log.
+        if !matches!(byte, b'\r' | b'\n' | 0x08 | b'\t') {
+            self.record(SeqKind::Control, control_name(byte), || {
+                let mut text = String::new();
+                push_byte(&mut text, byte);
+                text
+            });
+        }
+        self.inner.execute(byte);
+    }
+
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, action: char) {
+        self.unknown.count_dcs(intermediates, action);
+        self.record(SeqKind::Dcs, None, || {
+            format!("ESCP{}", csi_body(params, intermediates, action))
+        });
+        if let Some(log) = self.log.as_deref_mut() {
+            log.in_dcs = true;
+        }
+        self.inner.hook(params, intermediates, ignore, action);
+    }
+
+    fn put(&mut self, byte: u8) {
+        if let Some(log) = self.log.as_deref_mut().filter(|log| log.in_dcs) {
+            if let Some(record) = log.records.back_mut().filter(|r| r.text.len() < MAX_TEXT) {
+                push_byte(&mut record.text, byte);
+            }
+        }
+        self.inner.put(byte);
+    }
+
+    fn unhook(&mut self) {
+        if let Some(log) = self.log.as_deref_mut() {
+            log.in_dcs = false;
+        }
+        self.inner.unhook();
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        let command = params.first().copied().unwrap_or_default();
+        let name = osc_name(command);
+        if name.is_none() {
+            self.unknown.count_osc(command);
+        }
+        self.record(SeqKind::Osc, name, || osc_text(params));
+        self.inner.osc_dispatch(params, bell_terminated);
+    }
+
+    fn csi_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, action: char) {
+        let name = csi_name(intermediates, action);
+        if name.is_none() {
+            self.unknown.count_csi(intermediates, action);
+        }
+        self.record(SeqKind::Csi, name, || {
+            format!("ESC[{}", csi_body(params, intermediates, action))
+        });
+        self.inner
+            .csi_dispatch(params, intermediates, ignore, action);
+    }
+
+    fn esc_dispatch(&mut self, intermediates: &[u8], ignore: bool, byte: u8) {
+        self.record(SeqKind::Esc, esc_name(intermediates, byte), || {
+            let mut text = String::from("ESC");
+            for &b in intermediates.iter().chain([&byte]) {
+                push_byte(&mut text, b);
+            }
+            text
+        });
+        self.inner.esc_dispatch(intermediates, ignore, byte);
+    }
+
+    fn terminated(&self) -> bool {
+        self.inner.terminated()
+    }
+}
+
+/// The OSC parameters joined with `;`, controls in caret notation.
+fn osc_text(params: &[&[u8]]) -> String {
+    let mut text = String::from("ESC]");
+    for (i, param) in params.iter().enumerate() {
+        if i > 0 {
+            text.push(';');
+        }
+        for c in String::from_utf8_lossy(param).chars() {
+            match u8::try_from(c) {
+                Ok(byte) if byte.is_ascii() => push_byte(&mut text, byte),
+                _ => text.push(c),
+            }
+        }
+    }
+    elide(&mut text);
+    text
+}
+
+/// Private markers, parameters (`:` between subparameters), remaining
+/// intermediates, and the final byte, in wire order.
+fn csi_body(params: &Params, intermediates: &[u8], action: char) -> String {
+    let (markers, rest): (Vec<u8>, Vec<u8>) = intermediates
+        .iter()
+        .partition(|b| (b'<'..=b'?').contains(b));
+    let mut body: String = markers.iter().map(|&b| char::from(b)).collect();
+    for (i, param) in params.iter().enumerate() {
+        if i > 0 {
+            body.push(';');
+        }
+        for (j, sub) in param.iter().enumerate() {
+            if j > 0 {
+                body.push(':');
+            }
+            let _ = write!(body, "{sub}");
+        }
+    }
+    body.extend(rest.iter().map(|&b| char::from(b)));
+    body.push(action);
+    body
+}
+
+/// Cut `text` to [`MAX_TEXT`] bytes, marking the cut with an ellipsis.
+fn elide(text: &mut String) {
+    if text.len() > MAX_TEXT {
+        let mut end = MAX_TEXT;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        text.push('…');
+    }
+}
+
+/// Append `byte` to `text`, writing controls in caret notation (`^G`) and
+/// bytes outside ASCII as `\xHH`.
+fn push_byte(text: &mut String, byte: u8) {
+    match byte {
+        0x1B => text.push_str("ESC"),
+        0x00..=0x1F => {
+            text.push('^');
+            text.push(char::from(byte + 0x40));
+        }
+        0x7F => text.push_str("^?"),
+        0x80..=0xFF => {
+            let _ = write!(text, "\\x{byte:02X}");
+        }
+        _ => text.push(char::from(byte)),
+    }
+}
+
+impl Tab {
+    /// Start recording the escape sequences this tab receives.
+    pub fn start_inspecting(&self) {
+        let mut term = self.terminal.lock();
+        if term.inspector.is_none() {
+            term.inspector = Some(SequenceLog::default());
+        }
+    }
+
+    /// Stop recording and drop the recorded sequences.
+    pub fn stop_inspecting(&self) {
+        self.terminal.lock().inspector = None;
+        self.set_grid_dirty(true);
+    }
+
+    /// The ignored sequences this tab has received.
+    pub fn unknown_sequences(&self) -> UnknownSequences {
+        self.terminal.lock().unknown_sequences.clone()
+    }
+
+    /// Whether this tab's sequences are being recorded.
+    pub fn is_inspecting(&self) -> bool {
+        self.terminal.lock().inspector.is_some()
+    }
+}
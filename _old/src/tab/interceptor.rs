@@ -1,45 +1,94 @@
 //! Raw VTE interceptor for sequences the high-level processor drops.
 
-use vte::Perform;
+use vte::ansi::Rgb;
+use vte::{Params, Perform};
 
-use super::types::{Notification, PromptState};
+use crate::grid::row::RowFlags;
+use crate::term_handler::TextSizing;
+
+use super::notify::Osc99Assembler;
+use super::types::{Notification, NotificationEvent, PromptState, TaskProgress};
+
+/// An effect of a sequence `vte::ansi` drops that must land in stream
+/// order: after the processor has handled the bytes before it and before
+/// it handles the bytes after.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Deferred {
+    /// DECSTR soft terminal reset (`CSI ! p`).
+    SoftReset,
+    /// SGR 53 (overline on) or SGR 55 (overline off).
+    Overline(bool),
+    /// DECXCPR extended cursor position report (`CSI ? 6 n`).
+    ExtendedCursorReport,
+    /// Cursor position in pixels (`CSI ? 1016 n`).
+    CursorPixelReport,
+    /// Scaled text (`OSC 66 ; metadata ; text ST`).
+    TextSizing(TextSizing),
+    /// Shell integration mark for the cursor row (`OSC 133 ; A|B|C|D`),
+    /// with the exit status a `D` carries.
+    RowMark(RowFlags, Option<i32>),
+}
 
 /// Raw VTE `Perform` implementation that intercepts sequences the high-level
-/// `vte::ansi::Processor` drops: OSC 7 (CWD), OSC 133 (prompt markers),
-/// OSC 9/99/777 (notifications), and XTVERSION (CSI > q).
+/// `vte::ansi::Processor` drops: OSC 6 (tab color), OSC 7 (CWD), OSC 133
+/// (prompt markers), OSC 9/99/777 (notifications), OSC 9;4 (progress),
+/// XTVERSION (CSI > q), and ENQ (answerback).
+///
+/// Sequences with a [`Deferred`] effect are stored in `deferred` and stop
+/// the parser, so `TerminalState` can feed the processor up to that point
+/// and apply the effect before parsing on.
 pub(super) struct RawInterceptor<'a> {
     pub pty_responses: &'a mut Vec<u8>,
     pub cwd: &'a mut Option<String>,
+    pub host: &'a mut Option<String>,
     pub prompt_state: &'a mut PromptState,
-    pub pending_notifications: &'a mut Vec<Notification>,
-    pub prompt_mark_pending: &'a mut bool,
+    pub pending_notifications: &'a mut Vec<NotificationEvent>,
+    pub progress: &'a mut Option<TaskProgress>,
+    pub osc99: &'a mut Osc99Assembler,
     pub has_explicit_title: &'a mut bool,
     pub suppress_title: &'a mut bool,
     pub title_dirty: &'a mut bool,
+    pub tab_color: &'a mut Option<Rgb>,
+    pub answerback: &'a str,
+    pub deferred: Option<Deferred>,
 }
 
 impl Perform for RawInterceptor<'_> {
+    fn execute(&mut self, byte: u8) {
+        // ENQ — reply with the configured answerback string (nothing if empty).
+        if byte == 0x05 {
+            self.pty_responses
+                .extend_from_slice(self.answerback.as_bytes());
+        }
+    }
+
     fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
         if params.is_empty() || params[0].is_empty() {
             return;
         }
         match params[0] {
+            // OSC 6 — iTerm2 tab color, one channel at a time:
+            // OSC 6 ; 1 ; bg ; red|green|blue ; brightness ; N ST
+            // OSC 6 ; 1 ; bg ; * ; default ST
+            b"6" if matches!(params.get(1..3), Some([b"1", b"bg"]))
+                && apply_tab_color_osc(&params[3..], self.tab_color) =>
+            {
+                // Redraws the tab bar like a title change.
+                *self.title_dirty = true;
+            }
             // OSC 7 — Current working directory.
             // Format: OSC 7 ; file://hostname/path ST
             b"7" => {
                 if params.len() >= 2 {
                     let uri = std::str::from_utf8(params[1]).unwrap_or_default();
-                    // Strip file:// prefix and optional hostname to get the path.
-                    let path = uri.strip_prefix("file://").map_or(uri, |rest| {
-                        // Skip hostname (everything before the next /)
-                        if let Some(slash) = rest.find('/') {
-                            rest.split_at(slash).1
-                        } else {
-                            rest
-                        }
+                    // Split file://hostname/path into hostname and path.
+                    let (host, path) = uri.strip_prefix("file://").map_or(("", uri), |rest| {
+                        rest.find('/')
+                            .map_or((rest, ""), |slash| rest.split_at(slash))
                     });
                     if !path.is_empty() {
                         *self.cwd = Some(path.to_owned());
+                        *self.host = (!host.is_empty()).then(|| host.to_owned());
                         // CWD-based title should override ConPTY's auto-generated
                         // process title (e.g. C:\WINDOWS\system32\wsl.exe).
                         *self.has_explicit_title = false;
@@ -50,33 +99,36 @@ impl Perform for RawInterceptor<'_> {
             }
             // OSC 133 — Semantic prompt markers.
             // Format: OSC 133 ; <type>[;extras] ST
-            b"133" => {
-                if params.len() >= 2 && !params[1].is_empty() {
-                    match params[1][0] {
-                        b'A' => {
-                            *self.prompt_state = PromptState::PromptStart;
-                            *self.prompt_mark_pending = true;
-                            *self.suppress_title = false;
-                        }
-                        b'B' => *self.prompt_state = PromptState::CommandStart,
-                        b'C' => *self.prompt_state = PromptState::OutputStart,
-                        b'D' => *self.prompt_state = PromptState::None,
-                        _ => {}
-                    }
+            b"133" if params.len() >= 2 && !params[1].is_empty() => self.prompt_mark(params),
+            // OSC 66 — Kitty text sizing: ESC]66;metadata;text ST. The text
+            // may itself contain `;`, which vte splits on.
+            b"66" if params.len() >= 3 => {
+                let text = params[2..].join(&b';');
+                self.deferred = TextSizing::parse(params[1], &text).map(Deferred::TextSizing);
+            }
+            // OSC 9;4 — ConEmu progress: ESC]9;4;state;percent ST
+            b"9" if params.get(1) == Some(&&b"4"[..]) => {
+                if let Some(progress) = TaskProgress::from_osc(&params[2..]) {
+                    *self.progress = Some(progress);
                 }
             }
             // OSC 9 — iTerm2 simple notification: ESC]9;body ST
-            // OSC 99 — Kitty notification protocol: ESC]99;body ST
-            b"9" | b"99" => {
+            b"9" => {
                 let body = if params.len() >= 2 {
                     String::from_utf8_lossy(params[1]).into_owned()
                 } else {
                     String::new()
                 };
-                self.pending_notifications.push(Notification {
-                    title: String::new(),
-                    body,
-                });
+                self.pending_notifications
+                    .push(NotificationEvent::Show(Notification {
+                        body,
+                        ..Notification::default()
+                    }));
+            }
+            // OSC 99 — Kitty notification protocol: ESC]99;metadata;payload ST
+            b"99" => {
+                self.osc99
+                    .dispatch(&params[1..], self.pending_notifications, self.pty_responses);
             }
             // OSC 777 — rxvt-unicode notification: ESC]777;notify;title;body ST
             b"777" => {
@@ -92,7 +144,11 @@ impl Perform for RawInterceptor<'_> {
                             .map(|p| String::from_utf8_lossy(p).into_owned())
                             .unwrap_or_default();
                         self.pending_notifications
-                            .push(Notification { title, body });
+                            .push(NotificationEvent::Show(Notification {
+                                title,
+                                body,
+                                ..Notification::default()
+                            }));
                     }
                 }
             }
@@ -100,20 +156,119 @@ impl Perform for RawInterceptor<'_> {
         }
     }
 
-    fn csi_dispatch(
-        &mut self,
-        _params: &vte::Params,
-        intermediates: &[u8],
-        _ignore: bool,
-        action: char,
-    ) {
-        // XTVERSION: CSI > q — report terminal name and version.
-        if action == 'q' && intermediates == [b'>'] {
-            let version = env!("CARGO_PKG_VERSION");
-            let build = include_str!("../../BUILD_NUMBER").trim();
-            // Response: DCS > | terminal-name(version) ST
-            let response = format!("\x1bP>|oriterm({version} build {build})\x1b\\");
-            self.pty_responses.extend_from_slice(response.as_bytes());
+    fn csi_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, action: char) {
+        if ignore {
+            return;
         }
+        match (action, intermediates) {
+            // XTVERSION: CSI > q — report terminal name and version.
+            ('q', [b'>']) => {
+                let version = env!("CARGO_PKG_VERSION");
+                let build = include_str!("../../BUILD_NUMBER").trim();
+                // Response: DCS > | terminal-name(version) ST
+                let response = format!("\x1bP>|oriterm({version} build {build})\x1b\\");
+                self.pty_responses.extend_from_slice(response.as_bytes());
+            }
+            ('p', [b'!']) => self.deferred = Some(Deferred::SoftReset),
+            ('m', []) => self.deferred = sgr_overline(params).map(Deferred::Overline),
+            ('n', [b'?']) => {
+                self.deferred = match params.iter().next() {
+                    Some([6]) => Some(Deferred::ExtendedCursorReport),
+                    Some([1016]) => Some(Deferred::CursorPixelReport),
+                    _ => None,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    fn terminated(&self) -> bool {
+        self.deferred.is_some()
     }
 }
+
+impl RawInterceptor<'_> {
+    /// OSC 133 `A`, `B`, `C`, or `D[;status]`: track the prompt state and
+    /// defer marking the cursor row.
+    fn prompt_mark(&mut self, params: &[&[u8]]) {
+        let flag = match params[1][0] {
+            b'A' => {
+                *self.prompt_state = PromptState::PromptStart;
+                *self.suppress_title = false;
+                RowFlags::PROMPT_START
+            }
+            b'B' => {
+                *self.prompt_state = PromptState::CommandStart;
+                RowFlags::COMMAND_START
+            }
+            b'C' => {
+                *self.prompt_state = PromptState::OutputStart;
+                RowFlags::OUTPUT_START
+            }
+            b'D' => {
+                *self.prompt_state = PromptState::None;
+                RowFlags::COMMAND_END
+            }
+            _ => return,
+        };
+        let status = (flag == RowFlags::COMMAND_END)
+            .then(|| params.get(2))
+            .flatten()
+            .and_then(|p| std::str::from_utf8(p).ok())
+            .and_then(|p| p.parse().ok());
+        self.deferred = Some(Deferred::RowMark(flag, status));
+    }
+}
+
+/// The overline state an SGR parameter list leaves behind, if it sets one.
+///
+/// Semicolon-separated color arguments (`38;5;53`, `48;2;r;g;b`) are
+/// skipped so their values are not mistaken for attributes. SGR 0 clears
+/// overline through the normal reset path, so it only cancels an earlier
+/// 53 in the same sequence.
+fn sgr_overline(params: &Params) -> Option<bool> {
+    let mut state = None;
+    let mut iter = params.iter();
+    while let Some(param) = iter.next() {
+        match param {
+            [53] => state = Some(true),
+            [55] => state = Some(false),
+            [0] => state = None,
+            [38 | 48 | 58] => {
+                let skip = match iter.next() {
+                    Some([5]) => 1,
+                    Some([2]) => 3,
+                    _ => 0,
+                };
+                iter.by_ref().take(skip).for_each(drop);
+            }
+            _ => {}
+        }
+    }
+    state
+}
+
+/// Apply the `channel ; brightness ; N` or `channel ; default` parameters
+/// of OSC 6 to `color`. Channels not yet set start at 0. Returns `false`,
+/// leaving `color` alone, if the parameters are malformed.
+fn apply_tab_color_osc(params: &[&[u8]], color: &mut Option<Rgb>) -> bool {
+    let (channel, value) = match params {
+        [_, b"default"] => {
+            *color = None;
+            return true;
+        }
+        [channel, b"brightness", value] => (*channel, value),
+        _ => return false,
+    };
+    let Some(value) = std::str::from_utf8(value).ok().and_then(|v| v.parse::<u8>().ok()) else {
+        return false;
+    };
+    let set: fn(&mut Rgb, u8) = match channel {
+        b"red" => |rgb, v| rgb.r = v,
+        b"green" => |rgb, v| rgb.g = v,
+        b"blue" => |rgb, v| rgb.b = v,
+        _ => return false,
+    };
+    set(color.get_or_insert(Rgb { r: 0, g: 0, b: 0 }), value);
+    true
+}
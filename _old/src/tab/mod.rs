@@ -1,33 +1,64 @@
 //! Tab state: grid, PTY, VTE parser, and shell integration.
 
+mod c1;
+mod clear;
+mod focus;
+mod inspector;
 mod interceptor;
+mod notify;
+mod paste;
+mod process;
+mod prompt_nav;
+mod pty;
+mod resize;
+mod search;
+mod seq_names;
+mod settings;
+mod shutdown;
+mod stats;
 pub mod terminal_state;
+#[cfg(test)]
+mod tests;
 mod types;
+mod unknown_seq;
 
+pub use inspector::{SeqKind, SeqRecord, SequenceLog};
+pub use paste::Bracketing;
+pub use shutdown::{SHUTDOWN_TIMEOUT, shutdown_tabs};
+pub use stats::PtyStatsSnapshot;
+pub use unknown_seq::UnknownSequences;
 pub use terminal_state::TerminalState;
 pub use types::{
-    CharsetState, Notification, PromptState, PtyWriter, SpawnConfig, TabId, TermEvent,
+    CharsetState, EventSender, EventSink, Notification, NotificationEvent, NotificationResponse,
+    PromptState, PtyWriter, SpawnConfig, TabId, TaskProgress, TermEvent, Unseen,
 };
 
-use std::io::{Read, Write};
+use std::io::Write;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
-use std::thread;
+use std::thread::JoinHandle;
+use std::time::Instant;
 
 use parking_lot::{MappedMutexGuard, MutexGuard};
 use vte::ansi::CursorShape;
-use winit::event_loop::EventLoopProxy;
 
-use crate::config::ColorConfig;
+use crate::clipboard::ClipboardAccess;
 use crate::sync::FairMutex;
-use crate::grid::Grid;
+use crate::grid::{ClipEdge, Grid};
 use crate::log;
-use crate::palette::ColorScheme;
 use crate::search::SearchState;
 use crate::selection::{Selection, SelectionPoint};
-use crate::shell_integration;
+use crate::shell_integration::{self, CwdTarget};
 use crate::term_mode::TermMode;
+use pty::{ReaderShared, derive_initial_title, spawn_reader_thread};
+#[cfg(target_os = "windows")]
+use pty::spawn_child_waiter;
+use focus::FocusState;
+use paste::PasteSlot;
+use resize::PendingPtyResize;
+use stats::PtyStats;
 
+#[expect(clippy::struct_excessive_bools, reason = "Independent per-tab indicator flags")]
 pub struct Tab {
     pub id: TabId,
     /// Thread-shared terminal state behind a fair mutex.
@@ -43,8 +74,39 @@ pub struct Tab {
     // UI state (main thread only — never accessed by PTY thread)
     pub selection: Option<Selection>,
     pub search: Option<SearchState>,
-    /// True when an inactive tab received a bell — shows badge in tab bar.
-    pub has_bell_badge: bool,
+    /// Name the user gave the tab (`Action::RenameTab`), shown instead of
+    /// the OSC or directory title.
+    pub custom_title: Option<String>,
+    /// Bell and notifications not yet seen. The app keeps a running total
+    /// of these for its badge, so change them through that.
+    pub unseen: Unseen,
+    /// True when an inactive tab wrote output since it was last viewed —
+    /// shows an activity dot in the tab bar.
+    pub has_activity: bool,
+    /// A command other than the shell is running (see `refresh_foreground`).
+    busy: bool,
+    /// Name of the foreground process other than the shell, if known.
+    foreground: Option<String>,
+    /// When the foreground check scheduled by new output is due.
+    foreground_due: Option<Instant>,
+    /// Earliest time the next scheduled foreground check may run.
+    foreground_next: Instant,
+    /// Desktop toasts for this tab's notifications are suppressed.
+    pub notifications_muted: bool,
+    /// Left out when its window broadcasts input.
+    pub broadcast_excluded: bool,
+    /// Drawn compact at the left of the tab bar, and asks before closing.
+    pub pinned: bool,
+    /// Latest OSC 9;4 task progress.
+    pub progress: TaskProgress,
+    /// OSC 52 decision made for this tab, overriding `terminal.osc52`.
+    pub clipboard_access: Option<ClipboardAccess>,
+    /// Index of the `[[host_rule]]` restyling this tab.
+    pub host_rule: Option<usize>,
+    /// The shell runs inside WSL.
+    is_wsl: bool,
+    /// `TerminalState::scrolled_lines` when the user last viewed this tab.
+    seen_lines: u64,
     /// Grid content modified since last render. Set by PTY thread (via
     /// `TermEvent::Wakeup`), cleared by renderer after each frame.
     grid_dirty: AtomicBool,
@@ -55,6 +117,27 @@ pub struct Tab {
     /// Coalesces `Wakeup` events: the reader thread only sends a new Wakeup
     /// when this is `false`. The main thread clears it when processing.
     wakeup_pending: Arc<AtomicBool>,
+    /// Throughput, parse time, and echo latency, shared with the reader.
+    stats: Arc<PtyStats>,
+    /// Which edge of the alt screen is clipped when the window shrinks.
+    alt_screen_clip: ClipEdge,
+    /// PTY size change waiting for the resize gesture to settle.
+    pending_pty_resize: Option<PendingPtyResize>,
+    /// Last size (cells and pixels) reported to the PTY.
+    pty_size: portable_pty::PtySize,
+    /// OSC 99 notifications with an id that are currently shown, oldest
+    /// first and capped.
+    active_notifications: Vec<Notification>,
+    /// Focus last reported to the application (DECSET 1004).
+    focus: FocusState,
+    /// Set on shutdown: the reader discards output until the PTY closes.
+    closing: Arc<AtomicBool>,
+    /// Where background threads send their events.
+    events: EventSender,
+    /// Large paste being written in the background.
+    paste: PasteSlot,
+    /// The reader thread and (on Windows) the child waiter.
+    threads: Vec<JoinHandle<()>>,
 }
 
 impl Tab {
@@ -62,12 +145,13 @@ impl Tab {
         log(&format!("Tab::spawn start for {:?}", cfg.id));
 
         let pty_system = portable_pty::native_pty_system();
-        let pair = pty_system.openpty(portable_pty::PtySize {
+        let pty_size = portable_pty::PtySize {
             rows: cfg.rows as u16,
             cols: cfg.cols as u16,
             pixel_width: 0,
             pixel_height: 0,
-        })?;
+        };
+        let pair = pty_system.openpty(pty_size)?;
         log("  pty opened");
 
         let shell_line = cfg.shell.unwrap_or_else(Self::default_shell);
@@ -79,28 +163,17 @@ impl Tab {
             cmd.arg(arg);
         }
 
-        let detected_shell = cfg
-            .integration_dir
-            .as_ref()
-            .and_then(|_| shell_integration::detect_shell(&shell_program));
-
-        // For WSL, CWD is passed via --cd (Linux paths don't work as Windows CWD).
-        // For native shells, use cmd.cwd().
-        let is_wsl = detected_shell == Some(shell_integration::Shell::Wsl);
-        if let Some(ref dir) = cfg.cwd {
-            if !is_wsl {
-                cmd.cwd(dir);
-            }
-        }
+        let detected_shell = shell_integration::detect_shell(&shell_program);
+
+        // An inherited OSC 7 directory may come from the other side of WSL.
+        let target = CwdTarget::for_shell(detected_shell);
+        let is_wsl = target == CwdTarget::Wsl;
+        let cwd = cfg.cwd.as_deref().and_then(|dir| shell_integration::translate_cwd(dir, target));
+        shell_integration::apply_cwd(&mut cmd, cwd.as_deref(), target);
 
         if let Some(ref integ_dir) = cfg.integration_dir {
             if let Some(shell_type) = detected_shell {
-                let extra_arg = shell_integration::setup_injection(
-                    &mut cmd,
-                    shell_type,
-                    integ_dir,
-                    cfg.cwd.as_deref(),
-                );
+                let extra_arg = shell_integration::setup_injection(&mut cmd, shell_type, integ_dir);
                 if let Some(arg) = extra_arg {
                     cmd.arg(arg);
                 }
@@ -117,7 +190,7 @@ impl Tab {
 
         let initial_title = derive_initial_title(cfg.id, &shell_args, is_wsl);
 
-        let terminal_state = TerminalState::new(
+        let mut terminal_state = TerminalState::new(
             cfg.cols,
             cfg.rows,
             cfg.max_scrollback,
@@ -125,22 +198,41 @@ impl Tab {
             initial_title,
             is_wsl,
         );
+        terminal_state.answerback = cfg.answerback;
+        terminal_state.c1_controls = cfg.c1_controls;
+        if let Some(history) = &cfg.history {
+            terminal_state.restore_history(history);
+        }
+        // Restored history is not unread output.
+        let seen_lines = terminal_state.scrolled_lines();
         let terminal = Arc::new(FairMutex::new(terminal_state));
         let pty_writer: PtyWriter = Arc::new(parking_lot::Mutex::new(writer));
         let wakeup_pending = Arc::new(AtomicBool::new(false));
         let mode_cache = Arc::new(AtomicU32::new(TermMode::default().bits()));
+        let stats = Arc::new(PtyStats::new(Instant::now()));
 
-        spawn_reader_thread(
+        let closing = Arc::new(AtomicBool::new(false));
+        let events = Arc::clone(&cfg.events);
+        let paste = PasteSlot::default();
+
+        let reader_thread = spawn_reader_thread(
             cfg.id,
             reader,
-            Arc::clone(&terminal),
-            Arc::clone(&pty_writer),
-            Arc::clone(&wakeup_pending),
-            Arc::clone(&mode_cache),
-            cfg.proxy.clone(),
+            ReaderShared {
+                terminal: Arc::clone(&terminal),
+                pty_writer: Arc::clone(&pty_writer),
+                wakeup_pending: Arc::clone(&wakeup_pending),
+                mode_cache: Arc::clone(&mode_cache),
+                stats: Arc::clone(&stats),
+                closing: Arc::clone(&closing),
+                paste: Arc::clone(&paste),
+            },
+            Arc::clone(&cfg.events),
         );
         #[cfg(target_os = "windows")]
-        spawn_child_waiter(cfg.id, &*child, cfg.proxy);
+        let waiter_thread = spawn_child_waiter(cfg.id, &*child, cfg.events);
+        #[cfg(not(target_os = "windows"))]
+        let waiter_thread = None;
 
         log(&format!("Tab::spawn done for {:?}", cfg.id));
         Ok(Self {
@@ -151,23 +243,39 @@ impl Tab {
             child,
             selection: None,
             search: None,
-            has_bell_badge: false,
+            custom_title: None,
+            unseen: Unseen::default(),
+            has_activity: false,
+            busy: false,
+            foreground: None,
+            foreground_due: None,
+            foreground_next: Instant::now(),
+            notifications_muted: false,
+            broadcast_excluded: false,
+            pinned: false,
+            progress: TaskProgress::Hidden,
+            clipboard_access: None,
+            host_rule: None,
+            is_wsl,
+            seen_lines,
             grid_dirty: AtomicBool::new(true),
             mode_cache,
             wakeup_pending,
+            stats,
+            alt_screen_clip: cfg.alt_screen_clip,
+            pending_pty_resize: None,
+            pty_size,
+            active_notifications: Vec::new(),
+            focus: FocusState::default(),
+            closing,
+            events,
+            paste,
+            threads: std::iter::once(reader_thread).chain(waiter_thread).collect(),
         })
     }
 
-    /// Kill the child process and close PTY handles.
-    /// Must be called before dropping to avoid `ClosePseudoConsole` blocking
-    /// on Windows (the `ConPTY` reader thread holds a pipe handle).
-    pub fn shutdown(&mut self) {
-        // Kill the child process so ClosePseudoConsole returns quickly.
-        // The reader thread will see EOF or error and exit naturally.
-        let _ = self.child.kill();
-    }
-
-    fn default_shell() -> String {
+    /// The shell launched when `terminal.shell` is unset.
+    pub(crate) fn default_shell() -> String {
         #[cfg(target_os = "windows")]
         {
             "cmd.exe".to_owned()
@@ -257,6 +365,13 @@ impl Tab {
         self.set_grid_dirty(true);
     }
 
+    /// Scroll to `offset` rows back from live, clamped to the scrollback.
+    pub fn scroll_to_offset(&self, offset: usize) {
+        let grid = &mut *self.grid();
+        grid.display_offset = offset.min(grid.scrollback.len());
+        self.set_grid_dirty(true);
+    }
+
     /// Scroll by `delta` lines: positive = up (into history), negative = down.
     pub fn scroll_lines(&self, delta: i32) {
         let grid = &mut *self.grid();
@@ -285,6 +400,22 @@ impl Tab {
         self.set_grid_dirty(true);
     }
 
+    /// Select the scrollback and the screen (`Action::SelectAll`).
+    pub fn select_all(&mut self) {
+        let sel = Selection::all(self.terminal.lock().active_grid());
+        self.set_selection(sel);
+    }
+
+    /// Select the output of the most recent command
+    /// (`Action::SelectLastOutput`). Without shell integration marks the
+    /// selection is left alone.
+    pub fn select_last_output(&mut self) {
+        let sel = Selection::last_output(self.terminal.lock().active_grid());
+        if let Some(sel) = sel {
+            self.set_selection(sel);
+        }
+    }
+
     /// Update the end point of the current selection (drag tracking).
     pub fn update_selection_end(&mut self, end: SelectionPoint) {
         if let Some(ref mut sel) = self.selection {
@@ -293,59 +424,20 @@ impl Tab {
         }
     }
 
-    // ── Prompt navigation ──────────────────────────────────────────────
+    // ── Unread output ──────────────────────────────────────────────────
 
-    /// Navigate to the previous shell prompt (OSC 133 marker) in scrollback.
-    pub fn navigate_to_previous_prompt(&self) {
-        let grid = &mut *self.grid();
-        let sb_len = grid.scrollback.len();
-        // Current top of viewport as scrollback index.
-        let viewport_top_sb = sb_len.saturating_sub(grid.display_offset);
-        // Scan scrollback rows backwards from just above viewport top.
-        let mut target_sb = None;
-        for i in (0..viewport_top_sb).rev() {
-            if grid.scrollback[i].prompt_start {
-                target_sb = Some(i);
-                break;
-            }
-        }
-        if let Some(sb_idx) = target_sb {
-            grid.display_offset = sb_len.saturating_sub(sb_idx);
-            self.set_grid_dirty(true);
-        }
+    /// Lines of output scrolled since the user last viewed this tab.
+    pub fn unread_lines(&self) -> u64 {
+        self.terminal
+            .lock()
+            .scrolled_lines()
+            .saturating_sub(self.seen_lines)
     }
 
-    /// Navigate to the next shell prompt (OSC 133 marker) below the viewport.
-    pub fn navigate_to_next_prompt(&self) {
-        let grid = &mut *self.grid();
-        let sb_len = grid.scrollback.len();
-        // Current bottom of viewport as absolute index.
-        let viewport_bottom_sb = sb_len.saturating_sub(grid.display_offset) + grid.lines;
-        // Scan forward from below viewport.
-        let total_rows = sb_len + grid.lines;
-        let mut target_sb = None;
-        for i in viewport_bottom_sb..total_rows {
-            let has_prompt = if i < sb_len {
-                grid.scrollback[i].prompt_start
-            } else {
-                grid.row(i - sb_len).prompt_start
-            };
-            if has_prompt {
-                target_sb = Some(i);
-                break;
-            }
-        }
-        if let Some(idx) = target_sb {
-            if idx < sb_len {
-                grid.display_offset = sb_len.saturating_sub(idx);
-            } else {
-                grid.display_offset = 0;
-            }
-        } else {
-            // No prompt below — scroll to live.
-            grid.display_offset = 0;
-        }
-        self.set_grid_dirty(true);
+    /// Mark all output so far as seen (the tab is active).
+    pub fn mark_output_seen(&mut self) {
+        self.seen_lines = self.terminal.lock().scrolled_lines();
+        self.has_activity = false;
     }
 
     // ── PTY I/O ────────────────────────────────────────────────────────
@@ -353,241 +445,31 @@ impl Tab {
     /// Write raw bytes to the PTY stdin.
     ///
     /// Thread-safe: uses the shared writer mutex. Takes `&self` because the
-    /// writer is behind `Arc<Mutex<>>`.
+    /// writer is behind `Arc<Mutex<>>`. While a large paste is in flight
+    /// the bytes wait until it is done.
     pub fn send_pty(&self, data: &[u8]) {
-        if !data.is_empty() {
+        if !data.is_empty() && !self.queue_behind_paste(data) {
             let mut w = self.pty_writer.lock();
             let _ = w.write_all(data);
             let _ = w.flush();
         }
     }
 
-    // ── Resize ─────────────────────────────────────────────────────────
-
-    /// Resize grids and PTY to the given dimensions.
-    pub fn resize(&self, cols: usize, rows: usize, pixel_width: u16, pixel_height: u16) {
-        {
-            let mut term = self.terminal.lock();
-            // Alt screen never reflows (full-screen apps redraw themselves).
-            let reflow = true;
-            term.primary_grid.resize(cols, rows, reflow);
-            term.alt_grid.resize(cols, rows, false);
-        }
-        let _ = self.pty_master.resize(portable_pty::PtySize {
-            rows: rows as u16,
-            cols: cols as u16,
-            pixel_width,
-            pixel_height,
-        });
-    }
-
-    // ── Color / cursor config ──────────────────────────────────────────
-
-    /// Apply color scheme, overrides, and bold-is-bright in one call.
-    pub fn apply_color_config(
-        &self,
-        scheme: Option<&ColorScheme>,
-        colors: &ColorConfig,
-        bold_is_bright: bool,
-    ) {
-        self.terminal
-            .lock()
-            .apply_color_config(scheme, colors, bold_is_bright);
-    }
-
-    /// Set the cursor shape (block, underline, bar).
-    pub fn set_cursor_shape(&self, shape: CursorShape) {
-        self.terminal.lock().cursor_shape = shape;
-    }
-
-    // ── Search ─────────────────────────────────────────────────────────
-
-    /// Open a new search session, replacing any existing one.
-    pub fn open_search(&mut self) {
-        self.search = Some(SearchState::new());
-        self.set_grid_dirty(true);
-    }
-
-    /// Close the active search session.
-    pub fn close_search(&mut self) {
-        self.search = None;
-        self.set_grid_dirty(true);
-    }
-
-    /// Update the search query. Handles the borrow-checker dance of
-    /// borrowing the grid while mutating `self.search`.
-    pub fn update_search_query(&mut self) {
-        if let Some(mut search) = self.search.take() {
-            let term = self.terminal.lock();
-            search.update_query(term.active_grid());
-            drop(term);
-            self.search = Some(search);
-            self.set_grid_dirty(true);
+    /// Write keyboard input to the PTY and start timing its echo.
+    pub fn send_input(&self, data: &[u8]) {
+        if !data.is_empty() {
+            self.stats.mark_input(Instant::now());
+            self.send_pty(data);
         }
     }
 
-    // ── Title ──────────────────────────────────────────────────────────
-
-    /// Return the display title for the tab bar. If the shell explicitly set a
-    /// title via OSC 0/2, use that. Otherwise derive a short path from CWD.
-    ///
-    /// Returns an owned `String` because the title data is behind a mutex.
-    pub fn effective_title(&self) -> String {
-        self.terminal.lock().effective_title()
-    }
-}
-
-/// Maximum read buffer before forcing a lock. 1 MB.
-const READ_BUFFER_SIZE: usize = 0x10_0000;
-/// Maximum bytes to parse per lock hold. Limits how long the reader thread
-/// blocks the main thread. Larger = higher throughput, smaller = lower latency.
-const MAX_LOCKED_PARSE: usize = 0x1_0000; // 64 KB
-
-/// Spawn the PTY reader thread that parses VTE output under the terminal lock.
-///
-/// The reader thread shares the PTY writer (for VTE responses like DA,
-/// DECRPM). The main thread writes keyboard/mouse input through the same
-/// shared writer. No channel needed — both threads write directly.
-fn spawn_reader_thread(
-    id: TabId,
-    mut reader: Box<dyn Read + Send>,
-    terminal: Arc<FairMutex<TerminalState>>,
-    pty_writer: PtyWriter,
-    wakeup_pending: Arc<AtomicBool>,
-    mode_cache: Arc<AtomicU32>,
-    proxy: EventLoopProxy<TermEvent>,
-) {
-    thread::spawn(move || {
-        log(&format!("reader thread started for tab {:?}", id));
-        let mut buf = vec![0u8; READ_BUFFER_SIZE];
-        let mut pty_responses: Vec<u8> = Vec::new();
-        // Track unprocessed data as buf[start..end] to avoid shifting
-        // after every chunk. Only compact when the tail reaches the end.
-        let mut start = 0;
-        let mut end = 0;
-
-        loop {
-            // Read without holding a lease — the read may block
-            // indefinitely (idle shell), and the main thread must be
-            // able to lock the terminal for rendering in the meantime.
-            if start == end {
-                start = 0;
-                match reader.read(&mut buf) {
-                    Ok(0) => {
-                        log(&format!("reader: eof for tab {:?}", id));
-                        let _ = proxy.send_event(TermEvent::PtyExited(id));
-                        break;
-                    }
-                    Err(e) => {
-                        log(&format!("reader error for tab {:?}: {e}", id));
-                        let _ = proxy.send_event(TermEvent::PtyExited(id));
-                        break;
-                    }
-                    Ok(n) => {
-                        end = n;
-                    }
-                }
-            }
-
-            // Data is available — acquire a lease so the renderer yields
-            // to us, then try the lock. The lease is only held during the
-            // lock attempt, never across a blocking read.
-            let mut term = {
-                let lease = terminal.lease();
-                if let Some(t) = terminal.try_lock_unfair() {
-                    t
-                    // lease dropped here
-                } else {
-                    drop(lease);
-                    // Renderer holds the lock — yield to let it finish,
-                    // then wait for the data lock. We must not block on
-                    // a pipe read while unprocessed bytes remain: those
-                    // bytes may contain a DA/DSR query whose response
-                    // ConPTY awaits before sending more output, which
-                    // would deadlock (reader ← pipe ← ConPTY ← response
-                    // in unprocessed buf).
-                    thread::yield_now();
-                    terminal.lock_unfair()
-                }
-            };
-
-            // Parse in bounded chunks to limit lock hold time.
-            // This lets the main thread acquire the lock between
-            // chunks for rendering, keybindings, etc.
-            let chunk = (end - start).min(MAX_LOCKED_PARSE);
-            pty_responses.clear();
-            term.process_output(&buf[start..start + chunk], &mut pty_responses);
-            mode_cache.store(term.mode.bits(), Ordering::Relaxed);
-            drop(term);
-            start += chunk;
-
-            // Flush VTE responses (DA, DECRPM, etc.) outside the terminal
-            // lock. Writing to the ConPTY input pipe while holding the lock
-            // can deadlock: ConPTY may be blocked writing to its output pipe
-            // (waiting for us to read), so it can't read our responses.
-            if !pty_responses.is_empty() {
-                let mut w = pty_writer.lock();
-                let _ = w.write_all(&pty_responses);
-                let _ = w.flush();
-            }
-
-            // Only send Wakeup if one isn't already pending.
-            if !wakeup_pending.swap(true, Ordering::Relaxed) {
-                let _ = proxy.send_event(TermEvent::Wakeup(id));
-            }
-        }
-    });
-}
-
-/// Spawn a thread that waits for the child process to exit.
-///
-/// Windows/ConPTY does not reliably deliver EOF to the reader when the child
-/// exits, so we wait on the process handle directly (like Alacritty, `WezTerm`,
-/// and Ghostty all do).
-#[cfg(target_os = "windows")]
-fn spawn_child_waiter(
-    id: TabId,
-    child: &dyn portable_pty::Child,
-    proxy: EventLoopProxy<TermEvent>,
-) {
-    if let Some(pid) = child.process_id() {
-        thread::spawn(move || {
-            use windows_sys::Win32::Foundation::CloseHandle;
-            use windows_sys::Win32::System::Threading::{INFINITE, OpenProcess, WaitForSingleObject};
-            const SYNCHRONIZE: u32 = 0x0010_0000;
-
-            log(&format!(
-                "child waiter thread started for tab {:?} (pid {})",
-                id, pid
-            ));
-            #[allow(unsafe_code)]
-            let handle = unsafe { OpenProcess(SYNCHRONIZE, 0, pid) };
-            if !handle.is_null() {
-                #[allow(unsafe_code)]
-                unsafe {
-                    WaitForSingleObject(handle, INFINITE)
-                };
-                #[allow(unsafe_code)]
-                unsafe {
-                    CloseHandle(handle)
-                };
-                log(&format!("child exited (waiter) for tab {:?}", id));
-                let _ = proxy.send_event(TermEvent::PtyExited(id));
-            }
-        });
+    /// Whether the echo of a keystroke was parsed since the last call.
+    pub fn take_echo(&self) -> bool {
+        self.stats.take_echo()
     }
-}
 
-/// Derive a good initial title. For WSL, use the distro name.
-fn derive_initial_title(id: TabId, shell_args: &[&str], is_wsl: bool) -> String {
-    if is_wsl {
-        shell_args
-            .iter()
-            .zip(shell_args.iter().skip(1))
-            .find(|&(&flag, _)| flag == "-d" || flag == "--distribution")
-            .map_or_else(|| "WSL".to_owned(), |(_, &name)| name.to_owned())
-    } else {
-        format!("Tab {}", id.0)
+    /// PTY throughput, parse load, and input-to-echo latency.
+    pub fn pty_stats(&self) -> PtyStatsSnapshot {
+        self.stats.snapshot(Instant::now())
     }
 }
-
@@ -0,0 +1,252 @@
+//! Kitty desktop-notification protocol (OSC 99).
+//!
+//! Format: `OSC 99 ; key=value:key=value ; payload ST`. Payloads may be
+//! split across several sequences (`d=0`) and are joined by identifier.
+//! Activation and close events are reported back to the PTY as
+//! `OSC 99 ; i=<id> ; [button] ST` and `OSC 99 ; i=<id>:p=close ; ST`.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+
+use super::Tab;
+use super::types::{Notification, NotificationEvent};
+
+/// Reply to a `p=?` capability query.
+const CAPABILITIES: &str = "a=focus,report:c=1:p=title,body,?,close,buttons";
+
+/// Most notifications with an id a tab tracks; the oldest is forgotten
+/// past this.
+const MAX_ACTIVE_NOTIFICATIONS: usize = 32;
+
+/// Separator between button labels in a `p=buttons` payload (U+2028).
+const BUTTON_SEPARATOR: char = '\u{2028}';
+
+/// What the payload of one OSC 99 chunk carries (`p=`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PayloadKind {
+    Title,
+    Body,
+    Buttons,
+    Close,
+    Query,
+    Unsupported,
+}
+
+/// Parsed `key=value` metadata of one OSC 99 chunk.
+struct Metadata {
+    id: Option<String>,
+    done: bool,
+    kind: PayloadKind,
+    base64: bool,
+    report_activation: Option<bool>,
+    report_close: Option<bool>,
+}
+
+impl Metadata {
+    fn parse(raw: &[u8]) -> Self {
+        let mut meta = Self {
+            id: None,
+            done: true,
+            kind: PayloadKind::Title,
+            base64: false,
+            report_activation: None,
+            report_close: None,
+        };
+        let raw = String::from_utf8_lossy(raw);
+        for pair in raw.split(':') {
+            let Some((key, value)) = pair.split_once('=') else {
+                continue;
+            };
+            match key {
+                "i" => meta.id = sanitize_id(value),
+                "d" => meta.done = value != "0",
+                "e" => meta.base64 = value == "1",
+                "c" => meta.report_close = Some(value == "1"),
+                "a" => meta.report_activation = parse_actions(value),
+                "p" => {
+                    meta.kind = match value {
+                        "title" => PayloadKind::Title,
+                        "body" => PayloadKind::Body,
+                        "buttons" => PayloadKind::Buttons,
+                        "close" => PayloadKind::Close,
+                        "?" => PayloadKind::Query,
+                        _ => PayloadKind::Unsupported,
+                    };
+                }
+                _ => {}
+            }
+        }
+        meta
+    }
+}
+
+/// Keep only the identifier characters the spec allows.
+fn sanitize_id(raw: &str) -> Option<String> {
+    let id: String = raw
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '+' | '.'))
+        .collect();
+    (!id.is_empty()).then_some(id)
+}
+
+/// Whether `a=` enables (`report`) or disables (`-report`) activation reports.
+fn parse_actions(value: &str) -> Option<bool> {
+    value.split(',').fold(None, |acc, action| match action {
+        "report" => Some(true),
+        "-report" => Some(false),
+        _ => acc,
+    })
+}
+
+/// Joins chunked OSC 99 sequences into complete notification events.
+#[derive(Debug, Default)]
+pub(super) struct Osc99Assembler {
+    /// Notification whose chunks are still arriving (`d=0`).
+    partial: Option<Notification>,
+}
+
+impl Osc99Assembler {
+    /// Handle one `OSC 99` sequence. `params` are the vte OSC params after
+    /// the leading `99`; any `;` inside the payload is restored.
+    pub(super) fn dispatch(
+        &mut self,
+        params: &[&[u8]],
+        events: &mut Vec<NotificationEvent>,
+        pty_responses: &mut Vec<u8>,
+    ) {
+        let meta = Metadata::parse(params.first().copied().unwrap_or_default());
+        let payload = params.get(1..).unwrap_or_default().join(&b';');
+        let payload = if meta.base64 {
+            BASE64.decode(&payload).unwrap_or_default()
+        } else {
+            payload
+        };
+        let text = String::from_utf8_lossy(&payload);
+
+        match meta.kind {
+            PayloadKind::Query => {
+                let id = meta.id.map(|id| format!("i={id}:")).unwrap_or_default();
+                let reply = format!("\x1b]99;{id}p=?;{CAPABILITIES}\x1b\\");
+                pty_responses.extend_from_slice(reply.as_bytes());
+                return;
+            }
+            PayloadKind::Close => {
+                if let Some(id) = meta.id {
+                    events.push(NotificationEvent::Close(id));
+                }
+                return;
+            }
+            PayloadKind::Unsupported => return,
+            PayloadKind::Title | PayloadKind::Body | PayloadKind::Buttons => {}
+        }
+
+        // A chunk with a different id abandons the unfinished notification.
+        let mut notif = match self.partial.take() {
+            Some(partial) if partial.id == meta.id => partial,
+            _ => Notification {
+                id: meta.id,
+                ..Notification::default()
+            },
+        };
+        match meta.kind {
+            PayloadKind::Title => notif.title.push_str(&text),
+            PayloadKind::Body => notif.body.push_str(&text),
+            _ => notif.buttons.extend(
+                text.split(BUTTON_SEPARATOR)
+                    .filter(|b| !b.is_empty())
+                    .map(str::to_owned),
+            ),
+        }
+        if let Some(report) = meta.report_activation {
+            notif.report_activation = report;
+        }
+        if let Some(report) = meta.report_close {
+            notif.report_close = report;
+        }
+
+        if meta.done {
+            events.push(NotificationEvent::Show(notif));
+        } else {
+            self.partial = Some(notif);
+        }
+    }
+}
+
+impl Notification {
+    /// Bytes reporting that the user activated this notification, or pressed
+    /// button `button` (1-based). `None` unless the program asked for reports.
+    pub fn activation_report(&self, button: Option<usize>) -> Option<String> {
+        if !self.report_activation {
+            return None;
+        }
+        let id = self.id.as_deref().unwrap_or("0");
+        let button = button.map(|b| b.to_string()).unwrap_or_default();
+        Some(format!("\x1b]99;i={id};{button}\x1b\\"))
+    }
+
+    /// Bytes reporting that this notification was closed. `None` unless the
+    /// program asked for close reports with `c=1`.
+    pub fn close_report(&self) -> Option<String> {
+        if !self.report_close {
+            return None;
+        }
+        let id = self.id.as_deref().unwrap_or("0");
+        Some(format!("\x1b]99;i={id}:p=close;\x1b\\"))
+    }
+}
+
+impl Tab {
+    /// Apply a notification event to this tab's set of shown notifications.
+    ///
+    /// Returns the notification to display for `Show` events. A `Show` with
+    /// an id already on screen replaces it; a `Close` dismisses it and
+    /// reports the close if requested. Only the newest
+    /// [`MAX_ACTIVE_NOTIFICATIONS`] are tracked.
+    pub fn apply_notification_event(&mut self, event: NotificationEvent) -> Option<Notification> {
+        match event {
+            NotificationEvent::Show(notif) => {
+                if notif.id.is_some() {
+                    self.active_notifications.retain(|n| n.id != notif.id);
+                    self.active_notifications.push(notif.clone());
+                    let excess = self
+                        .active_notifications
+                        .len()
+                        .saturating_sub(MAX_ACTIVE_NOTIFICATIONS);
+                    self.active_notifications.drain(..excess);
+                }
+                Some(notif)
+            }
+            NotificationEvent::Close(id) => {
+                self.notification_closed(&id);
+                None
+            }
+        }
+    }
+
+    /// The user activated notification `id`, optionally via a button.
+    pub fn notification_activated(&self, id: &str, button: Option<usize>) {
+        let report = self
+            .active_notifications
+            .iter()
+            .find(|n| n.id.as_deref() == Some(id))
+            .and_then(|n| n.activation_report(button));
+        if let Some(report) = report {
+            self.send_pty(report.as_bytes());
+        }
+    }
+
+    /// Notification `id` was closed, by the user or by the program.
+    pub fn notification_closed(&mut self, id: &str) {
+        let Some(pos) = self
+            .active_notifications
+            .iter()
+            .position(|n| n.id.as_deref() == Some(id))
+        else {
+            return;
+        };
+        let notif = self.active_notifications.remove(pos);
+        if let Some(report) = notif.close_report() {
+            self.send_pty(report.as_bytes());
+        }
+    }
+}
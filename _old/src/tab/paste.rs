@@ -0,0 +1,230 @@
+//! Pasting into the PTY: small pastes are written at once, large ones in
+//! bounded chunks from a background thread so a slow reader never stalls
+//! the event loop.
+//!
+//! The bracketed paste markers always come as a pair, even when a paste is
+//! cancelled. Keys typed, text pasted, and the terminal's replies to the
+//! program while a paste is in flight wait until it is done, so they never
+//! land inside the brackets.
+
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread::{self, JoinHandle};
+
+use parking_lot::Mutex;
+
+use super::types::{EventSender, PtyWriter, TabId, TaskProgress, TermEvent};
+use super::Tab;
+use crate::log;
+
+/// Pastes longer than this are written from a background thread, one
+/// chunk of this size per writer lock.
+const CHUNK: usize = 16 * 1024;
+/// Pastes at least this big show their progress in the tab bar.
+const PROGRESS_MIN: usize = 1024 * 1024;
+
+const BRACKET_OPEN: &[u8] = b"\x1b[200~";
+const BRACKET_CLOSE: &[u8] = b"\x1b[201~";
+
+/// Whether pasted text is wrapped in bracketed paste markers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bracketing {
+    /// Send the text as is.
+    Plain,
+    /// Wrap it in `ESC [200~` … `ESC [201~` (DECSET 2004).
+    Bracketed,
+}
+
+/// State shared between the tab and its paste thread.
+struct PasteShared {
+    /// Text bytes written so far.
+    sent: AtomicUsize,
+    /// Text bytes in the paste.
+    total: usize,
+    cancel: AtomicBool,
+    /// Input held back until the paste is done, and whether it is (set by
+    /// the thread under this lock once it has written the queue).
+    queued: Mutex<(Vec<u8>, bool)>,
+}
+
+/// The tab's paste in flight, shared with the reader thread so the
+/// terminal's replies queue behind it too.
+pub(super) type PasteSlot = Arc<Mutex<Option<PasteJob>>>;
+
+/// Hold `bytes` until the paste in `slot` is done. Returns `false`, and
+/// holds nothing, when no paste is in flight.
+pub(super) fn queue_behind(slot: &PasteSlot, bytes: &[u8]) -> bool {
+    slot.lock().as_ref().is_some_and(|job| job.queue(bytes))
+}
+
+/// A paste being written by a background thread.
+pub(super) struct PasteJob {
+    shared: Arc<PasteShared>,
+    thread: JoinHandle<()>,
+}
+
+impl PasteJob {
+    /// Start writing `text` to `pty_writer` on a background thread.
+    pub(super) fn start(
+        id: TabId,
+        pty_writer: PtyWriter,
+        events: EventSender,
+        text: &str,
+        bracketing: Bracketing,
+    ) -> Self {
+        let shared = Arc::new(PasteShared {
+            sent: AtomicUsize::new(0),
+            total: text.len(),
+            cancel: AtomicBool::new(false),
+            queued: Mutex::new((Vec::new(), false)),
+        });
+        let writer = PasteWriter {
+            id,
+            pty_writer,
+            events,
+            shared: Arc::clone(&shared),
+        };
+        let text = text.as_bytes().to_vec();
+        let thread = thread::spawn(move || writer.run(&text, bracketing));
+        Self { shared, thread }
+    }
+
+    /// Hold `bytes` until the paste is done. Returns `false`, and holds
+    /// nothing, if it already is.
+    pub(super) fn queue(&self, bytes: &[u8]) -> bool {
+        let mut queued = self.shared.queued.lock();
+        if queued.1 {
+            return false;
+        }
+        queued.0.extend_from_slice(bytes);
+        true
+    }
+
+    fn is_done(&self) -> bool {
+        self.shared.queued.lock().1
+    }
+
+    /// Stop after the chunk being written, then close the brackets.
+    pub(super) fn cancel(&self) {
+        self.shared.cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// Wait for the thread to finish.
+    pub(super) fn join(self) {
+        if self.thread.join().is_err() {
+            log("paste: writer thread panicked");
+        }
+    }
+}
+
+impl Tab {
+    /// Paste `text` into the PTY, wrapped in paste markers per `bracketing`.
+    pub fn paste(&mut self, text: &str, bracketing: Bracketing) {
+        self.reap_paste();
+        let in_flight = self.paste.lock().is_some();
+        if in_flight || text.len() <= CHUNK {
+            // Behind a paste in flight, this waits its turn in `send_pty`.
+            let mut bytes = Vec::with_capacity(text.len() + 12);
+            if bracketing == Bracketing::Bracketed {
+                bytes.extend_from_slice(BRACKET_OPEN);
+                bytes.extend_from_slice(text.as_bytes());
+                bytes.extend_from_slice(BRACKET_CLOSE);
+            } else {
+                bytes.extend_from_slice(text.as_bytes());
+            }
+            self.send_pty(&bytes);
+            return;
+        }
+        let job = PasteJob::start(
+            self.id,
+            Arc::clone(&self.pty_writer),
+            Arc::clone(&self.events),
+            text,
+            bracketing,
+        );
+        *self.paste.lock() = Some(job);
+    }
+
+    /// Hold `bytes` until the paste in flight is done. Returns `false`, and
+    /// holds nothing, when no paste is in flight.
+    pub(super) fn queue_behind_paste(&self, bytes: &[u8]) -> bool {
+        queue_behind(&self.paste, bytes)
+    }
+
+    /// Cancel the paste in flight. Returns whether there was one.
+    pub fn cancel_paste(&mut self) -> bool {
+        match &*self.paste.lock() {
+            Some(job) if !job.is_done() => {
+                job.cancel();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Drop the paste job once its thread is done.
+    pub fn reap_paste(&mut self) {
+        let done = self.paste.lock().take_if(|job| job.is_done());
+        if let Some(job) = done {
+            job.join();
+        }
+    }
+
+    /// Progress to show in the tab bar: the paste in flight if it is large,
+    /// else the program's OSC 9;4 progress.
+    pub fn shown_progress(&self) -> TaskProgress {
+        match &*self.paste.lock() {
+            Some(job) if job.shared.total >= PROGRESS_MIN && !job.is_done() => {
+                let sent = job.shared.sent.load(Ordering::Relaxed);
+                TaskProgress::Normal((sent * 100 / job.shared.total) as u8)
+            }
+            _ => self.progress,
+        }
+    }
+}
+
+/// The paste thread's side of a [`PasteJob`].
+struct PasteWriter {
+    id: TabId,
+    pty_writer: PtyWriter,
+    events: EventSender,
+    shared: Arc<PasteShared>,
+}
+
+impl PasteWriter {
+    fn run(self, text: &[u8], bracketing: Bracketing) {
+        let bracketed = bracketing == Bracketing::Bracketed;
+        let report = self.shared.total >= PROGRESS_MIN;
+        let mut opened = false;
+        for chunk in text.chunks(CHUNK) {
+            if self.shared.cancel.load(Ordering::Relaxed) {
+                break;
+            }
+            let mut w = self.pty_writer.lock();
+            let open = if bracketed && !opened { BRACKET_OPEN } else { &[] };
+            opened = true;
+            if w.write_all(open).and_then(|()| w.write_all(chunk)).and_then(|()| w.flush()).is_err()
+            {
+                break;
+            }
+            drop(w);
+            self.shared.sent.fetch_add(chunk.len(), Ordering::Relaxed);
+            if report {
+                self.events.send(TermEvent::Paste(self.id));
+            }
+        }
+
+        // Close the brackets and send what was held back in one write, so
+        // nothing can slip in between.
+        let mut queued = self.shared.queued.lock();
+        let mut tail = if bracketed && opened { BRACKET_CLOSE.to_vec() } else { Vec::new() };
+        tail.append(&mut queued.0);
+        let mut w = self.pty_writer.lock();
+        let _ = w.write_all(&tail).and_then(|()| w.flush());
+        drop(w);
+        queued.1 = true;
+        drop(queued);
+        self.events.send(TermEvent::Paste(self.id));
+    }
+}
@@ -0,0 +1,227 @@
+//! The tab's shell, what it is running in the foreground, and the title
+//! shown for both.
+//!
+//! On Unix the foreground process is the PTY's foreground process group
+//! (`tcgetpgrp`), named from `/proc` on Linux and `proc_name` on macOS. On
+//! Windows it is the newest of the deepest processes under the shell, since
+//! a pseudo console has no notion of a foreground job.
+//!
+//! Asking the OS means a syscall or a full process snapshot, so output only
+//! schedules a check and checks run at most once per [`FOREGROUND_POLL`].
+
+use std::time::{Duration, Instant};
+
+use crate::icons::Icon;
+
+use super::Tab;
+use super::types::PromptState;
+
+/// Shortest time between two foreground checks of one tab.
+const FOREGROUND_POLL: Duration = Duration::from_millis(250);
+
+impl Tab {
+    /// Icon shown before the title in the tab bar.
+    pub fn icon(&self) -> Icon {
+        if self.is_wsl {
+            Icon::Penguin
+        } else {
+            Icon::Prompt
+        }
+    }
+
+    /// Return the display title for the tab bar. If the shell explicitly set a
+    /// title via OSC 0/2, use that. Otherwise derive a short path from CWD.
+    /// Either way a foreground process goes in front ("vim — ~/project").
+    ///
+    /// Returns an owned `String` because the title data is behind a mutex.
+    pub fn effective_title(&self) -> String {
+        if let Some(name) = &self.custom_title {
+            return name.clone();
+        }
+        let title = self.terminal.lock().effective_title();
+        match &self.foreground {
+            Some(name) => title_with_process(name, &title),
+            None => title,
+        }
+    }
+
+    /// Whether a command other than the shell was running at the last
+    /// [`refresh_foreground`](Self::refresh_foreground).
+    pub fn is_busy(&self) -> bool {
+        self.busy
+    }
+
+    /// Name of the foreground process other than the shell, as of the last
+    /// [`refresh_foreground`](Self::refresh_foreground).
+    pub fn foreground_process(&self) -> Option<&str> {
+        self.foreground.as_deref()
+    }
+
+    /// Re-check what the shell is running: shell integration reports a
+    /// command between OSC 133 C and D, and the OS reports the foreground
+    /// process. Returns whether either changed.
+    pub fn refresh_foreground(&mut self) -> bool {
+        self.foreground_due = None;
+        self.foreground_next = Instant::now() + FOREGROUND_POLL;
+        let (running, foreground) = self.query_foreground();
+        let busy = running || self.terminal.lock().prompt_state == PromptState::OutputStart;
+        let changed = busy != self.busy || foreground != self.foreground;
+        self.busy = busy;
+        self.foreground = foreground;
+        changed
+    }
+
+    /// Note that output arrived, so a command may have started or ended.
+    /// The check runs from [`poll_foreground`](Self::poll_foreground) no
+    /// sooner than [`FOREGROUND_POLL`] after the previous one.
+    pub fn foreground_may_have_changed(&mut self, now: Instant) {
+        self.foreground_due
+            .get_or_insert_with(|| self.foreground_next.max(now));
+    }
+
+    /// When the scheduled foreground check is due, if one is.
+    pub fn foreground_due(&self) -> Option<Instant> {
+        self.foreground_due
+    }
+
+    /// Run the scheduled foreground check if it is due. Returns whether the
+    /// busy state or the foreground process changed.
+    pub fn poll_foreground(&mut self, now: Instant) -> bool {
+        self.foreground_due.is_some_and(|due| due <= now) && self.refresh_foreground()
+    }
+
+    /// Whether a process other than the shell is in the foreground, and
+    /// its name if the OS tells.
+    #[cfg(unix)]
+    fn query_foreground(&self) -> (bool, Option<String>) {
+        let Some(fd) = self.pty_master.as_raw_fd() else {
+            return (false, None);
+        };
+        // SAFETY: `fd` is the PTY master, open for as long as `pty_master`.
+        #[allow(unsafe_code)]
+        let group = unsafe { libc::tcgetpgrp(fd) };
+        let leader = u32::try_from(group).ok().filter(|&pid| pid > 0);
+        let Some(leader) = leader.filter(|&pid| Some(pid) != self.child.process_id()) else {
+            return (false, None);
+        };
+        (true, process_name(leader))
+    }
+
+    /// Whether a process other than the shell is in the foreground, and
+    /// its name.
+    #[cfg(target_os = "windows")]
+    fn query_foreground(&self) -> (bool, Option<String>) {
+        // Processes inside WSL don't show up in the Windows process list.
+        let name = self
+            .child
+            .process_id()
+            .filter(|_| !self.is_wsl)
+            .and_then(windows::newest_descendant)
+            .map(|name| name.strip_suffix(".exe").unwrap_or(&name).to_owned());
+        (name.is_some(), name)
+    }
+}
+
+/// `title` prefixed with the foreground process `name`, unless the title
+/// already names it (programs that set their own title usually do).
+pub(super) fn title_with_process(name: &str, title: &str) -> String {
+    if title.to_lowercase().contains(&name.to_lowercase()) {
+        title.to_owned()
+    } else {
+        format!("{name} \u{2014} {title}")
+    }
+}
+
+/// Short name of process `pid`, from `/proc`.
+#[cfg(target_os = "linux")]
+fn process_name(pid: u32) -> Option<String> {
+    std::fs::read_to_string(format!("/proc/{pid}/comm"))
+        .ok()
+        .map(|comm| comm.trim_end().to_owned())
+        .filter(|name| !name.is_empty())
+}
+
+/// Short name of process `pid`, from `proc_name` (macOS has no `/proc`).
+#[cfg(target_os = "macos")]
+fn process_name(pid: u32) -> Option<String> {
+    let pid = i32::try_from(pid).ok()?;
+    let mut buf = [0u8; 64];
+    // SAFETY: `buf` is writable for the length passed.
+    #[allow(unsafe_code)]
+    let len = unsafe { libc::proc_name(pid, buf.as_mut_ptr().cast(), buf.len() as u32) };
+    let len = usize::try_from(len).ok().filter(|&len| len > 0)?;
+    Some(String::from_utf8_lossy(&buf[..len]).into_owned())
+}
+
+/// Other Unixes have no portable way to name a process; the tab still
+/// shows as busy.
+#[cfg(all(unix, not(any(target_os = "linux", target_os = "macos"))))]
+fn process_name(_pid: u32) -> Option<String> {
+    None
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::collections::{HashMap, HashSet};
+
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW,
+        TH32CS_SNAPPROCESS,
+    };
+
+    /// Executable name of the deepest process under `root`, the one
+    /// listed last (usually the newest) among equals. `None` if `root` has
+    /// no children.
+    pub(super) fn newest_descendant(root: u32) -> Option<String> {
+        let children = snapshot();
+        let mut best: Option<(usize, &str)> = None;
+        let mut seen = HashSet::from([root]);
+        let mut frontier = vec![(root, 0)];
+        while let Some((parent, depth)) = frontier.pop() {
+            for (pid, name) in children.get(&parent).into_iter().flatten() {
+                if best.is_none_or(|(d, _)| depth + 1 >= d) {
+                    best = Some((depth + 1, name));
+                }
+                // A recycled parent pid can make the tree loop.
+                if seen.insert(*pid) {
+                    frontier.push((*pid, depth + 1));
+                }
+            }
+        }
+        best.map(|(_, name)| name.to_owned())
+    }
+
+    /// Every running process as `(pid, executable name)`, grouped by
+    /// parent pid in snapshot order.
+    fn snapshot() -> HashMap<u32, Vec<(u32, String)>> {
+        let mut processes: HashMap<u32, Vec<(u32, String)>> = HashMap::new();
+        #[allow(unsafe_code)]
+        let snap = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) };
+        if snap == INVALID_HANDLE_VALUE {
+            return processes;
+        }
+        #[allow(unsafe_code)]
+        let mut entry: PROCESSENTRY32W = unsafe { std::mem::zeroed() };
+        entry.dwSize = size_of::<PROCESSENTRY32W>() as u32;
+        #[allow(unsafe_code)]
+        let mut more = unsafe { Process32FirstW(snap, &raw mut entry) } != 0;
+        while more {
+            let len = entry.szExeFile.iter().position(|&c| c == 0).unwrap_or(entry.szExeFile.len());
+            let name = String::from_utf16_lossy(&entry.szExeFile[..len]);
+            processes
+                .entry(entry.th32ParentProcessID)
+                .or_default()
+                .push((entry.th32ProcessID, name));
+            #[allow(unsafe_code)]
+            {
+                more = unsafe { Process32NextW(snap, &raw mut entry) } != 0;
+            }
+        }
+        #[allow(unsafe_code)]
+        unsafe {
+            CloseHandle(snap)
+        };
+        processes
+    }
+}
@@ -0,0 +1,36 @@
+//! Scrolling the viewport to shell prompts marked with OSC 133.
+
+use super::Tab;
+use crate::grid::ZoneKind;
+
+impl Tab {
+    /// Navigate to the previous shell prompt (OSC 133 marker) in scrollback.
+    pub fn navigate_to_previous_prompt(&self) {
+        let grid = &mut *self.grid();
+        let sb_len = grid.scrollback.len();
+        // Current top of viewport as absolute row.
+        let viewport_top = sb_len.saturating_sub(grid.display_offset);
+        let target = grid
+            .zones()
+            .filter(|z| z.kind == ZoneKind::Prompt && z.start < viewport_top)
+            .last();
+        if let Some(zone) = target {
+            grid.display_offset = sb_len - zone.start;
+            self.set_grid_dirty(true);
+        }
+    }
+
+    /// Navigate to the next shell prompt (OSC 133 marker) below the viewport.
+    pub fn navigate_to_next_prompt(&self) {
+        let grid = &mut *self.grid();
+        let sb_len = grid.scrollback.len();
+        // Current bottom of viewport as absolute row.
+        let viewport_bottom = sb_len.saturating_sub(grid.display_offset) + grid.lines;
+        let target = grid
+            .zones()
+            .find(|z| z.kind == ZoneKind::Prompt && z.start >= viewport_bottom);
+        // A prompt on the live screen, or none below: scroll to live.
+        grid.display_offset = target.map_or(0, |zone| sb_len.saturating_sub(zone.start));
+        self.set_grid_dirty(true);
+    }
+}
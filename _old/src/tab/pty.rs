@@ -0,0 +1,206 @@
+//! PTY background threads — output reader and (Windows) child waiter.
+
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+
+use super::paste::{PasteSlot, queue_behind};
+use super::stats::PtyStats;
+use super::{EventSender, PtyWriter, TabId, TermEvent, TerminalState};
+use crate::log;
+use crate::sync::FairMutex;
+
+/// Maximum read buffer before forcing a lock. 1 MB.
+const READ_BUFFER_SIZE: usize = 0x10_0000;
+/// Maximum bytes to parse per lock hold. Limits how long the reader thread
+/// blocks the main thread. Larger = higher throughput, smaller = lower latency.
+const MAX_LOCKED_PARSE: usize = 0x1_0000; // 64 KB
+
+/// State the reader thread shares with its [`Tab`](super::Tab).
+pub(super) struct ReaderShared {
+    pub terminal: Arc<FairMutex<TerminalState>>,
+    pub pty_writer: PtyWriter,
+    pub wakeup_pending: Arc<AtomicBool>,
+    pub mode_cache: Arc<AtomicU32>,
+    pub stats: Arc<PtyStats>,
+    /// Set when the tab shuts down.
+    pub closing: Arc<AtomicBool>,
+    /// Replies wait behind a paste in flight.
+    pub paste: PasteSlot,
+}
+
+/// Spawn the PTY reader thread that parses VTE output under the terminal lock.
+///
+/// The reader thread shares the PTY writer (for VTE responses like DA,
+/// DECRPM). The main thread writes keyboard/mouse input through the same
+/// shared writer. No channel needed — both threads write directly.
+///
+/// Once the tab is closing, output is read and discarded until EOF: on
+/// Windows `ClosePseudoConsole` waits for the output pipe to drain.
+pub(super) fn spawn_reader_thread(
+    id: TabId,
+    mut reader: Box<dyn Read + Send>,
+    shared: ReaderShared,
+    events: EventSender,
+) -> JoinHandle<()> {
+    let ReaderShared {
+        terminal,
+        pty_writer,
+        wakeup_pending,
+        mode_cache,
+        stats,
+        closing,
+        paste,
+    } = shared;
+    thread::spawn(move || {
+        log(&format!("reader thread started for tab {:?}", id));
+        let mut buf = vec![0u8; READ_BUFFER_SIZE];
+        let mut pty_responses: Vec<u8> = Vec::new();
+        // Track unprocessed data as buf[start..end] to avoid shifting
+        // after every chunk. Only compact when the tail reaches the end.
+        let mut start = 0;
+        let mut end = 0;
+
+        loop {
+            // Read without holding a lease — the read may block
+            // indefinitely (idle shell), and the main thread must be
+            // able to lock the terminal for rendering in the meantime.
+            if start == end {
+                start = 0;
+                let closed = match reader.read(&mut buf) {
+                    Ok(0) => {
+                        log(&format!("reader: eof for tab {:?}", id));
+                        true
+                    }
+                    Err(e) => {
+                        log(&format!("reader error for tab {:?}: {e}", id));
+                        true
+                    }
+                    Ok(_) if closing.load(Ordering::Relaxed) => continue,
+                    Ok(n) => {
+                        stats.record_read(n, Instant::now());
+                        end = n;
+                        false
+                    }
+                };
+                if closed {
+                    if !closing.load(Ordering::Relaxed) {
+                        events.send(TermEvent::PtyExited(id));
+                    }
+                    break;
+                }
+            }
+
+            // Data is available — acquire a lease so the renderer yields
+            // to us, then try the lock. The lease is only held during the
+            // lock attempt, never across a blocking read.
+            let mut term = {
+                let lease = terminal.lease();
+                if let Some(t) = terminal.try_lock_unfair() {
+                    t
+                    // lease dropped here
+                } else {
+                    drop(lease);
+                    // Renderer holds the lock — yield to let it finish,
+                    // then wait for the data lock. We must not block on
+                    // a pipe read while unprocessed bytes remain: those
+                    // bytes may contain a DA/DSR query whose response
+                    // ConPTY awaits before sending more output, which
+                    // would deadlock (reader ← pipe ← ConPTY ← response
+                    // in unprocessed buf).
+                    thread::yield_now();
+                    terminal.lock_unfair()
+                }
+            };
+
+            // Parse in bounded chunks to limit lock hold time.
+            // This lets the main thread acquire the lock between
+            // chunks for rendering, keybindings, etc.
+            let chunk = (end - start).min(MAX_LOCKED_PARSE);
+            pty_responses.clear();
+            let parse_start = Instant::now();
+            term.process_output(&buf[start..start + chunk], &mut pty_responses);
+            stats.record_parse(parse_start.elapsed());
+            mode_cache.store(term.mode.bits(), Ordering::Relaxed);
+            let progress = term.take_progress();
+            drop(term);
+            start += chunk;
+
+            if let Some(progress) = progress {
+                events.send(TermEvent::Progress(id, progress));
+            }
+
+            // Flush VTE responses (DA, DECRPM, etc.) outside the terminal
+            // lock. Writing to the ConPTY input pipe while holding the lock
+            // can deadlock: ConPTY may be blocked writing to its output pipe
+            // (waiting for us to read), so it can't read our responses.
+            // Behind a paste in flight they wait, like typed input.
+            if !pty_responses.is_empty() && !queue_behind(&paste, &pty_responses) {
+                let mut w = pty_writer.lock();
+                let _ = w.write_all(&pty_responses);
+                let _ = w.flush();
+            }
+
+            // Only send Wakeup if one isn't already pending.
+            if !wakeup_pending.swap(true, Ordering::Relaxed) {
+                events.send(TermEvent::Wakeup(id));
+            }
+        }
+    })
+}
+
+/// Spawn a thread that waits for the child process to exit.
+///
+/// Windows/ConPTY does not reliably deliver EOF to the reader when the child
+/// exits, so we wait on the process handle directly (like Alacritty, `WezTerm`,
+/// and Ghostty all do).
+#[cfg(target_os = "windows")]
+pub(super) fn spawn_child_waiter(
+    id: TabId,
+    child: &dyn portable_pty::Child,
+    events: EventSender,
+) -> Option<JoinHandle<()>> {
+    child.process_id().map(|pid| {
+        thread::spawn(move || {
+            use windows_sys::Win32::Foundation::CloseHandle;
+            use windows_sys::Win32::System::Threading::{INFINITE, OpenProcess, WaitForSingleObject};
+            const SYNCHRONIZE: u32 = 0x0010_0000;
+
+            log(&format!(
+                "child waiter thread started for tab {:?} (pid {})",
+                id, pid
+            ));
+            #[allow(unsafe_code)]
+            let handle = unsafe { OpenProcess(SYNCHRONIZE, 0, pid) };
+            if !handle.is_null() {
+                #[allow(unsafe_code)]
+                unsafe {
+                    WaitForSingleObject(handle, INFINITE)
+                };
+                #[allow(unsafe_code)]
+                unsafe {
+                    CloseHandle(handle)
+                };
+                log(&format!("child exited (waiter) for tab {:?}", id));
+                events.send(TermEvent::PtyExited(id));
+            }
+        })
+    })
+}
+
+/// Derive a good initial title. For WSL, use the distro name.
+pub(super) fn derive_initial_title(id: TabId, shell_args: &[&str], is_wsl: bool) -> String {
+    if is_wsl {
+        shell_args
+            .iter()
+            .zip(shell_args.iter().skip(1))
+            .find(|&(&flag, _)| flag == "-d" || flag == "--distribution")
+            .map_or_else(|| "WSL".to_owned(), |(_, &name)| name.to_owned())
+    } else {
+        format!("Tab {}", id.0)
+    }
+}
+
@@ -0,0 +1,88 @@
+//! Grid and PTY resize with coalesced `SIGWINCH` delivery.
+//!
+//! Grids are resized immediately so the renderer always matches the window,
+//! but the PTY is only told about the new size once the resize gesture has
+//! settled. Full-screen apps then repaint once instead of once per pixel step.
+
+use std::time::{Duration, Instant};
+
+use super::Tab;
+use crate::grid::{ClipEdge, EmojiPresentation};
+use crate::selection::Selection;
+use crate::term_mode::TermMode;
+
+/// How long the window size must stay unchanged before the PTY is notified.
+const PTY_RESIZE_SETTLE: Duration = Duration::from_millis(50);
+
+/// A PTY size change that has not been sent yet.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct PendingPtyResize {
+    size: portable_pty::PtySize,
+    deadline: Instant,
+}
+
+impl Tab {
+    /// Resize grids to the given dimensions and schedule a PTY resize.
+    ///
+    /// The PTY notification is deferred until [`Tab::flush_pty_resize`] is
+    /// called after the gesture settles.
+    pub fn resize(&mut self, cols: usize, rows: usize, pixel_width: u16, pixel_height: u16) {
+        {
+            let mut term = self.terminal.lock();
+            if term.active_is_alt {
+                // The app repaints the alt screen; its text can't be followed.
+                self.selection = None;
+            }
+            // The selection rides the reflow so the same text stays selected.
+            let mut points: Vec<_> = self.selection.iter().flat_map(Selection::positions).collect();
+            let emoji = if term.mode.contains(TermMode::GRAPHEME_CLUSTERS) {
+                EmojiPresentation::Wide
+            } else {
+                EmojiPresentation::Narrow
+            };
+            term.primary_grid.reflow_with_points(cols, rows, emoji, &mut points);
+            if let (Some(sel), Ok(points)) = (&mut self.selection, points.try_into()) {
+                sel.set_positions(points);
+            }
+            // Alt screen never reflows (full-screen apps redraw themselves).
+            term.alt_grid.resize_clipped(cols, rows, self.alt_screen_clip);
+            if cols > 0 && rows > 0 {
+                term.cell_size = (pixel_width / cols as u16, pixel_height / rows as u16);
+            }
+        }
+        self.pending_pty_resize = Some(PendingPtyResize {
+            size: portable_pty::PtySize {
+                rows: rows as u16,
+                cols: cols as u16,
+                pixel_width,
+                pixel_height,
+            },
+            deadline: Instant::now() + PTY_RESIZE_SETTLE,
+        });
+    }
+
+    /// Send the pending PTY resize if its settle deadline has passed.
+    ///
+    /// Returns the deadline of a resize that is still waiting, so the event
+    /// loop can schedule a wake-up for it. A size equal to the one last
+    /// reported, in cells and pixels, is dropped without signalling the
+    /// child.
+    pub fn flush_pty_resize(&mut self, now: Instant) -> Option<Instant> {
+        let pending = self.pending_pty_resize?;
+        if now < pending.deadline {
+            return Some(pending.deadline);
+        }
+        self.pending_pty_resize = None;
+
+        if pending.size != self.pty_size {
+            self.pty_size = pending.size;
+            let _ = self.pty_master.resize(pending.size);
+        }
+        None
+    }
+
+    /// Set which alt screen edge is clipped on vertical shrink.
+    pub fn set_alt_screen_clip(&mut self, clip: ClipEdge) {
+        self.alt_screen_clip = clip;
+    }
+}
@@ -0,0 +1,74 @@
+//! The tab's search session: opening, closing, and keeping its matches in
+//! step with the query and with new output.
+
+use std::sync::Arc;
+
+use super::Tab;
+use super::types::TermEvent;
+use crate::search::{SearchBatch, SearchState};
+
+/// Queries over more rows than this are searched in the background.
+const BACKGROUND_ROWS: usize = 10_000;
+
+impl Tab {
+    /// Open a new search session, replacing any existing one.
+    pub fn open_search(&mut self) {
+        self.search = Some(SearchState::new());
+        self.set_grid_dirty(true);
+    }
+
+    /// Close the active search session.
+    pub fn close_search(&mut self) {
+        self.search = None;
+        self.set_grid_dirty(true);
+    }
+
+    /// Update the search query. Long scrollback is searched in the
+    /// background, its matches arriving as [`TermEvent::Search`].
+    pub fn update_search_query(&mut self) {
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+        let term = self.terminal.lock();
+        search.on_alt_screen = term.active_is_alt;
+        let grid = term.active_grid();
+        if grid.scrollback.len() + grid.lines > BACKGROUND_ROWS {
+            drop(term);
+            let (id, events) = (self.id, Arc::clone(&self.events));
+            search.update_query_in_background(&self.terminal, move |batch| {
+                events.send(TermEvent::Search(id, batch));
+            });
+        } else {
+            search.update_query(grid);
+            drop(term);
+        }
+        self.set_grid_dirty(true);
+    }
+
+    /// Take in matches from the background search. Returns whether the
+    /// focused match moved, so the view can follow it.
+    pub fn apply_search_batch(&mut self, batch: SearchBatch) -> bool {
+        let Some(search) = self.search.as_mut() else {
+            return false;
+        };
+        let moved = search.apply_batch(batch, self.terminal.lock().active_grid());
+        self.set_grid_dirty(true);
+        moved
+    }
+
+    /// Search the rows new output may have changed. Returns whether the
+    /// focused match moved, so the view can follow it.
+    pub fn refresh_search(&mut self) -> bool {
+        let Some(search) = self.search.as_mut() else {
+            return false;
+        };
+        let term = self.terminal.lock();
+        if search.on_alt_screen != term.active_is_alt {
+            // The other screen's rows: search it all, and leave the view be.
+            search.on_alt_screen = term.active_is_alt;
+            search.update_query(term.active_grid());
+            return false;
+        }
+        search.refresh(term.active_grid())
+    }
+}
@@ -0,0 +1,129 @@
+//! Names for the escape sequences the terminal understands.
+//!
+//! Mirrors what `vte::ansi::Processor`, the raw interceptor, and the
+//! deferred-sequence splitter act on. A sequence without a name here is
+//! dropped. The table only looks at the final byte, intermediates, and (for
+//! OSC) the command number, so a known sequence with unsupported parameters
+//! still counts as handled.
+
+/// Name of a C0 control, or `None` if it does nothing.
+pub(super) fn control_name(byte: u8) -> Option<&'static str> {
+    Some(match byte {
+        0x05 => "ENQ",
+        0x07 => "BEL",
+        0x08 => "BS",
+        0x09 => "HT",
+        0x0A => "LF",
+        0x0B => "VT",
+        0x0C => "FF",
+        0x0D => "CR",
+        0x0E => "SO",
+        0x0F => "SI",
+        0x1A => "SUB",
+        _ => return None,
+    })
+}
+
+/// Name of `ESC <intermediates> <byte>`, or `None` if it is ignored.
+pub(super) fn esc_name(intermediates: &[u8], byte: u8) -> Option<&'static str> {
+    Some(match (byte, intermediates) {
+        (b'B' | b'0', [b'(' | b')' | b'*' | b'+']) => "SCS",
+        (b'D', []) => "IND",
+        (b'E', []) => "NEL",
+        (b'H', []) => "HTS",
+        (b'M', []) => "RI",
+        (b'Z', []) => "DECID",
+        (b'c', []) => "RIS",
+        (b'7', []) => "DECSC",
+        (b'8', [b'#']) => "DECALN",
+        (b'8', []) => "DECRC",
+        (b'=', []) => "DECKPAM",
+        (b'>', []) => "DECKPNM",
+        (b'\\', []) => "ST",
+        _ => return None,
+    })
+}
+
+/// Name of `CSI <intermediates> <action>`, or `None` if it is ignored.
+///
+/// Private markers (`?`, `>`, `<`, `=`) arrive as intermediates.
+pub(super) fn csi_name(intermediates: &[u8], action: char) -> Option<&'static str> {
+    Some(match (action, intermediates) {
+        ('@', []) => "ICH",
+        ('A', []) => "CUU",
+        ('B', []) => "CUD",
+        ('e', []) => "VPR",
+        ('b', []) => "REP",
+        ('C', []) => "CUF",
+        ('a', []) => "HPR",
+        ('c', []) => "DA1",
+        ('c', [b'>']) => "DA2",
+        ('D', []) => "CUB",
+        ('d', []) => "VPA",
+        ('E', []) => "CNL",
+        ('F', []) => "CPL",
+        ('G', []) => "CHA",
+        ('`', []) => "HPA",
+        ('W', [b'?']) => "DECST8C",
+        ('g', []) => "TBC",
+        ('H', []) => "CUP",
+        ('f', []) => "HVP",
+        ('h', []) => "SM",
+        ('h', [b'?']) => "DECSET",
+        ('I', []) => "CHT",
+        ('J', []) => "ED",
+        ('K', []) => "EL",
+        ('k', [b' ']) => "SCP",
+        ('L', []) => "IL",
+        ('l', []) => "RM",
+        ('l', [b'?']) => "DECRST",
+        ('M', []) => "DL",
+        ('m', []) => "SGR",
+        ('m', [b'>']) => "XTMODKEYS",
+        ('m', [b'?']) => "XTQMODKEYS",
+        ('n', []) => "DSR",
+        ('n', [b'?']) => "DECXCPR",
+        ('P', []) => "DCH",
+        ('p', [b'$'] | [b'?', b'$']) => "DECRQM",
+        ('p', [b'!']) => "DECSTR",
+        ('q', [b' ']) => "DECSCUSR",
+        ('q', [b'>']) => "XTVERSION",
+        ('r', []) => "DECSTBM",
+        ('S', []) => "SU",
+        ('s', []) => "SCOSC",
+        ('T', []) => "SD",
+        ('t', []) => "XTWINOPS",
+        ('u', [b'?']) => "kitty keyboard query",
+        ('u', [b'=']) => "kitty keyboard set",
+        ('u', [b'>']) => "kitty keyboard push",
+        ('u', [b'<']) => "kitty keyboard pop",
+        ('u', []) => "SCORC",
+        ('X', []) => "ECH",
+        ('Z', []) => "CBT",
+        _ => return None,
+    })
+}
+
+/// Name of an OSC command from its first parameter, or `None` if it is
+/// ignored.
+pub(super) fn osc_name(command: &[u8]) -> Option<&'static str> {
+    Some(match command {
+        b"0" | b"2" => "set title",
+        b"4" => "set palette color",
+        b"6" => "tab color",
+        b"7" => "working directory",
+        b"8" => "hyperlink",
+        b"9" => "notification",
+        b"10" | b"11" | b"12" => "dynamic color",
+        b"22" => "pointer shape",
+        b"50" => "cursor shape",
+        b"52" => "clipboard",
+        b"66" => "text sizing",
+        b"99" => "kitty notification",
+        b"104" => "reset palette color",
+        b"110" | b"111" | b"112" => "reset dynamic color",
+        b"133" => "prompt mark",
+        b"777" => "rxvt notification",
+        _ => return None,
+    })
+}
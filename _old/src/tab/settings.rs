@@ -0,0 +1,38 @@
+//! Config settings applied to a running tab on reload: colors, cursor
+//! shape, the ENQ answerback, and C1 control handling.
+
+use vte::ansi::CursorShape;
+
+use crate::config::ColorConfig;
+use crate::palette::ColorScheme;
+
+use super::Tab;
+
+impl Tab {
+    /// Apply color scheme, overrides, and bold-is-bright in one call.
+    pub fn apply_color_config(
+        &self,
+        scheme: Option<&ColorScheme>,
+        colors: &ColorConfig,
+        bold_is_bright: bool,
+    ) {
+        self.terminal
+            .lock()
+            .apply_color_config(scheme, colors, bold_is_bright);
+    }
+
+    /// Set the string sent in reply to ENQ.
+    pub fn set_answerback(&self, answerback: &str) {
+        answerback.clone_into(&mut self.terminal.lock().answerback);
+    }
+
+    /// Set whether raw 8-bit C1 bytes in output are read as controls.
+    pub fn set_c1_controls(&self, enabled: bool) {
+        self.terminal.lock().c1_controls = enabled;
+    }
+
+    /// Set the cursor shape (block, underline, bar).
+    pub fn set_cursor_shape(&self, shape: CursorShape) {
+        self.terminal.lock().cursor_shape = shape;
+    }
+}
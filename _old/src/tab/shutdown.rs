@@ -0,0 +1,77 @@
+//! Orderly tab shutdown: kill the child, close the PTY, join the threads.
+//!
+//! On Windows dropping the PTY master calls `ClosePseudoConsole`, which
+//! blocks until the reader thread has drained the output pipe, and can
+//! block indefinitely if a child process holds the console open. Closing
+//! therefore runs off the event loop thread, and [`shutdown_tabs`] waits
+//! for it with a timeout instead of forever.
+
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use super::Tab;
+use crate::log;
+
+/// How long app exit waits for PTYs to close and threads to finish.
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+impl Tab {
+    /// Kill the child process and tell the reader thread to discard any
+    /// further output. Call before [`Tab::close`].
+    pub fn shutdown(&mut self) {
+        self.closing.store(true, Ordering::Relaxed);
+        if let Some(paste) = &*self.paste.lock() {
+            paste.cancel();
+        }
+        let _ = self.child.kill();
+    }
+
+    /// Close the PTY and wait for the reader (and, on Windows, the child
+    /// waiter) to exit. Blocks; never call it on the event loop thread.
+    pub fn close(self) {
+        let id = self.id;
+        let Self {
+            pty_master,
+            threads,
+            paste,
+            ..
+        } = self;
+        drop(pty_master);
+        let paste = paste.lock().take();
+        if let Some(paste) = paste {
+            paste.join();
+        }
+        for handle in threads {
+            if handle.join().is_err() {
+                log(&format!("shutdown: a thread of tab {id:?} panicked"));
+            }
+        }
+    }
+}
+
+/// Shut down and close every tab in `tabs`, waiting up to `timeout`.
+///
+/// Closing runs on a worker thread. Returns `false` if it was still blocked
+/// when the time ran out; it is then left to die with the process.
+pub fn shutdown_tabs(mut tabs: Vec<Tab>, timeout: Duration) -> bool {
+    for tab in &mut tabs {
+        tab.shutdown();
+    }
+    let count = tabs.len();
+    let (done_tx, done_rx) = mpsc::channel();
+    thread::spawn(move || {
+        for tab in tabs {
+            tab.close();
+        }
+        let _ = done_tx.send(());
+    });
+    let finished = done_rx.recv_timeout(timeout).is_ok();
+    if !finished {
+        log(&format!(
+            "shutdown: {count} tab(s) still closing after {timeout:?}, exiting anyway"
+        ));
+    }
+    finished
+}
@@ -0,0 +1,160 @@
+//! Per-tab PTY throughput, parse time, and input-to-echo latency.
+//!
+//! The reader thread records every read and parse; the main thread marks
+//! when it sends keyboard input. Echo latency is the time from the oldest
+//! unanswered keystroke to the next PTY read, so a slow remote session shows
+//! up as high latency with a low parse load, while a slow parser shows up as
+//! a high parse load regardless of latency.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+/// Length of the window that throughput and parse load are averaged over.
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Weight of the newest sample in the smoothed echo latency (1/8).
+const LATENCY_SMOOTHING: u32 = 8;
+
+/// Thread-shared PTY statistics for one tab.
+#[derive(Debug)]
+pub(crate) struct PtyStats {
+    inner: Mutex<Counters>,
+}
+
+#[derive(Debug)]
+struct Counters {
+    total_bytes: u64,
+    window_start: Instant,
+    window_bytes: u64,
+    window_parse: Duration,
+    bytes_per_sec: f64,
+    parse_load: f64,
+    /// When the oldest keystroke not yet answered by output was sent.
+    input_sent: Option<Instant>,
+    /// A keystroke was answered by a read that isn't parsed yet.
+    echo_read: bool,
+    /// A keystroke's answer is parsed and not yet taken by the main thread.
+    echo_parsed: bool,
+    echo_latency: Option<Duration>,
+    echo_latency_max: Duration,
+}
+
+impl Counters {
+    /// Close the rate window once it is at least [`RATE_WINDOW`] old.
+    fn roll(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.window_start);
+        if elapsed < RATE_WINDOW {
+            return;
+        }
+        let secs = elapsed.as_secs_f64();
+        self.bytes_per_sec = self.window_bytes as f64 / secs;
+        self.parse_load = self.window_parse.as_secs_f64() / secs;
+        self.window_start = now;
+        self.window_bytes = 0;
+        self.window_parse = Duration::ZERO;
+    }
+}
+
+/// Point-in-time view of [`PtyStats`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PtyStatsSnapshot {
+    /// Bytes read from the PTY since the tab opened.
+    pub total_bytes: u64,
+    /// Read throughput over the last rate window.
+    pub bytes_per_sec: f64,
+    /// Fraction of wall time spent parsing over the last rate window.
+    pub parse_load: f64,
+    /// Smoothed input-to-echo latency, once any keystroke was echoed.
+    pub echo_latency: Option<Duration>,
+    /// Worst input-to-echo latency seen.
+    pub echo_latency_max: Duration,
+}
+
+impl PtyStats {
+    pub(crate) fn new(now: Instant) -> Self {
+        Self {
+            inner: Mutex::new(Counters {
+                total_bytes: 0,
+                window_start: now,
+                window_bytes: 0,
+                window_parse: Duration::ZERO,
+                bytes_per_sec: 0.0,
+                parse_load: 0.0,
+                input_sent: None,
+                echo_read: false,
+                echo_parsed: false,
+                echo_latency: None,
+                echo_latency_max: Duration::ZERO,
+            }),
+        }
+    }
+
+    /// Record `bytes` read from the PTY at `now`. Answers a pending keystroke.
+    pub(crate) fn record_read(&self, bytes: usize, now: Instant) {
+        let mut c = self.inner.lock();
+        c.roll(now);
+        c.total_bytes += bytes as u64;
+        c.window_bytes += bytes as u64;
+        if let Some(sent) = c.input_sent.take() {
+            c.echo_read = true;
+            let sample = now.saturating_duration_since(sent);
+            c.echo_latency = Some(c.echo_latency.map_or(sample, |avg| {
+                (avg * (LATENCY_SMOOTHING - 1) + sample) / LATENCY_SMOOTHING
+            }));
+            c.echo_latency_max = c.echo_latency_max.max(sample);
+        }
+    }
+
+    /// Record time spent parsing one chunk of output.
+    pub(crate) fn record_parse(&self, elapsed: Duration) {
+        let mut c = self.inner.lock();
+        c.window_parse += elapsed;
+        c.echo_parsed |= std::mem::take(&mut c.echo_read);
+    }
+
+    /// Whether output answering a keystroke was parsed since the last call.
+    pub(crate) fn take_echo(&self) -> bool {
+        std::mem::take(&mut self.inner.lock().echo_parsed)
+    }
+
+    /// Mark keyboard input sent at `now`. Only the oldest unanswered
+    /// keystroke is timed.
+    pub(crate) fn mark_input(&self, now: Instant) {
+        self.inner.lock().input_sent.get_or_insert(now);
+    }
+
+    /// Current statistics as of `now`.
+    pub(crate) fn snapshot(&self, now: Instant) -> PtyStatsSnapshot {
+        let mut c = self.inner.lock();
+        c.roll(now);
+        PtyStatsSnapshot {
+            total_bytes: c.total_bytes,
+            bytes_per_sec: c.bytes_per_sec,
+            parse_load: c.parse_load,
+            echo_latency: c.echo_latency,
+            echo_latency_max: c.echo_latency_max,
+        }
+    }
+}
+
+impl fmt::Display for PtyStatsSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.1} KB/s, parse {:.1}%",
+            self.bytes_per_sec / 1024.0,
+            self.parse_load * 100.0
+        )?;
+        match self.echo_latency {
+            Some(avg) => write!(
+                f,
+                ", echo {:.1}ms (max {:.1}ms)",
+                avg.as_secs_f64() * 1000.0,
+                self.echo_latency_max.as_secs_f64() * 1000.0
+            ),
+            None => write!(f, ", echo n/a"),
+        }
+    }
+}
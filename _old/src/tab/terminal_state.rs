@@ -4,19 +4,32 @@
 //! main/UI thread need access to. It is wrapped in `Arc<parking_lot::Mutex<>>`
 //! inside `Tab`.
 
+use std::borrow::Cow;
+use std::collections::VecDeque;
 use std::path::Path;
 use std::time::Instant;
 
-use vte::ansi::{CursorShape, KeyboardModes};
+use vte::ansi::{CursorShape, KeyboardModes, Rgb};
 
 use crate::config::ColorConfig;
-use crate::grid::Grid;
+use crate::grid::{Grid, TextFormat};
+use crate::hyperlink::LinkTable;
 use crate::palette::{ColorScheme, Palette};
 use crate::term_handler::{GraphemeState, TermHandler};
 use crate::term_mode::TermMode;
 
-use super::interceptor::RawInterceptor;
-use super::types::{CharsetState, Notification, PromptState};
+use super::c1::C1Translator;
+use super::inspector::{SequenceLog, Tee};
+use super::interceptor::{Deferred, RawInterceptor};
+use super::notify::Osc99Assembler;
+use super::types::{CharsetState, NotificationEvent, PromptState, TaskProgress};
+use super::unknown_seq::UnknownSequences;
+
+/// Cell size assumed until the first resize reports the real one.
+const DEFAULT_CELL_SIZE: (u16, u16) = (8, 16);
+
+/// BSU: begins a synchronized update (`CSI ? 2026 h`).
+const BEGIN_SYNC: &[u8] = b"\x1b[?2026h";
 
 /// Terminal state shared between the PTY reader thread and the main thread.
 ///
@@ -32,6 +45,7 @@ pub struct TerminalState {
     // VTE parsers (PTY thread uses these; they live with grid state)
     processor: vte::ansi::Processor,
     raw_parser: vte::Parser,
+    c1: C1Translator,
     grapheme_state: GraphemeState,
 
     // Terminal state that VTE parsing mutates AND rendering reads
@@ -40,7 +54,7 @@ pub struct TerminalState {
     pub cursor_shape: CursorShape,
     pub charset: CharsetState,
     pub title: String,
-    pub title_stack: Vec<String>,
+    pub title_stack: VecDeque<String>,
     pub has_explicit_title: bool,
     pub suppress_title: bool,
     pub keyboard_mode_stack: Vec<KeyboardModes>,
@@ -48,15 +62,44 @@ pub struct TerminalState {
 
     // Bell state (PTY sets, renderer reads)
     pub bell_start: Option<Instant>,
+    /// `bell_start` as of the last `take_new_bell`.
+    bell_seen: Option<Instant>,
 
     // Shell integration (PTY sets, main thread reads)
     pub cwd: Option<String>,
+    /// Hostname from the latest OSC 7, if it named one.
+    pub host: Option<String>,
     pub prompt_state: PromptState,
-    pub pending_notifications: Vec<Notification>,
-    prompt_mark_pending: bool,
+    pub pending_notifications: Vec<NotificationEvent>,
+    /// Latest OSC 9;4 progress not yet sent to the main thread.
+    pending_progress: Option<TaskProgress>,
+    /// Latest OSC 52 clipboard write, waiting for the main thread's policy.
+    pub clipboard_request: Option<String>,
+    osc99: Osc99Assembler,
+
+    /// Tab color set by the program with OSC 6 (iTerm2).
+    pub tab_color: Option<Rgb>,
 
     /// Set by VTE parsing when title or CWD changes; cleared by main thread.
     pub title_dirty: bool,
+
+    /// Reply sent in response to ENQ (`config.terminal.answerback`).
+    pub answerback: String,
+
+    /// Whether raw 8-bit C1 bytes in output are read as controls
+    /// (`config.terminal.c1_controls`).
+    pub c1_controls: bool,
+
+    /// OSC 8 links interned by id, shared by the cells that carry them.
+    links: LinkTable,
+
+    /// Cell size in pixels (width, height), for pixel cursor reports.
+    pub cell_size: (u16, u16),
+
+    /// Sequences recorded while the inspector is open.
+    pub inspector: Option<SequenceLog>,
+    /// Ignored CSI, OSC, and DCS sequences seen so far.
+    pub unknown_sequences: UnknownSequences,
 }
 
 impl TerminalState {
@@ -75,23 +118,35 @@ impl TerminalState {
             active_is_alt: false,
             processor: vte::ansi::Processor::new(),
             raw_parser: vte::Parser::new(),
+            c1: C1Translator::default(),
             grapheme_state: GraphemeState::default(),
             palette: Palette::new(),
             mode: TermMode::default(),
             cursor_shape,
             charset: CharsetState::default(),
             title: initial_title,
-            title_stack: Vec::new(),
+            title_stack: VecDeque::new(),
             has_explicit_title: false,
             suppress_title,
             keyboard_mode_stack: Vec::new(),
             inactive_keyboard_mode_stack: Vec::new(),
             bell_start: None,
+            bell_seen: None,
             cwd: None,
+            host: None,
             prompt_state: PromptState::default(),
             pending_notifications: Vec::new(),
-            prompt_mark_pending: false,
+            pending_progress: None,
+            clipboard_request: None,
+            osc99: Osc99Assembler::default(),
+            tab_color: None,
             title_dirty: false,
+            answerback: String::new(),
+            c1_controls: false,
+            links: LinkTable::default(),
+            cell_size: DEFAULT_CELL_SIZE,
+            inspector: None,
+            unknown_sequences: UnknownSequences::default(),
         }
     }
 
@@ -119,22 +174,96 @@ impl TerminalState {
     /// rather than written to the pipe directly. The caller must flush
     /// the buffer to the PTY **after** dropping the terminal lock to
     /// avoid a bidirectional `ConPTY` pipe deadlock.
+    ///
+    /// The interceptor stops after each sequence with a [`Deferred`]
+    /// effect; the processor is fed the same bytes and the effect applied
+    /// before parsing resumes, so it lands in stream order even when the
+    /// sequence spans two reads.
+    ///
+    /// With [`Self::c1_controls`] on, raw 8-bit C1 controls are rewritten
+    /// to their 7-bit forms first.
     pub fn process_output(&mut self, data: &[u8], pty_responses: &mut Vec<u8>) {
-        // Run the raw interceptor first to capture OSC 7/133/9/99/777/XTVERSION
-        // (sequences that vte::ansi::Processor silently drops).
+        let data = if self.c1_controls {
+            self.c1.translate(data)
+        } else {
+            Cow::Borrowed(data)
+        };
+        let mut rest = &*data;
+        while !rest.is_empty() {
+            let (len, deferred) = self.intercept(rest, pty_responses);
+            let (segment, tail) = rest.split_at(len);
+            self.with_handler(pty_responses, |processor, handler| {
+                processor.advance(handler, segment);
+            });
+            if let Some(deferred) = deferred {
+                self.apply_deferred(deferred, pty_responses);
+            }
+            rest = tail;
+        }
+    }
+
+    /// Run the raw interceptor over `data` until it finishes a sequence
+    /// with a deferred effect, returning the bytes it consumed and the
+    /// effect. It captures OSC 7/133/9/99/777/XTVERSION and the rest of
+    /// what `vte::ansi::Processor` silently drops.
+    fn intercept(&mut self, data: &[u8], pty_responses: &mut Vec<u8>) -> (usize, Option<Deferred>) {
         let mut interceptor = RawInterceptor {
             pty_responses,
             cwd: &mut self.cwd,
+            host: &mut self.host,
             prompt_state: &mut self.prompt_state,
             pending_notifications: &mut self.pending_notifications,
-            prompt_mark_pending: &mut self.prompt_mark_pending,
+            progress: &mut self.pending_progress,
+            osc99: &mut self.osc99,
             has_explicit_title: &mut self.has_explicit_title,
             suppress_title: &mut self.suppress_title,
             title_dirty: &mut self.title_dirty,
+            tab_color: &mut self.tab_color,
+            answerback: &self.answerback,
+            deferred: None,
+        };
+        let mut tee = Tee {
+            inner: &mut interceptor,
+            log: self.inspector.as_mut(),
+            unknown: &mut self.unknown_sequences,
         };
-        self.raw_parser.advance(&mut interceptor, data);
+        let len = self.raw_parser.advance_until_terminated(&mut tee, data);
+        (len, interceptor.deferred)
+    }
+
+    /// Apply a deferred effect after the processor has caught up to it.
+    ///
+    /// During a synchronized update (mode 2026) the processor holds output
+    /// back instead of parsing it, so the held bytes are replayed first and
+    /// the update is resumed afterwards.
+    fn apply_deferred(&mut self, deferred: Deferred, pty_responses: &mut Vec<u8>) {
+        let cell_size = self.cell_size;
+        self.with_handler(pty_responses, |processor, handler| {
+            let syncing = processor.sync_bytes_count() > 0;
+            if syncing {
+                processor.stop_sync(handler);
+            }
+            match deferred {
+                Deferred::SoftReset => handler.soft_reset(),
+                Deferred::Overline(on) => handler.set_overline(on),
+                Deferred::ExtendedCursorReport => handler.report_extended_cursor_position(),
+                Deferred::CursorPixelReport => handler.report_cursor_position_pixels(cell_size),
+                Deferred::TextSizing(sizing) => handler.write_sized_text(&sizing),
+                Deferred::RowMark(flag, status) => handler.mark_row(flag, status),
+            }
+            if syncing {
+                processor.advance(handler, BEGIN_SYNC);
+            }
+        });
+    }
 
-        // Then run the normal high-level Processor for everything else.
+    /// Build a `TermHandler` over this state and pass it, together with the
+    /// VTE processor, to `f`.
+    fn with_handler(
+        &mut self,
+        pty_responses: &mut Vec<u8>,
+        f: impl FnOnce(&mut vte::ansi::Processor, &mut TermHandler<'_>),
+    ) {
         let mut handler = TermHandler::new(
             &mut self.primary_grid,
             &mut self.alt_grid,
@@ -153,15 +282,10 @@ impl TerminalState {
             &mut self.has_explicit_title,
             &mut self.suppress_title,
             &mut self.title_dirty,
+            &mut self.links,
+            &mut self.clipboard_request,
         );
-        self.processor.advance(&mut handler, data);
-
-        // Mark the cursor row as a prompt start after both parsers have updated.
-        if self.prompt_mark_pending {
-            self.prompt_mark_pending = false;
-            let row = self.active_grid().cursor.row;
-            self.active_grid_mut().row_mut(row).prompt_start = true;
-        }
+        f(&mut self.processor, &mut handler);
     }
 
     /// Return the display title. If the shell explicitly set a title via
@@ -177,11 +301,28 @@ impl TerminalState {
         self.title.clone()
     }
 
+    /// Rows scrolled by output on either screen since the tab was created.
+    pub fn scrolled_lines(&self) -> u64 {
+        self.primary_grid.scrolled_lines() + self.alt_grid.scrolled_lines()
+    }
+
     /// Drain pending OSC 9/99/777 notifications, returning them to the caller.
-    pub fn drain_notifications(&mut self) -> Vec<Notification> {
+    pub fn drain_notifications(&mut self) -> Vec<NotificationEvent> {
         std::mem::take(&mut self.pending_notifications)
     }
 
+    /// Whether the bell rang since the last call.
+    pub fn take_new_bell(&mut self) -> bool {
+        let rang = self.bell_start != self.bell_seen;
+        self.bell_seen = self.bell_start;
+        rang
+    }
+
+    /// Take the latest OSC 9;4 progress reported since the last call.
+    pub fn take_progress(&mut self) -> Option<TaskProgress> {
+        self.pending_progress.take()
+    }
+
     /// Apply color scheme, overrides, and bold-is-bright in one call.
     pub fn apply_color_config(
         &mut self,
@@ -195,6 +336,36 @@ impl TerminalState {
         self.palette.apply_overrides(colors);
         self.palette.bold_is_bright = bold_is_bright;
     }
+
+    /// Up to `max_lines` lines of the primary screen and its scrollback,
+    /// ending above the cursor's line, as text with SGR escapes and line
+    /// attributes. Saved with the session and replayed by
+    /// [`Self::restore_history`].
+    pub fn history_text(&self, max_lines: usize) -> String {
+        let grid = &self.primary_grid;
+        let cursor = grid.scrollback.len() + grid.cursor.row;
+        if max_lines == 0 || cursor == 0 {
+            return String::new();
+        }
+        grid.serialize_rows(
+            cursor.saturating_sub(max_lines),
+            cursor - 1,
+            TextFormat::Saved,
+        )
+    }
+
+    /// Write `text` from [`Self::history_text`] and scroll all of it into
+    /// the primary screen's scrollback, out of reach of the shell, leaving
+    /// the screen blank. Call before the shell writes anything.
+    pub fn restore_history(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let grid = &mut self.primary_grid;
+        grid.restore_rows(text);
+        grid.scroll_up(grid.cursor.row + 1);
+        grid.goto(0, 0);
+    }
 }
 
 /// Shorten a path for tab bar display: `~` for home, last component otherwise.
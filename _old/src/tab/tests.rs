@@ -0,0 +1,524 @@
+use std::sync::{Arc, mpsc};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use vte::ansi::CursorShape;
+
+use super::focus::FocusState;
+use super::inspector::{SeqKind, SequenceLog};
+use super::paste::{Bracketing, PasteJob, PasteSlot, queue_behind};
+use super::process::title_with_process;
+use super::stats::PtyStats;
+use super::terminal_state::TerminalState;
+use super::types::{Notification, NotificationEvent};
+use super::{
+    EventSender, EventSink, PtyWriter, SpawnConfig, Tab, TabId, TermEvent, shutdown_tabs,
+};
+use crate::grid::{ClipEdge, StableRowIndex};
+use crate::term_mode::TermMode;
+
+#[test]
+fn throughput_and_parse_load_average_over_the_rate_window() {
+    let t0 = Instant::now();
+    let stats = PtyStats::new(t0);
+    stats.record_read(1024, t0);
+    stats.record_read(1024, t0 + Duration::from_millis(500));
+    stats.record_parse(Duration::from_millis(100));
+
+    // The window is still open: nothing averaged yet.
+    let snap = stats.snapshot(t0 + Duration::from_millis(900));
+    assert_eq!(snap.total_bytes, 2048);
+    assert!(snap.bytes_per_sec.abs() < 1e-6);
+
+    let snap = stats.snapshot(t0 + Duration::from_secs(2));
+    assert!((snap.bytes_per_sec - 1024.0).abs() < 1e-6);
+    assert!((snap.parse_load - 0.05).abs() < 1e-6);
+
+    // An idle window drops the rate back to zero but keeps the total.
+    let snap = stats.snapshot(t0 + Duration::from_secs(4));
+    assert!(snap.bytes_per_sec.abs() < 1e-6);
+    assert_eq!(snap.total_bytes, 2048);
+}
+
+#[test]
+fn echo_latency_times_the_oldest_unanswered_keystroke() {
+    let t0 = Instant::now();
+    let stats = PtyStats::new(t0);
+    assert_eq!(stats.snapshot(t0).echo_latency, None);
+
+    stats.mark_input(t0);
+    stats.mark_input(t0 + Duration::from_millis(10));
+    stats.record_read(1, t0 + Duration::from_millis(40));
+    let snap = stats.snapshot(t0 + Duration::from_millis(40));
+    assert_eq!(snap.echo_latency, Some(Duration::from_millis(40)));
+
+    // Output with no keystroke pending doesn't count as an echo.
+    stats.record_read(1, t0 + Duration::from_millis(500));
+    let snap = stats.snapshot(t0 + Duration::from_millis(500));
+    assert_eq!(snap.echo_latency, Some(Duration::from_millis(40)));
+
+    // Later samples are smoothed; the maximum is kept.
+    stats.mark_input(t0 + Duration::from_millis(600));
+    stats.record_read(1, t0 + Duration::from_millis(608));
+    let snap = stats.snapshot(t0 + Duration::from_millis(608));
+    assert_eq!(snap.echo_latency, Some(Duration::from_millis(36)));
+    assert_eq!(snap.echo_latency_max, Duration::from_millis(40));
+}
+
+#[test]
+fn echo_is_reported_once_its_read_is_parsed() {
+    let t0 = Instant::now();
+    let stats = PtyStats::new(t0);
+    stats.record_read(1, t0);
+    stats.record_parse(Duration::ZERO);
+    assert!(!stats.take_echo(), "output without a keystroke isn't an echo");
+
+    stats.mark_input(t0);
+    stats.record_read(1, t0 + Duration::from_millis(2));
+    assert!(!stats.take_echo(), "not parsed yet");
+    stats.record_parse(Duration::ZERO);
+    assert!(stats.take_echo());
+    assert!(!stats.take_echo());
+}
+
+#[test]
+fn snapshot_formats_for_the_stats_log() {
+    let t0 = Instant::now();
+    let stats = PtyStats::new(t0);
+    stats.record_read(2048, t0);
+    assert_eq!(
+        stats.snapshot(t0 + Duration::from_secs(1)).to_string(),
+        "2.0 KB/s, parse 0.0%, echo n/a"
+    );
+}
+
+#[test]
+fn focus_reports_only_changes_while_mode_1004_is_set() {
+    let on = TermMode::FOCUS_IN_OUT;
+    let mut focus = FocusState::default();
+
+    // Focus moves before the application asks for reports: nothing sent.
+    assert_eq!(focus.update(true, TermMode::empty()), None);
+
+    // Alt-tab away and back: one out, one in, never a repeat.
+    assert_eq!(focus.update(false, on), Some(&b"\x1b[O"[..]));
+    assert_eq!(focus.update(false, on), None);
+    assert_eq!(focus.update(true, on), Some(&b"\x1b[I"[..]));
+    assert_eq!(focus.update(true, on), None);
+}
+
+#[test]
+fn tab_switch_moves_focus_between_tabs() {
+    let on = TermMode::FOCUS_IN_OUT;
+    let mut old = FocusState::default();
+    let mut new = FocusState::default();
+    assert_eq!(old.update(true, on), Some(&b"\x1b[I"[..]));
+
+    // Switching tabs takes focus from the old tab and gives it to the new.
+    assert_eq!(old.update(false, on), Some(&b"\x1b[O"[..]));
+    assert_eq!(new.update(true, on), Some(&b"\x1b[I"[..]));
+
+    // The window losing focus only reaches the active tab.
+    assert_eq!(old.update(false, on), None);
+    assert_eq!(new.update(false, on), Some(&b"\x1b[O"[..]));
+}
+
+#[test]
+fn inspector_records_sequences_with_their_status() {
+    let mut term = TerminalState::new(80, 24, 100, CursorShape::Block, String::new(), false);
+    term.inspector = Some(SequenceLog::default());
+    let mut responses = Vec::new();
+    term.process_output(
+        b"hi\x1b[1;31m\r\n\x1b[?2027h\x1b]0;caf\xc3\xa9\x07\x1b[?1016n\x07\x1bP1$qm\x1b\\",
+        &mut responses,
+    );
+
+    let log = term.inspector.as_ref().expect("inspector is on");
+    let lines: Vec<String> = log.filtered("").map(ToString::to_string).collect();
+    assert_eq!(
+        lines,
+        [
+            "SGR        ESC[1;31m",
+            "DECSET     ESC[?2027h",
+            "set title  ESC]0;café",
+            "DECXCPR    ESC[?1016n",
+            "BEL        ^G",
+            "ignored    ESCP1$qm",
+            "ST         ESC\\",
+        ]
+    );
+    let ignored: Vec<_> = log.filtered("IGNORED").map(|r| r.kind).collect();
+    assert_eq!(ignored, [SeqKind::Dcs]);
+    assert_eq!(log.filtered("esc[? h").count(), 1);
+}
+
+#[test]
+fn ignored_sequences_are_counted_without_the_inspector() {
+    let mut term = TerminalState::new(80, 24, 100, CursorShape::Block, String::new(), false);
+    let mut responses = Vec::new();
+    term.process_output(
+        b"\x1b[?5W\x1b[1;2$t\x1b[1;2$t\x1b]1337;File=x\x07\x1bP$qm\x1b\\\x1b[2J\x1b]0;t\x07",
+        &mut responses,
+    );
+
+    let unknown = &term.unknown_sequences;
+    assert!(term.inspector.is_none());
+    assert_eq!(unknown.get("CSI $t"), 2);
+    assert_eq!(unknown.get("OSC 1337"), 1);
+    assert_eq!(unknown.get("DCS $q"), 1);
+    assert_eq!(unknown.get("CSI ?W"), 0);
+    assert_eq!(unknown.to_string(), "CSI $t ×2, DCS $q ×1, OSC 1337 ×1");
+}
+
+#[test]
+fn ed3_clears_only_the_primary_scrollback() {
+    let mut term = TerminalState::new(10, 3, 100, CursorShape::Block, String::new(), false);
+    let mut responses = Vec::new();
+    term.process_output(b"1\r\n2\r\n3\r\n4\r\n5", &mut responses);
+    assert_eq!(term.primary_grid.scrollback.len(), 2);
+    let top = StableRowIndex::from_visible(&term.primary_grid, 0);
+
+    // `clear -x` style: the screen stays, the scrollback goes, even from
+    // the alt screen.
+    term.process_output(b"\x1b[?1049h\x1b[3J\x1b[?1049l", &mut responses);
+    let grid = &term.primary_grid;
+    assert!(grid.scrollback.is_empty());
+    assert_eq!(grid.row(0)[0].c, '3');
+    assert_eq!(StableRowIndex::from_visible(grid, 0), top);
+    assert_eq!(StableRowIndex(0).to_absolute(grid), None);
+}
+
+#[test]
+fn saved_history_restores_into_scrollback() {
+    let mut term = TerminalState::new(10, 3, 100, CursorShape::Block, String::new(), false);
+    let mut responses = Vec::new();
+    term.process_output(b"1\r\n\x1b[31m2\x1b[0m\r\n3\r\n4\r\n$ ", &mut responses);
+    // The prompt line the cursor is on is left out.
+    let history = term.history_text(3);
+    assert_eq!(history, "\x1b[0;31m2\x1b[0m\n3\n4");
+
+    let mut restored = TerminalState::new(10, 3, 100, CursorShape::Block, String::new(), false);
+    restored.restore_history(&history);
+    let grid = &restored.primary_grid;
+    let firsts: Vec<char> = grid.scrollback.iter().map(|row| row[0].c).collect();
+    assert_eq!(firsts, ['2', '3', '4']);
+    assert!(grid.scrollback[0][0].fg != grid.scrollback[1][0].fg);
+    assert_eq!((grid.cursor.row, grid.cursor.col), (0, 0));
+    assert_eq!(grid.row(0)[0].c, ' ');
+    // Saving again keeps the restored history for the next launch.
+    assert_eq!(restored.history_text(3), history);
+}
+
+/// Records whether the tab reported its PTY exiting.
+struct ExitFlag(AtomicBool);
+
+impl EventSink for ExitFlag {
+    fn send(&self, event: TermEvent) {
+        if matches!(event, TermEvent::PtyExited(_)) {
+            self.0.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn shutdown_closes_the_pty_and_joins_the_reader() {
+    let exited = Arc::new(ExitFlag(AtomicBool::new(false)));
+    let tab = Tab::spawn(SpawnConfig {
+        id: TabId(1),
+        cols: 80,
+        rows: 24,
+        events: Arc::clone(&exited) as EventSender,
+        shell: Some("sh".to_owned()),
+        max_scrollback: 100,
+        cursor_shape: CursorShape::Block,
+        integration_dir: None,
+        cwd: None,
+        alt_screen_clip: ClipEdge::default(),
+        answerback: String::new(),
+        c1_controls: false,
+        history: None,
+    })
+    .expect("sh spawns in a PTY");
+
+    assert!(shutdown_tabs(vec![tab], Duration::from_secs(10)));
+    // A deliberate close is not reported as the shell exiting.
+    assert!(!exited.0.load(Ordering::Relaxed));
+}
+
+#[cfg(unix)]
+#[test]
+fn shell_integration_marks_a_running_command() {
+    let mut tab = Tab::spawn(SpawnConfig {
+        id: TabId(1),
+        cols: 80,
+        rows: 24,
+        events: Arc::new(Ignore) as EventSender,
+        shell: Some("sh".to_owned()),
+        max_scrollback: 100,
+        cursor_shape: CursorShape::Block,
+        integration_dir: None,
+        cwd: None,
+        alt_screen_clip: ClipEdge::default(),
+        answerback: String::new(),
+        c1_controls: false,
+        history: None,
+    })
+    .expect("sh spawns in a PTY");
+    let mut responses = Vec::new();
+
+    tab.terminal.lock().process_output(b"\x1b]133;C\x07", &mut responses);
+    assert!(tab.refresh_foreground());
+    assert!(tab.is_busy());
+    assert!(!tab.refresh_foreground());
+
+    tab.terminal.lock().process_output(b"\x1b]133;D;0\x07", &mut responses);
+    assert!(tab.refresh_foreground());
+    assert!(!tab.is_busy());
+    assert!(shutdown_tabs(vec![tab], Duration::from_secs(10)));
+}
+
+#[cfg(unix)]
+#[test]
+fn clear_scrollback_keeps_the_screen() {
+    // `cat` prints nothing unprompted, so only the output fed here lands.
+    let mut tab = Tab::spawn(SpawnConfig {
+        id: TabId(1),
+        cols: 10,
+        rows: 3,
+        events: Arc::new(Ignore) as EventSender,
+        shell: Some("cat".to_owned()),
+        max_scrollback: 100,
+        cursor_shape: CursorShape::Block,
+        integration_dir: None,
+        cwd: None,
+        alt_screen_clip: ClipEdge::default(),
+        answerback: String::new(),
+        c1_controls: false,
+        history: None,
+    })
+    .expect("cat spawns in a PTY");
+    let mut responses = Vec::new();
+    tab.terminal.lock().process_output(b"1\r\n2\r\n3\r\n4\r\n5", &mut responses);
+    assert_eq!(tab.grid().scrollback.len(), 2);
+
+    tab.clear_scrollback();
+    let term = tab.terminal.lock();
+    let grid = &term.primary_grid;
+    assert!(grid.scrollback.is_empty());
+    let screen: Vec<char> = (0..grid.lines).map(|line| grid.row(line)[0].c).collect();
+    assert_eq!(screen, ['3', '4', '5']);
+    assert_eq!((grid.cursor.row, grid.cursor.col), (2, 1));
+    drop(term);
+    assert!(shutdown_tabs(vec![tab], Duration::from_secs(10)));
+}
+
+#[cfg(unix)]
+#[test]
+fn pixel_only_resize_reaches_the_pty() {
+    let mut tab = Tab::spawn(SpawnConfig {
+        id: TabId(1),
+        cols: 10,
+        rows: 3,
+        events: Arc::new(Ignore) as EventSender,
+        shell: Some("cat".to_owned()),
+        max_scrollback: 100,
+        cursor_shape: CursorShape::Block,
+        integration_dir: None,
+        cwd: None,
+        alt_screen_clip: ClipEdge::default(),
+        answerback: String::new(),
+        c1_controls: false,
+        history: None,
+    })
+    .expect("cat spawns in a PTY");
+
+    // Same cell grid, new cell size (e.g. a DPI change).
+    tab.resize(10, 3, 100, 60);
+    assert_eq!(tab.flush_pty_resize(Instant::now() + Duration::from_secs(1)), None);
+    let size = tab.pty_master.get_size().expect("PTY size is readable");
+    assert_eq!((size.cols, size.rows), (10, 3));
+    assert_eq!((size.pixel_width, size.pixel_height), (100, 60));
+    assert!(shutdown_tabs(vec![tab], Duration::from_secs(10)));
+}
+
+#[cfg(unix)]
+#[test]
+fn active_notifications_keep_only_the_newest() {
+    let mut tab = Tab::spawn(SpawnConfig {
+        id: TabId(1),
+        cols: 10,
+        rows: 3,
+        events: Arc::new(Ignore) as EventSender,
+        shell: Some("cat".to_owned()),
+        max_scrollback: 100,
+        cursor_shape: CursorShape::Block,
+        integration_dir: None,
+        cwd: None,
+        alt_screen_clip: ClipEdge::default(),
+        answerback: String::new(),
+        c1_controls: false,
+        history: None,
+    })
+    .expect("cat spawns in a PTY");
+
+    for i in 0..40 {
+        tab.apply_notification_event(NotificationEvent::Show(Notification {
+            id: Some(i.to_string()),
+            ..Notification::default()
+        }));
+    }
+    let ids: Vec<_> = tab
+        .active_notifications
+        .iter()
+        .filter_map(|n| n.id.as_deref())
+        .collect();
+    assert_eq!(ids.len(), 32);
+    assert_eq!((ids[0], ids[31]), ("8", "39"));
+    assert!(shutdown_tabs(vec![tab], Duration::from_secs(10)));
+}
+
+#[cfg(unix)]
+#[test]
+fn output_schedules_one_throttled_foreground_check() {
+    let mut tab = Tab::spawn(SpawnConfig {
+        id: TabId(1),
+        cols: 80,
+        rows: 24,
+        events: Arc::new(Ignore) as EventSender,
+        shell: Some("sh".to_owned()),
+        max_scrollback: 100,
+        cursor_shape: CursorShape::Block,
+        integration_dir: None,
+        cwd: None,
+        alt_screen_clip: ClipEdge::default(),
+        answerback: String::new(),
+        c1_controls: false,
+        history: None,
+    })
+    .expect("sh spawns in a PTY");
+    let mut responses = Vec::new();
+    tab.refresh_foreground();
+    assert_eq!(tab.foreground_due(), None);
+
+    let now = Instant::now();
+    tab.terminal.lock().process_output(b"\x1b]133;C\x07", &mut responses);
+    tab.foreground_may_have_changed(now);
+    tab.foreground_may_have_changed(now + Duration::from_millis(10));
+    let due = tab.foreground_due().expect("output schedules a check");
+    assert!(due > now, "the check waits out the poll interval");
+    assert!(!tab.poll_foreground(now));
+    assert!(!tab.is_busy());
+
+    assert!(tab.poll_foreground(due));
+    assert!(tab.is_busy());
+    assert_eq!(tab.foreground_due(), None);
+    assert!(shutdown_tabs(vec![tab], Duration::from_secs(10)));
+}
+
+#[test]
+fn foreground_process_prefixes_the_title_once() {
+    assert_eq!(title_with_process("vim", "~/project"), "vim \u{2014} ~/project");
+    assert_eq!(title_with_process("vim", "VIM - notes.txt"), "VIM - notes.txt");
+}
+
+#[test]
+fn osc7_records_the_host() {
+    let mut term = TerminalState::new(10, 3, 100, CursorShape::Block, String::new(), false);
+    let mut responses = Vec::new();
+    term.process_output(b"\x1b]7;file://db-prod/var/log\x07", &mut responses);
+    assert_eq!(term.host.as_deref(), Some("db-prod"));
+    assert_eq!(term.cwd.as_deref(), Some("/var/log"));
+    term.process_output(b"\x1b]7;file:///home\x07", &mut responses);
+    assert_eq!(term.host, None);
+}
+
+/// PTY stand-in recording what was written. With a gate, the first write
+/// announces itself and waits to be let through.
+struct Recorder {
+    out: Arc<parking_lot::Mutex<Vec<u8>>>,
+    gate: Option<(mpsc::Sender<()>, mpsc::Receiver<()>)>,
+}
+
+impl std::io::Write for Recorder {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if let Some((entered, release)) = self.gate.take() {
+            let _ = entered.send(());
+            let _ = release.recv();
+        }
+        self.out.lock().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+struct Ignore;
+
+impl EventSink for Ignore {
+    fn send(&self, _event: TermEvent) {}
+}
+
+/// Start pasting 40 000 bytes, bracketed, into a [`Recorder`] and wait
+/// until the first chunk is being written. Returns the job, the sender
+/// that lets the write through, the pasted text, and the output.
+fn gated_paste() -> (PasteJob, mpsc::Sender<()>, String, Arc<parking_lot::Mutex<Vec<u8>>>) {
+    let (entered_tx, entered) = mpsc::channel();
+    let (release, release_rx) = mpsc::channel();
+    let out = Arc::new(parking_lot::Mutex::new(Vec::new()));
+    let recorder = Recorder {
+        out: Arc::clone(&out),
+        gate: Some((entered_tx, release_rx)),
+    };
+    let writer: PtyWriter = Arc::new(parking_lot::Mutex::new(Box::new(recorder)));
+    let text = "a".repeat(40_000);
+    let job = PasteJob::start(TabId(1), writer, Arc::new(Ignore), &text, Bracketing::Bracketed);
+    entered.recv().expect("writer starts");
+    (job, release, text, out)
+}
+
+#[test]
+fn large_paste_holds_typed_input_until_the_brackets_close() {
+    let (job, release, text, out) = gated_paste();
+
+    // The writer is stuck on the first chunk: typing waits its turn.
+    assert!(job.queue(b"typed"));
+    release.send(()).expect("writer is waiting");
+    job.join();
+
+    let expected = format!("\x1b[200~{text}\x1b[201~typed");
+    assert_eq!(*out.lock(), expected.into_bytes());
+}
+
+#[test]
+fn terminal_replies_wait_behind_a_paste_in_flight() {
+    let (job, release, text, out) = gated_paste();
+    let slot = PasteSlot::default();
+    assert!(!queue_behind(&slot, b"early"));
+    *slot.lock() = Some(job);
+
+    // A DA reply from the reader thread while the first chunk is written.
+    assert!(queue_behind(&slot, b"\x1b[?62c"));
+    release.send(()).expect("writer is waiting");
+    slot.lock().take().expect("job is in the slot").join();
+
+    let expected = format!("\x1b[200~{text}\x1b[201~\x1b[?62c");
+    assert_eq!(*out.lock(), expected.into_bytes());
+}
+
+#[test]
+fn cancelled_paste_still_closes_its_brackets() {
+    let (job, release, text, out) = gated_paste();
+
+    job.cancel();
+    release.send(()).expect("writer is waiting");
+    job.join();
+
+    // Only the chunk already being written went out.
+    let out = out.lock();
+    assert!(out.starts_with(b"\x1b[200~"));
+    assert!(out.ends_with(b"\x1b[201~"));
+    assert!(out.len() < text.len());
+}
@@ -8,6 +8,8 @@ use parking_lot::Mutex;
 use vte::ansi::{CharsetIndex, CursorShape, StandardCharset};
 use winit::event_loop::EventLoopProxy;
 
+use crate::grid::ClipEdge;
+
 /// Thread-shared PTY writer. Both the main thread (keyboard input) and the
 /// reader thread (VTE responses like DA, DECRPM) write through this.
 pub type PtyWriter = Arc<Mutex<Box<dyn Write + Send>>>;
@@ -62,9 +64,95 @@ pub enum PromptState {
 }
 
 /// A desktop notification from OSC 9, 99, or 777.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Notification {
     pub title: String,
     pub body: String,
+    /// OSC 99 identifier (`i=`). A later notification with the same id
+    /// replaces this one, and `p=close` with the id dismisses it.
+    pub id: Option<String>,
+    /// OSC 99 action button labels (`p=buttons`), numbered from 1.
+    pub buttons: Vec<String>,
+    /// Report activation back to the PTY (OSC 99 `a=report`).
+    pub report_activation: bool,
+    /// Report dismissal back to the PTY (OSC 99 `c=1`).
+    pub report_close: bool,
+}
+
+/// What a tab received that the user hasn't seen, shown as its tab bar
+/// badge and counted into the app badge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Unseen {
+    /// Notifications shown while the tab wasn't the focused tab.
+    pub notifications: u32,
+    /// A bell rang while the tab wasn't active.
+    pub bell: bool,
+}
+
+impl Unseen {
+    /// Items counted on the app badge: each notification, and the bell.
+    pub fn count(self) -> u32 {
+        self.notifications.saturating_add(u32::from(self.bell))
+    }
+}
+
+/// How the user closed a desktop notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationResponse {
+    /// The toast itself was clicked.
+    Clicked,
+    /// Action button `n` (1-based) was pressed.
+    Button(usize),
+    /// Dismissed or expired.
+    Dismissed,
+}
+
+/// Task progress from the `ConEmu` sequence `OSC 9 ; 4 ; state ; percent`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TaskProgress {
+    /// No progress shown (state 0).
+    #[default]
+    Hidden,
+    /// Normal progress, 0–100 (state 1).
+    Normal(u8),
+    /// Failed task, 0–100 (state 2).
+    Error(u8),
+    /// Busy with no known completion (state 3).
+    Indeterminate,
+    /// Paused or warning, 0–100 (state 4).
+    Paused(u8),
+}
+
+impl TaskProgress {
+    /// Parse the `state ; percent` parameters. Unknown states are ignored;
+    /// a missing or malformed percent counts as 0, and larger values clamp
+    /// to 100.
+    pub fn from_osc(params: &[&[u8]]) -> Option<Self> {
+        let number = |i: usize| {
+            params
+                .get(i)
+                .and_then(|p| std::str::from_utf8(p).ok())
+                .and_then(|p| p.parse::<u32>().ok())
+        };
+        let percent = number(1).map_or(0, |p| p.min(100) as u8);
+        match number(0)? {
+            0 => Some(Self::Hidden),
+            1 => Some(Self::Normal(percent)),
+            2 => Some(Self::Error(percent)),
+            3 => Some(Self::Indeterminate),
+            4 => Some(Self::Paused(percent)),
+            _ => None,
+        }
+    }
+}
+
+/// A notification request parsed from the PTY output stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotificationEvent {
+    /// Show a notification, replacing any shown one with the same id.
+    Show(Notification),
+    /// Close the notification with this id (OSC 99 `p=close`).
+    Close(String),
 }
 
 /// Configuration for spawning a new tab.
@@ -72,14 +160,37 @@ pub struct SpawnConfig {
     pub id: TabId,
     pub cols: usize,
     pub rows: usize,
-    pub proxy: EventLoopProxy<TermEvent>,
+    pub events: EventSender,
     pub shell: Option<String>,
     pub max_scrollback: usize,
     pub cursor_shape: CursorShape,
     pub integration_dir: Option<PathBuf>,
     pub cwd: Option<String>,
+    pub alt_screen_clip: ClipEdge,
+    pub answerback: String,
+    /// Translate raw C1 controls (`config.terminal.c1_controls`).
+    pub c1_controls: bool,
+    /// Scrollback saved with the last session, replayed as history before
+    /// the shell starts (see [`crate::session`]).
+    pub history: Option<String>,
 }
 
+/// Receives [`TermEvent`]s from a tab's background threads. The app's event
+/// loop proxy is one; an embedding host supplies its own.
+pub trait EventSink: Send + Sync {
+    /// Deliver `event`. Events sent after the receiver is gone are dropped.
+    fn send(&self, event: TermEvent);
+}
+
+impl EventSink for EventLoopProxy<TermEvent> {
+    fn send(&self, event: TermEvent) {
+        let _ = self.send_event(event);
+    }
+}
+
+/// Shared handle to a tab's [`EventSink`].
+pub type EventSender = Arc<dyn EventSink>;
+
 /// Events sent from background threads to the event loop.
 #[derive(Debug)]
 pub enum TermEvent {
@@ -89,4 +200,14 @@ pub enum TermEvent {
     PtyExited(TabId),
     /// Configuration file changed — reload.
     ConfigReload,
+    /// Tab reported task progress (OSC 9;4).
+    Progress(TabId, TaskProgress),
+    /// A desktop notification from the tab was closed (OSC 99 id, if any).
+    NotificationResponse(TabId, Option<String>, NotificationResponse),
+    /// A request from `oriterm cli`.
+    Ipc(crate::ipc::IpcCall),
+    /// A background paste wrote a chunk or finished.
+    Paste(TabId),
+    /// A background search found the matches in a batch of rows.
+    Search(TabId, crate::search::SearchBatch),
 }
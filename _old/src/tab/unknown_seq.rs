@@ -0,0 +1,74 @@
+//! Per-tab counts of the sequences the terminal ignores.
+//!
+//! Every CSI, OSC, and DCS without a name in [`super::seq_names`] is
+//! counted by its final byte (with private markers and intermediates) or
+//! OSC number, whether or not the inspector is open. The counts appear in
+//! the inspector header and the periodic stats log, so a user report can
+//! point at a missing sequence without a full trace.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Longest OSC command kept in a key; longer ones are cut.
+const MAX_OSC_KEY: usize = 16;
+
+/// How often each ignored sequence arrived, by key (`CSI ?W`, `OSC 1337`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnknownSequences {
+    counts: BTreeMap<String, u64>,
+}
+
+impl UnknownSequences {
+    /// Count an ignored `CSI <intermediates> <action>`.
+    pub(super) fn count_csi(&mut self, intermediates: &[u8], action: char) {
+        self.count(selector("CSI ", intermediates, action));
+    }
+
+    /// Count an ignored DCS by its introducer.
+    pub(super) fn count_dcs(&mut self, intermediates: &[u8], action: char) {
+        self.count(selector("DCS ", intermediates, action));
+    }
+
+    /// Count an ignored OSC by its command number.
+    pub(super) fn count_osc(&mut self, command: &[u8]) {
+        let command = String::from_utf8_lossy(command);
+        let command: String = command.chars().take(MAX_OSC_KEY).collect();
+        self.count(format!("OSC {command}"));
+    }
+
+    fn count(&mut self, key: String) {
+        *self.counts.entry(key).or_default() += 1;
+    }
+
+    /// Whether nothing has been ignored.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// The count for `key`, e.g. `CSI ?W` or `OSC 1337`.
+    pub fn get(&self, key: &str) -> u64 {
+        self.counts.get(key).copied().unwrap_or(0)
+    }
+}
+
+impl fmt::Display for UnknownSequences {
+    /// `CSI ?W ×3, OSC 1337 ×1`, in key order.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (key, count)) in self.counts.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{key} ×{count}")?;
+        }
+        Ok(())
+    }
+}
+
+/// `prefix`, the intermediates (private markers first, as on the wire),
+/// and the final byte.
+fn selector(prefix: &str, intermediates: &[u8], action: char) -> String {
+    let mut key = String::from(prefix);
+    key.extend(intermediates.iter().map(|&b| char::from(b)));
+    key.push(action);
+    key
+}
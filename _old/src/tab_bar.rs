@@ -1,4 +1,7 @@
-//! Tab bar layout and hit-testing.
+//! Tab bar layout, hit-testing, and the OS appearance the chrome follows.
+
+use vte::ansi::Rgb;
+use winit::window::Theme;
 
 // Tab bar constants
 pub const TAB_BAR_HEIGHT: usize = 46;
@@ -6,7 +9,10 @@ pub const TAB_LEFT_MARGIN: usize = 16; // space between window edge and first ta
 pub(crate) const TAB_TOP_MARGIN: usize = 8; // space between window edge and tab tops
 const TAB_MIN_WIDTH: usize = 80;
 const TAB_MAX_WIDTH: usize = 260;
+/// Width of a pinned tab, which shows only its icon.
+const PINNED_TAB_WIDTH: usize = 40;
 pub(crate) const TAB_PADDING: usize = 8;
+pub(crate) const TAB_ICON_SIZE: usize = 14;
 pub(crate) const CLOSE_BUTTON_WIDTH: usize = 24;
 pub(crate) const CLOSE_BUTTON_RIGHT_PAD: usize = 8; // padding from right edge of tab
 pub const NEW_TAB_BUTTON_WIDTH: usize = 38;
@@ -47,6 +53,26 @@ fn scaled(v: usize, scale: f64) -> usize {
     (v as f64 * scale).round() as usize
 }
 
+/// OS appearance the window chrome follows: light or dark context menus,
+/// and the system accent color (Windows) on the active tab and the window
+/// border.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChromeTheme {
+    /// The OS light/dark preference.
+    pub theme: Theme,
+    /// The OS accent color, where the platform has one.
+    pub accent: Option<Rgb>,
+}
+
+impl Default for ChromeTheme {
+    fn default() -> Self {
+        Self {
+            theme: Theme::Dark,
+            accent: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TabBarHit {
     Tab(usize),
@@ -60,39 +86,82 @@ pub enum TabBarHit {
     None,
 }
 
+/// Where the tabs sit in the bar. The first `pinned_count` tabs are
+/// pinned and drawn compact at `pinned_width`; the rest share the
+/// remaining room at `tab_width` each.
 pub struct TabBarLayout {
     pub tab_width: usize,
     pub tab_count: usize,
+    pub pinned_count: usize,
+    pub pinned_width: usize,
     pub bar_width: usize,
 }
 
 impl TabBarLayout {
     pub fn compute(
         tab_count: usize,
+        pinned_count: usize,
         bar_width: usize,
         scale: f64,
         max_tab_width: Option<usize>,
     ) -> Self {
         let s = |v: usize| scaled(v, scale);
-        // Reserve space for left margin, new-tab button, dropdown, and window controls
+        let pinned_count = pinned_count.min(tab_count);
+        let pinned_width = s(PINNED_TAB_WIDTH);
+        // Reserve space for left margin, pinned tabs, new-tab button,
+        // dropdown, and window controls
         let available = bar_width
             .saturating_sub(s(TAB_LEFT_MARGIN))
+            .saturating_sub(pinned_count * pinned_width)
             .saturating_sub(s(NEW_TAB_BUTTON_WIDTH))
             .saturating_sub(s(DROPDOWN_BUTTON_WIDTH))
             .saturating_sub(s(CONTROLS_ZONE_WIDTH));
         let upper = max_tab_width.unwrap_or_else(|| s(TAB_MAX_WIDTH));
-        let tab_width = if tab_count == 0 {
-            s(TAB_MIN_WIDTH)
-        } else {
-            (available / tab_count).clamp(s(TAB_MIN_WIDTH), upper)
-        };
+        let unpinned = tab_count - pinned_count;
+        let min = s(TAB_MIN_WIDTH);
+        let tab_width = available
+            .checked_div(unpinned)
+            .map_or(min, |w| w.clamp(min, upper));
         Self {
             tab_width,
             tab_count,
+            pinned_count,
+            pinned_width,
             bar_width,
         }
     }
 
+    /// Distance from the first tab's left edge to tab `idx`'s.
+    pub fn tab_offset(&self, idx: usize) -> usize {
+        let pinned = idx.min(self.pinned_count);
+        pinned * self.pinned_width + (idx - pinned) * self.tab_width
+    }
+
+    /// Width of tab `idx`.
+    pub fn width_of(&self, idx: usize) -> usize {
+        if idx < self.pinned_count {
+            self.pinned_width
+        } else {
+            self.tab_width
+        }
+    }
+
+    /// Width of all the tabs together.
+    pub fn tabs_width(&self) -> usize {
+        self.tab_offset(self.tab_count)
+    }
+
+    /// The tab `offset` pixels right of the first tab's left edge, if any.
+    pub fn tab_at(&self, offset: usize) -> Option<usize> {
+        let pinned_w = self.pinned_count * self.pinned_width;
+        let idx = if offset < pinned_w {
+            offset / self.pinned_width.max(1)
+        } else {
+            self.pinned_count + (offset - pinned_w) / self.tab_width.max(1)
+        };
+        (idx < self.tab_count).then_some(idx)
+    }
+
     pub fn hit_test(&self, x: usize, y: usize, scale: f64) -> TabBarHit {
         let s = |v: usize| scaled(v, scale);
 
@@ -154,7 +223,7 @@ impl TabBarLayout {
         // Tabs start after the left margin
         let left_margin = s(TAB_LEFT_MARGIN);
         let tab_x = x.saturating_sub(left_margin);
-        let tabs_end = left_margin + self.tab_count * self.tab_width;
+        let tabs_end = left_margin + self.tabs_width();
 
         // Check new tab button (at the end of all tabs)
         let new_tab_w = s(NEW_TAB_BUTTON_WIDTH);
@@ -171,10 +240,13 @@ impl TabBarLayout {
 
         // Check which tab
         if x >= left_margin && x < tabs_end {
-            let tab_idx = tab_x / self.tab_width;
-            if tab_idx < self.tab_count {
+            if let Some(tab_idx) = self.tab_at(tab_x) {
+                // Pinned tabs have no close button
+                if tab_idx < self.pinned_count {
+                    return TabBarHit::Tab(tab_idx);
+                }
                 // Check close button (inset from right edge of tab)
-                let tab_right = (tab_idx + 1) * self.tab_width;
+                let tab_right = self.tab_offset(tab_idx + 1);
                 let close_start =
                     tab_right.saturating_sub(s(CLOSE_BUTTON_WIDTH) + s(CLOSE_BUTTON_RIGHT_PAD));
                 if tab_x >= close_start
@@ -190,3 +262,45 @@ impl TabBarLayout {
         TabBarHit::DragArea
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pinned_tabs_are_compact_and_have_no_close_button() {
+        let layout = TabBarLayout::compute(4, 2, 1600, 1.0, None);
+        assert_eq!(layout.width_of(0), PINNED_TAB_WIDTH);
+        assert_eq!(layout.width_of(2), layout.tab_width);
+        assert_eq!(layout.tab_offset(2), 2 * PINNED_TAB_WIDTH);
+        assert_eq!(
+            layout.tab_offset(3),
+            2 * PINNED_TAB_WIDTH + layout.tab_width
+        );
+        assert_eq!(
+            layout.tabs_width(),
+            2 * PINNED_TAB_WIDTH + 2 * layout.tab_width
+        );
+
+        let y = TAB_BAR_HEIGHT / 2;
+        let x_in = |idx: usize, from_right: usize| {
+            TAB_LEFT_MARGIN + layout.tab_offset(idx + 1) - from_right
+        };
+        // The right edge of a pinned tab selects it; an unpinned tab's
+        // closes it.
+        let close_x = CLOSE_BUTTON_RIGHT_PAD + CLOSE_BUTTON_WIDTH / 2;
+        assert_eq!(layout.hit_test(x_in(1, close_x), y, 1.0), TabBarHit::Tab(1));
+        assert_eq!(
+            layout.hit_test(x_in(2, close_x), y, 1.0),
+            TabBarHit::CloseTab(2)
+        );
+        assert_eq!(layout.tab_at(layout.tabs_width()), None);
+    }
+
+    #[test]
+    fn pinned_count_never_exceeds_tab_count() {
+        let layout = TabBarLayout::compute(1, 3, 1600, 1.0, None);
+        assert_eq!(layout.pinned_count, 1);
+        assert_eq!(layout.tabs_width(), PINNED_TAB_WIDTH);
+    }
+}
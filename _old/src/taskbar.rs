@@ -0,0 +1,221 @@
+//! Windows taskbar progress and overlay — `ITaskbarList3::SetProgressState`,
+//! `SetProgressValue`, and `SetOverlayIcon`.
+//!
+//! `windows-sys` ships no COM interface bindings, so the taskbar list is
+//! created with `CoCreateInstance` and its methods are called through a
+//! hand-written prefix of the `ITaskbarList3` vtable. The object
+//! lives on the main thread for the life of the process; dropping it
+//! releases the interface and balances the COM initialization.
+
+use std::ffi::c_void;
+
+use windows_sys::Win32::Foundation::{HWND, RPC_E_CHANGED_MODE};
+use windows_sys::Win32::Graphics::Gdi::{CreateBitmap, DeleteObject};
+use windows_sys::Win32::System::Com::{
+    CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED, CoCreateInstance, CoInitializeEx,
+    CoUninitialize,
+};
+use windows_sys::Win32::UI::Shell::{
+    TBPF_ERROR, TBPF_INDETERMINATE, TBPF_NOPROGRESS, TBPF_NORMAL, TBPF_PAUSED, TBPFLAG,
+};
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    CreateIconIndirect, DestroyIcon, HICON, ICONINFO,
+};
+use windows_sys::core::{GUID, HRESULT, PCWSTR};
+
+use crate::badge;
+
+use crate::platform_windows::hwnd_from_window;
+use crate::tab::TaskProgress;
+
+const CLSID_TASKBAR_LIST: GUID = GUID::from_u128(0x56fdf344_fd6d_11d0_958a_006097c9a090);
+const IID_ITASKBAR_LIST3: GUID = GUID::from_u128(0xea1afb91_9e28_4b86_90e9_9e9f8a5eefaf);
+
+/// `ITaskbarList3` vtable up to `SetOverlayIcon`. Unused slots are
+/// opaque pointers.
+#[repr(C)]
+struct TaskbarListVtbl {
+    /// `QueryInterface` and `AddRef`.
+    _iunknown: [usize; 2],
+    release: unsafe extern "system" fn(*mut c_void) -> u32,
+    hr_init: unsafe extern "system" fn(*mut c_void) -> HRESULT,
+    /// `AddTab`, `DeleteTab`, `ActivateTab`, `SetActiveAlt`, and
+    /// `MarkFullscreenWindow`.
+    _unused: [usize; 5],
+    set_progress_value: unsafe extern "system" fn(*mut c_void, HWND, u64, u64) -> HRESULT,
+    set_progress_state: unsafe extern "system" fn(*mut c_void, HWND, TBPFLAG) -> HRESULT,
+    /// `RegisterTab`, `UnregisterTab`, `SetTabOrder`, `SetTabActive`, and
+    /// the three `ThumbBar` methods.
+    _tabs: [usize; 7],
+    set_overlay_icon: unsafe extern "system" fn(*mut c_void, HWND, HICON, PCWSTR) -> HRESULT,
+}
+
+/// An initialized `ITaskbarList3` instance.
+struct TaskbarList {
+    ptr: *mut c_void,
+    /// This thread's COM initialization succeeded (`S_OK` or `S_FALSE`)
+    /// and must be balanced with `CoUninitialize`.
+    com_initialized: bool,
+}
+
+impl TaskbarList {
+    #[allow(unsafe_code)]
+    fn create() -> Option<Self> {
+        // SAFETY: A successful call is balanced on drop. An apartment
+        // already set up as multithreaded (RPC_E_CHANGED_MODE) still serves
+        // in-process objects, but is not ours to uninitialize.
+        let init = unsafe { CoInitializeEx(std::ptr::null(), COINIT_APARTMENTTHREADED) };
+        if init < 0 && init != RPC_E_CHANGED_MODE {
+            return None;
+        }
+        let mut list = Self {
+            ptr: std::ptr::null_mut(),
+            com_initialized: init >= 0,
+        };
+        // SAFETY: `list.ptr` receives an owned interface pointer whose first
+        // field is the vtable laid out as `TaskbarListVtbl`. On failure
+        // `list` drops, releasing whatever was created.
+        unsafe {
+            let hr = CoCreateInstance(
+                &raw const CLSID_TASKBAR_LIST,
+                std::ptr::null_mut(),
+                CLSCTX_INPROC_SERVER,
+                &raw const IID_ITASKBAR_LIST3,
+                &raw mut list.ptr,
+            );
+            if hr < 0 || list.ptr.is_null() {
+                return None;
+            }
+            if ((*list.vtbl()).hr_init)(list.ptr) < 0 {
+                return None;
+            }
+        }
+        Some(list)
+    }
+
+    /// The interface's vtable.
+    ///
+    /// # Safety
+    ///
+    /// `self.ptr` must be a live `ITaskbarList3` pointer.
+    #[allow(unsafe_code)]
+    unsafe fn vtbl(&self) -> *const TaskbarListVtbl {
+        // SAFETY: A COM object pointer points at its vtable pointer.
+        unsafe { *self.ptr.cast::<*const TaskbarListVtbl>() }
+    }
+
+    #[allow(unsafe_code)]
+    fn set(&self, hwnd: HWND, progress: TaskProgress) {
+        let (state, percent) = match progress {
+            TaskProgress::Hidden => (TBPF_NOPROGRESS, None),
+            TaskProgress::Normal(p) => (TBPF_NORMAL, Some(p)),
+            TaskProgress::Error(p) => (TBPF_ERROR, Some(p)),
+            TaskProgress::Indeterminate => (TBPF_INDETERMINATE, None),
+            TaskProgress::Paused(p) => (TBPF_PAUSED, Some(p)),
+        };
+        // SAFETY: `self.ptr` was created by `CoCreateInstance` and is
+        // released only on drop; `hwnd` comes from a live winit window.
+        unsafe {
+            let vtbl = self.vtbl();
+            ((*vtbl).set_progress_state)(self.ptr, hwnd, state);
+            if let Some(p) = percent {
+                ((*vtbl).set_progress_value)(self.ptr, hwnd, u64::from(p), 100);
+            }
+        }
+    }
+
+    /// Put `icon` over `hwnd`'s taskbar button, or clear it when null.
+    #[allow(unsafe_code)]
+    fn set_overlay(&self, hwnd: HWND, icon: HICON) {
+        // SAFETY: As in `set`; the taskbar copies the icon, so the caller
+        // may destroy it afterwards.
+        unsafe {
+            ((*self.vtbl()).set_overlay_icon)(self.ptr, hwnd, icon, std::ptr::null());
+        }
+    }
+}
+
+impl Drop for TaskbarList {
+    #[allow(unsafe_code)]
+    fn drop(&mut self) {
+        // SAFETY: `self.ptr` is null or the interface this value owns, and
+        // COM is uninitialized only if this value initialized it, on the
+        // thread that did.
+        unsafe {
+            if !self.ptr.is_null() {
+                ((*self.vtbl()).release)(self.ptr);
+            }
+            if self.com_initialized {
+                CoUninitialize();
+            }
+        }
+    }
+}
+
+thread_local! {
+    static TASKBAR: Option<TaskbarList> = TaskbarList::create();
+}
+
+/// Show `progress` on `window`'s taskbar button.
+pub fn set_progress(window: &winit::window::Window, progress: TaskProgress) {
+    let Some(hwnd) = hwnd_from_window(window) else {
+        return;
+    };
+    TASKBAR.with(|list| {
+        if let Some(list) = list {
+            list.set(hwnd, progress);
+        }
+    });
+}
+
+/// Build the badge icon for `count`; null if GDI refuses.
+#[allow(unsafe_code)]
+fn badge_icon(count: u32) -> HICON {
+    let size = badge::ICON_SIZE as i32;
+    let color = badge::icon_bgra(count);
+    // A monochrome mask, rows padded to 16 bits; ignored where the color
+    // bitmap has alpha, which is everywhere.
+    let mask = vec![0u8; badge::ICON_SIZE.div_ceil(16) * 2 * badge::ICON_SIZE];
+    // SAFETY: Both buffers hold `size` rows in the bit depth requested, and
+    // the bitmaps are deleted once the icon has copied them.
+    unsafe {
+        let info = ICONINFO {
+            fIcon: 1,
+            xHotspot: 0,
+            yHotspot: 0,
+            hbmMask: CreateBitmap(size, size, 1, 1, mask.as_ptr().cast()),
+            hbmColor: CreateBitmap(size, size, 1, 32, color.as_ptr().cast()),
+        };
+        let icon = if info.hbmMask.is_null() || info.hbmColor.is_null() {
+            std::ptr::null_mut()
+        } else {
+            CreateIconIndirect(&raw const info)
+        };
+        DeleteObject(info.hbmMask);
+        DeleteObject(info.hbmColor);
+        icon
+    }
+}
+
+/// Show `count` over each of `windows`' taskbar buttons; zero clears it.
+#[allow(unsafe_code)]
+pub fn set_overlay<'a>(windows: impl Iterator<Item = &'a winit::window::Window>, count: u32) {
+    let icon = if count == 0 {
+        std::ptr::null_mut()
+    } else {
+        badge_icon(count)
+    };
+    TASKBAR.with(|list| {
+        if let Some(list) = list {
+            for hwnd in windows.filter_map(hwnd_from_window) {
+                list.set_overlay(hwnd, icon);
+            }
+        }
+    });
+    if !icon.is_null() {
+        // SAFETY: The icon was created above and the taskbar holds copies.
+        unsafe {
+            DestroyIcon(icon);
+        }
+    }
+}
@@ -18,7 +18,8 @@ impl TermHandler<'_> {
                 template.fg = Color::Named(NamedColor::Foreground);
                 template.bg = Color::Named(NamedColor::Background);
                 template.flags = CellFlags::empty();
-                template.extra = None;
+                // SGR 0 ends the underline color but not an OSC 8 hyperlink.
+                template.set_underline_color(None);
             }
             Attr::Bold => template.flags.insert(CellFlags::BOLD),
             Attr::Dim => template.flags.insert(CellFlags::DIM),
@@ -43,7 +44,14 @@ impl TermHandler<'_> {
                 template.flags.remove(CellFlags::ANY_UNDERLINE);
                 template.flags.insert(CellFlags::DASHED_UNDERLINE);
             }
-            Attr::BlinkSlow | Attr::BlinkFast => template.flags.insert(CellFlags::BLINK),
+            Attr::BlinkSlow => {
+                template.flags.remove(CellFlags::BLINK_FAST);
+                template.flags.insert(CellFlags::BLINK);
+            }
+            Attr::BlinkFast => {
+                template.flags.remove(CellFlags::BLINK);
+                template.flags.insert(CellFlags::BLINK_FAST);
+            }
             Attr::Reverse => template.flags.insert(CellFlags::INVERSE),
             Attr::Hidden => template.flags.insert(CellFlags::HIDDEN),
             Attr::Strike => template.flags.insert(CellFlags::STRIKEOUT),
@@ -54,7 +62,7 @@ impl TermHandler<'_> {
             }
             Attr::CancelItalic => template.flags.remove(CellFlags::ITALIC),
             Attr::CancelUnderline => template.flags.remove(CellFlags::ANY_UNDERLINE),
-            Attr::CancelBlink => template.flags.remove(CellFlags::BLINK),
+            Attr::CancelBlink => template.flags.remove(CellFlags::ANY_BLINK),
             Attr::CancelReverse => template.flags.remove(CellFlags::INVERSE),
             Attr::CancelHidden => template.flags.remove(CellFlags::HIDDEN),
             Attr::CancelStrike => template.flags.remove(CellFlags::STRIKEOUT),
@@ -75,7 +83,8 @@ impl TermHandler<'_> {
     }
 
     pub(super) fn handle_set_hyperlink(&mut self, hyperlink: Option<Hyperlink>) {
-        self.active_grid().cursor.template.set_hyperlink(hyperlink);
+        let link = hyperlink.map(|h| self.links.intern(h));
+        self.active_grid().cursor.template.set_hyperlink(link);
     }
 
     pub(super) fn handle_set_cursor_style(&mut self, style: Option<CursorStyle>) {
@@ -93,15 +102,10 @@ impl TermHandler<'_> {
         index: usize,
         terminator: &str,
     ) {
-        // OSC 10 = foreground, OSC 11 = background, OSC 12 = cursor color
-        // When the param is "?", we respond with the current color
-        let color = match index {
-            0 => Some(self.palette.default_fg()),   // OSC 10
-            1 => Some(self.palette.default_bg()),   // OSC 11
-            2 => Some(self.palette.cursor_color()), // OSC 12
-            _ => None,
-        };
-        if let Some(rgb) = color {
+        // `OSC 4 ; n ; ?` queries palette entry n (0-255); OSC 10/11/12 `?`
+        // arrive as the named foreground, background, and cursor entries
+        // (256-258). The reply echoes the query's prefix and terminator.
+        if let Some(rgb) = self.palette.color(index) {
             // Respond in XParseColor format: rgb:RRRR/GGGG/BBBB (16-bit per channel)
             let response = format!(
                 "\x1b]{prefix};rgb:{:04x}/{:04x}/{:04x}{terminator}",
@@ -1,56 +1,60 @@
-//! Cursor movement and save/restore.
+//! Cursor movement and save/restore, and marks on the cursor row.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::grid::row::RowFlags;
 
 use super::TermHandler;
 
 impl TermHandler<'_> {
     pub(super) fn handle_goto(&mut self, line: i32, col: usize) {
-        self.grapheme.after_zwj = false;
+        self.grapheme.reset();
         let grid = self.active_grid();
         let row = if line < 0 { 0 } else { line as usize };
         grid.goto(row, col);
     }
 
     pub(super) fn handle_goto_line(&mut self, line: i32) {
-        self.grapheme.after_zwj = false;
+        self.grapheme.reset();
         let grid = self.active_grid();
         let row = if line < 0 { 0 } else { line as usize };
         grid.goto_line(row);
     }
 
     pub(super) fn handle_goto_col(&mut self, col: usize) {
-        self.grapheme.after_zwj = false;
+        self.grapheme.reset();
         self.active_grid().goto_col(col);
     }
 
     pub(super) fn handle_move_up(&mut self, n: usize) {
-        self.grapheme.after_zwj = false;
+        self.grapheme.reset();
         self.active_grid().move_up(n);
     }
 
     pub(super) fn handle_move_down(&mut self, n: usize) {
-        self.grapheme.after_zwj = false;
+        self.grapheme.reset();
         self.active_grid().move_down(n);
     }
 
     pub(super) fn handle_move_forward(&mut self, n: usize) {
-        self.grapheme.after_zwj = false;
+        self.grapheme.reset();
         self.active_grid().move_forward(n);
     }
 
     pub(super) fn handle_move_backward(&mut self, n: usize) {
-        self.grapheme.after_zwj = false;
+        self.grapheme.reset();
         self.active_grid().move_backward(n);
     }
 
     pub(super) fn handle_move_down_and_cr(&mut self, n: usize) {
-        self.grapheme.after_zwj = false;
+        self.grapheme.reset();
         let grid = self.active_grid();
         grid.move_down(n);
         grid.carriage_return();
     }
 
     pub(super) fn handle_move_up_and_cr(&mut self, n: usize) {
-        self.grapheme.after_zwj = false;
+        self.grapheme.reset();
         let grid = self.active_grid();
         grid.move_up(n);
         grid.carriage_return();
@@ -63,4 +67,27 @@ impl TermHandler<'_> {
     pub(super) fn handle_restore_cursor_position(&mut self) {
         self.active_grid().restore_cursor();
     }
+
+    /// Set an OSC 133 shell integration mark on the cursor row, with the
+    /// exit status that ended a command.
+    ///
+    /// vte does not parse OSC 133, so the raw interceptor defers it like
+    /// DECSTR.
+    pub fn mark_row(&mut self, flag: RowFlags, exit_status: Option<i32>) {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+        let grid = self.active_grid();
+        let row = grid.mark_row(grid.cursor.row, flag);
+        if exit_status.is_some() {
+            row.meta_mut().exit_status = exit_status;
+        }
+        // B and D are stamped so D can time the command.
+        if flag.intersects(RowFlags::COMMAND_START | RowFlags::COMMAND_END) {
+            row.meta_mut().timestamp_ms = Some(now_ms);
+        }
+        if flag == RowFlags::COMMAND_END {
+            grid.finish_command(exit_status, now_ms);
+        }
+    }
 }
@@ -0,0 +1,284 @@
+//! `vte::ansi::Handler` for [`TermHandler`]: each callback forwards to the
+//! submodule that implements it.
+
+use vte::ansi::{
+    Attr, CharsetIndex, ClearMode, CursorShape, CursorStyle, Handler, Hyperlink, KeyboardModes,
+    KeyboardModesApplyBehavior, LineClearMode, Mode, ModifyOtherKeys, PrivateMode, Rgb,
+    StandardCharset, TabulationClearMode,
+};
+
+use super::TermHandler;
+
+impl Handler for TermHandler<'_> {
+    fn input(&mut self, c: char) {
+        self.handle_input(c);
+    }
+
+    fn goto(&mut self, line: i32, col: usize) {
+        self.handle_goto(line, col);
+    }
+
+    fn goto_line(&mut self, line: i32) {
+        self.handle_goto_line(line);
+    }
+
+    fn goto_col(&mut self, col: usize) {
+        self.handle_goto_col(col);
+    }
+
+    fn move_up(&mut self, n: usize) {
+        self.handle_move_up(n);
+    }
+
+    fn move_down(&mut self, n: usize) {
+        self.handle_move_down(n);
+    }
+
+    fn move_forward(&mut self, n: usize) {
+        self.handle_move_forward(n);
+    }
+
+    fn move_backward(&mut self, n: usize) {
+        self.handle_move_backward(n);
+    }
+
+    fn move_down_and_cr(&mut self, n: usize) {
+        self.handle_move_down_and_cr(n);
+    }
+
+    fn move_up_and_cr(&mut self, n: usize) {
+        self.handle_move_up_and_cr(n);
+    }
+
+    fn save_cursor_position(&mut self) {
+        self.handle_save_cursor_position();
+    }
+
+    fn restore_cursor_position(&mut self) {
+        self.handle_restore_cursor_position();
+    }
+
+    fn terminal_attribute(&mut self, attr: Attr) {
+        self.handle_terminal_attribute(attr);
+    }
+
+    fn set_color(&mut self, index: usize, color: Rgb) {
+        self.handle_set_color(index, color);
+    }
+
+    fn reset_color(&mut self, index: usize) {
+        self.handle_reset_color(index);
+    }
+
+    fn set_hyperlink(&mut self, hyperlink: Option<Hyperlink>) {
+        self.handle_set_hyperlink(hyperlink);
+    }
+
+    fn set_cursor_style(&mut self, style: Option<CursorStyle>) {
+        self.handle_set_cursor_style(style);
+    }
+
+    fn set_cursor_shape(&mut self, shape: CursorShape) {
+        self.handle_set_cursor_shape(shape);
+    }
+
+    fn dynamic_color_sequence(&mut self, prefix: String, index: usize, terminator: &str) {
+        self.handle_dynamic_color_sequence(prefix, index, terminator);
+    }
+
+    fn clear_screen(&mut self, mode: ClearMode) {
+        self.handle_clear_screen(mode);
+    }
+
+    fn clear_line(&mut self, mode: LineClearMode) {
+        self.handle_clear_line(mode);
+    }
+
+    fn clear_tabs(&mut self, mode: TabulationClearMode) {
+        self.handle_clear_tabs(mode);
+    }
+
+    fn erase_chars(&mut self, count: usize) {
+        self.handle_erase_chars(count);
+    }
+
+    fn delete_chars(&mut self, count: usize) {
+        self.handle_delete_chars(count);
+    }
+
+    fn insert_blank(&mut self, count: usize) {
+        self.handle_insert_blank(count);
+    }
+
+    fn insert_blank_lines(&mut self, count: usize) {
+        self.handle_insert_blank_lines(count);
+    }
+
+    fn delete_lines(&mut self, count: usize) {
+        self.handle_delete_lines(count);
+    }
+
+    fn scroll_up(&mut self, count: usize) {
+        self.handle_scroll_up(count);
+    }
+
+    fn scroll_down(&mut self, count: usize) {
+        self.handle_scroll_down(count);
+    }
+
+    fn set_scrolling_region(&mut self, top: usize, bottom: Option<usize>) {
+        self.handle_set_scrolling_region(top, bottom);
+    }
+
+    fn reverse_index(&mut self) {
+        self.handle_reverse_index();
+    }
+
+    fn linefeed(&mut self) {
+        self.handle_linefeed();
+    }
+
+    fn carriage_return(&mut self) {
+        self.handle_carriage_return();
+    }
+
+    fn backspace(&mut self) {
+        self.handle_backspace();
+    }
+
+    fn newline(&mut self) {
+        self.handle_newline();
+    }
+
+    fn put_tab(&mut self, count: u16) {
+        self.handle_put_tab(count);
+    }
+
+    fn move_forward_tabs(&mut self, count: u16) {
+        self.handle_move_forward_tabs(count);
+    }
+
+    fn move_backward_tabs(&mut self, count: u16) {
+        self.handle_move_backward_tabs(count);
+    }
+
+    fn set_horizontal_tabstop(&mut self) {
+        self.handle_set_horizontal_tabstop();
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.handle_set_mode(mode);
+    }
+
+    fn unset_mode(&mut self, mode: Mode) {
+        self.handle_unset_mode(mode);
+    }
+
+    fn set_private_mode(&mut self, mode: PrivateMode) {
+        self.handle_set_private_mode(mode);
+    }
+
+    fn unset_private_mode(&mut self, mode: PrivateMode) {
+        self.handle_unset_private_mode(mode);
+    }
+
+    fn set_keypad_application_mode(&mut self) {
+        self.handle_set_keypad_application_mode();
+    }
+
+    fn unset_keypad_application_mode(&mut self) {
+        self.handle_unset_keypad_application_mode();
+    }
+
+    fn report_mode(&mut self, mode: Mode) {
+        self.handle_report_mode(mode);
+    }
+
+    fn report_private_mode(&mut self, mode: PrivateMode) {
+        self.handle_report_private_mode(mode);
+    }
+
+    fn device_status(&mut self, status: usize) {
+        self.handle_device_status(status);
+    }
+
+    fn identify_terminal(&mut self, intermediate: Option<char>) {
+        self.handle_identify_terminal(intermediate);
+    }
+
+    fn text_area_size_chars(&mut self) {
+        self.handle_text_area_size_chars();
+    }
+
+    fn text_area_size_pixels(&mut self) {
+        self.handle_text_area_size_pixels();
+    }
+
+    fn bell(&mut self) {
+        self.handle_bell();
+    }
+
+    fn decaln(&mut self) {
+        self.handle_decaln();
+    }
+
+    fn reset_state(&mut self) {
+        self.handle_reset_state();
+    }
+
+    fn set_title(&mut self, title: Option<String>) {
+        self.handle_set_title(title);
+    }
+
+    fn push_title(&mut self) {
+        self.handle_push_title();
+    }
+
+    fn pop_title(&mut self) {
+        self.handle_pop_title();
+    }
+
+    fn configure_charset(&mut self, index: CharsetIndex, charset: StandardCharset) {
+        self.handle_configure_charset(index, charset);
+    }
+
+    fn set_active_charset(&mut self, index: CharsetIndex) {
+        self.handle_set_active_charset(index);
+    }
+
+    fn clipboard_store(&mut self, clipboard: u8, data: &[u8]) {
+        self.handle_clipboard_store(clipboard, data);
+    }
+
+    fn clipboard_load(&mut self, clipboard: u8, terminator: &str) {
+        self.handle_clipboard_load(clipboard, terminator);
+    }
+
+    fn substitute(&mut self) {
+        self.handle_substitute();
+    }
+
+    fn report_keyboard_mode(&mut self) {
+        self.handle_report_keyboard_mode();
+    }
+
+    fn push_keyboard_mode(&mut self, mode: KeyboardModes) {
+        self.handle_push_keyboard_mode(mode);
+    }
+
+    fn pop_keyboard_modes(&mut self, to_pop: u16) {
+        self.handle_pop_keyboard_modes(to_pop);
+    }
+
+    fn set_keyboard_mode(&mut self, mode: KeyboardModes, behavior: KeyboardModesApplyBehavior) {
+        self.handle_set_keyboard_mode(mode, behavior);
+    }
+
+    fn set_modify_other_keys(&mut self, mode: ModifyOtherKeys) {
+        self.handle_set_modify_other_keys(mode);
+    }
+
+    fn report_modify_other_keys(&mut self) {
+        self.handle_report_modify_other_keys();
+    }
+}
@@ -5,13 +5,19 @@ use vte::ansi::{ClearMode, LineClearMode, TabulationClearMode};
 use super::TermHandler;
 
 impl TermHandler<'_> {
+    /// ED. `ED 3` (`clear` without `-x`) erases only the saved lines,
+    /// which belong to the primary screen even while the alt screen is up.
     pub(super) fn handle_clear_screen(&mut self, mode: ClearMode) {
-        self.grapheme.after_zwj = false;
-        self.active_grid().erase_display(mode);
+        self.grapheme.reset();
+        if matches!(mode, ClearMode::Saved) {
+            self.grid.erase_display(mode);
+        } else {
+            self.active_grid().erase_display(mode);
+        }
     }
 
     pub(super) fn handle_clear_line(&mut self, mode: LineClearMode) {
-        self.grapheme.after_zwj = false;
+        self.grapheme.reset();
         self.active_grid().erase_line(mode);
     }
 
@@ -20,27 +26,27 @@ impl TermHandler<'_> {
     }
 
     pub(super) fn handle_erase_chars(&mut self, count: usize) {
-        self.grapheme.after_zwj = false;
+        self.grapheme.reset();
         self.active_grid().erase_chars(count);
     }
 
     pub(super) fn handle_delete_chars(&mut self, count: usize) {
-        self.grapheme.after_zwj = false;
+        self.grapheme.reset();
         self.active_grid().delete_chars(count);
     }
 
     pub(super) fn handle_insert_blank(&mut self, count: usize) {
-        self.grapheme.after_zwj = false;
+        self.grapheme.reset();
         self.active_grid().insert_blank_chars(count);
     }
 
     pub(super) fn handle_insert_blank_lines(&mut self, count: usize) {
-        self.grapheme.after_zwj = false;
+        self.grapheme.reset();
         self.active_grid().insert_lines(count);
     }
 
     pub(super) fn handle_delete_lines(&mut self, count: usize) {
-        self.grapheme.after_zwj = false;
+        self.grapheme.reset();
         self.active_grid().delete_lines(count);
     }
 }
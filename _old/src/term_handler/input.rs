@@ -1,10 +1,83 @@
 //! Character input pipeline.
 
-use unicode_width::UnicodeWidthChar;
+use unicode_segmentation::GraphemeCursor;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-use crate::cell::CellFlags;
+use crate::cell::{CellFlags, VS16};
+use crate::grid::Grid;
+use crate::term_mode::TermMode;
 
-use super::{TermHandler, prev_base_col};
+use super::{GraphemeState, TermHandler};
+
+/// Most chars one cell's cluster holds. Further chars that would extend it
+/// (a flood of combining marks) are dropped.
+pub(super) const MAX_CLUSTER_CHARS: usize = 32;
+
+/// How a printed char relates to the open grapheme cluster.
+enum Continuation {
+    /// It starts a new cell.
+    Breaks,
+    /// It joins the cluster.
+    Extends,
+    /// It would join a cluster that is already full.
+    Overflows,
+}
+
+impl GraphemeState {
+    /// End the current cluster; the next printable char starts a new cell.
+    pub(super) fn reset(&mut self) {
+        self.cluster.clear();
+    }
+
+    /// How `c` relates to the cluster held by the last written cell: it
+    /// extends it (combining marks, ZWJ sequences, emoji modifiers, regional
+    /// indicator pairs, variation selectors), starts a new cell, or would
+    /// extend a cluster that is already [`MAX_CLUSTER_CHARS`] long.
+    fn continues_with(&mut self, c: char) -> Continuation {
+        let Some(last) = self.cluster.chars().next_back() else {
+            return Continuation::Breaks;
+        };
+        // Fast path: ASCII never joins ASCII.
+        if last.is_ascii() && c.is_ascii() {
+            return Continuation::Breaks;
+        }
+        let at = self.cluster.len();
+        self.cluster.push(c);
+        let joined = !self.is_boundary(at);
+        if !joined {
+            self.cluster.truncate(at);
+            return Continuation::Breaks;
+        }
+        if self.chars == MAX_CLUSTER_CHARS {
+            self.cluster.truncate(at);
+            return Continuation::Overflows;
+        }
+        self.chars += 1;
+        Continuation::Extends
+    }
+
+    /// Whether UAX #29 puts a boundary at byte `at` of the cluster. The
+    /// cursor reads only the chars around `at` unless the rules need to look
+    /// further back (emoji ZWJ sequences, regional indicators), and the
+    /// cluster is capped, so the cost per char doesn't grow with the text.
+    fn is_boundary(&self, at: usize) -> bool {
+        let text = self.cluster.as_str();
+        GraphemeCursor::new(at, text.len(), true)
+            .is_boundary(text, 0)
+            .unwrap_or(true)
+    }
+
+    /// Start a new cluster at the cell just written for `c`.
+    fn start(&mut self, grid: &Grid, c: char) {
+        self.cluster.clear();
+        if let Some(col) = grid.prev_base_col() {
+            self.cluster.push(c);
+            self.chars = 1;
+            self.base_row = grid.cursor.row;
+            self.base_col = col;
+        }
+    }
+}
 
 impl TermHandler<'_> {
     pub(super) fn handle_input(&mut self, c: char) {
@@ -15,59 +88,93 @@ impl TermHandler<'_> {
         } else {
             &mut *self.grid
         };
-        let width = UnicodeWidthChar::width(c);
-
-        // ZWJ continuation: after a ZWJ, the next printable char joins the cluster
-        // (e.g., family emoji: 👩 + ZWJ + 👩 + ZWJ + 👧 + ZWJ + 👦)
-        if self.grapheme.after_zwj {
-            if let Some(w) = width {
-                if w > 0 {
-                    self.grapheme.after_zwj = false;
-                    let row = self.grapheme.base_row;
-                    let col = self.grapheme.base_col;
-                    if row < grid.lines && col < grid.cols {
-                        grid.row_mut(row)[col].push_zerowidth(c);
-                    }
-                    return;
-                }
-            } else {
-                // None width (control char) — abandon ZWJ state
-                self.grapheme.after_zwj = false;
-            }
-        }
 
-        // Emoji skin tone modifiers (U+1F3FB-U+1F3FF): attach to previous wide
-        // char (emoji) as zerowidth rather than occupying a new cell.
-        if matches!(c, '\u{1F3FB}'..='\u{1F3FF}') {
-            if let Some(prev_col) = prev_base_col(grid) {
-                let row = grid.cursor.row;
-                if grid.row(row)[prev_col].flags.contains(CellFlags::WIDE_CHAR) {
-                    grid.row_mut(row)[prev_col].push_zerowidth(c);
-                    return;
-                }
-            }
-            // Not following a wide char — fall through to normal handling
+        // Grapheme continuation (mode 2027): the char joins the cluster of
+        // the last written cell (e.g., family emoji 👩‍👩‍👧, flags 🇺🇸, e + U+0301).
+        // With the mode reset, each code point takes its own wcwidth.
+        let continuation = if self.mode.contains(TermMode::GRAPHEME_CLUSTERS) {
+            self.grapheme.continues_with(c)
+        } else {
+            Continuation::Breaks
+        };
+        match continuation {
+            Continuation::Extends => return extend_cluster(grid, self.grapheme, c),
+            Continuation::Overflows => return,
+            Continuation::Breaks => {}
         }
 
-        match width {
-            Some(2) => grid.put_wide_char(c),
+        match UnicodeWidthChar::width(c) {
+            Some(2) => {
+                grid.put_wide_char(c);
+                self.grapheme.start(grid, c);
+            }
             Some(0) => {
-                // Zero-width: attach to previous cell, skipping wide char spacers.
+                // Zero-width with no open cluster (e.g., after cursor motion):
+                // attach to previous cell, skipping wide char spacers.
                 // When input_needs_wrap is true, cursor.col points at the cell
                 // we just wrote (it was clamped back after advancing past the end).
-                if let Some(col) = prev_base_col(grid) {
+                if let Some(col) = grid.prev_base_col() {
                     let row = grid.cursor.row;
+                    if grid.row(row)[col].zerowidth().len() >= MAX_CLUSTER_CHARS {
+                        return;
+                    }
                     grid.row_mut(row)[col].push_zerowidth(c);
-
-                    // Track ZWJ for grapheme cluster continuation
-                    if c == '\u{200D}' {
-                        self.grapheme.after_zwj = true;
-                        self.grapheme.base_row = row;
-                        self.grapheme.base_col = col;
+                    // VS16 asks for emoji presentation: give the base two cells.
+                    if c == VS16
+                        && self.mode.contains(TermMode::GRAPHEME_CLUSTERS)
+                        && grid.row(row)[col].is_narrow_emoji_presentation()
+                    {
+                        widen_cell(grid, row, col);
                     }
                 }
             }
-            _ => grid.put_char(c),
+            _ => {
+                grid.put_char(c);
+                self.grapheme.start(grid, c);
+            }
         }
     }
 }
+
+/// Attach `c` to the base cell of the open cluster, widening the cell once
+/// the cluster needs two columns.
+fn extend_cluster(grid: &mut Grid, grapheme: &mut GraphemeState, c: char) {
+    let (row, col) = (grapheme.base_row, grapheme.base_col);
+    if row >= grid.lines || col >= grid.cols {
+        return;
+    }
+    grid.row_mut(row)[col].push_zerowidth(c);
+    grid.dirty.mark_row(row);
+    if !grid.row(row)[col].flags.contains(CellFlags::WIDE_CHAR)
+        && UnicodeWidthStr::width(grapheme.cluster.as_str()) >= 2
+    {
+        if let Some((row, col)) = widen_cell(grid, row, col) {
+            grapheme.base_row = row;
+            grapheme.base_col = col;
+        }
+    }
+}
+
+/// Rewrite the narrow cluster base at (`row`, `col`) as a wide char once
+/// the cluster needs two cells (regional indicator pairs, VS16 emoji
+/// presentation). The cell keeps its own extras (zero-width chars, link,
+/// underline color) rather than taking the cursor template's. Only done
+/// while the cursor still sits right after it. Returns where the base ended
+/// up (it moves to the next line when widened at the last column).
+fn widen_cell(grid: &mut Grid, row: usize, col: usize) -> Option<(usize, usize)> {
+    if grid.cursor.row != row || grid.prev_base_col() != Some(col) {
+        return None;
+    }
+    let cell = &mut grid.row_mut(row)[col];
+    let base = cell.c;
+    let extra = cell.extra.take();
+
+    grid.cursor.col = col;
+    grid.cursor.input_needs_wrap = false;
+    grid.put_wide_char(base);
+
+    let new_col = grid.prev_base_col()?;
+    let new_row = grid.cursor.row;
+    grid.row_mut(new_row)[new_col].extra = extra;
+    Some((new_row, new_col))
+}
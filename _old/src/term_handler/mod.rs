@@ -2,34 +2,40 @@
 
 mod attr;
 mod cursor;
+mod dispatch;
 mod erase;
 mod input;
 mod mode;
 mod scroll;
+mod text_sizing;
 mod title;
 
+pub use text_sizing::TextSizing;
+
+use std::collections::VecDeque;
 use std::time::Instant;
 
-use vte::ansi::{
-    Attr, CharsetIndex, ClearMode, CursorShape, CursorStyle, Handler, Hyperlink,
-    KeyboardModes, KeyboardModesApplyBehavior, LineClearMode, Mode, PrivateMode, Rgb,
-    StandardCharset, TabulationClearMode,
-};
+use vte::ansi::{CursorShape, KeyboardModes};
 
+use crate::cell::CellFlags;
 use crate::grid::Grid;
+use crate::hyperlink::LinkTable;
 use crate::palette::Palette;
 use crate::tab::CharsetState;
 use crate::term_mode::TermMode;
 
-/// Tracks grapheme cluster continuation for ZWJ emoji sequences.
+/// Tracks the grapheme cluster held by the last written cell.
 ///
-/// When a Zero-Width Joiner (U+200D) is attached to a cell, the next
-/// printable character should be attached to the same base cell as a
-/// zero-width character rather than starting a new cell.
+/// Each printed char is checked against the end of the cluster with UAX #29
+/// segmentation; chars that extend it (ZWJ sequences, emoji modifiers,
+/// regional indicator pairs, combining marks) are attached to the same base
+/// cell as zero-width chars rather than starting a new cell.
 #[derive(Debug, Default)]
 pub struct GraphemeState {
-    /// True when the last zero-width character was ZWJ (U+200D).
-    pub(super) after_zwj: bool,
+    /// Text of the open cluster; empty when the next char starts a new cell.
+    pub(super) cluster: String,
+    /// Chars in `cluster`.
+    pub(super) chars: usize,
     /// Row of the base cell that started this grapheme cluster.
     pub(super) base_row: usize,
     /// Column of the base cell that started this grapheme cluster.
@@ -47,7 +53,7 @@ pub struct TermHandler<'a> {
     pub(super) active_is_alt: &'a mut bool,
     pub(super) cursor_shape: &'a mut CursorShape,
     pub(super) charset: &'a mut CharsetState,
-    pub(super) title_stack: &'a mut Vec<String>,
+    pub(super) title_stack: &'a mut VecDeque<String>,
     pub(super) grapheme: &'a mut GraphemeState,
     pub(super) keyboard_mode_stack: &'a mut Vec<KeyboardModes>,
     pub(super) inactive_keyboard_mode_stack: &'a mut Vec<KeyboardModes>,
@@ -55,6 +61,8 @@ pub struct TermHandler<'a> {
     pub(super) has_explicit_title: &'a mut bool,
     pub(super) suppress_title: &'a mut bool,
     pub(super) title_dirty: &'a mut bool,
+    pub(super) links: &'a mut LinkTable,
+    pub(super) clipboard_request: &'a mut Option<String>,
 }
 
 impl<'a> TermHandler<'a> {
@@ -69,7 +77,7 @@ impl<'a> TermHandler<'a> {
         active_is_alt: &'a mut bool,
         cursor_shape: &'a mut CursorShape,
         charset: &'a mut CharsetState,
-        title_stack: &'a mut Vec<String>,
+        title_stack: &'a mut VecDeque<String>,
         grapheme: &'a mut GraphemeState,
         keyboard_mode_stack: &'a mut Vec<KeyboardModes>,
         inactive_keyboard_mode_stack: &'a mut Vec<KeyboardModes>,
@@ -77,6 +85,8 @@ impl<'a> TermHandler<'a> {
         has_explicit_title: &'a mut bool,
         suppress_title: &'a mut bool,
         title_dirty: &'a mut bool,
+        links: &'a mut LinkTable,
+        clipboard_request: &'a mut Option<String>,
     ) -> Self {
         Self {
             grid,
@@ -96,9 +106,31 @@ impl<'a> TermHandler<'a> {
             has_explicit_title,
             suppress_title,
             title_dirty,
+            links,
+            clipboard_request,
         }
     }
 
+    /// DECSTR soft terminal reset (`CSI ! p`).
+    ///
+    /// vte does not dispatch DECSTR, so the raw interceptor defers it and
+    /// `TerminalState` calls this at the matching position.
+    pub fn soft_reset(&mut self) {
+        self.handle_soft_reset();
+    }
+
+    /// Apply SGR 53 (`on`) or SGR 55 to the cursor template.
+    ///
+    /// vte does not parse overline, so the raw interceptor defers it like
+    /// DECSTR.
+    pub fn set_overline(&mut self, on: bool) {
+        self.active_grid()
+            .cursor
+            .template
+            .flags
+            .set(CellFlags::OVERLINE, on);
+    }
+
     /// Returns a mutable reference to the currently active grid.
     pub(super) fn active_grid(&mut self) -> &mut Grid {
         if *self.active_is_alt {
@@ -161,300 +193,5 @@ impl<'a> TermHandler<'a> {
     }
 }
 
-/// Find the column of the previous base cell (skipping wide char spacers).
-///
-/// Accounts for `input_needs_wrap`: when true, cursor.col already points
-/// at the last written cell rather than the cell after it.
-pub(super) fn prev_base_col(grid: &Grid) -> Option<usize> {
-    use crate::cell::CellFlags;
-
-    let row = grid.cursor.row;
-    let col = if grid.cursor.input_needs_wrap {
-        grid.cursor.col
-    } else if grid.cursor.col > 0 {
-        grid.cursor.col - 1
-    } else {
-        return None;
-    };
-
-    if row >= grid.lines || col >= grid.cols {
-        return None;
-    }
-
-    // Skip wide char spacer to find the base cell
-    if grid.row(row)[col]
-        .flags
-        .contains(CellFlags::WIDE_CHAR_SPACER)
-        && col > 0
-    {
-        Some(col - 1)
-    } else {
-        Some(col)
-    }
-}
-
-impl Handler for TermHandler<'_> {
-    fn input(&mut self, c: char) {
-        self.handle_input(c);
-    }
-
-    fn goto(&mut self, line: i32, col: usize) {
-        self.handle_goto(line, col);
-    }
-
-    fn goto_line(&mut self, line: i32) {
-        self.handle_goto_line(line);
-    }
-
-    fn goto_col(&mut self, col: usize) {
-        self.handle_goto_col(col);
-    }
-
-    fn move_up(&mut self, n: usize) {
-        self.handle_move_up(n);
-    }
-
-    fn move_down(&mut self, n: usize) {
-        self.handle_move_down(n);
-    }
-
-    fn move_forward(&mut self, n: usize) {
-        self.handle_move_forward(n);
-    }
-
-    fn move_backward(&mut self, n: usize) {
-        self.handle_move_backward(n);
-    }
-
-    fn move_down_and_cr(&mut self, n: usize) {
-        self.handle_move_down_and_cr(n);
-    }
-
-    fn move_up_and_cr(&mut self, n: usize) {
-        self.handle_move_up_and_cr(n);
-    }
-
-    fn save_cursor_position(&mut self) {
-        self.handle_save_cursor_position();
-    }
-
-    fn restore_cursor_position(&mut self) {
-        self.handle_restore_cursor_position();
-    }
-
-    fn terminal_attribute(&mut self, attr: Attr) {
-        self.handle_terminal_attribute(attr);
-    }
-
-    fn set_color(&mut self, index: usize, color: Rgb) {
-        self.handle_set_color(index, color);
-    }
-
-    fn reset_color(&mut self, index: usize) {
-        self.handle_reset_color(index);
-    }
-
-    fn set_hyperlink(&mut self, hyperlink: Option<Hyperlink>) {
-        self.handle_set_hyperlink(hyperlink);
-    }
-
-    fn set_cursor_style(&mut self, style: Option<CursorStyle>) {
-        self.handle_set_cursor_style(style);
-    }
-
-    fn set_cursor_shape(&mut self, shape: CursorShape) {
-        self.handle_set_cursor_shape(shape);
-    }
-
-    fn dynamic_color_sequence(&mut self, prefix: String, index: usize, terminator: &str) {
-        self.handle_dynamic_color_sequence(prefix, index, terminator);
-    }
-
-    fn clear_screen(&mut self, mode: ClearMode) {
-        self.handle_clear_screen(mode);
-    }
-
-    fn clear_line(&mut self, mode: LineClearMode) {
-        self.handle_clear_line(mode);
-    }
-
-    fn clear_tabs(&mut self, mode: TabulationClearMode) {
-        self.handle_clear_tabs(mode);
-    }
-
-    fn erase_chars(&mut self, count: usize) {
-        self.handle_erase_chars(count);
-    }
-
-    fn delete_chars(&mut self, count: usize) {
-        self.handle_delete_chars(count);
-    }
-
-    fn insert_blank(&mut self, count: usize) {
-        self.handle_insert_blank(count);
-    }
-
-    fn insert_blank_lines(&mut self, count: usize) {
-        self.handle_insert_blank_lines(count);
-    }
-
-    fn delete_lines(&mut self, count: usize) {
-        self.handle_delete_lines(count);
-    }
-
-    fn scroll_up(&mut self, count: usize) {
-        self.handle_scroll_up(count);
-    }
-
-    fn scroll_down(&mut self, count: usize) {
-        self.handle_scroll_down(count);
-    }
-
-    fn set_scrolling_region(&mut self, top: usize, bottom: Option<usize>) {
-        self.handle_set_scrolling_region(top, bottom);
-    }
-
-    fn reverse_index(&mut self) {
-        self.handle_reverse_index();
-    }
-
-    fn linefeed(&mut self) {
-        self.handle_linefeed();
-    }
-
-    fn carriage_return(&mut self) {
-        self.handle_carriage_return();
-    }
-
-    fn backspace(&mut self) {
-        self.handle_backspace();
-    }
-
-    fn newline(&mut self) {
-        self.handle_newline();
-    }
-
-    fn put_tab(&mut self, count: u16) {
-        self.handle_put_tab(count);
-    }
-
-    fn move_forward_tabs(&mut self, count: u16) {
-        self.handle_move_forward_tabs(count);
-    }
-
-    fn move_backward_tabs(&mut self, count: u16) {
-        self.handle_move_backward_tabs(count);
-    }
-
-    fn set_horizontal_tabstop(&mut self) {
-        self.handle_set_horizontal_tabstop();
-    }
-
-    fn set_mode(&mut self, mode: Mode) {
-        self.handle_set_mode(mode);
-    }
-
-    fn unset_mode(&mut self, mode: Mode) {
-        self.handle_unset_mode(mode);
-    }
-
-    fn set_private_mode(&mut self, mode: PrivateMode) {
-        self.handle_set_private_mode(mode);
-    }
-
-    fn unset_private_mode(&mut self, mode: PrivateMode) {
-        self.handle_unset_private_mode(mode);
-    }
-
-    fn set_keypad_application_mode(&mut self) {
-        self.handle_set_keypad_application_mode();
-    }
-
-    fn unset_keypad_application_mode(&mut self) {
-        self.handle_unset_keypad_application_mode();
-    }
-
-    fn report_mode(&mut self, mode: Mode) {
-        self.handle_report_mode(mode);
-    }
-
-    fn report_private_mode(&mut self, mode: PrivateMode) {
-        self.handle_report_private_mode(mode);
-    }
-
-    fn device_status(&mut self, status: usize) {
-        self.handle_device_status(status);
-    }
-
-    fn identify_terminal(&mut self, intermediate: Option<char>) {
-        self.handle_identify_terminal(intermediate);
-    }
-
-    fn text_area_size_chars(&mut self) {
-        self.handle_text_area_size_chars();
-    }
-
-    fn text_area_size_pixels(&mut self) {
-        self.handle_text_area_size_pixels();
-    }
-
-    fn bell(&mut self) {
-        self.handle_bell();
-    }
-
-    fn decaln(&mut self) {
-        self.handle_decaln();
-    }
-
-    fn reset_state(&mut self) {
-        self.handle_reset_state();
-    }
-
-    fn set_title(&mut self, title: Option<String>) {
-        self.handle_set_title(title);
-    }
-
-    fn push_title(&mut self) {
-        self.handle_push_title();
-    }
-
-    fn pop_title(&mut self) {
-        self.handle_pop_title();
-    }
-
-    fn configure_charset(&mut self, index: CharsetIndex, charset: StandardCharset) {
-        self.handle_configure_charset(index, charset);
-    }
-
-    fn set_active_charset(&mut self, index: CharsetIndex) {
-        self.handle_set_active_charset(index);
-    }
-
-    fn clipboard_store(&mut self, clipboard: u8, data: &[u8]) {
-        self.handle_clipboard_store(clipboard, data);
-    }
-
-    fn clipboard_load(&mut self, clipboard: u8, terminator: &str) {
-        self.handle_clipboard_load(clipboard, terminator);
-    }
-
-    fn substitute(&mut self) {
-        self.handle_substitute();
-    }
-
-    fn report_keyboard_mode(&mut self) {
-        self.handle_report_keyboard_mode();
-    }
-
-    fn push_keyboard_mode(&mut self, mode: KeyboardModes) {
-        self.handle_push_keyboard_mode(mode);
-    }
-
-    fn pop_keyboard_modes(&mut self, to_pop: u16) {
-        self.handle_pop_keyboard_modes(to_pop);
-    }
-
-    fn set_keyboard_mode(&mut self, mode: KeyboardModes, behavior: KeyboardModesApplyBehavior) {
-        self.handle_set_keyboard_mode(mode, behavior);
-    }
-}
+#[cfg(test)]
+mod tests;
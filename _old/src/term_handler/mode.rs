@@ -4,14 +4,39 @@ use std::time::Instant;
 
 use vte::ansi::{Mode, NamedMode, NamedPrivateMode, PrivateMode};
 
+use crate::tab::CharsetState;
 use crate::term_mode::TermMode;
 
-use super::TermHandler;
+use super::{GraphemeState, TermHandler};
+
+/// DECRPM mode status values (the `Pm` in `CSI ? Ps ; Pm $ y`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModeStatus {
+    NotRecognized = 0,
+    Set = 1,
+    Reset = 2,
+    PermanentlyReset = 4,
+}
+
+impl ModeStatus {
+    fn from_flag(mode: TermMode, flag: TermMode) -> Self {
+        if mode.contains(flag) { Self::Set } else { Self::Reset }
+    }
+}
+
+/// Grapheme cluster width mode, which vte doesn't name.
+const GRAPHEME_CLUSTER_MODE: u16 = 2027;
+
+/// ECMA-48 bi-directional support mode (BDSM), which vte doesn't name.
+const BIDI_SUPPORT_MODE: u16 = 8;
+
+/// xterm pop-on-bell mode, which vte doesn't name.
+const POP_ON_BELL_MODE: u16 = 1043;
 
 /// Maps a `NamedPrivateMode` to its DEC parameter number and `TermMode` flag.
 ///
 /// Returns `None` for modes that need special handling (e.g., alt screen swap)
-/// or are handled externally (e.g., `SyncUpdate`).
+/// or are not implemented.
 fn private_mode_flag(named: NamedPrivateMode) -> Option<(u32, TermMode)> {
     match named {
         NamedPrivateMode::CursorKeys => Some((1, TermMode::APP_CURSOR)),
@@ -22,33 +47,35 @@ fn private_mode_flag(named: NamedPrivateMode) -> Option<(u32, TermMode)> {
         NamedPrivateMode::ReportCellMouseMotion => Some((1002, TermMode::MOUSE_MOTION)),
         NamedPrivateMode::ReportAllMouseMotion => Some((1003, TermMode::MOUSE_ALL)),
         NamedPrivateMode::ReportFocusInOut => Some((1004, TermMode::FOCUS_IN_OUT)),
+        NamedPrivateMode::UrgencyHints => Some((1042, TermMode::URGENT_BELL)),
         NamedPrivateMode::Utf8Mouse => Some((1005, TermMode::UTF8_MOUSE)),
         NamedPrivateMode::SgrMouse => Some((1006, TermMode::SGR_MOUSE)),
         NamedPrivateMode::AlternateScroll => Some((1007, TermMode::ALTERNATE_SCROLL)),
         NamedPrivateMode::BracketedPaste => Some((2004, TermMode::BRACKETED_PASTE)),
+        NamedPrivateMode::SyncUpdate => Some((2026, TermMode::SYNC_UPDATE)),
         _ => None,
     }
 }
 
-/// Maps a `NamedMode` to its ANSI parameter number and `TermMode` flag.
-fn named_mode_flag(named: NamedMode) -> (u32, TermMode) {
-    match named {
-        NamedMode::Insert => (4, TermMode::INSERT),
-        NamedMode::LineFeedNewLine => (20, TermMode::LINE_FEED_NEW_LINE),
+/// Maps an ANSI mode to its parameter number and `TermMode` flag.
+fn mode_flag(mode: Mode) -> Option<(u32, TermMode)> {
+    match mode {
+        Mode::Named(NamedMode::Insert) => Some((4, TermMode::INSERT)),
+        Mode::Named(NamedMode::LineFeedNewLine) => Some((20, TermMode::LINE_FEED_NEW_LINE)),
+        Mode::Unknown(BIDI_SUPPORT_MODE) => Some((u32::from(BIDI_SUPPORT_MODE), TermMode::BIDI)),
+        Mode::Unknown(_) => None,
     }
 }
 
 impl TermHandler<'_> {
     pub(super) fn handle_set_mode(&mut self, mode: Mode) {
-        if let Mode::Named(named) = mode {
-            let (_, flag) = named_mode_flag(named);
+        if let Some((_, flag)) = mode_flag(mode) {
             self.mode.insert(flag);
         }
     }
 
     pub(super) fn handle_unset_mode(&mut self, mode: Mode) {
-        if let Mode::Named(named) = mode {
-            let (_, flag) = named_mode_flag(named);
+        if let Some((_, flag)) = mode_flag(mode) {
             self.mode.remove(flag);
         }
     }
@@ -63,6 +90,11 @@ impl TermHandler<'_> {
                     self.mode.insert(flag);
                 }
             }
+            PrivateMode::Unknown(GRAPHEME_CLUSTER_MODE) => {
+                self.mode.insert(TermMode::GRAPHEME_CLUSTERS);
+                self.grapheme.reset();
+            }
+            PrivateMode::Unknown(POP_ON_BELL_MODE) => self.mode.insert(TermMode::POP_ON_BELL),
             PrivateMode::Unknown(_) => {}
         }
     }
@@ -77,6 +109,11 @@ impl TermHandler<'_> {
                     self.mode.remove(flag);
                 }
             }
+            PrivateMode::Unknown(GRAPHEME_CLUSTER_MODE) => {
+                self.mode.remove(TermMode::GRAPHEME_CLUSTERS);
+                self.grapheme.reset();
+            }
+            PrivateMode::Unknown(POP_ON_BELL_MODE) => self.mode.remove(TermMode::POP_ON_BELL),
             PrivateMode::Unknown(_) => {}
         }
     }
@@ -91,38 +128,46 @@ impl TermHandler<'_> {
 
     pub(super) fn handle_report_mode(&mut self, mode: Mode) {
         // DECRPM response: CSI Ps; Pm $ y
-        // Pm: 1 = set, 2 = reset, 0 = not recognized
-        let (param, state) = match mode {
-            Mode::Named(named) => {
-                let (p, flag) = named_mode_flag(named);
-                (p, if self.mode.contains(flag) { 1u8 } else { 2 })
-            }
-            Mode::Unknown(n) => (n as u32, 0u8),
+        let (param, status) = match mode_flag(mode) {
+            Some((p, flag)) => (p, ModeStatus::from_flag(*self.mode, flag)),
+            None => (u32::from(mode.raw()), ModeStatus::NotRecognized),
         };
-        let response = format!("\x1b[{param};{state}$y");
+        let response = format!("\x1b[{param};{}$y", status as u8);
         self.write_pty(response.as_bytes());
     }
 
     pub(super) fn handle_report_private_mode(&mut self, mode: PrivateMode) {
         // DECRPM response: CSI ? Ps; Pm $ y
-        let (param, state) = match mode {
-            PrivateMode::Named(NamedPrivateMode::SwapScreenAndSetRestoreCursor) => {
-                let s = if self.mode.contains(TermMode::ALT_SCREEN) { 1u8 } else { 2 };
-                (1049u32, s)
-            }
-            PrivateMode::Named(named) => {
-                if let Some((p, flag)) = private_mode_flag(named) {
-                    (p, if self.mode.contains(flag) { 1u8 } else { 2 })
-                } else {
-                    return;
-                }
-            }
-            PrivateMode::Unknown(n) => (n as u32, 0u8),
+        let (param, status) = match mode {
+            PrivateMode::Named(named) => (named as u32, self.private_mode_status(named)),
+            PrivateMode::Unknown(GRAPHEME_CLUSTER_MODE) => (
+                u32::from(GRAPHEME_CLUSTER_MODE),
+                ModeStatus::from_flag(*self.mode, TermMode::GRAPHEME_CLUSTERS),
+            ),
+            PrivateMode::Unknown(POP_ON_BELL_MODE) => (
+                u32::from(POP_ON_BELL_MODE),
+                ModeStatus::from_flag(*self.mode, TermMode::POP_ON_BELL),
+            ),
+            PrivateMode::Unknown(n) => (u32::from(n), ModeStatus::NotRecognized),
         };
-        let response = format!("\x1b[?{param};{state}$y");
+        let response = format!("\x1b[?{param};{}$y", status as u8);
         self.write_pty(response.as_bytes());
     }
 
+    /// Current DECRPM status of a named private mode.
+    fn private_mode_status(&self, named: NamedPrivateMode) -> ModeStatus {
+        match named {
+            NamedPrivateMode::SwapScreenAndSetRestoreCursor => {
+                ModeStatus::from_flag(*self.mode, TermMode::ALT_SCREEN)
+            }
+            // 132-column switching is never honored.
+            NamedPrivateMode::ColumnMode => ModeStatus::PermanentlyReset,
+            named => private_mode_flag(named).map_or(ModeStatus::NotRecognized, |(_, flag)| {
+                ModeStatus::from_flag(*self.mode, flag)
+            }),
+        }
+    }
+
     pub(super) fn handle_device_status(&mut self, status: usize) {
         match status {
             // DSR 5 — Device Status Report: respond "OK"
@@ -131,15 +176,49 @@ impl TermHandler<'_> {
             }
             // DSR 6 — Cursor Position Report
             6 => {
-                let grid = self.active_grid_ref();
-                let response =
-                    format!("\x1b[{};{}R", grid.cursor.row + 1, grid.cursor.col + 1,);
-                self.write_pty(response.as_bytes());
+                let (row, col) = self.reported_cursor_position();
+                self.write_pty(format!("\x1b[{row};{col}R").as_bytes());
             }
             _ => {}
         }
     }
 
+    /// DECXCPR (`CSI ? 6 n`): like DSR 6, plus the page number (always 1).
+    ///
+    /// vte does not dispatch private DSRs, so the raw interceptor defers
+    /// this like DECSTR.
+    pub fn report_extended_cursor_position(&mut self) {
+        let (row, col) = self.reported_cursor_position();
+        self.write_pty(format!("\x1b[?{row};{col};1R").as_bytes());
+    }
+
+    /// The cursor's 1-based row and column for a position report. In
+    /// origin mode (DECOM) the row counts from the top of the scroll
+    /// region.
+    fn reported_cursor_position(&self) -> (usize, usize) {
+        let grid = self.active_grid_ref();
+        let top = if self.mode.contains(TermMode::ORIGIN) {
+            grid.scroll_top()
+        } else {
+            0
+        };
+        (grid.cursor.row.saturating_sub(top) + 1, grid.cursor.col + 1)
+    }
+
+    /// `CSI ? 1016 n`: the top-left pixel of the cursor cell, replied as
+    /// `CSI ? 1016 ; y ; x R`. Coordinates are 1-based like SGR-Pixels
+    /// (mode 1016) mouse reports, so tools can compare the two directly.
+    pub fn report_cursor_position_pixels(&mut self, cell_size: (u16, u16)) {
+        let grid = self.active_grid_ref();
+        let (cell_width, cell_height) = (usize::from(cell_size.0), usize::from(cell_size.1));
+        let response = format!(
+            "\x1b[?1016;{};{}R",
+            grid.cursor.row * cell_height + 1,
+            grid.cursor.col * cell_width + 1,
+        );
+        self.write_pty(response.as_bytes());
+    }
+
     pub(super) fn handle_identify_terminal(&mut self, intermediate: Option<char>) {
         match intermediate {
             // DA2 — Secondary Device Attributes (CSI > c)
@@ -178,13 +257,32 @@ impl TermHandler<'_> {
         self.active_grid().decaln();
     }
 
+    /// DECSTR: reset modes, margins, charsets, SGR, and the saved cursor.
+    ///
+    /// Screen contents, the active screen, and mouse/paste reporting modes
+    /// are left untouched.
+    pub(super) fn handle_soft_reset(&mut self) {
+        self.mode.remove(
+            TermMode::APP_CURSOR | TermMode::APP_KEYPAD | TermMode::ORIGIN | TermMode::INSERT,
+        );
+        self.mode.insert(TermMode::SHOW_CURSOR | TermMode::LINE_WRAP);
+        *self.charset = CharsetState::default();
+        self.grapheme.reset();
+        self.active_grid().soft_reset();
+    }
+
+    /// RIS: return to the initial state, clearing both screens, scrollback,
+    /// palette changes, and the title and keyboard mode stacks.
     pub(super) fn handle_reset_state(&mut self) {
-        self.grapheme.after_zwj = false;
-        let grid = self.active_grid();
-        grid.clear_all();
-        grid.cursor.reset_attrs();
-        *self.mode = TermMode::default();
         *self.active_is_alt = false;
+        self.grid.hard_reset();
+        self.alt_grid.hard_reset();
+        *self.mode = TermMode::default();
+        *self.charset = CharsetState::default();
+        *self.grapheme = GraphemeState::default();
+        self.palette.reset_all();
+        self.title_stack.clear();
         self.keyboard_mode_stack.clear();
+        self.inactive_keyboard_mode_stack.clear();
     }
 }
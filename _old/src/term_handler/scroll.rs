@@ -24,7 +24,7 @@ impl TermHandler<'_> {
     }
 
     pub(super) fn handle_linefeed(&mut self) {
-        self.grapheme.after_zwj = false;
+        self.grapheme.reset();
         let lf_newline = self.mode.contains(TermMode::LINE_FEED_NEW_LINE);
         let grid = self.active_grid();
         grid.linefeed();
@@ -34,17 +34,17 @@ impl TermHandler<'_> {
     }
 
     pub(super) fn handle_carriage_return(&mut self) {
-        self.grapheme.after_zwj = false;
+        self.grapheme.reset();
         self.active_grid().carriage_return();
     }
 
     pub(super) fn handle_backspace(&mut self) {
-        self.grapheme.after_zwj = false;
+        self.grapheme.reset();
         self.active_grid().backspace();
     }
 
     pub(super) fn handle_newline(&mut self) {
-        self.grapheme.after_zwj = false;
+        self.grapheme.reset();
         let grid = self.active_grid();
         grid.linefeed();
         grid.carriage_return();
@@ -0,0 +1,697 @@
+//! Handler unit tests, driven through `TerminalState::process_output`.
+
+use vte::ansi::{Color, CursorShape, Rgb};
+
+use crate::cell::CellFlags;
+use crate::grid::row::RowFlags;
+use crate::tab::{NotificationEvent, TaskProgress, TerminalState};
+use crate::term_mode::TermMode;
+
+fn state() -> TerminalState {
+    TerminalState::new(20, 5, 100, CursorShape::Block, "test".to_owned(), false)
+}
+
+/// Feed `input` and return everything the handler wrote back to the PTY.
+fn feed(term: &mut TerminalState, input: &[u8]) -> String {
+    let mut responses = Vec::new();
+    term.process_output(input, &mut responses);
+    String::from_utf8(responses).expect("responses are UTF-8")
+}
+
+#[test]
+fn decrpm_reports_set_and_reset() {
+    let mut term = state();
+    assert_eq!(feed(&mut term, b"\x1b[?2004$p"), "\x1b[?2004;2$y");
+    feed(&mut term, b"\x1b[?2004h\x1b[?1006h");
+    assert_eq!(feed(&mut term, b"\x1b[?2004$p"), "\x1b[?2004;1$y");
+    assert_eq!(feed(&mut term, b"\x1b[?1006$p"), "\x1b[?1006;1$y");
+    assert_eq!(feed(&mut term, b"\x1b[?25$p"), "\x1b[?25;1$y");
+}
+
+#[test]
+fn decrpm_tracks_alt_screen() {
+    let mut term = state();
+    assert_eq!(feed(&mut term, b"\x1b[?1049$p"), "\x1b[?1049;2$y");
+    feed(&mut term, b"\x1b[?1049h");
+    assert_eq!(feed(&mut term, b"\x1b[?1049$p"), "\x1b[?1049;1$y");
+}
+
+#[test]
+fn decrpm_sync_update_is_reset_outside_batch() {
+    let mut term = state();
+    assert_eq!(feed(&mut term, b"\x1b[?2026$p"), "\x1b[?2026;2$y");
+    let inside = feed(&mut term, b"\x1b[?2026h\x1b[?2026$p\x1b[?2026l");
+    assert_eq!(inside, "\x1b[?2026;1$y");
+}
+
+#[test]
+fn decrpm_permanent_and_unknown_modes() {
+    let mut term = state();
+    assert_eq!(feed(&mut term, b"\x1b[?3$p"), "\x1b[?3;4$y");
+    assert_eq!(feed(&mut term, b"\x1b[?4242$p"), "\x1b[?4242;0$y");
+}
+
+#[test]
+fn decstr_keeps_screen_and_resets_modes() {
+    let mut term = state();
+    feed(
+        &mut term,
+        b"hello\x1b[?1h\x1b[4h\x1b[2;4r\x1b[1;31m\x1b(0\x1b[!p",
+    );
+    assert_eq!(term.primary_grid.row(0)[0].c, 'h');
+    assert!(!term.mode.contains(TermMode::APP_CURSOR));
+    assert!(!term.mode.contains(TermMode::INSERT));
+    assert!(term.primary_grid.cursor.template.flags.is_empty());
+    // Charsets are back to ASCII: `q` is not mapped to a line-drawing glyph.
+    feed(&mut term, b"\rq");
+    assert_eq!(term.primary_grid.row(0)[0].c, 'q');
+}
+
+#[test]
+fn decstr_applies_in_stream_order() {
+    let mut term = state();
+    feed(&mut term, b"\x1b[?1h\x1b[!p\x1b[?6h");
+    assert!(!term.mode.contains(TermMode::APP_CURSOR));
+    assert!(term.mode.contains(TermMode::ORIGIN));
+}
+
+#[test]
+fn decxcpr_reports_position_and_page() {
+    let mut term = state();
+    assert_eq!(feed(&mut term, b"\x1b[3;7H\x1b[6n"), "\x1b[3;7R");
+    assert_eq!(feed(&mut term, b"\x1b[?6n"), "\x1b[?3;7;1R");
+    // Reported at its place in the stream, after the motion before it.
+    assert_eq!(feed(&mut term, b"\x1b[H\x1b[?6n\x1b[2;2H"), "\x1b[?1;1;1R");
+}
+
+#[test]
+fn cursor_reports_count_from_the_scroll_region_in_origin_mode() {
+    let mut term = state();
+    // Region rows 2-5, cursor on row 4.
+    term.active_grid_mut().set_scroll_region(1, Some(4));
+    feed(&mut term, b"\x1b[4;4H");
+    assert_eq!(feed(&mut term, b"\x1b[?6n"), "\x1b[?4;4;1R");
+    feed(&mut term, b"\x1b[?6h");
+    assert_eq!(feed(&mut term, b"\x1b[?6n"), "\x1b[?3;4;1R");
+    assert_eq!(feed(&mut term, b"\x1b[6n"), "\x1b[3;4R");
+}
+
+#[test]
+fn cursor_pixel_report_uses_cell_size() {
+    let mut term = state();
+    term.cell_size = (9, 18);
+    assert_eq!(feed(&mut term, b"\x1b[?1016n"), "\x1b[?1016;1;1R");
+    assert_eq!(feed(&mut term, b"\x1b[3;5H\x1b[?1016n"), "\x1b[?1016;37;37R");
+}
+
+#[test]
+fn ris_clears_screens_scrollback_and_stacks() {
+    let mut term = state();
+    for _ in 0..10 {
+        feed(&mut term, b"line\r\n");
+    }
+    feed(&mut term, b"\x1b[22;0t\x1b[>1u\x1b[?1049hALT");
+    assert!(!term.primary_grid.scrollback.is_empty());
+    assert!(!term.title_stack.is_empty());
+
+    feed(&mut term, b"\x1bc");
+    assert!(!term.active_is_alt);
+    assert!(term.primary_grid.scrollback.is_empty());
+    assert_eq!(term.primary_grid.row(0)[0].c, ' ');
+    assert_eq!(term.alt_grid.row(0)[0].c, ' ');
+    assert!(term.title_stack.is_empty());
+    assert!(term.keyboard_mode_stack.is_empty());
+    assert_eq!(term.mode, TermMode::default());
+}
+
+#[test]
+fn xtmodkeys_sets_and_reports_modify_other_keys() {
+    let mut term = state();
+    assert_eq!(feed(&mut term, b"\x1b[?4m"), "\x1b[>4;0m");
+    feed(&mut term, b"\x1b[>4;2m");
+    assert!(term.mode.contains(TermMode::MODIFY_ALL_KEYS));
+    assert_eq!(feed(&mut term, b"\x1b[?4m"), "\x1b[>4;2m");
+    feed(&mut term, b"\x1b[>4;1m");
+    assert!(!term.mode.contains(TermMode::MODIFY_ALL_KEYS));
+    assert_eq!(feed(&mut term, b"\x1b[?4m"), "\x1b[>4;1m");
+    feed(&mut term, b"\x1b[>4m");
+    assert!(!term.mode.intersects(TermMode::MODIFY_OTHER_KEYS | TermMode::MODIFY_ALL_KEYS));
+}
+
+#[test]
+fn enq_replies_with_answerback() {
+    let mut term = state();
+    assert_eq!(feed(&mut term, b"\x05"), "");
+    term.answerback = "ori".to_owned();
+    assert_eq!(feed(&mut term, b"a\x05b"), "ori");
+}
+
+#[test]
+fn osc_104_without_params_resets_all_indexed_colors() {
+    let mut term = state();
+    let original = [
+        term.palette.resolve(Color::Indexed(1), CellFlags::empty()),
+        term.palette
+            .resolve(Color::Indexed(100), CellFlags::empty()),
+        term.palette
+            .resolve(Color::Indexed(240), CellFlags::empty()),
+    ];
+    feed(
+        &mut term,
+        b"\x1b]4;1;rgb:12/34/56;100;rgb:12/34/56;240;rgb:12/34/56\x07",
+    );
+    let changed = term
+        .palette
+        .resolve(Color::Indexed(100), CellFlags::empty());
+    assert_eq!(
+        changed,
+        Rgb {
+            r: 0x12,
+            g: 0x34,
+            b: 0x56
+        }
+    );
+
+    feed(&mut term, b"\x1b]104\x07");
+    let restored = [
+        term.palette.resolve(Color::Indexed(1), CellFlags::empty()),
+        term.palette
+            .resolve(Color::Indexed(100), CellFlags::empty()),
+        term.palette
+            .resolve(Color::Indexed(240), CellFlags::empty()),
+    ];
+    assert_eq!(restored, original);
+}
+
+#[test]
+fn c1_csi_and_osc_are_recognized() {
+    let mut term = state();
+    term.c1_controls = true;
+    feed(&mut term, b"\x9b1;31mX\x9d2;c1 title\x9c");
+    let cell = &term.primary_grid.row(0)[0];
+    assert_eq!(cell.c, 'X');
+    assert!(cell.flags.contains(CellFlags::BOLD));
+    assert_eq!(term.title, "c1 title");
+    assert_eq!(feed(&mut term, b"\x9b?2004$p"), "\x1b[?2004;2$y");
+}
+
+#[test]
+fn c1_nel_ind_ri_move_cursor() {
+    let mut term = state();
+    term.c1_controls = true;
+    feed(&mut term, b"ab\x85");
+    assert_eq!(
+        (term.primary_grid.cursor.row, term.primary_grid.cursor.col),
+        (1, 0)
+    );
+    feed(&mut term, b"\x84");
+    assert_eq!(term.primary_grid.cursor.row, 2);
+    feed(&mut term, b"\x8d\x8d");
+    assert_eq!(term.primary_grid.cursor.row, 0);
+}
+
+#[test]
+fn stray_c1_bytes_are_text_by_default() {
+    let mut term = state();
+    // Read as C1, the DCS (0x90) would swallow the rest of the output.
+    // As invalid UTF-8 it is dropped and the text after it kept.
+    feed(&mut term, b"a\x90bc\x9bd");
+    let row = &term.primary_grid.row(0);
+    let text: String = (0..4).map(|col| row[col].c).collect();
+    assert_eq!(text, "abcd");
+}
+
+#[test]
+fn utf8_continuation_bytes_are_not_c1() {
+    let mut term = state();
+    term.c1_controls = true;
+    // U+011B (ě) is C4 9B; U+4E2D (中) is E4 B8 AD. Split the second across reads.
+    feed(&mut term, "ě".as_bytes());
+    feed(&mut term, b"\xe4");
+    feed(&mut term, b"\xb8\xad");
+    assert_eq!(term.primary_grid.row(0)[0].c, 'ě');
+    assert_eq!(term.primary_grid.row(0)[1].c, '中');
+}
+
+#[test]
+fn osc99_chunks_join_by_id() {
+    let mut term = state();
+    feed(&mut term, b"\x1b]99;i=build:d=0:a=report;Build\x1b\\");
+    assert!(term.drain_notifications().is_empty());
+    feed(
+        &mut term,
+        b"\x1b]99;i=build:p=body:c=1;done; 3 warnings\x1b\\",
+    );
+    let events = term.drain_notifications();
+    let [NotificationEvent::Show(notif)] = events.as_slice() else {
+        panic!("expected one notification, got {events:?}");
+    };
+    assert_eq!(notif.id.as_deref(), Some("build"));
+    assert_eq!(notif.title, "Build");
+    assert_eq!(notif.body, "done; 3 warnings");
+    assert!(notif.report_activation && notif.report_close);
+    assert_eq!(
+        notif.activation_report(Some(2)).as_deref(),
+        Some("\x1b]99;i=build;2\x1b\\")
+    );
+    assert_eq!(
+        notif.close_report().as_deref(),
+        Some("\x1b]99;i=build:p=close;\x1b\\")
+    );
+}
+
+#[test]
+fn osc99_buttons_base64_and_close() {
+    let mut term = state();
+    // "Hi" base64-encoded as the title.
+    feed(&mut term, b"\x1b]99;i=x:d=0:e=1;SGk=\x1b\\");
+    feed(
+        &mut term,
+        "\x1b]99;i=x:p=buttons;Yes\u{2028}No\x1b\\".as_bytes(),
+    );
+    feed(&mut term, b"\x1b]99;i=x:p=close;\x1b\\");
+    let events = term.drain_notifications();
+    let [NotificationEvent::Show(notif), NotificationEvent::Close(id)] = events.as_slice() else {
+        panic!("expected show + close, got {events:?}");
+    };
+    assert_eq!(notif.title, "Hi");
+    assert_eq!(notif.buttons, ["Yes", "No"]);
+    assert_eq!(notif.activation_report(None), None);
+    assert_eq!(id, "x");
+}
+
+#[test]
+fn osc99_capability_query() {
+    let mut term = state();
+    let reply = feed(&mut term, b"\x1b]99;i=q:p=?;\x1b\\");
+    assert!(
+        reply.starts_with("\x1b]99;i=q:p=?;a=focus,report"),
+        "{reply:?}"
+    );
+    assert!(term.drain_notifications().is_empty());
+}
+
+#[test]
+fn osc9_4_reports_progress_not_a_notification() {
+    let mut term = state();
+    feed(&mut term, b"\x1b]9;4;1;42\x07");
+    assert_eq!(term.take_progress(), Some(TaskProgress::Normal(42)));
+    assert_eq!(term.take_progress(), None);
+    assert!(term.drain_notifications().is_empty());
+
+    // Only the latest update in a chunk is kept; percent clamps to 100.
+    feed(&mut term, b"\x1b]9;4;3\x07\x1b]9;4;2;250\x07");
+    assert_eq!(term.take_progress(), Some(TaskProgress::Error(100)));
+    feed(&mut term, b"\x1b]9;4;0\x07\x1b]9;4;9;5\x07");
+    assert_eq!(term.take_progress(), Some(TaskProgress::Hidden));
+}
+
+#[test]
+fn osc6_sets_and_resets_the_tab_color() {
+    let mut term = state();
+    feed(&mut term, b"\x1b]6;1;bg;red;brightness;200\x07");
+    assert_eq!(term.tab_color, Some(Rgb { r: 200, g: 0, b: 0 }));
+    assert!(term.title_dirty);
+    feed(&mut term, b"\x1b]6;1;bg;green;brightness;100\x1b\\\x1b]6;1;bg;blue;brightness;50\x07");
+    assert_eq!(term.tab_color, Some(Rgb { r: 200, g: 100, b: 50 }));
+
+    // Malformed values leave the color alone; `default` clears it.
+    feed(&mut term, b"\x1b]6;1;bg;red;brightness;300\x07\x1b]6;1;bg;alpha;brightness;1\x07");
+    assert_eq!(term.tab_color, Some(Rgb { r: 200, g: 100, b: 50 }));
+    feed(&mut term, b"\x1b]6;1;bg;*;default\x07");
+    assert_eq!(term.tab_color, None);
+    feed(&mut term, b"\x1b]6;1;bg;alpha;brightness;1\x07");
+    assert_eq!(term.tab_color, None);
+}
+
+#[test]
+fn sgr_underline_styles_and_color() {
+    let mut term = state();
+    feed(&mut term, b"\x1b[4:3;58:2::255:0:0mA\x1b[4:2;59mB\x1b[21mC\x1b[4:0mD");
+    let row = term.primary_grid.row(0);
+    assert!(row[0].flags.contains(CellFlags::UNDERCURL));
+    assert_eq!(row[0].underline_color(), Some(Color::Spec(Rgb { r: 255, g: 0, b: 0 })));
+    assert!(row[1].flags.contains(CellFlags::DOUBLE_UNDERLINE));
+    assert!(!row[1].flags.contains(CellFlags::UNDERCURL));
+    assert_eq!(row[1].underline_color(), None);
+    assert!(row[2].flags.intersects(CellFlags::ANY_UNDERLINE));
+    assert!(!row[3].flags.intersects(CellFlags::ANY_UNDERLINE));
+}
+
+#[test]
+fn sgr_reset_keeps_hyperlink() {
+    let mut term = state();
+    feed(&mut term, b"\x1b]8;;https://example.com\x1b\\\x1b[58;5;1m\x1b[0mA");
+    let cell = &term.primary_grid.row(0)[0];
+    assert_eq!(cell.underline_color(), None);
+    assert_eq!(cell.hyperlink().map(|h| h.uri.as_str()), Some("https://example.com"));
+}
+
+#[test]
+fn osc8_cells_with_one_id_share_a_link() {
+    let mut term = state();
+    // Two regions with id=a (on different rows), one id-less link twice.
+    feed(
+        &mut term,
+        b"\x1b]8;id=a;https://a.example\x1b\\ab\x1b]8;;\x1b\\ \
+          \x1b]8;;https://a.example\x1b\\cd\x1b]8;;\x1b\\ \
+          \x1b]8;;https://a.example\x1b\\ef\x1b]8;;\x1b\\\r\n\
+          \x1b]8;id=a;https://a.example\x1b\\gh\x1b]8;;\x1b\\",
+    );
+    let link = |row: usize, col: usize| term.primary_grid.row(row)[col].hyperlink().map(|h| h.id);
+    assert_eq!(link(0, 0), link(0, 1));
+    assert_eq!(link(0, 0), link(1, 0));
+    assert_eq!(link(0, 2), None);
+    assert_eq!(link(0, 3), link(0, 4));
+    assert_ne!(link(0, 3), link(0, 6));
+    assert_ne!(link(0, 0), link(0, 3));
+}
+
+#[test]
+fn color_queries_report_current_palette() {
+    let mut term = state();
+    feed(&mut term, b"\x1b]4;1;rgb:12/34/56\x07\x1b]11;#abcdef\x07");
+    assert_eq!(feed(&mut term, b"\x1b]4;1;?\x07"), "\x1b]4;1;rgb:1212/3434/5656\x07");
+    assert_eq!(
+        feed(&mut term, b"\x1b]11;?\x1b\\"),
+        "\x1b]11;rgb:abab/cdcd/efef\x1b\\"
+    );
+    // One OSC 10 can query the following dynamic colors too.
+    let fg = term.palette.default_fg();
+    let reply = feed(&mut term, b"\x1b]10;?;?\x07");
+    assert_eq!(
+        reply,
+        format!(
+            "\x1b]10;rgb:{0:02x}{0:02x}/{1:02x}{1:02x}/{2:02x}{2:02x}\x07\
+             \x1b]11;rgb:abab/cdcd/efef\x07",
+            fg.r, fg.g, fg.b
+        )
+    );
+}
+
+#[test]
+fn title_stack_depth_is_capped() {
+    let mut term = state();
+    feed(&mut term, b"\x1b]2;first\x07\x1b[22t\x1b]2;second\x07");
+    for _ in 0..super::title::TITLE_STACK_DEPTH {
+        feed(&mut term, b"\x1b[22t");
+    }
+    assert_eq!(term.title_stack.len(), super::title::TITLE_STACK_DEPTH);
+    // The oldest entry ("first") was dropped to make room.
+    assert!(term.title_stack.iter().all(|t| t == "second"));
+}
+
+#[test]
+fn osc52_store_is_queued_for_the_main_thread() {
+    let mut term = state();
+    // "hi" then "there": only the latest write is kept.
+    feed(&mut term, b"\x1b]52;c;aGk=\x07\x1b]52;c;dGhlcmU=\x07");
+    assert_eq!(term.clipboard_request.take().as_deref(), Some("there"));
+    // Invalid base64 is ignored.
+    feed(&mut term, b"\x1b]52;c;!!!\x07");
+    assert_eq!(term.clipboard_request, None);
+}
+
+#[test]
+fn sgr_overline_applies_in_stream_order() {
+    let mut term = state();
+    feed(&mut term, b"a\x1b[1;53mb\x1b[38;5;55mc\x1b[55md\x1b[53;0me\x1b[53m\x1b[0mf");
+    let overlined: Vec<bool> = (0..6)
+        .map(|col| term.primary_grid.row(0)[col].flags.contains(CellFlags::OVERLINE))
+        .collect();
+    assert_eq!(overlined, [false, true, true, false, false, false]);
+    assert!(term.primary_grid.row(0)[1].flags.contains(CellFlags::BOLD));
+}
+
+#[test]
+fn deferred_sequences_split_across_reads_apply_in_order() {
+    let mut term = state();
+    feed(&mut term, b"a\x1b[5");
+    feed(&mut term, b"3mb\x1b]13");
+    feed(&mut term, b"3;A\x07");
+    let row = term.primary_grid.row(0);
+    assert!(!row[0].flags.contains(CellFlags::OVERLINE));
+    assert!(row[1].flags.contains(CellFlags::OVERLINE));
+    assert!(row.flags.contains(RowFlags::PROMPT_START));
+}
+
+#[test]
+fn deferred_sequences_keep_their_place_in_a_synchronized_update() {
+    let mut term = state();
+    feed(&mut term, b"\x1b[?2026ha\x1b[53mb\x1b[55mc");
+    assert!(term.mode.contains(TermMode::SYNC_UPDATE));
+    feed(&mut term, b"\x1b[?2026l");
+    let overlined: Vec<bool> = (0..3)
+        .map(|col| term.primary_grid.row(0)[col].flags.contains(CellFlags::OVERLINE))
+        .collect();
+    assert_eq!(overlined, [false, true, false]);
+    assert!(!term.mode.contains(TermMode::SYNC_UPDATE));
+}
+
+#[test]
+fn sgr_strikeout_set_and_cleared() {
+    let mut term = state();
+    feed(&mut term, b"\x1b[9ma\x1b[29mb");
+    assert!(term.primary_grid.row(0)[0].flags.contains(CellFlags::STRIKEOUT));
+    assert!(!term.primary_grid.row(0)[1].flags.contains(CellFlags::STRIKEOUT));
+}
+
+#[test]
+fn sgr_conceal_set_and_reset() {
+    let mut term = state();
+    feed(&mut term, b"\x1b[8ma\x1b[28mb\x1b[8mc\x1b[0md");
+    let row = term.primary_grid.row(0);
+    assert!(row[0].flags.contains(CellFlags::HIDDEN));
+    assert!(!row[1].flags.contains(CellFlags::HIDDEN));
+    assert!(row[2].flags.contains(CellFlags::HIDDEN));
+    assert!(!row[3].flags.contains(CellFlags::HIDDEN));
+    assert_eq!(row[0].c, 'a');
+}
+
+#[test]
+fn sgr_blink_slow_and_fast() {
+    let mut term = state();
+    feed(&mut term, b"\x1b[5ma\x1b[6mb\x1b[25mc");
+    let row = term.primary_grid.row(0);
+    assert_eq!(row[0].flags & CellFlags::ANY_BLINK, CellFlags::BLINK);
+    assert_eq!(row[1].flags & CellFlags::ANY_BLINK, CellFlags::BLINK_FAST);
+    assert!(!row[2].flags.intersects(CellFlags::ANY_BLINK));
+}
+
+#[test]
+fn grapheme_zwj_family_occupies_one_wide_cell() {
+    let mut term = state();
+    feed(&mut term, "👩\u{200D}👩\u{200D}👧x".as_bytes());
+    let grid = &term.primary_grid;
+    let row = grid.row(0);
+    assert_eq!(row[0].c, '👩');
+    assert_eq!(row[0].zerowidth(), &['\u{200D}', '👩', '\u{200D}', '👧']);
+    assert!(row[0].flags.contains(CellFlags::WIDE_CHAR));
+    assert_eq!(row[2].c, 'x');
+    assert_eq!(grid.cursor.col, 3);
+}
+
+#[test]
+fn grapheme_regional_indicator_pairs_form_wide_flags() {
+    let mut term = state();
+    // Three flags: 🇺🇸 🇫🇷 🇯🇵, then a marker.
+    feed(&mut term, "🇺🇸🇫🇷🇯🇵.".as_bytes());
+    let row = term.primary_grid.row(0);
+    for (col, (base, second)) in [(0, ('🇺', '🇸')), (2, ('🇫', '🇷')), (4, ('🇯', '🇵'))] {
+        assert_eq!(row[col].c, base);
+        assert_eq!(row[col].zerowidth(), &[second]);
+        assert!(row[col].flags.contains(CellFlags::WIDE_CHAR));
+        assert!(row[col + 1].flags.contains(CellFlags::WIDE_CHAR_SPACER));
+    }
+    assert_eq!(row[6].c, '.');
+}
+
+#[test]
+fn grapheme_modifiers_and_combining_marks_join() {
+    let mut term = state();
+    feed(&mut term, "👍\u{1F3FD}e\u{0301}a".as_bytes());
+    let row = term.primary_grid.row(0);
+    assert_eq!(row[0].zerowidth(), &['\u{1F3FD}']);
+    assert_eq!(row[2].c, 'e');
+    assert_eq!(row[2].zerowidth(), &['\u{0301}']);
+    assert_eq!(row[3].c, 'a');
+    assert!(row[3].zerowidth().is_empty());
+}
+
+#[test]
+fn grapheme_cluster_ends_at_cursor_motion() {
+    let mut term = state();
+    // A regional indicator, a cursor move, then another: no pairing.
+    feed(&mut term, "🇺\x1b[5G🇸".as_bytes());
+    let row = term.primary_grid.row(0);
+    assert!(row[0].zerowidth().is_empty());
+    assert_eq!(row[4].c, '🇸');
+}
+
+#[test]
+fn grapheme_widening_at_last_column_wraps() {
+    let mut term = state();
+    let cols = term.primary_grid.cols;
+    feed(&mut term, format!("\x1b[{cols}G🇺🇸").as_bytes());
+    let grid = &term.primary_grid;
+    assert!(grid.row(0)[cols - 1].flags.contains(CellFlags::LEADING_WIDE_CHAR_SPACER));
+    assert!(grid.row(0)[cols - 1].zerowidth().is_empty());
+    assert_eq!(grid.row(1)[0].c, '🇺');
+    assert_eq!(grid.row(1)[0].zerowidth(), &['🇸']);
+}
+
+#[test]
+fn widening_keeps_the_cells_own_extras() {
+    let mut term = state();
+    // The heart is written with a link and underline color that end before
+    // its VS16 arrives.
+    feed(&mut term, "\x1b]8;;https://a.example\x1b\\\x1b[58;5;1m❤\x1b[59m\x1b]8;;\x1b\\\u{FE0F}".as_bytes());
+    let cell = &term.primary_grid.row(0)[0];
+    assert!(cell.flags.contains(CellFlags::WIDE_CHAR));
+    assert_eq!(cell.zerowidth(), &['\u{FE0F}']);
+    assert!(cell.hyperlink().is_some());
+    assert!(cell.underline_color().is_some());
+}
+
+#[test]
+fn a_flood_of_combining_marks_is_capped() {
+    let mut term = state();
+    let marks = "\u{0301}".repeat(1000);
+    feed(&mut term, format!("e{marks}x\x1b[5G{marks}").as_bytes());
+    let row = term.primary_grid.row(0);
+    assert_eq!(row[0].zerowidth().len(), super::input::MAX_CLUSTER_CHARS - 1);
+    assert_eq!(row[1].c, 'x');
+    assert_eq!(row[3].zerowidth().len(), super::input::MAX_CLUSTER_CHARS);
+}
+
+#[test]
+fn vs16_emoji_presentation_takes_two_cells() {
+    let mut term = state();
+    // ❤ is narrow on its own; VS16 makes it a two-cell emoji, also when
+    // the VS16 arrives after the cluster was closed.
+    feed(&mut term, "❤\u{FE0F}x\r\n❤\x1b[?2027h\u{FE0F}x".as_bytes());
+    for line in 0..2 {
+        let row = term.primary_grid.row(line);
+        assert_eq!(row[0].c, '❤');
+        assert_eq!(row[0].zerowidth(), &['\u{FE0F}']);
+        assert!(row[0].flags.contains(CellFlags::WIDE_CHAR));
+        assert!(row[1].flags.contains(CellFlags::WIDE_CHAR_SPACER));
+        assert_eq!(row[2].c, 'x');
+    }
+
+    // With mode 2027 reset VS16 is zero-width and the heart stays narrow.
+    feed(&mut term, "\x1b[?2027l\r\n❤\u{FE0F}z".as_bytes());
+    let row = term.primary_grid.row(2);
+    assert!(!row[0].flags.contains(CellFlags::WIDE_CHAR));
+    assert_eq!(row[1].c, 'z');
+}
+
+#[test]
+fn mode_2027_is_reported_and_toggles_width_path() {
+    let mut term = state();
+    assert_eq!(feed(&mut term, b"\x1b[?2027$p"), "\x1b[?2027;1$y");
+
+    // Reset: legacy per-code-point widths, so a flag takes two narrow cells.
+    feed(&mut term, "\x1b[?2027l🇺🇸".as_bytes());
+    assert_eq!(feed(&mut term, b"\x1b[?2027$p"), "\x1b[?2027;2$y");
+    let row = term.primary_grid.row(0);
+    assert_eq!(row[0].c, '🇺');
+    assert_eq!(row[1].c, '🇸');
+    assert!(row[0].zerowidth().is_empty());
+
+    // Set again: the pair forms one wide cluster.
+    feed(&mut term, "\x1b[?2027h\r\n🇺🇸".as_bytes());
+    let row = term.primary_grid.row(1);
+    assert_eq!(row[0].zerowidth(), &['🇸']);
+    assert!(row[0].flags.contains(CellFlags::WIDE_CHAR));
+}
+
+#[test]
+fn bdsm_mode_8_toggles_bidi_reordering() {
+    let mut term = state();
+    assert!(term.mode.contains(TermMode::BIDI));
+    assert_eq!(feed(&mut term, b"\x1b[8$p"), "\x1b[8;1$y");
+
+    // An application doing its own reordering switches to explicit mode.
+    feed(&mut term, b"\x1b[8l");
+    assert!(!term.mode.contains(TermMode::BIDI));
+    assert_eq!(feed(&mut term, b"\x1b[8$p"), "\x1b[8;2$y");
+
+    feed(&mut term, b"\x1b[8h");
+    assert!(term.mode.contains(TermMode::BIDI));
+}
+
+#[test]
+fn ris_restores_mode_2027() {
+    let mut term = state();
+    feed(&mut term, b"\x1b[?2027l\x1bc");
+    assert_eq!(feed(&mut term, b"\x1b[?2027$p"), "\x1b[?2027;1$y");
+}
+
+#[test]
+fn bell_modes_1042_1043_toggle_and_report() {
+    let mut term = state();
+    assert_eq!(feed(&mut term, b"\x1b[?1042$p"), "\x1b[?1042;2$y");
+    feed(&mut term, b"\x1b[?1042;1043h");
+    assert!(term.mode.contains(TermMode::URGENT_BELL | TermMode::POP_ON_BELL));
+    assert_eq!(feed(&mut term, b"\x1b[?1043$p"), "\x1b[?1043;1$y");
+    feed(&mut term, b"\x1b[?1043l");
+    assert!(!term.mode.contains(TermMode::POP_ON_BELL));
+
+    // Each bell is reported once.
+    assert!(!term.take_new_bell());
+    feed(&mut term, b"\x07");
+    assert!(term.take_new_bell());
+    assert!(!term.take_new_bell());
+}
+
+#[test]
+fn osc66_writes_scaled_blocks_and_moves_past_them() {
+    let mut term = state();
+    feed(&mut term, b"a\x1b]66;s=2;Hi\x07b");
+    let top = term.primary_grid.row(0);
+    assert_eq!(top[1].c, 'H');
+    assert_eq!(top[1].text_scale(), 2);
+    assert!(top[2].flags.contains(CellFlags::WIDE_CHAR_SPACER));
+    assert_eq!(top[3].c, 'i');
+    assert_eq!(top[5].c, 'b');
+    assert_eq!(top[5].text_scale(), 1);
+    assert_eq!(term.primary_grid.row(1)[1].c, ' ');
+
+    // An explicit width, an ST terminator, and a `;` inside the text.
+    feed(&mut term, b"\r\n\r\n\x1b]66;w=3:s=1;x;y\x1b\\");
+    let row = term.primary_grid.row(2);
+    assert_eq!(row[0].c, 'x');
+    assert_eq!(row[3].c, ';');
+    assert_eq!(row[6].c, 'y');
+    assert_eq!(term.primary_grid.cursor.col, 9);
+}
+
+#[test]
+fn osc66_keeps_combining_marks_on_their_base() {
+    let mut term = state();
+    feed(&mut term, "\x1b]66;s=2;e\u{301}x\x07".as_bytes());
+    let top = term.primary_grid.row(0);
+    assert_eq!(top[0].c, 'e');
+    assert_eq!(top[0].zerowidth(), ['\u{301}']);
+    assert_eq!(top[2].c, 'x');
+    assert_eq!(term.primary_grid.cursor.col, 4);
+}
+
+#[test]
+fn osc133_marks_the_row_where_each_phase_starts() {
+    let mut term = state();
+    feed(&mut term, b"\x1b]133;A\x07$ ls\x1b]133;B\x07\r\n\x1b]133;C\x07out\r\n");
+    feed(&mut term, b"out\r\n\x1b]133;D;2\x07");
+    let flags = |row: usize| term.primary_grid.row(row).flags;
+    assert_eq!(flags(0), RowFlags::PROMPT_START | RowFlags::COMMAND_START);
+    assert_eq!(flags(1), RowFlags::OUTPUT_START);
+    assert_eq!(flags(2), RowFlags::empty());
+    assert_eq!(flags(3), RowFlags::COMMAND_END);
+    let meta = term.primary_grid.row(3).meta.as_deref().copied();
+    assert_eq!(meta.and_then(|m| m.exit_status), Some(2));
+    // The prompt row carries the outcome, timed from B to D.
+    let command = term.primary_grid.row(0).meta.as_deref().and_then(|m| m.command);
+    assert_eq!(command.and_then(|c| c.exit_code), Some(2));
+    assert!(command.is_some_and(|c| c.duration_ms.is_some()));
+}
@@ -0,0 +1,84 @@
+//! Kitty text sizing protocol (OSC 66), experimental.
+//!
+//! `OSC 66 ; s=<scale>:w=<width> ; text ST` draws `text` `scale` times
+//! larger: each character takes a block `scale` rows tall and
+//! `scale × width` columns wide, where `width` defaults to the character's
+//! own cell width. Fractional scales (`n`, `d`) and alignment (`v`, `h`)
+//! are ignored. The cursor ends on the block's top row just past the text,
+//! as in kitty.
+
+use unicode_width::UnicodeWidthChar;
+
+use super::TermHandler;
+use super::input::MAX_CLUSTER_CHARS;
+
+/// Largest scale and explicit width the protocol allows.
+const MAX_SCALE: u8 = 7;
+
+/// A parsed OSC 66 request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextSizing {
+    /// Block height in rows, 1–7.
+    pub scale: u8,
+    /// Cells per character before scaling; 0 uses each character's width.
+    pub width: u8,
+    pub text: String,
+}
+
+impl TextSizing {
+    /// Parse the `key=value:…` metadata and the text of an OSC 66. Unknown
+    /// keys are skipped; `None` if the text isn't UTF-8.
+    pub fn parse(metadata: &[u8], text: &[u8]) -> Option<Self> {
+        let mut sizing = Self {
+            scale: 1,
+            width: 0,
+            text: std::str::from_utf8(text).ok()?.to_owned(),
+        };
+        for pair in metadata.split(|&b| b == b':') {
+            let Some(eq) = pair.iter().position(|&b| b == b'=') else {
+                continue;
+            };
+            let value = std::str::from_utf8(&pair[eq + 1..])
+                .ok()
+                .and_then(|v| v.parse::<u8>().ok());
+            match (&pair[..eq], value) {
+                (b"s", Some(v)) => sizing.scale = v.clamp(1, MAX_SCALE),
+                (b"w", Some(v)) => sizing.width = v.min(MAX_SCALE),
+                _ => {}
+            }
+        }
+        Some(sizing)
+    }
+}
+
+impl TermHandler<'_> {
+    /// Write OSC 66 text at the cursor, one scaled block per character.
+    /// Zero-width characters join the block before them.
+    ///
+    /// vte does not parse OSC 66, so the raw interceptor defers it like
+    /// DECSTR.
+    pub fn write_sized_text(&mut self, sizing: &TextSizing) {
+        self.grapheme.reset();
+        let scale = usize::from(sizing.scale);
+        let grid = self.active_grid();
+        let mut base = None;
+        for c in sizing.text.chars() {
+            let width = UnicodeWidthChar::width(c).unwrap_or(0);
+            if width == 0 {
+                if let Some(col) = base {
+                    let row = grid.cursor.row;
+                    let cell = &mut grid.row_mut(row)[col];
+                    if cell.zerowidth().len() < MAX_CLUSTER_CHARS {
+                        cell.push_zerowidth(c);
+                    }
+                }
+                continue;
+            }
+            let width = match sizing.width {
+                0 => width,
+                w => usize::from(w),
+            };
+            base = grid.put_scaled_char(c, scale, width * scale);
+        }
+    }
+}
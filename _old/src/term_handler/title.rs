@@ -1,10 +1,19 @@
-//! Title, charset, clipboard, and keyboard mode operations.
+//! Title, charset, clipboard, and keyboard mode operations (Kitty
+//! keyboard protocol and xterm modifyOtherKeys).
 
 use base64::engine::general_purpose::STANDARD as BASE64;
 use base64::Engine;
-use vte::ansi::{CharsetIndex, KeyboardModes, KeyboardModesApplyBehavior, StandardCharset};
+use vte::ansi::{
+    CharsetIndex, KeyboardModes, KeyboardModesApplyBehavior, ModifyOtherKeys, StandardCharset,
+};
 
 use super::TermHandler;
+use crate::term_mode::TermMode;
+
+/// Deepest title stack kept (XTWINOPS 22/23), as in xterm. Pushing past it
+/// drops the oldest entry, so a program that pushes in a loop can't grow it
+/// forever.
+pub(super) const TITLE_STACK_DEPTH: usize = 10;
 
 impl TermHandler<'_> {
     pub(super) fn handle_set_title(&mut self, title: Option<String>) {
@@ -19,11 +28,14 @@ impl TermHandler<'_> {
     }
 
     pub(super) fn handle_push_title(&mut self) {
-        self.title_stack.push(self.title.clone());
+        if self.title_stack.len() >= TITLE_STACK_DEPTH {
+            self.title_stack.pop_front();
+        }
+        self.title_stack.push_back(self.title.clone());
     }
 
     pub(super) fn handle_pop_title(&mut self) {
-        if let Some(t) = self.title_stack.pop() {
+        if let Some(t) = self.title_stack.pop_back() {
             *self.title = t;
             *self.title_dirty = true;
         }
@@ -43,13 +55,14 @@ impl TermHandler<'_> {
         self.charset.active = index;
     }
 
-    #[allow(clippy::needless_pass_by_ref_mut, reason = "Called via Handler trait which requires &mut self")]
     pub(super) fn handle_clipboard_store(&mut self, _clipboard: u8, data: &[u8]) {
         // OSC 52 clipboard store: data is base64-encoded text from the application.
-        // Selector byte (_clipboard) maps c/p/s — all go to system clipboard on Windows.
+        // Selector byte (_clipboard) maps c/p/s — all go to the system clipboard.
+        // The write is queued: the main thread applies the size limit and
+        // the tab's `terminal.osc52` policy before touching the clipboard.
         if let Ok(decoded) = BASE64.decode(data) {
             if let Ok(text) = String::from_utf8(decoded) {
-                crate::clipboard::set_text(&text);
+                *self.clipboard_request = Some(text);
             }
         }
     }
@@ -115,4 +128,27 @@ impl TermHandler<'_> {
         }
         self.apply_keyboard_mode();
     }
+
+    /// XTMODKEYS (`CSI > 4 ; Pv m`): select the modifyOtherKeys level.
+    pub(super) fn handle_set_modify_other_keys(&mut self, level: ModifyOtherKeys) {
+        self.mode.remove(TermMode::MODIFY_OTHER_KEYS | TermMode::MODIFY_ALL_KEYS);
+        match level {
+            ModifyOtherKeys::Reset => {}
+            ModifyOtherKeys::EnableExceptWellDefined => {
+                self.mode.insert(TermMode::MODIFY_OTHER_KEYS);
+            }
+            ModifyOtherKeys::EnableAll => self.mode.insert(TermMode::MODIFY_ALL_KEYS),
+        }
+    }
+
+    /// XTQMODKEYS (`CSI ? 4 m`): reply `CSI > 4 ; Pv m` with the current
+    /// modifyOtherKeys level.
+    pub(super) fn handle_report_modify_other_keys(&mut self) {
+        let level = if self.mode.contains(TermMode::MODIFY_ALL_KEYS) {
+            2
+        } else {
+            u8::from(self.mode.contains(TermMode::MODIFY_OTHER_KEYS))
+        };
+        self.write_pty(format!("\x1b[>4;{level}m").as_bytes());
+    }
 }
@@ -35,6 +35,34 @@ bitflags! {
             | Self::REPORT_ALTERNATE_KEYS.bits()
             | Self::REPORT_ALL_KEYS_AS_ESC.bits()
             | Self::REPORT_ASSOCIATED_TEXT.bits();
+
+        /// Synchronized output (mode 2026) — set between BSU and ESU.
+        const SYNC_UPDATE             = 1 << 21;
+
+        /// Grapheme cluster width (mode 2027): whole clusters, not single
+        /// code points, decide how many cells a character takes.
+        const GRAPHEME_CLUSTERS       = 1 << 22;
+
+        /// Bi-directional support mode (BDSM, ECMA-48 mode 8): when set the
+        /// terminal reorders right-to-left text for display; applications
+        /// that reorder it themselves reset it.
+        const BIDI                    = 1 << 23;
+
+        /// Bell is urgent (xterm mode 1042): a bell in a background window
+        /// asks for attention even with `bell.urgent` off.
+        const URGENT_BELL             = 1 << 24;
+
+        /// Pop on bell (xterm mode 1043): a bell raises its window.
+        const POP_ON_BELL             = 1 << 25;
+
+        /// xterm modifyOtherKeys level 1 (`CSI > 4 ; 1 m`): modified keys
+        /// without a well-known legacy encoding are sent as
+        /// `CSI 27 ; mod ; code ~`.
+        const MODIFY_OTHER_KEYS       = 1 << 26;
+
+        /// xterm modifyOtherKeys level 2 (`CSI > 4 ; 2 m`): every modified
+        /// key except plain Shift+character is sent as `CSI 27 ; mod ; code ~`.
+        const MODIFY_ALL_KEYS         = 1 << 27;
     }
 }
 
@@ -69,6 +97,6 @@ impl From<KeyboardModes> for TermMode {
 
 impl Default for TermMode {
     fn default() -> Self {
-        Self::LINE_WRAP | Self::SHOW_CURSOR
+        Self::LINE_WRAP | Self::SHOW_CURSOR | Self::GRAPHEME_CLUSTERS | Self::BIDI
     }
 }
@@ -0,0 +1,9 @@
+//! Widgets for oriterm's own UI: the search bar, the settings window, and
+//! the tab switcher.
+
+pub mod settings_layout;
+pub mod tab_switcher;
+pub mod text_input;
+
+#[cfg(test)]
+mod tests;
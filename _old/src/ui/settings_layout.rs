@@ -0,0 +1,154 @@
+//! Settings window layout: which options each page shows for the current
+//! filter, and where they sit.
+//!
+//! The layout is recomputed only when the page or the filter text changes,
+//! and both the renderer and mouse hit-testing read from the same
+//! [`SettingsLayout`], so filtered rows are drawn and clicked at the same
+//! positions.
+
+use crate::palette::contrast::ANSI_NAMES;
+use crate::palette::{BUILTIN_SCHEMES, ColorScheme};
+
+/// Title bar height (pixels).
+pub const TITLE_HEIGHT: f32 = 50.0;
+
+/// Height of the filter box band below the title bar (pixels).
+pub const FILTER_HEIGHT: f32 = 36.0;
+
+/// Width of each page tab label in the title bar (pixels, after a 16px
+/// margin).
+pub const PAGE_TAB_WIDTH: f32 = 80.0;
+
+/// Theme list row height, also used for the contrast toggle (pixels).
+pub const THEME_ROW_HEIGHT: f32 = 40.0;
+
+/// Contrast audit row height (pixels). The colors fill two columns.
+pub const CONTRAST_ROW_HEIGHT: f32 = 28.0;
+
+/// Space between the contrast audit and the toggle below it (pixels).
+pub const CONTRAST_TOGGLE_GAP: f32 = 8.0;
+
+/// Label of the minimum-contrast toggle.
+pub const CONTRAST_TOGGLE_LABEL: &str = "Enforce 4.5:1 minimum";
+
+/// A page of the settings window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SettingsPage {
+    /// Color scheme picker.
+    #[default]
+    Theme,
+    /// WCAG contrast of each ANSI color against the background.
+    Contrast,
+}
+
+/// Page tabs in title bar order.
+pub const PAGES: [(SettingsPage, &str); 2] = [
+    (SettingsPage::Theme, "Theme"),
+    (SettingsPage::Contrast, "Contrast"),
+];
+
+/// What a click in the settings window landed on.
+#[derive(Debug, Clone, Copy)]
+pub enum SettingsHit {
+    /// A page tab in the title bar.
+    Page(SettingsPage),
+    /// A row of the theme list.
+    Scheme(&'static ColorScheme),
+    /// The minimum-contrast toggle.
+    ContrastToggle,
+}
+
+/// The options visible on one page under one filter.
+#[derive(Debug, Clone, Default)]
+pub struct SettingsLayout {
+    /// The page shown.
+    pub page: SettingsPage,
+    /// Theme rows, top to bottom.
+    pub schemes: Vec<&'static ColorScheme>,
+    /// ANSI indices of the contrast rows, filling the left column first.
+    pub colors: Vec<usize>,
+    /// Whether the minimum-contrast toggle matches the filter.
+    pub show_toggle: bool,
+}
+
+impl SettingsLayout {
+    /// Lay out `page`, keeping the options whose label contains `filter`
+    /// (ignoring case).
+    pub fn compute(page: SettingsPage, filter: &str) -> Self {
+        let needle = filter.trim().to_lowercase();
+        let matches = |label: &str| label.to_lowercase().contains(&needle);
+        let mut layout = Self {
+            page,
+            ..Self::default()
+        };
+        match page {
+            SettingsPage::Theme => {
+                layout.schemes = BUILTIN_SCHEMES
+                    .iter()
+                    .copied()
+                    .filter(|s| matches(s.name))
+                    .collect();
+            }
+            SettingsPage::Contrast => {
+                layout.colors = (0..ANSI_NAMES.len())
+                    .filter(|&i| matches(ANSI_NAMES[i]) || i.to_string() == needle)
+                    .collect();
+                layout.show_toggle = matches(CONTRAST_TOGGLE_LABEL);
+            }
+        }
+        layout
+    }
+
+    /// Top of the page body, below the title bar and filter box.
+    pub fn content_top() -> f32 {
+        TITLE_HEIGHT + FILTER_HEIGHT
+    }
+
+    /// Rows per column of the contrast audit.
+    pub fn color_rows(&self) -> usize {
+        self.colors.len().div_ceil(2)
+    }
+
+    /// Column and row of the `pos`-th visible contrast entry.
+    pub fn color_slot(&self, pos: usize) -> (usize, usize) {
+        let rows = self.color_rows().max(1);
+        (pos / rows, pos % rows)
+    }
+
+    /// Top of the minimum-contrast toggle, when it is shown.
+    pub fn toggle_top(&self) -> Option<f32> {
+        let rows = self.color_rows() as f32;
+        let gap = if self.colors.is_empty() {
+            0.0
+        } else {
+            CONTRAST_TOGGLE_GAP
+        };
+        self.show_toggle
+            .then_some(Self::content_top() + rows * CONTRAST_ROW_HEIGHT + gap)
+    }
+
+    /// What sits at (`x`, `y`), in window pixels.
+    pub fn hit(&self, x: f32, y: f32) -> Option<SettingsHit> {
+        if y < TITLE_HEIGHT {
+            let tab = ((x - 16.0) / PAGE_TAB_WIDTH).floor();
+            return (tab >= 0.0)
+                .then(|| PAGES.get(tab as usize))
+                .flatten()
+                .map(|&(page, _)| SettingsHit::Page(page));
+        }
+        let body_y = y - Self::content_top();
+        if body_y < 0.0 {
+            return None;
+        }
+        match self.page {
+            SettingsPage::Theme => {
+                let row = (body_y / THEME_ROW_HEIGHT) as usize;
+                self.schemes.get(row).map(|&s| SettingsHit::Scheme(s))
+            }
+            SettingsPage::Contrast => self
+                .toggle_top()
+                .filter(|top| (*top..top + THEME_ROW_HEIGHT).contains(&y))
+                .map(|_| SettingsHit::ContrastToggle),
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! The tab switcher: every tab in every window in one list, narrowed by
+//! fuzzy matching as the user types, with the last few lines of each
+//! tab's screen as a preview.
+//!
+//! A query matches a tab when its characters appear in order in the tab's
+//! title or directory. Matches are ranked by how tightly the characters
+//! cluster and whether they start words, so `vp` ranks "vim — ~/project"
+//! above "envelope".
+
+use std::cmp::Reverse;
+
+use crate::tab::TabId;
+
+use super::text_input::TextInput;
+
+/// Score bonus for a matched character right after the previous match.
+const CONSECUTIVE_BONUS: u32 = 8;
+/// Score bonus for a matched character at the start of a word.
+const WORD_START_BONUS: u32 = 6;
+/// Score for any matched character.
+const MATCH_SCORE: u32 = 1;
+
+/// One tab as the switcher lists it.
+#[derive(Debug, Clone)]
+pub struct SwitcherEntry {
+    pub tab: TabId,
+    pub title: String,
+    /// Working directory reported by the shell (OSC 7), if any.
+    pub cwd: Option<String>,
+    /// The last non-blank lines of the tab's screen, oldest first.
+    pub preview: Vec<String>,
+}
+
+/// An open tab switcher.
+#[derive(Debug, Default)]
+pub struct TabSwitcher {
+    /// The filter query.
+    pub input: TextInput,
+    entries: Vec<SwitcherEntry>,
+    /// Indices into `entries` that match the query, best first.
+    shown: Vec<usize>,
+    /// Index into `shown` of the highlighted entry.
+    selected: usize,
+}
+
+impl TabSwitcher {
+    /// A switcher listing `entries` in order, with entry `selected`
+    /// highlighted.
+    pub fn new(entries: Vec<SwitcherEntry>, selected: usize) -> Self {
+        let shown = (0..entries.len()).collect();
+        Self {
+            input: TextInput::default(),
+            entries,
+            shown,
+            selected,
+        }
+    }
+
+    /// Re-rank the entries against the current query. The best match is
+    /// highlighted; an empty query lists every tab in its original order.
+    pub fn refilter(&mut self) {
+        let query = self.input.text();
+        let mut scored: Vec<(u32, usize)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, entry)| {
+                let title = fuzzy_score(query, &entry.title);
+                let cwd = entry.cwd.as_deref().and_then(|cwd| fuzzy_score(query, cwd));
+                title.max(cwd).map(|score| (score, i))
+            })
+            .collect();
+        // Stable, so ties keep their window and tab order.
+        scored.sort_by_key(|&(score, _)| Reverse(score));
+        self.shown = scored.into_iter().map(|(_, i)| i).collect();
+        self.selected = 0;
+    }
+
+    /// Move the highlight `delta` entries down (negative is up), wrapping.
+    pub fn move_selection(&mut self, delta: isize) {
+        if let Some(n) = isize::try_from(self.shown.len()).ok().filter(|&n| n > 0) {
+            self.selected = (self.selected as isize + delta).rem_euclid(n) as usize;
+        }
+    }
+
+    /// The highlighted entry, if any tab matches.
+    pub fn selected(&self) -> Option<&SwitcherEntry> {
+        self.entries.get(*self.shown.get(self.selected)?)
+    }
+
+    /// Position of the highlighted entry among [`Self::shown`].
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    /// The entries matching the query, best first.
+    pub fn shown(&self) -> impl Iterator<Item = &SwitcherEntry> {
+        self.shown.iter().filter_map(|&i| self.entries.get(i))
+    }
+}
+
+/// How well `query` matches `text`, case-insensitively, or `None` if its
+/// characters don't all appear in order. An empty query matches anything
+/// with score 0.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let mut score = 0;
+    let mut chars = text.chars().flat_map(char::to_lowercase).enumerate();
+    let mut prev: Option<char> = None;
+    let mut last_match: Option<usize> = None;
+    for q in query.chars().flat_map(char::to_lowercase) {
+        if q.is_whitespace() {
+            continue;
+        }
+        loop {
+            let (i, c) = chars.next()?;
+            let word_start = prev.is_none_or(|p| !p.is_alphanumeric());
+            prev = Some(c);
+            if c != q {
+                continue;
+            }
+            score += MATCH_SCORE;
+            if word_start {
+                score += WORD_START_BONUS;
+            }
+            if last_match.is_some_and(|last| last + 1 == i) {
+                score += CONSECUTIVE_BONUS;
+            }
+            last_match = Some(i);
+            break;
+        }
+    }
+    Some(score)
+}
+
+/// The last `max` non-blank lines of `text`, trailing spaces removed,
+/// oldest first.
+pub fn preview_lines(text: &str, max: usize) -> Vec<String> {
+    let mut lines: Vec<String> = text
+        .lines()
+        .rev()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .take(max)
+        .map(str::to_owned)
+        .collect();
+    lines.reverse();
+    lines
+}
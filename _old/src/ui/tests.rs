@@ -0,0 +1,219 @@
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+use super::settings_layout::{
+    CONTRAST_ROW_HEIGHT, CONTRAST_TOGGLE_GAP, SettingsHit, SettingsLayout, SettingsPage,
+    THEME_ROW_HEIGHT,
+};
+use super::tab_switcher::{SwitcherEntry, TabSwitcher, fuzzy_score, preview_lines};
+use super::text_input::{KeyOutcome, Motion, Preedit, TextInput};
+use crate::tab::TabId;
+
+fn input(text: &str) -> TextInput {
+    let mut input = TextInput::default();
+    input.set_text(text);
+    input
+}
+
+#[test]
+fn cursor_moves_by_char_and_word_over_multibyte_text() {
+    let mut t = input("grep -n héllo_wörld");
+    t.move_cursor(Motion::Left);
+    assert_eq!(t.cursor(), "grep -n héllo_wörl".len());
+    t.move_cursor(Motion::WordLeft);
+    assert_eq!(t.cursor(), "grep -n ".len());
+    t.move_cursor(Motion::WordLeft);
+    assert_eq!(t.cursor(), "grep -".len());
+    t.move_cursor(Motion::WordRight);
+    assert_eq!(t.cursor(), "grep -n".len());
+    t.move_cursor(Motion::Home);
+    t.move_cursor(Motion::Right);
+    assert_eq!(t.cursor(), 1);
+}
+
+#[test]
+fn typing_replaces_the_selection() {
+    let mut t = input("foo bar");
+    t.select(Motion::WordLeft);
+    assert_eq!(t.selected_text(), Some("bar"));
+    t.insert("baz\nqux");
+    assert_eq!(t.text(), "foo baz qux");
+    assert_eq!(t.selection(), None);
+
+    // Moving without Shift collapses the selection to its edge.
+    t.select_all();
+    t.move_cursor(Motion::Left);
+    assert_eq!((t.cursor(), t.selection()), (0, None));
+}
+
+#[test]
+fn deletion_and_cut() {
+    let mut t = input("alpha beta");
+    assert!(t.delete_back(Motion::WordLeft));
+    assert_eq!(t.text(), "alpha ");
+    t.move_cursor(Motion::Home);
+    assert!(!t.delete_back(Motion::Left));
+    assert!(t.delete_forward(Motion::Right));
+    assert_eq!(t.text(), "lpha ");
+
+    t.select(Motion::WordRight);
+    assert_eq!(t.cut().as_deref(), Some("lpha"));
+    assert_eq!(t.text(), " ");
+    assert_eq!(t.cut(), None);
+}
+
+#[test]
+fn keys_map_to_edits_and_motions() {
+    let mut t = input("ab");
+    let shift = ModifiersState::SHIFT;
+    let ctrl = ModifiersState::CONTROL;
+    let left = Key::Named(NamedKey::ArrowLeft);
+    assert_eq!(t.handle_key(&left, shift), KeyOutcome::Moved);
+    assert_eq!(t.selected_text(), Some("b"));
+    assert_eq!(
+        t.handle_key(&Key::Character("c".into()), ModifiersState::empty()),
+        KeyOutcome::Edited
+    );
+    assert_eq!(t.text(), "ac");
+    assert_eq!(
+        t.handle_key(&Key::Named(NamedKey::Space), ModifiersState::empty()),
+        KeyOutcome::Edited
+    );
+    assert_eq!(
+        t.handle_key(&Key::Character("a".into()), ctrl),
+        KeyOutcome::Moved
+    );
+    assert_eq!(t.selected_text(), Some("ac "));
+    // Unbound shortcuts are left to the owner.
+    assert_eq!(
+        t.handle_key(&Key::Character("f".into()), ctrl),
+        KeyOutcome::Ignored
+    );
+    assert_eq!(
+        t.handle_key(&Key::Named(NamedKey::Enter), ModifiersState::empty()),
+        KeyOutcome::Ignored
+    );
+}
+
+#[test]
+fn ime_preedit_sits_at_the_cursor_until_committed() {
+    let mut t = input("ab");
+    t.move_cursor(Motion::Left);
+    t.set_preedit("にほ", Some((3, 6)));
+    assert_eq!(t.split_at_cursor(), ("a", "b"));
+    assert_eq!((t.preedit(), t.preedit_cursor()), ("にほ", Some(6)));
+    assert_eq!(t.text(), "ab");
+
+    t.commit("日本");
+    assert_eq!(t.text(), "a日本b");
+    assert_eq!(t.preedit(), "");
+    assert_eq!(t.cursor(), "a日本".len());
+}
+
+#[test]
+fn preedit_keeps_the_end_of_the_ime_range_within_the_text() {
+    let p = Preedit::new("かな", Some((0, 3)));
+    assert_eq!((p.text.as_str(), p.cursor), ("かな", Some(3)));
+    assert_eq!(Preedit::new("か", Some((0, 9))).cursor, Some(3));
+    assert_eq!(Preedit::new("か", None).cursor, None);
+}
+
+#[test]
+fn settings_filter_narrows_the_scheme_list_ignoring_case() {
+    let all = SettingsLayout::compute(SettingsPage::Theme, "");
+    let some = SettingsLayout::compute(SettingsPage::Theme, "  CATPPUCCIN ");
+    assert!(all.schemes.len() > some.schemes.len());
+    assert_eq!(some.schemes.len(), 2);
+    assert!(
+        some.schemes
+            .iter()
+            .all(|s| s.name.starts_with("Catppuccin"))
+    );
+
+    // Rows are hit where the filtered list draws them.
+    let second = SettingsLayout::content_top() + THEME_ROW_HEIGHT * 1.5;
+    let hit = some.hit(40.0, second);
+    assert!(matches!(hit, Some(SettingsHit::Scheme(s)) if s.name == some.schemes[1].name));
+    assert!(some.hit(40.0, second + THEME_ROW_HEIGHT).is_none());
+}
+
+#[test]
+fn settings_filter_relayouts_the_contrast_page() {
+    let all = SettingsLayout::compute(SettingsPage::Contrast, "");
+    assert_eq!((all.colors.len(), all.color_rows()), (16, 8));
+    assert!(all.show_toggle);
+
+    let red = SettingsLayout::compute(SettingsPage::Contrast, "red");
+    assert_eq!(red.colors, [1, 9]);
+    assert_eq!(red.color_slot(1), (1, 0));
+    assert!(!red.show_toggle);
+
+    // The toggle moves up under fewer rows and stays clickable there.
+    let toggle = SettingsLayout::compute(SettingsPage::Contrast, "minimum");
+    assert!(toggle.colors.is_empty());
+    assert_eq!(toggle.toggle_top(), Some(SettingsLayout::content_top()));
+    let y = SettingsLayout::content_top() + 1.0;
+    assert!(matches!(
+        toggle.hit(40.0, y),
+        Some(SettingsHit::ContrastToggle)
+    ));
+    assert!(all.hit(40.0, y).is_none());
+    let rows = all.color_rows() as f32;
+    let below_rows =
+        SettingsLayout::content_top() + rows * CONTRAST_ROW_HEIGHT + CONTRAST_TOGGLE_GAP + 1.0;
+    assert!(matches!(
+        all.hit(40.0, below_rows),
+        Some(SettingsHit::ContrastToggle)
+    ));
+}
+
+#[test]
+fn fuzzy_score_prefers_word_starts_and_runs() {
+    assert_eq!(fuzzy_score("", "anything"), Some(0));
+    assert_eq!(fuzzy_score("xyz", "vim"), None);
+    assert_eq!(fuzzy_score("mv", "vim"), None);
+    let tight = fuzzy_score("vp", "vim ~/project");
+    let loose = fuzzy_score("vp", "envelope");
+    assert!(tight.is_some() && loose.is_some());
+    assert!(tight > loose);
+    assert!(fuzzy_score("VIM", "vim") > fuzzy_score("vim", "v_i_m"));
+}
+
+#[test]
+fn switcher_filters_and_wraps_selection() {
+    let entry = |id, title: &str, cwd: Option<&str>| SwitcherEntry {
+        tab: TabId(id),
+        title: title.to_owned(),
+        cwd: cwd.map(str::to_owned),
+        preview: Vec::new(),
+    };
+    let mut switcher = TabSwitcher::new(
+        vec![
+            entry(1, "bash", Some("/home/me/notes")),
+            entry(2, "htop", None),
+            entry(3, "vim", Some("/srv/web")),
+        ],
+        1,
+    );
+    assert_eq!(switcher.selected().map(|e| e.tab), Some(TabId(2)));
+    switcher.move_selection(2);
+    assert_eq!(switcher.selected().map(|e| e.tab), Some(TabId(1)));
+
+    switcher.input.set_text("web");
+    switcher.refilter();
+    let shown: Vec<_> = switcher.shown().map(|e| e.tab).collect();
+    assert_eq!(shown, [TabId(3)]);
+    assert_eq!(switcher.selected_index(), 0);
+
+    switcher.input.set_text("zzz");
+    switcher.refilter();
+    assert!(switcher.selected().is_none());
+    switcher.move_selection(1);
+    assert_eq!(switcher.selected_index(), 0);
+}
+
+#[test]
+fn preview_keeps_last_non_blank_lines() {
+    let text = "one\ntwo  \n\nthree\n   \n";
+    assert_eq!(preview_lines(text, 2), ["two", "three"]);
+    assert!(preview_lines("", 3).is_empty());
+}
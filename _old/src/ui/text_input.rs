@@ -0,0 +1,364 @@
+//! Single-line text field: cursor movement, selection, clipboard, and IME
+//! preedit.
+//!
+//! The field only edits its own state; the owner routes keys to
+//! [`TextInput::handle_key`] and IME events to [`TextInput::handle_ime`],
+//! then re-runs whatever depends on the text when told it was
+//! [`KeyOutcome::Edited`]. Offsets are byte offsets into
+//! [`TextInput::text`] and always fall on `char` boundaries.
+
+use std::ops::Range;
+
+use winit::event::Ime;
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+use crate::clipboard;
+
+/// A cursor movement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    /// One character left.
+    Left,
+    /// One character right.
+    Right,
+    /// To the start of the current or previous word.
+    WordLeft,
+    /// To the end of the current or next word.
+    WordRight,
+    /// To the start of the text.
+    Home,
+    /// To the end of the text.
+    End,
+}
+
+/// What a key did to a [`TextInput`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyOutcome {
+    /// Not an editing key; the owner may use it.
+    Ignored,
+    /// The cursor or selection moved, or text was copied.
+    Moved,
+    /// The text changed.
+    Edited,
+}
+
+/// IME composition in progress, shown at the cursor but not yet part of the
+/// text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Preedit {
+    /// The composing text.
+    pub text: String,
+    /// Cursor within `text`, as the IME reports it.
+    pub cursor: Option<usize>,
+}
+
+impl Preedit {
+    /// A composition as winit reports it: `cursor` is the IME's byte range
+    /// within `text`, of which only the end is kept.
+    pub fn new(text: &str, cursor: Option<(usize, usize)>) -> Self {
+        Self {
+            text: text.to_owned(),
+            cursor: cursor.map(|(_, end)| end.min(text.len())),
+        }
+    }
+}
+
+/// An editable single line of text.
+#[derive(Debug, Clone, Default)]
+pub struct TextInput {
+    text: String,
+    cursor: usize,
+    /// The other end of the selection; the cursor is the moving end.
+    anchor: Option<usize>,
+    preedit: Preedit,
+}
+
+impl TextInput {
+    /// The committed text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Whether there is no committed text.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Cursor position.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Replace the text, placing the cursor at the end.
+    pub fn set_text(&mut self, text: &str) {
+        text.clone_into(&mut self.text);
+        self.cursor = self.text.len();
+        self.anchor = None;
+        self.preedit = Preedit::default();
+    }
+
+    /// Remove all text.
+    pub fn clear(&mut self) {
+        self.set_text("");
+    }
+
+    /// The selected range, if it is not empty.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.anchor?;
+        let range = anchor.min(self.cursor)..anchor.max(self.cursor);
+        (!range.is_empty()).then_some(range)
+    }
+
+    /// The selected text, if any.
+    pub fn selected_text(&self) -> Option<&str> {
+        self.selection().and_then(|r| self.text.get(r))
+    }
+
+    /// Move the cursor, dropping the selection.
+    pub fn move_cursor(&mut self, motion: Motion) {
+        let collapse = match (self.selection(), motion) {
+            (Some(r), Motion::Left) => Some(r.start),
+            (Some(r), Motion::Right) => Some(r.end),
+            _ => None,
+        };
+        self.cursor = collapse.unwrap_or_else(|| self.target(motion));
+        self.anchor = None;
+    }
+
+    /// Move the cursor, extending the selection.
+    pub fn select(&mut self, motion: Motion) {
+        self.anchor.get_or_insert(self.cursor);
+        self.cursor = self.target(motion);
+    }
+
+    /// Select all text.
+    pub fn select_all(&mut self) {
+        self.anchor = Some(0);
+        self.cursor = self.text.len();
+    }
+
+    /// Insert `s` at the cursor, replacing the selection. Line breaks and
+    /// other control characters become spaces.
+    pub fn insert(&mut self, s: &str) {
+        self.delete_selection();
+        let clean: String = s
+            .chars()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect();
+        self.text.insert_str(self.cursor, &clean);
+        self.cursor += clean.len();
+    }
+
+    /// Delete the selection, or the character (word with `Motion::WordLeft`)
+    /// before the cursor. Returns whether anything was removed.
+    pub fn delete_back(&mut self, motion: Motion) -> bool {
+        if self.delete_selection() {
+            return true;
+        }
+        let start = self.target(motion);
+        self.remove(start..self.cursor)
+    }
+
+    /// Delete the selection, or the character (word with
+    /// `Motion::WordRight`) after the cursor. Returns whether anything was
+    /// removed.
+    pub fn delete_forward(&mut self, motion: Motion) -> bool {
+        if self.delete_selection() {
+            return true;
+        }
+        let end = self.target(motion);
+        self.remove(self.cursor..end)
+    }
+
+    /// Remove and return the selected text.
+    pub fn cut(&mut self) -> Option<String> {
+        let taken = self.selected_text().map(str::to_owned);
+        self.delete_selection();
+        taken
+    }
+
+    /// Show an IME composition at the cursor. `cursor` is the IME's byte
+    /// range within `text`; only its end is used. An empty `text` ends the
+    /// composition.
+    pub fn set_preedit(&mut self, text: &str, cursor: Option<(usize, usize)>) {
+        if !text.is_empty() {
+            self.delete_selection();
+        }
+        self.preedit = Preedit::new(text, cursor);
+    }
+
+    /// Insert IME-committed text, ending any composition.
+    pub fn commit(&mut self, text: &str) {
+        self.preedit = Preedit::default();
+        self.insert(text);
+    }
+
+    /// Apply an IME event: update the composition or insert the committed
+    /// text.
+    pub fn handle_ime(&mut self, ime: Ime) -> KeyOutcome {
+        match ime {
+            Ime::Preedit(text, cursor) => {
+                self.set_preedit(&text, cursor);
+                KeyOutcome::Moved
+            }
+            Ime::Commit(text) => {
+                self.commit(&text);
+                KeyOutcome::Edited
+            }
+            Ime::Enabled | Ime::Disabled => {
+                self.set_preedit("", None);
+                KeyOutcome::Moved
+            }
+        }
+    }
+
+    /// The IME composition, empty when none is in progress.
+    pub fn preedit(&self) -> &str {
+        &self.preedit.text
+    }
+
+    /// Where the cursor is drawn inside the preedit, if the IME said.
+    pub fn preedit_cursor(&self) -> Option<usize> {
+        self.preedit.cursor
+    }
+
+    /// Text before and after the cursor, for drawing the preedit between.
+    pub fn split_at_cursor(&self) -> (&str, &str) {
+        self.text.split_at_checked(self.cursor).unwrap_or((&self.text, ""))
+    }
+
+    /// Apply an editing key: motions (with Shift to select, Ctrl for
+    /// words), deletion, clipboard shortcuts, and typed characters.
+    /// Cmd stands in for Ctrl on macOS.
+    pub fn handle_key(&mut self, key: &Key, mods: ModifiersState) -> KeyOutcome {
+        let command = mods.control_key() || mods.super_key();
+        let word = |plain, by_word| if command { by_word } else { plain };
+        let motion = match key {
+            Key::Named(NamedKey::ArrowLeft) => Some(word(Motion::Left, Motion::WordLeft)),
+            Key::Named(NamedKey::ArrowRight) => Some(word(Motion::Right, Motion::WordRight)),
+            Key::Named(NamedKey::Home) => Some(Motion::Home),
+            Key::Named(NamedKey::End) => Some(Motion::End),
+            _ => None,
+        };
+        if let Some(motion) = motion {
+            if mods.shift_key() {
+                self.select(motion);
+            } else {
+                self.move_cursor(motion);
+            }
+            return KeyOutcome::Moved;
+        }
+
+        match key {
+            Key::Named(NamedKey::Backspace) => {
+                edited(self.delete_back(word(Motion::Left, Motion::WordLeft)))
+            }
+            Key::Named(NamedKey::Delete) if mods.shift_key() => self.cut_to_clipboard(),
+            Key::Named(NamedKey::Delete) => {
+                edited(self.delete_forward(word(Motion::Right, Motion::WordRight)))
+            }
+            Key::Named(NamedKey::Insert) if mods.shift_key() => self.paste_from_clipboard(),
+            Key::Named(NamedKey::Insert) if command => self.copy_to_clipboard(),
+            Key::Named(NamedKey::Space) if !command => {
+                self.insert(" ");
+                KeyOutcome::Edited
+            }
+            Key::Character(c) if command => match c.to_lowercase().as_str() {
+                "a" => {
+                    self.select_all();
+                    KeyOutcome::Moved
+                }
+                "c" => self.copy_to_clipboard(),
+                "x" => self.cut_to_clipboard(),
+                "v" => self.paste_from_clipboard(),
+                _ => KeyOutcome::Ignored,
+            },
+            Key::Character(c) => {
+                self.insert(c);
+                KeyOutcome::Edited
+            }
+            _ => KeyOutcome::Ignored,
+        }
+    }
+
+    fn copy_to_clipboard(&self) -> KeyOutcome {
+        match self.selected_text() {
+            Some(text) => {
+                clipboard::set_text(text);
+                KeyOutcome::Moved
+            }
+            None => KeyOutcome::Ignored,
+        }
+    }
+
+    fn cut_to_clipboard(&mut self) -> KeyOutcome {
+        match self.cut() {
+            Some(text) => {
+                clipboard::set_text(&text);
+                KeyOutcome::Edited
+            }
+            None => KeyOutcome::Ignored,
+        }
+    }
+
+    fn paste_from_clipboard(&mut self) -> KeyOutcome {
+        match clipboard::get_text() {
+            Some(text) if !text.is_empty() => {
+                self.insert(&text);
+                KeyOutcome::Edited
+            }
+            _ => KeyOutcome::Moved,
+        }
+    }
+
+    fn delete_selection(&mut self) -> bool {
+        let Some(range) = self.selection() else {
+            self.anchor = None;
+            return false;
+        };
+        self.anchor = None;
+        self.remove(range)
+    }
+
+    fn remove(&mut self, range: Range<usize>) -> bool {
+        if range.is_empty() {
+            return false;
+        }
+        self.cursor = range.start;
+        self.text.replace_range(range, "");
+        true
+    }
+
+    /// Where `motion` takes the cursor.
+    fn target(&self, motion: Motion) -> usize {
+        let (before, after) = self.split_at_cursor();
+        match motion {
+            Motion::Left => before.char_indices().next_back().map_or(0, |(i, _)| i),
+            Motion::Right => self.cursor + after.chars().next().map_or(0, char::len_utf8),
+            Motion::WordLeft => {
+                let gap = before.trim_end_matches(|c| !is_word_char(c));
+                gap.trim_end_matches(is_word_char).len()
+            }
+            Motion::WordRight => {
+                let skipped = after.trim_start_matches(|c| !is_word_char(c));
+                let word_end = skipped.trim_start_matches(is_word_char);
+                self.text.len() - word_end.len()
+            }
+            Motion::Home => 0,
+            Motion::End => self.text.len(),
+        }
+    }
+}
+
+/// Letters, digits, and `_` make up words for Ctrl+arrow motions.
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn edited(changed: bool) -> KeyOutcome {
+    if changed {
+        KeyOutcome::Edited
+    } else {
+        KeyOutcome::Ignored
+    }
+}
@@ -0,0 +1,255 @@
+//! Implicit URL and rule-match detection in grid text using regex patterns.
+//!
+//! Every logical line is scanned with the user's `[[rules]]` first, in
+//! config order, then with the built-in URL pattern, and last for file
+//! paths. Where matches overlap, the earlier one wins.
+
+mod paths;
+mod rules;
+
+pub use paths::{FileTarget, file_url, is_executable};
+pub use rules::{MatchAction, RuleAction, RuleConfig, RuleSet};
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::grid::{Grid, WrapDetection};
+use crate::search::extract_row_text;
+
+/// A single row-segment of a detected match.
+pub type UrlSegment = (usize, usize, usize);
+
+/// A URL or rule match detected across one or more grid rows (handles
+/// soft-wrapped lines).
+#[derive(Debug, Clone)]
+pub struct DetectedMatch {
+    /// Per-row segments, each inclusive.
+    pub segments: Vec<UrlSegment>,
+    /// What Ctrl+click does with the match.
+    pub action: MatchAction,
+}
+
+impl DetectedMatch {
+    /// Check whether this match covers (`abs_row`, `col`).
+    pub fn contains(&self, abs_row: usize, col: usize) -> bool {
+        self.segments
+            .iter()
+            .any(|&(r, sc, ec)| r == abs_row && col >= sc && col <= ec)
+    }
+}
+
+/// Cache of detected matches keyed by the first absolute row of the logical line.
+///
+/// Lazily computes matches for logical lines (sequences of wrapped rows) and caches
+/// them to avoid redundant regex matching on hover/click.
+#[derive(Default)]
+pub struct UrlDetectCache {
+    rules: RuleSet,
+    /// Logical line start row -> detected matches for that logical line.
+    lines: HashMap<usize, Vec<DetectedMatch>>,
+    /// Row index -> logical line start (for fast lookup of any row).
+    row_to_line: HashMap<usize, usize>,
+}
+
+impl UrlDetectCache {
+    /// Creates an empty cache that detects with `rules` and the built-in URL pattern.
+    pub fn new(rules: RuleSet) -> Self {
+        Self {
+            rules,
+            ..Self::default()
+        }
+    }
+
+    /// Replaces the user rules (after a config reload) and drops cached matches.
+    pub fn set_rules(&mut self, rules: RuleSet) {
+        self.rules = rules;
+        self.invalidate();
+    }
+
+    /// Finds a match at the specified grid position, computing and caching the logical line if needed.
+    pub fn match_at(&mut self, grid: &Grid, abs_row: usize, col: usize) -> Option<DetectedMatch> {
+        let line_start = self.ensure_logical_line(grid, abs_row);
+        let matches = self.lines.get(&line_start)?;
+        matches.iter().find(|u| u.contains(abs_row, col)).cloned()
+    }
+
+    /// Ensures the logical line containing the row is computed and cached.
+    ///
+    /// Returns the absolute row index of the logical line start.
+    fn ensure_logical_line(&mut self, grid: &Grid, abs_row: usize) -> usize {
+        if let Some(&ls) = self.row_to_line.get(&abs_row) {
+            return ls;
+        }
+        let line_start = logical_line_start(grid, abs_row);
+        let line_end = logical_line_end(grid, abs_row);
+
+        // Detect matches across the entire logical line
+        let matches = detect_in_logical_line(grid, &self.rules, line_start, line_end);
+
+        // Register all rows in this logical line
+        for r in line_start..=line_end {
+            self.row_to_line.insert(r, line_start);
+        }
+        self.lines.insert(line_start, matches);
+        line_start
+    }
+
+    /// Invalidates the entire cache (call after PTY output, scroll, resize).
+    pub fn invalidate(&mut self) {
+        self.lines.clear();
+        self.row_to_line.clear();
+    }
+}
+
+/// Walks backwards to find the start of contiguous text for URL detection.
+fn logical_line_start(grid: &Grid, abs_row: usize) -> usize {
+    grid.logical_line_start(abs_row, WrapDetection::WrapOrFilled)
+}
+
+/// Walks forwards to find the end of contiguous text for URL detection.
+fn logical_line_end(grid: &Grid, abs_row: usize) -> usize {
+    grid.logical_line_end(abs_row, WrapDetection::WrapOrFilled)
+}
+
+/// URL regex pattern.
+static URL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:https?|ftp|file)://[^\s<>\[\]'"]+"#).expect("URL regex is valid")
+});
+
+/// Trims trailing punctuation from a URL, preserving balanced parentheses.
+fn trim_url_trailing(url: &str) -> &str {
+    let mut s = url;
+    loop {
+        let prev = s;
+        s = s.trim_end_matches(['.', ',', ';', ':', '!', '?']);
+        // Trim trailing ')' only if it's unbalanced
+        if let Some(stripped) = s.strip_suffix(')') {
+            let open = s.chars().filter(|&c| c == '(').count();
+            let close = s.chars().filter(|&c| c == ')').count();
+            if close > open {
+                s = stripped;
+            }
+        }
+        if s == prev {
+            break;
+        }
+    }
+    s
+}
+
+/// Concatenated text of `line_start..=line_end` with the (`abs_row`, `col`)
+/// of every character.
+fn logical_line_text(
+    grid: &Grid,
+    line_start: usize,
+    line_end: usize,
+) -> (String, Vec<(usize, usize)>) {
+    let mut text = String::new();
+    let mut char_to_pos = Vec::new();
+    for abs_row in line_start..=line_end {
+        let Some(row) = grid.absolute_row(abs_row) else {
+            continue;
+        };
+        let (row_text, col_map) = extract_row_text(row);
+        for (byte, _) in row_text.char_indices() {
+            char_to_pos.push((abs_row, col_map[byte]));
+        }
+        text.push_str(&row_text);
+    }
+    (text, char_to_pos)
+}
+
+/// Detects rule matches, URLs, and file paths across a logical line spanning
+/// `line_start..=line_end` (absolute rows).
+///
+/// Concatenates text from all rows, runs the regexes, then maps byte spans
+/// back to per-row segments.
+#[expect(
+    clippy::string_slice,
+    reason = "regex match offsets are char boundaries"
+)]
+fn detect_in_logical_line(
+    grid: &Grid,
+    rules: &RuleSet,
+    line_start: usize,
+    line_end: usize,
+) -> Vec<DetectedMatch> {
+    let (text, char_to_pos) = logical_line_text(grid, line_start, line_end);
+
+    // Candidate (byte start, byte end, action) in priority order.
+    let mut candidates = Vec::new();
+    for rule in &rules.rules {
+        for caps in rule.regex.captures_iter(&text) {
+            if let Some(m) = caps.get(0).filter(|m| !m.is_empty()) {
+                candidates.push((m.start(), m.end(), rule.resolve(&caps)));
+            }
+        }
+    }
+    for m in URL_RE.find_iter(&text) {
+        let trimmed = trim_url_trailing(m.as_str());
+        if trimmed.len() > "https://".len() {
+            let end = m.start() + trimmed.len();
+            candidates.push((m.start(), end, MatchAction::Open(trimmed.to_string())));
+        }
+    }
+    candidates.extend(paths::path_candidates(&text));
+
+    let mut claimed: Vec<(usize, usize)> = Vec::new();
+    let mut found = Vec::new();
+    for (start, end, action) in candidates {
+        if claimed.iter().any(|&(s, e)| start < e && s < end) {
+            continue;
+        }
+        // Convert byte offsets to char offsets (inclusive end).
+        let char_start = text[..start].chars().count();
+        let char_end = char_start + text[start..end].chars().count() - 1;
+        let Some(positions) = char_to_pos.get(char_start..=char_end) else {
+            continue;
+        };
+        // OSC 8 hyperlinks take priority over anything detected in them.
+        if covers_hyperlink(grid, positions) {
+            continue;
+        }
+        claimed.push((start, end));
+        found.push(DetectedMatch {
+            segments: row_segments(positions),
+            action,
+        });
+    }
+    found.sort_by_key(|m| m.segments.first().map(|&(r, c, _)| (r, c)));
+    found
+}
+
+/// Whether any of `positions` holds an OSC 8 hyperlink.
+fn covers_hyperlink(grid: &Grid, positions: &[(usize, usize)]) -> bool {
+    positions.iter().any(|&(ar, col)| {
+        grid.absolute_row(ar)
+            .is_some_and(|row| col < row.len() && row[col].hyperlink().is_some())
+    })
+}
+
+/// Splits consecutive (`abs_row`, `col`) positions into per-row segments.
+fn row_segments(positions: &[(usize, usize)]) -> Vec<UrlSegment> {
+    let mut segments = Vec::new();
+    let Some(&(first_row, first_col)) = positions.first() else {
+        return segments;
+    };
+    let mut current_row = first_row;
+    let mut seg_start_col = first_col;
+    let mut seg_end_col = first_col;
+    for &(ar, col) in positions {
+        if ar != current_row {
+            segments.push((current_row, seg_start_col, seg_end_col));
+            current_row = ar;
+            seg_start_col = col;
+        }
+        seg_end_col = col;
+    }
+    segments.push((current_row, seg_start_col, seg_end_col));
+    segments
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,147 @@
+//! File path detection for compiler-output navigation.
+//!
+//! Recognizes paths like `src/main.rs:12:5`, `./build.sh`, `~/notes.md`,
+//! and MSVC-style `C:\src\app.cpp(42,7)`. A match needs a directory
+//! separator or a line number so that `e.g.` and version numbers aren't
+//! mistaken for files.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use super::MatchAction;
+
+/// A file path detected in grid text, with an optional position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTarget {
+    pub path: String,
+    pub line: Option<u32>,
+    pub col: Option<u32>,
+}
+
+impl FileTarget {
+    /// Resolve the path against `cwd` (or the process directory), expanding
+    /// a leading `~`. Returns `None` if nothing exists there.
+    pub fn resolve(&self, cwd: Option<&Path>) -> Option<PathBuf> {
+        let home = || std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"));
+        let path = match self
+            .path
+            .strip_prefix("~/")
+            .or_else(|| self.path.strip_prefix("~\\"))
+        {
+            Some(rest) => PathBuf::from(home()?).join(rest),
+            None => PathBuf::from(&self.path),
+        };
+        let path = if path.is_absolute() {
+            path
+        } else {
+            match cwd {
+                Some(dir) => dir.join(path),
+                None => std::env::current_dir().ok()?.join(path),
+            }
+        };
+        path.exists().then_some(path)
+    }
+
+    /// The command line for `template` (`behavior.editor`), split on
+    /// whitespace with `{path}`, `{line}`, and `{col}` substituted. A
+    /// missing line or column is 1. The path is appended if the template
+    /// doesn't mention it.
+    #[expect(
+        clippy::literal_string_with_formatting_args,
+        reason = "editor template placeholders, not format arguments"
+    )]
+    pub fn editor_argv(&self, template: &str, path: &Path) -> Vec<String> {
+        let path = path.to_string_lossy();
+        let line = self.line.unwrap_or(1).to_string();
+        let col = self.col.unwrap_or(1).to_string();
+        let mut argv: Vec<String> = template
+            .split_whitespace()
+            .map(|arg| {
+                arg.replace("{path}", &path)
+                    .replace("{line}", &line)
+                    .replace("{col}", &col)
+            })
+            .collect();
+        if !template.contains("{path}") {
+            argv.push(path.into_owned());
+        }
+        argv
+    }
+}
+
+/// Extensions the system handler would run rather than open.
+const EXECUTABLE_EXTENSIONS: [&str; 26] = [
+    "app", "appimage", "bat", "cmd", "com", "command", "cpl", "desktop", "exe", "hta", "jar",
+    "js", "jse", "lnk", "msc", "msi", "pif", "ps1", "reg", "run", "scr", "sh", "vbe", "vbs",
+    "wsf", "wsh",
+];
+
+/// Whether the system handler would run `path` instead of opening it: a
+/// script or program by extension, or on Unix a file with an execute bit.
+pub fn is_executable(path: &Path) -> bool {
+    let by_extension = path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| {
+        EXECUTABLE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str())
+    });
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let by_mode = std::fs::metadata(path)
+            .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0);
+        by_extension || by_mode
+    }
+    #[cfg(not(unix))]
+    by_extension
+}
+
+/// The `file://` URL of absolute `path`, percent-encoding every byte but
+/// unreserved characters, separators, and a drive letter's colon.
+pub fn file_url(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut url = String::from(if path.starts_with('/') { "file://" } else { "file:///" });
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/:".contains(&byte) {
+            url.push(char::from(byte));
+        } else {
+            let _ = write!(url, "%{byte:02X}");
+        }
+    }
+    url
+}
+
+/// A path (group `path`) after a delimiter, with an optional `:line:col`
+/// or `(line,col)` suffix.
+static PATH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?:^|[\s'"`(\[<=])(?P<path>(?:[A-Za-z]:[\\/]|~[\\/]|\.{1,2}[\\/]|[\\/])?(?:[\w.@+-]+[\\/])*[\w@+-][\w.@+-]*\.[A-Za-z]\w*)(?::(?P<line>\d+)(?::(?P<col>\d+))?|\((?P<pline>\d+)(?:,(?P<pcol>\d+))?\))?"#,
+    )
+    .expect("path regex is valid")
+});
+
+/// Byte spans and actions of the file paths in `text`.
+pub(super) fn path_candidates(text: &str) -> Vec<(usize, usize, MatchAction)> {
+    let number = |name: &str, caps: &regex::Captures<'_>| {
+        caps.name(name).and_then(|m| m.as_str().parse().ok())
+    };
+    PATH_RE
+        .captures_iter(text)
+        .filter_map(|caps| {
+            let path = caps.name("path")?;
+            let end = caps.get(0)?.end();
+            let line = number("line", &caps).or_else(|| number("pline", &caps));
+            let col = number("col", &caps).or_else(|| number("pcol", &caps));
+            let has_dir = path.as_str().contains(['/', '\\']);
+            if !has_dir && line.is_none() {
+                return None;
+            }
+            let target = FileTarget {
+                path: path.as_str().to_string(),
+                line,
+                col,
+            };
+            Some((path.start(), end, MatchAction::OpenFile(target)))
+        })
+        .collect()
+}
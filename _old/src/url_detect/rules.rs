@@ -0,0 +1,139 @@
+//! User-defined detection rules from `[[rules]]` in the config file.
+//!
+//! Each rule pairs a regex with what Ctrl+click does to the text it
+//! matches: open it, copy it, or hand it to a program.
+//!
+//! ```toml
+//! [[rules]]
+//! regex = "\\b[A-Z]+-\\d+\\b"
+//! action = "open"
+//! url = "https://issues.example.com/browse/$0"
+//!
+//! [[rules]]
+//! regex = "\\b[0-9a-f]{40}\\b"
+//! action = "copy"
+//!
+//! [[rules]]
+//! regex = "[\\w./-]+\\.rs:\\d+"
+//! action = "run"
+//! program = "code"
+//! args = ["--goto"]
+//! ```
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+use crate::log;
+
+use super::FileTarget;
+
+/// What a rule does with its match.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    /// Open the match, or the rule's `url` template, in the browser.
+    #[default]
+    Open,
+    /// Copy the match to the clipboard.
+    Copy,
+    /// Run the rule's `program` with its `args`, then `--` and the match,
+    /// so a match starting with `-` is never parsed as an option.
+    Run,
+}
+
+/// One `[[rules]]` entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleConfig {
+    pub regex: String,
+    #[serde(default)]
+    pub action: RuleAction,
+    /// URL to open instead of the match. `$0` is the whole match and `$1`,
+    /// `$2`, ... are capture groups.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Program for the `run` action.
+    #[serde(default)]
+    pub program: Option<String>,
+    /// Arguments passed before the `--` that precedes the match.
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// What Ctrl+click does with one detected match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchAction {
+    Open(String),
+    Copy(String),
+    Run { program: String, args: Vec<String> },
+    /// Open a detected file path in the configured editor.
+    OpenFile(FileTarget),
+}
+
+/// A rule with its regex compiled.
+#[derive(Debug, Clone)]
+pub(super) struct Rule {
+    pub(super) regex: Regex,
+    config: RuleConfig,
+}
+
+impl Rule {
+    /// The action for one match of this rule.
+    pub(super) fn resolve(&self, caps: &Captures<'_>) -> MatchAction {
+        let text = caps.get(0).map_or("", |m| m.as_str()).to_owned();
+        match self.config.action {
+            RuleAction::Open => match &self.config.url {
+                Some(template) => {
+                    let mut url = String::new();
+                    caps.expand(template, &mut url);
+                    MatchAction::Open(url)
+                }
+                None => MatchAction::Open(text),
+            },
+            RuleAction::Copy => MatchAction::Copy(text),
+            RuleAction::Run => {
+                let mut args = self.config.args.clone();
+                args.push("--".to_owned());
+                args.push(text);
+                MatchAction::Run {
+                    program: self.config.program.clone().unwrap_or_default(),
+                    args,
+                }
+            }
+        }
+    }
+}
+
+/// The compiled user rules, in config order.
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    pub(super) rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    /// Compile `[[rules]]` entries. Invalid entries are logged and skipped.
+    pub fn new(configs: &[RuleConfig]) -> Self {
+        let rules = configs
+            .iter()
+            .filter_map(|config| {
+                if config.action == RuleAction::Run && config.program.is_none() {
+                    log(&format!(
+                        "rules: '{}' has action run but no program",
+                        config.regex
+                    ));
+                    return None;
+                }
+                match Regex::new(&config.regex) {
+                    Ok(regex) => Some(Rule {
+                        regex,
+                        config: config.clone(),
+                    }),
+                    Err(e) => {
+                        log(&format!("rules: invalid regex '{}': {e}", config.regex));
+                        None
+                    }
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+}
@@ -0,0 +1,279 @@
+use std::path::Path;
+
+use crate::grid::Grid;
+
+use super::{
+    DetectedMatch, FileTarget, MatchAction, RuleAction, RuleConfig, RuleSet, detect_in_logical_line,
+    file_url, is_executable,
+};
+
+fn detect(grid: &Grid, start: usize, end: usize) -> Vec<DetectedMatch> {
+    detect_in_logical_line(grid, &RuleSet::default(), start, end)
+}
+
+fn open(url: &str) -> MatchAction {
+    MatchAction::Open(url.to_string())
+}
+
+fn grid_with(text: &str) -> Grid {
+    let mut grid = Grid::new(80, 1);
+    for c in text.chars() {
+        grid.put_char(c);
+    }
+    grid
+}
+
+fn rule(regex: &str, action: RuleAction) -> RuleConfig {
+    RuleConfig {
+        regex: regex.to_string(),
+        action,
+        url: None,
+        program: None,
+        args: Vec::new(),
+    }
+}
+
+#[test]
+fn detect_simple_url() {
+    let mut grid = Grid::new(80, 1);
+    for (i, c) in "Visit https://example.com for info".chars().enumerate() {
+        grid.goto(0, i);
+        grid.put_char(c);
+    }
+    let urls = detect(&grid, 0, 0);
+    assert_eq!(urls.len(), 1);
+    assert_eq!(urls[0].action, open("https://example.com"));
+    assert_eq!(urls[0].segments.len(), 1);
+    assert_eq!(urls[0].segments[0], (0, 6, 24));
+}
+
+#[test]
+fn detect_multiple_urls() {
+    let mut grid = Grid::new(80, 1);
+    for (i, c) in "see https://a.com and http://b.com/x ok"
+        .chars()
+        .enumerate()
+    {
+        grid.goto(0, i);
+        grid.put_char(c);
+    }
+    let urls = detect(&grid, 0, 0);
+    assert_eq!(urls.len(), 2);
+    assert_eq!(urls[0].action, open("https://a.com"));
+    assert_eq!(urls[1].action, open("http://b.com/x"));
+}
+
+#[test]
+fn detect_url_with_parens() {
+    let mut grid = Grid::new(80, 1);
+    for (i, c) in "see https://en.wikipedia.org/wiki/Rust_(language) ok"
+        .chars()
+        .enumerate()
+    {
+        grid.goto(0, i);
+        grid.put_char(c);
+    }
+    let urls = detect(&grid, 0, 0);
+    assert_eq!(urls.len(), 1);
+    assert_eq!(
+        urls[0].action,
+        open("https://en.wikipedia.org/wiki/Rust_(language)")
+    );
+}
+
+#[test]
+fn no_urls() {
+    let mut grid = Grid::new(80, 1);
+    for (i, c) in "just plain text here".chars().enumerate() {
+        grid.goto(0, i);
+        grid.put_char(c);
+    }
+    let urls = detect(&grid, 0, 0);
+    assert!(urls.is_empty());
+}
+
+#[test]
+fn detect_wrapped_url() {
+    // 20-col grid: URL wraps to second row
+    let mut grid = Grid::new(20, 2);
+    let text = "go https://example.com/long/path ok";
+    // Write characters — grid auto-wraps at col 20
+    for c in text.chars() {
+        grid.put_char(c);
+    }
+    // Row 0 should be wrapped (text overflows 20 cols)
+    let urls = detect(&grid, 0, 1);
+    assert_eq!(urls.len(), 1);
+    assert_eq!(urls[0].action, open("https://example.com/long/path"));
+    assert_eq!(urls[0].segments.len(), 2);
+    // First segment: starts at col 3 on row 0, goes to col 19
+    assert_eq!(urls[0].segments[0].0, 0);
+    assert_eq!(urls[0].segments[0].1, 3); // "go " = 3 chars
+    assert_eq!(urls[0].segments[0].2, 19);
+    // Second segment: continues on row 1
+    assert_eq!(urls[0].segments[1].0, 1);
+}
+
+#[test]
+fn url_contains() {
+    let url = DetectedMatch {
+        segments: vec![(5, 3, 19), (6, 0, 10)],
+        action: open("https://example.com/long/path"),
+    };
+    assert!(url.contains(5, 3));
+    assert!(url.contains(5, 19));
+    assert!(url.contains(6, 0));
+    assert!(url.contains(6, 10));
+    assert!(!url.contains(5, 2));
+    assert!(!url.contains(5, 20));
+    assert!(!url.contains(6, 11));
+    assert!(!url.contains(7, 0));
+}
+
+#[test]
+fn rule_actions_resolve_per_match() {
+    let grid = grid_with("see ABC-12 at deadbeef, run src/main.rs:7");
+    let mut issue = rule(r"[A-Z]+-(\d+)", RuleAction::Open);
+    issue.url = Some("https://issues.example.com/$1".to_string());
+    let mut edit = rule(r"\S+\.rs:\d+", RuleAction::Run);
+    edit.program = Some("code".to_string());
+    edit.args = vec!["--goto".to_string()];
+    let rules = RuleSet::new(&[issue, rule("deadbeef", RuleAction::Copy), edit]);
+
+    let found = detect_in_logical_line(&grid, &rules, 0, 0);
+    let actions: Vec<_> = found.iter().map(|m| m.action.clone()).collect();
+    assert_eq!(
+        actions,
+        vec![
+            open("https://issues.example.com/12"),
+            MatchAction::Copy("deadbeef".to_string()),
+            MatchAction::Run {
+                program: "code".to_string(),
+                args: vec![
+                    "--goto".to_string(),
+                    "--".to_string(),
+                    "src/main.rs:7".to_string(),
+                ],
+            },
+        ]
+    );
+    assert_eq!(found[0].segments, vec![(0, 4, 9)]);
+}
+
+#[test]
+fn run_rules_end_options_before_the_match() {
+    let grid = grid_with("try -rf now");
+    let mut run = rule(r"-\S+", RuleAction::Run);
+    run.program = Some("echo".to_string());
+    let found = detect_in_logical_line(&grid, &RuleSet::new(&[run]), 0, 0);
+    assert_eq!(
+        found[0].action,
+        MatchAction::Run {
+            program: "echo".to_string(),
+            args: vec!["--".to_string(), "-rf".to_string()],
+        }
+    );
+}
+
+#[test]
+fn user_rules_win_over_overlapping_urls() {
+    let grid = grid_with("at https://example.com/x now");
+    let rules = RuleSet::new(&[rule(r"https://\S+", RuleAction::Copy)]);
+    let found = detect_in_logical_line(&grid, &rules, 0, 0);
+    assert_eq!(found.len(), 1);
+    assert_eq!(
+        found[0].action,
+        MatchAction::Copy("https://example.com/x".to_string())
+    );
+}
+
+#[test]
+fn invalid_rules_are_skipped() {
+    let rules = RuleSet::new(&[
+        rule("(unclosed", RuleAction::Open),
+        rule("x", RuleAction::Run),
+        rule("ok", RuleAction::Copy),
+    ]);
+    assert_eq!(rules.rules.len(), 1);
+}
+
+fn file(path: &str, line: Option<u32>, col: Option<u32>) -> MatchAction {
+    MatchAction::OpenFile(FileTarget {
+        path: path.to_string(),
+        line,
+        col,
+    })
+}
+
+#[test]
+fn detect_compiler_paths() {
+    let grid = grid_with("  --> src/main.rs:12:5 and C:\\src\\app.cpp(42,7) ok");
+    let found = detect(&grid, 0, 0);
+    let actions: Vec<_> = found.iter().map(|m| m.action.clone()).collect();
+    assert_eq!(
+        actions,
+        vec![
+            file("src/main.rs", Some(12), Some(5)),
+            file("C:\\src\\app.cpp", Some(42), Some(7)),
+        ]
+    );
+    // The span covers the path and its position, not the leading space.
+    assert_eq!(found[0].segments, vec![(0, 6, 21)]);
+}
+
+#[test]
+fn bare_words_with_dots_are_not_paths() {
+    let grid = grid_with("e.g. version 1.2.3 but lib.rs:7 counts");
+    let found = detect(&grid, 0, 0);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].action, file("lib.rs", Some(7), None));
+}
+
+#[test]
+fn urls_are_not_paths() {
+    let grid = grid_with("get https://example.com/a/b.tar.gz now");
+    let found = detect(&grid, 0, 0);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].action, open("https://example.com/a/b.tar.gz"));
+}
+
+#[test]
+#[expect(
+    clippy::literal_string_with_formatting_args,
+    reason = "editor template placeholders, not format arguments"
+)]
+fn editor_template_substitutes_position() {
+    let target = FileTarget {
+        path: "src/main.rs".to_string(),
+        line: Some(12),
+        col: None,
+    };
+    let path = Path::new("/work/src/main.rs");
+    assert_eq!(
+        target.editor_argv("code -g {path}:{line}:{col}", path),
+        ["code", "-g", "/work/src/main.rs:12:1"]
+    );
+    assert_eq!(
+        target.editor_argv("subl", path),
+        ["subl", "/work/src/main.rs"]
+    );
+}
+
+#[test]
+fn file_urls_are_percent_encoded() {
+    assert_eq!(
+        file_url(Path::new("/tmp/a b/#1%.txt")),
+        "file:///tmp/a%20b/%231%25.txt"
+    );
+    assert_eq!(
+        file_url(Path::new("C:\\src\\app.cpp")),
+        "file:///C:/src/app.cpp"
+    );
+}
+
+#[test]
+fn programs_and_scripts_count_as_executable() {
+    assert!(is_executable(Path::new("setup.EXE")));
+    assert!(is_executable(Path::new("/tmp/missing/run.sh")));
+    assert!(!is_executable(Path::new("/tmp/missing/notes.md")));
+}
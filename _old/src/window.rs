@@ -1,10 +1,13 @@
 //! Window wrapper for terminal surface and tab management.
 
 use std::sync::Arc;
+use std::time::Instant;
 
 use winit::window::Window;
 
 use crate::gpu::GpuState;
+#[cfg(target_os = "windows")]
+use crate::tab::TaskProgress;
 use crate::tab::TabId;
 use crate::tab_bar::TAB_BAR_HEIGHT;
 
@@ -16,6 +19,19 @@ pub struct TermWindow {
     pub active_tab: usize,
     pub tab_bar_height: usize,
     pub is_maximized: bool,
+    /// Title last given to the OS window, to skip redundant updates.
+    pub os_title: String,
+    /// Viewport line of the exit status mark under the mouse.
+    pub gutter_hover: Option<usize>,
+    /// Keys typed here go to every tab not left out of the broadcast
+    /// (`Action::ToggleBroadcast`).
+    pub broadcast: bool,
+    /// When the window opened. `Action::MoveTabToWindow` numbers windows
+    /// in this order.
+    pub opened: Instant,
+    /// Progress last shown on the taskbar button.
+    #[cfg(target_os = "windows")]
+    pub taskbar_progress: TaskProgress,
 }
 
 impl TermWindow {
@@ -30,6 +46,12 @@ impl TermWindow {
             active_tab: 0,
             tab_bar_height: TAB_BAR_HEIGHT,
             is_maximized: false,
+            os_title: String::new(),
+            gutter_hover: None,
+            broadcast: false,
+            opened: Instant::now(),
+            #[cfg(target_os = "windows")]
+            taskbar_progress: TaskProgress::Hidden,
         })
     }
 
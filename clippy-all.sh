@@ -6,5 +6,9 @@ TARGET="x86_64-pc-windows-gnu"
 echo "=== cargo clippy --workspace (${TARGET}) ==="
 cargo clippy --workspace --target "${TARGET}" -- -D warnings
 
+echo ""
+echo "=== cargo clippy -p oriterm_core --no-default-features ==="
+cargo clippy -p oriterm_core --no-default-features -- -D warnings
+
 echo ""
 echo "All clippy checks passed."
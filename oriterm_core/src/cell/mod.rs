@@ -5,8 +5,10 @@
 //! underlines, or hyperlinks allocate heap storage via `CellExtra`. Extra data
 //! is stored behind `Arc` so cloning a cell (e.g. from cursor template) is O(1).
 
-use std::fmt;
-use std::sync::Arc;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
 
 use bitflags::bitflags;
 use unicode_width::UnicodeWidthChar;
@@ -16,20 +18,35 @@ bitflags! {
     /// Per-cell attribute flags (SGR and internal).
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub struct CellFlags: u16 {
+        /// Bold or increased intensity (SGR 1).
         const BOLD              = 1 << 0;
+        /// Faint or decreased intensity (SGR 2).
         const DIM               = 1 << 1;
+        /// Italic (SGR 3).
         const ITALIC            = 1 << 2;
+        /// Single underline (SGR 4).
         const UNDERLINE         = 1 << 3;
+        /// Blinking text (SGR 5).
         const BLINK             = 1 << 4;
+        /// Swap foreground and background (SGR 7).
         const INVERSE           = 1 << 5;
+        /// Concealed text (SGR 8).
         const HIDDEN            = 1 << 6;
+        /// Crossed-out text (SGR 9).
         const STRIKETHROUGH     = 1 << 7;
+        /// First cell of a double-width character.
         const WIDE_CHAR         = 1 << 8;
+        /// Placeholder cell following a wide character.
         const WIDE_CHAR_SPACER  = 1 << 9;
+        /// Line soft-wrapped at this cell (last column).
         const WRAP              = 1 << 10;
+        /// Curly underline (SGR 4:3).
         const CURLY_UNDERLINE   = 1 << 11;
+        /// Dotted underline (SGR 4:4).
         const DOTTED_UNDERLINE  = 1 << 12;
+        /// Dashed underline (SGR 4:5).
         const DASHED_UNDERLINE  = 1 << 13;
+        /// Double underline (SGR 4:2 or SGR 21).
         const DOUBLE_UNDERLINE  = 1 << 14;
     }
 }
@@ -5,9 +5,10 @@
 //! framework — tests use `VoidListener`, the real app routes events
 //! through winit's event loop proxy.
 
-use std::borrow::Cow;
-use std::fmt;
-use std::sync::Arc;
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::fmt;
 
 use crate::color::Rgb;
 
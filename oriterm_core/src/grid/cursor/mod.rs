@@ -13,10 +13,14 @@ use crate::index::Column;
 /// concept re-exported through `grid::CursorShape`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum CursorShape {
+    /// Filled block (DECSCUSR 1/2).
     #[default]
     Block,
+    /// Underline (DECSCUSR 3/4).
     Underline,
+    /// Vertical bar (DECSCUSR 5/6).
     Bar,
+    /// Outline block, used when the window is unfocused.
     HollowBlock,
 }
 
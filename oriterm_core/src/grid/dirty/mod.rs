@@ -4,7 +4,9 @@
 //! renderer calls `drain()` each frame to discover dirty lines, rebuilds
 //! only those lines' instance buffers, and the tracker resets to clean.
 
-use std::ops::Range;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
 
 /// Tracks which rows have changed since last read.
 ///
@@ -222,9 +222,7 @@ impl Grid {
                 }
                 self.dirty.mark_all();
             }
-            EraseMode::Scrollback => {
-                // Scrollback clearing will be implemented in 1.10.
-            }
+            EraseMode::Scrollback => self.clear_history(),
         }
     }
 
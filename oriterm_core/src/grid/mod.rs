@@ -11,8 +11,10 @@ pub mod navigation;
 pub mod ring;
 pub mod row;
 pub mod scroll;
+pub mod stable_index;
 
-use std::ops::{Index, IndexMut, Range};
+use alloc::vec::Vec;
+use core::ops::{Index, IndexMut, Range};
 
 use crate::index::Line;
 
@@ -21,7 +23,8 @@ pub use dirty::DirtyTracker;
 pub use editing::EraseMode;
 pub use navigation::TabClearMode;
 pub use ring::ScrollbackBuffer;
-pub use row::Row;
+pub use row::{CommandStatus, Row, RowFlags, RowMeta, decode_attrs, encode_attrs};
+pub use stable_index::{StableRowIndex, StableRows};
 
 /// The 2D terminal cell grid.
 ///
@@ -48,6 +51,8 @@ pub struct Grid {
     scrollback: ScrollbackBuffer,
     /// How many lines scrolled back into history (0 = live view).
     display_offset: usize,
+    /// Rows that have left scrollback (evicted or cleared) since creation.
+    total_evicted: u64,
     /// Tracks which rows have changed since last drain.
     dirty: DirtyTracker,
 }
@@ -77,6 +82,7 @@ impl Grid {
             scroll_region: 0..lines,
             scrollback: ScrollbackBuffer::new(max_scrollback),
             display_offset: 0,
+            total_evicted: 0,
             dirty: DirtyTracker::new(lines),
         }
     }
@@ -101,6 +107,11 @@ impl Grid {
         &mut self.cursor
     }
 
+    /// Cursor saved by DECSC, if any.
+    pub fn saved_cursor(&self) -> Option<&Cursor> {
+        self.saved_cursor.as_ref()
+    }
+
     /// Immutable reference to tab stops.
     #[cfg(test)]
     pub(crate) fn tab_stops(&self) -> &[bool] {
@@ -122,6 +133,22 @@ impl Grid {
         &self.scrollback
     }
 
+    /// Row at absolute index `abs_row` (0 = oldest scrollback row, then
+    /// the visible rows). Returns `None` past the bottom of the grid.
+    pub fn absolute_row(&self, abs_row: usize) -> Option<&Row> {
+        let history = self.scrollback.len();
+        match abs_row.checked_sub(history) {
+            Some(line) => self.rows.get(line),
+            None => self.scrollback.get(history - 1 - abs_row),
+        }
+    }
+
+    /// Rows that have left scrollback history since the grid was created,
+    /// whether evicted by the capacity limit or discarded by a clear.
+    pub fn total_evicted(&self) -> u64 {
+        self.total_evicted
+    }
+
     /// Immutable reference to the dirty tracker.
     pub fn dirty(&self) -> &DirtyTracker {
         &self.dirty
@@ -146,6 +173,22 @@ impl Grid {
         }
     }
 
+    /// Return to the live view (display offset 0).
+    pub fn scroll_to_bottom(&mut self) {
+        if self.display_offset != 0 {
+            self.display_offset = 0;
+            self.dirty.mark_all();
+        }
+    }
+
+    /// Discard all scrollback history and return to the live view.
+    pub fn clear_history(&mut self) {
+        self.total_evicted += self.scrollback.len() as u64;
+        self.scrollback.clear();
+        self.display_offset = 0;
+        self.dirty.mark_all();
+    }
+
     /// Initialize tab stops every 8 columns.
     fn init_tab_stops(cols: usize) -> Vec<bool> {
         (0..cols).map(|c| c % 8 == 0).collect()
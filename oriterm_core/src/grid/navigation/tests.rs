@@ -1,5 +1,5 @@
 use super::TabClearMode;
-use crate::grid::Grid;
+use crate::grid::{Cursor, Grid};
 use crate::index::{Column, Line};
 
 #[test]
@@ -169,6 +169,7 @@ fn save_and_restore_cursor_round_trip() {
     grid.cursor_mut().set_line(10);
     grid.cursor_mut().set_col(Column(42));
     grid.save_cursor();
+    assert_eq!(grid.saved_cursor().map(Cursor::line), Some(10));
 
     // Move cursor elsewhere.
     grid.cursor_mut().set_line(0);
@@ -4,6 +4,9 @@
 //! The buffer grows incrementally up to `max_scrollback`, then overwrites
 //! the oldest entry on each push (classic ring buffer).
 
+use alloc::vec::Vec;
+use core::mem;
+
 use super::row::Row;
 
 /// Default maximum scrollback lines.
@@ -37,20 +40,25 @@ impl ScrollbackBuffer {
         }
     }
 
-    /// Add a row to scrollback (evicts the oldest if full).
-    pub(super) fn push(&mut self, row: Row) {
+    /// Add a row to scrollback, returning the row that left history.
+    ///
+    /// When the buffer is full the oldest row is evicted and returned; with
+    /// a capacity of zero the pushed row itself is returned.
+    pub(super) fn push(&mut self, row: Row) -> Option<Row> {
         if self.max_scrollback == 0 {
-            return;
+            return Some(row);
         }
 
         if self.inner.len() < self.max_scrollback {
             // Growing phase: just append.
             self.inner.push(row);
             self.len = self.inner.len();
+            None
         } else {
             // Full: overwrite the oldest slot and advance start.
-            self.inner[self.start] = row;
+            let evicted = mem::replace(&mut self.inner[self.start], row);
             self.start = (self.start + 1) % self.max_scrollback;
+            Some(evicted)
         }
     }
 
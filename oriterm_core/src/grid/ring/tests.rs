@@ -1,6 +1,6 @@
 use crate::cell::Cell;
 use crate::grid::row::Row;
-use crate::grid::Grid;
+use crate::grid::{EraseMode, Grid};
 use crate::index::{Column, Line};
 
 use super::ScrollbackBuffer;
@@ -151,6 +151,20 @@ fn exact_capacity_boundary_first_eviction() {
     assert_eq!(row_text(sb.get(2).unwrap()), "R1");
 }
 
+#[test]
+fn push_returns_evicted_row() {
+    let mut sb = ScrollbackBuffer::new(2);
+    assert!(sb.push(make_row("R0")).is_none());
+    assert!(sb.push(make_row("R1")).is_none());
+
+    let evicted = sb.push(make_row("R2")).expect("full buffer evicts");
+    assert_eq!(row_text(&evicted), "R0");
+
+    let mut sb_zero = ScrollbackBuffer::new(0);
+    let dropped = sb_zero.push(make_row("A")).expect("zero capacity drops the row");
+    assert_eq!(row_text(&dropped), "A");
+}
+
 // ---------------------------------------------------------------------------
 // Wide character scrollback tests
 // ---------------------------------------------------------------------------
@@ -298,6 +312,37 @@ fn display_offset_clamped_to_scrollback_len() {
     assert_eq!(grid.display_offset(), 0);
 }
 
+#[test]
+fn scroll_to_bottom_returns_to_live_view() {
+    let mut grid = Grid::new(3, 3);
+    for _ in 0..4 {
+        grid.scroll_up(1);
+    }
+    grid.scroll_display(2);
+    grid.dirty_mut().drain().for_each(drop);
+
+    grid.scroll_to_bottom();
+    assert_eq!(grid.display_offset(), 0);
+    assert!(grid.dirty().is_any_dirty());
+}
+
+#[test]
+fn erase_scrollback_clears_history_and_offset() {
+    let mut grid = Grid::new(3, 3);
+    write_row(&mut grid, 0, "AAA");
+    grid.scroll_up(1);
+    write_row(&mut grid, 0, "BBB");
+    grid.scroll_up(1);
+    write_row(&mut grid, 0, "CCC");
+    grid.scroll_display(1);
+
+    grid.erase_display(EraseMode::Scrollback);
+    assert!(grid.scrollback().is_empty());
+    assert_eq!(grid.display_offset(), 0);
+    // Visible content is untouched.
+    assert_eq!(grid[Line(0)][Column(0)].ch, 'C');
+}
+
 #[test]
 fn total_lines_reflects_scrollback() {
     let mut grid = Grid::new(3, 5);
@@ -0,0 +1,170 @@
+//! Per-row line attributes.
+//!
+//! Every row carries a one-byte [`RowFlags`] word. Rarer, larger attributes
+//! (command exit status, write timestamp) live in a boxed [`RowMeta`] side
+//! table that ordinary rows never allocate. Both serialize to a compact
+//! byte form so they survive with saved scrollback.
+
+use alloc::vec::Vec;
+
+use bitflags::bitflags;
+
+bitflags! {
+    /// Line-level flags for one row.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct RowFlags: u8 {
+        /// A shell prompt starts on this row (OSC 133;A).
+        const PROMPT_START         = 1 << 0;
+        /// The command line starts on this row (OSC 133;B).
+        const COMMAND_START        = 1 << 1;
+        /// Command output starts on this row (OSC 133;C).
+        const OUTPUT_START         = 1 << 2;
+        /// A command finished on this row (OSC 133;D).
+        const COMMAND_END          = 1 << 3;
+        /// Double-width line (DECDWL).
+        const DOUBLE_WIDTH         = 1 << 4;
+        /// Top half of a double-height line (DECDHL).
+        const DOUBLE_HEIGHT_TOP    = 1 << 5;
+        /// Bottom half of a double-height line (DECDHL).
+        const DOUBLE_HEIGHT_BOTTOM = 1 << 6;
+    }
+}
+
+impl RowFlags {
+    /// Shell integration marks, which follow their text through reflow.
+    pub const SEMANTIC: Self = Self::PROMPT_START
+        .union(Self::COMMAND_START)
+        .union(Self::OUTPUT_START)
+        .union(Self::COMMAND_END);
+}
+
+/// Rare per-row attributes, kept off the row itself.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RowMeta {
+    /// Exit status from OSC 133;D, on the row where the command ended.
+    pub exit_status: Option<i32>,
+    /// When the row was written, in milliseconds since the Unix epoch.
+    pub timestamp_ms: Option<u64>,
+    /// How the command whose prompt starts on this row finished.
+    pub command: Option<CommandStatus>,
+}
+
+/// The outcome of one shell command, recorded on its prompt row at OSC 133;D.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandStatus {
+    /// Exit code the shell reported, if any.
+    pub exit_code: Option<i32>,
+    /// Time from OSC 133;B to D, when the command line was marked.
+    pub duration_ms: Option<u64>,
+}
+
+/// `RowMeta` presence bits in the serialized form.
+const HAS_EXIT_STATUS: u8 = 1 << 0;
+const HAS_TIMESTAMP: u8 = 1 << 1;
+const HAS_COMMAND: u8 = 1 << 2;
+const HAS_COMMAND_EXIT: u8 = 1 << 3;
+const HAS_COMMAND_DURATION: u8 = 1 << 4;
+
+impl RowMeta {
+    /// Whether no attribute is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Which fields are present, as the serialized presence byte.
+    fn presence(&self) -> u8 {
+        let mut present = 0;
+        if self.exit_status.is_some() {
+            present |= HAS_EXIT_STATUS;
+        }
+        if self.timestamp_ms.is_some() {
+            present |= HAS_TIMESTAMP;
+        }
+        if let Some(command) = self.command {
+            present |= HAS_COMMAND;
+            if command.exit_code.is_some() {
+                present |= HAS_COMMAND_EXIT;
+            }
+            if command.duration_ms.is_some() {
+                present |= HAS_COMMAND_DURATION;
+            }
+        }
+        present
+    }
+}
+
+/// Append `flags` and `meta` to `out` in the compact form saved with
+/// scrollback: the flags byte, a presence byte, then each present field
+/// little-endian.
+pub fn encode_attrs(flags: RowFlags, meta: Option<&RowMeta>, out: &mut Vec<u8>) {
+    let meta = meta.copied().unwrap_or_default();
+    out.extend_from_slice(&[flags.bits(), meta.presence()]);
+    if let Some(status) = meta.exit_status {
+        out.extend_from_slice(&status.to_le_bytes());
+    }
+    if let Some(ts) = meta.timestamp_ms {
+        out.extend_from_slice(&ts.to_le_bytes());
+    }
+    if let Some(command) = meta.command {
+        if let Some(code) = command.exit_code {
+            out.extend_from_slice(&code.to_le_bytes());
+        }
+        if let Some(ms) = command.duration_ms {
+            out.extend_from_slice(&ms.to_le_bytes());
+        }
+    }
+}
+
+/// Read attributes written by [`encode_attrs`] from the start of `bytes`.
+///
+/// Returns the flags, the side table (`None` when empty), and the number of
+/// bytes consumed; `None` if `bytes` is truncated. Unknown flag bits are
+/// dropped.
+pub fn decode_attrs(bytes: &[u8]) -> Option<(RowFlags, Option<RowMeta>, usize)> {
+    let (&[flags, present], rest) = bytes.split_first_chunk::<2>()?;
+    let mut fields = Fields {
+        rest,
+        present,
+        truncated: false,
+    };
+    let mut meta = RowMeta {
+        exit_status: fields.next(HAS_EXIT_STATUS).map(i32::from_le_bytes),
+        timestamp_ms: fields.next(HAS_TIMESTAMP).map(u64::from_le_bytes),
+        command: None,
+    };
+    if present & HAS_COMMAND != 0 {
+        meta.command = Some(CommandStatus {
+            exit_code: fields.next(HAS_COMMAND_EXIT).map(i32::from_le_bytes),
+            duration_ms: fields.next(HAS_COMMAND_DURATION).map(u64::from_le_bytes),
+        });
+    }
+    if fields.truncated {
+        return None;
+    }
+    let used = bytes.len() - fields.rest.len();
+    let meta = (!meta.is_empty()).then_some(meta);
+    Some((RowFlags::from_bits_truncate(flags), meta, used))
+}
+
+/// The little-endian fields after the presence byte.
+struct Fields<'a> {
+    rest: &'a [u8],
+    present: u8,
+    /// A present field ran past the end of the input.
+    truncated: bool,
+}
+
+impl Fields<'_> {
+    /// The next `N` bytes if `bit` is present.
+    fn next<const N: usize>(&mut self, bit: u8) -> Option<[u8; N]> {
+        if self.present & bit == 0 {
+            return None;
+        }
+        let Some((value, tail)) = self.rest.split_first_chunk::<N>() else {
+            self.truncated = true;
+            return None;
+        };
+        self.rest = tail;
+        Some(*value)
+    }
+}
@@ -1,13 +1,21 @@
 //! Terminal grid row.
 //!
 //! A `Row` is a contiguous array of `Cell`s representing one terminal line,
-//! with occupancy tracking for efficient sparse-row operations.
+//! with occupancy tracking for efficient sparse-row operations and
+//! line-level attributes ([`RowFlags`], [`RowMeta`]).
 
-use std::ops::{Index, IndexMut, Range};
+mod attrs;
+
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::{Index, IndexMut, Range};
 
 use crate::cell::Cell;
 use crate::index::Column;
 
+pub use attrs::{CommandStatus, RowFlags, RowMeta, decode_attrs, encode_attrs};
+
 /// One row of cells in the terminal grid.
 #[derive(Debug, Clone)]
 pub struct Row {
@@ -19,13 +27,18 @@ pub struct Row {
     /// The value may exceed the true occupancy (lazy dirty-tracking, matching
     /// Alacritty's pattern). Use `clamp_occ` / `set_occ` for O(1) adjustments.
     occ: usize,
+    /// Line-level flags (prompt marks, double-width/height).
+    flags: RowFlags,
+    /// Rare attributes; `None` for almost every row.
+    meta: Option<Box<RowMeta>>,
 }
 
-/// Equality compares cell content only — `occ` is internal bookkeeping
-/// (dirty-tracking upper bound) and must not affect semantic equality.
+/// Equality compares cell content and line attributes — `occ` is internal
+/// bookkeeping (dirty-tracking upper bound) and must not affect semantic
+/// equality.
 impl PartialEq for Row {
     fn eq(&self, other: &Self) -> bool {
-        self.inner == other.inner
+        self.inner == other.inner && self.flags == other.flags && self.meta == other.meta
     }
 }
 
@@ -37,10 +50,13 @@ impl Row {
         Self {
             inner: vec![Cell::default(); cols],
             occ: 0,
+            flags: RowFlags::empty(),
+            meta: None,
         }
     }
 
-    /// Reset all cells to the template, resizing if needed.
+    /// Reset all cells to the template, resizing if needed. Line attributes
+    /// are cleared.
     ///
     /// Only iterates `[0..occ]` when the template background matches existing
     /// empty cells (the common case). When template bg differs (BCE), marks
@@ -59,6 +75,33 @@ impl Row {
             cell.reset(template);
         }
         self.occ = 0;
+        self.flags = RowFlags::empty();
+        self.meta = None;
+    }
+
+    /// Line-level flags.
+    pub fn flags(&self) -> RowFlags {
+        self.flags
+    }
+
+    /// Set `flags` on this row.
+    pub fn insert_flags(&mut self, flags: RowFlags) {
+        self.flags.insert(flags);
+    }
+
+    /// Clear `flags` on this row.
+    pub fn remove_flags(&mut self, flags: RowFlags) {
+        self.flags.remove(flags);
+    }
+
+    /// Rare attributes, if any are set.
+    pub fn meta(&self) -> Option<&RowMeta> {
+        self.meta.as_deref()
+    }
+
+    /// Mutable rare attributes, allocating the side table on first use.
+    pub fn meta_mut(&mut self) -> &mut RowMeta {
+        self.meta.get_or_insert_default()
     }
 
     /// Number of columns in this row.
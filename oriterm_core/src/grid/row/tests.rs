@@ -1,4 +1,4 @@
-use super::Row;
+use super::{CommandStatus, Row, RowFlags, RowMeta, decode_attrs, encode_attrs};
 use crate::cell::{Cell, CellFlags};
 use crate::index::Column;
 
@@ -265,3 +265,49 @@ fn truncate_beyond_row_is_noop() {
     row.truncate(Column(20), &Cell::default());
     assert_eq!(row[Column(0)].ch, 'A');
 }
+
+#[test]
+fn line_attributes_reset_with_the_row() {
+    let mut row = Row::new(10);
+    assert_eq!(row.flags(), RowFlags::empty());
+    assert!(row.meta().is_none());
+
+    row.insert_flags(RowFlags::PROMPT_START | RowFlags::COMMAND_END);
+    row.remove_flags(RowFlags::COMMAND_END);
+    row.meta_mut().exit_status = Some(1);
+    assert_eq!(row.flags(), RowFlags::PROMPT_START);
+    assert_eq!(row.meta().and_then(|m| m.exit_status), Some(1));
+
+    row.reset(10, &Cell::default());
+    assert_eq!(row.flags(), RowFlags::empty());
+    assert!(row.meta().is_none());
+}
+
+#[test]
+fn line_attributes_round_trip_through_bytes() {
+    let meta = RowMeta {
+        exit_status: Some(-2),
+        timestamp_ms: Some(1_700_000_000_000),
+        command: Some(CommandStatus {
+            exit_code: Some(1),
+            duration_ms: None,
+        }),
+    };
+    let flags = RowFlags::COMMAND_END | RowFlags::DOUBLE_WIDTH;
+
+    let mut bytes = Vec::new();
+    encode_attrs(flags, Some(&meta), &mut bytes);
+    encode_attrs(RowFlags::empty(), None, &mut bytes);
+    assert_eq!(bytes.len(), 2 + 4 + 8 + 4 + 2);
+
+    let (decoded, decoded_meta, used) = decode_attrs(&bytes).expect("complete attributes");
+    assert_eq!((decoded, decoded_meta), (flags, Some(meta)));
+    assert_eq!(
+        decode_attrs(&bytes[used..]),
+        Some((RowFlags::empty(), None, 2))
+    );
+
+    // Truncated input decodes to nothing.
+    assert_eq!(decode_attrs(&bytes[..5]), None);
+    assert_eq!(decode_attrs(&bytes[..1]), None);
+}
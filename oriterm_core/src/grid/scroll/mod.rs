@@ -4,8 +4,8 @@
 //! `insert_lines`, and `delete_lines`. All operations use O(1) rotation
 //! of existing row allocations and fill new rows with BCE background.
 
-use std::mem;
-use std::ops::Range;
+use core::mem;
+use core::ops::Range;
 
 use crate::cell::Cell;
 use crate::index::Column;
@@ -62,7 +62,9 @@ impl Grid {
                 // The blank rotates to the bottom via scroll_range_up, where
                 // it would be reset anyway — but it's already blank (no-op).
                 let evicted = mem::replace(&mut self.rows[i], Row::new(self.cols));
-                self.scrollback.push(evicted);
+                if self.scrollback.push(evicted).is_some() {
+                    self.total_evicted += 1;
+                }
             }
         }
 
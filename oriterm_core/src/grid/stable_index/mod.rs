@@ -0,0 +1,85 @@
+//! Stable row identity that survives scrollback eviction.
+//!
+//! Absolute row indices (0 = oldest scrollback row, then the visible rows)
+//! shift down by one every time a full scrollback evicts its oldest row.
+//! Anything that must keep pointing at the same content while output
+//! streams in, such as a selection, stores a [`StableRowIndex`] instead.
+
+use super::Grid;
+
+/// A grid whose rows a [`StableRowIndex`] can name.
+///
+/// Implemented by [`Grid`]. A front end with its own grid type implements
+/// it to share stable indices, and the search matches keyed by them, with
+/// this crate.
+pub trait StableRows {
+    /// Rows that have left scrollback history since the grid was created.
+    fn evicted_rows(&self) -> u64;
+    /// Rows currently in scrollback history.
+    fn history_rows(&self) -> usize;
+    /// Rows on screen.
+    fn screen_rows(&self) -> usize;
+    /// Rows the view is scrolled back into history (0 = live view).
+    fn view_offset(&self) -> usize;
+}
+
+impl StableRows for Grid {
+    fn evicted_rows(&self) -> u64 {
+        self.total_evicted
+    }
+
+    fn history_rows(&self) -> usize {
+        self.scrollback.len()
+    }
+
+    fn screen_rows(&self) -> usize {
+        self.lines
+    }
+
+    fn view_offset(&self) -> usize {
+        self.display_offset
+    }
+}
+
+/// Row identity that stays fixed as scrollback evicts rows.
+///
+/// Row 0 is the first row ever written to the grid. Each row keeps its
+/// index for as long as it exists; once evicted it can no longer be
+/// resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StableRowIndex(pub u64);
+
+impl StableRowIndex {
+    /// Stable index of absolute row `abs_row` (0 = oldest scrollback row).
+    pub fn from_absolute(grid: &impl StableRows, abs_row: usize) -> Self {
+        Self(grid.evicted_rows() + abs_row as u64)
+    }
+
+    /// Stable index of visible line `line`, accounting for the display
+    /// offset.
+    pub fn from_visible(grid: &impl StableRows, line: usize) -> Self {
+        let top = grid.history_rows().saturating_sub(grid.view_offset());
+        Self::from_absolute(grid, top + line)
+    }
+
+    /// Absolute row index (0 = oldest scrollback row) of this row.
+    ///
+    /// Returns `None` if the row has been evicted or lies past the bottom
+    /// of the grid.
+    pub fn to_absolute(self, grid: &impl StableRows) -> Option<usize> {
+        let abs = usize::try_from(self.0.checked_sub(grid.evicted_rows())?).ok()?;
+        (abs < grid.history_rows() + grid.screen_rows()).then_some(abs)
+    }
+
+    /// Visible line of this row at the current display offset.
+    ///
+    /// Returns `None` if the row is evicted or scrolled out of view.
+    pub fn to_visible(self, grid: &impl StableRows) -> Option<usize> {
+        let top = grid.history_rows().saturating_sub(grid.view_offset());
+        let line = self.to_absolute(grid)?.checked_sub(top)?;
+        (line < grid.screen_rows()).then_some(line)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,102 @@
+use crate::cell::Cell;
+use crate::grid::Grid;
+use crate::index::{Column, Line};
+
+use super::StableRowIndex;
+
+/// Write `ch` into the first cell of visible line `line`.
+fn tag_row(grid: &mut Grid, line: usize, ch: char) {
+    grid[Line(line as i32)][Column(0)] = Cell {
+        ch,
+        ..Cell::default()
+    };
+}
+
+/// Push `count` rows into scrollback by scrolling the full screen.
+fn scroll(grid: &mut Grid, count: usize) {
+    for _ in 0..count {
+        grid.scroll_up(1);
+    }
+}
+
+#[test]
+fn fresh_grid_stable_equals_absolute() {
+    let grid = Grid::with_scrollback(3, 4, 10);
+    let idx = StableRowIndex::from_visible(&grid, 2);
+    assert_eq!(idx, StableRowIndex(2));
+    assert_eq!(idx.to_absolute(&grid), Some(2));
+    assert_eq!(idx.to_visible(&grid), Some(2));
+}
+
+#[test]
+fn index_follows_row_while_scrollback_fills() {
+    let mut grid = Grid::with_scrollback(3, 4, 10);
+    tag_row(&mut grid, 1, 'X');
+    let idx = StableRowIndex::from_visible(&grid, 1);
+
+    scroll(&mut grid, 1);
+    assert_eq!(grid.total_evicted(), 0);
+    assert_eq!(idx.to_absolute(&grid), Some(1));
+    assert_eq!(idx.to_visible(&grid), Some(0));
+    assert_eq!(grid[Line(0)][Column(0)].ch, 'X');
+}
+
+#[test]
+fn index_survives_eviction() {
+    let mut grid = Grid::with_scrollback(3, 4, 2);
+    scroll(&mut grid, 2);
+    // Scrollback is full; the tagged row is the bottom visible row.
+    tag_row(&mut grid, 2, 'X');
+    let idx = StableRowIndex::from_visible(&grid, 2);
+    assert_eq!(idx.to_absolute(&grid), Some(4));
+
+    // Each further scroll evicts a row, shifting absolute indices down.
+    scroll(&mut grid, 2);
+    assert_eq!(grid.total_evicted(), 2);
+    assert_eq!(idx.to_absolute(&grid), Some(2));
+    assert_eq!(idx.to_visible(&grid), Some(0));
+    assert_eq!(grid[Line(0)][Column(0)].ch, 'X');
+}
+
+#[test]
+fn evicted_row_no_longer_resolves() {
+    let mut grid = Grid::with_scrollback(2, 4, 1);
+    let idx = StableRowIndex::from_visible(&grid, 0);
+    scroll(&mut grid, 1);
+    assert_eq!(idx.to_absolute(&grid), Some(0));
+
+    scroll(&mut grid, 1);
+    assert_eq!(idx.to_absolute(&grid), None);
+}
+
+#[test]
+fn clear_history_counts_as_eviction() {
+    let mut grid = Grid::with_scrollback(2, 4, 10);
+    scroll(&mut grid, 3);
+    let idx = StableRowIndex::from_visible(&grid, 0);
+
+    grid.clear_history();
+    assert_eq!(grid.total_evicted(), 3);
+    assert_eq!(idx.to_absolute(&grid), Some(0));
+    assert_eq!(StableRowIndex(0).to_absolute(&grid), None);
+}
+
+#[test]
+fn from_visible_accounts_for_display_offset() {
+    let mut grid = Grid::with_scrollback(2, 4, 10);
+    scroll(&mut grid, 4);
+    grid.scroll_display(3);
+
+    let idx = StableRowIndex::from_visible(&grid, 0);
+    assert_eq!(idx.to_absolute(&grid), Some(1));
+    assert_eq!(idx.to_visible(&grid), Some(0));
+
+    grid.scroll_to_bottom();
+    assert_eq!(idx.to_visible(&grid), None);
+}
+
+#[test]
+fn rows_past_the_bottom_do_not_resolve() {
+    let grid = Grid::with_scrollback(2, 4, 10);
+    assert_eq!(StableRowIndex(2).to_absolute(&grid), None);
+}
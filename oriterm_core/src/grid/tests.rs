@@ -79,3 +79,19 @@ fn all_rows_initialized_empty() {
         }
     }
 }
+
+#[test]
+fn absolute_row_spans_scrollback_then_screen() {
+    let mut grid = Grid::with_scrollback(2, 4, 10);
+    for ch in ['a', 'b', 'c'] {
+        grid[Line(1)][Column(0)].ch = ch;
+        grid.scroll_up(1);
+    }
+    // Oldest scrollback row first, then the visible rows.
+    let first = |abs| grid.absolute_row(abs).map(|row| row[Column(0)].ch);
+    assert_eq!(first(0), Some(' '));
+    assert_eq!(first(1), Some('a'));
+    assert_eq!(first(2), Some('b'));
+    assert_eq!(first(3), Some('c'));
+    assert_eq!(first(5), None);
+}
@@ -4,9 +4,9 @@
 //! `Point` combines them into a grid coordinate. `Side`, `Direction`, and
 //! `Boundary` encode semantic meanings used by selection and navigation.
 
-use std::cmp::Ordering;
-use std::fmt;
-use std::ops::{Add, AddAssign, Sub, SubAssign};
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Add, AddAssign, Sub, SubAssign};
 
 /// Generate arithmetic and conversion impls for a newtype index wrapper.
 macro_rules! index_ops {
@@ -74,7 +74,9 @@ index_ops!(Column, usize);
 /// A grid coordinate combining a line and column.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub struct Point<L = Line> {
+    /// Line coordinate.
     pub line: L,
+    /// Column coordinate.
     pub column: Column,
 }
 
@@ -103,14 +105,18 @@ impl<L: Ord> PartialOrd for Point<L> {
 /// Which half of a cell the cursor is on (for selection granularity).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Side {
+    /// Left half of the cell.
     Left,
+    /// Right half of the cell.
     Right,
 }
 
 /// Horizontal direction for search and movement.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
+    /// Toward column 0 / earlier lines.
     Left,
+    /// Toward the last column / later lines.
     Right,
 }
 
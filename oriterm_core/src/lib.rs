@@ -2,21 +2,46 @@
 //!
 //! This crate provides the foundational types for terminal emulation:
 //! cell representation, grid storage, cursor management, scrollback,
-//! and all grid editing/navigation operations. It contains no GUI,
-//! rendering, or platform-specific code.
+//! scrollback search, selection, and all grid editing/navigation
+//! operations. It contains no GUI, rendering, or platform-specific code.
+//!
+//! # API stability
+//!
+//! Everything re-exported from the crate root, plus the public items of
+//! `grid`, `search`, `selection`, `term`, and `index`, is the supported embedding surface and
+//! follows semver: while the version is `0.x`, breaking changes bump the
+//! minor version and are listed in `CHANGELOG.md`.
+//!
+//! # Features
+//!
+//! - `std` (default): links the standard library. Without it the crate is
+//!   `no_std` and needs only `alloc`, so it builds for targets such as
+//!   `wasm32-unknown-unknown`. The core never reads clocks or does I/O;
+//!   embedders feed it bytes and decide when to render.
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+extern crate alloc;
 
 pub mod cell;
 pub mod color;
 pub mod event;
 pub mod grid;
 pub mod index;
+pub mod search;
+pub mod selection;
 pub mod term;
 
 pub use cell::{Cell, CellExtra, CellFlags, Hyperlink};
 pub use color::{Palette, Rgb};
 pub use event::{ClipboardType, Event, EventListener, Notify, VoidListener};
-pub use grid::{Cursor, CursorShape, EraseMode, Grid, Row, TabClearMode};
+pub use grid::{
+    CommandStatus, Cursor, CursorShape, DirtyTracker, EraseMode, Grid, Row, RowFlags, RowMeta,
+    ScrollbackBuffer, StableRowIndex, StableRows, TabClearMode, decode_attrs, encode_attrs,
+};
 pub use index::{Boundary, Column, Direction, Line, Point, Side};
-pub use term::{Term, TermMode};
+pub use search::{SearchMatch, SearchOptions, SearchQuery};
+pub use selection::{Selection, SelectionMode, SelectionPoint};
+pub use term::{Frame, FrameCell, FrameCursor, FrameSource, Term, TermMode};
@@ -0,0 +1,181 @@
+//! Match finding: compile a query and find it in a range of grid rows.
+
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use regex_automata::meta::Regex;
+use regex_automata::util::syntax;
+
+use super::text::{byte_span_to_cols, extract_row_text};
+use super::{SearchMatch, SearchOptions};
+use crate::grid::{Grid, StableRowIndex};
+
+/// A compiled search query.
+///
+/// Cheap to clone, so a background search can take its own copy.
+#[derive(Debug, Clone)]
+pub struct SearchQuery {
+    kind: Kind,
+}
+
+#[derive(Debug, Clone)]
+enum Kind {
+    /// Plain text, lowercased when the search ignores case.
+    Plain { needle: String, case_sensitive: bool },
+    Regex(Regex),
+}
+
+impl SearchQuery {
+    /// Compile `query` with `options`.
+    ///
+    /// Returns `None` if `query` is empty or is not a valid regex.
+    pub fn new(query: &str, options: SearchOptions) -> Option<Self> {
+        if query.is_empty() {
+            return None;
+        }
+        let pattern = match (options.use_regex, options.whole_word) {
+            (false, false) => {
+                let needle = if options.case_sensitive {
+                    query.to_owned()
+                } else {
+                    fold_case(query).0
+                };
+                let case_sensitive = options.case_sensitive;
+                return Some(Self {
+                    kind: Kind::Plain { needle, case_sensitive },
+                });
+            }
+            (true, false) => query.to_owned(),
+            // The half boundaries only look at the outer side, so a query
+            // that starts or ends in punctuation still finds whole words.
+            (true, true) => format!(r"\b{{start-half}}(?:{query})\b{{end-half}}"),
+            (false, true) => format!(
+                r"\b{{start-half}}{}\b{{end-half}}",
+                regex_syntax::escape(query)
+            ),
+        };
+        let config = syntax::Config::new().case_insensitive(!options.case_sensitive);
+        let regex = Regex::builder().syntax(config).build(&pattern).ok()?;
+        Some(Self {
+            kind: Kind::Regex(regex),
+        })
+    }
+
+    /// Every match in the grid, scrollback included, oldest first.
+    pub fn find_all(&self, grid: &Grid) -> Vec<SearchMatch> {
+        let mut matches = Vec::new();
+        self.find_in_rows(grid, 0..grid.total_lines(), &mut matches);
+        matches
+    }
+
+    /// Append the matches in absolute rows `rows` to `out`, in order.
+    ///
+    /// Rows past the bottom of the grid are ignored, so a caller searching
+    /// in batches can pass a range that ends beyond the grid.
+    pub fn find_in_rows(&self, grid: &Grid, rows: Range<usize>, out: &mut Vec<SearchMatch>) {
+        for abs_row in rows {
+            let Some(row) = grid.absolute_row(abs_row) else {
+                break;
+            };
+            let (text, col_map) = extract_row_text(row);
+            let stable = StableRowIndex::from_absolute(grid, abs_row);
+            self.find_in_text(&text, &col_map, stable, out);
+        }
+    }
+
+    /// Append the matches in `text`, the text of row `stable` with the
+    /// byte-to-column map `col_map` (see [`extract_row_text`]), to `out`.
+    ///
+    /// For front ends with their own row type: extract the row's text the
+    /// same way, and the matches line up with this crate's.
+    pub fn find_in_text(
+        &self,
+        text: &str,
+        col_map: &[usize],
+        stable: StableRowIndex,
+        out: &mut Vec<SearchMatch>,
+    ) {
+        let span = |start, end| byte_span_to_cols(col_map, start, end);
+        let single_row = |(start_col, end_col)| SearchMatch {
+            start_row: stable,
+            start_col,
+            end_row: stable,
+            end_col,
+            captures: Vec::new(),
+        };
+        match &self.kind {
+            // Without groups, skip the slower capture search.
+            Kind::Regex(re) if re.captures_len() == 1 => {
+                out.extend(
+                    re.find_iter(text)
+                        .filter_map(|m| span(m.start(), m.end()))
+                        .map(single_row),
+                );
+            }
+            Kind::Regex(re) => {
+                for caps in re.captures_iter(text) {
+                    let Some(whole) = caps.get_match().and_then(|m| span(m.start(), m.end()))
+                    else {
+                        continue;
+                    };
+                    let mut found = single_row(whole);
+                    found.captures = caps
+                        .iter()
+                        .skip(1)
+                        .flatten()
+                        .filter_map(|s| span(s.start, s.end))
+                        .collect();
+                    out.push(found);
+                }
+            }
+            Kind::Plain { needle, case_sensitive: true } => {
+                for start in overlapping_starts(text, needle) {
+                    out.extend(span(start, start + needle.len()).map(single_row));
+                }
+            }
+            Kind::Plain { needle, case_sensitive: false } => {
+                // Lowercasing can change a character's length, so map the
+                // match back to the bytes it covers in `text`.
+                let (lowered, origin) = fold_case(text);
+                for start in overlapping_starts(&lowered, needle) {
+                    let end = origin[start + needle.len() - 1] + 1;
+                    out.extend(span(origin[start], end).map(single_row));
+                }
+            }
+        }
+    }
+}
+
+/// `text` lowercased, and for each byte of it the offset in `text` of the
+/// character it came from.
+fn fold_case(text: &str) -> (String, Vec<usize>) {
+    let mut lowered = String::with_capacity(text.len());
+    let mut origin = Vec::with_capacity(text.len());
+    for (offset, ch) in text.char_indices() {
+        for lower in ch.to_lowercase() {
+            lowered.push(lower);
+            origin.resize(lowered.len(), offset);
+        }
+    }
+    (lowered, origin)
+}
+
+/// Byte offsets where `needle` starts in `haystack`, overlaps included.
+fn overlapping_starts(haystack: &str, needle: &str) -> Vec<usize> {
+    let mut starts = Vec::new();
+    let mut from = 0;
+    while let Some(pos) = haystack.get(from..).and_then(|rest| rest.find(needle)) {
+        let start = from + pos;
+        starts.push(start);
+        // Resume one character on, not past the match.
+        from = start
+            + haystack
+                .get(start..)
+                .and_then(|rest| rest.chars().next())
+                .map_or(1, char::len_utf8);
+    }
+    starts
+}
@@ -0,0 +1,54 @@
+//! Scrollback search: find text in a [`Grid`] and its scrollback.
+//!
+//! A [`SearchQuery`] compiles a query once, then finds its matches in any
+//! range of absolute rows (0 = oldest scrollback row, then the visible
+//! rows). Matches are keyed by [`StableRowIndex`], so they keep pointing
+//! at the same text while output streams in and scrollback evicts rows.
+//! Searching needs only a `&Grid`: a GUI can run it on a background thread
+//! a batch of rows at a time, and a headless consumer can search directly.
+//!
+//! [`Grid`]: crate::grid::Grid
+
+mod find;
+mod text;
+
+use alloc::vec::Vec;
+
+use crate::grid::StableRowIndex;
+
+pub use find::SearchQuery;
+pub use text::{byte_span_to_cols, extract_row_text};
+
+/// How a query matches text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchOptions {
+    /// Match case exactly; otherwise case is ignored.
+    pub case_sensitive: bool,
+    /// Treat the query as a regular expression.
+    pub use_regex: bool,
+    /// Only match at word boundaries.
+    pub whole_word: bool,
+}
+
+/// One match, from its first cell to its last (both inclusive).
+///
+/// Matches never span rows: `start_row == end_row` for every match the
+/// search finds. Columns count grid cells, so a wide character is two
+/// columns wide.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch {
+    /// Row of the first matched cell.
+    pub start_row: StableRowIndex,
+    /// Column of the first matched cell.
+    pub start_col: usize,
+    /// Row of the last matched cell.
+    pub end_row: StableRowIndex,
+    /// Column of the last matched cell.
+    pub end_col: usize,
+    /// Column spans `(start, end)` of the regex capture groups that took
+    /// part in the match, in group order. Empty for plain-text queries.
+    pub captures: Vec<(usize, usize)>,
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,168 @@
+use crate::grid::{Grid, StableRowIndex};
+use crate::index::{Column, Line};
+
+use super::{SearchMatch, SearchOptions, SearchQuery, byte_span_to_cols, extract_row_text};
+
+/// Helper: a grid holding `rows`, one per line, scrolling older lines into
+/// a scrollback of at most `max_scrollback` rows.
+fn grid_with_rows(lines: usize, cols: usize, max_scrollback: usize, rows: &[&str]) -> Grid {
+    let mut grid = Grid::with_scrollback(lines, cols, max_scrollback);
+    for (i, row) in rows.iter().enumerate() {
+        if i > 0 {
+            grid.carriage_return();
+            grid.linefeed();
+        }
+        for ch in row.chars() {
+            grid.put_char(ch);
+        }
+    }
+    grid
+}
+
+/// Helper: `(row, start_col, end_col)` of every match of `query`.
+fn spans(grid: &Grid, query: &str, options: SearchOptions) -> Vec<(u64, usize, usize)> {
+    SearchQuery::new(query, options)
+        .map(|q| q.find_all(grid))
+        .unwrap_or_default()
+        .iter()
+        .map(|m| (m.start_row.0, m.start_col, m.end_col))
+        .collect()
+}
+
+#[test]
+fn plain_search_ignores_case_by_default() {
+    let grid = grid_with_rows(3, 20, 10, &["Hello world", "say HELLO"]);
+    let found = spans(&grid, "hello", SearchOptions::default());
+    assert_eq!(found, [(0, 0, 4), (1, 4, 8)]);
+}
+
+#[test]
+fn case_sensitive_search_matches_exact_case() {
+    let grid = grid_with_rows(3, 20, 10, &["Hello world", "say HELLO"]);
+    let options = SearchOptions {
+        case_sensitive: true,
+        ..SearchOptions::default()
+    };
+    assert_eq!(spans(&grid, "HELLO", options), [(1, 4, 8)]);
+}
+
+#[test]
+fn plain_search_finds_overlapping_matches() {
+    let grid = grid_with_rows(1, 10, 0, &["aaaa"]);
+    assert_eq!(
+        spans(&grid, "aa", SearchOptions::default()),
+        [(0, 0, 1), (0, 1, 2), (0, 2, 3)]
+    );
+}
+
+#[test]
+fn search_covers_scrollback_with_stable_rows() {
+    let grid = grid_with_rows(2, 10, 10, &["match", "x", "y", "match"]);
+    assert_eq!(grid.scrollback().len(), 2);
+    let query = SearchQuery::new("match", SearchOptions::default());
+    let found = query.map(|q| q.find_all(&grid)).unwrap_or_default();
+    assert_eq!(
+        found[0],
+        SearchMatch {
+            start_row: StableRowIndex(0),
+            start_col: 0,
+            end_row: StableRowIndex(0),
+            end_col: 4,
+            captures: Vec::new(),
+        }
+    );
+    assert_eq!(found[1].start_row, StableRowIndex(3));
+    assert_eq!(found[1].start_row.to_visible(&grid), Some(1));
+}
+
+#[test]
+fn stable_rows_survive_eviction() {
+    let grid = grid_with_rows(2, 10, 1, &["a", "b", "match", "c"]);
+    // Row "a" has been evicted; "b" is the only scrollback row.
+    assert_eq!(grid.total_evicted(), 1);
+    assert_eq!(spans(&grid, "match", SearchOptions::default()), [(2, 0, 4)]);
+}
+
+#[test]
+fn find_in_rows_searches_only_the_range() {
+    let grid = grid_with_rows(4, 10, 0, &["x", "x", "x", "x"]);
+    let Some(query) = SearchQuery::new("x", SearchOptions::default()) else {
+        panic!("plain query compiles");
+    };
+    let mut out = Vec::new();
+    query.find_in_rows(&grid, 1..3, &mut out);
+    let rows: Vec<u64> = out.iter().map(|m| m.start_row.0).collect();
+    assert_eq!(rows, [1, 2]);
+
+    // A range past the bottom of the grid stops there.
+    out.clear();
+    query.find_in_rows(&grid, 3..100, &mut out);
+    assert_eq!(out.len(), 1);
+}
+
+#[test]
+fn regex_search_reports_captures() {
+    let grid = grid_with_rows(1, 30, 0, &["error: code=42"]);
+    let options = SearchOptions {
+        use_regex: true,
+        ..SearchOptions::default()
+    };
+    let found = SearchQuery::new(r"code=(\d+)", options)
+        .map(|q| q.find_all(&grid))
+        .unwrap_or_default();
+    assert_eq!(found.len(), 1);
+    assert_eq!((found[0].start_col, found[0].end_col), (7, 13));
+    assert_eq!(found[0].captures, [(12, 13)]);
+}
+
+#[test]
+fn invalid_regex_and_empty_query_do_not_compile() {
+    let options = SearchOptions {
+        use_regex: true,
+        ..SearchOptions::default()
+    };
+    assert!(SearchQuery::new("(unclosed", options).is_none());
+    assert!(SearchQuery::new("", SearchOptions::default()).is_none());
+}
+
+#[test]
+fn whole_word_skips_matches_inside_words() {
+    let grid = grid_with_rows(1, 30, 0, &["cat concat cat-"]);
+    let options = SearchOptions {
+        whole_word: true,
+        ..SearchOptions::default()
+    };
+    assert_eq!(spans(&grid, "cat", options), [(0, 0, 2), (0, 11, 13)]);
+    assert_eq!(spans(&grid, "cat-", options), [(0, 11, 14)]);
+}
+
+#[test]
+fn wide_chars_map_to_both_columns() {
+    let grid = grid_with_rows(1, 10, 0, &["a好b"]);
+    // '好' fills columns 1 and 2; 'b' is in column 3.
+    assert_eq!(spans(&grid, "好", SearchOptions::default()), [(0, 1, 1)]);
+    assert_eq!(spans(&grid, "b", SearchOptions::default()), [(0, 3, 3)]);
+}
+
+#[test]
+fn combining_marks_belong_to_their_base_cell() {
+    let mut grid = Grid::new(1, 10);
+    grid.put_char('e');
+    grid[Line(0)][Column(0)].push_zerowidth('\u{301}');
+    grid.put_char('x');
+    let (text, col_map) = extract_row_text(&grid[Line(0)]);
+    assert!(text.starts_with("e\u{301}x"));
+    // One entry per byte: the two-byte mark maps to its base's column.
+    assert_eq!(&col_map[..4], [0, 0, 0, 1]);
+    // The span of "\u{301}x" starts in the cell of the mark's base.
+    assert_eq!(byte_span_to_cols(&col_map, 1, 4), Some((0, 1)));
+    assert_eq!(byte_span_to_cols(&col_map, 2, 2), None);
+    assert_eq!(byte_span_to_cols(&col_map, 0, col_map.len() + 1), None);
+}
+
+#[test]
+fn case_folding_that_changes_length_keeps_columns() {
+    // 'İ' lowercases to two characters, one byte longer.
+    let grid = grid_with_rows(1, 10, 0, &["İx"]);
+    assert_eq!(spans(&grid, "x", SearchOptions::default()), [(0, 1, 1)]);
+}
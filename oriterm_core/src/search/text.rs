@@ -0,0 +1,46 @@
+//! Row text extraction: turn a row of cells into a searchable string and
+//! map byte spans of that string back to grid columns.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::cell::CellFlags;
+use crate::grid::Row;
+use crate::index::Column;
+
+/// Text of `row` and, for each byte of it, the column it came from.
+///
+/// Wide-character spacers are skipped, and combining marks follow their
+/// base character and map to its column.
+pub fn extract_row_text(row: &Row) -> (String, Vec<usize>) {
+    let mut text = String::new();
+    let mut col_map = Vec::new();
+    for col in 0..row.cols() {
+        let cell = &row[Column(col)];
+        if cell.flags.contains(CellFlags::WIDE_CHAR_SPACER) {
+            continue;
+        }
+        text.push(if cell.ch == '\0' { ' ' } else { cell.ch });
+        if let Some(extra) = &cell.extra {
+            text.extend(extra.zerowidth.iter());
+        }
+        col_map.resize(text.len(), col);
+    }
+    (text, col_map)
+}
+
+/// Columns `(start, end)`, both inclusive, of the cells covering the byte
+/// span `byte_start..byte_end` of text from [`extract_row_text`], whose
+/// byte-to-column map is `col_map`.
+///
+/// Returns `None` for an empty span or one outside the text.
+pub fn byte_span_to_cols(
+    col_map: &[usize],
+    byte_start: usize,
+    byte_end: usize,
+) -> Option<(usize, usize)> {
+    if byte_start >= byte_end {
+        return None;
+    }
+    Some((*col_map.get(byte_start)?, *col_map.get(byte_end - 1)?))
+}
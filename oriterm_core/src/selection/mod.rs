@@ -0,0 +1,161 @@
+//! Selection in stable row coordinates.
+//!
+//! Points are stored as [`StableRowIndex`] rather than absolute rows, so
+//! an active selection keeps covering the same text while a command
+//! streams output and scrollback evicts rows above it. The model is the
+//! three-point one (anchor, pivot, end) used for character, word, line,
+//! and block selection.
+
+use core::cmp::Ordering;
+
+use crate::grid::{Grid, StableRowIndex};
+use crate::index::{Column, Side};
+
+/// A selection boundary: a cell, and which half of it was hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelectionPoint {
+    /// Row of the cell.
+    pub row: StableRowIndex,
+    /// Column of the cell.
+    pub col: Column,
+    /// Half of the cell the pointer was on.
+    pub side: Side,
+}
+
+impl SelectionPoint {
+    /// First column covered when this point starts a selection: a point on
+    /// the right half of a cell starts at the next column.
+    pub fn start_col(&self) -> usize {
+        match self.side {
+            Side::Left => self.col.0,
+            Side::Right => self.col.0 + 1,
+        }
+    }
+
+    /// Last column covered when this point ends a selection: a point on
+    /// the left half of a cell ends at the previous column.
+    pub fn end_col(&self) -> usize {
+        match self.side {
+            Side::Left => self.col.0.saturating_sub(1),
+            Side::Right => self.col.0,
+        }
+    }
+}
+
+impl Ord for SelectionPoint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let side = |s: Side| matches!(s, Side::Right);
+        self.row
+            .cmp(&other.row)
+            .then(self.col.cmp(&other.col))
+            .then(side(self.side).cmp(&side(other.side)))
+    }
+}
+
+impl PartialOrd for SelectionPoint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Unit a selection grows by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionMode {
+    /// Cell by cell.
+    Char,
+    /// Whole words (double click).
+    Word,
+    /// Whole lines (triple click).
+    Line,
+    /// A rectangle of columns (Alt+drag).
+    Block,
+}
+
+/// A selection of grid cells.
+///
+/// `anchor` is where the selection started and `pivot` the far edge of the
+/// first unit (the end of the clicked word or line; the anchor itself in
+/// [`SelectionMode::Char`]), so dragging back past the anchor keeps that
+/// unit selected. `end` follows the pointer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    /// Unit the selection grows by.
+    pub mode: SelectionMode,
+    /// Where the selection started.
+    pub anchor: SelectionPoint,
+    /// Far edge of the first unit.
+    pub pivot: SelectionPoint,
+    /// Current pointer position.
+    pub end: SelectionPoint,
+}
+
+impl Selection {
+    /// Start a selection of `mode` at `anchor`, with its first unit
+    /// reaching to `pivot`.
+    pub fn new(mode: SelectionMode, anchor: SelectionPoint, pivot: SelectionPoint) -> Self {
+        Self {
+            mode,
+            anchor,
+            pivot,
+            end: anchor,
+        }
+    }
+
+    /// Move the end of the selection to `end`.
+    pub fn update(&mut self, end: SelectionPoint) {
+        self.end = end;
+    }
+
+    /// The first and last points covered, pivot included.
+    pub fn ordered(&self) -> (SelectionPoint, SelectionPoint) {
+        let mut points = [self.anchor, self.pivot, self.end];
+        points.sort();
+        (points[0], points[2])
+    }
+
+    /// Leftmost and rightmost columns of a block selection.
+    pub fn block_columns(&self) -> (usize, usize) {
+        let (start, end) = self.ordered();
+        (start.col.0.min(end.col.0), start.col.0.max(end.col.0))
+    }
+
+    /// Whether the cell at `row`, `col` is selected.
+    pub fn contains(&self, row: StableRowIndex, col: Column) -> bool {
+        let (start, end) = self.ordered();
+        if row < start.row || row > end.row {
+            return false;
+        }
+        let (first, last) = if self.mode == SelectionMode::Block {
+            self.block_columns()
+        } else {
+            let first = if row == start.row {
+                start.start_col()
+            } else {
+                0
+            };
+            let last = if row == end.row {
+                end.end_col()
+            } else {
+                usize::MAX
+            };
+            (first, last)
+        };
+        col.0 >= first && col.0 <= last
+    }
+
+    /// Whether the selection covers nothing: a character selection that
+    /// has not moved.
+    pub fn is_empty(&self) -> bool {
+        self.mode == SelectionMode::Char && self.anchor == self.end
+    }
+
+    /// Whether every row the selection covers is still in `grid`. Once
+    /// scrollback evicts its first row the selection should be cleared.
+    pub fn is_live(&self, grid: &Grid) -> bool {
+        let (start, end) = self.ordered();
+        start.row.to_absolute(grid).is_some() && end.row.to_absolute(grid).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,116 @@
+use crate::cell::Cell;
+use crate::grid::{Grid, StableRowIndex};
+use crate::index::{Column, Line, Side};
+
+use super::{Selection, SelectionMode, SelectionPoint};
+
+fn point(row: u64, col: usize, side: Side) -> SelectionPoint {
+    SelectionPoint {
+        row: StableRowIndex(row),
+        col: Column(col),
+        side,
+    }
+}
+
+/// A character selection from `start` to `end`.
+fn char_selection(start: SelectionPoint, end: SelectionPoint) -> Selection {
+    let mut selection = Selection::new(SelectionMode::Char, start, start);
+    selection.update(end);
+    selection
+}
+
+#[test]
+fn char_selection_spans_rows_and_honors_sides() {
+    let sel = char_selection(point(1, 2, Side::Right), point(3, 1, Side::Left));
+    // Row 1 starts after the clicked cell; row 3 ends before it.
+    assert!(!sel.contains(StableRowIndex(1), Column(2)));
+    assert!(sel.contains(StableRowIndex(1), Column(3)));
+    assert!(sel.contains(StableRowIndex(2), Column(0)));
+    assert!(sel.contains(StableRowIndex(2), Column(500)));
+    assert!(sel.contains(StableRowIndex(3), Column(0)));
+    assert!(!sel.contains(StableRowIndex(3), Column(1)));
+    assert!(!sel.contains(StableRowIndex(4), Column(0)));
+}
+
+#[test]
+fn dragging_backwards_orders_points() {
+    let sel = char_selection(point(5, 4, Side::Left), point(2, 0, Side::Left));
+    let (start, end) = sel.ordered();
+    assert_eq!(start.row, StableRowIndex(2));
+    assert_eq!(end.row, StableRowIndex(5));
+}
+
+#[test]
+fn word_selection_keeps_its_first_word_when_dragged_back() {
+    let mut sel = Selection::new(
+        SelectionMode::Word,
+        point(0, 4, Side::Left),
+        point(0, 8, Side::Right),
+    );
+    sel.update(point(0, 0, Side::Left));
+    assert!(sel.contains(StableRowIndex(0), Column(0)));
+    assert!(sel.contains(StableRowIndex(0), Column(8)));
+    assert!(!sel.contains(StableRowIndex(0), Column(9)));
+}
+
+#[test]
+fn block_selection_is_a_rectangle() {
+    let mut sel = Selection::new(
+        SelectionMode::Block,
+        point(1, 6, Side::Left),
+        point(1, 6, Side::Left),
+    );
+    sel.update(point(3, 2, Side::Left));
+    assert_eq!(sel.block_columns(), (2, 6));
+    assert!(sel.contains(StableRowIndex(2), Column(2)));
+    assert!(sel.contains(StableRowIndex(2), Column(6)));
+    assert!(!sel.contains(StableRowIndex(2), Column(7)));
+    assert!(!sel.contains(StableRowIndex(0), Column(4)));
+}
+
+#[test]
+fn unmoved_char_selection_is_empty() {
+    let start = point(0, 0, Side::Left);
+    assert!(Selection::new(SelectionMode::Char, start, start).is_empty());
+    assert!(!Selection::new(SelectionMode::Line, start, start).is_empty());
+}
+
+#[test]
+fn selection_stays_on_its_text_while_scrollback_evicts() {
+    let mut grid = Grid::with_scrollback(3, 4, 2);
+    for _ in 0..2 {
+        grid.scroll_up(1);
+    }
+    grid[Line(2)][Column(0)] = Cell {
+        ch: 'X',
+        ..Cell::default()
+    };
+    let row = StableRowIndex::from_visible(&grid, 2);
+    let sel = char_selection(
+        SelectionPoint {
+            row,
+            col: Column(0),
+            side: Side::Left,
+        },
+        SelectionPoint {
+            row,
+            col: Column(0),
+            side: Side::Right,
+        },
+    );
+
+    // Two more rows of output evict two rows; the selected row moves up.
+    for _ in 0..2 {
+        grid.scroll_up(1);
+    }
+    assert!(sel.is_live(&grid));
+    let line = row.to_visible(&grid).expect("row still on screen");
+    assert_eq!(grid[Line(line as i32)][Column(0)].ch, 'X');
+    assert!(sel.contains(StableRowIndex::from_visible(&grid, line), Column(0)));
+
+    // Once the row itself is evicted the selection is stale.
+    for _ in 0..3 {
+        grid.scroll_up(1);
+    }
+    assert!(!sel.is_live(&grid));
+}
@@ -0,0 +1,187 @@
+//! Frame snapshots for embedders.
+//!
+//! A host that draws the terminal itself calls [`Term::frame`] after
+//! feeding output: it gets the visible cells with colors resolved against
+//! the palette, the lines that changed since the previous frame, and the
+//! cursor. Nothing is drawn here and nothing is cloned beyond the cells.
+//! Front ends with their own grid type implement [`FrameSource`] to build
+//! the same frames.
+
+use alloc::vec::Vec;
+
+use crate::cell::{Cell, CellFlags};
+use crate::color::{Palette, Rgb};
+use crate::event::EventListener;
+use crate::grid::{CursorShape, Grid};
+use crate::index::Column;
+
+use super::{Term, TermMode};
+
+/// One visible cell with its colors resolved against the palette.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameCell {
+    /// Base character (`' '` for blank cells and wide-char spacers).
+    pub ch: char,
+    /// Combining marks drawn over [`Self::ch`], in order; empty for almost
+    /// every cell.
+    pub zerowidth: Vec<char>,
+    /// Foreground color after inverse and hidden are applied.
+    pub fg: Rgb,
+    /// Background color after inverse is applied.
+    pub bg: Rgb,
+    /// SGR attributes and wide-char markers.
+    pub flags: CellFlags,
+}
+
+impl FrameCell {
+    /// Resolve `cell`'s colors against `palette`.
+    pub fn resolve(cell: &Cell, palette: &Palette) -> Self {
+        let (mut fg, mut bg) = (palette.resolve(cell.fg), palette.resolve(cell.bg));
+        if cell.flags.contains(CellFlags::INVERSE) {
+            core::mem::swap(&mut fg, &mut bg);
+        }
+        if cell.flags.contains(CellFlags::HIDDEN) {
+            fg = bg;
+        }
+        Self {
+            ch: cell.ch,
+            zerowidth: cell
+                .extra
+                .as_ref()
+                .map(|extra| extra.zerowidth.clone())
+                .unwrap_or_default(),
+            fg,
+            bg,
+            flags: cell.flags,
+        }
+    }
+}
+
+/// Cursor position and shape for a [`Frame`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameCursor {
+    /// Viewport line.
+    pub line: usize,
+    /// Column.
+    pub col: usize,
+    /// Shape requested by the application (DECSCUSR).
+    pub shape: CursorShape,
+}
+
+/// The visible screen, row-major, plus the lines that changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// Width in cells.
+    pub cols: usize,
+    /// Height in cells.
+    pub rows: usize,
+    /// `cols * rows` cells, top row first.
+    pub cells: Vec<FrameCell>,
+    /// Viewport lines that changed since the previous frame, ascending.
+    pub damage: Vec<usize>,
+    /// The cursor, unless hidden (DECTCEM) or scrolled out of view.
+    pub cursor: Option<FrameCursor>,
+}
+
+/// A screen that [`Frame::capture`] can snapshot.
+///
+/// [`Term::frame`] captures a [`Grid`] through it. A front end with its own
+/// grid type implements it to hand embedders the same [`Frame`].
+pub trait FrameSource {
+    /// Width and height of the visible screen, in cells.
+    fn frame_size(&self) -> (usize, usize);
+    /// Append the cells of viewport line `line`, colors resolved, to `out`.
+    fn push_frame_row(&self, line: usize, out: &mut Vec<FrameCell>);
+    /// The cursor, unless hidden (DECTCEM) or scrolled out of view.
+    fn frame_cursor(&self) -> Option<FrameCursor>;
+    /// Viewport lines changed since the last call, ascending. Resets the
+    /// damage.
+    fn take_damage(&mut self) -> Vec<usize>;
+}
+
+impl Frame {
+    /// Capture the viewport of `source` and reset its damage.
+    ///
+    /// Returns `None` when nothing changed since the last capture.
+    pub fn capture(source: &mut impl FrameSource) -> Option<Self> {
+        let damage = source.take_damage();
+        if damage.is_empty() {
+            return None;
+        }
+        let (cols, rows) = source.frame_size();
+        let mut cells = Vec::with_capacity(cols * rows);
+        for line in 0..rows {
+            source.push_frame_row(line, &mut cells);
+        }
+        Some(Self {
+            cols,
+            rows,
+            cells,
+            damage,
+            cursor: source.frame_cursor(),
+        })
+    }
+
+    /// The cells of viewport line `line`.
+    pub fn row(&self, line: usize) -> &[FrameCell] {
+        let start = (line * self.cols).min(self.cells.len());
+        let end = (start + self.cols).min(self.cells.len());
+        &self.cells[start..end]
+    }
+}
+
+/// A [`Grid`] with the terminal state its frame depends on.
+struct Screen<'a> {
+    grid: &'a mut Grid,
+    palette: &'a Palette,
+    mode: TermMode,
+    shape: CursorShape,
+}
+
+impl FrameSource for Screen<'_> {
+    fn frame_size(&self) -> (usize, usize) {
+        (self.grid.cols(), self.grid.lines())
+    }
+
+    fn push_frame_row(&self, line: usize, out: &mut Vec<FrameCell>) {
+        let top = self.grid.scrollback().len() - self.grid.display_offset();
+        if let Some(row) = self.grid.absolute_row(top + line) {
+            let cells = (0..self.grid.cols()).map(|col| &row[Column(col)]);
+            out.extend(cells.map(|cell| FrameCell::resolve(cell, self.palette)));
+        }
+    }
+
+    fn frame_cursor(&self) -> Option<FrameCursor> {
+        let visible = self.mode.contains(TermMode::SHOW_CURSOR) && self.grid.display_offset() == 0;
+        visible.then(|| FrameCursor {
+            line: self.grid.cursor().line(),
+            col: self.grid.cursor().col().0,
+            shape: self.shape,
+        })
+    }
+
+    fn take_damage(&mut self) -> Vec<usize> {
+        self.grid.dirty_mut().drain().collect()
+    }
+}
+
+impl<T: EventListener> Term<T> {
+    /// The active screen as of now, or `None` if nothing changed since the
+    /// last frame.
+    pub fn frame(&mut self) -> Option<Frame> {
+        let grid = if self.active_is_alt {
+            &mut self.alt_grid
+        } else {
+            &mut self.grid
+        };
+        Frame::capture(&mut Screen {
+            grid,
+            palette: &self.palette,
+            mode: self.mode,
+            shape: self.cursor_shape,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,76 @@
+//! Tests for frame snapshots.
+
+use vte::ansi::{Color, NamedColor};
+
+use crate::cell::CellFlags;
+use crate::event::VoidListener;
+use crate::grid::CursorShape;
+use crate::index::{Column, Line};
+use crate::term::{Term, TermMode};
+
+fn make_term() -> Term<VoidListener> {
+    Term::new(2, 4, 10, VoidListener)
+}
+
+#[test]
+fn frame_reports_cells_damage_and_cursor_once() {
+    let mut term = make_term();
+    term.grid_mut().dirty_mut().mark_all();
+    term.grid_mut().put_char('h');
+    term.grid_mut().put_char('i');
+
+    let frame = term.frame().expect("everything is damaged");
+    assert_eq!((frame.cols, frame.rows, frame.cells.len()), (4, 2, 8));
+    assert_eq!(frame.damage, [0, 1]);
+    let text: String = frame.row(0).iter().map(|c| c.ch).collect();
+    assert_eq!(text, "hi  ");
+    let default_fg = term.palette().resolve(Color::Named(NamedColor::Foreground));
+    assert_eq!(frame.row(1)[0].fg, default_fg);
+    let cursor = frame.cursor.expect("cursor shown by default");
+    assert_eq!(
+        (cursor.line, cursor.col, cursor.shape),
+        (0, 2, CursorShape::Block)
+    );
+
+    // Nothing changed since: no frame.
+    assert_eq!(term.frame(), None);
+
+    // Only the touched line is damaged.
+    term.grid_mut().cursor_mut().set_line(1);
+    term.grid_mut().cursor_mut().set_col(Column(0));
+    term.grid_mut().put_char('x');
+    let frame = term.frame().expect("line 1 changed");
+    assert_eq!(frame.damage, [1]);
+    assert_eq!(frame.row(1)[0].ch, 'x');
+}
+
+#[test]
+fn hidden_cursor_is_left_out() {
+    let mut term = make_term();
+    term.mode = TermMode::empty();
+    term.grid_mut().dirty_mut().mark_all();
+    assert_eq!(term.frame().expect("damaged").cursor, None);
+}
+
+#[test]
+fn inverse_swaps_colors() {
+    let mut term = make_term();
+    term.grid_mut().cursor_mut().template.flags = CellFlags::INVERSE;
+    term.grid_mut().put_char('x');
+    let frame = term.frame().expect("damaged");
+    let cell = &frame.row(0)[0];
+    let palette = term.palette();
+    assert_eq!(cell.fg, palette.background());
+    assert_eq!(cell.bg, palette.foreground());
+}
+
+#[test]
+fn frame_cells_carry_combining_marks() {
+    let mut term = make_term();
+    term.grid_mut().put_char('e');
+    term.grid_mut()[Line(0)][Column(0)].push_zerowidth('\u{301}');
+    let frame = term.frame().expect("damaged");
+    assert_eq!(frame.row(0)[0].ch, 'e');
+    assert_eq!(frame.row(0)[0].zerowidth, ['\u{301}']);
+    assert!(frame.row(0)[1].zerowidth.is_empty());
+}
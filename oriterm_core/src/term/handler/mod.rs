@@ -79,5 +79,6 @@ impl<T: EventListener> Handler for Term<T> {
     }
 }
 
-#[cfg(test)]
+// The tests drive `vte::ansi::Processor`, whose default timeout is std-only.
+#[cfg(all(test, feature = "std"))]
 mod tests;
@@ -6,12 +6,17 @@
 //! from the UI layer.
 
 pub mod charset;
+pub mod frame;
 mod handler;
 pub mod mode;
 
 pub use charset::CharsetState;
+pub use frame::{Frame, FrameCell, FrameCursor, FrameSource};
 pub use mode::TermMode;
 
+use alloc::string::String;
+use alloc::vec::Vec;
+
 use crate::color::Palette;
 use crate::event::EventListener;
 use crate::grid::{CursorShape, Grid};
@@ -141,7 +146,7 @@ impl<T: EventListener> Term<T> {
         }
 
         self.active_is_alt = !self.active_is_alt;
-        std::mem::swap(&mut self.keyboard_mode_stack, &mut self.inactive_keyboard_mode_stack);
+        core::mem::swap(&mut self.keyboard_mode_stack, &mut self.inactive_keyboard_mode_stack);
         self.grid_mut().dirty_mut().mark_all();
     }
 }
@@ -4,5 +4,9 @@ set -euo pipefail
 echo "=== cargo test --workspace ==="
 cargo test --workspace
 
+echo ""
+echo "=== cargo test -p oriterm_core --no-default-features ==="
+cargo test -p oriterm_core --no-default-features
+
 echo ""
 echo "All tests passed."